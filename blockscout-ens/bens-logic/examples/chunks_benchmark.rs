@@ -0,0 +1,69 @@
+use bens_logic::subgraphs_reader::{
+    blockscout::BlockscoutClient, BatchResolveAddressNamesInput, ChunkPolicy, NetworkInfo,
+    SubgraphReader,
+};
+use sqlx::postgres::PgPoolOptions;
+use std::{collections::HashMap, sync::Arc, time::Instant};
+use tracing_subscriber::fmt::format::FmtSpan;
+
+/// Compares batch resolving of large job as single query and in concurrent chunks.
+/// Addresses are derived from their index, so job is the same between runs.
+///
+/// DATABASE_URL=... JOB_SIZE=20000 cargo run --example chunks_benchmark
+#[tokio::main]
+async fn main() -> Result<(), anyhow::Error> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .with_span_events(FmtSpan::CLOSE)
+        .init();
+
+    let url = std::env::var("DATABASE_URL").expect("no database url");
+    let job_size: usize = std::env::var("JOB_SIZE")
+        .map(|s| s.parse().expect("invalid job size"))
+        .unwrap_or(20_000);
+    let pool = Arc::new(
+        PgPoolOptions::new()
+            .max_connections(40)
+            .connect(&url)
+            .await?,
+    );
+    let eth_client = BlockscoutClient::new("https://eth.blockscout.com".parse().unwrap(), 5, 30);
+    let networks = HashMap::from_iter([(1, NetworkInfo::from_client(eth_client))]);
+
+    let addresses: Vec<ethers::types::Address> = (0..job_size)
+        .map(|i| {
+            let hash = ethers::utils::keccak256(i.to_be_bytes());
+            ethers::types::Address::from_slice(&hash[12..])
+        })
+        .collect();
+
+    for (title, policy) in [
+        (
+            "single query",
+            ChunkPolicy {
+                chunk_size: usize::MAX,
+                max_concurrent_chunks: 1,
+            },
+        ),
+        ("default chunks", ChunkPolicy::default()),
+    ] {
+        let reader = SubgraphReader::initialize(pool.clone(), networks.clone())
+            .await?
+            .with_chunk_policy(policy);
+        let now = Instant::now();
+        let result = reader
+            .batch_resolve_address_names(BatchResolveAddressNamesInput {
+                network_id: 1,
+                addresses: addresses.clone(),
+            })
+            .await
+            .expect("failed to resolve");
+        println!(
+            "{title}: job size is {}. elapsed {:?}s. resolved as {} domains",
+            addresses.len(),
+            now.elapsed().as_secs_f32(),
+            result.names.len()
+        );
+    }
+    Ok(())
+}
@@ -148,7 +148,7 @@ async fn main() -> Result<(), anyhow::Error> {
         "job size is {}. elapsed {:?}s. resolved as {} domains",
         size,
         now.elapsed().as_secs_f32(),
-        result.len()
+        result.names.len()
     );
     Ok(())
 }
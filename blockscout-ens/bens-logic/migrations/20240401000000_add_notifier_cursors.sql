@@ -0,0 +1,6 @@
+CREATE TABLE notifier_cursors (
+    network_id BIGINT PRIMARY KEY,
+    -- last block which changes are published
+    block_number BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
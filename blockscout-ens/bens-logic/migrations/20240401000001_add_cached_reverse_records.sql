@@ -0,0 +1,9 @@
+CREATE TABLE cached_reverse_records (
+    -- schema of subgraph in graph-node database
+    schema_name TEXT NOT NULL,
+    addr_reverse_id TEXT NOT NULL,
+    resolved_address TEXT NOT NULL,
+    reversed_name TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (schema_name, addr_reverse_id)
+);
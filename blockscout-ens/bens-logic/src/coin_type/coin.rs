@@ -33,6 +33,38 @@ impl Coin {
             .cloned()
             .unwrap_or_else(|| Coin::unknown_type(coin_type.to_string()))
     }
+
+    /// Coin with given ticker, like `BTC`, case insensitive
+    pub fn find_by_name(name: &str) -> Option<Self> {
+        COINS_TYPES
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+            .cloned()
+    }
+
+    /// Formats hex representation of address according to encoding of coin.
+    /// Address is returned as is if encoding is unknown or fails
+    pub fn format_address(&self, address: String) -> String {
+        match &self.encoding {
+            Some(encoding) => encoding.encode(&address).unwrap_or(address),
+            None => address,
+        }
+    }
+
+    /// Parses address formatted according to encoding of coin into bytes stored in resolver.
+    /// `0x`-prefixed hex is accepted for any coin
+    pub fn parse_address(&self, address: &str) -> Result<Vec<u8>, anyhow::Error> {
+        if let Some(hex) = address.strip_prefix("0x") {
+            return Ok(hex::decode(hex)?);
+        }
+        match &self.encoding {
+            Some(encoding) => encoding.decode(address),
+            None => Err(anyhow::anyhow!(
+                "encoding of {} is unknown, address should be hex",
+                self.name
+            )),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -52,4 +84,24 @@ mod tests {
             assert_eq!(maybe_coin.name.to_string(), expected_name.to_string())
         }
     }
+
+    #[test]
+    fn parse_address_works() {
+        let btc = Coin::find_or_unknown("0");
+        let script = hex::decode("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap();
+        assert_eq!(
+            btc.parse_address("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4")
+                .unwrap(),
+            script
+        );
+        assert_eq!(
+            btc.parse_address("0x0014751e76e8199196d454941c45d1b3a323f1433bd6")
+                .unwrap(),
+            script
+        );
+        assert!(btc.parse_address("not an address").is_err());
+        let unknown = Coin::find_or_unknown("9999999999");
+        assert_eq!(unknown.parse_address("0xdead").unwrap(), vec![0xde, 0xad]);
+        assert!(unknown.parse_address("dead").is_err());
+    }
 }
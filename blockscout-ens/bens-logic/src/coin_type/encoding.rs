@@ -1,3 +1,4 @@
+use bech32::{FromBase32, ToBase32, Variant};
 use ethers::{types::Address, utils::to_checksum};
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
@@ -6,6 +7,20 @@ use std::str::FromStr;
 #[serde(rename_all = "camelCase")]
 pub enum Encoding {
     CheckSummedHex(Option<u8>),
+    Bitcoin(BitcoinEncoding),
+    /// Raw address bytes encoded as bech32 with human readable part, like `cosmos1...`
+    Bech32(String),
+}
+
+/// Address of bitcoin-like coin is stored as output script, which is encoded
+/// as base58check P2PKH/P2SH address or as bech32 segwit address
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BitcoinEncoding {
+    pub p2pkh_version: u8,
+    pub p2sh_version: u8,
+    /// Human readable part of segwit addresses, None if coin doesn't support segwit
+    pub hrp: Option<String>,
 }
 
 impl Encoding {
@@ -15,6 +30,160 @@ impl Encoding {
                 let address = Address::from_str(address)?;
                 Ok(to_checksum(&address, *chain_id))
             }
+            Self::Bitcoin(encoding) => {
+                let script = hex::decode(address.trim_start_matches("0x"))?;
+                encoding.encode(&script)
+            }
+            Self::Bech32(hrp) => {
+                let data = hex::decode(address.trim_start_matches("0x"))?;
+                Ok(bech32::encode(hrp, data.to_base32(), Variant::Bech32)?)
+            }
         }
     }
+
+    /// Inverse of [`Encoding::encode`], returns bytes of address as they are stored in resolver
+    pub fn decode(&self, address: &str) -> Result<Vec<u8>, anyhow::Error> {
+        match self {
+            Self::CheckSummedHex(_) => Ok(Address::from_str(address)?.as_bytes().to_vec()),
+            Self::Bitcoin(encoding) => encoding.decode(address),
+            Self::Bech32(hrp) => {
+                let (decoded_hrp, data, variant) = bech32::decode(address)?;
+                if decoded_hrp != *hrp || variant != Variant::Bech32 {
+                    return Err(anyhow::anyhow!("invalid bech32 address"));
+                }
+                Ok(Vec::<u8>::from_base32(&data)?)
+            }
+        }
+    }
+}
+
+impl BitcoinEncoding {
+    fn encode(&self, script: &[u8]) -> Result<String, anyhow::Error> {
+        match script {
+            // OP_DUP OP_HASH160 <20 bytes> OP_EQUALVERIFY OP_CHECKSIG
+            [0x76, 0xa9, 0x14, hash @ .., 0x88, 0xac] if hash.len() == 20 => Ok(bs58::encode(hash)
+                .with_check_version(self.p2pkh_version)
+                .into_string()),
+            // OP_HASH160 <20 bytes> OP_EQUAL
+            [0xa9, 0x14, hash @ .., 0x87] if hash.len() == 20 => Ok(bs58::encode(hash)
+                .with_check_version(self.p2sh_version)
+                .into_string()),
+            // OP_n <program>
+            [version @ (0x00 | 0x51..=0x60), len, program @ ..]
+                if self.hrp.is_some() && *len as usize == program.len() =>
+            {
+                let hrp = self.hrp.as_deref().unwrap_or_default();
+                let (version, variant) = match version {
+                    0x00 => (0, Variant::Bech32),
+                    v => (v - 0x50, Variant::Bech32m),
+                };
+                let mut data = vec![bech32::u5::try_from_u8(version)?];
+                data.extend(program.to_base32());
+                Ok(bech32::encode(hrp, data, variant)?)
+            }
+            _ => Err(anyhow::anyhow!("unsupported output script")),
+        }
+    }
+
+    fn decode(&self, address: &str) -> Result<Vec<u8>, anyhow::Error> {
+        if let Some(hrp) = &self.hrp {
+            if address.to_lowercase().starts_with(&format!("{hrp}1")) {
+                return self.decode_segwit(hrp, address);
+            }
+        }
+        let payload = bs58::decode(address).with_check(None).into_vec()?;
+        match payload.as_slice() {
+            [version, hash @ ..] if hash.len() == 20 && *version == self.p2pkh_version => {
+                Ok([&[0x76, 0xa9, 0x14], hash, &[0x88, 0xac]].concat())
+            }
+            [version, hash @ ..] if hash.len() == 20 && *version == self.p2sh_version => {
+                Ok([&[0xa9, 0x14], hash, &[0x87]].concat())
+            }
+            _ => Err(anyhow::anyhow!("unsupported address version")),
+        }
+    }
+
+    fn decode_segwit(&self, hrp: &str, address: &str) -> Result<Vec<u8>, anyhow::Error> {
+        let (decoded_hrp, data, variant) = bech32::decode(address)?;
+        let (version, program) = data
+            .split_first()
+            .ok_or_else(|| anyhow::anyhow!("empty segwit address"))?;
+        let version = version.to_u8();
+        let expected_variant = if version == 0 {
+            Variant::Bech32
+        } else {
+            Variant::Bech32m
+        };
+        if decoded_hrp != hrp || variant != expected_variant || version > 16 {
+            return Err(anyhow::anyhow!("invalid segwit address"));
+        }
+        let program = Vec::<u8>::from_base32(program)?;
+        let op = if version == 0 { 0x00 } else { version + 0x50 };
+        Ok([&[op, program.len() as u8], program.as_slice()].concat())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn bitcoin_works() {
+        let btc = Encoding::Bitcoin(BitcoinEncoding {
+            p2pkh_version: 0x00,
+            p2sh_version: 0x05,
+            hrp: Some("bc".to_string()),
+        });
+        for (script, expected) in [
+            (
+                "76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac",
+                "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa",
+            ),
+            (
+                "a914b472a266d0bd89c13706a4132ccfb16f7c3b9fcb87",
+                "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy",
+            ),
+            (
+                "0014751e76e8199196d454941c45d1b3a323f1433bd6",
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            ),
+            (
+                "5120a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c",
+                "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+            ),
+        ] {
+            assert_eq!(
+                btc.encode(script).unwrap(),
+                expected,
+                "invalid address of {script}"
+            );
+            assert_eq!(
+                hex::encode(btc.decode(expected).unwrap()),
+                script,
+                "invalid script of {expected}"
+            );
+        }
+        assert!(btc.encode("0xdeadbeef").is_err());
+        assert!(btc.decode("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb").is_err());
+        assert!(btc
+            .decode("ltc1qw508d6qejxtdg4y5r3zarvary0c5xw7kgmn4n9")
+            .is_err());
+    }
+
+    #[test]
+    fn bech32_works() {
+        let atom = Encoding::Bech32("cosmos".to_string());
+        let address = "cosmos1depk54cuajgkzmhf5z3tq36tnjwdzv4a80fj2m";
+        let raw = "0x6e436a571cec91616ee9a0a2b0474b9c9cd132bd";
+        assert_eq!(atom.encode(raw).unwrap(), address);
+        assert_eq!(
+            hex::encode(atom.decode(address).unwrap()),
+            raw.trim_start_matches("0x")
+        );
+        assert!(atom
+            .decode("terra1depk54cuajgkzmhf5z3tq36tnjwdzv4aptnjgm")
+            .is_err());
+        assert!(atom.encode("not hex").is_err());
+    }
 }
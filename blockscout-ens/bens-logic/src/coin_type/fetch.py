@@ -11,7 +11,29 @@ EIP_1191_COIN_NAMES = {
     "RSK": 30
 }
 
+# base58check versions of P2PKH and P2SH addresses and segwit hrp
+BITCOIN_LIKE_COINS = {
+    "BTC": {"p2pkhVersion": 0x00, "p2shVersion": 0x05, "hrp": "bc"},
+    "LTC": {"p2pkhVersion": 0x30, "p2shVersion": 0x32, "hrp": "ltc"},
+    "DOGE": {"p2pkhVersion": 0x1e, "p2shVersion": 0x16, "hrp": None},
+}
+
+# human readable part of bech32 addresses of cosmos-like coins
+BECH32_COINS = {
+    "ATOM": "cosmos",
+    "LUNA": "terra",
+    "KAVA": "kava",
+    "IRIS": "iaa",
+    "BNB": "bnb",
+    "RUNE": "thor",
+    "ONE": "one",
+}
+
 def convert_encoding(encoding, coin_name):
+    if coin_name in BITCOIN_LIKE_COINS:
+        return {"bitcoin": BITCOIN_LIKE_COINS[coin_name]}
+    if coin_name in BECH32_COINS:
+        return {"bech32": BECH32_COINS[coin_name]}
     maybe_chain_id = EIP_1191_COIN_NAMES.get(coin_name)
     return {
         "checksummed-hex": {"checkSummedHex": maybe_chain_id},
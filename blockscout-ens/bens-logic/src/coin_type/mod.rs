@@ -2,4 +2,4 @@ mod coin;
 mod encoding;
 
 pub use coin::Coin;
-pub use encoding::Encoding;
+pub use encoding::{BitcoinEncoding, Encoding};
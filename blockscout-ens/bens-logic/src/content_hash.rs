@@ -0,0 +1,252 @@
+use anyhow::Context;
+
+/// Decoded value of ENSIP-7 contenthash field
+/// https://docs.ens.domains/ens-improvement-proposals/ensip-7-contenthash-field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentHash {
+    /// Hex representation of raw contenthash bytes
+    pub raw: String,
+    /// Human readable form, like `ipfs://Qm...` or `bzz://...`.
+    /// None if codec of contenthash is unknown
+    pub decoded: Option<String>,
+    /// Protocol of decoded contenthash. None if codec of contenthash is unknown
+    pub protocol: Option<ContentHashProtocol>,
+    /// Decoded hash without protocol prefix, like `Qm...` for IPFS
+    pub hash: Option<String>,
+}
+
+/// Protocols of contenthash namespace codecs supported by decoder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ContentHashProtocol {
+    Ipfs,
+    Ipns,
+    Swarm,
+    Arweave,
+}
+
+impl ContentHashProtocol {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ContentHashProtocol::Ipfs => "ipfs",
+            ContentHashProtocol::Ipns => "ipns",
+            ContentHashProtocol::Swarm => "swarm",
+            ContentHashProtocol::Arweave => "arweave",
+        }
+    }
+
+    /// Scheme of URI of content, like `bzz` for swarm
+    pub fn scheme(&self) -> &'static str {
+        match self {
+            ContentHashProtocol::Ipfs => "ipfs",
+            ContentHashProtocol::Ipns => "ipns",
+            ContentHashProtocol::Swarm => "bzz",
+            ContentHashProtocol::Arweave => "ar",
+        }
+    }
+}
+
+// https://github.com/multiformats/multicodec/blob/master/table.csv
+const IPFS_NS_CODEC: u64 = 0xe3;
+const SWARM_NS_CODEC: u64 = 0xe4;
+const IPNS_NS_CODEC: u64 = 0xe5;
+const ARWEAVE_NS_CODEC: u64 = 0xb29910;
+const DAG_PB_CODEC: u64 = 0x70;
+const SHA2_256_MULTIHASH: u64 = 0x12;
+const IDENTITY_MULTIHASH: u64 = 0x00;
+
+impl ContentHash {
+    /// None if contenthash is empty, which means that it was cleared by owner
+    pub fn new(raw: &[u8]) -> Option<Self> {
+        if raw.is_empty() {
+            return None;
+        }
+        let (protocol, hash) = match decode(raw) {
+            Ok((protocol, hash)) => (Some(protocol), Some(hash)),
+            Err(err) => {
+                tracing::warn!(err =? err, raw = hex::encode(raw), "cannot decode contenthash");
+                (None, None)
+            }
+        };
+        Some(Self {
+            raw: crate::hash_name::hex(raw),
+            decoded: protocol
+                .zip(hash.as_ref())
+                .map(|(protocol, hash)| format!("{}://{hash}", protocol.scheme())),
+            protocol,
+            hash,
+        })
+    }
+}
+
+fn decode(raw: &[u8]) -> Result<(ContentHashProtocol, String), anyhow::Error> {
+    let (codec, value) = read_varint(raw).context("reading namespace codec")?;
+    match codec {
+        IPFS_NS_CODEC => Ok((ContentHashProtocol::Ipfs, cid_to_string(value)?)),
+        IPNS_NS_CODEC => Ok((ContentHashProtocol::Ipns, ipns_name(value)?)),
+        SWARM_NS_CODEC => Ok((ContentHashProtocol::Swarm, swarm_hash(value)?)),
+        // arweave contenthash is raw transaction id
+        ARWEAVE_NS_CODEC => Ok((
+            ContentHashProtocol::Arweave,
+            data_encoding::BASE64URL_NOPAD.encode(value),
+        )),
+        _ => Err(anyhow::anyhow!("unknown contenthash codec {codec:#x}")),
+    }
+}
+
+/// Converts CID to CIDv0 (`Qm...`) if possible, otherwise to base32 CIDv1 (`b...`)
+fn cid_to_string(cid: &[u8]) -> Result<String, anyhow::Error> {
+    let (version, rest) = read_varint(cid).context("reading cid version")?;
+    if version != 1 {
+        anyhow::bail!("unsupported cid version {version}");
+    }
+    let (codec, multihash) = read_varint(rest).context("reading cid codec")?;
+    let (hash_fn, _) = read_varint(multihash).context("reading multihash function")?;
+    if codec == DAG_PB_CODEC && hash_fn == SHA2_256_MULTIHASH {
+        Ok(bs58::encode(multihash).into_string())
+    } else {
+        Ok(format!(
+            "b{}",
+            data_encoding::BASE32_NOPAD.encode(cid).to_ascii_lowercase()
+        ))
+    }
+}
+
+/// Converts IPNS CID to base36 CIDv1 (`k51...`). Legacy DNSLink names are stored
+/// as identity multihash of domain name and returned as is, like `app.uniswap.org`
+fn ipns_name(cid: &[u8]) -> Result<String, anyhow::Error> {
+    let (version, rest) = read_varint(cid).context("reading cid version")?;
+    if version != 1 {
+        anyhow::bail!("unsupported cid version {version}");
+    }
+    let (codec, multihash) = read_varint(rest).context("reading cid codec")?;
+    let (hash_fn, rest) = read_varint(multihash).context("reading multihash function")?;
+    if codec == DAG_PB_CODEC && hash_fn == IDENTITY_MULTIHASH {
+        let (_length, name) = read_varint(rest).context("reading multihash length")?;
+        return String::from_utf8(name.to_vec()).context("decoding dnslink name");
+    }
+    Ok(format!("k{}", base36(cid)))
+}
+
+/// Lowercase base36 encoding, leading zero bytes are encoded as `0`
+fn base36(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 36] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let zeros = bytes.iter().take_while(|b| **b == 0).count();
+    // digits of number in base 36, least significant first
+    let mut digits: Vec<u8> = vec![];
+    for byte in &bytes[zeros..] {
+        let mut carry = *byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 36) as u8;
+            carry /= 36;
+        }
+        while carry > 0 {
+            digits.push((carry % 36) as u8);
+            carry /= 36;
+        }
+    }
+    let mut encoded = "0".repeat(zeros);
+    encoded.extend(digits.iter().rev().map(|d| ALPHABET[*d as usize] as char));
+    encoded
+}
+
+/// Extracts hex encoded hash from swarm CID
+fn swarm_hash(cid: &[u8]) -> Result<String, anyhow::Error> {
+    let (_version, rest) = read_varint(cid).context("reading cid version")?;
+    let (_codec, multihash) = read_varint(rest).context("reading cid codec")?;
+    let (_hash_fn, rest) = read_varint(multihash).context("reading multihash function")?;
+    let (length, digest) = read_varint(rest).context("reading multihash length")?;
+    if digest.len() != length as usize {
+        anyhow::bail!(
+            "invalid swarm hash length: expected {length}, got {}",
+            digest.len()
+        );
+    }
+    Ok(hex::encode(digest))
+}
+
+/// Reads unsigned varint (https://github.com/multiformats/unsigned-varint)
+/// and returns it along with remaining bytes
+fn read_varint(bytes: &[u8]) -> Result<(u64, &[u8]), anyhow::Error> {
+    let mut value: u64 = 0;
+    for (i, byte) in bytes.iter().enumerate().take(9) {
+        value |= ((byte & 0x7f) as u64) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, &bytes[i + 1..]));
+        }
+    }
+    Err(anyhow::anyhow!("invalid varint"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn it_works() {
+        for (raw, expected) in [
+            // examples from ENSIP-7
+            (
+                "e3010170122029f2d17be6139079dc48696d1f582a8530eb9805b561eda517e22a892c7e3f1f",
+                Some("ipfs://QmRAQB6YaCyidP37UdDnjFY5vQuiBrcqdyoW1CuDgwxkD4"),
+            ),
+            (
+                "e40101fa011b20d1de9994b4d039f6548d191eb26786769f580809256b4685ef316805265ea162",
+                Some("bzz://d1de9994b4d039f6548d191eb26786769f580809256b4685ef316805265ea162"),
+            ),
+            // vitalik.eth
+            (
+                "e3010170122084b970556c2bcbd1f37d3b001c6c5566fc0ee7c7f1a76fdaedb78aefdca0ed00",
+                Some("ipfs://QmXGkXcfasLtjLk9i5brs2sMYNbk34c8FGdBDCP864mrhV"),
+            ),
+            // dag-cbor codec is encoded as CIDv1
+            (
+                "e30101711220e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855",
+                Some("ipfs://bafyreihdwdcefgh4dqkjv67uzcmw7ojee6xedzdetojuzjevtenxquvyku"),
+            ),
+            // ipns libp2p key
+            (
+                "e5010172002408011220000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f",
+                Some("ipns://k51qzi5uqu5dg6lcd99r9gmb963kgugjinxxggwy7o93oagk3f2eg3qcjh7127"),
+            ),
+            // legacy ipns dnslink
+            (
+                "e5010170000f6170702e756e69737761702e6f7267",
+                Some("ipns://app.uniswap.org"),
+            ),
+            // arweave transaction
+            (
+                "90b2ca05cacf9c9bfaf2d253b3de161032f3717c7df25639872b43e6fc43fd4d6e14a226",
+                Some("ar://ys-cm_ry0lOz3hYQMvNxfH3yVjmHK0Pm_EP9TW4UoiY"),
+            ),
+            // unknown codec
+            ("0101", None),
+        ] {
+            let raw = hex::decode(raw).unwrap();
+            let content_hash = ContentHash::new(&raw).expect("contenthash is not empty");
+            assert_eq!(content_hash.decoded.as_deref(), expected);
+            assert_eq!(content_hash.raw, format!("0x{}", hex::encode(&raw)));
+        }
+        // cleared contenthash
+        assert_eq!(ContentHash::new(&[]), None);
+    }
+
+    #[test]
+    fn protocol_and_hash_are_split() {
+        let raw = hex::decode(
+            "e40101fa011b20d1de9994b4d039f6548d191eb26786769f580809256b4685ef316805265ea162",
+        )
+        .unwrap();
+        let content_hash = ContentHash::new(&raw).unwrap();
+        assert_eq!(content_hash.protocol, Some(ContentHashProtocol::Swarm));
+        assert_eq!(
+            content_hash.hash.as_deref(),
+            Some("d1de9994b4d039f6548d191eb26786769f580809256b4685ef316805265ea162")
+        );
+
+        let content_hash = ContentHash::new(&hex::decode("0101").unwrap()).unwrap();
+        assert_eq!(content_hash.protocol, None);
+        assert_eq!(content_hash.hash, None);
+    }
+}
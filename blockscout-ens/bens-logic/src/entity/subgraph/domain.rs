@@ -1,6 +1,6 @@
 use chrono::Utc;
 use sqlx::types::BigDecimal;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, sqlx::FromRow)]
 pub struct DetailedDomain {
@@ -20,9 +20,24 @@ pub struct DetailedDomain {
     pub wrapped_owner: Option<String>,
     pub created_at: BigDecimal,
     pub expiry_date: Option<chrono::DateTime<Utc>>,
-    pub is_expired: bool,
+    #[sqlx(try_from = "String")]
+    pub expiry_status: ExpiryStatus,
     #[sqlx(default)]
     pub other_addresses: sqlx::types::Json<HashMap<String, String>>,
+    #[sqlx(default)]
+    pub content_hash: Option<Vec<u8>>,
+    /// Latest value of every text key of resolver, like `avatar` or `com.twitter`
+    #[sqlx(default)]
+    pub text_records: sqlx::types::Json<BTreeMap<String, String>>,
+    /// True if namehash of `name` doesn't match `id`, set only if verification is enabled
+    #[sqlx(default)]
+    pub namehash_mismatch: bool,
+    /// Name of provider, which issued domain offchain. Such domains are not stored in subgraph
+    #[sqlx(default)]
+    pub offchain_provider: Option<String>,
+    /// True if domain is not stored in subgraph and was resolved offchain by CCIP-read gateway
+    #[sqlx(default)]
+    pub stored_offchain: bool,
 }
 
 #[derive(Debug, Clone, Default, PartialEq, Eq, sqlx::FromRow)]
@@ -32,10 +47,66 @@ pub struct Domain {
     pub resolved_address: Option<String>,
     pub registration_date: chrono::DateTime<Utc>,
     pub owner: String,
+    pub registrant: Option<String>,
     pub wrapped_owner: Option<String>,
     pub created_at: BigDecimal,
     pub expiry_date: Option<chrono::DateTime<Utc>>,
-    pub is_expired: bool,
+    #[sqlx(try_from = "String")]
+    pub expiry_status: ExpiryStatus,
+    #[sqlx(try_from = "String")]
+    pub status: DomainStatus,
+    /// True if name of domain is verified primary name of looked up address
+    #[sqlx(default)]
+    pub is_primary: bool,
+    /// True if namehash of `name` doesn't match `id`, set only if verification is enabled
+    #[sqlx(default)]
+    pub namehash_mismatch: bool,
+}
+
+/// Domain is `Active` until `expiry_date`, then it stays `InGracePeriod`
+/// while owner still can renew it, and only after that becomes `Expired`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExpiryStatus {
+    #[default]
+    Active,
+    InGracePeriod,
+    Expired,
+}
+
+impl TryFrom<String> for ExpiryStatus {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "active" => Ok(Self::Active),
+            "in_grace_period" => Ok(Self::InGracePeriod),
+            "expired" => Ok(Self::Expired),
+            _ => Err(format!("unknown expiry status '{value}'")),
+        }
+    }
+}
+
+/// Domain is `Resolved` if it resolves to non-zero address. Otherwise it is `Burned`
+/// if owned by zero address, and `Unresolved` if somebody still owns it
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DomainStatus {
+    #[default]
+    Resolved,
+    Unresolved,
+    Burned,
+}
+
+impl TryFrom<String> for DomainStatus {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        match value.as_str() {
+            "resolved" => Ok(Self::Resolved),
+            "unresolved" => Ok(Self::Unresolved),
+            "burned" => Ok(Self::Burned),
+            _ => Err(format!("unknown domain status '{value}'")),
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
@@ -45,6 +116,22 @@ pub struct DomainWithAddress {
     pub resolved_address: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct TextRecord {
+    pub key: String,
+    pub value: String,
+}
+
+/// Records of current domain resolver: hex encoded address of every coin type,
+/// text records and raw contenthash
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct DomainRecordsRow {
+    pub id: String,
+    pub addresses: sqlx::types::Json<BTreeMap<String, String>>,
+    pub text_records: sqlx::types::Json<BTreeMap<String, String>>,
+    pub content_hash: Option<Vec<u8>>,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
 pub struct ReverseRecord {
     pub addr_reverse_id: String,
@@ -58,3 +145,27 @@ pub struct AddrReverseDomainWithActualName {
     pub resolved_address: String,
     pub name: String,
 }
+
+/// Single row of address summary query: summary columns are repeated
+/// for every domain of the first page, `domain` is None if page is empty
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AddressSummaryRow {
+    pub resolved_count: i64,
+    pub owned_count: i64,
+    pub primary_name: Option<String>,
+    pub domain: Option<Domain>,
+}
+
+impl<'r> sqlx::FromRow<'r, sqlx::postgres::PgRow> for AddressSummaryRow {
+    fn from_row(row: &'r sqlx::postgres::PgRow) -> Result<Self, sqlx::Error> {
+        use sqlx::Row;
+        let domain_id: Option<String> = row.try_get("id")?;
+        let domain = domain_id.map(|_| Domain::from_row(row)).transpose()?;
+        Ok(Self {
+            resolved_count: row.try_get("resolved_count")?,
+            owned_count: row.try_get("owned_count")?,
+            primary_name: row.try_get("primary_name")?,
+            domain,
+        })
+    }
+}
@@ -1,4 +1,5 @@
 use ethers::types::{Address, TxHash};
+use serde::Serialize;
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, sqlx::FromRow)]
 pub struct DomainEventTransaction {
@@ -16,3 +17,49 @@ pub struct DomainEvent {
     pub method: Option<String>,
     pub actions: Vec<String>,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct AddrChangedEvent {
+    pub addr: String,
+    pub block_number: i32,
+    pub transaction_id: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResolvedAddressChange {
+    pub address: Address,
+    pub block_number: i64,
+    pub transaction_hash: TxHash,
+    /// None if transaction was not found in blockscout
+    pub timestamp: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct DomainChangeRow {
+    pub domain_id: String,
+    pub domain_name: Option<String>,
+    pub kind: String,
+    pub value: String,
+    pub block_number: i32,
+    pub transaction_id: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DomainChangeKind {
+    Owner,
+    WrappedOwner,
+    ResolvedAddress,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct DomainChangeEvent {
+    pub network_id: i64,
+    pub domain_id: String,
+    pub domain_name: Option<String>,
+    pub kind: DomainChangeKind,
+    /// New value of changed field, lowercase hex address
+    pub value: String,
+    pub block_number: i64,
+    pub transaction_hash: TxHash,
+}
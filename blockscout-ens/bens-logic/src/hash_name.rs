@@ -1,24 +1,47 @@
+use crate::name_safety::is_encoded_label_hash;
 use ethers::{types::Bytes, utils::keccak256};
 
 /// Implementation of
 /// https://docs.ens.domains/contract-api-reference/name-processing#algorithm
-/// with custom empty_label_hash
+/// with custom empty_label_hash. Unknown labels encoded as `[labelhash]` are hashed to their labelhash
 pub fn hash_ens_domain_name(name: &str, empty_label_hash: Option<Bytes>) -> Bytes {
     if name.is_empty() {
         empty_label_hash.unwrap_or_else(|| [0; 32].into())
     } else {
         let (label, remainder) = name.split_once('.').unwrap_or((name, ""));
         let remainder_hash = hash_ens_domain_name(remainder, empty_label_hash);
-        let label_hash = keccak256(label.as_bytes());
+        let label_hash = label_hash(label);
         let concatenated: Vec<u8> = remainder_hash.into_iter().chain(label_hash).collect();
         keccak256(concatenated).into()
     }
 }
 
+fn label_hash(label: &str) -> [u8; 32] {
+    let mut hash = [0; 32];
+    if is_encoded_label_hash(label)
+        && hex::decode_to_slice(&label[1..label.len() - 1], &mut hash).is_ok()
+    {
+        hash
+    } else {
+        keccak256(label.as_bytes())
+    }
+}
+
 pub fn domain_id(name: &str, empty_label_hash: Option<Bytes>) -> String {
     hex(hash_ens_domain_name(name, empty_label_hash))
 }
 
+/// DNS wire format of name, every label is prefixed with its length
+pub fn dns_encode(name: &str) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(name.len() + 2);
+    for label in name.split('.') {
+        encoded.push(label.len() as u8);
+        encoded.extend_from_slice(label.as_bytes());
+    }
+    encoded.push(0);
+    encoded
+}
+
 pub fn hex<T>(data: T) -> String
 where
     T: AsRef<[u8]>,
@@ -51,6 +74,11 @@ mod tests {
                 "vitalik.eth",
                 "0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835",
             ),
+            // labelhash of `vitalik`
+            (
+                "[af2caa1c2ca1d027f1ac823b529d0a67cd144264b2789fa2ea4d63a67c7103cc].eth",
+                "0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835",
+            ),
         ] {
             let hash = domain_id(name, None);
             assert_eq!(hash, expected_hash);
@@ -85,4 +113,12 @@ mod tests {
             assert_eq!(hash, expected_hash);
         }
     }
+
+    #[test]
+    fn dns_encode_works() {
+        assert_eq!(
+            dns_encode("alice.cb.id"),
+            b"\x05alice\x02cb\x02id\x00".to_vec()
+        );
+    }
 }
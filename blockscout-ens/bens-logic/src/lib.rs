@@ -1,6 +1,11 @@
 pub mod coin_type;
+pub mod content_hash;
 pub mod entity;
 pub mod hash_name;
+pub mod metrics;
+pub mod name_safety;
+pub mod notifier;
+pub mod state;
 pub mod subgraphs_reader;
 #[cfg(feature = "test-utils")]
 pub mod test_utils;
@@ -0,0 +1,62 @@
+use lazy_static::lazy_static;
+use prometheus::{register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec};
+use std::future::Future;
+
+lazy_static! {
+    pub static ref SQL_QUERIES_TOTAL: IntCounterVec = register_int_counter_vec!(
+        "bens_sql_queries_total",
+        "total number of sql queries to subgraph database",
+        &["query", "schema", "status"],
+    )
+    .unwrap();
+    pub static ref SQL_QUERY_TIME: HistogramVec = register_histogram_vec!(
+        "bens_sql_query_time_seconds",
+        "single sql query to subgraph database time in seconds",
+        &["query", "schema"],
+        vec![0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0],
+    )
+    .unwrap();
+}
+
+/// Executes `query` future and records its latency and result status
+/// under `query_name` and `schema` labels
+pub async fn observe_query<T, E>(
+    query_name: &str,
+    schema: &str,
+    query: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let timer = SQL_QUERY_TIME
+        .with_label_values(&[query_name, schema])
+        .start_timer();
+    let result = query.await;
+    timer.observe_duration();
+    let status = if result.is_ok() { "ok" } else { "error" };
+    SQL_QUERIES_TOTAL
+        .with_label_values(&[query_name, schema, status])
+        .inc();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn observe_query_works() {
+        let ok: Result<(), ()> = observe_query("test_query", "sgd_test", async { Ok(()) }).await;
+        assert!(ok.is_ok());
+        let err: Result<(), ()> = observe_query("test_query", "sgd_test", async { Err(()) }).await;
+        assert!(err.is_err());
+
+        for status in ["ok", "error"] {
+            let count = SQL_QUERIES_TOTAL
+                .with_label_values(&["test_query", "sgd_test", status])
+                .get();
+            assert_eq!(count, 1);
+        }
+        let observed = SQL_QUERY_TIME
+            .with_label_values(&["test_query", "sgd_test"])
+            .get_sample_count();
+        assert_eq!(observed, 2);
+    }
+}
@@ -0,0 +1,140 @@
+use ens_normalize_rs::{DisallowedSequence, EnsNameNormalizer, ProcessError};
+use lazy_static::lazy_static;
+use std::collections::BTreeSet;
+
+lazy_static! {
+    /// Building normalizer parses ENSIP-15 spec, so it's shared
+    pub(crate) static ref NORMALIZER: EnsNameNormalizer = EnsNameNormalizer::default();
+}
+
+/// Reason why domain name could be used for spoofing
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum NameWarning {
+    /// Label mixes characters of different scripts, like latin `vit` and cyrillic `аlik`
+    MixedScripts,
+    /// Whole label consists of characters of one script,
+    /// which look like characters of another script, like cyrillic `аррӏе`
+    ConfusableCharacters,
+    /// Label contains zero-width or other invisible code points
+    InvisibleCharacters,
+    /// Label contains characters disallowed by ENSIP-15
+    DisallowedCharacters,
+    /// Name differs from its ENSIP-15 normalized form,
+    /// so it cannot be reached by typing
+    NotNormalized,
+}
+
+// Code points which are ignored during normalization, so they could be used
+// to register name visually identical to existing one
+const INVISIBLE_CHARACTERS: [char; 9] = [
+    '\u{00ad}', '\u{180e}', '\u{200b}', '\u{200c}', '\u{200d}', '\u{2060}', '\u{2061}', '\u{2062}',
+    '\u{feff}',
+];
+
+/// Classifies domain name returned from subgraph.
+/// Empty result means that name is safe to display as is
+pub fn name_warnings(name: &str) -> Vec<NameWarning> {
+    if name.is_empty() {
+        return vec![];
+    }
+    let warnings: BTreeSet<NameWarning> = name
+        .split('.')
+        .filter(|label| !is_encoded_label_hash(label))
+        .filter_map(label_warning)
+        .collect();
+    warnings.into_iter().collect()
+}
+
+fn label_warning(label: &str) -> Option<NameWarning> {
+    if label.is_empty() {
+        return Some(NameWarning::DisallowedCharacters);
+    }
+    match NORMALIZER.normalize(label) {
+        Ok(normalized) if normalized == label => None,
+        Ok(_) if label.contains(INVISIBLE_CHARACTERS) => Some(NameWarning::InvisibleCharacters),
+        Ok(_) => Some(NameWarning::NotNormalized),
+        Err(ProcessError::Confused(_)) => Some(NameWarning::MixedScripts),
+        Err(ProcessError::ConfusedGroups { .. }) => Some(NameWarning::ConfusableCharacters),
+        Err(ProcessError::DisallowedSequence(DisallowedSequence::InvisibleCharacter(_))) => {
+            Some(NameWarning::InvisibleCharacters)
+        }
+        Err(_) => Some(NameWarning::DisallowedCharacters),
+    }
+}
+
+/// Unknown labels are stored in subgraph as `[labelhash]`
+pub(crate) fn is_encoded_label_hash(label: &str) -> bool {
+    label
+        .strip_prefix('[')
+        .and_then(|l| l.strip_suffix(']'))
+        .map(|hash| hash.len() == 64 && hash.chars().all(|c| c.is_ascii_hexdigit()))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn safe_names_work() {
+        for name in [
+            "vitalik.eth",
+            "vita1ik.eth",
+            "wa🇬🇲i.eth",
+            "👨‍👩‍👧.eth",
+            "levvv.gno",
+            "1234.eth",
+            "",
+            "東京.eth",
+            "москва.eth",
+            "[dec08c9dbbdd0890e300eb5062089b2d4b1c40e3673bbccb5423f7b37dcf9a9c].eth",
+        ] {
+            assert_eq!(name_warnings(name), vec![], "name '{name}' should be safe");
+        }
+    }
+
+    #[test]
+    fn spoofed_names_work() {
+        for (name, expected) in [
+            // cyrillic `а`
+            ("vitаlik.eth", vec![NameWarning::MixedScripts]),
+            // cyrillic `р` and `а`
+            ("раураl.eth", vec![NameWarning::MixedScripts]),
+            // whole-script confusables
+            ("аррӏе.eth", vec![NameWarning::ConfusableCharacters]),
+            ("ѕсоре.eth", vec![NameWarning::ConfusableCharacters]),
+            // zero-width characters
+            (
+                "vita\u{200b}lik.eth",
+                vec![NameWarning::InvisibleCharacters],
+            ),
+            (
+                "vitalik\u{feff}.eth",
+                vec![NameWarning::InvisibleCharacters],
+            ),
+            (
+                "vit\u{200d}alik.eth",
+                vec![NameWarning::InvisibleCharacters],
+            ),
+            // disallowed characters
+            ("vit@lik.eth", vec![NameWarning::DisallowedCharacters]),
+            ("vitalik .eth", vec![NameWarning::DisallowedCharacters]),
+            ("vitalik..eth", vec![NameWarning::DisallowedCharacters]),
+            // not normalized
+            ("Vitalik.eth", vec![NameWarning::NotNormalized]),
+            ("ｖｉｔａｌｉｋ.eth", vec![NameWarning::NotNormalized]),
+            // several labels
+            (
+                "Vitalik.vitаlik.eth",
+                vec![NameWarning::MixedScripts, NameWarning::NotNormalized],
+            ),
+        ] {
+            assert_eq!(
+                name_warnings(name),
+                expected,
+                "invalid warnings for '{name}'"
+            );
+        }
+    }
+}
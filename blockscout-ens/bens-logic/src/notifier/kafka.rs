@@ -0,0 +1,42 @@
+use super::EventPublisher;
+use crate::entity::subgraph::domain_event::DomainChangeEvent;
+use async_trait::async_trait;
+use rdkafka::{
+    producer::{FutureProducer, FutureRecord},
+    util::Timeout,
+    ClientConfig,
+};
+
+/// Sends every event as separate JSON message to `topic`.
+/// Domain id is used as message key, so changes of one domain keep their order
+pub struct KafkaPublisher {
+    producer: FutureProducer,
+    topic: String,
+}
+
+impl KafkaPublisher {
+    pub fn new(brokers: &str, topic: String) -> Result<Self, anyhow::Error> {
+        let producer = ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set("enable.idempotence", "true")
+            .create()?;
+        Ok(Self { producer, topic })
+    }
+}
+
+#[async_trait]
+impl EventPublisher for KafkaPublisher {
+    async fn publish(&self, events: &[DomainChangeEvent]) -> Result<(), anyhow::Error> {
+        for event in events {
+            let payload = serde_json::to_vec(event)?;
+            let record = FutureRecord::to(&self.topic)
+                .key(&event.domain_id)
+                .payload(&payload);
+            self.producer
+                .send(record, Timeout::Never)
+                .await
+                .map_err(|(err, _)| err)?;
+        }
+        Ok(())
+    }
+}
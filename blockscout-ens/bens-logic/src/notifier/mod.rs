@@ -0,0 +1,208 @@
+//! Publishes changes of domain owner, wrapped owner and resolved address
+//! to external consumers, so they don't have to diff full dumps of domains.
+//!
+//! Every poll reads changes indexed since block stored in cursor of the network.
+//! Cursors are kept in state database, so restarts don't lose changes.
+//! Delivery is at-least-once: cursor moves only after events are published,
+//! and events of blocks reverted by chain reorg are not retracted.
+
+#[cfg(feature = "kafka")]
+mod kafka;
+mod publisher;
+
+#[cfg(feature = "kafka")]
+pub use kafka::KafkaPublisher;
+pub use publisher::{EventPublisher, WebhookPublisher};
+
+use crate::subgraphs_reader::SubgraphReader;
+use anyhow::Context;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::sync::Arc;
+
+pub struct Notifier {
+    reader: Arc<SubgraphReader>,
+    publisher: Arc<dyn EventPublisher>,
+    max_blocks_per_poll: i64,
+    /// Pool of state database with `notifier_cursors` table
+    state_pool: Arc<PgPool>,
+}
+
+impl Notifier {
+    pub fn new(
+        reader: Arc<SubgraphReader>,
+        publisher: Arc<dyn EventPublisher>,
+        max_blocks_per_poll: i64,
+        state_pool: Arc<PgPool>,
+    ) -> Self {
+        Self {
+            reader,
+            publisher,
+            max_blocks_per_poll,
+            state_pool,
+        }
+    }
+
+    /// Publishes changes of all networks indexed since their cursors and returns number of published events.
+    /// Network without cursor only gets cursor at its latest indexed block, so history is not replayed
+    pub async fn poll(&self) -> usize {
+        let mut published = 0;
+        for network_id in self.reader.network_ids() {
+            match self.poll_network(network_id).await {
+                Ok(n) => published += n,
+                Err(err) => {
+                    tracing::error!(network_id, err = ?err, "failed to publish domain changes");
+                }
+            }
+        }
+        published
+    }
+
+    async fn poll_network(&self, network_id: i64) -> Result<usize, anyhow::Error> {
+        let Some(latest_block) = self
+            .reader
+            .latest_indexed_block(network_id)
+            .await
+            .context("reading latest indexed block")?
+        else {
+            return Ok(0);
+        };
+        // cursor row is locked until poll is finished, so overlapping polls
+        // and other instances don't publish same events twice
+        let mut tx = self.state_pool.begin().await?;
+        let cursor: Option<i64> = sqlx::query_scalar(
+            "SELECT block_number FROM notifier_cursors WHERE network_id = $1 FOR UPDATE",
+        )
+        .bind(network_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .context("reading cursor")?;
+        let after_block = match cursor {
+            Some(after_block) if after_block < latest_block => after_block,
+            Some(_) => return Ok(0),
+            None => {
+                save_cursor(&mut tx, network_id, latest_block).await?;
+                tx.commit().await?;
+                return Ok(0);
+            }
+        };
+        let to_block = latest_block.min(after_block + self.max_blocks_per_poll);
+        let events = self
+            .reader
+            .domain_changes(network_id, after_block, to_block)
+            .await
+            .context("reading domain changes")?;
+        if !events.is_empty() {
+            self.publisher
+                .publish(&events)
+                .await
+                .context("publishing domain changes")?;
+        }
+        save_cursor(&mut tx, network_id, to_block).await?;
+        tx.commit().await?;
+        Ok(events.len())
+    }
+}
+
+async fn save_cursor(
+    tx: &mut Transaction<'_, Postgres>,
+    network_id: i64,
+    block_number: i64,
+) -> Result<(), anyhow::Error> {
+    sqlx::query(
+        r#"
+        INSERT INTO notifier_cursors (network_id, block_number) VALUES ($1, $2)
+        ON CONFLICT (network_id) DO UPDATE
+        SET block_number = EXCLUDED.block_number, updated_at = now()
+        "#,
+    )
+    .bind(network_id)
+    .bind(block_number)
+    .execute(&mut **tx)
+    .await
+    .context("saving cursor")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        entity::subgraph::domain_event::{DomainChangeEvent, DomainChangeKind},
+        test_utils::mocked_networks_with_blockscout,
+    };
+    use async_trait::async_trait;
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::sync::Mutex;
+
+    #[derive(Default)]
+    struct MemoryPublisher {
+        events: Mutex<Vec<DomainChangeEvent>>,
+        fail: AtomicBool,
+    }
+
+    #[async_trait]
+    impl EventPublisher for MemoryPublisher {
+        async fn publish(&self, events: &[DomainChangeEvent]) -> Result<(), anyhow::Error> {
+            if self.fail.load(Ordering::SeqCst) {
+                anyhow::bail!("publisher is down");
+            }
+            self.events.lock().await.extend_from_slice(events);
+            Ok(())
+        }
+    }
+
+    async fn set_latest_block(pool: &PgPool, block: i64) {
+        sqlx::query(
+            "UPDATE subgraphs.subgraph_deployment \
+            SET latest_ethereum_block_number = $1 WHERE deployment = 'Qmb_1'",
+        )
+        .bind(block)
+        .execute(pool)
+        .await
+        .expect("failed to update deployment");
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn notifier_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        crate::state::run_migrations(&pool)
+            .await
+            .expect("failed to run state migrations");
+        let reader = Arc::new(reader);
+        let publisher = Arc::new(MemoryPublisher::default());
+        let notifier = Notifier::new(reader.clone(), publisher.clone(), 1000, pool.clone());
+
+        // first poll doesn't replay history
+        assert_eq!(notifier.poll().await, 0);
+        assert_eq!(notifier.poll().await, 0);
+
+        set_latest_block(&pool, 18000010).await;
+        publisher.fail.store(true, Ordering::SeqCst);
+        assert_eq!(notifier.poll().await, 0);
+        // cursor is not moved if publishing failed
+        publisher.fail.store(false, Ordering::SeqCst);
+        // cursor survives restart of notifier
+        let notifier = Notifier::new(reader, publisher.clone(), 1000, pool.clone());
+        assert_eq!(notifier.poll().await, 1);
+        assert_eq!(notifier.poll().await, 0);
+
+        let events = publisher.events.lock().await.clone();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].domain_name.as_deref(), Some("sashaxyz.eth"));
+        assert_eq!(events[0].kind, DomainChangeKind::Owner);
+        assert_eq!(
+            events[0].value,
+            "0x3333333333333333333333333333333333333333"
+        );
+        assert_eq!(events[0].block_number, 18000005);
+        assert_eq!(
+            serde_json::to_value(&events[0]).unwrap()["kind"],
+            serde_json::json!("owner")
+        );
+    }
+}
@@ -0,0 +1,49 @@
+use crate::entity::subgraph::domain_event::DomainChangeEvent;
+use async_trait::async_trait;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use serde::Serialize;
+
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    async fn publish(&self, events: &[DomainChangeEvent]) -> Result<(), anyhow::Error>;
+}
+
+/// Sends every batch of events as JSON `{"events": [...]}` in POST request to `url`
+pub struct WebhookPublisher {
+    url: url::Url,
+    client: ClientWithMiddleware,
+}
+
+#[derive(Serialize)]
+struct WebhookBody<'a> {
+    events: &'a [DomainChangeEvent],
+}
+
+impl WebhookPublisher {
+    pub fn new(url: url::Url, timeout_seconds: u64) -> Self {
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
+        let client = ClientBuilder::new(
+            reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(timeout_seconds))
+                .build()
+                .expect("valid client"),
+        )
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .build();
+        Self { url, client }
+    }
+}
+
+#[async_trait]
+impl EventPublisher for WebhookPublisher {
+    async fn publish(&self, events: &[DomainChangeEvent]) -> Result<(), anyhow::Error> {
+        self.client
+            .post(self.url.clone())
+            .json(&WebhookBody { events })
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
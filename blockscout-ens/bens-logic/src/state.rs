@@ -0,0 +1,12 @@
+//! Own database of bens, which keeps state derived from subgraphs,
+//! like cursors of notifier. Database of graph-node is only read.
+
+use sqlx::{migrate::MigrateError, PgPool};
+
+/// Applies migrations of state database. Migrations applied by other tools
+/// are ignored, so state tables could live in database shared with them
+pub async fn run_migrations(pool: &PgPool) -> Result<(), MigrateError> {
+    let mut migrator = sqlx::migrate!("./migrations");
+    migrator.set_ignore_missing(true);
+    migrator.run(pool).await
+}
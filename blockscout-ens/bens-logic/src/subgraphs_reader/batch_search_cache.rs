@@ -0,0 +1,134 @@
+use crate::{
+    entity::subgraph::domain::DomainWithAddress,
+    subgraphs_reader::{sql, sql::QueryOptions, SubgraphReadError, TldRules},
+};
+use cached::{Cached, TimedSizedCache};
+use sqlx::PgPool;
+use std::{collections::HashMap, sync::Mutex};
+
+/// Short-living cache of `batch_search_addresses` results, keyed by schema and address.
+/// Addresses without resolved domain are cached as well, so only cache misses
+/// of the batch are requested from database
+pub struct BatchSearchCache {
+    inner: Mutex<TimedSizedCache<(String, String), Option<DomainWithAddress>>>,
+}
+
+impl BatchSearchCache {
+    pub fn new(ttl_seconds: u64, size: usize) -> Self {
+        Self {
+            inner: Mutex::new(TimedSizedCache::with_size_and_lifespan(size, ttl_seconds)),
+        }
+    }
+
+    pub async fn batch_search_addresses(
+        &self,
+        pool: &PgPool,
+        options: &QueryOptions,
+        schema: &str,
+        addresses: &[String],
+        tld_rules: &TldRules,
+        quirks: sql::SqlQuirks,
+    ) -> Result<Vec<DomainWithAddress>, SubgraphReadError> {
+        let mut found = Vec::with_capacity(addresses.len());
+        let mut misses = Vec::new();
+        {
+            let mut cache = self
+                .inner
+                .lock()
+                .expect("batch search cache lock is poisoned");
+            for address in addresses {
+                match cache.cache_get(&(schema.to_string(), address.clone())) {
+                    Some(cached) => found.extend(cached.clone()),
+                    None => misses.push(address.clone()),
+                }
+            }
+        }
+        if misses.is_empty() {
+            return Ok(found);
+        }
+
+        let mut fetched: HashMap<String, DomainWithAddress> =
+            sql::batch_search_addresses(pool, options, schema, &misses, tld_rules, quirks)
+                .await?
+                .into_iter()
+                .map(|domain| (domain.resolved_address.clone(), domain))
+                .collect();
+        let mut cache = self
+            .inner
+            .lock()
+            .expect("batch search cache lock is poisoned");
+        for address in misses {
+            let domain = fetched.remove(&address);
+            cache.cache_set((schema.to_string(), address), domain.clone());
+            found.extend(domain);
+        }
+        Ok(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subgraphs_reader::sql::SqlQuirks;
+    use pretty_assertions::assert_eq;
+
+    const VITALIK: &str = "0xd8da6bf26964af9d7eed9e03e53415d37aa96045";
+    const WAGMI: &str = "0x9c996076a85b46061d9a70ff81f013853a86b619";
+    const UNKNOWN: &str = "0x0101010101010101010101010101010101010102";
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn batch_search_cache_works(pool: PgPool) {
+        let cache = BatchSearchCache::new(60, 100);
+        let names = |domains: Vec<DomainWithAddress>| {
+            let mut names: Vec<String> = domains.into_iter().map(|d| d.domain_name).collect();
+            names.sort();
+            names
+        };
+
+        let domains = cache
+            .batch_search_addresses(
+                &pool,
+                &QueryOptions::default(),
+                "sgd1",
+                &[VITALIK.to_string(), UNKNOWN.to_string()],
+                &TldRules::default(),
+                SqlQuirks::default(),
+            )
+            .await
+            .expect("failed to search addresses");
+        assert_eq!(names(domains), vec!["vitalik.eth"]);
+
+        // remove resolved addresses, so only cache misses are affected
+        sqlx::query("UPDATE sgd1.domain SET resolved_address = NULL")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let domains = cache
+            .batch_search_addresses(
+                &pool,
+                &QueryOptions::default(),
+                "sgd1",
+                &[VITALIK.to_string(), WAGMI.to_string(), UNKNOWN.to_string()],
+                &TldRules::default(),
+                SqlQuirks::default(),
+            )
+            .await
+            .expect("failed to search addresses");
+        assert_eq!(names(domains), vec!["vitalik.eth"]);
+
+        // other schema is cached separately
+        let domains = cache
+            .batch_search_addresses(
+                &pool,
+                &QueryOptions::default(),
+                "sgd2",
+                &[VITALIK.to_string()],
+                &TldRules::default(),
+                SqlQuirks::default(),
+            )
+            .await
+            .expect("failed to search addresses");
+        assert_eq!(names(domains), Vec::<String>::new());
+    }
+}
@@ -0,0 +1,155 @@
+//! Changes of domains indexed in block ranges, polled by notifier of domain owners
+
+use super::{freshness, sql, SubgraphReadError, SubgraphReader};
+use crate::entity::subgraph::domain_event::{DomainChangeEvent, DomainChangeKind};
+use ethers::types::TxHash;
+
+impl SubgraphReader {
+    /// Latest block indexed by default subgraph of network.
+    /// None if indexing status of subgraph is unknown
+    pub async fn latest_indexed_block(
+        &self,
+        network_id: i64,
+    ) -> Result<Option<i64>, SubgraphReadError> {
+        let network = self.network(network_id)?;
+        let schema = network.default_subgraph.schema_name;
+        let status = freshness::deployment_statuses(self.read_pool.as_ref(), &[schema])
+            .await?
+            .into_iter()
+            .next();
+        Ok(status.and_then(|s| s.latest_block_number))
+    }
+
+    /// Changes of owner, wrapped owner and resolved address of domains
+    /// indexed in blocks `(after_block, to_block]`, in chronological order
+    pub async fn domain_changes(
+        &self,
+        network_id: i64,
+        after_block: i64,
+        to_block: i64,
+    ) -> Result<Vec<DomainChangeEvent>, SubgraphReadError> {
+        let network = self.network(network_id)?;
+        let schema = &network.default_subgraph.schema_name;
+        let rows = sql::find_domain_changes(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
+            schema,
+            after_block,
+            to_block,
+        )
+        .await?;
+        rows.into_iter()
+            .map(|row| {
+                let kind = match row.kind.as_str() {
+                    "owner" => DomainChangeKind::Owner,
+                    "wrapped_owner" => DomainChangeKind::WrappedOwner,
+                    "resolved_address" => DomainChangeKind::ResolvedAddress,
+                    kind => {
+                        return Err(SubgraphReadError::Internal(format!(
+                            "unknown domain change kind '{kind}'"
+                        )))
+                    }
+                };
+                Ok(DomainChangeEvent {
+                    network_id,
+                    domain_id: row.domain_id,
+                    domain_name: row.domain_name,
+                    kind,
+                    value: row.value,
+                    block_number: row.block_number as i64,
+                    transaction_hash: TxHash::from_slice(row.transaction_id.as_slice()),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{mocked_networks_with_blockscout, tx_hash, DEFAULT_CHAIN_ID};
+    use pretty_assertions::assert_eq;
+    use sqlx::PgPool;
+    use std::sync::Arc;
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn domain_changes_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        let latest_block = reader
+            .latest_indexed_block(DEFAULT_CHAIN_ID)
+            .await
+            .expect("failed to get latest block");
+        assert_eq!(latest_block, Some(18000000));
+
+        let vitalik_id = "0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835";
+        let sashaxyz_id = "0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff";
+        let changes = reader
+            .domain_changes(DEFAULT_CHAIN_ID, 8000000, 18000000)
+            .await
+            .expect("failed to get domain changes");
+        let changes: Vec<_> = changes
+            .into_iter()
+            .map(|c| {
+                assert_eq!(c.network_id, DEFAULT_CHAIN_ID);
+                (
+                    c.domain_id,
+                    c.domain_name,
+                    c.kind,
+                    c.value,
+                    c.block_number,
+                    c.transaction_hash,
+                )
+            })
+            .collect();
+        assert_eq!(
+            changes,
+            vec![
+                (
+                    vitalik_id.to_string(),
+                    Some("vitalik.eth".to_string()),
+                    DomainChangeKind::ResolvedAddress,
+                    "0xd8da6bf26964af9d7eed9e03e53415d37aa96045".to_string(),
+                    8834378,
+                    tx_hash("0x09922ac0caf1efcc8f68ce004f382b46732258870154d8805707a1d4b098dfd0"),
+                ),
+                (
+                    vitalik_id.to_string(),
+                    Some("vitalik.eth".to_string()),
+                    DomainChangeKind::ResolvedAddress,
+                    "0xd8da6bf26964af9d7eed9e03e53415d37aa96045".to_string(),
+                    11862656,
+                    tx_hash("0x160ef4492c731ac6b59beebe1e234890cd55d4c556f8847624a0b47125fe4f84"),
+                ),
+                (
+                    sashaxyz_id.to_string(),
+                    Some("sashaxyz.eth".to_string()),
+                    DomainChangeKind::Owner,
+                    "0x1111111111111111111111111111111111111111".to_string(),
+                    17999990,
+                    tx_hash("0x1a7e2f5b9c0d3e6f8a1b4c7d0e3f6a9b2c5d8e1f4a7b0c3d6e9f2a5b8c1d4e7f"),
+                ),
+                (
+                    sashaxyz_id.to_string(),
+                    Some("sashaxyz.eth".to_string()),
+                    DomainChangeKind::WrappedOwner,
+                    "0x2222222222222222222222222222222222222222".to_string(),
+                    17999995,
+                    tx_hash("0x3c9a4b7d1e2f5a8b0c3d6e9f2a5b8c1d4e7f0a3b6c9d2e5f8a1b4c7d0e3f6a9b"),
+                ),
+            ]
+        );
+
+        let changes = reader
+            .domain_changes(DEFAULT_CHAIN_ID, 17999990, 18000000)
+            .await
+            .expect("failed to get domain changes");
+        assert_eq!(
+            changes.iter().map(|c| c.kind).collect::<Vec<_>>(),
+            vec![DomainChangeKind::WrappedOwner]
+        );
+    }
+}
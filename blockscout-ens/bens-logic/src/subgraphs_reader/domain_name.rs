@@ -1,5 +1,11 @@
-use crate::hash_name::domain_id;
+use crate::{
+    hash_name::domain_id,
+    name_safety::{is_encoded_label_hash, NORMALIZER},
+};
+use ens_normalize_rs::{DisallowedSequence, ProcessError};
 use ethers::types::{Address, Bytes};
+use serde::Deserialize;
+use thiserror::Error;
 
 #[derive(Debug, Clone)]
 pub struct DomainName {
@@ -8,32 +14,217 @@ pub struct DomainName {
     pub name: String,
 }
 
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum DomainNameError {
+    #[error("empty name provided")]
+    Empty,
+    #[error("invalid label '{label}': {reason}")]
+    InvalidLabel {
+        label: String,
+        #[source]
+        reason: ProcessError,
+    },
+    #[error("unsupported top-level domain '{0}'")]
+    UnsupportedTld(String),
+    #[error("invalid dns name '{name}': {reason}")]
+    InvalidDnsName { name: String, reason: &'static str },
+}
+
+/// Maximum length of DNS label, longer labels are not DNS labels
+const MAX_DNS_LABEL_LENGTH: usize = 63;
+
+/// Top-level domains served by subgraph, like `eth` for ENS or `bnb` for SpaceID
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+pub struct TldRules {
+    /// Names with other TLDs are rejected. Empty list means any TLD
+    pub allowed_tlds: Vec<String>,
+    /// Appended to names consisting of single label, like `vitalik` -> `vitalik.eth`
+    pub default_tld: Option<String>,
+    /// TLDs registered onchain, like `eth` for ENS. Names with other TLDs, like `example.com`,
+    /// are imported from DNS by DNSSEC. Names are not expected to be DNS names if empty
+    #[serde(default)]
+    pub native_tlds: Vec<String>,
+}
+
+impl TldRules {
+    fn is_tld(&self, name: &str) -> bool {
+        self.default_tld.as_deref() == Some(name) || self.allowed_tlds.iter().any(|t| t == name)
+    }
+
+    /// Returns true if TLD of normalized `name` is allowed
+    pub fn is_allowed(&self, name: &str) -> bool {
+        let tld = name.rsplit('.').next().unwrap_or(name);
+        self.allowed_tlds.is_empty() || self.allowed_tlds.iter().any(|t| t == tld)
+    }
+
+    /// Returns true if `name` is imported from DNS, that is its TLD is not native.
+    /// Single label gets default TLD, so it's not DNS name if default TLD is set
+    pub fn is_dns_name(&self, name: &str) -> bool {
+        let name = name.trim_matches('.');
+        let tld = match name.rsplit_once('.') {
+            Some((_, tld)) => tld,
+            None if self.default_tld.is_some() => return false,
+            None => name,
+        };
+        !self.native_tlds.is_empty()
+            && !tld.is_empty()
+            && !self.native_tlds.iter().any(|t| t.eq_ignore_ascii_case(tld))
+    }
+}
+
+/// Reverse registrar keeps primary name of address as name of `{addr}.{node_suffix}` node.
+/// Mainnet registrar uses `addr.reverse`, while L2 registrars (ENSIP-19) use
+/// `{coin_type}.reverse` with hex coin type of the chain, like `80002105.reverse` for Base
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct ReverseRegistrar {
+    pub node_suffix: String,
+}
+
+impl Default for ReverseRegistrar {
+    fn default() -> Self {
+        Self {
+            node_suffix: "addr.reverse".to_string(),
+        }
+    }
+}
+
+impl ReverseRegistrar {
+    pub fn from_coin_type(coin_type: u64) -> Self {
+        Self {
+            node_suffix: format!("{coin_type:x}.reverse"),
+        }
+    }
+
+    /// Coin type of EVM chain according to ENSIP-11
+    pub fn from_chain_id(chain_id: u64) -> Self {
+        Self::from_coin_type(0x80000000 | chain_id)
+    }
+
+    pub fn reverse_node(&self, addr: &Address) -> DomainName {
+        // label name is hexed address without 0x prefix
+        let label_name = format!("{:x}", addr);
+        let name = format!("{}.{}", label_name, self.node_suffix);
+        // note that reverse nodes don't need empty_label_hash
+        let id = domain_id(&name, None);
+        DomainName {
+            id,
+            label_name,
+            name,
+        }
+    }
+
+    /// Id of parent of all reverse nodes, like namehash(`addr.reverse`)
+    pub fn parent_id(&self) -> String {
+        domain_id(&self.node_suffix, None)
+    }
+}
+
 impl DomainName {
-    pub fn new(name: &str, empty_label_hash: Option<Bytes>) -> Result<Self, anyhow::Error> {
+    /// Normalizes name according to ENSIP-15 and computes its namehash.
+    /// Labels with unknown name, encoded as `[labelhash]`, are kept as is
+    pub fn new(name: &str, empty_label_hash: Option<Bytes>) -> Result<Self, DomainNameError> {
+        Self::new_with_namehash(name, |name| domain_id(name, empty_label_hash.clone()))
+    }
+
+    /// Same as [`DomainName::new`], but id is computed by `namehash` of naming protocol
+    pub fn new_with_namehash(
+        name: &str,
+        namehash: impl Fn(&str) -> String,
+    ) -> Result<Self, DomainNameError> {
         let name = name.trim_matches('.');
         if name.is_empty() {
-            anyhow::bail!("empty name provided");
+            return Err(DomainNameError::Empty);
         }
-        let (label_name, _) = name.split_once('.').unwrap_or((name, ""));
-        let id = domain_id(name, empty_label_hash);
+        let name = name
+            .split('.')
+            .map(normalize_label)
+            .collect::<Result<Vec<_>, _>>()?
+            .join(".");
+        let (label_name, _) = name.split_once('.').unwrap_or((&name, ""));
+        let id = namehash(&name);
         Ok(Self {
             id,
             label_name: label_name.to_string(),
-            name: name.to_string(),
+            name,
         })
     }
 
-    pub fn addr_reverse(addr: &Address) -> Self {
-        // label name is hexed address without 0x prefix
-        let label_name = format!("{:x}", addr);
-        let name = format!("{}.addr.reverse", label_name);
-        // note that addr.reverse doesn't need empty_label_hash
-        let id = domain_id(&name, None);
-        Self {
+    /// Parses name imported from DNS by DNSSEC, like `example.com`. Labels following DNS rules,
+    /// including punycode `xn--` labels rejected by ENSIP-15, are only lowercased.
+    /// Other labels, like ENS subnames of DNS name, are normalized according to ENSIP-15.
+    /// Id is computed by `namehash` of naming protocol, like for other names
+    pub fn new_dns(name: &str, namehash: impl Fn(&str) -> String) -> Result<Self, DomainNameError> {
+        let name = name.trim_matches('.');
+        if name.is_empty() {
+            return Err(DomainNameError::Empty);
+        }
+        let name = name
+            .split('.')
+            .map(|label| {
+                if is_dns_label(label) {
+                    Ok(label.to_ascii_lowercase())
+                } else {
+                    normalize_label(label)
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?
+            .join(".");
+        let invalid = |reason| DomainNameError::InvalidDnsName {
+            name: name.clone(),
+            reason,
+        };
+        let (label_name, _) = name.split_once('.').unwrap_or((&name, ""));
+        let tld = name.rsplit('.').next().unwrap_or_default();
+        if !is_dns_label(tld) {
+            return Err(invalid("top-level domain is not dns label"));
+        }
+        if name.split('.').any(|label| label.len() > u8::MAX as usize) {
+            return Err(invalid("label is too long for dns encoding"));
+        }
+        let id = namehash(&name);
+        Ok(Self {
             id,
-            label_name,
+            label_name: label_name.to_string(),
             name,
+        })
+    }
+
+    /// Same as [`DomainName::new`], but appends default TLD to single-label name
+    /// and rejects name with TLD not allowed by `tld_rules`.
+    /// Names imported from DNS are parsed by [`DomainName::new_dns`]
+    pub fn with_tld_rules(
+        name: &str,
+        empty_label_hash: Option<Bytes>,
+        tld_rules: &TldRules,
+    ) -> Result<Self, DomainNameError> {
+        Self::with_tld_rules_and_namehash(name, tld_rules, |name| {
+            domain_id(name, empty_label_hash.clone())
+        })
+    }
+
+    /// Same as [`DomainName::with_tld_rules`], but id is computed by `namehash` of naming protocol
+    pub fn with_tld_rules_and_namehash(
+        name: &str,
+        tld_rules: &TldRules,
+        namehash: impl Fn(&str) -> String,
+    ) -> Result<Self, DomainNameError> {
+        if tld_rules.is_dns_name(name) {
+            let domain_name = Self::new_dns(name, &namehash)?;
+            return check_allowed_tld(domain_name, tld_rules);
         }
+        let domain_name = Self::new_with_namehash(name, &namehash)?;
+        let domain_name = match &tld_rules.default_tld {
+            Some(tld) if !domain_name.is_child_of_tld() && !tld_rules.is_tld(&domain_name.name) => {
+                Self::new_with_namehash(&format!("{}.{tld}", domain_name.name), &namehash)?
+            }
+            _ => domain_name,
+        };
+        check_allowed_tld(domain_name, tld_rules)
+    }
+
+    /// Node of mainnet reverse registrar, `{addr}.addr.reverse`
+    pub fn addr_reverse(addr: &Address) -> Self {
+        ReverseRegistrar::default().reverse_node(addr)
     }
 
     // Returns true if the domain name is a child of a TLD
@@ -44,6 +235,51 @@ impl DomainName {
     }
 }
 
+/// Recomputes namehash of `name` and compares it with `id` of subgraph row.
+/// Mismatch means that row is corrupted or name was saved incorrectly
+pub fn verify_domain_name(id: &str, name: &str, empty_label_hash: Option<Bytes>) -> bool {
+    domain_id(name, empty_label_hash).eq_ignore_ascii_case(id)
+}
+
+fn check_allowed_tld(
+    domain_name: DomainName,
+    tld_rules: &TldRules,
+) -> Result<DomainName, DomainNameError> {
+    if !tld_rules.is_allowed(&domain_name.name) {
+        let tld = domain_name.name.rsplit('.').next().unwrap_or_default();
+        return Err(DomainNameError::UnsupportedTld(tld.to_string()));
+    }
+    Ok(domain_name)
+}
+
+/// Letters, digits and hyphens not at the edges (LDH rule), up to 63 characters.
+/// Punycode labels like `xn--80ak6aa92e` are DNS labels too
+fn is_dns_label(label: &str) -> bool {
+    !label.is_empty()
+        && label.len() <= MAX_DNS_LABEL_LENGTH
+        && !label.starts_with('-')
+        && !label.ends_with('-')
+        && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+}
+
+fn normalize_label(label: &str) -> Result<String, DomainNameError> {
+    if label.is_empty() {
+        return Err(DomainNameError::InvalidLabel {
+            label: label.to_string(),
+            reason: DisallowedSequence::EmptyLabel.into(),
+        });
+    }
+    if is_encoded_label_hash(label) {
+        return Ok(label.to_string());
+    }
+    NORMALIZER
+        .normalize(label)
+        .map_err(|reason| DomainNameError::InvalidLabel {
+            label: label.to_string(),
+            reason,
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -103,6 +339,165 @@ mod tests {
         }
     }
 
+    #[test]
+    fn normalization_works() {
+        for (name, expected_name) in [
+            ("VitaLik.ETH", "vitalik.eth"),
+            ("wa🇬🇲i.eth", "wa🇬🇲i.eth"),
+            // emoji ZWJ sequence
+            ("👨‍👩‍👧.eth", "👨‍👩‍👧.eth"),
+            // full width characters
+            ("ｖｉｔａｌｉｋ.eth", "vitalik.eth"),
+            // invisible characters are ignored
+            ("vitalik.e\u{200b}th", "vitalik.eth"),
+            (
+                "[dec08c9dbbdd0890e300eb5062089b2d4b1c40e3673bbccb5423f7b37dcf9a9c].eth",
+                "[dec08c9dbbdd0890e300eb5062089b2d4b1c40e3673bbccb5423f7b37dcf9a9c].eth",
+            ),
+        ] {
+            let domain_name = DomainName::new(name, None).expect("failed to build domain name");
+            assert_eq!(domain_name.name, expected_name);
+            assert_eq!(domain_name.id, domain_id(expected_name, None));
+        }
+
+        assert_eq!(
+            DomainName::new("..", None).unwrap_err(),
+            DomainNameError::Empty
+        );
+        for (name, invalid_label) in [
+            ("te_st.eth", "te_st"),
+            ("vit@lik.eth", "vit@lik"),
+            ("vitalik..eth", ""),
+            ("[abc].eth", "[abc]"),
+        ] {
+            let err = DomainName::new(name, None).expect_err("name should be invalid");
+            assert!(
+                matches!(&err, DomainNameError::InvalidLabel { label, .. } if label == invalid_label),
+                "unexpected error for '{name}': {err:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn tld_rules_work() {
+        let rules = TldRules {
+            allowed_tlds: vec!["bnb".to_string(), "arb".to_string()],
+            default_tld: Some("bnb".to_string()),
+            native_tlds: vec![],
+        };
+        for (name, expected_name) in [
+            ("levvv.bnb", "levvv.bnb"),
+            ("LEVVV.ARB", "levvv.arb"),
+            ("sub.levvv.arb", "sub.levvv.arb"),
+            // single label gets default tld
+            ("levvv", "levvv.bnb"),
+            // tlds themselves are kept as is
+            ("bnb", "bnb"),
+            ("arb", "arb"),
+        ] {
+            let domain_name = DomainName::with_tld_rules(name, None, &rules)
+                .expect("failed to build domain name");
+            assert_eq!(domain_name.name, expected_name);
+            assert_eq!(domain_name.id, domain_id(expected_name, None));
+        }
+        assert_eq!(
+            DomainName::with_tld_rules("levvv.eth", None, &rules).unwrap_err(),
+            DomainNameError::UnsupportedTld("eth".to_string())
+        );
+
+        // default rules accept any name as is
+        let domain_name = DomainName::with_tld_rules("levvv", None, &TldRules::default())
+            .expect("failed to build domain name");
+        assert_eq!(domain_name.name, "levvv");
+    }
+
+    #[test]
+    fn dns_names_work() {
+        for (name, expected_name, expected_label) in [
+            ("example.com", "example.com", "example"),
+            ("Example.COM.", "example.com", "example"),
+            // punycode is not valid ENSIP-15 label
+            ("xn--80ak6aa92e.com", "xn--80ak6aa92e.com", "xn--80ak6aa92e"),
+            // subnames created in ENS are normalized
+            (
+                "Vitalik👍.example.com",
+                "vitalik👍.example.com",
+                "vitalik👍",
+            ),
+            ("com", "com", "com"),
+        ] {
+            let domain_name = DomainName::new_dns(name, |name| domain_id(name, None))
+                .expect("failed to build dns name");
+            assert_eq!(domain_name.name, expected_name);
+            assert_eq!(domain_name.label_name, expected_label);
+            assert_eq!(domain_name.id, domain_id(expected_name, None));
+        }
+        let empty_label_hash = Bytes::from([1; 32]);
+        let domain_name = DomainName::new_dns("example.com", |name| {
+            domain_id(name, Some(empty_label_hash.clone()))
+        })
+        .expect("failed to build dns name");
+        assert_eq!(
+            domain_name.id,
+            domain_id("example.com", Some(empty_label_hash))
+        );
+        assert!(DomainName::new("xn--80ak6aa92e.com", None).is_err());
+        assert_eq!(
+            DomainName::new_dns("..", |name| domain_id(name, None)).unwrap_err(),
+            DomainNameError::Empty
+        );
+        assert!(matches!(
+            DomainName::new_dns("example.-com", |name| domain_id(name, None)).unwrap_err(),
+            DomainNameError::InvalidDnsName { name, .. } if name == "example.-com"
+        ));
+        assert!(matches!(
+            DomainName::new_dns(&format!("example.{}", "a".repeat(64)), |name| {
+                domain_id(name, None)
+            })
+            .unwrap_err(),
+            DomainNameError::InvalidDnsName { .. }
+        ));
+    }
+
+    #[test]
+    fn dns_names_dispatch_by_tld() {
+        let rules = TldRules {
+            allowed_tlds: vec![],
+            default_tld: Some("eth".to_string()),
+            native_tlds: vec!["eth".to_string(), "reverse".to_string()],
+        };
+        for (name, is_dns_name) in [
+            ("example.com", true),
+            ("sub.example.XYZ", true),
+            ("vitalik.eth", false),
+            ("vitalik.ETH", false),
+            ("addr.reverse", false),
+            // single label gets default tld
+            ("vitalik", false),
+            ("", false),
+        ] {
+            assert_eq!(rules.is_dns_name(name), is_dns_name, "{name}");
+        }
+        // native tlds are not configured
+        assert!(!TldRules::default().is_dns_name("example.com"));
+
+        let domain_name = DomainName::with_tld_rules("xn--80ak6aa92e.com", None, &rules)
+            .expect("failed to build dns name");
+        assert_eq!(domain_name.name, "xn--80ak6aa92e.com");
+        let domain_name =
+            DomainName::with_tld_rules("vitalik", None, &rules).expect("failed to build name");
+        assert_eq!(domain_name.name, "vitalik.eth");
+
+        let rules = TldRules {
+            allowed_tlds: vec!["eth".to_string(), "com".to_string()],
+            ..rules
+        };
+        assert_eq!(
+            DomainName::with_tld_rules("example.xyz", None, &rules).unwrap_err(),
+            DomainNameError::UnsupportedTld("xyz".to_string())
+        );
+    }
+
     #[test]
     fn reverse_works() {
         let addr = Address::from_str("0x43C960FA130e3Eb58e7AaF65f46F76B5C607C3a9").unwrap();
@@ -116,4 +511,44 @@ mod tests {
             "43c960fa130e3eb58e7aaf65f46f76b5c607c3a9"
         )
     }
+
+    #[test]
+    fn verify_domain_name_works() {
+        let vitalik_id = "0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835";
+        assert!(verify_domain_name(vitalik_id, "vitalik.eth", None));
+        assert!(verify_domain_name(
+            &vitalik_id.to_uppercase().replace("0X", "0x"),
+            "vitalik.eth",
+            None
+        ));
+        assert!(verify_domain_name(
+            vitalik_id,
+            "[af2caa1c2ca1d027f1ac823b529d0a67cd144264b2789fa2ea4d63a67c7103cc].eth",
+            None
+        ));
+        assert!(!verify_domain_name(vitalik_id, "notvitalik.eth", None));
+        assert!(!verify_domain_name(
+            vitalik_id,
+            "vitalik.eth",
+            Some([1; 32].into())
+        ));
+    }
+
+    #[test]
+    fn l2_reverse_works() {
+        let addr = Address::from_str("0x43C960FA130e3Eb58e7AaF65f46F76B5C607C3a9").unwrap();
+        let base = ReverseRegistrar::from_chain_id(8453);
+        assert_eq!(base, ReverseRegistrar::from_coin_type(2147492101));
+        assert_eq!(base.node_suffix, "80002105.reverse");
+        let domain_name = base.reverse_node(&addr);
+        assert_eq!(
+            domain_name.name,
+            "43c960fa130e3eb58e7aaf65f46f76b5c607c3a9.80002105.reverse"
+        );
+        assert_eq!(domain_name.id, domain_id(&domain_name.name, None));
+        assert_eq!(
+            ReverseRegistrar::default().parent_id(),
+            "0x91d1777781884d03a6757a803996e38de2a42967fb37eeaca72729271025a9e2"
+        );
+    }
 }
@@ -5,22 +5,62 @@ use bigdecimal::{num_bigint::BigInt, Num};
 use ethers::types::Address;
 use std::str::FromStr;
 
+/// Token contracts of subgraph, shared by naming protocols
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TokenContracts {
+    pub native: Option<Address>,
+    pub wrapped: Option<Address>,
+    /// Template of token metadata url with `{contract}` and `{id}` placeholders
+    pub metadata_url: Option<String>,
+}
+
+impl From<&SubgraphSettings> for TokenContracts {
+    fn from(settings: &SubgraphSettings) -> Self {
+        Self {
+            native: settings.native_token_contract,
+            wrapped: settings.wrapped_token_contract,
+            metadata_url: settings.token_metadata_url.clone(),
+        }
+    }
+}
+
+impl TokenContracts {
+    pub fn token(&self, contract: Address, id: String, _type: DomainTokenType) -> DomainToken {
+        DomainToken {
+            metadata_url: self.metadata_url(&contract, &id),
+            id,
+            contract,
+            _type,
+        }
+    }
+
+    fn metadata_url(&self, contract: &Address, id: &str) -> Option<String> {
+        self.metadata_url.as_ref().map(|template| {
+            template
+                .replace("{contract}", &format!("{contract:#x}"))
+                .replace("{id}", id)
+        })
+    }
+}
+
+/// Tokens of ENS-like registrar: ERC-721 token of second level domain
+/// and ERC-1155 token of NameWrapper
 #[tracing::instrument(
     level = "info",
-    skip(domain, subgraph_settings),
+    skip(domain, contracts),
     fields(
         domain_name = domain.name,
-        native_token_contract =? subgraph_settings.native_token_contract,
+        native_token_contract =? contracts.native,
     ),
     err,
 )]
 pub fn extract_tokens_from_domain(
     domain: &DetailedDomain,
-    subgraph_settings: &SubgraphSettings,
+    contracts: &TokenContracts,
 ) -> Result<Vec<DomainToken>, anyhow::Error> {
     let mut tokens = vec![];
 
-    if let Some(contract) = subgraph_settings.native_token_contract {
+    if let Some(contract) = contracts.native {
         let is_second_level_domain = domain
             .name
             .as_ref()
@@ -33,29 +73,27 @@ pub fn extract_tokens_from_domain(
                 .as_ref()
                 .ok_or_else(|| anyhow::anyhow!("no labelhash in database"))?;
 
+            // ERC-721 token id of registrar is uint256 of labelhash
             let id = token_id(&hex::encode(labelhash))?;
-            tokens.push(DomainToken {
-                id,
-                contract,
-                _type: DomainTokenType::Native,
-            });
+            tokens.push(contracts.token(contract, id, DomainTokenType::Native));
         }
     };
 
     if domain.wrapped_owner.is_some() {
+        // ERC-1155 token id of NameWrapper is uint256 of namehash
         let id = token_id(&domain.id)?;
-        let contract = Address::from_str(&domain.owner).context("parse owner as address")?;
-        tokens.push(DomainToken {
-            id,
-            contract,
-            _type: DomainTokenType::Wrapped,
-        });
+        let contract = match contracts.wrapped {
+            Some(contract) => contract,
+            // owner of wrapped domain is NameWrapper contract itself
+            None => Address::from_str(&domain.owner).context("parse owner as address")?,
+        };
+        tokens.push(contracts.token(contract, id, DomainTokenType::Wrapped));
     };
 
     Ok(tokens)
 }
 
-fn token_id(hexed_id: &str) -> Result<String, anyhow::Error> {
+pub fn token_id(hexed_id: &str) -> Result<String, anyhow::Error> {
     let id = BigInt::from_str_radix(hexed_id.trim_start_matches("0x"), 16)
         .context("convert token_id to number")?;
     Ok(id.to_string())
@@ -125,6 +163,7 @@ mod tests {
                     contract: Address::from_str(native_contract)
                         .expect("invalid native_contract provided"),
                     _type: DomainTokenType::Native,
+                    metadata_url: None,
                 }],
             ),
             // Native contract provided, wrapped owner provided, but third level domain, so only wrapped token
@@ -142,6 +181,7 @@ mod tests {
                     contract: Address::from_str(wrapped_contract)
                         .expect("invalid wrapped_contract provided"),
                     _type: DomainTokenType::Wrapped,
+                    metadata_url: None,
                 }],
             ),
             // Everything is provided
@@ -160,24 +200,69 @@ mod tests {
                         contract: Address::from_str(native_contract)
                             .expect("invalid native_contract provided"),
                         _type: DomainTokenType::Native,
+                        metadata_url: None,
                     },
                     DomainToken {
                         id: "25625468407840116393736812939389551247551040926951238633020744494000165263268".to_string(),
                         contract: Address::from_str(wrapped_contract)
                             .expect("invalid wrapped_contract provided"),
                         _type: DomainTokenType::Wrapped,
+                        metadata_url: None,
                     },
                 ],
             ),
         ] {
-            let settings = SubgraphSettings {
-                native_token_contract,
+            let contracts = TokenContracts {
+                native: native_token_contract,
                 ..Default::default()
             };
-            let tokens = extract_tokens_from_domain(&domain, &settings)
+            let tokens = extract_tokens_from_domain(&domain, &contracts)
                 .expect("failed to extract tokens from domain");
 
             assert_eq!(tokens, expected_tokens);
         }
     }
+
+    #[test]
+    fn settings_contracts_and_metadata_url_are_used() {
+        let native_contract = "0x1234567890123456789012345678901234567890";
+        let wrapped_contract = "0x0987654321098765432109876543210987654321";
+        let owner = "0x1111111111111111111111111111111111111111";
+        let settings = SubgraphSettings {
+            native_token_contract: addr(native_contract),
+            wrapped_token_contract: addr(wrapped_contract),
+            token_metadata_url: Some(
+                "https://metadata.ens.domains/mainnet/{contract}/{id}".to_string(),
+            ),
+            ..Default::default()
+        };
+        // owner is not a NameWrapper, so wrapped contract is taken from settings
+        let domain = domain("levvv.eth", "0x0200", "0x0100", owner, Some(owner));
+        let tokens = extract_tokens_from_domain(&domain, &(&settings).into())
+            .expect("failed to extract tokens from domain");
+
+        assert_eq!(
+            tokens,
+            vec![
+                DomainToken {
+                    id: "256".to_string(),
+                    contract: Address::from_str(native_contract)
+                        .expect("invalid native_contract provided"),
+                    _type: DomainTokenType::Native,
+                    metadata_url: Some(format!(
+                        "https://metadata.ens.domains/mainnet/{native_contract}/256"
+                    )),
+                },
+                DomainToken {
+                    id: "512".to_string(),
+                    contract: Address::from_str(wrapped_contract)
+                        .expect("invalid wrapped_contract provided"),
+                    _type: DomainTokenType::Wrapped,
+                    metadata_url: Some(format!(
+                        "https://metadata.ens.domains/mainnet/{wrapped_contract}/512"
+                    )),
+                },
+            ]
+        );
+    }
 }
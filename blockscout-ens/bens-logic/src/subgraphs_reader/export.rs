@@ -0,0 +1,175 @@
+//! Export of all domains of address, read in batches by keyset cursor
+
+use super::{
+    label_decoder::LabelDecoderClient,
+    patch::patch_unknown_labels,
+    reader::{parent_domain_id, UNRESOLVABLE_ADDRESSES},
+    sql, ExportAddressInput, Subgraph, SubgraphReadError, SubgraphReader,
+};
+use crate::entity::subgraph::domain::Domain;
+use sqlx::{postgres::PgPool, types::BigDecimal};
+use std::sync::Arc;
+
+/// Keyset cursor over domains of address, so all of them could be read in batches
+/// without loading whole list into memory. Every batch is read by separate query,
+/// so cursor doesn't hold database connection while batches are processed
+pub struct DomainsCursor {
+    pool: Arc<PgPool>,
+    read_pool: Arc<PgPool>,
+    /// Options of batch queries of cursor
+    read_options: sql::QueryOptions,
+    /// Options of background patches of returned domains
+    query_options: sql::QueryOptions,
+    label_decoder: Option<Arc<LabelDecoderClient>>,
+    subgraph: Subgraph,
+    input: ExportAddressInput,
+    parent_id: Option<String>,
+    batch_size: u32,
+    /// `(created_at, id)` of the last returned domain
+    last_key: Option<(BigDecimal, String)>,
+    exhausted: bool,
+}
+
+impl DomainsCursor {
+    /// Returns next batch of domains, empty batch means that cursor is exhausted
+    pub async fn next_batch(&mut self) -> Result<Vec<Domain>, SubgraphReadError> {
+        if self.exhausted {
+            return Ok(vec![]);
+        }
+        let subgraph = &self.subgraph;
+        let domains = sql::find_domains_by_address_after(
+            self.read_pool.as_ref(),
+            &self.read_options,
+            &subgraph.schema_name,
+            &self.input,
+            self.parent_id.as_deref(),
+            subgraph.address_resolve_technique(),
+            subgraph.reverse_registrar(),
+            subgraph.tld_rules(),
+            subgraph.sql_quirks(),
+            self.last_key
+                .as_ref()
+                .map(|(created_at, id)| (created_at, id.as_str())),
+            self.batch_size,
+        )
+        .await?;
+        self.exhausted = domains.len() < self.batch_size as usize;
+        if let Some(last) = domains.last() {
+            self.last_key = Some((last.created_at.clone(), last.id.clone()));
+        }
+        let domains = patch_unknown_labels(
+            self.pool.clone(),
+            self.query_options,
+            &subgraph.schema_name,
+            self.label_decoder.as_deref(),
+            domains,
+        )
+        .await;
+        Ok(subgraph.verify_namehashes(domains))
+    }
+}
+
+impl SubgraphReader {
+    /// Cursor over all domains of `input.address` ordered by creation,
+    /// so they could be exported in batches of `batch_size` without pagination limit
+    pub async fn export_address_domains(
+        &self,
+        input: ExportAddressInput,
+        batch_size: u32,
+    ) -> Result<DomainsCursor, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
+        let subgraph = network.default_subgraph.clone();
+        let parent_id = parent_domain_id(input.parent_domain.as_deref(), &subgraph)?;
+        Ok(DomainsCursor {
+            pool: self.pool.clone(),
+            read_pool: self.read_pool.clone(),
+            read_options: self.batch_options(),
+            query_options: self.query_options,
+            label_decoder: network.label_decoder.clone(),
+            subgraph,
+            exhausted: UNRESOLVABLE_ADDRESSES.contains(&input.address),
+            input,
+            parent_id,
+            batch_size: batch_size.max(1),
+            last_key: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{addr, mocked_networks_with_blockscout, DEFAULT_CHAIN_ID};
+    use pretty_assertions::assert_eq;
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn export_address_domains_works(pool: PgPool) {
+        let clients = mocked_networks_with_blockscout().await;
+        let pool = Arc::new(pool);
+        let reader = SubgraphReader::initialize(pool.clone(), clients)
+            .await
+            .expect("failed to init reader");
+
+        let input = ExportAddressInput {
+            network_id: DEFAULT_CHAIN_ID,
+            address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+            resolved_to: true,
+            owned_by: true,
+            registered_by: false,
+            only_active: false,
+            include_burned: false,
+            parent_domain: None,
+        };
+        // batch size is smaller than number of domains to check that cursor is read till the end
+        let mut cursor = reader
+            .export_address_domains(input, 1)
+            .await
+            .expect("failed to open cursor");
+        let mut batches = vec![];
+        loop {
+            let batch = cursor.next_batch().await.expect("failed to fetch batch");
+            if batch.is_empty() {
+                break;
+            }
+            batches.push(
+                batch
+                    .into_iter()
+                    .map(|d| d.name.unwrap_or_default())
+                    .collect::<Vec<_>>(),
+            );
+        }
+        assert_eq!(
+            batches,
+            vec![
+                vec!["vitalik.eth".to_string()],
+                vec!["sashaxyz.eth".to_string()]
+            ]
+        );
+        // exhausted cursor keeps returning empty batches
+        assert_eq!(
+            cursor.next_batch().await.expect("failed to fetch batch"),
+            vec![]
+        );
+
+        let mut cursor = reader
+            .export_address_domains(
+                ExportAddressInput {
+                    network_id: DEFAULT_CHAIN_ID,
+                    address: addr("0x0000000000000000000000000000000000000000"),
+                    resolved_to: true,
+                    owned_by: true,
+                    registered_by: false,
+                    only_active: false,
+                    include_burned: false,
+                    parent_domain: None,
+                },
+                100,
+            )
+            .await
+            .expect("failed to open cursor");
+        assert_eq!(
+            cursor.next_batch().await.expect("failed to fetch batch"),
+            vec![]
+        );
+    }
+}
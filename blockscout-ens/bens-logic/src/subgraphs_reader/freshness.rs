@@ -0,0 +1,211 @@
+use super::{SubgraphReadError, SubgraphReader};
+use crate::metrics;
+use sqlx::PgPool;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct DeploymentStatus {
+    pub schema_name: String,
+    pub latest_block_number: Option<i64>,
+    pub chain_head_block_number: Option<i64>,
+    pub synced: bool,
+    pub failed: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubgraphFreshness {
+    pub schema_name: String,
+    /// Latest block indexed by subgraph
+    pub latest_block_number: Option<i64>,
+    /// Latest block of chain known to graph-node
+    pub chain_head_block_number: Option<i64>,
+    /// Number of blocks subgraph is behind chain head.
+    /// None if any of block numbers is unknown
+    pub blocks_lag: Option<i64>,
+    pub synced: bool,
+    pub failed: bool,
+    pub is_stale: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkFreshness {
+    pub network_id: i64,
+    pub subgraphs: Vec<SubgraphFreshness>,
+    /// Network is stale if its default subgraph is stale
+    pub is_stale: bool,
+}
+
+impl SubgraphFreshness {
+    pub fn new(status: DeploymentStatus, max_blocks_lag: i64) -> Self {
+        let blocks_lag = status
+            .chain_head_block_number
+            .zip(status.latest_block_number)
+            .map(|(head, latest)| (head - latest).max(0));
+        let is_stale = status.failed
+            || status.latest_block_number.is_none()
+            || blocks_lag
+                .map(|lag| lag > max_blocks_lag)
+                .unwrap_or_default();
+        Self {
+            schema_name: status.schema_name,
+            latest_block_number: status.latest_block_number,
+            chain_head_block_number: status.chain_head_block_number,
+            blocks_lag,
+            synced: status.synced,
+            failed: status.failed,
+            is_stale,
+        }
+    }
+}
+
+pub async fn deployment_statuses(
+    pool: &PgPool,
+    schemas: &[String],
+) -> Result<Vec<DeploymentStatus>, sqlx::Error> {
+    let query = sqlx::query_as(
+        r#"
+        SELECT
+            ds.name AS schema_name,
+            sd.latest_ethereum_block_number::BIGINT AS latest_block_number,
+            en.head_block_number AS chain_head_block_number,
+            sd.synced,
+            sd.failed
+        FROM public.deployment_schemas ds
+        JOIN subgraphs.subgraph_deployment sd ON sd.deployment = ds.subgraph
+        LEFT JOIN public.ethereum_networks en ON en.name = ds.network
+        WHERE ds.name = ANY($1)
+        "#,
+    )
+    .bind(schemas)
+    .fetch_all(pool);
+    metrics::observe_query("deployment_statuses", "subgraphs", query).await
+}
+
+impl SubgraphReader {
+    /// Reports indexing status of all subgraphs of served networks.
+    /// Subgraph is stale if it's failed or lags more than `max_blocks_lag` blocks behind chain head
+    pub async fn freshness(
+        &self,
+        max_blocks_lag: i64,
+    ) -> Result<Vec<NetworkFreshness>, SubgraphReadError> {
+        let networks = self
+            .networks
+            .read()
+            .expect("networks lock is poisoned")
+            .clone();
+        let schemas: Vec<String> = networks
+            .values()
+            .flat_map(|n| n.subgraphs.iter().map(|s| s.schema_name.clone()))
+            .collect();
+        let mut statuses: HashMap<String, DeploymentStatus> =
+            deployment_statuses(self.read_pool.as_ref(), &schemas)
+                .await?
+                .into_iter()
+                .map(|status| (status.schema_name.clone(), status))
+                .collect();
+        let mut result: Vec<NetworkFreshness> = networks
+            .into_iter()
+            .map(|(network_id, network)| {
+                let subgraphs: Vec<SubgraphFreshness> = network
+                    .subgraphs
+                    .iter()
+                    .map(|subgraph| {
+                        let status =
+                            statuses
+                                .remove(&subgraph.schema_name)
+                                .unwrap_or(DeploymentStatus {
+                                    schema_name: subgraph.schema_name.clone(),
+                                    latest_block_number: None,
+                                    chain_head_block_number: None,
+                                    synced: false,
+                                    failed: false,
+                                });
+                        SubgraphFreshness::new(status, max_blocks_lag)
+                    })
+                    .collect();
+                let is_stale = subgraphs
+                    .iter()
+                    .find(|s| s.schema_name == network.default_subgraph.schema_name)
+                    .map(|s| s.is_stale)
+                    .unwrap_or(true);
+                NetworkFreshness {
+                    network_id,
+                    subgraphs,
+                    is_stale,
+                }
+            })
+            .collect();
+        result.sort_by_key(|n| n.network_id);
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::{mocked_networks_with_blockscout, DEFAULT_CHAIN_ID, DEFAULT_SCHEMA};
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    #[test]
+    fn it_works() {
+        for (latest, head, failed, expected_lag, expected_stale) in [
+            (Some(100), Some(150), false, Some(50), false),
+            (Some(100), Some(101), false, Some(1), false),
+            (Some(100), Some(201), false, Some(101), true),
+            // subgraph is ahead of outdated chain head
+            (Some(100), Some(90), false, Some(0), false),
+            (Some(100), Some(100), true, Some(0), true),
+            (None, Some(100), false, None, true),
+            (Some(100), None, false, None, false),
+        ] {
+            let freshness = SubgraphFreshness::new(
+                DeploymentStatus {
+                    schema_name: "sgd1".to_string(),
+                    latest_block_number: latest,
+                    chain_head_block_number: head,
+                    synced: true,
+                    failed,
+                },
+                100,
+            );
+            assert_eq!(freshness.blocks_lag, expected_lag);
+            assert_eq!(freshness.is_stale, expected_stale);
+        }
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn freshness_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        let freshness = reader
+            .freshness(100)
+            .await
+            .expect("failed to get freshness");
+        assert_eq!(freshness.len(), 1);
+        let network = &freshness[0];
+        assert_eq!(network.network_id, DEFAULT_CHAIN_ID);
+        assert!(!network.is_stale);
+        let subgraphs: HashMap<_, _> = network
+            .subgraphs
+            .iter()
+            .map(|s| (s.schema_name.as_str(), s))
+            .collect();
+        let sgd1 = subgraphs.get(DEFAULT_SCHEMA).expect("sgd1 not found");
+        assert_eq!(sgd1.latest_block_number, Some(18000000));
+        assert_eq!(sgd1.chain_head_block_number, Some(18000010));
+        assert_eq!(sgd1.blocks_lag, Some(10));
+        assert!(!sgd1.is_stale);
+        let sgd3 = subgraphs.get("sgd3").expect("sgd3 not found");
+        assert_eq!(sgd3.blocks_lag, Some(10010));
+        assert!(sgd3.is_stale);
+
+        // default subgraph lags behind chain head more than allowed
+        let freshness = reader.freshness(5).await.expect("failed to get freshness");
+        assert!(freshness[0].is_stale);
+    }
+}
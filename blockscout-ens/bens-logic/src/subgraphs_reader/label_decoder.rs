@@ -0,0 +1,152 @@
+use super::domain_name::DomainName;
+use crate::name_safety::{is_encoded_label_hash, NORMALIZER};
+use cached::proc_macro::cached;
+use ethers::{types::U256, utils::keccak256};
+use reqwest::StatusCode;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use serde::Deserialize;
+use tracing::instrument;
+
+/// Client of service, which knows names of labels missing in subgraph,
+/// like ENS metadata service or offchain name decoder
+#[derive(Debug, Clone)]
+pub struct LabelDecoderClient {
+    /// Template with `{labelhash}` (hex) and `{token_id}` (decimal labelhash) placeholders
+    url_template: String,
+    inner: ClientWithMiddleware,
+    max_concurrent_requests: usize,
+}
+
+/// Only name is read from response, for example ENS metadata service responds with
+/// `{"name": "vitalik.eth", ...}` for `/mainnet/{base registrar}/{token_id}`
+#[derive(Debug, Clone, Deserialize)]
+struct DecodedName {
+    name: String,
+}
+
+impl LabelDecoderClient {
+    pub fn new(url_template: String, max_concurrent_requests: usize, timeout_seconds: u64) -> Self {
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
+        let client = ClientBuilder::new(
+            reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(timeout_seconds))
+                .build()
+                .expect("valid client"),
+        )
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .build();
+        Self {
+            url_template,
+            inner: client,
+            max_concurrent_requests,
+        }
+    }
+
+    pub fn max_concurrent_requests(&self) -> usize {
+        self.max_concurrent_requests
+    }
+
+    fn url(&self, labelhash: &[u8; 32]) -> String {
+        self.url_template
+            .replace("{labelhash}", &format!("0x{}", hex::encode(labelhash)))
+            .replace("{token_id}", &U256::from_big_endian(labelhash).to_string())
+    }
+
+    /// Label with given hash or None if service doesn't know it.
+    /// Returned label is normalized and matches the hash, so it's safe to persist
+    #[instrument(name = "label_decoder:decode_label", skip(self), err, level = "debug")]
+    pub async fn decode_label(
+        &self,
+        labelhash: [u8; 32],
+    ) -> reqwest_middleware::Result<Option<String>> {
+        let maybe_name = cached_decoded_name(self, labelhash).await?;
+        let maybe_label = maybe_name
+            .as_deref()
+            .map(|name| name.split_once('.').map(|(l, _)| l).unwrap_or(name))
+            .filter(|label| is_valid_label(label, &labelhash))
+            .map(String::from);
+        Ok(maybe_label)
+    }
+}
+
+#[cached(
+    key = "String",
+    convert = r#"{ client.url(&labelhash) }"#,
+    result = true,
+    time = 86_400, // 24 * 60 * 60 seconds
+    size = 50_000,
+    sync_writes = true,
+)]
+async fn cached_decoded_name(
+    client: &LabelDecoderClient,
+    labelhash: [u8; 32],
+) -> reqwest_middleware::Result<Option<String>> {
+    let response = client.inner.get(client.url(&labelhash)).send().await?;
+    let maybe_name = match response.status() {
+        StatusCode::OK => Some(response.json::<DecodedName>().await?.name),
+        StatusCode::NOT_FOUND => None,
+        // error is not cached, so label is requested again later
+        status => {
+            return Err(reqwest_middleware::Error::Middleware(anyhow::anyhow!(
+                "unexpected status of label decoder response: {status}"
+            )))
+        }
+    };
+    Ok(maybe_name)
+}
+
+fn is_valid_label(label: &str, labelhash: &[u8; 32]) -> bool {
+    !is_encoded_label_hash(label)
+        && keccak256(label.as_bytes()) == *labelhash
+        && NORMALIZER.normalize(label).is_ok_and(|l| l == label)
+}
+
+/// Hash of the first label of name if it's unknown, like `[labelhash].eth`
+pub fn unknown_labelhash(name: &str) -> Option<[u8; 32]> {
+    let (label, _) = name.split_once('.').unwrap_or((name, ""));
+    if !is_encoded_label_hash(label) {
+        return None;
+    }
+    let mut labelhash = [0; 32];
+    hex::decode_to_slice(&label[1..label.len() - 1], &mut labelhash).ok()?;
+    Some(labelhash)
+}
+
+/// Name of domain with the first label replaced by decoded one. Id is kept,
+/// since namehash depends only on labelhashes
+pub fn with_decoded_label(domain_id: &str, name: &str, label: &str) -> DomainName {
+    let name = match name.split_once('.') {
+        Some((_, parent)) => format!("{label}.{parent}"),
+        None => label.to_string(),
+    };
+    DomainName {
+        id: domain_id.to_string(),
+        label_name: label.to_string(),
+        name,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn unknown_labelhash_works() {
+        let vitalik_hash = keccak256("vitalik");
+        let name = format!("[{}].eth", hex::encode(vitalik_hash));
+        assert_eq!(unknown_labelhash(&name), Some(vitalik_hash));
+        assert_eq!(unknown_labelhash("vitalik.eth"), None);
+        assert_eq!(unknown_labelhash(&format!("abc.{name}")), None);
+
+        let decoded = with_decoded_label("0x1", &name, "vitalik");
+        assert_eq!(decoded.name, "vitalik.eth");
+        assert_eq!(decoded.label_name, "vitalik");
+        assert_eq!(decoded.id, "0x1");
+
+        assert!(is_valid_label("vitalik", &vitalik_hash));
+        assert!(!is_valid_label("Vitalik", &keccak256("Vitalik")));
+        assert!(!is_valid_label("notvitalik", &vitalik_hash));
+    }
+}
@@ -0,0 +1,549 @@
+use super::{BatchResolveAddressNamesOutput, GetDomainOutput, SubgraphReadError};
+use crate::entity::subgraph::domain::DetailedDomain;
+use cached::{Cached, TimedSizedCache};
+use ethers::types::{Address, H160};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    collections::{btree_map::Entry, HashMap},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tracing::instrument;
+
+/// Namespace of Lens handles, full handle looks like `lens/stani`
+const LENS_NAMESPACE: &str = "lens";
+/// Maximum number of owners in one `profiles` query of Lens API
+const MAX_OWNERS_PER_REQUEST: usize = 50;
+/// Handles of addresses change rarely, so they are cached for a while,
+/// addresses without handle as well
+const HANDLES_CACHE_TTL_SECONDS: u64 = 600;
+const HANDLES_CACHE_SIZE: usize = 100_000;
+
+const HANDLE_TO_ADDRESS_QUERY: &str = r#"
+query HandleToAddress($handle: Handle!) {
+  handleToAddress(request: { handle: $handle })
+}
+"#;
+
+const PROFILES_QUERY: &str = r#"
+query Profiles($ownedBy: [EvmAddress!]!, $cursor: Cursor) {
+  profiles(request: { where: { ownedBy: $ownedBy }, limit: Fifty, cursor: $cursor }) {
+    items {
+      ownedBy { address }
+      handle { fullHandle }
+    }
+    pageInfo { next }
+  }
+}
+"#;
+
+/// Client of Lens API. Lens handles are not ENS-like domains, they live in Lens protocol
+/// contracts on Polygon, so handles are resolved by Lens API instead of subgraph
+#[derive(Debug, Clone)]
+pub struct LensClient {
+    url: String,
+    inner: ClientWithMiddleware,
+    /// Limit of total time of resolving handles of addresses, including retries
+    timeout: Duration,
+    handles_cache: Arc<Mutex<TimedSizedCache<Address, Option<String>>>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum LensError {
+    #[error("lens api request failed: {0}")]
+    Request(#[from] reqwest_middleware::Error),
+    #[error("lens api error: {0}")]
+    Api(String),
+    #[error("lens api didn't respond in {0:?}")]
+    Timeout(Duration),
+}
+
+impl From<reqwest::Error> for LensError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Request(err.into())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GraphqlResponse<T> {
+    data: Option<T>,
+    #[serde(default)]
+    errors: Vec<GraphqlError>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GraphqlError {
+    message: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HandleToAddressData {
+    handle_to_address: Option<Address>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProfilesData {
+    profiles: ProfilesPage,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfilesPage {
+    items: Vec<Profile>,
+    page_info: PageInfo,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct PageInfo {
+    /// Cursor of the next page, None on the last page
+    next: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Profile {
+    owned_by: ProfileOwner,
+    handle: Option<ProfileHandle>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProfileOwner {
+    address: Address,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProfileHandle {
+    full_handle: String,
+}
+
+impl LensClient {
+    pub fn new(url: String, timeout_seconds: u64) -> Self {
+        let timeout = Duration::from_secs(timeout_seconds);
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
+        let client = ClientBuilder::new(
+            reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .expect("valid client"),
+        )
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .build();
+        Self {
+            url,
+            inner: client,
+            timeout,
+            handles_cache: Arc::new(Mutex::new(TimedSizedCache::with_size_and_lifespan(
+                HANDLES_CACHE_SIZE,
+                HANDLES_CACHE_TTL_SECONDS,
+            ))),
+        }
+    }
+
+    /// Owner of handle like `lens/stani` or None if handle is not minted
+    #[instrument(name = "lens:resolve_handle", skip(self), err, level = "debug")]
+    pub async fn resolve_handle(&self, handle: &str) -> Result<Option<Address>, LensError> {
+        let data: HandleToAddressData = self
+            .query(
+                HANDLE_TO_ADDRESS_QUERY,
+                serde_json::json!({ "handle": handle }),
+            )
+            .await?;
+        Ok(data.handle_to_address.filter(|address| !address.is_zero()))
+    }
+
+    /// Handles of profiles owned by `addresses`. If address owns several profiles,
+    /// handle of the first profile returned by Lens API is used.
+    /// Only addresses missing in cache are requested, and all requests together
+    /// take no longer than timeout of client
+    #[instrument(
+        name = "lens:handles_of",
+        skip_all,
+        fields(addresses = addresses.len()),
+        err,
+        level = "debug"
+    )]
+    pub async fn handles_of(
+        &self,
+        addresses: &[Address],
+    ) -> Result<HashMap<Address, String>, LensError> {
+        let mut handles = HashMap::new();
+        let mut misses = Vec::new();
+        {
+            let mut cache = self
+                .handles_cache
+                .lock()
+                .expect("lens handles cache lock is poisoned");
+            for address in addresses {
+                match cache.cache_get(address) {
+                    Some(cached) => handles.extend(cached.clone().map(|h| (*address, h))),
+                    None => misses.push(*address),
+                }
+            }
+        }
+        if misses.is_empty() {
+            return Ok(handles);
+        }
+
+        let mut fetched = tokio::time::timeout(self.timeout, self.fetch_handles(&misses))
+            .await
+            .map_err(|_| LensError::Timeout(self.timeout))??;
+        let mut cache = self
+            .handles_cache
+            .lock()
+            .expect("lens handles cache lock is poisoned");
+        for address in misses {
+            let handle = fetched.remove(&address);
+            cache.cache_set(address, handle.clone());
+            handles.extend(handle.map(|h| (address, h)));
+        }
+        Ok(handles)
+    }
+
+    async fn fetch_handles(
+        &self,
+        addresses: &[Address],
+    ) -> Result<HashMap<Address, String>, LensError> {
+        let mut handles = HashMap::new();
+        for owners in addresses.chunks(MAX_OWNERS_PER_REQUEST) {
+            let owners_hex: Vec<String> = owners.iter().map(|a| format!("{a:#x}")).collect();
+            let mut cursor: Option<String> = None;
+            // owners with several profiles fill pages, so pages are read until every owner
+            // of chunk has handle or there are no more profiles
+            loop {
+                let data: ProfilesData = self
+                    .query(
+                        PROFILES_QUERY,
+                        serde_json::json!({ "ownedBy": owners_hex, "cursor": cursor }),
+                    )
+                    .await?;
+                for profile in data.profiles.items {
+                    if let Some(handle) = profile.handle {
+                        handles
+                            .entry(profile.owned_by.address)
+                            .or_insert(handle.full_handle);
+                    }
+                }
+                cursor = data.profiles.page_info.next;
+                if cursor.is_none() || owners.iter().all(|o| handles.contains_key(o)) {
+                    break;
+                }
+            }
+        }
+        Ok(handles)
+    }
+
+    async fn query<T: DeserializeOwned>(
+        &self,
+        query: &str,
+        variables: serde_json::Value,
+    ) -> Result<T, LensError> {
+        let request = serde_json::json!({ "query": query, "variables": variables });
+        let response: GraphqlResponse<T> = self
+            .inner
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(err) = response.errors.into_iter().next() {
+            return Err(LensError::Api(err.message));
+        }
+        response
+            .data
+            .ok_or_else(|| LensError::Api("response without data".to_string()))
+    }
+}
+
+/// Full handle like `lens/stani` if `name` is Lens handle, written as `lens/stani`,
+/// `lens/@stani` or `@stani`. Handles consist of lowercase letters, digits and underscores
+pub fn parse_handle(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    let local_name = match name.split_once('/') {
+        Some((namespace, local_name)) if namespace == LENS_NAMESPACE => {
+            local_name.strip_prefix('@').unwrap_or(local_name)
+        }
+        Some(_) => return None,
+        None => name.strip_prefix('@')?,
+    };
+    let is_valid = !local_name.is_empty()
+        && local_name.len() <= 26
+        && local_name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_');
+    is_valid.then(|| format!("{LENS_NAMESPACE}/{local_name}"))
+}
+
+/// Lens handle is not a domain of subgraph, so only handle and its owner are returned.
+/// Handle resolves to address of its owner. Lens API failures are logged
+/// and treated as unknown handle, same as in resolving of addresses
+pub(super) async fn get_lens_domain(
+    lens: &LensClient,
+    handle: String,
+) -> Result<Option<GetDomainOutput>, SubgraphReadError> {
+    let maybe_owner = lens.resolve_handle(&handle).await.unwrap_or_else(|err| {
+        tracing::warn!(handle, err = ?err, "failed to resolve lens handle");
+        None
+    });
+    Ok(maybe_owner.map(|owner| lens_domain(handle, owner)))
+}
+pub(super) fn lens_domain(handle: String, owner: Address) -> GetDomainOutput {
+    let owner = format!("{owner:#x}");
+    let domain = DetailedDomain {
+        id: handle.clone(),
+        label_name: handle
+            .split_once('/')
+            .map(|(_, local_name)| local_name.to_string()),
+        name: Some(handle),
+        owner: owner.clone(),
+        resolved_address: Some(owner),
+        offchain_provider: Some("lens".to_string()),
+        stored_offchain: true,
+        ..Default::default()
+    };
+    GetDomainOutput {
+        domain,
+        tokens: vec![],
+        resolved_address_history: vec![],
+    }
+}
+/// Lens handles of addresses. Lens API failures don't fail requests of addresses,
+/// so they are logged and addresses are treated as addresses without handles
+pub(super) async fn lens_handles(lens: &LensClient, addresses: &[H160]) -> HashMap<H160, String> {
+    lens.handles_of(addresses).await.unwrap_or_else(|err| {
+        tracing::warn!(err = ?err, "failed to get lens handles of addresses, skip them");
+        HashMap::new()
+    })
+}
+/// Lens handles of addresses are used as primary names of addresses without domains
+/// and listed in `other_names` otherwise
+pub(super) async fn add_lens_handles(
+    lens: &LensClient,
+    addresses: &[H160],
+    output: &mut BatchResolveAddressNamesOutput,
+) {
+    for (address, handle) in lens_handles(lens, addresses).await {
+        match output.names.entry(format!("{address:#x}")) {
+            Entry::Occupied(entry) => output
+                .other_names
+                .entry(entry.key().clone())
+                .or_default()
+                .push(handle),
+            Entry::Vacant(entry) => {
+                entry.insert(handle);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        subgraphs_reader::{BatchResolveAddressNamesInput, GetDomainInput, SubgraphReader},
+        test_utils::{addr, mocked_networks_with_blockscout, DEFAULT_CHAIN_ID},
+    };
+    use pretty_assertions::assert_eq;
+    use sqlx::PgPool;
+
+    #[test]
+    fn parse_handle_works() {
+        for name in [
+            "lens/stani",
+            "lens/@stani",
+            "@stani",
+            "Lens/Stani",
+            " @stani ",
+        ] {
+            assert_eq!(parse_handle(name).as_deref(), Some("lens/stani"), "{name}");
+        }
+        for name in [
+            "stani",
+            "stani.eth",
+            "stani.lens",
+            "other/stani",
+            "lens/",
+            "@",
+            "@sta.ni",
+            "lens/stani/1",
+        ] {
+            assert_eq!(parse_handle(name), None, "{name}");
+        }
+    }
+
+    #[test]
+    fn profiles_response_is_parsed() {
+        let response: GraphqlResponse<ProfilesData> = serde_json::from_value(serde_json::json!({
+            "data": {
+                "profiles": {
+                    "items": [
+                        {
+                            "ownedBy": { "address": "0x7241dddec3a6af367882eaf9651b87e1c7549dff" },
+                            "handle": { "fullHandle": "lens/stani" }
+                        },
+                        {
+                            "ownedBy": { "address": "0x7241dddec3a6af367882eaf9651b87e1c7549dff" },
+                            "handle": null
+                        }
+                    ],
+                    "pageInfo": { "next": "cursor-1" }
+                }
+            }
+        }))
+        .unwrap();
+        let page = response.data.unwrap().profiles;
+        assert_eq!(page.page_info.next.as_deref(), Some("cursor-1"));
+        let items = page.items;
+        assert_eq!(items.len(), 2);
+        assert_eq!(
+            items[0].handle.as_ref().map(|h| h.full_handle.as_str()),
+            Some("lens/stani")
+        );
+        assert!(items[1].handle.is_none());
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn lens_handles_work(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let stani_address = addr("0x7241dddec3a6af367882eaf9651b87e1c7549dff");
+        let vitalik_address = addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045");
+        let server = wiremock::MockServer::start().await;
+        let lens_query = |query: &str| {
+            wiremock::Mock::given(wiremock::matchers::path("/lens"))
+                .and(wiremock::matchers::body_string_contains(query))
+        };
+        lens_query("handleToAddress")
+            .and(wiremock::matchers::body_string_contains("lens/stani"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": { "handleToAddress": format!("{stani_address:#x}") }
+                })),
+            )
+            .mount(&server)
+            .await;
+        lens_query("handleToAddress")
+            .and(wiremock::matchers::body_string_contains("lens/nobody"))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "data": { "handleToAddress": null } })),
+            )
+            .mount(&server)
+            .await;
+        // profiles are split between pages, and every page is requested once, since
+        // handles are cached after the first request
+        lens_query("profiles")
+            .and(wiremock::matchers::body_string_contains(r#""cursor":null"#))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": { "profiles": {
+                        "items": [{
+                            "ownedBy": { "address": format!("{stani_address:#x}") },
+                            "handle": { "fullHandle": "lens/stani" }
+                        }],
+                        "pageInfo": { "next": "page-2" }
+                    } }
+                })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        lens_query("profiles")
+            .and(wiremock::matchers::body_string_contains(
+                r#""cursor":"page-2""#,
+            ))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "data": { "profiles": {
+                        "items": [{
+                            "ownedBy": { "address": format!("{vitalik_address:#x}") },
+                            "handle": { "fullHandle": "lens/vitalik" }
+                        }],
+                        "pageInfo": { "next": null }
+                    } }
+                })),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut networks = mocked_networks_with_blockscout().await;
+        networks.get_mut(&DEFAULT_CHAIN_ID).unwrap().lens =
+            Some(LensClient::new(format!("{}/lens", server.uri()), 5));
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        let get_domain = |name: &str| {
+            reader.get_domain(GetDomainInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: name.to_string(),
+                only_active: false,
+                coin_types: vec![],
+                include_resolved_address_history: false,
+            })
+        };
+
+        for name in ["lens/stani", "@stani"] {
+            let stani = get_domain(name)
+                .await
+                .expect("failed to get lens handle")
+                .expect("lens handle not found");
+            assert_eq!(
+                stani.domain,
+                DetailedDomain {
+                    id: "lens/stani".to_string(),
+                    name: Some("lens/stani".to_string()),
+                    label_name: Some("stani".to_string()),
+                    owner: format!("{stani_address:#x}"),
+                    resolved_address: Some(format!("{stani_address:#x}")),
+                    offchain_provider: Some("lens".to_string()),
+                    stored_offchain: true,
+                    ..Default::default()
+                }
+            );
+        }
+        let result = get_domain("lens/nobody")
+            .await
+            .expect("failed to get lens handle");
+        assert!(result.is_none());
+
+        // handle is primary name only if address has no domain
+        let names = reader
+            .batch_resolve_address_names(BatchResolveAddressNamesInput {
+                network_id: DEFAULT_CHAIN_ID,
+                addresses: vec![stani_address, vitalik_address],
+            })
+            .await
+            .expect("failed to resolve addresses");
+        assert_eq!(
+            names
+                .names
+                .get(&format!("{stani_address:#x}"))
+                .map(String::as_str),
+            Some("lens/stani")
+        );
+        assert_eq!(
+            names
+                .names
+                .get(&format!("{vitalik_address:#x}"))
+                .map(String::as_str),
+            Some("vitalik.eth")
+        );
+        assert_eq!(
+            names.other_names.get(&format!("{vitalik_address:#x}")),
+            Some(&vec!["lens/vitalik".to_string()])
+        );
+
+        // address without domains is resolved to its handle
+        let stani = reader
+            .get_address(DEFAULT_CHAIN_ID, stani_address)
+            .await
+            .expect("failed to get address")
+            .expect("lens handle of address not found");
+        assert_eq!(stani.domain.name.as_deref(), Some("lens/stani"));
+    }
+}
@@ -1,14 +1,31 @@
+mod batch_search_cache;
 pub mod blockscout;
+mod domain_changes;
 mod domain_name;
 mod domain_tokens;
+mod export;
+mod freshness;
+pub mod label_decoder;
+pub mod lens;
+mod namehash_check;
+pub mod offchain;
 mod pagination;
 mod patch;
+mod protocol;
 mod reader;
 mod resolve_addresses;
+pub mod resolver_rpc;
+mod reverse_records_cache;
 mod schema_selector;
 mod sql;
 mod types;
 
+pub use domain_name::{verify_domain_name, DomainNameError, ReverseRegistrar, TldRules};
+pub use export::DomainsCursor;
+pub use freshness::{NetworkFreshness, SubgraphFreshness};
+pub use namehash_check::{NamehashMismatch, SubgraphNamehashReport};
 pub use pagination::*;
+pub use protocol::{EnsProtocol, NamingProtocol, ProtocolKind, UnstoppableDomainsProtocol};
 pub use reader::*;
+pub use sql::{ChunkPolicy, RetryPolicy, SqlQuirks, StatementTimeouts};
 pub use types::*;
@@ -0,0 +1,237 @@
+//! Consistency check of subgraph rows: namehash of every current domain with known name
+//! is recomputed and compared with its id, so corrupted rows could be found and reindexed
+
+use super::{sql, sql::QueryOptions, Network, Subgraph, SubgraphReadError, SubgraphReader};
+use futures::FutureExt;
+use sqlx::PgPool;
+
+const CHECK_BATCH_SIZE: i64 = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+struct NamedDomainRow {
+    vid: i64,
+    id: String,
+    name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NamehashMismatch {
+    pub id: String,
+    pub name: String,
+    /// Namehash of `name`
+    pub expected_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubgraphNamehashReport {
+    pub network_id: i64,
+    pub schema_name: String,
+    pub checked_domains: u64,
+    pub mismatches_count: u64,
+    /// First mismatches found, limited by `max_reported_mismatches` of the check
+    pub mismatches: Vec<NamehashMismatch>,
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Scans current domains of subgraph in batches ordered by `vid`
+pub async fn check_subgraph_namehashes(
+    pool: &PgPool,
+    options: &QueryOptions,
+    network_id: i64,
+    subgraph: &Subgraph,
+    max_reported_mismatches: usize,
+) -> Result<SubgraphNamehashReport, SubgraphReadError> {
+    let schema = subgraph.schema_name();
+    let mut report = SubgraphNamehashReport {
+        network_id,
+        schema_name: schema.to_string(),
+        checked_domains: 0,
+        mismatches_count: 0,
+        mismatches: vec![],
+        checked_at: chrono::Utc::now(),
+    };
+    let mut last_vid = -1;
+    loop {
+        let rows = named_domains_batch(pool, options, schema, last_vid).await?;
+        let Some(last_row) = rows.last() else {
+            break;
+        };
+        last_vid = last_row.vid;
+        report.checked_domains += rows.len() as u64;
+        for row in rows {
+            let expected_id = subgraph.namehash(&row.name);
+            if expected_id.eq_ignore_ascii_case(&row.id) {
+                continue;
+            }
+            report.mismatches_count += 1;
+            if report.mismatches.len() < max_reported_mismatches {
+                report.mismatches.push(NamehashMismatch {
+                    expected_id,
+                    id: row.id,
+                    name: row.name,
+                });
+            }
+        }
+    }
+    if report.mismatches_count > 0 {
+        tracing::warn!(
+            network_id,
+            schema,
+            mismatches_count = report.mismatches_count,
+            "found domains with namehash not matching name"
+        );
+    }
+    Ok(report)
+}
+
+async fn named_domains_batch(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    after_vid: i64,
+) -> Result<Vec<NamedDomainRow>, SubgraphReadError> {
+    let query = format!(
+        r#"
+        SELECT vid, id, name
+        FROM {schema}.domain
+        WHERE {block_range} AND name IS NOT NULL AND vid > $1
+        ORDER BY vid
+        LIMIT $2
+        "#,
+        block_range = sql::DOMAIN_BLOCK_RANGE_WHERE_CLAUSE,
+    );
+    options
+        .run(pool, "named_domains_batch", schema, |conn| {
+            sqlx::query_as(&query)
+                .bind(after_vid)
+                .bind(CHECK_BATCH_SIZE)
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
+        .await
+}
+
+impl SubgraphReader {
+    /// Recomputes namehashes of all current domains of served subgraphs and reports
+    /// domains with name not matching id. Subgraphs failed to check are skipped
+    pub async fn check_namehashes(
+        &self,
+        max_reported_mismatches: usize,
+    ) -> Vec<SubgraphNamehashReport> {
+        let mut networks: Vec<(i64, Network)> = self
+            .networks
+            .read()
+            .expect("networks lock is poisoned")
+            .clone()
+            .into_iter()
+            .collect();
+        networks.sort_by_key(|(network_id, _)| *network_id);
+        let mut reports = vec![];
+        for (network_id, network) in networks {
+            for subgraph in network.subgraphs {
+                match check_subgraph_namehashes(
+                    self.read_pool.as_ref(),
+                    &self.batch_options(),
+                    network_id,
+                    &subgraph,
+                    max_reported_mismatches,
+                )
+                .await
+                {
+                    Ok(report) => reports.push(report),
+                    Err(err) => tracing::error!(
+                        err =? err,
+                        schema = subgraph.schema_name,
+                        "failed to check namehashes of subgraph"
+                    ),
+                }
+            }
+        }
+        reports
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        subgraphs_reader::{
+            domain_name::DomainName, LookupAddressInput, SubgraphReader, SubgraphSettings,
+        },
+        test_utils::{addr, mocked_networks_with_blockscout, DEFAULT_CHAIN_ID, DEFAULT_SCHEMA},
+    };
+    use pretty_assertions::assert_eq;
+    use std::sync::Arc;
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn namehash_verification_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let mut networks = mocked_networks_with_blockscout().await;
+        networks
+            .get_mut(&DEFAULT_CHAIN_ID)
+            .unwrap()
+            .subgraph_configs
+            .insert(
+                "ens-subgraph".to_string(),
+                SubgraphSettings {
+                    verify_namehash: true,
+                    ..Default::default()
+                },
+            );
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        // mock data already has some placeholder reverse names
+        let initial_reports = reader.check_namehashes(10).await;
+
+        let sashaxyz_id = "0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff";
+        sqlx::query("UPDATE sgd1.domain SET name = 'corrupted.eth' WHERE id = $1")
+            .bind(sashaxyz_id)
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+
+        let result = reader
+            .lookup_address(LookupAddressInput {
+                network_id: DEFAULT_CHAIN_ID,
+                address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+                resolved_to: true,
+                owned_by: false,
+                registered_by: false,
+                only_active: false,
+                include_burned: false,
+                parent_domain: None,
+                pagination: Default::default(),
+            })
+            .await
+            .expect("failed to get vitalik domains");
+        assert_eq!(
+            result
+                .items
+                .iter()
+                .map(|d| (d.name.as_deref(), d.namehash_mismatch))
+                .collect::<Vec<_>>(),
+            vec![(Some("vitalik.eth"), false), (Some("corrupted.eth"), true)]
+        );
+
+        // subgraph without tables fails to check and is skipped
+        let reports = reader.check_namehashes(10).await;
+        assert_eq!(reports.len(), 1);
+        let report = &reports[0];
+        assert_eq!(report.schema_name, DEFAULT_SCHEMA);
+        assert_eq!(report.checked_domains, initial_reports[0].checked_domains);
+        assert_eq!(
+            report.mismatches_count,
+            initial_reports[0].mismatches_count + 1
+        );
+        assert!(report.mismatches.contains(&NamehashMismatch {
+            id: sashaxyz_id.to_string(),
+            name: "corrupted.eth".to_string(),
+            expected_id: DomainName::new("corrupted.eth", None).unwrap().id,
+        }));
+
+        let reports = reader.check_namehashes(1).await;
+        assert_eq!(reports[0].mismatches.len(), 1);
+        assert_eq!(reports[0].mismatches_count, report.mismatches_count);
+    }
+}
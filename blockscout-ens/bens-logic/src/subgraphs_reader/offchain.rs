@@ -0,0 +1,341 @@
+use super::{domain_name::DomainName, GetDomainOutput, SubgraphReadError};
+use crate::{entity::subgraph::domain::DetailedDomain, hash_name::dns_encode};
+use ethers::{
+    abi::{self, ParamType, Token},
+    types::{Address, Bytes},
+    utils::id,
+};
+use reqwest::StatusCode;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use serde::{Deserialize, Serialize};
+use tracing::instrument;
+
+/// Provider of subdomains issued offchain, like `*.cb.id` of Coinbase.
+/// Such subdomains never appear in subgraph, so they are resolved by CCIP-read (EIP-3668)
+/// request to gateway of provider, which responds like ENS `OffchainResolver` gateway
+#[derive(Debug, Clone)]
+pub struct OffchainProvider {
+    /// Label of provider returned with resolved domains, like `coinbase`
+    pub name: String,
+    /// Normalized parent name, like `cb.id`
+    pub parent: String,
+    /// Offchain resolver of parent, passed to gateway as `{sender}`
+    pub resolver: Address,
+    /// Gateway url with `{sender}` and `{data}` placeholders. Without `{data}` gateway gets POST request
+    gateway_url: String,
+    inner: ClientWithMiddleware,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct GatewayRequest {
+    sender: String,
+    data: Bytes,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct GatewayResponse {
+    data: Bytes,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum OffchainResolveError {
+    #[error("gateway request failed: {0}")]
+    Request(#[from] reqwest_middleware::Error),
+    #[error("unexpected gateway response status: {0}")]
+    UnexpectedStatus(StatusCode),
+    #[error("failed to decode gateway response: {0}")]
+    Decode(#[from] abi::Error),
+}
+
+impl From<reqwest::Error> for OffchainResolveError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Request(err.into())
+    }
+}
+
+impl OffchainProvider {
+    pub fn new(
+        name: String,
+        parent: String,
+        resolver: Address,
+        gateway_url: String,
+        timeout_seconds: u64,
+    ) -> Self {
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
+        let client = ClientBuilder::new(
+            reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(timeout_seconds))
+                .build()
+                .expect("valid client"),
+        )
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .build();
+        Self {
+            name,
+            parent,
+            resolver,
+            gateway_url,
+            inner: client,
+        }
+    }
+
+    /// True if normalized `name` is subdomain of provider parent. Parent itself lives onchain
+    pub fn is_provider_of(&self, name: &str) -> bool {
+        name.strip_suffix(&self.parent)
+            .is_some_and(|label| label.len() > 1 && label.ends_with('.'))
+    }
+
+    /// Address resolved by gateway for domain or None if name is not issued.
+    /// Signature of response is not verified, since it requires call of resolver contract
+    #[instrument(
+        name = "offchain_provider:resolve_address",
+        skip_all,
+        fields(provider = self.name, name = domain_name.name),
+        err,
+        level = "debug"
+    )]
+    pub async fn resolve_address(
+        &self,
+        domain_name: &DomainName,
+    ) -> Result<Option<Address>, OffchainResolveError> {
+        let data = resolve_calldata(domain_name);
+        let response = request_gateway(&self.inner, &self.gateway_url, self.resolver, data).await?;
+        match response {
+            Some(response) => decode_resolved_address(&response),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Sends CCIP-read request to gateway. `url` is template with `{sender}` and `{data}`
+/// placeholders, without `{data}` gateway gets POST request.
+/// Returns `data` of gateway response or None if gateway doesn't know requested name
+pub(super) async fn request_gateway(
+    client: &ClientWithMiddleware,
+    url: &str,
+    sender: Address,
+    data: Bytes,
+) -> Result<Option<Bytes>, OffchainResolveError> {
+    let sender = format!("{sender:#x}");
+    let request = if url.contains("{data}") {
+        let url = url
+            .replace("{sender}", &sender)
+            .replace("{data}", &data.to_string());
+        client.get(url)
+    } else {
+        let url = url.replace("{sender}", &sender);
+        client.post(url).json(&GatewayRequest { sender, data })
+    };
+    let response = request.send().await?;
+    match response.status() {
+        StatusCode::OK => Ok(Some(response.json::<GatewayResponse>().await?.data)),
+        StatusCode::NOT_FOUND => Ok(None),
+        status => Err(OffchainResolveError::UnexpectedStatus(status)),
+    }
+}
+
+/// Calldata of `resolve(bytes name, bytes data)` of ENSIP-10 with `addr(bytes32 node)` as inner call
+pub(super) fn resolve_calldata(domain_name: &DomainName) -> Bytes {
+    let node = hex::decode(domain_name.id.trim_start_matches("0x")).expect("id is valid hex");
+    let addr_call = [
+        id("addr(bytes32)").as_slice(),
+        &abi::encode(&[Token::FixedBytes(node)]),
+    ]
+    .concat();
+    let args = abi::encode(&[
+        Token::Bytes(dns_encode(&domain_name.name)),
+        Token::Bytes(addr_call),
+    ]);
+    [id("resolve(bytes,bytes)").as_slice(), &args]
+        .concat()
+        .into()
+}
+
+/// Gateway responds with `(bytes result, uint64 expires, bytes signature)`,
+/// where result is abi encoded address
+fn decode_resolved_address(data: &[u8]) -> Result<Option<Address>, OffchainResolveError> {
+    let tokens = abi::decode(
+        &[ParamType::Bytes, ParamType::Uint(64), ParamType::Bytes],
+        data,
+    )?;
+    let result = tokens
+        .into_iter()
+        .next()
+        .and_then(Token::into_bytes)
+        .unwrap_or_default();
+    Ok(decode_address(&result)?)
+}
+
+/// Decodes result of `addr(bytes32 node)`. Empty result and zero address mean that name
+/// doesn't resolve to any address
+pub(super) fn decode_address(result: &[u8]) -> Result<Option<Address>, abi::Error> {
+    if result.is_empty() {
+        return Ok(None);
+    }
+    let address = abi::decode(&[ParamType::Address], result)?
+        .into_iter()
+        .next()
+        .and_then(Token::into_address)
+        .filter(|address| !address.is_zero());
+    Ok(address)
+}
+
+/// Offchain subdomain has no onchain data, so only name and records known by gateway are returned
+pub(super) async fn get_offchain_domain(
+    provider: &OffchainProvider,
+    domain_name: DomainName,
+) -> Result<Option<GetDomainOutput>, SubgraphReadError> {
+    let maybe_address = provider.resolve_address(&domain_name).await.map_err(|e| {
+        SubgraphReadError::Internal(format!(
+            "failed to resolve offchain domain via '{}' gateway: {e}",
+            provider.name
+        ))
+    })?;
+    let Some(address) = maybe_address else {
+        return Ok(None);
+    };
+    let domain = DetailedDomain {
+        id: domain_name.id,
+        name: Some(domain_name.name),
+        label_name: Some(domain_name.label_name),
+        resolved_address: Some(format!("{address:#x}")),
+        resolver: Some(format!("{:#x}", provider.resolver)),
+        offchain_provider: Some(provider.name.clone()),
+        stored_offchain: true,
+        ..Default::default()
+    };
+    Ok(Some(GetDomainOutput {
+        domain,
+        tokens: vec![],
+        resolved_address_history: vec![],
+    }))
+}
+
+/// Gateway response with given resolved address, as produced by ENS `OffchainResolver` gateway
+#[cfg(test)]
+pub(crate) fn encode_gateway_response(address: Address) -> serde_json::Value {
+    let result = abi::encode(&[Token::Address(address)]);
+    let data = abi::encode(&[
+        Token::Bytes(result),
+        Token::Uint(u64::MAX.into()),
+        Token::Bytes(vec![0; 65]),
+    ]);
+    serde_json::json!({ "data": Bytes::from(data) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        subgraphs_reader::{GetDomainInput, SubgraphReader},
+        test_utils::{addr, mocked_networks_with_blockscout, DEFAULT_CHAIN_ID},
+    };
+    use pretty_assertions::assert_eq;
+    use sqlx::PgPool;
+    use std::sync::Arc;
+
+    #[test]
+    fn is_provider_of_works() {
+        let provider = OffchainProvider::new(
+            "coinbase".to_string(),
+            "cb.id".to_string(),
+            Address::zero(),
+            "http://localhost/{sender}/{data}".to_string(),
+            5,
+        );
+        assert!(provider.is_provider_of("alice.cb.id"));
+        assert!(provider.is_provider_of("sub.alice.cb.id"));
+        assert!(!provider.is_provider_of("cb.id"));
+        assert!(!provider.is_provider_of("alicecb.id"));
+        assert!(!provider.is_provider_of("alice.cb.eth"));
+    }
+
+    #[test]
+    fn decode_resolved_address_works() {
+        let address = Address::from_low_u64_be(42);
+        let response: GatewayResponse =
+            serde_json::from_value(encode_gateway_response(address)).unwrap();
+        assert_eq!(
+            decode_resolved_address(&response.data).unwrap(),
+            Some(address)
+        );
+        let response: GatewayResponse =
+            serde_json::from_value(encode_gateway_response(Address::zero())).unwrap();
+        assert_eq!(decode_resolved_address(&response.data).unwrap(), None);
+        assert!(decode_resolved_address(&[1, 2, 3]).is_err());
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn offchain_providers_work(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let resolver = addr("0x1934fc75ab4f65a5ee3a2e0ad3f40e8d5ab1bc4a");
+        let alice_address = addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045");
+        // gateway knows only alice, dns encoded name is part of calldata
+        let gateway_server = wiremock::MockServer::start().await;
+        wiremock::Mock::given(wiremock::matchers::path_regex(format!(
+            "^/gateway/{resolver:#x}/0x[0-9a-f]*{}[0-9a-f]*$",
+            hex::encode(b"\x05alice\x02cb\x02id\x00")
+        )))
+        .respond_with(
+            wiremock::ResponseTemplate::new(200)
+                .set_body_json(encode_gateway_response(alice_address)),
+        )
+        .mount(&gateway_server)
+        .await;
+        let mut networks = mocked_networks_with_blockscout().await;
+        networks
+            .get_mut(&DEFAULT_CHAIN_ID)
+            .unwrap()
+            .offchain_providers = vec![OffchainProvider::new(
+            "coinbase".to_string(),
+            "cb.id".to_string(),
+            resolver,
+            format!("{}/gateway/{{sender}}/{{data}}", gateway_server.uri()),
+            5,
+        )];
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        let get_domain = |name: &str| {
+            reader.get_domain(GetDomainInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: name.to_string(),
+                only_active: false,
+                coin_types: vec![],
+                include_resolved_address_history: false,
+            })
+        };
+
+        let alice = get_domain("Alice.cb.id")
+            .await
+            .expect("failed to get offchain domain")
+            .expect("offchain domain not found");
+        assert_eq!(
+            alice.domain,
+            DetailedDomain {
+                id: DomainName::new("alice.cb.id", None).unwrap().id,
+                name: Some("alice.cb.id".to_string()),
+                label_name: Some("alice".to_string()),
+                resolved_address: Some(format!("{alice_address:#x}")),
+                resolver: Some(format!("{resolver:#x}")),
+                offchain_provider: Some("coinbase".to_string()),
+                stored_offchain: true,
+                ..Default::default()
+            }
+        );
+        assert!(alice.tokens.is_empty());
+
+        // name unknown to gateway and parent itself, which is not in subgraph
+        for name in ["bob.cb.id", "cb.id"] {
+            let result = get_domain(name).await.expect("failed to get domain");
+            assert!(result.is_none(), "{name} should not be found");
+        }
+
+        let vitalik = get_domain("vitalik.eth")
+            .await
+            .expect("failed to get domain")
+            .expect("vitalik.eth not found");
+        assert_eq!(vitalik.domain.offchain_provider, None);
+    }
+}
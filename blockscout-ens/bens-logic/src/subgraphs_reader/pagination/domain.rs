@@ -1,4 +1,7 @@
-use super::{paginate_list, Order, PaginatedList, PaginationInput, Paginator};
+use super::{
+    merge::{merge_sorted, MergedPageToken},
+    paginate_list, Order, PaginatedList, PaginatedResult, PaginationInput, Paginator,
+};
 use crate::{entity::subgraph::domain::Domain, subgraphs_reader::DomainSortField};
 use anyhow::Context;
 use sea_query::{Expr, SelectStatement, SimpleExpr};
@@ -14,6 +17,15 @@ impl Paginator<Domain> for DomainPaginationInput {
         Ok(list)
     }
 
+    fn paginate_result_with_total(
+        &self,
+        items: Vec<Domain>,
+        total_records: i64,
+    ) -> Result<PaginatedResult<Domain>, anyhow::Error> {
+        let list = self.paginate_result(items)?;
+        Ok(PaginatedResult::new(list, total_records, self.page_size))
+    }
+
     fn add_to_query(&self, query: &mut SelectStatement) -> Result<(), anyhow::Error> {
         query
             .order_by(
@@ -41,3 +53,60 @@ impl Paginator<Domain> for DomainPaginationInput {
         Ok(())
     }
 }
+
+impl DomainPaginationInput {
+    /// Pagination of subgraph with `subgraph_index` for list merged from several subgraphs
+    pub fn for_subgraph(&self, subgraph_index: usize) -> Result<Self, anyhow::Error> {
+        let page_token = self
+            .page_token
+            .as_deref()
+            .map(|token| {
+                MergedPageToken::parse(token)
+                    .map(|token| token.subgraph_token(subgraph_index, self.order))
+            })
+            .transpose()?;
+        Ok(Self {
+            page_token,
+            ..self.clone()
+        })
+    }
+
+    /// Merges pages of subgraphs queried with [`Self::for_subgraph`] into single page
+    pub fn paginate_merged(
+        &self,
+        pages: Vec<Vec<Domain>>,
+        total_records: i64,
+    ) -> Result<PaginatedResult<Domain>, anyhow::Error> {
+        let page_size = self.page_size as usize;
+        let mut items = match self.sort {
+            DomainSortField::RegistrationDate => {
+                merge_sorted(pages, self.order, page_size + 1, |d| d.created_at.clone())
+            }
+        };
+        let next_page_token = match items.get(page_size) {
+            Some((subgraph_index, item)) => {
+                let key = match self.sort {
+                    DomainSortField::RegistrationDate => item
+                        .created_at
+                        .to_string()
+                        .parse::<u64>()
+                        .context("cannot build page_token for 'registration_date' sort")?,
+                };
+                Some(
+                    MergedPageToken {
+                        key,
+                        subgraph_index: *subgraph_index,
+                    }
+                    .to_string(),
+                )
+            }
+            None => None,
+        };
+        items.truncate(page_size);
+        let list = PaginatedList {
+            items: items.into_iter().map(|(_, item)| item).collect(),
+            next_page_token,
+        };
+        Ok(PaginatedResult::new(list, total_records, self.page_size))
+    }
+}
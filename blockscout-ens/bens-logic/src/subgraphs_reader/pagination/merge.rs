@@ -0,0 +1,191 @@
+use super::Order;
+use anyhow::Context;
+use std::fmt::Display;
+
+/// Page token of list merged from several subgraphs.
+///
+/// Items are ordered by sort key and then by index of subgraph they came from,
+/// so token points to exact item even if several subgraphs have the same key.
+/// Token of the first subgraph is just a key, so single-subgraph tokens stay the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MergedPageToken {
+    pub key: u64,
+    pub subgraph_index: usize,
+}
+
+impl MergedPageToken {
+    pub fn parse(token: &str) -> Result<Self, anyhow::Error> {
+        let (key, subgraph_index) = match token.split_once(':') {
+            Some((key, index)) => (
+                key,
+                index
+                    .parse()
+                    .context("cannot parse subgraph index of page_token")?,
+            ),
+            None => (token, 0),
+        };
+        let key = key.parse().context("cannot parse key of page_token")?;
+        Ok(Self {
+            key,
+            subgraph_index,
+        })
+    }
+
+    /// Page token to query subgraph with `subgraph_index` with.
+    /// Items of previous subgraphs with the same key were returned on previous page
+    pub fn subgraph_token(&self, subgraph_index: usize, order: Order) -> String {
+        let key = if subgraph_index >= self.subgraph_index {
+            self.key
+        } else {
+            match order {
+                Order::Asc => self.key.saturating_add(1),
+                Order::Desc => self.key.saturating_sub(1),
+            }
+        };
+        key.to_string()
+    }
+}
+
+impl Display for MergedPageToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.subgraph_index {
+            0 => write!(f, "{}", self.key),
+            index => write!(f, "{}:{}", self.key, index),
+        }
+    }
+}
+
+/// K-way merge of lists sorted by `key` in `order`.
+/// Returns at most `limit` items together with index of list they came from
+pub fn merge_sorted<I, K: Ord>(
+    lists: Vec<Vec<I>>,
+    order: Order,
+    limit: usize,
+    key: impl Fn(&I) -> K,
+) -> Vec<(usize, I)> {
+    let mut lists: Vec<_> = lists
+        .into_iter()
+        .map(|list| list.into_iter().peekable())
+        .collect();
+    let mut merged = Vec::new();
+    while merged.len() < limit {
+        let next = lists
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(index, list)| list.peek().map(|item| (index, key(item))))
+            .min_by(|(a_index, a), (b_index, b)| {
+                let by_key = match order {
+                    Order::Asc => a.cmp(b),
+                    Order::Desc => b.cmp(a),
+                };
+                by_key.then(a_index.cmp(b_index))
+            });
+        match next {
+            Some((index, _)) => {
+                let item = lists[index].next().expect("item was peeked");
+                merged.push((index, item));
+            }
+            None => break,
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    #[test]
+    fn page_token_works() {
+        for (token, expected) in [("100", (100, 0)), ("100:0", (100, 0)), ("100:2", (100, 2))] {
+            let parsed = MergedPageToken::parse(token).unwrap();
+            assert_eq!(
+                parsed,
+                MergedPageToken {
+                    key: expected.0,
+                    subgraph_index: expected.1
+                }
+            );
+        }
+        assert!(MergedPageToken::parse("abc").is_err());
+        assert!(MergedPageToken::parse("100:abc").is_err());
+
+        let token = MergedPageToken {
+            key: 100,
+            subgraph_index: 1,
+        };
+        assert_eq!(token.to_string(), "100:1");
+        assert_eq!(token.subgraph_token(0, Order::Asc), "101");
+        assert_eq!(token.subgraph_token(0, Order::Desc), "99");
+        assert_eq!(token.subgraph_token(1, Order::Asc), "100");
+        assert_eq!(token.subgraph_token(2, Order::Desc), "100");
+    }
+
+    #[test]
+    fn merge_sorted_works() {
+        let lists = vec![vec![1, 3, 5, 5], vec![2, 3, 4, 6]];
+        assert_eq!(
+            merge_sorted(lists.clone(), Order::Asc, 10, |i| *i),
+            vec![
+                (0, 1),
+                (1, 2),
+                (0, 3),
+                (1, 3),
+                (1, 4),
+                (0, 5),
+                (0, 5),
+                (1, 6)
+            ]
+        );
+        assert_eq!(
+            merge_sorted(lists, Order::Asc, 3, |i| *i),
+            vec![(0, 1), (1, 2), (0, 3)]
+        );
+
+        let lists = vec![vec![5, 3], vec![], vec![6, 3, 1]];
+        assert_eq!(
+            merge_sorted(lists, Order::Desc, 10, |i| *i),
+            vec![(2, 6), (0, 5), (0, 3), (2, 3), (2, 1)]
+        );
+    }
+
+    #[test]
+    fn merged_pages_are_continuous() {
+        // emulates subgraph queries with page token of previous merged page
+        let lists = vec![vec![1, 2, 2, 4], vec![2, 2, 3], vec![1, 4]];
+        let all = merge_sorted(lists.clone(), Order::Asc, usize::MAX, |i| *i);
+        let page_size = 2;
+        let mut token: Option<MergedPageToken> = None;
+        let mut pages = vec![];
+        loop {
+            let subgraph_lists = lists
+                .iter()
+                .enumerate()
+                .map(|(index, list)| {
+                    let min = token
+                        .map(|t| t.subgraph_token(index, Order::Asc).parse().unwrap())
+                        .unwrap_or(0);
+                    list.iter()
+                        .copied()
+                        .filter(|i| *i >= min)
+                        .take(page_size + 1)
+                        .collect()
+                })
+                .collect();
+            let mut merged = merge_sorted(subgraph_lists, Order::Asc, page_size + 1, |i| *i);
+            token = merged
+                .get(page_size)
+                .map(|(subgraph_index, key)| MergedPageToken {
+                    key: *key as u64,
+                    subgraph_index: *subgraph_index,
+                });
+            merged.truncate(page_size);
+            pages.extend(merged);
+            if token.is_none() {
+                break;
+            }
+        }
+        assert_eq!(pages, all);
+    }
+}
@@ -1,6 +1,7 @@
 mod domain;
+mod merge;
 mod paginator;
 
 pub use domain::DomainPaginationInput;
 pub(crate) use paginator::paginate_list;
-pub use paginator::{Order, PaginatedList, PaginationInput, Paginator};
+pub use paginator::{Order, PaginatedList, PaginatedResult, PaginationInput, Paginator};
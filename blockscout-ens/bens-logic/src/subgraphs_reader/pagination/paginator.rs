@@ -5,6 +5,14 @@ pub trait Paginator<I> {
     fn add_to_query(&self, query: &mut sea_query::SelectStatement) -> Result<(), anyhow::Error>;
 
     fn paginate_result(&self, items: Vec<I>) -> Result<PaginatedList<I>, anyhow::Error>;
+
+    /// Same as `paginate_result`, but also carries total number of records
+    /// matching the query, calculated by separate count query
+    fn paginate_result_with_total(
+        &self,
+        items: Vec<I>,
+        total_records: i64,
+    ) -> Result<PaginatedResult<I>, anyhow::Error>;
 }
 
 #[derive(Debug, Clone)]
@@ -30,6 +38,31 @@ impl<I> PaginatedList<I> {
     }
 }
 
+/// Page of list endpoint with metadata required to render pager
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaginatedResult<I> {
+    pub items: Vec<I>,
+    /// Number of records on all pages
+    pub total_records: i64,
+    pub page_size: u32,
+    pub next_page_token: Option<String>,
+}
+
+impl<I> PaginatedResult<I> {
+    pub fn new(list: PaginatedList<I>, total_records: i64, page_size: u32) -> Self {
+        Self {
+            items: list.items,
+            total_records,
+            page_size,
+            next_page_token: list.next_page_token,
+        }
+    }
+
+    pub fn empty(page_size: u32) -> Self {
+        Self::new(PaginatedList::empty(), 0, page_size)
+    }
+}
+
 macro_rules! paginate_list {
     ($items:ident, $page_size:expr, $order_field:ident) => {{
         let page_size = $page_size as usize;
@@ -1,13 +1,19 @@
-use super::{domain_name::DomainName, sql};
+use super::{
+    domain_name::DomainName,
+    label_decoder::{unknown_labelhash, with_decoded_label, LabelDecoderClient},
+    sql::{self, QueryOptions},
+};
 use crate::{
     coin_type::Coin,
     entity::subgraph::domain::{DetailedDomain, Domain},
 };
+use futures::StreamExt;
 use sqlx::postgres::PgPool;
 use std::sync::Arc;
 
 pub fn patch_domain(
     pool: Arc<PgPool>,
+    options: QueryOptions,
     schema: &str,
     mut domain: Domain,
     domain_name: &DomainName,
@@ -20,18 +26,21 @@ pub fn patch_domain(
             "domain has invalid name, creating task to fix to"
         );
         domain.name = Some(domain_name.name.clone());
-        update_domain_name_in_background(pool, schema, domain_name);
+        update_domain_name_in_background(pool, options, schema, domain_name);
     };
     domain
 }
 
 pub fn patch_detailed_domain(
     pool: Arc<PgPool>,
+    options: QueryOptions,
     schema: &str,
     mut domain: DetailedDomain,
-    domain_name: &DomainName,
+    domain_name: Option<&DomainName>,
 ) -> DetailedDomain {
-    if domain.name.as_ref() != Some(&domain_name.name) && domain.id == domain_name.id {
+    if let Some(domain_name) =
+        domain_name.filter(|n| domain.name.as_ref() != Some(&n.name) && domain.id == n.id)
+    {
         tracing::warn!(
             domain_id = domain.id,
             input_name = domain_name.name,
@@ -40,7 +49,7 @@ pub fn patch_detailed_domain(
         );
         domain.name = Some(domain_name.name.clone());
         domain.label_name = Some(domain_name.label_name.clone());
-        update_domain_name_in_background(pool, schema, domain_name);
+        update_domain_name_in_background(pool, options, schema, domain_name);
     };
     domain.other_addresses = sqlx::types::Json(
         domain
@@ -49,11 +58,7 @@ pub fn patch_detailed_domain(
             .into_iter()
             .map(|(coin_type, address)| {
                 let coin = Coin::find_or_unknown(&coin_type);
-                let address = if let Some(encoding) = coin.encoding {
-                    encoding.encode(&address).unwrap_or(address)
-                } else {
-                    address
-                };
+                let address = coin.format_address(address);
                 (coin.name, address)
             })
             .collect(),
@@ -61,11 +66,93 @@ pub fn patch_detailed_domain(
     domain
 }
 
-fn update_domain_name_in_background(pool: Arc<PgPool>, schema: &str, domain_name: &DomainName) {
+/// Replaces unknown first label of domains, like `[labelhash].eth`, with label decoded
+/// by `decoder` and creates tasks to persist decoded names. Domains are kept as is
+/// if decoder is not set or doesn't know the label
+pub async fn patch_unknown_labels(
+    pool: Arc<PgPool>,
+    options: QueryOptions,
+    schema: &str,
+    decoder: Option<&LabelDecoderClient>,
+    domains: Vec<Domain>,
+) -> Vec<Domain> {
+    let Some(decoder) = decoder else {
+        return domains;
+    };
+    futures::stream::iter(domains.into_iter().map(|mut domain| {
+        let pool = pool.clone();
+        async move {
+            let maybe_domain_name =
+                decode_unknown_label(decoder, &domain.id, domain.name.as_deref()).await;
+            if let Some(domain_name) = maybe_domain_name {
+                domain.name = Some(domain_name.name.clone());
+                update_domain_name_in_background(pool, options, schema, &domain_name);
+            }
+            domain
+        }
+    }))
+    .buffered(decoder.max_concurrent_requests().max(1))
+    .collect()
+    .await
+}
+
+/// Same as [`patch_unknown_labels`] for single detailed domain
+pub async fn patch_unknown_label_of_detailed_domain(
+    pool: Arc<PgPool>,
+    options: QueryOptions,
+    schema: &str,
+    decoder: Option<&LabelDecoderClient>,
+    mut domain: DetailedDomain,
+) -> DetailedDomain {
+    let Some(decoder) = decoder else {
+        return domain;
+    };
+    let maybe_domain_name = decode_unknown_label(decoder, &domain.id, domain.name.as_deref()).await;
+    if let Some(domain_name) = maybe_domain_name {
+        domain.name = Some(domain_name.name.clone());
+        domain.label_name = Some(domain_name.label_name.clone());
+        update_domain_name_in_background(pool, options, schema, &domain_name);
+    }
+    domain
+}
+
+/// Name of domain with unknown first label replaced by label decoded by `decoder`.
+/// None if label is known or decoder doesn't know it
+async fn decode_unknown_label(
+    decoder: &LabelDecoderClient,
+    domain_id: &str,
+    name: Option<&str>,
+) -> Option<DomainName> {
+    let name = name?;
+    let labelhash = unknown_labelhash(name)?;
+    match decoder.decode_label(labelhash).await {
+        Ok(Some(label)) => {
+            let domain_name = with_decoded_label(domain_id, name, &label);
+            tracing::info!(
+                domain_id,
+                domain_name = domain_name.name,
+                "decoded unknown label of domain, creating task to save it"
+            );
+            Some(domain_name)
+        }
+        Ok(None) => None,
+        Err(err) => {
+            tracing::warn!(domain_id, "cannot decode label: {err}");
+            None
+        }
+    }
+}
+
+fn update_domain_name_in_background(
+    pool: Arc<PgPool>,
+    options: QueryOptions,
+    schema: &str,
+    domain_name: &DomainName,
+) {
     let schema = schema.to_string();
     let domain_name = domain_name.clone();
     tokio::spawn(async move {
-        match sql::update_domain_name(pool.as_ref(), &schema, &domain_name).await {
+        match sql::update_domain_name(pool.as_ref(), &options, &schema, &domain_name).await {
             Ok(r) => {
                 tracing::info!(
                     rows_affected = r.rows_affected(),
@@ -0,0 +1,37 @@
+use super::NamingProtocol;
+use crate::{
+    entity::subgraph::domain::DetailedDomain,
+    hash_name::domain_id,
+    subgraphs_reader::{
+        domain_tokens::{extract_tokens_from_domain, TokenContracts},
+        DomainToken, SubgraphSettings,
+    },
+};
+use ethers::types::Bytes;
+
+/// ENS and its forks, like SpaceID or RNS
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EnsProtocol {
+    /// Hash of empty name, forks may use other value than zero hash of ENS
+    empty_label_hash: Option<Bytes>,
+    tokens: TokenContracts,
+}
+
+impl EnsProtocol {
+    pub fn new(settings: &SubgraphSettings) -> Self {
+        Self {
+            empty_label_hash: settings.empty_label_hash.clone(),
+            tokens: settings.into(),
+        }
+    }
+}
+
+impl NamingProtocol for EnsProtocol {
+    fn namehash(&self, name: &str) -> String {
+        domain_id(name, self.empty_label_hash.clone())
+    }
+
+    fn domain_tokens(&self, domain: &DetailedDomain) -> Result<Vec<DomainToken>, anyhow::Error> {
+        extract_tokens_from_domain(domain, &self.tokens)
+    }
+}
@@ -0,0 +1,132 @@
+use super::{sql::SqlQuirks, DomainToken, SubgraphSettings};
+use crate::entity::subgraph::domain::DetailedDomain;
+use serde::Deserialize;
+use std::{collections::BTreeMap, fmt::Debug, sync::Arc};
+
+mod ens;
+mod unstoppable_domains;
+
+pub use ens::EnsProtocol;
+pub use unstoppable_domains::UnstoppableDomainsProtocol;
+
+/// Protocol implemented by subgraph, selected per subgraph in networks config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProtocolKind {
+    /// ENS and its forks, like SpaceID or RNS
+    #[default]
+    Ens,
+    /// Unstoppable Domains registry, like `brad.crypto`
+    UnstoppableDomains,
+}
+
+impl ProtocolKind {
+    /// Protocol configured by settings of subgraph
+    pub fn protocol(&self, settings: &SubgraphSettings) -> Arc<dyn NamingProtocol> {
+        match self {
+            ProtocolKind::Ens => Arc::new(EnsProtocol::new(settings)),
+            ProtocolKind::UnstoppableDomains => Arc::new(UnstoppableDomainsProtocol::new(settings)),
+        }
+    }
+
+    /// TLDs served by protocol if `allowed_tlds` are not configured. Empty list means any TLD
+    pub fn default_tlds(&self) -> Vec<String> {
+        match self {
+            ProtocolKind::Ens => vec![],
+            ProtocolKind::UnstoppableDomains => unstoppable_domains::UNSTOPPABLE_DOMAINS_TLDS
+                .iter()
+                .map(|tld| tld.to_string())
+                .collect(),
+        }
+    }
+}
+
+/// Naming protocol served by subgraph, like ENS or Unstoppable Domains.
+/// Reader asks protocol for everything specific to it, so protocols with other namehash,
+/// tokens or records are added as implementation of this trait without changes of query code
+pub trait NamingProtocol: Debug + Send + Sync {
+    /// Id of domain node as stored in subgraph, namehash for ENS-like protocols
+    fn namehash(&self, name: &str) -> String;
+
+    /// Tokens representing domain, like ERC-721 token of registrar
+    fn domain_tokens(&self, domain: &DetailedDomain) -> Result<Vec<DomainToken>, anyhow::Error>;
+
+    /// Addresses keyed by coin type, which protocol stores as text records
+    /// instead of multicoin records of resolver
+    fn coin_addresses_from_text_records(
+        &self,
+        _text_records: &BTreeMap<String, String>,
+    ) -> BTreeMap<String, String> {
+        BTreeMap::new()
+    }
+
+    /// Differences of protocol subgraph from ENS subgraph, applied by sql queries
+    fn sql_quirks(&self) -> SqlQuirks {
+        SqlQuirks::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subgraphs_reader::{DomainNameError, Subgraph, TldRules};
+    use pretty_assertions::assert_eq;
+
+    /// Protocol with id equal to name, so reader doesn't assume ENS namehash
+    #[derive(Debug)]
+    struct PlainNames;
+
+    impl NamingProtocol for PlainNames {
+        fn namehash(&self, name: &str) -> String {
+            format!("id:{name}")
+        }
+
+        fn domain_tokens(
+            &self,
+            _domain: &DetailedDomain,
+        ) -> Result<Vec<DomainToken>, anyhow::Error> {
+            Ok(vec![])
+        }
+
+        fn sql_quirks(&self) -> SqlQuirks {
+            SqlQuirks {
+                expiring_domains: false,
+                ..Default::default()
+            }
+        }
+    }
+
+    #[test]
+    fn custom_protocol_works() {
+        let settings = SubgraphSettings {
+            tld_rules: TldRules {
+                allowed_tlds: vec!["lens".to_string()],
+                default_tld: Some("lens".to_string()),
+                native_tlds: vec![],
+            },
+            grace_period_days: 10,
+            verify_namehash: true,
+            ..Default::default()
+        };
+        let subgraph =
+            Subgraph::new("sgd1".to_string(), settings).with_protocol(Arc::new(PlainNames));
+        let name = subgraph.domain_name("Stani").unwrap();
+        assert_eq!(name.id, "id:stani.lens");
+        assert_eq!(name.name, "stani.lens");
+        assert_eq!(
+            subgraph.domain_name("vitalik.eth").unwrap_err(),
+            DomainNameError::UnsupportedTld("eth".to_string())
+        );
+        assert!(subgraph.tld_ids().iter().all(|id| id == "id:lens"));
+        assert!(!subgraph.namehash_mismatch("id:stani.lens", Some("stani.lens")));
+        assert!(subgraph.namehash_mismatch("id:stani.lens", Some("other.lens")));
+        assert_eq!(
+            subgraph.sql_quirks(),
+            SqlQuirks {
+                expiring_domains: false,
+                wrapped_owners: true,
+                grace_period_days: 10,
+            }
+        );
+    }
+}
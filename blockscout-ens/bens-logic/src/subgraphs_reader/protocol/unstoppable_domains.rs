@@ -0,0 +1,167 @@
+use super::NamingProtocol;
+use crate::{
+    coin_type::Coin,
+    entity::subgraph::domain::DetailedDomain,
+    hash_name::domain_id,
+    subgraphs_reader::{
+        domain_tokens::{token_id, TokenContracts},
+        sql::SqlQuirks,
+        DomainToken, DomainTokenType, SubgraphSettings,
+    },
+};
+use std::collections::BTreeMap;
+
+/// TLDs of Unstoppable Domains registry (UNS) on EVM chains
+pub const UNSTOPPABLE_DOMAINS_TLDS: &[&str] = &[
+    "crypto",
+    "nft",
+    "x",
+    "wallet",
+    "bitcoin",
+    "dao",
+    "888",
+    "blockchain",
+];
+
+/// Unstoppable Domains registry, like `brad.crypto`. Every domain is ERC-721 token
+/// with namehash as token id, addresses are stored as records like `crypto.BTC.address`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct UnstoppableDomainsProtocol {
+    tokens: TokenContracts,
+}
+
+impl UnstoppableDomainsProtocol {
+    pub fn new(settings: &SubgraphSettings) -> Self {
+        Self {
+            tokens: settings.into(),
+        }
+    }
+}
+
+impl NamingProtocol for UnstoppableDomainsProtocol {
+    fn namehash(&self, name: &str) -> String {
+        // UNS uses EIP-137 namehash, custom empty label hash of ENS forks doesn't apply
+        domain_id(name, None)
+    }
+
+    fn domain_tokens(&self, domain: &DetailedDomain) -> Result<Vec<DomainToken>, anyhow::Error> {
+        // every domain of UNS registry, including subdomains, is ERC-721 token
+        // with uint256 of namehash as id
+        let Some(contract) = self.tokens.native else {
+            return Ok(vec![]);
+        };
+        let id = token_id(&domain.id)?;
+        Ok(vec![self.tokens.token(
+            contract,
+            id,
+            DomainTokenType::Native,
+        )])
+    }
+
+    fn coin_addresses_from_text_records(
+        &self,
+        text_records: &BTreeMap<String, String>,
+    ) -> BTreeMap<String, String> {
+        unstoppable_domains_addresses(text_records)
+    }
+
+    fn sql_quirks(&self) -> SqlQuirks {
+        // UNS domains are owned forever and there is no wrapper of registry
+        SqlQuirks {
+            expiring_domains: false,
+            wrapped_owners: false,
+            ..Default::default()
+        }
+    }
+}
+
+/// Addresses of Unstoppable Domains records like `crypto.BTC.address`, keyed by coin type.
+/// Records of unknown tickers and versioned records of tokens are skipped
+fn unstoppable_domains_addresses(
+    text_records: &BTreeMap<String, String>,
+) -> BTreeMap<String, String> {
+    text_records
+        .iter()
+        .filter(|(_, address)| !address.is_empty())
+        .filter_map(|(key, address)| {
+            let ticker = key.strip_prefix("crypto.")?.strip_suffix(".address")?;
+            if ticker.contains('.') {
+                return None;
+            }
+            let coin = Coin::find_by_name(ticker)?;
+            Some((coin.coin_type, address.clone()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ethers::types::Address;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    #[test]
+    fn unstoppable_domains_addresses_works() {
+        let text_records = BTreeMap::from_iter(
+            [
+                (
+                    "crypto.ETH.address",
+                    "0x8aaD44321A86b170879d7A244c1e8d360c99DdA8",
+                ),
+                (
+                    "crypto.BTC.address",
+                    "bc1q359khn0phg58xgezyqsuuaha28zkwx047c0c3y",
+                ),
+                (
+                    "crypto.USDT.version.ERC20.address",
+                    "0x8aaD44321A86b170879d7A244c1e8d360c99DdA8",
+                ),
+                ("crypto.UNKNOWNCOIN.address", "0x01"),
+                ("crypto.SOL.address", ""),
+                ("social.twitter.username", "brad"),
+            ]
+            .map(|(key, value)| (key.to_string(), value.to_string())),
+        );
+        assert_eq!(
+            unstoppable_domains_addresses(&text_records),
+            BTreeMap::from_iter(
+                [
+                    ("0", "bc1q359khn0phg58xgezyqsuuaha28zkwx047c0c3y"),
+                    ("60", "0x8aaD44321A86b170879d7A244c1e8d360c99DdA8"),
+                ]
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+            )
+        );
+    }
+
+    #[test]
+    fn unstoppable_domains_tokens_use_namehash() {
+        let uns_contract = "0x049aba7510f45ba5b64ea9e658e342f904db358d";
+        let protocol = UnstoppableDomainsProtocol::new(&SubgraphSettings {
+            native_token_contract: Address::from_str(uns_contract).ok(),
+            ..Default::default()
+        });
+        // subdomains are tokens of registry as well
+        let domain = DetailedDomain {
+            id: "0x0200".to_string(),
+            name: Some("pay.brad.crypto".to_string()),
+            labelhash: Some(vec![1, 0]),
+            owner: "0x1111111111111111111111111111111111111111".to_string(),
+            ..Default::default()
+        };
+        let tokens = protocol
+            .domain_tokens(&domain)
+            .expect("failed to extract tokens from domain");
+
+        assert_eq!(
+            tokens,
+            vec![DomainToken {
+                id: "512".to_string(),
+                contract: Address::from_str(uns_contract).expect("invalid uns_contract provided"),
+                _type: DomainTokenType::Native,
+                metadata_url: None,
+            }]
+        );
+    }
+}
@@ -1,36 +1,54 @@
 use super::{
+    batch_search_cache::BatchSearchCache,
     blockscout::{self, BlockscoutClient},
-    domain_name::DomainName,
-    domain_tokens::extract_tokens_from_domain,
-    pagination::{PaginatedList, Paginator},
-    patch::{patch_detailed_domain, patch_domain},
+    domain_name::{DomainName, DomainNameError, ReverseRegistrar, TldRules},
+    label_decoder::LabelDecoderClient,
+    lens::{self, LensClient},
+    offchain::{self, OffchainProvider},
+    pagination::{DomainPaginationInput, PaginatedList, PaginatedResult, Paginator},
+    patch::{
+        patch_detailed_domain, patch_domain, patch_unknown_label_of_detailed_domain,
+        patch_unknown_labels,
+    },
+    protocol::{NamingProtocol, ProtocolKind},
+    resolver_rpc::ResolverRpcClient,
+    reverse_records_cache::ReverseRecordsCache,
     schema_selector::subgraph_deployments,
-    sql, BatchResolveAddressNamesInput, GetDomainHistoryInput, GetDomainInput, GetDomainOutput,
-    LookupAddressInput, LookupDomainInput,
+    sql, AddressDomainsFilter, AddressSummary, BatchResolveAddressNamesInput,
+    BatchResolveAddressNamesOutput, BatchResolveDomainNamesInput, CoinAddress, DomainRecords,
+    DomainStateFilter, GetDomainByHashInput, GetDomainHistoryInput, GetDomainInput,
+    GetDomainOutput, GetDomainRecordsInput, GetSubdomainsInput, GetTextRecordsInput,
+    LookupAddressInput, LookupDomainInput, LookupMulticoinAddressInput, NetworkStats,
+    QuickSearchInput,
 };
 use crate::{
+    coin_type::Coin,
+    content_hash::ContentHash,
     entity::subgraph::{
-        domain::{DetailedDomain, Domain},
-        domain_event::{DomainEvent, DomainEventTransaction},
+        domain::{DetailedDomain, Domain, DomainRecordsRow, TextRecord},
+        domain_event::{
+            AddrChangedEvent, DomainEvent, DomainEventTransaction, ResolvedAddressChange,
+        },
     },
-    hash_name::domain_id,
-    subgraphs_reader::{resolve_addresses::resolve_addresses, sql::CachedView},
+    subgraphs_reader::{resolve_addresses::resolve_addresses_by_priority, sql::CachedView},
 };
 use anyhow::Context;
+use cached::proc_macro::cached;
 use ethers::types::{Address, Bytes, TxHash, H160};
 use serde::Deserialize;
 use sqlx::postgres::PgPool;
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
     default::Default,
+    future::Future,
     str::FromStr,
-    sync::Arc,
+    sync::{Arc, RwLock},
 };
 use thiserror::Error;
 use tracing::instrument;
 
 lazy_static::lazy_static! {
-    static ref UNRESOLVABLE_ADDRESSES: Vec<H160> = {
+    pub(super) static ref UNRESOLVABLE_ADDRESSES: Vec<H160> = {
         vec![
             "0x0000000000000000000000000000000000000000",
         ]
@@ -41,29 +59,207 @@ lazy_static::lazy_static! {
 }
 
 pub struct SubgraphReader {
-    pool: Arc<PgPool>,
-    networks: HashMap<i64, Network>,
+    /// Primary pool, used for mutating queries
+    pub(super) pool: Arc<PgPool>,
+    /// Pool for read-only queries, same as `pool` unless read replica is set
+    pub(super) read_pool: Arc<PgPool>,
+    pub(super) networks: RwLock<HashMap<i64, Network>>,
+    /// Optional short-living cache of resolved names of addresses
+    pub(super) batch_search_cache: Option<Arc<BatchSearchCache>>,
+    /// Optional cache of verified reverse records kept in state database
+    pub(super) reverse_records_cache: Option<Arc<ReverseRecordsCache>>,
+    /// Options passed to every sql query of reader
+    pub(super) query_options: sql::QueryOptions,
+    /// Optional timeouts of read-only queries, see [`Self::interactive_options`]
+    pub(super) statement_timeouts: Option<sql::StatementTimeouts>,
 }
 
 #[derive(Debug, Clone)]
 pub struct Network {
-    blockscout_client: Arc<BlockscoutClient>,
-    subgraphs: Vec<Subgraph>,
-    default_subgraph: Subgraph,
+    pub(super) blockscout_client: Arc<BlockscoutClient>,
+    /// Optional client used to decode unknown labels of domains, like `[labelhash].eth`
+    pub(super) label_decoder: Option<Arc<LabelDecoderClient>>,
+    /// Providers of subdomains issued offchain, which are resolved by their gateways
+    pub(super) offchain_providers: Vec<Arc<OffchainProvider>>,
+    /// Optional client used to resolve names missing in subgraph by wildcard resolvers
+    pub(super) resolver_rpc: Option<Arc<ResolverRpcClient>>,
+    /// Optional client of Lens API, used to resolve Lens handles like `lens/stani`
+    pub(super) lens: Option<Arc<LensClient>>,
+    pub(super) subgraphs: Vec<Subgraph>,
+    pub(super) default_subgraph: Subgraph,
+    /// Subgraphs used to find names of addresses, ordered by protocol priority
+    pub(super) primary_name_subgraphs: Vec<Subgraph>,
+    /// Subgraphs whose domains are merged in lists, like lookup by name or address
+    pub(super) list_subgraphs: Vec<Subgraph>,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+impl Network {
+    fn subgraph_for_name(&self, name: &str) -> &Subgraph {
+        subgraph_for_name(&self.subgraphs, &self.default_subgraph, name)
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct Subgraph {
     pub schema_name: String,
     pub settings: SubgraphSettings,
+    /// Naming protocol of subgraph, built from `settings.protocol`
+    protocol: Arc<dyn NamingProtocol>,
 }
 
-#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Default)]
+impl PartialEq for Subgraph {
+    fn eq(&self, other: &Self) -> bool {
+        self.schema_name == other.schema_name && self.settings == other.settings
+    }
+}
+
+impl Eq for Subgraph {}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
 pub struct SubgraphSettings {
     pub use_cache: bool,
     pub address_resolve_technique: AddressResolveTechnique,
     pub empty_label_hash: Option<Bytes>,
     pub native_token_contract: Option<Address>,
+    pub wrapped_token_contract: Option<Address>,
+    pub token_metadata_url: Option<String>,
+    #[serde(default)]
+    pub tld_rules: TldRules,
+    /// Days after `expiry_date` during which owner still can renew domain
+    #[serde(default = "default_grace_period_days")]
+    pub grace_period_days: u32,
+    /// Registrar of reverse records, used by `reverse_registry` address resolve technique
+    #[serde(default)]
+    pub reverse_registrar: ReverseRegistrar,
+    /// Recompute namehash of returned domains and flag domains with name not matching id
+    #[serde(default)]
+    pub verify_namehash: bool,
+    /// Naming protocol implemented by subgraph, `ens` by default
+    #[serde(default)]
+    pub protocol: ProtocolKind,
+}
+
+/// Grace period is specific to registrar, so it is disabled unless configured for subgraph
+pub const DEFAULT_GRACE_PERIOD_DAYS: u32 = 0;
+
+fn default_grace_period_days() -> u32 {
+    DEFAULT_GRACE_PERIOD_DAYS
+}
+
+impl Default for SubgraphSettings {
+    fn default() -> Self {
+        Self {
+            use_cache: Default::default(),
+            address_resolve_technique: Default::default(),
+            empty_label_hash: Default::default(),
+            native_token_contract: Default::default(),
+            wrapped_token_contract: Default::default(),
+            token_metadata_url: Default::default(),
+            tld_rules: Default::default(),
+            grace_period_days: DEFAULT_GRACE_PERIOD_DAYS,
+            reverse_registrar: Default::default(),
+            verify_namehash: Default::default(),
+            protocol: Default::default(),
+        }
+    }
+}
+
+impl Subgraph {
+    pub fn new(schema_name: String, settings: SubgraphSettings) -> Self {
+        Self {
+            protocol: settings.protocol.protocol(&settings),
+            schema_name,
+            settings,
+        }
+    }
+
+    /// Replaces protocol built from `settings.protocol`,
+    /// used by protocols not listed in [`ProtocolKind`]
+    pub fn with_protocol(mut self, protocol: Arc<dyn NamingProtocol>) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    pub fn protocol(&self) -> &dyn NamingProtocol {
+        self.protocol.as_ref()
+    }
+
+    /// Schema of subgraph deployment in database, like `sgd1`
+    pub fn schema_name(&self) -> &str {
+        &self.schema_name
+    }
+
+    /// Top-level domains served by subgraph
+    pub fn tld_rules(&self) -> &TldRules {
+        &self.settings.tld_rules
+    }
+
+    pub fn namehash(&self, name: &str) -> String {
+        self.protocol.namehash(name)
+    }
+
+    /// Quirks of protocol with grace period of subgraph, passed to sql queries
+    pub fn sql_quirks(&self) -> sql::SqlQuirks {
+        sql::SqlQuirks {
+            grace_period_days: self.settings.grace_period_days,
+            ..self.protocol.sql_quirks()
+        }
+    }
+
+    /// How primary names of addresses are found in subgraph
+    pub fn address_resolve_technique(&self) -> &AddressResolveTechnique {
+        &self.settings.address_resolve_technique
+    }
+
+    /// Registrar of reverse records, used by `reverse_registry` address resolve technique
+    pub fn reverse_registrar(&self) -> &ReverseRegistrar {
+        &self.settings.reverse_registrar
+    }
+
+    /// Parses name provided by user according to TLD rules of subgraph
+    pub fn domain_name(&self, name: &str) -> Result<DomainName, DomainNameError> {
+        DomainName::with_tld_rules_and_namehash(name, self.tld_rules(), |name| self.namehash(name))
+    }
+
+    /// Parses name without TLD rules, like name of reverse record.
+    /// Names imported from DNS are parsed by DNS rules
+    pub fn parse_name(&self, name: &str) -> Result<DomainName, DomainNameError> {
+        if self.tld_rules().is_dns_name(name) {
+            DomainName::new_dns(name, |name| self.namehash(name))
+        } else {
+            DomainName::new_with_namehash(name, |name| self.namehash(name))
+        }
+    }
+
+    /// Namehashes of TLDs from `tld_rules`. Empty if TLDs are not configured
+    pub fn tld_ids(&self) -> Vec<String> {
+        let tld_rules = self.tld_rules();
+        tld_rules
+            .default_tld
+            .iter()
+            .chain(&tld_rules.allowed_tlds)
+            .map(|tld| self.namehash(tld))
+            .collect()
+    }
+
+    /// True if verification is enabled and namehash of `name` doesn't match `id`
+    pub fn namehash_mismatch(&self, id: &str, name: Option<&str>) -> bool {
+        self.settings.verify_namehash
+            && name.is_some_and(|name| !self.namehash(name).eq_ignore_ascii_case(id))
+    }
+
+    pub fn verify_namehashes(&self, domains: Vec<Domain>) -> Vec<Domain> {
+        if !self.settings.verify_namehash {
+            return domains;
+        }
+        domains
+            .into_iter()
+            .map(|domain| Domain {
+                namehash_mismatch: self.namehash_mismatch(&domain.id, domain.name.as_deref()),
+                ..domain
+            })
+            .collect()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Default)]
@@ -77,14 +273,30 @@ pub enum AddressResolveTechnique {
 #[derive(Debug, Clone)]
 pub struct NetworkInfo {
     pub blockscout_client: BlockscoutClient,
+    pub label_decoder: Option<LabelDecoderClient>,
+    pub offchain_providers: Vec<OffchainProvider>,
+    pub resolver_rpc: Option<ResolverRpcClient>,
+    pub lens: Option<LensClient>,
     pub subgraph_configs: HashMap<String, SubgraphSettings>,
+    /// Names of subgraphs whose reverse records win in address resolving, highest priority first.
+    /// Only default subgraph is used if empty
+    pub protocol_priority: Vec<String>,
+    /// Names of subgraphs whose domains are merged in lists, like lookup by name or address.
+    /// Only default subgraph is used if empty
+    pub merged_protocols: Vec<String>,
 }
 
 impl NetworkInfo {
     pub fn from_client(blockscout_client: BlockscoutClient) -> Self {
         Self {
             blockscout_client,
+            label_decoder: Default::default(),
+            offchain_providers: Default::default(),
+            resolver_rpc: Default::default(),
+            lens: Default::default(),
             subgraph_configs: Default::default(),
+            protocol_priority: Default::default(),
+            merged_protocols: Default::default(),
         }
     }
 }
@@ -92,70 +304,115 @@ impl NetworkInfo {
 impl SubgraphReader {
     pub async fn initialize(
         pool: Arc<PgPool>,
-        mut network_infos: HashMap<i64, NetworkInfo>,
+        network_infos: HashMap<i64, NetworkInfo>,
     ) -> Result<Self, anyhow::Error> {
-        let deployments = subgraph_deployments(&pool).await?;
-        tracing::info!(deployments =? deployments, "found subgraph deployments");
-        let networks = deployments
-            .into_iter()
-            .filter(|(_, d)| !d.is_empty())
-            .filter_map(|(id, deployments)| {
-                let maybe_network = network_infos.remove(&id).map(|info| {
-                    let subgraphs: Vec<Subgraph> = deployments
-                        .into_iter()
-                        .map(|d| {
-                            let settings = match info.subgraph_configs.get(&d.subgraph_name) {
-                                Some(c) => c.clone(),
-                                None => {
-                                    tracing::warn!(
-                                        "no settings found for subgraph '{}', use default",
-                                        d.subgraph_name
-                                    );
-                                    Default::default()
-                                }
-                            };
-                            Subgraph {
-                                schema_name: d.schema_name,
-                                settings,
-                            }
-                        })
-                        .collect();
-                    let default_subgraph = subgraphs
-                        .first()
-                        .expect("at least one deployment persist")
-                        .to_owned();
-                    (
-                        id,
-                        Network {
-                            blockscout_client: Arc::new(info.blockscout_client),
-                            subgraphs,
-                            default_subgraph,
-                        },
-                    )
-                });
-                if maybe_network.is_none() {
-                    tracing::warn!("no blockscout url for chain {id}, skip this network")
-                }
-                maybe_network
-            })
-            .collect::<HashMap<_, _>>();
-        for (id, info) in network_infos.iter() {
-            tracing::warn!("no chain found for blockscout url with chain_id {id} and url {}, skip this network", info.blockscout_client.url())
-        }
+        let networks = build_networks(pool.as_ref(), network_infos).await?;
         let this = Self::new(pool, networks);
         this.init_cache().await.context("init cache tables")?;
-        tracing::info!(networks =? this.networks.keys().collect::<Vec<_>>(), "initialized subgraph reader");
+        tracing::info!(networks =? this.network_ids(), "initialized subgraph reader");
         Ok(this)
     }
 
     pub fn new(pool: Arc<PgPool>, networks: HashMap<i64, Network>) -> Self {
-        Self { pool, networks }
+        Self {
+            read_pool: pool.clone(),
+            pool,
+            networks: RwLock::new(networks),
+            batch_search_cache: None,
+            reverse_records_cache: None,
+            query_options: Default::default(),
+            statement_timeouts: None,
+        }
+    }
+
+    /// Routes read-only queries to `read_pool`, while mutating
+    /// queries keep using primary pool
+    pub fn with_read_replica(mut self, read_pool: Arc<PgPool>) -> Self {
+        self.read_pool = read_pool;
+        self
+    }
+
+    /// Read-only queries are cancelled by database after timeout of their budget.
+    /// Queries of background refreshes are not limited
+    pub fn with_statement_timeouts(mut self, timeouts: sql::StatementTimeouts) -> Self {
+        self.statement_timeouts = Some(timeouts);
+        self
+    }
+
+    /// Retries queries failed with transient errors according to `policy`
+    /// instead of default one
+    pub fn with_retry_policy(mut self, policy: sql::RetryPolicy) -> Self {
+        self.query_options.retry = policy;
+        self
+    }
+
+    /// Splits large batch lookups into concurrent chunks according to `policy`
+    /// instead of default one
+    pub fn with_chunk_policy(mut self, policy: sql::ChunkPolicy) -> Self {
+        self.query_options.chunks = policy;
+        self
+    }
+
+    /// Options of read-only queries of single domain or address lookups
+    pub(super) fn interactive_options(&self) -> sql::QueryOptions {
+        sql::QueryOptions {
+            statement_timeout: self.statement_timeouts.map(|timeouts| timeouts.interactive),
+            ..self.query_options
+        }
+    }
+
+    /// Options of read-only queries of batch resolving, exports and stats
+    pub(super) fn batch_options(&self) -> sql::QueryOptions {
+        sql::QueryOptions {
+            statement_timeout: self.statement_timeouts.map(|timeouts| timeouts.batch),
+            ..self.query_options
+        }
+    }
+
+    /// Caches names of addresses found by batch search for `ttl_seconds`.
+    /// Only used by subgraphs resolving addresses with `all_domains` technique without cache views
+    pub fn with_batch_search_cache(mut self, ttl_seconds: u64, size: usize) -> Self {
+        self.batch_search_cache = Some(Arc::new(BatchSearchCache::new(ttl_seconds, size)));
+        self
+    }
+
+    /// Caches verified reverse records in `state_pool`, which are rebuilt by
+    /// [`Self::refresh_reverse_records`]. Only used by subgraphs resolving addresses
+    /// with `reverse_registry` technique without cache views
+    pub fn with_reverse_records_cache(mut self, state_pool: Arc<PgPool>) -> Self {
+        self.reverse_records_cache = Some(Arc::new(ReverseRecordsCache::new(state_pool)));
+        self
+    }
+
+    /// Replaces served networks with ones built from `network_infos`.
+    /// Cache views of new subgraphs are created before the swap,
+    /// so in-flight queries keep using networks they started with
+    pub async fn reload_networks(
+        &self,
+        network_infos: HashMap<i64, NetworkInfo>,
+    ) -> Result<(), anyhow::Error> {
+        let networks = build_networks(self.pool.as_ref(), network_infos).await?;
+        let subgraphs: Vec<Subgraph> = networks
+            .values()
+            .flat_map(|n| n.subgraphs.clone())
+            .collect();
+        init_cache(self.pool.as_ref(), &subgraphs)
+            .await
+            .context("init cache tables")?;
+        let old_ids = self.network_ids();
+        *self.networks.write().expect("networks lock is poisoned") = networks;
+        tracing::info!(
+            old_networks =? old_ids,
+            networks =? self.network_ids(),
+            "reloaded subgraph reader networks"
+        );
+        Ok(())
     }
 
     pub async fn refresh_cache(&self) -> Result<(), anyhow::Error> {
-        for subgraph in self.iter_subgraphs().filter(|s| s.settings.use_cache) {
+        for subgraph in self.subgraphs().iter().filter(|s| s.settings.use_cache) {
             let schema = &subgraph.schema_name;
-            let address_resolve_technique = &subgraph.settings.address_resolve_technique;
+            let address_resolve_technique = subgraph.address_resolve_technique();
             tracing::info!(
                 address_resolve_technique =? address_resolve_technique,
                 "refreshing cache table for schema {schema}"
@@ -181,30 +438,222 @@ impl SubgraphReader {
     }
 
     pub async fn init_cache(&self) -> Result<(), anyhow::Error> {
-        for subgraph in self.iter_subgraphs().filter(|s| s.settings.use_cache) {
+        init_cache(self.pool.as_ref(), &self.subgraphs()).await
+    }
+
+    /// Rebuilds cached reverse records of subgraphs resolving addresses
+    /// by reverse registry without cache views, if reverse records cache is set
+    pub async fn refresh_reverse_records(&self) {
+        if let Some(cache) = &self.reverse_records_cache {
+            refresh_reverse_records(cache, self.read_pool.as_ref(), &self.subgraphs()).await
+        }
+    }
+
+    /// Builds missing or invalid search indexes of served subgraphs. Indexes are built
+    /// concurrently, which takes a while for large subgraphs, so it's done in background
+    pub async fn ensure_search_indexes(&self) {
+        for subgraph in self.subgraphs() {
             let schema = &subgraph.schema_name;
-            match subgraph.settings.address_resolve_technique {
-                AddressResolveTechnique::ReverseRegistry => {
-                    sql::AddrReverseNamesView::create_view(self.pool.as_ref(), schema)
-                        .await
-                        .context(format!(
-                            "failed to create AddrReverseNamesView for schema {schema}"
-                        ))?;
-                }
-                AddressResolveTechnique::AllDomains => {
-                    sql::AddressNamesView::create_view(self.pool.as_ref(), schema)
-                        .await
-                        .context(format!(
-                            "failed to create AddressNamesView for schema {schema}"
-                        ))?;
-                }
+            // subgraph without domain table shouldn't block building indexes of others
+            match sql::ensure_domain_name_search_index(self.pool.as_ref(), schema).await {
+                Ok(sql::IndexState::Valid | sql::IndexState::Building) => {}
+                Ok(state) => tracing::info!(
+                    previous_state =? state,
+                    "built domain name search index for schema {schema}"
+                ),
+                Err(err) => tracing::error!(
+                    err =? err,
+                    "failed to build domain name search index for schema {schema}"
+                ),
+            }
+        }
+    }
+
+    pub fn subgraphs(&self) -> Vec<Subgraph> {
+        self.networks
+            .read()
+            .expect("networks lock is poisoned")
+            .values()
+            .flat_map(|n| n.subgraphs.clone())
+            .collect()
+    }
+
+    pub fn network_ids(&self) -> Vec<i64> {
+        let mut ids: Vec<i64> = self
+            .networks
+            .read()
+            .expect("networks lock is poisoned")
+            .keys()
+            .copied()
+            .collect();
+        ids.sort();
+        ids
+    }
+
+    pub(super) fn network(&self, network_id: i64) -> Result<Network, SubgraphReadError> {
+        self.networks
+            .read()
+            .expect("networks lock is poisoned")
+            .get(&network_id)
+            .cloned()
+            .ok_or(SubgraphReadError::NetworkNotFound(network_id))
+    }
+}
+
+async fn build_networks(
+    pool: &PgPool,
+    mut network_infos: HashMap<i64, NetworkInfo>,
+) -> Result<HashMap<i64, Network>, anyhow::Error> {
+    let deployments = subgraph_deployments(pool).await?;
+    tracing::info!(deployments =? deployments, "found subgraph deployments");
+    let networks = deployments
+        .into_iter()
+        .filter(|(_, d)| !d.is_empty())
+        .filter_map(|(id, deployments)| {
+            let maybe_network = network_infos.remove(&id).map(|info| {
+                let subgraph_names: Vec<String> = deployments
+                    .iter()
+                    .map(|d| d.subgraph_name.clone())
+                    .collect();
+                let subgraphs: Vec<Subgraph> = deployments
+                    .into_iter()
+                    .map(|d| {
+                        let settings = match info.subgraph_configs.get(&d.subgraph_name) {
+                            Some(c) => c.clone(),
+                            None => {
+                                tracing::warn!(
+                                    "no settings found for subgraph '{}', use default",
+                                    d.subgraph_name
+                                );
+                                Default::default()
+                            }
+                        };
+                        Subgraph::new(d.schema_name, settings)
+                    })
+                    .collect();
+                let default_subgraph = subgraphs
+                    .first()
+                    .expect("at least one deployment persist")
+                    .to_owned();
+                let primary_name_subgraphs = select_subgraphs(
+                    &info.protocol_priority,
+                    &subgraph_names,
+                    &subgraphs,
+                    &default_subgraph,
+                );
+                let list_subgraphs = select_subgraphs(
+                    &info.merged_protocols,
+                    &subgraph_names,
+                    &subgraphs,
+                    &default_subgraph,
+                );
+                (
+                    id,
+                    Network {
+                        blockscout_client: Arc::new(info.blockscout_client),
+                        label_decoder: info.label_decoder.map(Arc::new),
+                        offchain_providers: info
+                            .offchain_providers
+                            .into_iter()
+                            .map(Arc::new)
+                            .collect(),
+                        resolver_rpc: info.resolver_rpc.map(Arc::new),
+                        lens: info.lens.map(Arc::new),
+                        subgraphs,
+                        default_subgraph,
+                        primary_name_subgraphs,
+                        list_subgraphs,
+                    },
+                )
+            });
+            if maybe_network.is_none() {
+                tracing::warn!("no blockscout url for chain {id}, skip this network")
+            }
+            maybe_network
+        })
+        .collect::<HashMap<_, _>>();
+    for (id, info) in network_infos.iter() {
+        tracing::warn!(
+            "no chain found for blockscout url with chain_id {id} and url {}, skip this network",
+            info.blockscout_client.url()
+        )
+    }
+    Ok(networks)
+}
+
+/// Subgraphs with `names` in the same order, or only default subgraph if none of them found
+fn select_subgraphs(
+    names: &[String],
+    subgraph_names: &[String],
+    subgraphs: &[Subgraph],
+    default_subgraph: &Subgraph,
+) -> Vec<Subgraph> {
+    let selected: Vec<Subgraph> = names
+        .iter()
+        .filter_map(|name| {
+            let maybe_subgraph = subgraph_names
+                .iter()
+                .position(|n| n == name)
+                .map(|i| subgraphs[i].clone());
+            if maybe_subgraph.is_none() {
+                tracing::warn!("no deployment found for subgraph '{name}', skip it");
+            }
+            maybe_subgraph
+        })
+        .collect();
+    if selected.is_empty() {
+        vec![default_subgraph.clone()]
+    } else {
+        selected
+    }
+}
+
+async fn init_cache(pool: &PgPool, subgraphs: &[Subgraph]) -> Result<(), anyhow::Error> {
+    for subgraph in subgraphs.iter().filter(|s| s.settings.use_cache) {
+        let schema = &subgraph.schema_name;
+        match subgraph.address_resolve_technique() {
+            AddressResolveTechnique::ReverseRegistry => {
+                sql::AddrReverseNamesView::create_view(pool, subgraph)
+                    .await
+                    .context(format!(
+                        "failed to create AddrReverseNamesView for schema {schema}"
+                    ))?;
+            }
+            AddressResolveTechnique::AllDomains => {
+                sql::AddressNamesView::create_view(pool, subgraph)
+                    .await
+                    .context(format!(
+                        "failed to create AddressNamesView for schema {schema}"
+                    ))?;
             }
         }
-        Ok(())
     }
+    Ok(())
+}
 
-    pub fn iter_subgraphs(&self) -> impl Iterator<Item = &Subgraph> {
-        self.networks.values().flat_map(|n| &n.subgraphs)
+async fn refresh_reverse_records(
+    cache: &ReverseRecordsCache,
+    pool: &PgPool,
+    subgraphs: &[Subgraph],
+) {
+    let subgraphs = subgraphs.iter().filter(|s| {
+        !s.settings.use_cache
+            && *s.address_resolve_technique() == AddressResolveTechnique::ReverseRegistry
+    });
+    for subgraph in subgraphs {
+        let schema = &subgraph.schema_name;
+        // subgraph without reverse records shouldn't block refreshing of others
+        let reverse_registrar = subgraph.reverse_registrar();
+        match cache.refresh(pool, schema, reverse_registrar).await {
+            Ok(cached) => tracing::info!(
+                cached,
+                "refreshed cached reverse records for schema {schema}"
+            ),
+            Err(err) => tracing::error!(
+                err =? err,
+                "failed to refresh cached reverse records for schema {schema}"
+            ),
+        }
     }
 }
 
@@ -212,45 +661,246 @@ impl SubgraphReader {
 pub enum SubgraphReadError {
     #[error("Network with id {0} not found")]
     NetworkNotFound(i64),
+    #[error("invalid name: {0}")]
+    InvalidName(#[from] DomainNameError),
     #[error("Db err")]
-    DbErr(#[from] sqlx::Error),
+    DbErr(#[source] sqlx::Error),
+    /// Db error which could disappear on retry, like connection loss
+    #[error("transient db err")]
+    Transient(#[source] sqlx::Error),
+    /// Query was cancelled by database after statement timeout
+    #[error("db query timeout")]
+    Timeout(#[source] sqlx::Error),
     #[error("internal error: {0}")]
     Internal(String),
 }
 
+impl From<sqlx::Error> for SubgraphReadError {
+    fn from(err: sqlx::Error) -> Self {
+        if sql::is_timeout(&err) {
+            Self::Timeout(err)
+        } else if sql::is_transient(&err) {
+            Self::Transient(err)
+        } else {
+            Self::DbErr(err)
+        }
+    }
+}
+
 impl SubgraphReader {
     pub async fn get_domain(
         &self,
         input: GetDomainInput,
     ) -> Result<Option<GetDomainOutput>, SubgraphReadError> {
-        let network = self
-            .networks
-            .get(&input.network_id)
-            .ok_or_else(|| SubgraphReadError::NetworkNotFound(input.network_id))?;
-        let subgraph = &network.default_subgraph;
-        let empty_label_hash = subgraph.settings.empty_label_hash.clone();
-        let domain_name = DomainName::new(&input.name, empty_label_hash)
-            .map_err(|e| SubgraphReadError::Internal(e.to_string()))?;
-        let maybe_domain: Option<DetailedDomain> = sql::get_domain(
-            self.pool.as_ref(),
-            &domain_name,
+        let network = self.network(input.network_id)?;
+        if let Some((lens, handle)) = network.lens.as_ref().zip(lens::parse_handle(&input.name)) {
+            return lens::get_lens_domain(lens, handle).await;
+        }
+        let subgraph = network.subgraph_for_name(&input.name);
+        let domain_name = subgraph.parse_name(&input.name)?;
+        if let Some(provider) = network
+            .offchain_providers
+            .iter()
+            .find(|p| p.is_provider_of(&domain_name.name))
+        {
+            return offchain::get_offchain_domain(provider, domain_name).await;
+        }
+        let maybe_domain = self
+            .get_domain_from_subgraph(&network, subgraph, &input)
+            .await?;
+        match (maybe_domain, &network.resolver_rpc) {
+            (None, Some(resolver_rpc)) => {
+                // domain filtered out as expired is known to subgraph, so it's not resolved offchain
+                let exists = input.only_active
+                    && sql::domain_exists(
+                        self.read_pool.as_ref(),
+                        &self.interactive_options(),
+                        &subgraph.schema_name,
+                        &domain_name.id,
+                    )
+                    .await?;
+                if exists {
+                    return Ok(None);
+                }
+                self.get_wildcard_domain(resolver_rpc, subgraph, domain_name)
+                    .await
+            }
+            (maybe_domain, _) => Ok(maybe_domain),
+        }
+    }
+
+    /// Domain with given namehash or, if there is no such domain, second-level domain
+    /// with given labelhash. Name of domain is unknown, so default subgraph is searched first,
+    /// then other subgraphs of network serving their own TLDs. Offchain domains are not found,
+    /// since their hashes are unknown
+    pub async fn get_domain_by_hash(
+        &self,
+        input: GetDomainByHashInput,
+    ) -> Result<Option<GetDomainOutput>, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
+        let hash = format!("{:#x}", input.hash);
+        let other_subgraphs = network
+            .subgraphs
+            .iter()
+            .filter(|s| s.schema_name != network.default_subgraph.schema_name)
+            .filter(|s| !s.tld_rules().allowed_tlds.is_empty());
+        for subgraph in std::iter::once(&network.default_subgraph).chain(other_subgraphs) {
+            let maybe_domain = self
+                .get_domain_by_hash_from_subgraph(&network, subgraph, &hash, input.only_active)
+                .await?;
+            if maybe_domain.is_some() {
+                return Ok(maybe_domain);
+            }
+        }
+        Ok(None)
+    }
+
+    async fn get_domain_by_hash_from_subgraph(
+        &self,
+        network: &Network,
+        subgraph: &Subgraph,
+        hash: &str,
+        only_active: bool,
+    ) -> Result<Option<GetDomainOutput>, SubgraphReadError> {
+        let by_namehash = self
+            .get_domain_by_id_from_subgraph(network, subgraph, hash, None, only_active, false)
+            .await?;
+        if by_namehash.is_some() {
+            return Ok(by_namehash);
+        }
+        let maybe_id = sql::find_domain_id_by_labelhash(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
             &subgraph.schema_name,
-            &input,
+            hash,
+            &subgraph.tld_ids(),
+        )
+        .await?;
+        match maybe_id {
+            Some(id) => {
+                self.get_domain_by_id_from_subgraph(
+                    network,
+                    subgraph,
+                    &id,
+                    None,
+                    only_active,
+                    false,
+                )
+                .await
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn get_domain_from_subgraph(
+        &self,
+        network: &Network,
+        subgraph: &Subgraph,
+        input: &GetDomainInput,
+    ) -> Result<Option<GetDomainOutput>, SubgraphReadError> {
+        let domain_name = subgraph.domain_name(&input.name)?;
+        let maybe_output = self
+            .get_domain_by_id_from_subgraph(
+                network,
+                subgraph,
+                &domain_name.id,
+                Some(&domain_name),
+                input.only_active,
+                input.include_resolved_address_history,
+            )
+            .await?;
+        Ok(maybe_output.map(|mut output| {
+            if !input.coin_types.is_empty() {
+                // addresses are already keyed by coin names
+                let names: HashSet<String> = input
+                    .coin_types
+                    .iter()
+                    .map(|coin_type| Coin::find_or_unknown(coin_type).name)
+                    .collect();
+                output
+                    .domain
+                    .other_addresses
+                    .0
+                    .retain(|name, _| names.contains(name));
+            }
+            output
+        }))
+    }
+
+    /// Detailed domain by id. If `domain_name` is known, stored name of domain is fixed to match it.
+    /// History of resolved address requires Blockscout calls, so it is read only on request
+    async fn get_domain_by_id_from_subgraph(
+        &self,
+        network: &Network,
+        subgraph: &Subgraph,
+        id: &str,
+        domain_name: Option<&DomainName>,
+        only_active: bool,
+        include_resolved_address_history: bool,
+    ) -> Result<Option<GetDomainOutput>, SubgraphReadError> {
+        let maybe_domain: Option<DetailedDomain> = sql::get_domain_by_id(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
+            id,
+            &subgraph.schema_name,
+            only_active,
+            subgraph.sql_quirks(),
         )
         .await?
-        .map(|domain| {
+        .map(|mut domain| {
+            let addresses = subgraph
+                .protocol()
+                .coin_addresses_from_text_records(&domain.text_records.0);
+            for (coin_type, address) in addresses {
+                domain.other_addresses.0.entry(coin_type).or_insert(address);
+            }
             patch_detailed_domain(
                 self.pool.clone(),
+                self.query_options,
                 &subgraph.schema_name,
                 domain,
-                &domain_name,
+                domain_name,
             )
         });
+        // name is unknown only if domain is requested by id
+        let maybe_domain = match maybe_domain {
+            Some(domain) if domain_name.is_none() => Some(
+                patch_unknown_label_of_detailed_domain(
+                    self.pool.clone(),
+                    self.query_options,
+                    &subgraph.schema_name,
+                    network.label_decoder.as_deref(),
+                    domain,
+                )
+                .await,
+            ),
+            maybe_domain => maybe_domain,
+        }
+        .map(|domain| DetailedDomain {
+            namehash_mismatch: subgraph.namehash_mismatch(&domain.id, domain.name.as_deref()),
+            ..domain
+        });
         if let Some(domain) = maybe_domain {
-            let tokens = extract_tokens_from_domain(&domain, &subgraph.settings).map_err(|e| {
+            let tokens = subgraph.protocol().domain_tokens(&domain).map_err(|e| {
                 SubgraphReadError::Internal(format!("failed to extract domain tokens: {e}"))
             })?;
-            Ok(Some(GetDomainOutput { tokens, domain }))
+            let resolved_address_history = if include_resolved_address_history {
+                let addr_changes = sql::find_resolved_address_changes(
+                    self.read_pool.as_ref(),
+                    &self.interactive_options(),
+                    &subgraph.schema_name,
+                    &domain.id,
+                )
+                .await?;
+                resolved_address_history(network.blockscout_client.clone(), addr_changes).await
+            } else {
+                vec![]
+            };
+            Ok(Some(GetDomainOutput {
+                tokens,
+                domain,
+                resolved_address_history,
+            }))
         } else {
             Ok(None)
         }
@@ -260,122 +910,501 @@ impl SubgraphReader {
         &self,
         input: GetDomainHistoryInput,
     ) -> Result<Vec<DomainEvent>, SubgraphReadError> {
-        let network = self
-            .networks
-            .get(&input.network_id)
-            .ok_or_else(|| SubgraphReadError::NetworkNotFound(input.network_id))?;
-        let subgraph = &network.default_subgraph;
-        let id = domain_id(&input.name, subgraph.settings.empty_label_hash.clone());
-        let domain_txns: Vec<DomainEventTransaction> =
-            sql::find_transaction_events(self.pool.as_ref(), &subgraph.schema_name, &id, &input)
-                .await?;
+        let network = self.network(input.network_id)?;
+        let subgraph = network.subgraph_for_name(&input.name);
+        let id = subgraph.domain_name(&input.name)?.id;
+        let domain_txns: Vec<DomainEventTransaction> = sql::find_transaction_events(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
+            &subgraph.schema_name,
+            &id,
+            &input,
+        )
+        .await?;
         let domain_events =
             events_from_transactions(network.blockscout_client.clone(), domain_txns).await?;
         Ok(domain_events)
     }
 
-    pub async fn lookup_domain_name(
+    /// Text records of current domain resolver, like `url` or `com.twitter`
+    pub async fn get_text_records(
         &self,
-        input: LookupDomainInput,
-    ) -> Result<PaginatedList<Domain>, SubgraphReadError> {
-        let network = self
-            .networks
-            .get(&input.network_id)
-            .ok_or_else(|| SubgraphReadError::NetworkNotFound(input.network_id))?;
-        let subgraph = &network.default_subgraph;
-        let empty_label_hash = subgraph.settings.empty_label_hash.clone();
-        let maybe_domain_name = input
-            .name
-            .as_ref()
-            .map(|name| {
-                DomainName::new(name, empty_label_hash)
-                    .map_err(|e| SubgraphReadError::Internal(e.to_string()))
-            })
-            .transpose()?;
-
-        let domain_names = maybe_domain_name.as_ref().map(|name| {
-            if name.is_child_of_tld() {
-                vec![name]
-            } else {
-                vec![]
-            }
-        });
-        let domains: Vec<Domain> = sql::find_domains(
-            self.pool.as_ref(),
+        input: GetTextRecordsInput,
+    ) -> Result<Vec<TextRecord>, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
+        let subgraph = network.subgraph_for_name(&input.name);
+        let id = subgraph.domain_name(&input.name)?.id;
+        let records = sql::find_text_records(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
             &subgraph.schema_name,
-            domain_names,
-            input.only_active,
-            Some(&input.pagination),
+            &id,
         )
-        .await?
-        .into_iter()
-        .map(|domain| {
-            if let Some(domain_name) = maybe_domain_name.as_ref() {
-                patch_domain(
-                    self.pool.clone(),
-                    &subgraph.schema_name,
-                    domain,
-                    domain_name,
-                )
-            } else {
-                domain
-            }
-        })
-        .collect();
-        let paginated = input
-            .pagination
-            .paginate_result(domains)
-            .map_err(|e| SubgraphReadError::Internal(format!("cannot paginate result: {e}")))?;
-        Ok(paginated)
+        .await?;
+        Ok(records)
     }
 
-    pub async fn lookup_address(
+    /// Every multicoin address, text record and contenthash of current domain resolver,
+    /// read by single query. None if domain doesn't exist
+    pub async fn get_domain_records(
         &self,
-        input: LookupAddressInput,
-    ) -> Result<PaginatedList<Domain>, SubgraphReadError> {
-        let network = self
-            .networks
-            .get(&input.network_id)
-            .ok_or_else(|| SubgraphReadError::NetworkNotFound(input.network_id))?;
-        if UNRESOLVABLE_ADDRESSES.contains(&input.address) {
-            return Ok(PaginatedList::empty());
-        }
-        let domains: Vec<Domain> = sql::find_resolved_addresses(
-            self.pool.as_ref(),
-            &network.default_subgraph.schema_name,
-            &input,
+        input: GetDomainRecordsInput,
+    ) -> Result<Option<DomainRecords>, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
+        let subgraph = network.subgraph_for_name(&input.name);
+        let domain_name = subgraph.domain_name(&input.name)?;
+        let maybe_row = sql::get_domain_records(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
+            &subgraph.schema_name,
+            &domain_name.id,
         )
         .await?;
-        let paginated = input
-            .pagination
-            .paginate_result(domains)
-            .map_err(|e| SubgraphReadError::Internal(format!("cannot paginate result: {e}")))?;
-        Ok(paginated)
+        Ok(maybe_row.map(|mut row| {
+            let addresses = subgraph
+                .protocol()
+                .coin_addresses_from_text_records(&row.text_records.0);
+            for (coin_type, address) in addresses {
+                row.addresses.0.entry(coin_type).or_insert(address);
+            }
+            domain_records_from_row(row, domain_name)
+        }))
     }
 
-    pub async fn get_address(
+    /// Counters of registered domains of default subgraph, cached for a few minutes
+    pub async fn get_network_stats(
         &self,
         network_id: i64,
-        address: Address,
-    ) -> Result<Option<GetDomainOutput>, SubgraphReadError> {
-        let network = self
-            .networks
-            .get(&network_id)
-            .ok_or_else(|| SubgraphReadError::NetworkNotFound(network_id))?;
+    ) -> Result<NetworkStats, SubgraphReadError> {
+        let network = self.network(network_id)?;
         let subgraph = &network.default_subgraph;
-        let maybe_domain_name = resolve_addresses(self.pool.as_ref(), subgraph, vec![address])
-            .await?
-            .into_iter()
-            .next()
-            .map(|d| d.domain_name);
-        if let Some(domain_name) = maybe_domain_name {
-            let result = self
-                .get_domain(GetDomainInput {
-                    network_id,
-                    name: domain_name,
-                    only_active: true,
-                })
-                .await?
+        cached_network_stats(
+            self.read_pool.as_ref(),
+            &self.batch_options(),
+            &subgraph.schema_name,
+            subgraph.reverse_registrar(),
+            subgraph.sql_quirks(),
+        )
+        .await
+    }
+
+    /// Best matches of search bar query: domain with exact name first, then domains
+    /// with name starting with query. Names are searched in subgraph serving TLD of query.
+    /// If query is address, its primary name is returned instead
+    pub async fn quick_search(
+        &self,
+        input: QuickSearchInput,
+    ) -> Result<Vec<Domain>, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
+        let query = input.query.trim();
+        if let Ok(address) = Address::from_str(query) {
+            return self.quick_search_address(&network, address).await;
+        }
+        let subgraph = network.subgraph_for_name(query);
+        // query is incomplete while user types, so invalid names are not an error
+        let exact_name = subgraph.domain_name(query).ok();
+        let prefix = DomainName::new(query, None)
+            .map(|n| n.name)
+            .unwrap_or_else(|_| query.to_lowercase());
+        if prefix.is_empty() {
+            return Ok(vec![]);
+        }
+        let domains = sql::quick_search_domains(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
+            &subgraph.schema_name,
+            exact_name.as_ref(),
+            &prefix,
+            input.limit,
+            input.rank,
+            subgraph.sql_quirks(),
+        )
+        .await?;
+        let domains = self
+            .decode_unknown_labels(network.label_decoder.as_deref(), subgraph, domains)
+            .await;
+        Ok(subgraph.verify_namehashes(domains))
+    }
+
+    async fn quick_search_address(
+        &self,
+        network: &Network,
+        address: Address,
+    ) -> Result<Vec<Domain>, SubgraphReadError> {
+        let maybe_names = resolve_addresses_by_priority(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
+            &network.primary_name_subgraphs,
+            vec![address],
+            self.batch_search_cache.as_deref(),
+            self.reverse_records_cache.as_deref(),
+        )
+        .await?
+        .into_values()
+        .next();
+        let Some(names) = maybe_names else {
+            return Ok(vec![]);
+        };
+        let subgraph = &network.primary_name_subgraphs[names.subgraph_index];
+        let domain_name = subgraph.domain_name(&names.primary.domain_name)?;
+        let domains = sql::find_domains(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
+            &subgraph.schema_name,
+            Some(vec![&domain_name]),
+            DomainStateFilter {
+                only_active: true,
+                include_burned: true,
+            },
+            subgraph.sql_quirks(),
+            subgraph.tld_rules(),
+            None,
+        )
+        .await?
+        .into_iter()
+        .map(|domain| Domain {
+            is_primary: true,
+            ..domain
+        })
+        .collect();
+        let domains = self
+            .decode_unknown_labels(network.label_decoder.as_deref(), subgraph, domains)
+            .await;
+        Ok(subgraph.verify_namehashes(domains))
+    }
+
+    /// Unknown labels of domains are listed as `[labelhash]`, tries to decode them
+    async fn decode_unknown_labels(
+        &self,
+        label_decoder: Option<&LabelDecoderClient>,
+        subgraph: &Subgraph,
+        domains: Vec<Domain>,
+    ) -> Vec<Domain> {
+        patch_unknown_labels(
+            self.pool.clone(),
+            self.query_options,
+            &subgraph.schema_name,
+            label_decoder,
+            domains,
+        )
+        .await
+    }
+
+    /// Direct subdomains of domain, like `vitalik.eth` for `eth`
+    pub async fn get_subdomains(
+        &self,
+        input: GetSubdomainsInput,
+    ) -> Result<PaginatedResult<Domain>, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
+        accepted_by_any(&network.list_subgraphs, Some(&input.name))?;
+        let label_decoder = network.label_decoder.as_deref();
+        let input = &input;
+        merged_domains_page(
+            &network.list_subgraphs,
+            &input.pagination,
+            |subgraph, pagination| async move {
+                let Ok(name) = subgraph.domain_name(&input.name) else {
+                    return Ok(None);
+                };
+                let domains = sql::find_subdomains(
+                    self.read_pool.as_ref(),
+                    &self.interactive_options(),
+                    &subgraph.schema_name,
+                    &name.id,
+                    input.state_filter(),
+                    subgraph.sql_quirks(),
+                    &pagination,
+                )
+                .await?;
+                let domains = self
+                    .decode_unknown_labels(label_decoder, subgraph, domains)
+                    .await;
+                let domains = subgraph.verify_namehashes(domains);
+                let total_records = sql::count_subdomains(
+                    self.read_pool.as_ref(),
+                    &self.interactive_options(),
+                    &subgraph.schema_name,
+                    &name.id,
+                    input.state_filter(),
+                    subgraph.sql_quirks(),
+                )
+                .await?;
+                Ok(Some((domains, total_records)))
+            },
+        )
+        .await
+    }
+
+    pub async fn lookup_domain_name(
+        &self,
+        input: LookupDomainInput,
+    ) -> Result<PaginatedResult<Domain>, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
+        accepted_by_any(&network.list_subgraphs, input.name.as_deref())?;
+        let label_decoder = network.label_decoder.as_deref();
+        let input = &input;
+        merged_domains_page(
+            &network.list_subgraphs,
+            &input.pagination,
+            |subgraph, pagination| async move {
+                let Ok(maybe_domain_name) = input
+                    .name
+                    .as_ref()
+                    .map(|name| subgraph.domain_name(name))
+                    .transpose()
+                else {
+                    return Ok(None);
+                };
+                let domain_names = maybe_domain_name.as_ref().map(|name| {
+                    if name.is_child_of_tld() {
+                        vec![name]
+                    } else {
+                        vec![]
+                    }
+                });
+                let total_records = sql::count_domains(
+                    self.read_pool.as_ref(),
+                    &self.interactive_options(),
+                    &subgraph.schema_name,
+                    domain_names.clone(),
+                    input.state_filter(),
+                    subgraph.sql_quirks(),
+                    subgraph.tld_rules(),
+                )
+                .await?;
+                let domains: Vec<Domain> = sql::find_domains(
+                    self.read_pool.as_ref(),
+                    &self.interactive_options(),
+                    &subgraph.schema_name,
+                    domain_names,
+                    input.state_filter(),
+                    subgraph.sql_quirks(),
+                    subgraph.tld_rules(),
+                    Some(&pagination),
+                )
+                .await?
+                .into_iter()
+                .map(|domain| {
+                    if let Some(domain_name) = maybe_domain_name.as_ref() {
+                        patch_domain(
+                            self.pool.clone(),
+                            self.query_options,
+                            &subgraph.schema_name,
+                            domain,
+                            domain_name,
+                        )
+                    } else {
+                        domain
+                    }
+                })
+                .collect();
+                let domains = self
+                    .decode_unknown_labels(label_decoder, subgraph, domains)
+                    .await;
+                let domains = subgraph.verify_namehashes(domains);
+                Ok(Some((domains, total_records)))
+            },
+        )
+        .await
+    }
+
+    pub async fn lookup_address(
+        &self,
+        input: LookupAddressInput,
+    ) -> Result<PaginatedResult<Domain>, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
+        if UNRESOLVABLE_ADDRESSES.contains(&input.address) {
+            return Ok(PaginatedResult::empty(input.pagination.page_size));
+        }
+        accepted_by_any(&network.list_subgraphs, input.parent_domain.as_deref())?;
+        let label_decoder = network.label_decoder.as_deref();
+        let input = &input;
+        merged_domains_page(
+            &network.list_subgraphs,
+            &input.pagination,
+            |subgraph, pagination| async move {
+                let Ok(parent_id) = parent_domain_id(input.parent_domain.as_deref(), subgraph)
+                else {
+                    return Ok(None);
+                };
+                let subgraph_input = LookupAddressInput {
+                    pagination,
+                    ..input.clone()
+                };
+                let domains: Vec<Domain> = sql::find_resolved_addresses(
+                    self.read_pool.as_ref(),
+                    &self.interactive_options(),
+                    &subgraph.schema_name,
+                    &subgraph_input,
+                    parent_id.as_deref(),
+                    subgraph.address_resolve_technique(),
+                    subgraph.reverse_registrar(),
+                    subgraph.tld_rules(),
+                    subgraph.sql_quirks(),
+                )
+                .await?;
+                let domains = self
+                    .decode_unknown_labels(label_decoder, subgraph, domains)
+                    .await;
+                let domains = subgraph.verify_namehashes(domains);
+                let total_records = sql::count_domains_by_address(
+                    self.read_pool.as_ref(),
+                    &self.interactive_options(),
+                    &subgraph.schema_name,
+                    &input.address_filter(),
+                    subgraph.sql_quirks(),
+                    parent_id.as_deref(),
+                    subgraph.tld_rules(),
+                )
+                .await?;
+                Ok(Some((domains, total_records)))
+            },
+        )
+        .await
+    }
+
+    /// Domains resolved to address of non-EVM coin, like BTC, by multicoin records of resolver
+    pub async fn lookup_multicoin_address(
+        &self,
+        input: LookupMulticoinAddressInput,
+    ) -> Result<PaginatedResult<Domain>, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
+        if input.address.is_empty() {
+            return Ok(PaginatedResult::empty(input.pagination.page_size));
+        }
+        accepted_by_any(&network.list_subgraphs, input.parent_domain.as_deref())?;
+        let label_decoder = network.label_decoder.as_deref();
+        let input = &input;
+        merged_domains_page(
+            &network.list_subgraphs,
+            &input.pagination,
+            |subgraph, pagination| async move {
+                let Ok(parent_id) = parent_domain_id(input.parent_domain.as_deref(), subgraph)
+                else {
+                    return Ok(None);
+                };
+                let subgraph_input = LookupMulticoinAddressInput {
+                    pagination,
+                    ..input.clone()
+                };
+                let domains = sql::find_domains_by_multicoin_address(
+                    self.read_pool.as_ref(),
+                    &self.interactive_options(),
+                    &subgraph.schema_name,
+                    &subgraph_input,
+                    parent_id.as_deref(),
+                    subgraph.tld_rules(),
+                    subgraph.sql_quirks(),
+                )
+                .await?;
+                let domains = self
+                    .decode_unknown_labels(label_decoder, subgraph, domains)
+                    .await;
+                let domains = subgraph.verify_namehashes(domains);
+                let total_records = sql::count_domains_by_multicoin_address(
+                    self.read_pool.as_ref(),
+                    &self.interactive_options(),
+                    &subgraph.schema_name,
+                    &subgraph_input,
+                    parent_id.as_deref(),
+                    subgraph.tld_rules(),
+                    subgraph.sql_quirks(),
+                )
+                .await?;
+                Ok(Some((domains, total_records)))
+            },
+        )
+        .await
+    }
+
+    /// Returns counts of resolved and owned domains, primary name
+    /// and first page of domains of `input.address` using single query
+    pub async fn get_address_summary(
+        &self,
+        input: LookupAddressInput,
+    ) -> Result<AddressSummary, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
+        if UNRESOLVABLE_ADDRESSES.contains(&input.address) {
+            return Ok(AddressSummary {
+                resolved_count: 0,
+                owned_count: 0,
+                primary_name: None,
+                domains: PaginatedList::empty(),
+            });
+        }
+        let subgraph = &network.default_subgraph;
+        let parent_id = parent_domain_id(input.parent_domain.as_deref(), subgraph)?;
+        let rows = sql::get_address_summary(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
+            &subgraph.schema_name,
+            &input,
+            parent_id.as_deref(),
+            subgraph.address_resolve_technique(),
+            subgraph.reverse_registrar(),
+            subgraph.tld_rules(),
+            subgraph.sql_quirks(),
+        )
+        .await?;
+        let summary = rows.first().ok_or_else(|| {
+            SubgraphReadError::Internal("address summary query returned no rows".to_string())
+        })?;
+        let (resolved_count, owned_count, primary_name) = (
+            summary.resolved_count,
+            summary.owned_count,
+            summary.primary_name.clone(),
+        );
+        let domains: Vec<Domain> = rows.into_iter().filter_map(|row| row.domain).collect();
+        let domains = self
+            .decode_unknown_labels(network.label_decoder.as_deref(), subgraph, domains)
+            .await;
+        let domains = input
+            .pagination
+            .paginate_result(domains)
+            .map_err(|e| SubgraphReadError::Internal(format!("cannot paginate result: {e}")))?;
+        Ok(AddressSummary {
+            resolved_count,
+            owned_count,
+            primary_name,
+            domains,
+        })
+    }
+
+    /// Detailed primary name of address. Name is returned only if it's verified,
+    /// that is reverse record of address is set to it and it resolves back to the address
+    pub async fn get_address(
+        &self,
+        network_id: i64,
+        address: Address,
+    ) -> Result<Option<GetDomainOutput>, SubgraphReadError> {
+        let network = self.network(network_id)?;
+        if UNRESOLVABLE_ADDRESSES.contains(&address) {
+            return Ok(None);
+        }
+        let maybe_names = resolve_addresses_by_priority(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
+            &network.primary_name_subgraphs,
+            vec![address],
+            self.batch_search_cache.as_deref(),
+            self.reverse_records_cache.as_deref(),
+        )
+        .await?
+        .into_values()
+        .next();
+        if let Some(names) = maybe_names {
+            // primary name is read from subgraph of protocol it was found in
+            let subgraph = &network.primary_name_subgraphs[names.subgraph_index];
+            let input = GetDomainInput {
+                network_id,
+                name: names.primary.domain_name,
+                only_active: true,
+                coin_types: vec![],
+                include_resolved_address_history: false,
+            };
+            let result = self
+                .get_domain_from_subgraph(&network, subgraph, &input)
+                .await?
                 .ok_or_else(|| {
                     SubgraphReadError::Internal(
                         "batch search found domain for address, but detailed domain info not found"
@@ -383,6 +1412,10 @@ impl SubgraphReader {
                     )
                 })?;
             Ok(Some(result))
+        } else if let Some(lens) = &network.lens {
+            // address without domains is labeled by its Lens handle
+            let maybe_handle = lens::lens_handles(lens, &[address]).await.remove(&address);
+            Ok(maybe_handle.map(|handle| lens::lens_domain(handle, address)))
         } else {
             Ok(None)
         }
@@ -391,23 +1424,18 @@ impl SubgraphReader {
     pub async fn count_domains_by_address(
         &self,
         network_id: i64,
-        address: Address,
-        resolved_to: bool,
-        owned_by: bool,
+        filter: AddressDomainsFilter,
     ) -> Result<i64, SubgraphReadError> {
-        let network = self
-            .networks
-            .get(&network_id)
-            .ok_or_else(|| SubgraphReadError::NetworkNotFound(network_id))?;
+        let network = self.network(network_id)?;
         let subgraph = &network.default_subgraph;
-        let only_active = true;
         let count = sql::count_domains_by_address(
-            self.pool.as_ref(),
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
             &subgraph.schema_name,
-            address,
-            only_active,
-            resolved_to,
-            owned_by,
+            &filter,
+            subgraph.sql_quirks(),
+            None,
+            subgraph.tld_rules(),
         )
         .await?;
         Ok(count)
@@ -416,23 +1444,106 @@ impl SubgraphReader {
     pub async fn batch_resolve_address_names(
         &self,
         input: BatchResolveAddressNamesInput,
-    ) -> Result<BTreeMap<String, String>, SubgraphReadError> {
-        let network = self
-            .networks
-            .get(&input.network_id)
-            .ok_or_else(|| SubgraphReadError::NetworkNotFound(input.network_id))?;
-        let subgraph = &network.default_subgraph;
+    ) -> Result<BatchResolveAddressNamesOutput, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
         // remove duplicates
         let addresses = remove_addresses_from_batch(input.addresses);
         let addresses_len = addresses.len();
-        let result = resolve_addresses(self.pool.as_ref(), subgraph, addresses).await?;
+        let result = resolve_addresses_by_priority(
+            self.read_pool.as_ref(),
+            &self.batch_options(),
+            &network.primary_name_subgraphs,
+            addresses.clone(),
+            self.batch_search_cache.as_deref(),
+            self.reverse_records_cache.as_deref(),
+        )
+        .await?;
+
+        let mut output = BatchResolveAddressNamesOutput::default();
+        for (address, names) in result {
+            if !names.other_names.is_empty() {
+                output
+                    .other_names
+                    .insert(address.clone(), names.other_names);
+            }
+            output.names.insert(address, names.primary.domain_name);
+        }
+        if let Some(lens) = &network.lens {
+            lens::add_lens_handles(lens, &addresses, &mut output).await;
+        }
+        tracing::info!(address_to_name =? output.names, "{}/{addresses_len} names found from batch request", output.names.len());
+        Ok(output)
+    }
+
+    pub async fn batch_resolve_domain_names(
+        &self,
+        input: BatchResolveDomainNamesInput,
+    ) -> Result<BTreeMap<String, String>, SubgraphReadError> {
+        let network = self.network(input.network_id)?;
+        let names_len = input.names.len();
+        // names are resolved by subgraph serving their TLD
+        let mut names_by_schema: HashMap<String, Vec<String>> = HashMap::new();
+        for name in input.names {
+            let schema = &network.subgraph_for_name(&name).schema_name;
+            names_by_schema
+                .entry(schema.clone())
+                .or_default()
+                .push(name);
+        }
+        let mut name_to_address = BTreeMap::new();
+        for subgraph in &network.subgraphs {
+            let Some(names) = names_by_schema.remove(&subgraph.schema_name) else {
+                continue;
+            };
+            let resolved = self.batch_resolve_subgraph_names(subgraph, names).await?;
+            name_to_address.extend(resolved);
+        }
+        tracing::info!(name_to_address =? name_to_address, "{}/{names_len} addresses found from batch request", name_to_address.len());
+        Ok(name_to_address)
+    }
 
-        let address_to_name: BTreeMap<String, String> = result
+    async fn batch_resolve_subgraph_names(
+        &self,
+        subgraph: &Subgraph,
+        names: Vec<String>,
+    ) -> Result<BTreeMap<String, String>, SubgraphReadError> {
+        // remove duplicates, but keep every requested spelling of name for response
+        let mut requested: HashMap<String, (DomainName, Vec<String>)> = HashMap::new();
+        for name in names {
+            match subgraph.domain_name(&name) {
+                Ok(domain_name) => requested
+                    .entry(domain_name.id.clone())
+                    .or_insert_with(|| (domain_name, vec![]))
+                    .1
+                    .push(name),
+                Err(err) => {
+                    tracing::warn!(err =? err, "invalid name '{name}' in batch request, skip");
+                }
+            }
+        }
+        let domain_names: Vec<DomainName> = requested.values().map(|(d, _)| d.clone()).collect();
+        let domains = sql::batch_resolve_domain_names(
+            self.read_pool.as_ref(),
+            &self.batch_options(),
+            &subgraph.schema_name,
+            &domain_names,
+            subgraph.sql_quirks(),
+        )
+        .await?;
+        let name_to_address: BTreeMap<String, String> = domains
             .into_iter()
-            .map(|d| (d.resolved_address, d.domain_name))
+            .filter_map(|d| {
+                requested
+                    .get(&d.id)
+                    .map(|(_, names)| (names, d.resolved_address))
+            })
+            .flat_map(|(names, address)| {
+                names
+                    .iter()
+                    .map(move |name| (name.clone(), address.clone()))
+            })
             .collect();
-        tracing::info!(address_to_name =? address_to_name, "{}/{addresses_len} names found from batch request", address_to_name.len());
-        Ok(address_to_name)
+        Ok(name_to_address)
     }
 }
 
@@ -445,6 +1556,85 @@ fn remove_addresses_from_batch(addresses: impl IntoIterator<Item = H160>) -> Vec
     addresses.into_iter().collect()
 }
 
+/// Subgraph serving TLD of `name`, like SpaceID subgraph for `.bnb` names.
+/// Default subgraph is used if no other subgraph lists TLD of name in its `allowed_tlds`
+fn subgraph_for_name<'a>(
+    subgraphs: &'a [Subgraph],
+    default_subgraph: &'a Subgraph,
+    name: &str,
+) -> &'a Subgraph {
+    let name = name.trim_matches('.').to_lowercase();
+    std::iter::once(default_subgraph)
+        .chain(subgraphs)
+        .find(|subgraph| {
+            let tld_rules = subgraph.tld_rules();
+            !tld_rules.allowed_tlds.is_empty() && tld_rules.is_allowed(&name)
+        })
+        .unwrap_or(default_subgraph)
+}
+
+/// Fails with error of the first subgraph only if none of `subgraphs` accepts `name`,
+/// otherwise subgraphs which reject it are skipped
+fn accepted_by_any(subgraphs: &[Subgraph], name: Option<&str>) -> Result<(), DomainNameError> {
+    let Some(name) = name else {
+        return Ok(());
+    };
+    let mut first_err = None;
+    for subgraph in subgraphs {
+        match subgraph.domain_name(name) {
+            Ok(_) => return Ok(()),
+            Err(err) => {
+                first_err.get_or_insert(err);
+            }
+        }
+    }
+    first_err.map_or(Ok(()), Err)
+}
+
+/// Page of domains merged from several subgraphs by sort key of `pagination`.
+/// `fetch` reads page of single subgraph with total number of its records,
+/// subgraph is skipped if it returns `None`
+async fn merged_domains_page<'a, F, Fut>(
+    subgraphs: &'a [Subgraph],
+    pagination: &DomainPaginationInput,
+    fetch: F,
+) -> Result<PaginatedResult<Domain>, SubgraphReadError>
+where
+    F: Fn(&'a Subgraph, DomainPaginationInput) -> Fut,
+    Fut: Future<Output = Result<Option<(Vec<Domain>, i64)>, SubgraphReadError>>,
+{
+    let queries = subgraphs
+        .iter()
+        .enumerate()
+        .map(|(index, subgraph)| {
+            pagination
+                .for_subgraph(index)
+                .map(|pagination| fetch(subgraph, pagination))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| SubgraphReadError::Internal(format!("adding pagination to query: {e}")))?;
+    let mut pages = Vec::with_capacity(queries.len());
+    let mut total_records = 0;
+    for result in futures::future::try_join_all(queries).await? {
+        let (domains, total) = result.unwrap_or_default();
+        pages.push(domains);
+        total_records += total;
+    }
+    pagination
+        .paginate_merged(pages, total_records)
+        .map_err(|e| SubgraphReadError::Internal(format!("cannot paginate result: {e}")))
+}
+
+pub(super) fn parent_domain_id(
+    parent_domain: Option<&str>,
+    subgraph: &Subgraph,
+) -> Result<Option<String>, DomainNameError> {
+    parent_domain
+        .map(|name| subgraph.domain_name(name))
+        .transpose()
+        .map(|name| name.map(|n| n.id))
+}
+
 #[instrument(name = "events_from_transactions", skip_all, fields(job_size = txns.len()), err, level = "info")]
 async fn events_from_transactions(
     client: Arc<BlockscoutClient>,
@@ -487,15 +1677,151 @@ async fn events_from_transactions(
     Ok(events)
 }
 
+/// Timestamps of changes are taken from blockscout. Since history is optional part
+/// of domain details, blockscout errors are logged and lead to empty timestamps
+async fn resolved_address_history(
+    client: Arc<BlockscoutClient>,
+    events: Vec<AddrChangedEvent>,
+) -> Vec<ResolvedAddressChange> {
+    let txn_ids: HashSet<TxHash> = events
+        .iter()
+        .map(|e| TxHash::from_slice(e.transaction_id.as_slice()))
+        .collect();
+    let timestamps: HashMap<TxHash, String> = match client.transactions_batch(txn_ids).await {
+        Ok(txns) => txns
+            .into_iter()
+            .filter_map(|(hash, result)| match result {
+                blockscout::Response::Ok(t) => Some((hash, t.timestamp)),
+                e => {
+                    tracing::warn!(
+                        "invalid response from blockscout transaction '{hash:#x}' api: {e:?}"
+                    );
+                    None
+                }
+            })
+            .collect(),
+        Err(err) => {
+            tracing::warn!(err =? err, "failed to fetch transactions of address changes");
+            Default::default()
+        }
+    };
+    events
+        .into_iter()
+        .filter_map(|event| {
+            let address = Address::from_str(&event.addr)
+                .map_err(|err| {
+                    tracing::warn!(addr = event.addr, err =? err, "invalid address in addr_changed")
+                })
+                .ok()?;
+            let transaction_hash = TxHash::from_slice(event.transaction_id.as_slice());
+            Some(ResolvedAddressChange {
+                address,
+                block_number: event.block_number as i64,
+                transaction_hash,
+                timestamp: timestamps.get(&transaction_hash).cloned(),
+            })
+        })
+        .collect()
+}
+
+fn domain_records_from_row(row: DomainRecordsRow, domain_name: DomainName) -> DomainRecords {
+    let mut addresses: Vec<CoinAddress> = row
+        .addresses
+        .0
+        .into_iter()
+        .map(|(coin_type, address)| {
+            let coin = Coin::find_or_unknown(&coin_type);
+            let address = coin.format_address(address);
+            CoinAddress {
+                coin_type,
+                coin_name: coin.name,
+                address,
+            }
+        })
+        .collect();
+    addresses.sort_by_key(|a| a.coin_type.parse::<u64>().unwrap_or(u64::MAX));
+    let text_records = row
+        .text_records
+        .0
+        .into_iter()
+        .map(|(key, value)| TextRecord { key, value })
+        .collect();
+    DomainRecords {
+        id: row.id,
+        name: domain_name.name,
+        addresses,
+        text_records,
+        content_hash: row.content_hash.as_deref().and_then(ContentHash::new),
+    }
+}
+
+#[cached(
+    key = "String",
+    convert = r#"{ schema.to_string() }"#,
+    result = true,
+    time = 300,
+    size = 1_000,
+    sync_writes = true
+)]
+async fn cached_network_stats(
+    pool: &PgPool,
+    options: &sql::QueryOptions,
+    schema: &str,
+    reverse_registrar: &ReverseRegistrar,
+    quirks: sql::SqlQuirks,
+) -> Result<NetworkStats, SubgraphReadError> {
+    sql::get_network_stats(pool, options, schema, reverse_registrar, quirks).await
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{subgraphs_reader::sql, test_utils::mocked_networks_with_blockscout};
-    use ethers::types::Address;
+    use crate::{
+        content_hash::ContentHash,
+        entity::subgraph::domain::{DomainStatus, ExpiryStatus},
+        hash_name::domain_id,
+        subgraphs_reader::{sql, DomainPaginationInput, Order, QuickSearchRank},
+        test_utils::{
+            addr, mocked_networks_with_blockscout, tx_hash, DEFAULT_CHAIN_ID, DEFAULT_SCHEMA,
+        },
+    };
+    use ethers::types::{Address, H256};
     use pretty_assertions::assert_eq;
 
-    const DEFAULT_CHAIN_ID: i64 = 1;
-    const DEFAULT_SCHEMA: &str = "sgd1";
+    #[test]
+    fn subgraph_for_name_works() {
+        let subgraph = |schema_name: &str, allowed_tlds: &[&str]| {
+            Subgraph::new(
+                schema_name.to_string(),
+                SubgraphSettings {
+                    tld_rules: TldRules {
+                        allowed_tlds: allowed_tlds.iter().map(|tld| tld.to_string()).collect(),
+                        default_tld: None,
+                        native_tlds: vec![],
+                    },
+                    ..Default::default()
+                },
+            )
+        };
+        let ens = subgraph("sgd1", &[]);
+        let space_id_bnb = subgraph("sgd2", &["bnb"]);
+        let space_id_arb = subgraph("sgd3", &["arb"]);
+        let subgraphs = vec![ens.clone(), space_id_bnb.clone(), space_id_arb.clone()];
+
+        for (name, expected) in [
+            ("vitalik.eth", &ens),
+            ("vitalik", &ens),
+            ("levvv.bnb", &space_id_bnb),
+            ("sub.levvv.BNB.", &space_id_bnb),
+            ("levvv.arb", &space_id_arb),
+        ] {
+            assert_eq!(
+                subgraph_for_name(&subgraphs, &ens, name).schema_name,
+                expected.schema_name,
+                "invalid subgraph of '{name}'"
+            );
+        }
+    }
 
     #[sqlx::test(migrations = "tests/migrations")]
     async fn get_domain_works(pool: PgPool) {
@@ -512,6 +1838,8 @@ mod tests {
                 network_id: DEFAULT_CHAIN_ID,
                 name,
                 only_active: false,
+                coin_types: vec![],
+                include_resolved_address_history: true,
             })
             .await
             .expect("failed to get vitalik domain")
@@ -528,6 +1856,83 @@ mod tests {
         }))
         .unwrap();
         assert_eq!(domain.other_addresses, other_addresses.into());
+        let content_hash = ContentHash::new(
+            domain
+                .content_hash
+                .as_deref()
+                .expect("vitalik.eth has contenthash"),
+        )
+        .expect("contenthash is not empty");
+        assert_eq!(
+            content_hash.decoded.as_deref(),
+            Some("ipfs://QmXGkXcfasLtjLk9i5brs2sMYNbk34c8FGdBDCP864mrhV")
+        );
+        assert_eq!(
+            result.resolved_address_history,
+            vec![
+                ResolvedAddressChange {
+                    address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+                    block_number: 8834378,
+                    transaction_hash: TxHash::from_str(
+                        "0x09922ac0caf1efcc8f68ce004f382b46732258870154d8805707a1d4b098dfd0"
+                    )
+                    .unwrap(),
+                    timestamp: Some("2019-10-29T13:47:34.000000Z".to_string()),
+                },
+                ResolvedAddressChange {
+                    address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+                    block_number: 11862656,
+                    transaction_hash: TxHash::from_str(
+                        "0x160ef4492c731ac6b59beebe1e234890cd55d4c556f8847624a0b47125fe4f84"
+                    )
+                    .unwrap(),
+                    timestamp: Some("2021-02-15T17:19:09.000000Z".to_string()),
+                },
+            ]
+        );
+
+        // name is normalized before lookup
+        let result = reader
+            .get_domain(GetDomainInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: "VitaLik.ETH".to_string(),
+                only_active: false,
+                coin_types: vec![],
+                include_resolved_address_history: false,
+            })
+            .await
+            .expect("failed to get vitalik domain")
+            .expect("domain not found");
+        assert_eq!(result.domain.name.as_deref(), Some("vitalik.eth"));
+
+        // other addresses are filtered by coin types
+        let result = reader
+            .get_domain(GetDomainInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: "vitalik.eth".to_string(),
+                only_active: false,
+                coin_types: vec!["137".to_string(), "0".to_string()],
+                include_resolved_address_history: false,
+            })
+            .await
+            .expect("failed to get vitalik domain")
+            .expect("domain not found");
+        let other_addresses: HashMap<String, String> = serde_json::from_value(serde_json::json!({
+            "RSK": "0xf0d485009714cE586358E3761754929904D76B9D",
+        }))
+        .unwrap();
+        assert_eq!(result.domain.other_addresses, other_addresses.into());
+        let err = reader
+            .get_domain(GetDomainInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: "vita_lik.eth".to_string(),
+                only_active: false,
+                coin_types: vec![],
+                include_resolved_address_history: false,
+            })
+            .await
+            .expect_err("invalid name should not be found");
+        assert!(matches!(err, SubgraphReadError::InvalidName(_)));
 
         // get expired domain
         let name = "expired.eth".to_string();
@@ -536,25 +1941,27 @@ mod tests {
                 network_id: DEFAULT_CHAIN_ID,
                 name: name.clone(),
                 only_active: false,
+                coin_types: vec![],
+                include_resolved_address_history: false,
             })
             .await
             .expect("failed to get expired domain")
             .expect("expired domain not found");
         let domain = result.domain;
-        assert!(
-            domain.is_expired,
-            "expired domain has is_expired=false: {:?}",
-            domain
-        );
+        assert_eq!(domain.expiry_status, ExpiryStatus::Expired);
         // since no info in multicoin_addr_changed
         assert!(domain.other_addresses.is_empty());
+        // since no info in contenthash_changed
+        assert!(domain.content_hash.is_none());
 
         // get expired domain with only_active filter
         let result = reader
             .get_domain(GetDomainInput {
                 network_id: DEFAULT_CHAIN_ID,
-                name,
+                name: name.clone(),
                 only_active: true,
+                coin_types: vec![],
+                include_resolved_address_history: false,
             })
             .await
             .expect("failed to get expired domain");
@@ -563,115 +1970,1025 @@ mod tests {
             "expired domain returned with only_active=true: {:?}",
             result
         );
+
+        // domain expired recently is still active during grace period of subgraph
+        sqlx::query(
+            "UPDATE sgd1.domain SET expiry_date = extract(epoch from now() - interval '10 days') \
+            WHERE name = 'expired.eth'",
+        )
+        .execute(pool.as_ref())
+        .await
+        .unwrap();
+        let input = GetDomainInput {
+            network_id: DEFAULT_CHAIN_ID,
+            name,
+            only_active: true,
+            coin_types: vec![],
+            include_resolved_address_history: false,
+        };
+        // grace period is disabled by default
+        let result = reader
+            .get_domain(input.clone())
+            .await
+            .expect("failed to get expired domain");
+        assert!(result.is_none());
+        let mut networks = mocked_networks_with_blockscout().await;
+        networks
+            .get_mut(&DEFAULT_CHAIN_ID)
+            .unwrap()
+            .subgraph_configs
+            .insert(
+                "ens-subgraph".to_string(),
+                SubgraphSettings {
+                    grace_period_days: 90,
+                    ..Default::default()
+                },
+            );
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        let result = reader
+            .get_domain(input)
+            .await
+            .expect("failed to get domain in grace period")
+            .expect("domain in grace period not found");
+        assert_eq!(result.domain.expiry_status, ExpiryStatus::InGracePeriod);
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn get_domain_by_hash_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        let get_by_hash = |hash: &str| GetDomainByHashInput {
+            network_id: DEFAULT_CHAIN_ID,
+            hash: H256::from_str(hash).unwrap(),
+            only_active: false,
+        };
+
+        for (hash, expected_name) in [
+            // namehash of `vitalik.eth`
+            (
+                "0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835",
+                Some("vitalik.eth"),
+            ),
+            // labelhash of `vitalik`
+            (
+                "0xaf2caa1c2ca1d027f1ac823b529d0a67cd144264b2789fa2ea4d63a67c7103cc",
+                Some("vitalik.eth"),
+            ),
+            // labelhash of unknown label is resolved too
+            (
+                "0x0b0e081f36b3970ff8e337f0ff7bdfad321a702fa00916b6ccfc47877144f7ad",
+                Some("[0b0e081f36b3970ff8e337f0ff7bdfad321a702fa00916b6ccfc47877144f7ad].eth"),
+            ),
+            (
+                "0x0000000000000000000000000000000000000000000000000000000000000001",
+                None,
+            ),
+        ] {
+            let result = reader
+                .get_domain_by_hash(get_by_hash(hash))
+                .await
+                .expect("failed to get domain by hash");
+            assert_eq!(
+                result.as_ref().and_then(|r| r.domain.name.as_deref()),
+                expected_name,
+                "hash {hash}"
+            );
+        }
+        let result = reader
+            .get_domain_by_hash(get_by_hash(
+                "0xaf2caa1c2ca1d027f1ac823b529d0a67cd144264b2789fa2ea4d63a67c7103cc",
+            ))
+            .await
+            .expect("failed to get domain by hash")
+            .expect("domain not found");
+        assert_eq!(
+            result.domain.id,
+            "0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835"
+        );
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn lookup_domain_name_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        let result = reader
+            .lookup_domain_name(LookupDomainInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: Some("vitalik.eth".to_string()),
+                only_active: false,
+                include_burned: false,
+                pagination: Default::default(),
+            })
+            .await
+            .expect("failed to get vitalik domains");
+        assert_eq!(result.next_page_token, None);
+        assert_eq!(result.total_records, 1);
+        let result = result.items;
+        assert_eq!(
+            vec![Some("vitalik.eth")],
+            result.iter().map(|d| d.name.as_deref()).collect::<Vec<_>>(),
+        );
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn burned_domains_filter_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        // vitalik.eth is transferred to zero address and doesn't resolve anymore
+        sqlx::query(&format!(
+            "UPDATE sgd1.domain SET owner = '{}', resolved_address = NULL WHERE name = 'vitalik.eth'",
+            sql::ZERO_ADDRESS
+        ))
+        .execute(pool.as_ref())
+        .await
+        .unwrap();
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        let lookup = |name: Option<&str>, include_burned| LookupDomainInput {
+            network_id: DEFAULT_CHAIN_ID,
+            name: name.map(String::from),
+            only_active: false,
+            include_burned,
+            pagination: DomainPaginationInput {
+                page_size: 50,
+                ..Default::default()
+            },
+        };
+        let result = reader
+            .lookup_domain_name(lookup(Some("vitalik.eth"), false))
+            .await
+            .expect("failed to lookup domains");
+        assert_eq!(result.total_records, 0);
+        assert_eq!(result.items, vec![]);
+
+        let result = reader
+            .lookup_domain_name(lookup(Some("vitalik.eth"), true))
+            .await
+            .expect("failed to lookup domains");
+        assert_eq!(result.total_records, 1);
+        assert_eq!(result.items[0].status, DomainStatus::Burned);
+
+        let hidden = reader
+            .lookup_domain_name(lookup(None, false))
+            .await
+            .expect("failed to lookup domains");
+        let all = reader
+            .lookup_domain_name(lookup(None, true))
+            .await
+            .expect("failed to lookup domains");
+        assert_eq!(hidden.total_records + 1, all.total_records);
+        let status_of = |name: &str| {
+            all.items
+                .iter()
+                .find(|d| d.name.as_deref() == Some(name))
+                .map(|d| d.status)
+        };
+        assert_eq!(status_of("vitalik.eth"), Some(DomainStatus::Burned));
+        assert_eq!(status_of("sashaxyz.eth"), Some(DomainStatus::Resolved));
+        assert!(hidden
+            .items
+            .iter()
+            .all(|d| d.status != DomainStatus::Burned));
+
+        let subdomains = |include_burned| GetSubdomainsInput {
+            network_id: DEFAULT_CHAIN_ID,
+            name: "eth".to_string(),
+            only_active: false,
+            include_burned,
+            pagination: DomainPaginationInput {
+                page_size: 50,
+                ..Default::default()
+            },
+        };
+        let hidden = reader
+            .get_subdomains(subdomains(false))
+            .await
+            .expect("failed to get subdomains");
+        let all = reader
+            .get_subdomains(subdomains(true))
+            .await
+            .expect("failed to get subdomains");
+        assert_eq!(hidden.total_records + 1, all.total_records);
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn label_decoder_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let sashaxyz_hash = hex::encode(ethers::utils::keccak256("sashaxyz"));
+        let vitalik_hash = hex::encode(ethers::utils::keccak256("vitalik"));
+        for label in ["sashaxyz", "vitalik"] {
+            let hash = hex::encode(ethers::utils::keccak256(label));
+            sqlx::query(&format!(
+                "UPDATE sgd1.domain SET name = '[{hash}].eth', label_name = NULL WHERE name = '{label}.eth'"
+            ))
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+        }
+        // decoder knows sashaxyz and responds with name of wrong hash for vitalik
+        let decoder_server = wiremock::MockServer::start().await;
+        for (hash, name) in [
+            (&sashaxyz_hash, "sashaxyz.eth"),
+            (&vitalik_hash, "notvitalik.eth"),
+        ] {
+            wiremock::Mock::given(wiremock::matchers::path(format!("/labels/0x{hash}")))
+                .respond_with(
+                    wiremock::ResponseTemplate::new(200)
+                        .set_body_json(serde_json::json!({ "name": name })),
+                )
+                .mount(&decoder_server)
+                .await;
+        }
+        let mut networks = mocked_networks_with_blockscout().await;
+        networks.get_mut(&DEFAULT_CHAIN_ID).unwrap().label_decoder = Some(LabelDecoderClient::new(
+            format!("{}/labels/{{labelhash}}", decoder_server.uri()),
+            1,
+            5,
+        ));
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        let result = reader
+            .get_subdomains(GetSubdomainsInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: "eth".to_string(),
+                only_active: false,
+                include_burned: false,
+                pagination: DomainPaginationInput {
+                    page_size: 50,
+                    ..Default::default()
+                },
+            })
+            .await
+            .expect("failed to get subdomains");
+        let names: Vec<&str> = result
+            .items
+            .iter()
+            .filter_map(|d| d.name.as_deref())
+            .collect();
+        assert!(names.contains(&"sashaxyz.eth"), "{names:?}");
+        let undecoded_vitalik_name = format!("[{vitalik_hash}].eth");
+        assert!(
+            names.contains(&undecoded_vitalik_name.as_str()),
+            "{names:?}"
+        );
+
+        // decoded name is saved in background
+        let mut saved_label_name: Option<String> = None;
+        for _ in 0..50 {
+            saved_label_name = sqlx::query_scalar(
+                "SELECT label_name FROM sgd1.domain WHERE name = 'sashaxyz.eth'",
+            )
+            .fetch_optional(pool.as_ref())
+            .await
+            .unwrap()
+            .flatten();
+            if saved_label_name.is_some() {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+        assert_eq!(saved_label_name.as_deref(), Some("sashaxyz"));
+
+        // label of domain requested by hash is decoded as well. Unexpected response
+        // of decoder is not cached, so label is decoded once decoder recovers
+        let test_hash = hex::encode(ethers::utils::keccak256("test"));
+        sqlx::query(&format!(
+            "UPDATE sgd1.domain SET name = '[{test_hash}].eth', label_name = NULL WHERE name = 'test.eth'"
+        ))
+        .execute(pool.as_ref())
+        .await
+        .unwrap();
+        wiremock::Mock::given(wiremock::matchers::path(format!("/labels/0x{test_hash}")))
+            .respond_with(wiremock::ResponseTemplate::new(403))
+            .up_to_n_times(1)
+            .mount(&decoder_server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::path(format!("/labels/0x{test_hash}")))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({ "name": "test.eth" })),
+            )
+            .mount(&decoder_server)
+            .await;
+        let get_test_name = || async {
+            reader
+                .get_domain_by_hash(GetDomainByHashInput {
+                    network_id: DEFAULT_CHAIN_ID,
+                    hash: H256::from_str(&domain_id("test.eth", None)).unwrap(),
+                    only_active: false,
+                })
+                .await
+                .expect("failed to get domain by hash")
+                .expect("test.eth exists")
+                .domain
+                .name
+        };
+        assert_eq!(get_test_name().await, Some(format!("[{test_hash}].eth")));
+        assert_eq!(get_test_name().await.as_deref(), Some("test.eth"));
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn lookup_addresses_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        let result = reader
+            .lookup_address(LookupAddressInput {
+                network_id: DEFAULT_CHAIN_ID,
+                address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+                resolved_to: true,
+                owned_by: false,
+                registered_by: false,
+                only_active: false,
+                include_burned: false,
+                parent_domain: None,
+                pagination: Default::default(),
+            })
+            .await
+            .expect("failed to get vitalik domains");
+        assert_eq!(result.next_page_token, None);
+        assert_eq!(result.total_records, 2);
+        let result = result.items;
+        assert_eq!(
+            result.iter().map(|d| d.name.as_deref()).collect::<Vec<_>>(),
+            vec![Some("vitalik.eth"), Some("sashaxyz.eth")]
+        );
+        // only name from verified reverse record is primary
+        assert_eq!(
+            result.iter().map(|d| d.is_primary).collect::<Vec<_>>(),
+            vec![true, false]
+        );
+
+        let result = reader
+            .lookup_address(LookupAddressInput {
+                network_id: DEFAULT_CHAIN_ID,
+                address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+                resolved_to: true,
+                owned_by: false,
+                registered_by: false,
+                only_active: false,
+                include_burned: false,
+                parent_domain: None,
+                pagination: DomainPaginationInput {
+                    page_size: 1,
+                    ..Default::default()
+                },
+            })
+            .await
+            .expect("failed to get vitalik domains");
+        assert_eq!(result.items.len(), 1);
+        assert!(result.next_page_token.is_some());
+        assert_eq!(result.page_size, 1);
+        // total is calculated over all pages
+        assert_eq!(result.total_records, 2);
+
+        let result = reader
+            .lookup_address(LookupAddressInput {
+                network_id: DEFAULT_CHAIN_ID,
+                address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+                resolved_to: false,
+                owned_by: true,
+                registered_by: false,
+                only_active: false,
+                include_burned: false,
+                parent_domain: None,
+                pagination: Default::default(),
+            })
+            .await
+            .expect("failed to get vitalik domains");
+        assert_eq!(result.next_page_token, None);
+        assert_eq!(result.total_records, 1);
+        let result = result.items;
+        assert_eq!(
+            result.iter().map(|d| d.name.as_deref()).collect::<Vec<_>>(),
+            vec![Some("vitalik.eth")]
+        );
+
+        // filter by parent domain
+        for (parent_domain, expected) in [
+            ("eth", vec![Some("vitalik.eth"), Some("sashaxyz.eth")]),
+            ("vitalik.eth", vec![]),
+        ] {
+            let result = reader
+                .lookup_address(LookupAddressInput {
+                    network_id: DEFAULT_CHAIN_ID,
+                    address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+                    resolved_to: true,
+                    owned_by: false,
+                    registered_by: false,
+                    only_active: false,
+                    include_burned: false,
+                    parent_domain: Some(parent_domain.to_string()),
+                    pagination: Default::default(),
+                })
+                .await
+                .expect("failed to get vitalik domains");
+            assert_eq!(result.total_records, expected.len() as i64);
+            assert_eq!(
+                result
+                    .items
+                    .iter()
+                    .map(|d| d.name.as_deref())
+                    .collect::<Vec<_>>(),
+                expected,
+                "invalid domains for parent domain '{parent_domain}'"
+            );
+        }
+
+        // search for expired address
+        let result = reader
+            .lookup_address(LookupAddressInput {
+                network_id: DEFAULT_CHAIN_ID,
+                address: addr("0x9f7f7ddbfb8e14d1756580ba8037530da0880b99"),
+                resolved_to: true,
+                owned_by: true,
+                registered_by: false,
+                only_active: false,
+                include_burned: false,
+                parent_domain: None,
+                pagination: Default::default(),
+            })
+            .await
+            .expect("failed to get expired domains");
+        assert_eq!(result.next_page_token, None);
+        assert_eq!(result.total_records, 1);
+        let result = result.items;
+        // expired domain shoudn't be returned as resolved
+        assert_eq!(
+            result.iter().map(|d| d.name.as_deref()).collect::<Vec<_>>(),
+            vec![Some("expired.eth")]
+        );
+        // search for expired address with only_active
+        let result = reader
+            .lookup_address(LookupAddressInput {
+                network_id: DEFAULT_CHAIN_ID,
+                address: addr("0x9f7f7ddbfb8e14d1756580ba8037530da0880b99"),
+                resolved_to: true,
+                owned_by: true,
+                registered_by: false,
+                only_active: true,
+                include_burned: false,
+                parent_domain: None,
+                pagination: Default::default(),
+            })
+            .await
+            .expect("failed to get expired domains");
+        assert_eq!(result.next_page_token, None);
+        assert_eq!(result.total_records, 0);
+        let result = result.items;
+        // expired domain shoudn't be returned as resolved
+        assert_eq!(
+            result.iter().map(|d| d.name.as_deref()).collect::<Vec<_>>(),
+            vec![]
+        );
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn lookup_addresses_by_registrant_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        let registrant = "0x220866b1a2219f40e72f5c628b65d54268ca3a9d";
+        let lookup = |registered_by| LookupAddressInput {
+            network_id: DEFAULT_CHAIN_ID,
+            address: addr(registrant),
+            resolved_to: false,
+            owned_by: true,
+            registered_by,
+            only_active: false,
+            include_burned: false,
+            parent_domain: None,
+            pagination: Default::default(),
+        };
+
+        // registrant doesn't own vitalik.eth in registry
+        let result = reader
+            .lookup_address(lookup(false))
+            .await
+            .expect("failed to get owned domains");
+        assert_eq!(result.total_records, 0);
+
+        let result = reader
+            .lookup_address(lookup(true))
+            .await
+            .expect("failed to get registered domains");
+        assert_eq!(result.total_records, 1);
+        let domain = &result.items[0];
+        assert_eq!(domain.name.as_deref(), Some("vitalik.eth"));
+        assert_eq!(domain.owner, "0xd8da6bf26964af9d7eed9e03e53415d37aa96045");
+        assert_eq!(domain.registrant.as_deref(), Some(registrant));
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn lookup_multicoin_address_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        for (coin_type, address, expected_names) in [
+            // current BTC address of sashaxyz.eth
+            (
+                "0",
+                "0x0014751e76e8199196d454941c45d1b3a323f1433bd6",
+                vec!["sashaxyz.eth"],
+            ),
+            (
+                "0",
+                "0x0014751e76e8199196d454941c45d1b3a323f1433bd7",
+                vec![],
+            ),
+            // LTC address was removed later
+            (
+                "2",
+                "0x76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac",
+                vec![],
+            ),
+            // ETH address was changed later
+            ("60", "0x66a6f7744ce4dea450910b81a7168588f992eafb", vec![]),
+            (
+                "60",
+                "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+                vec!["vitalik.eth", "sashaxyz.eth"],
+            ),
+        ] {
+            let result = reader
+                .lookup_multicoin_address(LookupMulticoinAddressInput {
+                    network_id: DEFAULT_CHAIN_ID,
+                    coin_type: coin_type.to_string(),
+                    address: Bytes::from_str(address).unwrap(),
+                    only_active: false,
+                    include_burned: false,
+                    parent_domain: None,
+                    pagination: Default::default(),
+                })
+                .await
+                .expect("failed to lookup multicoin address");
+            assert_eq!(result.total_records, expected_names.len() as i64);
+            assert_eq!(
+                result
+                    .items
+                    .iter()
+                    .map(|d| d.name.as_deref().unwrap_or_default())
+                    .collect::<Vec<_>>(),
+                expected_names,
+                "coin type {coin_type}, address {address}"
+            );
+        }
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn get_address_summary_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        let result = reader
+            .get_address_summary(LookupAddressInput {
+                network_id: DEFAULT_CHAIN_ID,
+                address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+                resolved_to: true,
+                owned_by: true,
+                registered_by: false,
+                only_active: false,
+                include_burned: false,
+                parent_domain: None,
+                pagination: DomainPaginationInput {
+                    page_size: 1,
+                    ..Default::default()
+                },
+            })
+            .await
+            .expect("failed to get vitalik summary");
+        assert_eq!(result.resolved_count, 2);
+        assert_eq!(result.owned_count, 1);
+        assert_eq!(result.primary_name.as_deref(), Some("vitalik.eth"));
+        assert!(result.domains.next_page_token.is_some());
+        assert!(result.domains.items[0].is_primary);
+        assert_eq!(
+            result
+                .domains
+                .items
+                .iter()
+                .map(|d| d.name.as_deref())
+                .collect::<Vec<_>>(),
+            vec![Some("vitalik.eth")]
+        );
+
+        // `test.eth` resolves to this address, but reverse record points to another name
+        let result = reader
+            .get_address_summary(LookupAddressInput {
+                network_id: DEFAULT_CHAIN_ID,
+                address: addr("0xeefb13c7d42efcc655e528da6d6f7bbcf9a2251d"),
+                resolved_to: true,
+                owned_by: false,
+                registered_by: false,
+                only_active: false,
+                include_burned: false,
+                parent_domain: None,
+                pagination: Default::default(),
+            })
+            .await
+            .expect("failed to get test.eth summary");
+        assert_eq!(result.primary_name, None);
+
+        // address without any domains
+        let result = reader
+            .get_address_summary(LookupAddressInput {
+                network_id: DEFAULT_CHAIN_ID,
+                address: addr("0x0101010101010101010101010101010101010102"),
+                resolved_to: true,
+                owned_by: true,
+                registered_by: false,
+                only_active: false,
+                include_burned: false,
+                parent_domain: None,
+                pagination: Default::default(),
+            })
+            .await
+            .expect("failed to get empty summary");
+        assert_eq!(
+            result,
+            AddressSummary {
+                resolved_count: 0,
+                owned_count: 0,
+                primary_name: None,
+                domains: PaginatedList::empty(),
+            }
+        );
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn reload_networks_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let reader = SubgraphReader::initialize(pool.clone(), Default::default())
+            .await
+            .expect("failed to init reader");
+        assert_eq!(reader.network_ids(), Vec::<i64>::new());
+        let input = GetDomainInput {
+            network_id: DEFAULT_CHAIN_ID,
+            name: "vitalik.eth".to_string(),
+            only_active: false,
+            coin_types: vec![],
+            include_resolved_address_history: false,
+        };
+        let err = reader
+            .get_domain(input.clone())
+            .await
+            .expect_err("network should not be served before reload");
+        assert!(matches!(
+            err,
+            SubgraphReadError::NetworkNotFound(DEFAULT_CHAIN_ID)
+        ));
+
+        reader
+            .reload_networks(mocked_networks_with_blockscout().await)
+            .await
+            .expect("failed to reload networks");
+        assert_eq!(reader.network_ids(), vec![DEFAULT_CHAIN_ID]);
+        reader
+            .get_domain(input.clone())
+            .await
+            .expect("failed to get domain after reload")
+            .expect("domain not found after reload");
+
+        reader
+            .reload_networks(Default::default())
+            .await
+            .expect("failed to reload networks");
+        let err = reader
+            .get_domain(input)
+            .await
+            .expect_err("network should not be served after removal");
+        assert!(matches!(
+            err,
+            SubgraphReadError::NetworkNotFound(DEFAULT_CHAIN_ID)
+        ));
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn get_text_records_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        for (name, expected_records) in [
+            (
+                "sashaxyz.eth",
+                vec![("com.twitter", "sasha"), ("url", "https://sasha.xyz")],
+            ),
+            ("vitalik.eth", vec![]),
+            ("this-is-unknown-domain.eth", vec![]),
+        ] {
+            let records = reader
+                .get_text_records(GetTextRecordsInput {
+                    network_id: DEFAULT_CHAIN_ID,
+                    name: name.to_string(),
+                })
+                .await
+                .expect("failed to get text records");
+            assert_eq!(
+                records
+                    .iter()
+                    .map(|r| (r.key.as_str(), r.value.as_str()))
+                    .collect::<Vec<_>>(),
+                expected_records,
+                "invalid text records of {name}"
+            );
+
+            // same records are returned with detailed domain
+            let detailed_records = reader
+                .get_domain(GetDomainInput {
+                    network_id: DEFAULT_CHAIN_ID,
+                    name: name.to_string(),
+                    only_active: false,
+                    coin_types: vec![],
+                    include_resolved_address_history: false,
+                })
+                .await
+                .expect("failed to get domain")
+                .map(|output| output.domain.text_records.0)
+                .unwrap_or_default();
+            let expected_records: BTreeMap<String, String> = expected_records
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), value.to_string()))
+                .collect();
+            assert_eq!(
+                detailed_records, expected_records,
+                "invalid text records of detailed {name}"
+            );
+        }
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn get_network_stats_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        // mock domains are registered long ago, so make one of them fresh
+        sqlx::query(
+            "UPDATE sgd1.domain SET created_at = extract(epoch from now()) WHERE name = 'sashaxyz.eth'",
+        )
+        .execute(pool.as_ref())
+        .await
+        .unwrap();
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        let stats = reader
+            .get_network_stats(DEFAULT_CHAIN_ID)
+            .await
+            .expect("failed to get network stats");
+        assert_eq!(
+            stats,
+            NetworkStats {
+                total_domains: 25,
+                active_domains: 24,
+                registered_last_24h: 1,
+                registered_last_7d: 1,
+                unique_owners: 24,
+            }
+        );
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn quick_search_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        for (query, limit, expected_names) in [
+            // exact name goes first
+            ("test", 3, vec!["test.eth"]),
+            ("Vitalik.eth", 10, vec!["vitalik.eth"]),
+            ("b", 3, vec!["bandcamp.eth", "berkeley.eth", "biglobe.eth"]),
+            (
+                "bl",
+                10,
+                vec!["blogger.eth", "blogspot.eth", "bloomberg.eth"],
+            ),
+            // expired domains are skipped
+            ("expired", 10, vec![]),
+            // wildcards of LIKE are matched literally
+            ("%", 10, vec![]),
+            ("", 10, vec![]),
+            // primary name of address
+            (
+                "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+                10,
+                vec!["vitalik.eth"],
+            ),
+            ("0x0000000000000000000000000000000000000001", 10, vec![]),
+        ] {
+            let domains = reader
+                .quick_search(QuickSearchInput {
+                    network_id: DEFAULT_CHAIN_ID,
+                    query: query.to_string(),
+                    limit,
+                    rank: Default::default(),
+                })
+                .await
+                .expect("failed to quick search");
+            assert_eq!(
+                domains
+                    .iter()
+                    .map(|d| d.name.as_deref().unwrap_or_default())
+                    .collect::<Vec<_>>(),
+                expected_names,
+                "invalid result of '{query}'"
+            );
+        }
+
+        // recently registered domains go first
+        let domains = reader
+            .quick_search(QuickSearchInput {
+                network_id: DEFAULT_CHAIN_ID,
+                query: "bl".to_string(),
+                limit: 2,
+                rank: QuickSearchRank::RegistrationDate,
+            })
+            .await
+            .expect("failed to quick search");
+        assert_eq!(
+            domains
+                .iter()
+                .map(|d| d.name.as_deref().unwrap_or_default())
+                .collect::<Vec<_>>(),
+            vec!["bloomberg.eth", "blogspot.eth"]
+        );
     }
 
     #[sqlx::test(migrations = "tests/migrations")]
-    async fn lookup_domain_name_works(pool: PgPool) {
+    async fn ensure_search_indexes_works(pool: PgPool) {
         let pool = Arc::new(pool);
         let networks = mocked_networks_with_blockscout().await;
         let reader = SubgraphReader::initialize(pool.clone(), networks)
             .await
             .expect("failed to init reader");
+        let index_state = || sql::domain_name_search_index_state(pool.as_ref(), DEFAULT_SCHEMA);
 
-        let result = reader
-            .lookup_domain_name(LookupDomainInput {
-                network_id: DEFAULT_CHAIN_ID,
-                name: Some("vitalik.eth".to_string()),
-                only_active: false,
-                pagination: Default::default(),
-            })
-            .await
-            .expect("failed to get vitalik domains");
-        assert_eq!(result.next_page_token, None);
-        let result = result.items;
-        assert_eq!(
-            vec![Some("vitalik.eth")],
-            result.iter().map(|d| d.name.as_deref()).collect::<Vec<_>>(),
-        );
+        // index is not built on start
+        assert_eq!(index_state().await.unwrap(), sql::IndexState::Missing);
+        reader.ensure_search_indexes().await;
+        assert_eq!(index_state().await.unwrap(), sql::IndexState::Valid);
+
+        // index left by failed concurrent build is rebuilt
+        sqlx::query(&format!(
+            "UPDATE pg_index SET indisvalid = false \
+            WHERE indexrelid = '{DEFAULT_SCHEMA}.domain_name_search'::regclass"
+        ))
+        .execute(pool.as_ref())
+        .await
+        .unwrap();
+        assert_eq!(index_state().await.unwrap(), sql::IndexState::Invalid);
+        reader.ensure_search_indexes().await;
+        assert_eq!(index_state().await.unwrap(), sql::IndexState::Valid);
     }
 
     #[sqlx::test(migrations = "tests/migrations")]
-    async fn lookup_addresses_works(pool: PgPool) {
+    async fn get_domain_records_works(pool: PgPool) {
         let pool = Arc::new(pool);
         let networks = mocked_networks_with_blockscout().await;
         let reader = SubgraphReader::initialize(pool.clone(), networks)
             .await
             .expect("failed to init reader");
-
-        let result = reader
-            .lookup_address(LookupAddressInput {
+        let get_records = |name: &str| {
+            reader.get_domain_records(GetDomainRecordsInput {
                 network_id: DEFAULT_CHAIN_ID,
-                address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
-                resolved_to: true,
-                owned_by: false,
-                only_active: false,
-                pagination: Default::default(),
+                name: name.to_string(),
             })
+        };
+
+        let records = get_records("sashaxyz.eth")
             .await
-            .expect("failed to get vitalik domains");
-        assert_eq!(result.next_page_token, None);
-        let result = result.items;
+            .expect("failed to get records")
+            .expect("sashaxyz.eth exists");
         assert_eq!(
-            result.iter().map(|d| d.name.as_deref()).collect::<Vec<_>>(),
-            vec![Some("vitalik.eth"), Some("sashaxyz.eth")]
+            records
+                .addresses
+                .iter()
+                .map(|a| (
+                    a.coin_type.as_str(),
+                    a.coin_name.as_str(),
+                    a.address.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                ("0", "BTC", "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4"),
+                ("60", "ETH", "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+            ]
+        );
+        assert_eq!(
+            records.text_records,
+            vec![
+                TextRecord {
+                    key: "com.twitter".to_string(),
+                    value: "sasha".to_string(),
+                },
+                TextRecord {
+                    key: "url".to_string(),
+                    value: "https://sasha.xyz".to_string(),
+                },
+            ]
         );
+        assert!(records.content_hash.is_none());
 
-        let result = reader
-            .lookup_address(LookupAddressInput {
-                network_id: DEFAULT_CHAIN_ID,
-                address: addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
-                resolved_to: false,
-                owned_by: true,
-                only_active: false,
-                pagination: Default::default(),
-            })
+        let records = get_records("vitalik.eth")
             .await
-            .expect("failed to get vitalik domains");
-        assert_eq!(result.next_page_token, None);
-        let result = result.items;
+            .expect("failed to get records")
+            .expect("vitalik.eth exists");
         assert_eq!(
-            result.iter().map(|d| d.name.as_deref()).collect::<Vec<_>>(),
-            vec![Some("vitalik.eth")]
+            records
+                .addresses
+                .iter()
+                .map(|a| (a.coin_name.as_str(), a.address.as_str()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("ETH", "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+                ("RSK", "0xf0d485009714cE586358E3761754929904D76B9D"),
+            ]
+        );
+        assert!(records.text_records.is_empty());
+        assert_eq!(
+            records.content_hash.and_then(|c| c.decoded).as_deref(),
+            Some("ipfs://QmXGkXcfasLtjLk9i5brs2sMYNbk34c8FGdBDCP864mrhV")
         );
 
-        // search for expired address
-        let result = reader
-            .lookup_address(LookupAddressInput {
+        let records = get_records("this-is-unknown-domain.eth")
+            .await
+            .expect("failed to get records");
+        assert!(records.is_none());
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn get_subdomains_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        let subdomains = |name: &str, page_size: u32, page_token: Option<String>| {
+            reader.get_subdomains(GetSubdomainsInput {
                 network_id: DEFAULT_CHAIN_ID,
-                address: addr("0x9f7f7ddbfb8e14d1756580ba8037530da0880b99"),
-                resolved_to: true,
-                owned_by: true,
+                name: name.to_string(),
                 only_active: false,
-                pagination: Default::default(),
+                include_burned: false,
+                pagination: DomainPaginationInput {
+                    page_size,
+                    page_token,
+                    ..Default::default()
+                },
             })
+        };
+
+        let result = subdomains("vitalik.eth", 50, None)
             .await
-            .expect("failed to get expired domains");
+            .expect("failed to get subdomains");
+        assert_eq!(result.items, vec![]);
         assert_eq!(result.next_page_token, None);
-        let result = result.items;
-        // expired domain shoudn't be returned as resolved
-        assert_eq!(
-            result.iter().map(|d| d.name.as_deref()).collect::<Vec<_>>(),
-            vec![Some("expired.eth")]
-        );
-        // search for expired address with only_active
-        let result = reader
-            .lookup_address(LookupAddressInput {
-                network_id: DEFAULT_CHAIN_ID,
-                address: addr("0x9f7f7ddbfb8e14d1756580ba8037530da0880b99"),
-                resolved_to: true,
-                owned_by: true,
-                only_active: true,
-                pagination: Default::default(),
-            })
+        assert_eq!(result.total_records, 0);
+
+        let first_page = subdomains("eth", 2, None)
             .await
-            .expect("failed to get expired domains");
-        assert_eq!(result.next_page_token, None);
-        let result = result.items;
-        // expired domain shoudn't be returned as resolved
-        assert_eq!(
-            result.iter().map(|d| d.name.as_deref()).collect::<Vec<_>>(),
-            vec![]
-        );
+            .expect("failed to get subdomains");
+        assert_eq!(first_page.items.len(), 2);
+        assert!(first_page.items.iter().all(|d| d
+            .name
+            .as_deref()
+            .unwrap_or_default()
+            .ends_with(".eth")));
+        let next_page_token = first_page.next_page_token.expect("no next page");
+        let second_page = subdomains("eth", 2, Some(next_page_token))
+            .await
+            .expect("failed to get subdomains");
+        assert_eq!(second_page.items.len(), 2);
+        assert_ne!(first_page.items, second_page.items);
+        assert_eq!(first_page.page_size, 2);
+        assert!(first_page.total_records > 4);
+        assert_eq!(first_page.total_records, second_page.total_records);
     }
 
     #[sqlx::test(migrations = "tests/migrations")]
@@ -800,7 +3117,392 @@ mod tests {
             })
             .await
             .expect("failed to resolve addresess");
-        assert_eq!(domains, expected_domains);
+        assert_eq!(domains.names, expected_domains);
+        assert_eq!(domains.other_names, BTreeMap::new());
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn get_address_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        let result = reader
+            .get_address(
+                DEFAULT_CHAIN_ID,
+                addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045"),
+            )
+            .await
+            .expect("failed to get address")
+            .expect("primary name of vitalik not found");
+        assert_eq!(result.domain.name.as_deref(), Some("vitalik.eth"));
+        assert_eq!(
+            result.domain.resolved_address.as_deref(),
+            Some("0xd8da6bf26964af9d7eed9e03e53415d37aa96045")
+        );
+
+        for address in [
+            // reverse record is `this-is-not-test.eth`, which doesn't resolve back to the address
+            "0xeefb13c7d42efcc655e528da6d6f7bbcf9a2251d",
+            // was address of test.eth until 13294741 block
+            "0x226159d592e2b063810a10ebf6dcbada94ed68b8",
+            "0x0000000000000000000000000000000000000000",
+        ] {
+            let result = reader
+                .get_address(DEFAULT_CHAIN_ID, addr(address))
+                .await
+                .expect("failed to get address");
+            assert!(
+                result.is_none(),
+                "unverified name returned for {address}: {result:?}"
+            );
+        }
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn protocol_priority_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        // second protocol of mainnet contains single domain with other name of vitalik
+        for sql in [
+            "CREATE SCHEMA sgd3",
+            "CREATE TABLE sgd3.domain AS SELECT * FROM sgd1.domain WHERE name = 'vitalik.eth'",
+            "UPDATE sgd3.domain SET name = 'vitalik.other', label_name = 'vitalik'",
+        ] {
+            sqlx::query(sql).execute(pool.as_ref()).await.unwrap();
+        }
+        let addresses: Vec<Address> = [
+            "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+            "0x9c996076a85b46061d9a70ff81f013853a86b619",
+        ]
+        .into_iter()
+        .map(addr)
+        .collect();
+
+        for (protocol_priority, expected_names, expected_other_names) in [
+            (
+                vec!["yet-another-ens-subgraph", "ens-subgraph"],
+                serde_json::json!({
+                    "0x9c996076a85b46061d9a70ff81f013853a86b619": "wa🇬🇲i.eth",
+                    "0xd8da6bf26964af9d7eed9e03e53415d37aa96045": "vitalik.other",
+                }),
+                serde_json::json!({
+                    "0xd8da6bf26964af9d7eed9e03e53415d37aa96045": ["vitalik.eth"],
+                }),
+            ),
+            (
+                vec!["ens-subgraph", "yet-another-ens-subgraph"],
+                serde_json::json!({
+                    "0x9c996076a85b46061d9a70ff81f013853a86b619": "wa🇬🇲i.eth",
+                    "0xd8da6bf26964af9d7eed9e03e53415d37aa96045": "vitalik.eth",
+                }),
+                serde_json::json!({
+                    "0xd8da6bf26964af9d7eed9e03e53415d37aa96045": ["vitalik.other"],
+                }),
+            ),
+        ] {
+            let mut networks = mocked_networks_with_blockscout().await;
+            let network = networks.get_mut(&DEFAULT_CHAIN_ID).unwrap();
+            network.protocol_priority = protocol_priority.into_iter().map(String::from).collect();
+            network.subgraph_configs.insert(
+                "yet-another-ens-subgraph".to_string(),
+                SubgraphSettings {
+                    address_resolve_technique: AddressResolveTechnique::AllDomains,
+                    ..Default::default()
+                },
+            );
+            let reader = SubgraphReader::initialize(pool.clone(), networks)
+                .await
+                .expect("failed to init reader");
+            let output = reader
+                .batch_resolve_address_names(BatchResolveAddressNamesInput {
+                    network_id: DEFAULT_CHAIN_ID,
+                    addresses: addresses.clone(),
+                })
+                .await
+                .expect("failed to resolve addresess");
+            assert_eq!(
+                output,
+                BatchResolveAddressNamesOutput {
+                    names: serde_json::from_value(expected_names).unwrap(),
+                    other_names: serde_json::from_value(expected_other_names).unwrap(),
+                }
+            );
+        }
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn merged_protocols_work(pool: PgPool) {
+        let pool = Arc::new(pool);
+        // second protocol of mainnet contains copies of vitalik.eth registered at the same time
+        // and of sashaxyz.eth registered one second later
+        for sql in [
+            "CREATE SCHEMA sgd3",
+            "CREATE TABLE sgd3.domain AS SELECT * FROM sgd1.domain
+            WHERE name IN ('vitalik.eth', 'sashaxyz.eth')",
+            "UPDATE sgd3.domain SET name = replace(name, '.eth', '.other')",
+            "UPDATE sgd3.domain SET created_at = created_at + 1 WHERE name = 'sashaxyz.other'",
+        ] {
+            sqlx::query(sql).execute(pool.as_ref()).await.unwrap();
+        }
+        let mut networks = mocked_networks_with_blockscout().await;
+        networks
+            .get_mut(&DEFAULT_CHAIN_ID)
+            .unwrap()
+            .merged_protocols = vec![
+            "ens-subgraph".to_string(),
+            "yet-another-ens-subgraph".to_string(),
+        ];
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        let lookup = |order, page_size, page_token| {
+            reader.lookup_domain_name(LookupDomainInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: None,
+                only_active: false,
+                include_burned: false,
+                pagination: DomainPaginationInput {
+                    order,
+                    page_size,
+                    page_token,
+                    ..Default::default()
+                },
+            })
+        };
+        for (order, expected_neighbours) in [
+            (
+                Order::Asc,
+                [
+                    ["vitalik.eth", "vitalik.other"],
+                    ["sashaxyz.eth", "sashaxyz.other"],
+                ],
+            ),
+            (
+                Order::Desc,
+                [
+                    ["vitalik.eth", "vitalik.other"],
+                    ["sashaxyz.other", "sashaxyz.eth"],
+                ],
+            ),
+        ] {
+            let all = lookup(order, 100, None)
+                .await
+                .expect("failed to lookup domains");
+            assert_eq!(all.total_records, 27);
+            assert_eq!(all.next_page_token, None);
+            let all: Vec<String> = all.items.into_iter().filter_map(|d| d.name).collect();
+            for neighbours in expected_neighbours {
+                assert!(
+                    all.windows(2).any(|w| w == neighbours),
+                    "{neighbours:?} are not neighbours in {order} order"
+                );
+            }
+
+            // pages of two items split tie of vitalik domains between pages
+            let mut paged = vec![];
+            let mut page_token = None;
+            loop {
+                let page = lookup(order, 2, page_token)
+                    .await
+                    .expect("failed to lookup domains");
+                assert_eq!(page.total_records, 27);
+                paged.extend(page.items.into_iter().filter_map(|d| d.name));
+                page_token = page.next_page_token;
+                if page_token.is_none() {
+                    break;
+                }
+            }
+            assert_eq!(paged, all);
+        }
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn second_tld_subgraph_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        // second protocol of mainnet serves `.bnb` names and contains vitalik.bnb only
+        for sql in [
+            "CREATE SCHEMA sgd3",
+            "CREATE TABLE sgd3.domain AS SELECT * FROM sgd1.domain WHERE name = 'vitalik.eth'",
+            "CREATE TABLE sgd3.multicoin_addr_changed (LIKE sgd1.multicoin_addr_changed)",
+            "CREATE TABLE sgd3.contenthash_changed (LIKE sgd1.contenthash_changed)",
+            "CREATE TABLE sgd3.text_changed (LIKE sgd1.text_changed)",
+        ] {
+            sqlx::query(sql).execute(pool.as_ref()).await.unwrap();
+        }
+        let bnb_id = domain_id("vitalik.bnb", None);
+        sqlx::query("UPDATE sgd3.domain SET name = 'vitalik.bnb', id = $1")
+            .bind(&bnb_id)
+            .execute(pool.as_ref())
+            .await
+            .unwrap();
+        let mut networks = mocked_networks_with_blockscout().await;
+        networks
+            .get_mut(&DEFAULT_CHAIN_ID)
+            .unwrap()
+            .subgraph_configs
+            .insert(
+                "yet-another-ens-subgraph".to_string(),
+                SubgraphSettings {
+                    tld_rules: TldRules {
+                        allowed_tlds: vec!["bnb".to_string()],
+                        default_tld: None,
+                        native_tlds: vec![],
+                    },
+                    ..Default::default()
+                },
+            );
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        for name in ["vitalik.bnb", "vitalik.eth"] {
+            let domain = reader
+                .get_domain(GetDomainInput {
+                    network_id: DEFAULT_CHAIN_ID,
+                    name: name.to_string(),
+                    only_active: false,
+                    coin_types: vec![],
+                    include_resolved_address_history: false,
+                })
+                .await
+                .expect("failed to get domain")
+                .unwrap_or_else(|| panic!("{name} not found"))
+                .domain;
+            assert_eq!(domain.name.as_deref(), Some(name));
+        }
+
+        let by_hash = reader
+            .get_domain_by_hash(GetDomainByHashInput {
+                network_id: DEFAULT_CHAIN_ID,
+                hash: H256::from_str(&bnb_id).unwrap(),
+                only_active: false,
+            })
+            .await
+            .expect("failed to get domain by hash")
+            .expect("vitalik.bnb not found by hash");
+        assert_eq!(by_hash.domain.name.as_deref(), Some("vitalik.bnb"));
+
+        let found: Vec<String> = reader
+            .quick_search(QuickSearchInput {
+                network_id: DEFAULT_CHAIN_ID,
+                query: "vitalik.bnb".to_string(),
+                limit: 10,
+                rank: Default::default(),
+            })
+            .await
+            .expect("failed to quick search")
+            .into_iter()
+            .filter_map(|d| d.name)
+            .collect();
+        assert_eq!(found, vec!["vitalik.bnb"]);
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn l2_reverse_registrar_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let vitalik = addr("0xd8da6bf26964af9d7eed9e03e53415d37aa96045");
+        let wagmi = addr("0x9c996076a85b46061d9a70ff81f013853a86b619");
+        // copy mainnet reverse record of vitalik to reverse registrar of base
+        let base_registrar = ReverseRegistrar::from_chain_id(8453);
+        let mainnet_node = DomainName::addr_reverse(&vitalik);
+        let base_node = base_registrar.reverse_node(&vitalik);
+        for sql in [
+            format!(
+                "CREATE TABLE l2_domain AS SELECT * FROM sgd1.domain WHERE id = '{}'",
+                mainnet_node.id
+            ),
+            "CREATE TABLE l2_name_changed AS SELECT * FROM sgd1.name_changed
+            WHERE resolver = (SELECT resolver FROM l2_domain)"
+                .to_string(),
+            format!(
+                "UPDATE l2_domain SET vid = vid + 1000000, id = '{}', name = '{}', parent = '{}', resolver = 'l2-resolver'",
+                base_node.id,
+                base_node.name,
+                base_registrar.parent_id(),
+            ),
+            "UPDATE l2_name_changed SET vid = vid + 1000000, id = id || '-l2', resolver = 'l2-resolver'"
+                .to_string(),
+            "INSERT INTO sgd1.domain SELECT * FROM l2_domain".to_string(),
+            "INSERT INTO sgd1.name_changed SELECT * FROM l2_name_changed".to_string(),
+        ] {
+            sqlx::query(&sql).execute(pool.as_ref()).await.unwrap();
+        }
+
+        for use_cache in [false, true] {
+            let mut networks = mocked_networks_with_blockscout().await;
+            let network = networks.get_mut(&DEFAULT_CHAIN_ID).unwrap();
+            network.subgraph_configs.insert(
+                "ens-subgraph".to_string(),
+                SubgraphSettings {
+                    use_cache,
+                    reverse_registrar: base_registrar.clone(),
+                    ..Default::default()
+                },
+            );
+            let reader = SubgraphReader::initialize(pool.clone(), networks)
+                .await
+                .expect("failed to init reader");
+            // only reverse records of base registrar are used, so wagmi has no name
+            let output = reader
+                .batch_resolve_address_names(BatchResolveAddressNamesInput {
+                    network_id: DEFAULT_CHAIN_ID,
+                    addresses: vec![vitalik, wagmi],
+                })
+                .await
+                .expect("failed to resolve addresess");
+            assert_eq!(
+                output.names,
+                serde_json::from_value::<BTreeMap<String, String>>(serde_json::json!({
+                    "0xd8da6bf26964af9d7eed9e03e53415d37aa96045": "vitalik.eth",
+                }))
+                .unwrap(),
+                "invalid names with use_cache = {use_cache}"
+            );
+        }
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn batch_resolve_domain_names_works(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+
+        let names = [
+            "vitalik.eth",
+            // duplicate
+            "vitalik.eth",
+            // another spelling of the same name
+            "Vitalik.ETH",
+            "sashaxyz.eth",
+            // expired domain
+            "expired.eth",
+            // not in database
+            "this-name-does-not-exist.eth",
+            // invalid name
+            "",
+        ]
+        .into_iter()
+        .map(String::from)
+        .collect();
+        let expected_addresses = serde_json::from_value(serde_json::json!({
+            "vitalik.eth": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+            "Vitalik.ETH": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+            "sashaxyz.eth": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+        }))
+        .unwrap();
+        let addresses = reader
+            .batch_resolve_domain_names(BatchResolveDomainNamesInput {
+                network_id: DEFAULT_CHAIN_ID,
+                names,
+            })
+            .await
+            .expect("failed to resolve names");
+        assert_eq!(addresses, expected_addresses);
     }
 
     #[sqlx::test(migrations = "tests/migrations")]
@@ -816,13 +3518,17 @@ mod tests {
         // Make sure that database contains unresolved domain
         let domain = sql::get_domain(
             pool.as_ref(),
+            &sql::QueryOptions::default(),
             &DomainName::new(unresolved, None).expect("unresolved name is valid"),
             DEFAULT_SCHEMA,
             &GetDomainInput {
                 network_id: DEFAULT_CHAIN_ID,
                 name: unresolved.to_string(),
                 only_active: false,
+                coin_types: vec![],
+                include_resolved_address_history: false,
             },
+            sql::SqlQuirks::default(),
         )
         .await
         .expect("failed to get domain")
@@ -839,6 +3545,8 @@ mod tests {
                 network_id: DEFAULT_CHAIN_ID,
                 name: unresolved.to_string(),
                 only_active: false,
+                coin_types: vec![],
+                include_resolved_address_history: false,
             })
             .await
             .expect("failed to get domain")
@@ -851,13 +3559,17 @@ mod tests {
         tokio::time::sleep(std::time::Duration::from_millis(500)).await;
         let domain = sql::get_domain(
             pool.as_ref(),
+            &sql::QueryOptions::default(),
             &DomainName::new(unresolved, None).expect("unresolved name is valid"),
             DEFAULT_SCHEMA,
             &GetDomainInput {
                 network_id: DEFAULT_CHAIN_ID,
                 name: unresolved.to_string(),
                 only_active: false,
+                coin_types: vec![],
+                include_resolved_address_history: false,
             },
+            sql::SqlQuirks::default(),
         )
         .await
         .expect("failed to get domain")
@@ -866,21 +3578,142 @@ mod tests {
         assert_eq!(domain.label_name.as_deref(), Some(unresolved_label));
     }
 
-    fn addr(a: &str) -> Address {
-        let a = a.trim_start_matches("0x");
-        Address::from_slice(
-            hex::decode(a)
-                .expect("invalid hex provided in addr()")
-                .as_slice(),
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn read_replica_works(pool: PgPool) {
+        let unresolved = "you-dont-know-this-label.eth";
+        // read-only connection to the same database emulates replica
+        let replica = PgPool::connect_with(
+            pool.connect_options()
+                .as_ref()
+                .clone()
+                .options([("default_transaction_read_only", "on")]),
         )
+        .await
+        .expect("failed to connect replica");
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader")
+            .with_read_replica(Arc::new(replica));
+
+        // reading goes to replica
+        let result = reader
+            .get_domain(GetDomainInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: unresolved.to_string(),
+                only_active: false,
+                coin_types: vec![],
+                include_resolved_address_history: false,
+            })
+            .await
+            .expect("failed to get domain")
+            .expect("unresolved domain not found using reader");
+        assert_eq!(result.domain.name.as_deref(), Some(unresolved));
+
+        // fixing of domain name goes to primary in background
+        let primary = pool.as_ref();
+        let domain_name = &DomainName::new(unresolved, None).expect("unresolved name is valid");
+        let fixed = poll_until(|| async move {
+            sql::get_domain(
+                primary,
+                &sql::QueryOptions::default(),
+                domain_name,
+                DEFAULT_SCHEMA,
+                &GetDomainInput {
+                    network_id: DEFAULT_CHAIN_ID,
+                    name: unresolved.to_string(),
+                    only_active: false,
+                    coin_types: vec![],
+                    include_resolved_address_history: false,
+                },
+                sql::SqlQuirks::default(),
+            )
+            .await
+            .expect("failed to get domain")
+            .filter(|domain| domain.name.as_deref() == Some(unresolved))
+        })
+        .await;
+        assert!(fixed.is_some(), "domain name was not fixed on primary");
     }
 
-    fn tx_hash(h: &str) -> TxHash {
-        let h = h.trim_start_matches("0x");
-        TxHash::from_slice(
-            hex::decode(h)
-                .expect("invalid hex provided in tx_hash()")
-                .as_slice(),
-        )
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn statement_timeouts_work(pool: PgPool) {
+        let pool = Arc::new(pool);
+        let networks = mocked_networks_with_blockscout().await;
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader")
+            .with_statement_timeouts(sql::StatementTimeouts {
+                interactive: std::time::Duration::from_millis(100),
+                batch: std::time::Duration::from_secs(10),
+            });
+
+        // queries wait for lock, which is held longer than interactive budget
+        let mut lock = pool.begin().await.unwrap();
+        sqlx::query("LOCK TABLE sgd1.domain IN ACCESS EXCLUSIVE MODE")
+            .execute(&mut *lock)
+            .await
+            .unwrap();
+        let err = reader
+            .get_domain(GetDomainInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: "vitalik.eth".to_string(),
+                only_active: false,
+                coin_types: vec![],
+                include_resolved_address_history: false,
+            })
+            .await
+            .expect_err("lookup should time out");
+        assert!(matches!(err, SubgraphReadError::Timeout(_)), "{err:?}");
+
+        let batch = reader.batch_resolve_domain_names(BatchResolveDomainNamesInput {
+            network_id: DEFAULT_CHAIN_ID,
+            names: vec!["vitalik.eth".to_string()],
+        });
+        // lock is released only after batch query waits longer than interactive budget
+        let release = async {
+            let waited = poll_until(|| async {
+                sqlx::query_scalar::<_, bool>(
+                    r#"
+                    SELECT true FROM pg_stat_activity
+                    WHERE wait_event_type = 'Lock'
+                        AND now() - query_start > interval '300 milliseconds'
+                    "#,
+                )
+                .fetch_optional(pool.as_ref())
+                .await
+                .unwrap()
+            })
+            .await;
+            assert!(waited.is_some(), "batch query doesn't wait for lock");
+            lock.rollback().await.unwrap();
+        };
+        let (addresses, _) = tokio::join!(batch, release);
+        assert_eq!(
+            addresses.expect("batch should fit its budget"),
+            BTreeMap::from([(
+                "vitalik.eth".to_string(),
+                "0xd8da6bf26964af9d7eed9e03e53415d37aa96045".to_string()
+            )])
+        );
+    }
+
+    /// Polls `check` until it returns value or deadline passes
+    async fn poll_until<T, F, Fut>(mut check: F) -> Option<T>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Option<T>>,
+    {
+        let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(5);
+        loop {
+            if let Some(value) = check().await {
+                return Some(value);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return None;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+        }
     }
 }
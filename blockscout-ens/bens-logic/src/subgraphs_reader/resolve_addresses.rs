@@ -2,49 +2,148 @@ use crate::{
     entity::subgraph::domain::{Domain, DomainWithAddress},
     hash_name::hex,
     subgraphs_reader::{
-        domain_name::DomainName, reader::Subgraph, sql, AddressResolveTechnique, SubgraphReadError,
+        batch_search_cache::BatchSearchCache, domain_name::DomainName, reader::Subgraph,
+        reverse_records_cache::ReverseRecordsCache, sql, sql::QueryOptions,
+        AddressResolveTechnique, DomainStateFilter, SubgraphReadError,
     },
 };
 use ethers::types::Address;
 use sqlx::PgPool;
-use std::{collections::HashMap, str::FromStr};
+use std::{
+    collections::{btree_map::Entry, BTreeMap, HashMap},
+    str::FromStr,
+};
+
+/// Names of address found in subgraphs of different protocols
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrioritizedNames {
+    /// Index of subgraph of primary name in list of prioritized subgraphs
+    pub subgraph_index: usize,
+    pub primary: DomainWithAddress,
+    /// Names found in subgraphs with lower priority, ordered by priority
+    pub other_names: Vec<String>,
+}
+
+/// Resolves addresses in every subgraph. Name from subgraph with the highest priority
+/// becomes primary name of address, names from other subgraphs are kept in `other_names`
+pub async fn resolve_addresses_by_priority(
+    pool: &PgPool,
+    options: &QueryOptions,
+    subgraphs: &[Subgraph],
+    addresses: Vec<Address>,
+    batch_search_cache: Option<&BatchSearchCache>,
+    reverse_records_cache: Option<&ReverseRecordsCache>,
+) -> Result<BTreeMap<String, PrioritizedNames>, SubgraphReadError> {
+    let results = futures::future::try_join_all(subgraphs.iter().map(|subgraph| {
+        resolve_addresses(
+            pool,
+            options,
+            subgraph,
+            addresses.clone(),
+            batch_search_cache,
+            reverse_records_cache,
+        )
+    }))
+    .await?;
+    let mut names: BTreeMap<String, PrioritizedNames> = BTreeMap::new();
+    for (subgraph_index, domains) in results.into_iter().enumerate() {
+        for domain in domains {
+            match names.entry(domain.resolved_address.clone()) {
+                Entry::Vacant(entry) => {
+                    entry.insert(PrioritizedNames {
+                        subgraph_index,
+                        primary: domain,
+                        other_names: vec![],
+                    });
+                }
+                Entry::Occupied(mut entry) => {
+                    let names = entry.get_mut();
+                    if names.primary.domain_name != domain.domain_name
+                        && !names.other_names.contains(&domain.domain_name)
+                    {
+                        names.other_names.push(domain.domain_name);
+                    }
+                }
+            }
+        }
+    }
+    Ok(names)
+}
 
 pub async fn resolve_addresses(
     pool: &PgPool,
+    options: &QueryOptions,
     subgraph: &Subgraph,
     addresses: Vec<Address>,
+    batch_search_cache: Option<&BatchSearchCache>,
+    reverse_records_cache: Option<&ReverseRecordsCache>,
 ) -> Result<Vec<DomainWithAddress>, SubgraphReadError> {
     let addresses_str: Vec<String> = addresses.iter().map(hex).collect();
-    match subgraph.settings.address_resolve_technique {
+    let tld_rules = subgraph.tld_rules();
+    let mut domains = match subgraph.address_resolve_technique() {
         AddressResolveTechnique::AllDomains => match subgraph.settings.use_cache {
             true => {
                 sql::AddressNamesView::batch_search_addresses(
                     pool,
+                    options,
                     &subgraph.schema_name,
                     &addresses_str,
                 )
                 .await
             }
-            false => sql::batch_search_addresses(pool, &subgraph.schema_name, &addresses_str).await,
+            false => match batch_search_cache {
+                Some(cache) => {
+                    cache
+                        .batch_search_addresses(
+                            pool,
+                            options,
+                            &subgraph.schema_name,
+                            &addresses_str,
+                            tld_rules,
+                            subgraph.sql_quirks(),
+                        )
+                        .await
+                }
+                None => {
+                    sql::batch_search_addresses(
+                        pool,
+                        options,
+                        &subgraph.schema_name,
+                        &addresses_str,
+                        tld_rules,
+                        subgraph.sql_quirks(),
+                    )
+                    .await
+                }
+            },
         },
         AddressResolveTechnique::ReverseRegistry => match subgraph.settings.use_cache {
-            true => resolve_addr_reverse_cached(pool, subgraph, addresses).await,
-            false => resolve_addr_reverse(pool, subgraph, addresses).await,
+            true => resolve_addr_reverse_cached(pool, options, subgraph, addresses).await,
+            false => {
+                resolve_addr_reverse(pool, options, subgraph, addresses, reverse_records_cache)
+                    .await
+            }
         },
-    }
+    }?;
+    // cache views and reverse records are not filtered by tld in sql
+    domains.retain(|domain| tld_rules.is_allowed(&domain.domain_name));
+    Ok(domains)
 }
 
 async fn resolve_addr_reverse_cached(
     pool: &PgPool,
+    options: &QueryOptions,
     subgraph: &Subgraph,
     addresses: Vec<Address>,
 ) -> Result<Vec<DomainWithAddress>, SubgraphReadError> {
+    let reverse_registrar = subgraph.reverse_registrar();
     let addr_reverse_hashes = addresses
         .iter()
-        .map(|addr| DomainName::addr_reverse(addr).id)
+        .map(|addr| reverse_registrar.reverse_node(addr).id)
         .collect::<Vec<String>>();
     let addr_reverse_domains = sql::AddrReverseNamesView::batch_search_addresses(
         pool,
+        options,
         &subgraph.schema_name,
         &addr_reverse_hashes,
     )
@@ -54,7 +153,7 @@ async fn resolve_addr_reverse_cached(
         .into_iter()
         .filter_map(|row| {
             let addr = Address::from_str(&row.resolved_address).ok()?;
-            let addr_reverse_id = DomainName::addr_reverse(&addr).id;
+            let addr_reverse_id = reverse_registrar.reverse_node(&addr).id;
             if addr_reverse_id == row.reversed_domain_id {
                 Some(DomainWithAddress {
                     id: row.domain_id,
@@ -72,42 +171,70 @@ async fn resolve_addr_reverse_cached(
 
 async fn resolve_addr_reverse(
     pool: &PgPool,
+    options: &QueryOptions,
     subgraph: &Subgraph,
     addresses: Vec<Address>,
+    reverse_records_cache: Option<&ReverseRecordsCache>,
 ) -> Result<Vec<DomainWithAddress>, SubgraphReadError> {
+    let reverse_registrar = subgraph.reverse_registrar();
     let addr_reverse_hashes = addresses
         .iter()
-        .map(|addr| DomainName::addr_reverse(addr).id)
+        .map(|addr| reverse_registrar.reverse_node(addr).id)
         .collect::<Vec<String>>();
 
+    let reverse_records = match reverse_records_cache {
+        Some(cache) => {
+            cache
+                .batch_search_addr_reverse_names(
+                    pool,
+                    options,
+                    &subgraph.schema_name,
+                    &addr_reverse_hashes,
+                )
+                .await?
+        }
+        None => {
+            sql::batch_search_addr_reverse_names(
+                pool,
+                options,
+                &subgraph.schema_name,
+                &addr_reverse_hashes,
+            )
+            .await?
+        }
+    };
     // mapping of
-    // hash(`{addr}.addr.reverse`) -> domain name
-    let reversed_names: HashMap<String, DomainName> =
-        sql::batch_search_addr_reverse_names(
-            pool,
-            &subgraph.schema_name,
-            &addr_reverse_hashes,
-        )
-        .await?
+    // hash(`{addr}.{node_suffix}`) -> domain name
+    let reversed_names: HashMap<String, DomainName> = reverse_records
         .into_iter()
-        .filter_map(|reverse_record| {
-            match DomainName::new(&reverse_record.reversed_name, subgraph.settings.empty_label_hash.to_owned()) {
-                Ok(name ) => Some((reverse_record.addr_reverse_id, name)),
+        .filter_map(
+            |reverse_record| match subgraph.parse_name(&reverse_record.reversed_name) {
+                Ok(name) => Some((reverse_record.addr_reverse_id, name)),
                 Err(err) => {
-                    tracing::warn!(err =? err, "failed to hash reversed name '{}', skip", reverse_record.reversed_name);
+                    tracing::warn!(
+                        err =? err,
+                        "failed to hash reversed name '{}', skip",
+                        reverse_record.reversed_name
+                    );
                     None
                 }
-            }
-        })
+            },
+        )
         .collect();
 
     // mapping of
-    // hash(name(`{addr}.addr.reverse`)) -> Domain of name(`{addr}.addr.reverse`)
+    // hash(name(`{addr}.{node_suffix}`)) -> Domain of name(`{addr}.{node_suffix}`)
     let reversed_domains: HashMap<String, Domain> = sql::find_domains(
         pool,
+        options,
         &subgraph.schema_name,
         Some(reversed_names.values().collect()),
-        true,
+        DomainStateFilter {
+            only_active: true,
+            include_burned: true,
+        },
+        subgraph.sql_quirks(),
+        subgraph.tld_rules(),
         None,
     )
     .await?
@@ -118,7 +245,7 @@ async fn resolve_addr_reverse(
     let domains = addresses
         .into_iter()
         .filter_map(|addr| {
-            let addr_reverse = DomainName::addr_reverse(&addr);
+            let addr_reverse = reverse_registrar.reverse_node(&addr);
             let reversed_name = reversed_names.get(&addr_reverse.id)?;
             let reversed_domain = reversed_domains.get(&reversed_name.id)?;
             if let Some(resolved_address) = &reversed_domain.resolved_address {
@@ -0,0 +1,663 @@
+use super::{
+    domain_name::DomainName,
+    offchain::{decode_address, request_gateway, resolve_calldata},
+    sql, GetDomainOutput, Subgraph, SubgraphReadError, SubgraphReader,
+};
+use crate::entity::subgraph::domain::DetailedDomain;
+use ethers::{
+    abi::{self, ParamType, Token},
+    types::{Address, Bytes},
+    utils::id,
+};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::{policies::ExponentialBackoff, RetryTransientMiddleware};
+use serde::Deserialize;
+use std::{
+    collections::HashSet,
+    net::{IpAddr, Ipv4Addr},
+    str::FromStr,
+};
+use tracing::instrument;
+
+/// Number of `OffchainLookup` reverts followed for one call, as recommended by EIP-3668
+const MAX_OFFCHAIN_LOOKUPS: usize = 4;
+
+/// Client of json rpc node, used to call resolver contracts directly.
+/// Names missing in subgraph are resolved by wildcard resolver (ENSIP-10) of their ancestor.
+/// If resolver reverts with `OffchainLookup`, name is resolved by CCIP-read (EIP-3668)
+/// and gateway response is verified by callback of resolver.
+/// Gateway urls come from resolver contracts, so only https gateways on public hosts
+/// are requested, unless gateway hosts are allowed explicitly
+#[derive(Debug, Clone)]
+pub struct ResolverRpcClient {
+    url: String,
+    inner: ClientWithMiddleware,
+    /// If set, only gateways on these hosts are requested, regardless of scheme and address
+    allowed_gateway_hosts: Option<HashSet<String>>,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ResolverRpcError {
+    #[error("rpc request failed: {0}")]
+    Request(#[from] reqwest_middleware::Error),
+    #[error("rpc error: {0}")]
+    Rpc(String),
+    #[error("failed to decode call result: {0}")]
+    Decode(#[from] abi::Error),
+    #[error("offchain lookup has unexpected sender {0:#x}")]
+    InvalidSender(Address),
+    #[error("no gateway of offchain lookup responded: {0}")]
+    Gateway(String),
+    #[error("gateway {0} is not allowed: {1}")]
+    ForbiddenGateway(String, &'static str),
+    #[error("too many offchain lookups")]
+    TooManyLookups,
+}
+
+impl From<reqwest::Error> for ResolverRpcError {
+    fn from(err: reqwest::Error) -> Self {
+        Self::Request(err.into())
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RpcResponse {
+    result: Option<Bytes>,
+    error: Option<RpcErrorObject>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct RpcErrorObject {
+    message: String,
+    /// Revert data of failed call
+    #[serde(default)]
+    data: Option<serde_json::Value>,
+}
+
+enum CallResult {
+    Success(Bytes),
+    Revert(Bytes),
+}
+
+/// `OffchainLookup(address sender, string[] urls, bytes callData, bytes4 callbackFunction, bytes extraData)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct OffchainLookup {
+    sender: Address,
+    urls: Vec<String>,
+    call_data: Bytes,
+    callback_function: [u8; 4],
+    extra_data: Bytes,
+}
+
+impl OffchainLookup {
+    const SIGNATURE: &'static str = "OffchainLookup(address,string[],bytes,bytes4,bytes)";
+
+    /// None if revert data is not `OffchainLookup` error
+    fn decode(revert: &[u8]) -> Result<Option<Self>, abi::Error> {
+        let Some(args) = revert.strip_prefix(id(Self::SIGNATURE).as_slice()) else {
+            return Ok(None);
+        };
+        let tokens = abi::decode(
+            &[
+                ParamType::Address,
+                ParamType::Array(Box::new(ParamType::String)),
+                ParamType::Bytes,
+                ParamType::FixedBytes(4),
+                ParamType::Bytes,
+            ],
+            args,
+        )?;
+        let [sender, urls, call_data, callback_function, extra_data]: [Token; 5] =
+            tokens.try_into().map_err(|_| abi::Error::InvalidData)?;
+        let invalid = || abi::Error::InvalidData;
+        Ok(Some(Self {
+            sender: sender.into_address().ok_or_else(invalid)?,
+            urls: urls
+                .into_array()
+                .ok_or_else(invalid)?
+                .into_iter()
+                .filter_map(Token::into_string)
+                .collect(),
+            call_data: call_data.into_bytes().ok_or_else(invalid)?.into(),
+            callback_function: callback_function
+                .into_fixed_bytes()
+                .and_then(|selector| selector.try_into().ok())
+                .ok_or_else(invalid)?,
+            extra_data: extra_data.into_bytes().ok_or_else(invalid)?.into(),
+        }))
+    }
+
+    /// Calldata of callback, which verifies gateway response and returns result of original call
+    fn callback_calldata(&self, response: &[u8]) -> Bytes {
+        let args = abi::encode(&[
+            Token::Bytes(response.to_vec()),
+            Token::Bytes(self.extra_data.to_vec()),
+        ]);
+        [self.callback_function.as_slice(), &args].concat().into()
+    }
+}
+
+impl ResolverRpcClient {
+    pub fn new(url: String, timeout_seconds: u64) -> Self {
+        let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
+        let client = ClientBuilder::new(
+            reqwest::Client::builder()
+                .timeout(std::time::Duration::from_secs(timeout_seconds))
+                // redirect of gateway could lead to host which is not allowed
+                .redirect(reqwest::redirect::Policy::none())
+                .build()
+                .expect("valid client"),
+        )
+        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
+        .build();
+        Self {
+            url,
+            inner: client,
+            allowed_gateway_hosts: None,
+        }
+    }
+
+    /// Requests only gateways on `hosts` instead of any public https gateway
+    pub fn with_allowed_gateway_hosts(mut self, hosts: impl IntoIterator<Item = String>) -> Self {
+        self.allowed_gateway_hosts = Some(hosts.into_iter().collect());
+        self
+    }
+
+    /// Address resolved by `resolver` for domain or None if resolver doesn't know the name
+    #[instrument(
+        name = "resolver_rpc:resolve_address",
+        skip_all,
+        fields(resolver = ?resolver, name = domain_name.name),
+        err,
+        level = "debug"
+    )]
+    pub async fn resolve_address(
+        &self,
+        resolver: Address,
+        domain_name: &DomainName,
+    ) -> Result<Option<Address>, ResolverRpcError> {
+        let mut calldata = resolve_calldata(domain_name);
+        for _ in 0..MAX_OFFCHAIN_LOOKUPS {
+            let revert = match self.eth_call(resolver, &calldata).await? {
+                CallResult::Success(result) => return decode_resolve_result(&result),
+                CallResult::Revert(revert) => revert,
+            };
+            // any other revert means that resolver can't resolve the name
+            let Some(lookup) = OffchainLookup::decode(&revert)? else {
+                return Ok(None);
+            };
+            if lookup.sender != resolver {
+                return Err(ResolverRpcError::InvalidSender(lookup.sender));
+            }
+            let Some(response) = self.request_gateways(&lookup).await? else {
+                return Ok(None);
+            };
+            calldata = lookup.callback_calldata(&response);
+        }
+        Err(ResolverRpcError::TooManyLookups)
+    }
+
+    /// Gateways are requested in order until one of them responds
+    async fn request_gateways(
+        &self,
+        lookup: &OffchainLookup,
+    ) -> Result<Option<Bytes>, ResolverRpcError> {
+        let mut errors = vec![];
+        for url in &lookup.urls {
+            if let Err(err) = self.check_gateway_url(url).await {
+                tracing::warn!(url = url.as_str(), err = ?err, "offchain lookup gateway skipped");
+                errors.push(err.to_string());
+                continue;
+            }
+            let result =
+                request_gateway(&self.inner, url, lookup.sender, lookup.call_data.clone()).await;
+            match result {
+                Ok(response) => return Ok(response),
+                Err(err) => {
+                    tracing::warn!(url = url.as_str(), err = ?err, "offchain lookup gateway failed");
+                    errors.push(err.to_string());
+                }
+            }
+        }
+        Err(ResolverRpcError::Gateway(errors.join("; ")))
+    }
+
+    async fn check_gateway_url(&self, url: &str) -> Result<(), ResolverRpcError> {
+        let forbidden = |reason| ResolverRpcError::ForbiddenGateway(url.to_string(), reason);
+        let parsed = url::Url::parse(url).map_err(|_| forbidden("invalid url"))?;
+        let host = parsed.host_str().ok_or_else(|| forbidden("no host"))?;
+        if let Some(allowed_hosts) = &self.allowed_gateway_hosts {
+            return match allowed_hosts.contains(host) {
+                true => Ok(()),
+                false => Err(forbidden("host is not in allowed list")),
+            };
+        }
+        if parsed.scheme() != "https" {
+            return Err(forbidden("only https is allowed"));
+        }
+        let ips: Vec<IpAddr> = match parsed.host() {
+            Some(url::Host::Ipv4(ip)) => vec![ip.into()],
+            Some(url::Host::Ipv6(ip)) => vec![ip.into()],
+            _ => tokio::net::lookup_host((host, parsed.port_or_known_default().unwrap_or(443)))
+                .await
+                .map_err(|_| forbidden("host is not resolved"))?
+                .map(|addr| addr.ip())
+                .collect(),
+        };
+        match !ips.is_empty() && ips.iter().all(is_public_ip) {
+            true => Ok(()),
+            false => Err(forbidden("host is not public")),
+        }
+    }
+
+    async fn eth_call(&self, to: Address, data: &Bytes) -> Result<CallResult, ResolverRpcError> {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_call",
+            "params": [{ "to": to, "data": data }, "latest"],
+        });
+        let response: RpcResponse = self
+            .inner
+            .post(&self.url)
+            .json(&request)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        match (response.result, response.error) {
+            (Some(result), _) => Ok(CallResult::Success(result)),
+            (None, Some(error)) => {
+                let revert = error
+                    .data
+                    .as_ref()
+                    .and_then(|data| data.as_str())
+                    .and_then(|data| Bytes::from_str(data).ok());
+                match revert {
+                    Some(revert) => Ok(CallResult::Revert(revert)),
+                    None => Err(ResolverRpcError::Rpc(error.message)),
+                }
+            }
+            (None, None) => Err(ResolverRpcError::Rpc("empty response".to_string())),
+        }
+    }
+}
+
+/// Address is reachable from internet, so it's not loopback, private network, link-local
+/// (like cloud metadata service) or other special-purpose address
+fn is_public_ip(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(&ip),
+            None => {
+                let first = ip.segments()[0];
+                !(ip.is_loopback()
+                    || ip.is_unspecified()
+                    || ip.is_multicast()
+                    // unique local fc00::/7 and link-local fe80::/10
+                    || (first & 0xfe00) == 0xfc00
+                    || (first & 0xffc0) == 0xfe80)
+            }
+        },
+    }
+}
+
+fn is_public_ipv4(ip: &Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // "this network" 0.0.0.0/8, shared address space 100.64.0.0/10, reserved 240.0.0.0/4
+        || a == 0
+        || (a == 100 && (b & 0xc0) == 64)
+        || a >= 240)
+}
+
+/// `resolve(bytes,bytes)` returns result of inner `addr(bytes32)` call as bytes
+fn decode_resolve_result(result: &[u8]) -> Result<Option<Address>, ResolverRpcError> {
+    let result = abi::decode(&[ParamType::Bytes], result)?
+        .into_iter()
+        .next()
+        .and_then(Token::into_bytes)
+        .unwrap_or_default();
+    Ok(decode_address(&result)?)
+}
+
+impl SubgraphReader {
+    /// Domain missing in subgraph, resolved by wildcard resolver (ENSIP-10) of its closest
+    /// ancestor. Failures of resolving are logged and treated as unknown domain
+    pub(super) async fn get_wildcard_domain(
+        &self,
+        resolver_rpc: &ResolverRpcClient,
+        subgraph: &Subgraph,
+        domain_name: DomainName,
+    ) -> Result<Option<GetDomainOutput>, SubgraphReadError> {
+        let ancestor_ids: Vec<String> = std::iter::successors(
+            domain_name.name.split_once('.').map(|(_, parent)| parent),
+            |name| name.split_once('.').map(|(_, parent)| parent),
+        )
+        .map(|ancestor| subgraph.namehash(ancestor))
+        .collect();
+        let maybe_resolver = sql::find_closest_resolver(
+            self.read_pool.as_ref(),
+            &self.interactive_options(),
+            &subgraph.schema_name,
+            &ancestor_ids,
+        )
+        .await?;
+        // resolver id is `0x{address}-0x{node}`
+        let Some(resolver) = maybe_resolver
+            .as_deref()
+            .and_then(|resolver| resolver.split('-').next())
+            .and_then(|address| Address::from_str(address).ok())
+            .filter(|address| !address.is_zero())
+        else {
+            return Ok(None);
+        };
+        let address = match resolver_rpc.resolve_address(resolver, &domain_name).await {
+            Ok(Some(address)) => address,
+            Ok(None) => return Ok(None),
+            Err(err) => {
+                tracing::warn!(
+                    name = domain_name.name,
+                    resolver = ?resolver,
+                    err = ?err,
+                    "failed to resolve domain by wildcard resolver"
+                );
+                return Ok(None);
+            }
+        };
+        let domain = DetailedDomain {
+            id: domain_name.id,
+            name: Some(domain_name.name),
+            label_name: Some(domain_name.label_name),
+            resolved_address: Some(format!("{address:#x}")),
+            resolver: Some(format!("{resolver:#x}")),
+            stored_offchain: true,
+            ..Default::default()
+        };
+        Ok(Some(GetDomainOutput {
+            domain,
+            tokens: vec![],
+            resolved_address_history: vec![],
+        }))
+    }
+}
+
+/// Revert data of `OffchainLookup` error with single gateway url
+#[cfg(test)]
+pub(crate) fn encode_offchain_lookup(
+    sender: Address,
+    url: &str,
+    call_data: &[u8],
+    callback_function: [u8; 4],
+    extra_data: &[u8],
+) -> Bytes {
+    let args = abi::encode(&[
+        Token::Address(sender),
+        Token::Array(vec![Token::String(url.to_string())]),
+        Token::Bytes(call_data.to_vec()),
+        Token::FixedBytes(callback_function.to_vec()),
+        Token::Bytes(extra_data.to_vec()),
+    ]);
+    [id(OffchainLookup::SIGNATURE).as_slice(), &args]
+        .concat()
+        .into()
+}
+
+/// Result of `resolve(bytes,bytes)` with given resolved address
+#[cfg(test)]
+pub(crate) fn encode_resolve_result(address: Address) -> Bytes {
+    abi::encode(&[Token::Bytes(abi::encode(&[Token::Address(address)]))]).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        subgraphs_reader::GetDomainInput,
+        test_utils::{addr, mocked_networks_with_blockscout, DEFAULT_CHAIN_ID},
+    };
+    use pretty_assertions::assert_eq;
+    use sqlx::PgPool;
+    use std::sync::Arc;
+
+    #[test]
+    fn offchain_lookup_works() {
+        let sender = Address::from_low_u64_be(1);
+        let revert = encode_offchain_lookup(
+            sender,
+            "https://gateway/{sender}/{data}.json",
+            &[1, 2, 3],
+            [0xde, 0xad, 0xbe, 0xef],
+            &[4, 5],
+        );
+        let lookup = OffchainLookup::decode(&revert).unwrap().unwrap();
+        assert_eq!(
+            lookup,
+            OffchainLookup {
+                sender,
+                urls: vec!["https://gateway/{sender}/{data}.json".to_string()],
+                call_data: vec![1, 2, 3].into(),
+                callback_function: [0xde, 0xad, 0xbe, 0xef],
+                extra_data: vec![4, 5].into(),
+            }
+        );
+        let callback = lookup.callback_calldata(&[6]);
+        assert_eq!(&callback[..4], &[0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(
+            abi::decode(&[ParamType::Bytes, ParamType::Bytes], &callback[4..]).unwrap(),
+            vec![Token::Bytes(vec![6]), Token::Bytes(vec![4, 5])]
+        );
+
+        // other errors are not offchain lookups
+        let error = [id("Error(string)").as_slice(), &[0; 32]].concat();
+        assert_eq!(OffchainLookup::decode(&error).unwrap(), None);
+        assert!(OffchainLookup::decode(&revert[..10]).is_err());
+    }
+
+    #[test]
+    fn is_public_ip_works() {
+        for ip in [
+            "1.1.1.1",
+            "8.8.8.8",
+            "2606:4700:4700::1111",
+            "::ffff:1.1.1.1",
+        ] {
+            assert!(is_public_ip(&ip.parse().unwrap()), "{ip}");
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.0.0.1",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "255.255.255.255",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(&ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[tokio::test]
+    async fn check_gateway_url_works() {
+        let client = ResolverRpcClient::new("http://localhost:8545".to_string(), 5);
+        client
+            .check_gateway_url("https://1.1.1.1/{sender}/{data}.json")
+            .await
+            .expect("public https gateway should be allowed");
+        for url in [
+            "http://1.1.1.1/{sender}/{data}.json",
+            "https://127.0.0.1/{sender}/{data}.json",
+            "https://169.254.169.254/latest/meta-data",
+            "https://[::1]/{sender}",
+            "https://localhost/{sender}",
+            "not a url",
+        ] {
+            let err = client.check_gateway_url(url).await.unwrap_err();
+            assert!(
+                matches!(err, ResolverRpcError::ForbiddenGateway(_, _)),
+                "{url}: {err:?}"
+            );
+        }
+
+        let client = client.with_allowed_gateway_hosts(["127.0.0.1".to_string()]);
+        client
+            .check_gateway_url("http://127.0.0.1:8080/{sender}/{data}.json")
+            .await
+            .expect("allowed host should be allowed");
+        assert!(client
+            .check_gateway_url("https://1.1.1.1/{sender}/{data}.json")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn decode_resolve_result_works() {
+        let address = Address::from_low_u64_be(42);
+        assert_eq!(
+            decode_resolve_result(&encode_resolve_result(address)).unwrap(),
+            Some(address)
+        );
+        assert_eq!(
+            decode_resolve_result(&encode_resolve_result(Address::zero())).unwrap(),
+            None
+        );
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn wildcard_resolvers_work(pool: PgPool) {
+        let pool = Arc::new(pool);
+        // resolver of vitalik.eth in subgraph
+        let resolver = addr("0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41");
+        let alice_address = addr("0x0000000000000000000000000000000000000a11");
+        let callback_function = [0x12, 0x34, 0x56, 0x78];
+        let server = wiremock::MockServer::start().await;
+        let rpc_call = |selector: &str| {
+            wiremock::Mock::given(wiremock::matchers::path("/rpc")).and(
+                wiremock::matchers::body_string_contains(format!("\"data\":\"0x{selector}")),
+            )
+        };
+        // resolver reverts with offchain lookup and verifies gateway response in callback
+        rpc_call(&hex::encode(id("resolve(bytes,bytes)")))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "error": {
+                        "code": 3,
+                        "message": "execution reverted",
+                        "data": encode_offchain_lookup(
+                            resolver,
+                            &format!("{}/gateway/{{sender}}/{{data}}.json", server.uri()),
+                            b"gateway call",
+                            callback_function,
+                            b"extra data",
+                        ),
+                    },
+                })),
+            )
+            .mount(&server)
+            .await;
+        rpc_call(&hex::encode(callback_function))
+            .respond_with(
+                wiremock::ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": 1,
+                    "result": encode_resolve_result(alice_address),
+                })),
+            )
+            .mount(&server)
+            .await;
+        wiremock::Mock::given(wiremock::matchers::path(format!(
+            "/gateway/{resolver:#x}/0x{}.json",
+            hex::encode(b"gateway call")
+        )))
+        .respond_with(
+            wiremock::ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({ "data": "0x1234" })),
+        )
+        .mount(&server)
+        .await;
+
+        let mut networks = mocked_networks_with_blockscout().await;
+        // gateway of mock server is local, so it should be allowed explicitly
+        networks.get_mut(&DEFAULT_CHAIN_ID).unwrap().resolver_rpc = Some(
+            ResolverRpcClient::new(format!("{}/rpc", server.uri()), 5)
+                .with_allowed_gateway_hosts(["127.0.0.1".to_string()]),
+        );
+        // expired domain under ancestor with wildcard resolver
+        sqlx::query(
+            "UPDATE sgd1.domain SET id = $1, name = 'expired.vitalik.eth' WHERE name = 'expired.eth'",
+        )
+        .bind(DomainName::new("expired.vitalik.eth", None).unwrap().id)
+        .execute(pool.as_ref())
+        .await
+        .unwrap();
+        let reader = SubgraphReader::initialize(pool.clone(), networks)
+            .await
+            .expect("failed to init reader");
+        let get_domain = |name: &str, only_active: bool| {
+            reader.get_domain(GetDomainInput {
+                network_id: DEFAULT_CHAIN_ID,
+                name: name.to_string(),
+                only_active,
+                coin_types: vec![],
+                include_resolved_address_history: false,
+            })
+        };
+
+        let alice = get_domain("alice.vitalik.eth", true)
+            .await
+            .expect("failed to get wildcard domain")
+            .expect("wildcard domain not found");
+        assert_eq!(
+            alice.domain,
+            DetailedDomain {
+                id: DomainName::new("alice.vitalik.eth", None).unwrap().id,
+                name: Some("alice.vitalik.eth".to_string()),
+                label_name: Some("alice".to_string()),
+                resolved_address: Some(format!("{alice_address:#x}")),
+                resolver: Some(format!("{resolver:#x}")),
+                stored_offchain: true,
+                ..Default::default()
+            }
+        );
+
+        // domains in subgraph are not resolved offchain
+        let vitalik = get_domain("vitalik.eth", false)
+            .await
+            .expect("failed to get domain")
+            .expect("vitalik.eth not found");
+        assert!(!vitalik.domain.stored_offchain);
+        // domain filtered out as expired is not resolved offchain either
+        let expired = get_domain("expired.vitalik.eth", false)
+            .await
+            .expect("failed to get domain")
+            .expect("expired domain not found");
+        assert!(!expired.domain.stored_offchain);
+        let result = get_domain("expired.vitalik.eth", true)
+            .await
+            .expect("failed to get domain");
+        assert!(
+            result.is_none(),
+            "expired domain resolved offchain: {result:?}"
+        );
+        // no ancestor with resolver
+        let result = get_domain("alice.nothing", false)
+            .await
+            .expect("failed to get domain");
+        assert!(result.is_none());
+    }
+}
@@ -0,0 +1,72 @@
+use crate::{
+    entity::subgraph::domain::ReverseRecord,
+    subgraphs_reader::{sql, sql::QueryOptions, ReverseRegistrar, SubgraphReadError},
+};
+use sqlx::PgPool;
+use std::{
+    collections::HashSet,
+    sync::{Arc, RwLock},
+};
+
+/// Verified reverse records of subgraphs cached in state database, so graph-node database
+/// is only read. Cache is rebuilt in background, reverse records of subgraphs
+/// not rebuilt yet by this process are read from subgraph directly
+pub struct ReverseRecordsCache {
+    state_pool: Arc<PgPool>,
+    refreshed_schemas: RwLock<HashSet<String>>,
+}
+
+impl ReverseRecordsCache {
+    pub fn new(state_pool: Arc<PgPool>) -> Self {
+        Self {
+            state_pool,
+            refreshed_schemas: Default::default(),
+        }
+    }
+
+    /// Rebuilds cached reverse records of subgraph and returns number of cached records
+    pub async fn refresh(
+        &self,
+        pool: &PgPool,
+        schema: &str,
+        reverse_registrar: &ReverseRegistrar,
+    ) -> Result<u64, SubgraphReadError> {
+        let cached = sql::refresh_cached_reverse_records(
+            pool,
+            self.state_pool.as_ref(),
+            schema,
+            reverse_registrar,
+        )
+        .await?;
+        self.refreshed_schemas
+            .write()
+            .expect("reverse records cache lock is poisoned")
+            .insert(schema.to_string());
+        Ok(cached)
+    }
+
+    pub async fn batch_search_addr_reverse_names(
+        &self,
+        pool: &PgPool,
+        options: &QueryOptions,
+        schema: &str,
+        addr_reverse_hashes: &[String],
+    ) -> Result<Vec<ReverseRecord>, SubgraphReadError> {
+        let is_refreshed = self
+            .refreshed_schemas
+            .read()
+            .expect("reverse records cache lock is poisoned")
+            .contains(schema);
+        if is_refreshed {
+            sql::batch_search_cached_reverse_names(
+                self.state_pool.as_ref(),
+                options,
+                schema,
+                addr_reverse_hashes,
+            )
+            .await
+        } else {
+            sql::batch_search_addr_reverse_names(pool, options, schema, addr_reverse_hashes).await
+        }
+    }
+}
@@ -2,10 +2,11 @@ use super::CachedView;
 use crate::{
     entity::subgraph::domain::AddrReverseDomainWithActualName,
     subgraphs_reader::{
-        sql::{bind_string_list, DOMAIN_BLOCK_RANGE_WHERE_CLAUSE},
-        SubgraphReadError,
+        sql::{bind_string_list, in_chunks, QueryOptions, DOMAIN_BLOCK_RANGE_WHERE_CLAUSE},
+        Subgraph, SubgraphReadError,
     },
 };
+use futures::FutureExt;
 use sqlx::PgPool;
 use tracing::instrument;
 
@@ -25,9 +26,11 @@ impl CachedView for AddrReverseNamesView {
         "reversed_domain_id"
     }
 
-    fn table_sql(schema: &str) -> String {
-        // Filter all domain that has
-        // parent = hashname('addr.reverse') = 0x91d1777781884d03a6757a803996e38de2a42967fb37eeaca72729271025a9e2
+    fn table_sql(subgraph: &Subgraph) -> String {
+        let schema = &subgraph.schema_name;
+        // Filter all domain that has parent = namehash(node_suffix of reverse registrar),
+        // like namehash('addr.reverse') = 0x91d1777781884d03a6757a803996e38de2a42967fb37eeaca72729271025a9e2
+        let reverse_parent_id = subgraph.settings.reverse_registrar.parent_id();
         format!(
             r#"
         SELECT
@@ -43,7 +46,7 @@ impl CachedView for AddrReverseNamesView {
         JOIN {schema}.domain addr_reversed_domain ON nc.resolver = addr_reversed_domain.resolver
         JOIN {schema}.domain domain ON domain.name = nc.name
         WHERE true
-        AND addr_reversed_domain.parent = '0x91d1777781884d03a6757a803996e38de2a42967fb37eeaca72729271025a9e2'
+        AND addr_reversed_domain.parent = '{reverse_parent_id}'
         AND addr_reversed_domain.{DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
         AND domain.{DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
         AND nc.{DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
@@ -55,27 +58,36 @@ impl CachedView for AddrReverseNamesView {
 impl AddrReverseNamesView {
     #[instrument(
         name = "AddrReverseNamesView::batch_search_addresses",
-        skip(pool, address_hashes),
+        skip(pool, options, address_hashes),
         fields(job_size = address_hashes.len()),
         err(level = "error"),
         level = "info",
     )]
     pub async fn batch_search_addresses(
         pool: &PgPool,
+        options: &QueryOptions,
         schema: &str,
         address_hashes: &[impl AsRef<str>],
     ) -> Result<Vec<AddrReverseDomainWithActualName>, SubgraphReadError> {
         let view_table_name = Self::view_table_name();
-        let domains: Vec<AddrReverseDomainWithActualName> = sqlx::query_as(&format!(
+        let sql = format!(
             r#"
             SELECT *
             FROM {schema}.{view_table_name}
             WHERE reversed_domain_id = ANY($1)
             "#
-        ))
-        .bind(bind_string_list(address_hashes))
-        .fetch_all(pool)
-        .await?;
-        Ok(domains)
+        );
+        let sql = &sql;
+        in_chunks(&options.chunks, address_hashes, |chunk| async move {
+            options
+                .run(pool, "addr_reverse_names_batch_search", schema, |conn| {
+                    sqlx::query_as(sql)
+                        .bind(bind_string_list(chunk))
+                        .fetch_all(&mut **conn)
+                        .boxed()
+                })
+                .await
+        })
+        .await
     }
 }
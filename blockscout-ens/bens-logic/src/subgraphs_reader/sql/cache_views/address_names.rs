@@ -3,12 +3,13 @@ use crate::{
     entity::subgraph::domain::DomainWithAddress,
     subgraphs_reader::{
         sql::{
-            bind_string_list, DOMAIN_BLOCK_RANGE_WHERE_CLAUSE, DOMAIN_NONEMPTY_LABEL_WHERE_CLAUSE,
-            DOMAIN_NOT_EXPIRED_WHERE_CLAUSE,
+            bind_string_list, domain_not_expired_where_clause, in_chunks, QueryOptions,
+            DOMAIN_BLOCK_RANGE_WHERE_CLAUSE, DOMAIN_NONEMPTY_LABEL_WHERE_CLAUSE,
         },
-        SubgraphReadError,
+        Subgraph, SubgraphReadError,
     },
 };
+use futures::FutureExt;
 use sqlx::PgPool;
 use tracing::instrument;
 
@@ -28,7 +29,9 @@ impl CachedView for AddressNamesView {
         "resolved_address"
     }
 
-    fn table_sql(schema: &str) -> String {
+    fn table_sql(subgraph: &Subgraph) -> String {
+        let schema = &subgraph.schema_name;
+        let not_expired = domain_not_expired_where_clause(subgraph.sql_quirks());
         format!(
             r#"
         SELECT DISTINCT ON (resolved_address)
@@ -41,7 +44,7 @@ impl CachedView for AddressNamesView {
             AND name NOT LIKE '%[%'
             AND {DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
             AND {DOMAIN_NONEMPTY_LABEL_WHERE_CLAUSE}
-            AND {DOMAIN_NOT_EXPIRED_WHERE_CLAUSE}
+            AND {not_expired}
         ORDER BY resolved_address, created_at"#
         )
     }
@@ -51,29 +54,37 @@ impl AddressNamesView {
     // TODO: rewrite to sea_query generation
     #[instrument(
         name = "AddressNamesView::batch_search_addresses",
-        skip(pool, addresses),
+        skip(pool, options, addresses),
         fields(job_size = addresses.len()),
         err(level = "error"),
         level = "info",
     )]
     pub async fn batch_search_addresses(
         pool: &PgPool,
+        options: &QueryOptions,
         schema: &str,
         addresses: &[impl AsRef<str>],
     ) -> Result<Vec<DomainWithAddress>, SubgraphReadError> {
         let view_table_name = Self::view_table_name();
-        let domains: Vec<DomainWithAddress> = sqlx::query_as(&format!(
+        let sql = format!(
             r#"
             SELECT id, domain_name, resolved_address
             FROM {schema}.{view_table_name}
             where
                 resolved_address = ANY($1)
             "#
-        ))
-        .bind(bind_string_list(addresses))
-        .fetch_all(pool)
-        .await?;
-
-        Ok(domains)
+        );
+        let sql = &sql;
+        in_chunks(&options.chunks, addresses, |chunk| async move {
+            options
+                .run(pool, "address_names_batch_search", schema, |conn| {
+                    sqlx::query_as(sql)
+                        .bind(bind_string_list(chunk))
+                        .fetch_all(&mut **conn)
+                        .boxed()
+                })
+                .await
+        })
+        .await
     }
 }
@@ -1,4 +1,6 @@
+use crate::{metrics, subgraphs_reader::Subgraph};
 use anyhow::Context;
+use ethers::utils::keccak256;
 use sqlx::{Executor, PgPool};
 
 mod addr_reverse_names;
@@ -12,22 +14,50 @@ pub trait CachedView {
     fn refresh_function_name() -> &'static str;
     fn view_table_name() -> &'static str;
     fn unique_field() -> &'static str;
-    fn table_sql(schema: &str) -> String;
+    fn table_sql(subgraph: &Subgraph) -> String;
 
-    async fn create_view(pool: &PgPool, schema: &str) -> Result<(), anyhow::Error> {
+    /// Creates view or, if its definition was changed since it was created,
+    /// for example by settings of subgraph, drops and recreates it.
+    /// Definition is identified by hash stored in comment of view
+    async fn create_view(pool: &PgPool, subgraph: &Subgraph) -> Result<(), anyhow::Error> {
+        let schema = subgraph.schema_name.as_str();
         let view_table_name = Self::view_table_name();
         let refresh_function_name = Self::refresh_function_name();
         let unique_field = Self::unique_field();
-        let table_sql = Self::table_sql(schema);
+        let table_sql = Self::table_sql(subgraph);
+        let definition_hash = hex::encode(keccak256(table_sql.as_bytes()));
         let mut tx = pool.begin().await?;
-        tx.execute(sqlx::query(&format!(
-            r#"
-            CREATE MATERIALIZED VIEW IF NOT EXISTS {schema}.{view_table_name} AS
-            {table_sql}
-            "#,
-        )))
-        .await
-        .context("creating materialized view")?;
+        let current_hash: Option<String> =
+            sqlx::query_scalar("SELECT obj_description(to_regclass($1), 'pg_class')")
+                .bind(format!("{schema}.{view_table_name}"))
+                .fetch_one(&mut *tx)
+                .await
+                .context("reading definition of materialized view")?;
+        if current_hash.as_deref() != Some(definition_hash.as_str()) {
+            tracing::info!(
+                schema,
+                view_table_name,
+                "definition of materialized view changed, recreating it"
+            );
+            tx.execute(sqlx::query(&format!(
+                "DROP MATERIALIZED VIEW IF EXISTS {schema}.{view_table_name}"
+            )))
+            .await
+            .context("dropping materialized view")?;
+            tx.execute(sqlx::query(&format!(
+                r#"
+                CREATE MATERIALIZED VIEW {schema}.{view_table_name} AS
+                {table_sql}
+                "#,
+            )))
+            .await
+            .context("creating materialized view")?;
+            tx.execute(sqlx::query(&format!(
+                "COMMENT ON MATERIALIZED VIEW {schema}.{view_table_name} IS '{definition_hash}'"
+            )))
+            .await
+            .context("saving definition of materialized view")?;
+        }
         let index_name = format!("{}_unique_{}", view_table_name, unique_field);
         tx.execute(sqlx::query(&format!(
             r#"
@@ -60,9 +90,9 @@ pub trait CachedView {
 
     async fn refresh_view(pool: &PgPool, schema: &str) -> Result<(), anyhow::Error> {
         let refresh_function_name = Self::refresh_function_name();
-        sqlx::query(&format!("SELECT {schema}.{refresh_function_name};"))
-            .execute(pool)
-            .await?;
+        let sql = format!("SELECT {schema}.{refresh_function_name};");
+        let query = sqlx::query(&sql).execute(pool);
+        metrics::observe_query(Self::view_table_name(), schema, query).await?;
         Ok(())
     }
 }
@@ -70,6 +100,7 @@ pub trait CachedView {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::subgraphs_reader::SubgraphSettings;
     use sqlx::{Executor, PgPool, Row};
 
     struct TestView;
@@ -87,8 +118,11 @@ mod tests {
             "max_int"
         }
 
-        fn table_sql(schema: &str) -> String {
-            format!("SELECT bar as max_int FROM {schema}.foo ORDER BY bar DESC LIMIT 1")
+        fn table_sql(subgraph: &Subgraph) -> String {
+            format!(
+                "SELECT bar + {} as max_int FROM {}.foo ORDER BY bar DESC LIMIT 1",
+                subgraph.settings.grace_period_days, subgraph.schema_name
+            )
         }
     }
 
@@ -99,15 +133,33 @@ mod tests {
         conn.execute("CREATE TABLE sgd1.foo(bar integer)").await?;
         conn.execute("INSERT INTO sgd1.foo VALUES (1)").await?;
 
-        TestView::create_view(&pool, "sgd1").await?;
+        TestView::create_view(&pool, &subgraph(Default::default())).await?;
         assert_current_max_is(&pool, 1).await;
         conn.execute("INSERT INTO sgd1.foo VALUES (100)").await?;
         assert_current_max_is(&pool, 1).await;
         TestView::refresh_view(&pool, "sgd1").await?;
         assert_current_max_is(&pool, 100).await;
+
+        // view with the same definition is kept as is
+        conn.execute("INSERT INTO sgd1.foo VALUES (200)").await?;
+        TestView::create_view(&pool, &subgraph(Default::default())).await?;
+        assert_current_max_is(&pool, 100).await;
+        // view is recreated once definition is changed
+        let settings = SubgraphSettings {
+            grace_period_days: 10,
+            ..Default::default()
+        };
+        TestView::create_view(&pool, &subgraph(settings)).await?;
+        assert_current_max_is(&pool, 210).await;
+        TestView::refresh_view(&pool, "sgd1").await?;
+        assert_current_max_is(&pool, 210).await;
         Ok(())
     }
 
+    fn subgraph(settings: SubgraphSettings) -> Subgraph {
+        Subgraph::new("sgd1".to_string(), settings)
+    }
+
     async fn assert_current_max_is(pool: &PgPool, max_int: i32) {
         let row = sqlx::query("SELECT * FROM sgd1.test_view")
             .fetch_one(pool)
@@ -0,0 +1,104 @@
+//! Splitting of large batch lookups into chunks. Single query with huge `ANY($1)` array
+//! takes seconds, while several smaller queries run concurrently finish much faster.
+//! Policy is part of [`super::QueryOptions`] passed to every query
+
+use crate::subgraphs_reader::SubgraphReadError;
+use futures::{StreamExt, TryStreamExt};
+use std::future::Future;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChunkPolicy {
+    /// Max number of items bound into one query
+    pub chunk_size: usize,
+    /// Max number of chunk queries executed at the same time
+    pub max_concurrent_chunks: usize,
+}
+
+impl Default for ChunkPolicy {
+    fn default() -> Self {
+        Self {
+            chunk_size: 1000,
+            max_concurrent_chunks: 4,
+        }
+    }
+}
+
+/// Runs `query` for every chunk of `items` and merges results in order of chunks.
+/// Every item gets into exactly one chunk, so queries grouping rows by item stay correct
+pub async fn in_chunks<'a, I, T, F, Fut>(
+    policy: &ChunkPolicy,
+    items: &'a [I],
+    query: F,
+) -> Result<Vec<T>, SubgraphReadError>
+where
+    F: Fn(&'a [I]) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, SubgraphReadError>>,
+{
+    let chunk_size = policy.chunk_size.max(1);
+    if items.len() <= chunk_size {
+        return query(items).await;
+    }
+    let mut queries = Vec::with_capacity(items.len().div_ceil(chunk_size));
+    for chunk in items.chunks(chunk_size) {
+        queries.push(query(chunk));
+    }
+    let results: Vec<Vec<T>> = futures::stream::iter(queries)
+        .buffered(policy.max_concurrent_chunks.max(1))
+        .try_collect()
+        .await?;
+    Ok(results.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[tokio::test]
+    async fn in_chunks_works() {
+        let policy = ChunkPolicy {
+            chunk_size: 3,
+            max_concurrent_chunks: 2,
+        };
+        let items: Vec<u32> = (0..10).collect();
+
+        // results of chunks are merged in order of items
+        let queries = AtomicUsize::new(0);
+        let result = in_chunks(&policy, &items, |chunk| {
+            queries.fetch_add(1, Ordering::SeqCst);
+            let doubled: Vec<u32> = chunk.iter().map(|i| i * 2).collect();
+            async move { Ok(doubled) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(result, items.iter().map(|i| i * 2).collect::<Vec<_>>());
+        assert_eq!(queries.load(Ordering::SeqCst), 4);
+
+        // small batch is queried at once
+        let queries = AtomicUsize::new(0);
+        let result = in_chunks(&policy, &items[..3], |chunk| {
+            queries.fetch_add(1, Ordering::SeqCst);
+            let chunk = chunk.to_vec();
+            async move { Ok(chunk) }
+        })
+        .await
+        .unwrap();
+        assert_eq!(result, vec![0, 1, 2]);
+        assert_eq!(queries.load(Ordering::SeqCst), 1);
+
+        // error of any chunk fails the whole batch
+        let result: Result<Vec<u32>, _> = in_chunks(&policy, &items, |chunk| {
+            let fail = chunk.contains(&7);
+            async move {
+                if fail {
+                    Err(SubgraphReadError::Internal("failed chunk".to_string()))
+                } else {
+                    Ok(vec![])
+                }
+            }
+        })
+        .await;
+        assert!(matches!(result, Err(SubgraphReadError::Internal(_))));
+    }
+}
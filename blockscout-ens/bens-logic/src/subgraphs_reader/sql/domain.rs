@@ -1,18 +1,35 @@
 use crate::{
-    entity::subgraph::domain::{DetailedDomain, Domain, DomainWithAddress, ReverseRecord},
+    entity::subgraph::{
+        domain::{
+            AddressSummaryRow, DetailedDomain, Domain, DomainRecordsRow, DomainWithAddress,
+            TextRecord,
+        },
+        domain_event::{AddrChangedEvent, DomainChangeRow},
+    },
     hash_name::hex,
     subgraphs_reader::{
-        domain_name::DomainName, pagination::Paginator, GetDomainInput, LookupAddressInput,
-        SubgraphReadError,
+        domain_name::DomainName, pagination::Paginator, AddressDomainsFilter,
+        AddressResolveTechnique, DomainStateFilter, ExportAddressInput, GetDomainInput,
+        LookupAddressInput, LookupMulticoinAddressInput, QuickSearchRank, ReverseRegistrar,
+        SubgraphReadError, TldRules,
     },
 };
 use anyhow::Context;
 use ethers::addressbook::Address;
-use sea_query::{Alias, Condition, Expr, PostgresQueryBuilder, SelectStatement};
-use sqlx::postgres::{PgPool, PgQueryResult};
+use futures::FutureExt;
+use sea_query::{
+    extension::postgres::PgFunc, Alias, Asterisk, CommonTableExpression, Condition, Expr, Func,
+    JoinType, Order, PostgresQueryBuilder, Query, SelectStatement, SimpleExpr, UnionType,
+    UpdateStatement, WithClause,
+};
+use sea_query_binder::{SqlxBinder, SqlxValues};
+use sqlx::{
+    postgres::{PgPool, PgQueryResult},
+    types::BigDecimal,
+};
 use tracing::instrument;
 
-mod sql_gen {
+pub(super) mod sql_gen {
     use super::*;
 
     pub trait QueryBuilderExt {
@@ -20,9 +37,13 @@ mod sql_gen {
 
         fn with_non_empty_label(&mut self) -> &mut Self;
 
-        fn with_not_expired(&mut self) -> &mut Self;
+        fn with_not_expired(&mut self, quirks: SqlQuirks) -> &mut Self;
+
+        fn with_not_burned(&mut self) -> &mut Self;
 
-        fn with_resolved_names(&mut self) -> &mut Self;
+        fn with_state_filter(&mut self, state: DomainStateFilter, quirks: SqlQuirks) -> &mut Self;
+
+        fn with_resolved_names(&mut self, tld_rules: &TldRules) -> &mut Self;
     }
 
     impl QueryBuilderExt for sea_query::SelectStatement {
@@ -34,35 +55,86 @@ mod sql_gen {
             self.and_where(Expr::cust(DOMAIN_NONEMPTY_LABEL_WHERE_CLAUSE))
         }
 
-        fn with_not_expired(&mut self) -> &mut SelectStatement {
-            self.and_where(Expr::cust(DOMAIN_NOT_EXPIRED_WHERE_CLAUSE))
+        fn with_not_expired(&mut self, quirks: SqlQuirks) -> &mut SelectStatement {
+            self.and_where(Expr::cust(domain_not_expired_where_clause(quirks)))
+        }
+
+        fn with_not_burned(&mut self) -> &mut SelectStatement {
+            self.and_where(Expr::cust(domain_not_burned_where_clause()))
         }
 
-        fn with_resolved_names(&mut self) -> &mut SelectStatement {
-            self.and_where(Expr::cust("name NOT LIKE '%[%'"))
+        fn with_state_filter(
+            &mut self,
+            state: DomainStateFilter,
+            quirks: SqlQuirks,
+        ) -> &mut SelectStatement {
+            if state.only_active {
+                self.with_not_expired(quirks);
+            }
+            if !state.include_burned {
+                self.with_not_burned();
+            }
+            self
         }
+
+        /// Names without unknown labels and with TLD allowed by `tld_rules`
+        fn with_resolved_names(&mut self, tld_rules: &TldRules) -> &mut SelectStatement {
+            self.and_where(Expr::cust("name NOT LIKE '%[%'"));
+            if !tld_rules.allowed_tlds.is_empty() {
+                let name = || Expr::col(Alias::new("name"));
+                let allowed = tld_rules
+                    .allowed_tlds
+                    .iter()
+                    .fold(Condition::any(), |cond, tld| {
+                        cond.add(name().like(format!("%.{tld}")))
+                            .add(name().eq(tld))
+                    });
+                self.cond_where(allowed);
+            }
+            self
+        }
+    }
+
+    /// Schema-qualified table reference, like `sgd1.domain`
+    pub fn schema_table(schema: &str, table: &str) -> (Alias, Alias) {
+        (Alias::new(schema), Alias::new(table))
+    }
+
+    /// Block range filter for table with `alias`, like `d.block_range @> 2147483647`
+    pub fn block_range_of(alias: &str) -> SimpleExpr {
+        Expr::cust(format!("{alias}.{DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}"))
     }
 
-    #[allow(dead_code)]
-    pub fn detailed_domain_select(schema: &str) -> SelectStatement {
+    pub fn sub_query(query: SelectStatement) -> SimpleExpr {
+        SimpleExpr::SubQuery(None, Box::new(query.into_sub_query_statement()))
+    }
+
+    pub fn detailed_domain_select(schema: &str, quirks: SqlQuirks) -> SelectStatement {
         sea_query::Query::select()
             .expr(Expr::cust(DETAILED_DOMAIN_DEFAULT_SELECT_CLAUSE))
-            .from((Alias::new(schema), Alias::new("domain")))
+            .expr(Expr::cust(domain_expiry_status_clause(quirks)))
+            .from(schema_table(schema, "domain"))
             .to_owned()
     }
 
-    pub fn domain_select(schema: &str) -> SelectStatement {
+    pub fn domain_select(schema: &str, quirks: SqlQuirks) -> SelectStatement {
         domain_select_custom(schema, DOMAIN_DEFAULT_SELECT_CLAUSE)
+            .expr(Expr::cust(domain_expiry_status_clause(quirks)))
+            .expr(Expr::cust(domain_status_clause()))
+            .to_owned()
     }
 
     pub fn domain_select_custom(schema: &str, select: &str) -> SelectStatement {
         sea_query::Query::select()
             .expr(Expr::cust(select))
-            .from((Alias::new(schema), Alias::new("domain")))
+            .from(schema_table(schema, "domain"))
             .to_owned()
     }
 }
-use crate::subgraphs_reader::{sql::bind_string_list, DomainPaginationInput};
+use crate::subgraphs_reader::{
+    sql::{bind_string_list, in_chunks, QueryOptions, SqlQuirks},
+    DomainPaginationInput,
+};
 use sql_gen::QueryBuilderExt;
 
 const DETAILED_DOMAIN_DEFAULT_SELECT_CLAUSE: &str = r#"
@@ -83,8 +155,7 @@ to_timestamp(created_at) as registration_date,
 owner,
 registrant,
 wrapped_owner,
-to_timestamp(expiry_date) as expiry_date,
-COALESCE(to_timestamp(expiry_date) < now(), false) AS is_expired
+to_timestamp(expiry_date) as expiry_date
 "#;
 
 const DOMAIN_DEFAULT_SELECT_CLAUSE: &str = r#"
@@ -94,9 +165,9 @@ resolved_address,
 created_at,
 to_timestamp(created_at) as registration_date,
 owner,
+registrant,
 wrapped_owner,
-to_timestamp(expiry_date) as expiry_date,
-COALESCE(to_timestamp(expiry_date) < now(), false) AS is_expired
+to_timestamp(expiry_date) as expiry_date
 "#;
 
 // `block_range @>` is special sql syntax for fast filtering int4range
@@ -106,80 +177,857 @@ pub const DOMAIN_BLOCK_RANGE_WHERE_CLAUSE: &str = "block_range @> 2147483647";
 
 pub const DOMAIN_NONEMPTY_LABEL_WHERE_CLAUSE: &str = "label_name IS NOT NULL";
 
-pub const DOMAIN_NOT_EXPIRED_WHERE_CLAUSE: &str = r#"
+pub const ZERO_ADDRESS: &str = "0x0000000000000000000000000000000000000000";
+
+/// Domain is not expired until the end of grace period after `expiry_date`.
+/// Domains of protocols without expiration are always active
+pub fn domain_not_expired_where_clause(quirks: SqlQuirks) -> String {
+    if !quirks.expiring_domains {
+        return "TRUE".to_string();
+    }
+    let grace_period_days = quirks.grace_period_days;
+    format!(
+        r#"
 (
     expiry_date is null
-    OR to_timestamp(expiry_date) > now()
+    OR to_timestamp(expiry_date) + interval '{grace_period_days} days' > now()
 )
-"#;
+"#
+    )
+}
+
+fn domain_expiry_status_clause(quirks: SqlQuirks) -> String {
+    if !quirks.expiring_domains {
+        return "'active' AS expiry_status".to_string();
+    }
+    let grace_period_days = quirks.grace_period_days;
+    format!(
+        r#"
+CASE
+    WHEN expiry_date is null OR to_timestamp(expiry_date) > now() THEN 'active'
+    WHEN to_timestamp(expiry_date) + interval '{grace_period_days} days' > now() THEN 'in_grace_period'
+    ELSE 'expired'
+END AS expiry_status
+"#
+    )
+}
+
+/// Domain is burned if it is owned by zero address and doesn't resolve to any address
+pub fn domain_not_burned_where_clause() -> String {
+    format!(
+        r#"
+(
+    owner <> '{ZERO_ADDRESS}'
+    OR (resolved_address IS NOT NULL AND resolved_address <> '{ZERO_ADDRESS}')
+)
+"#
+    )
+}
 
-// TODO: rewrite to sea_query generation
-#[instrument(name = "get_domain", skip(pool), err(level = "error"), level = "info")]
+fn domain_status_clause() -> String {
+    format!(
+        r#"
+CASE
+    WHEN resolved_address IS NOT NULL AND resolved_address <> '{ZERO_ADDRESS}' THEN 'resolved'
+    WHEN owner = '{ZERO_ADDRESS}' THEN 'burned'
+    ELSE 'unresolved'
+END AS status
+"#
+    )
+}
+
+#[instrument(
+    name = "get_domain",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
 pub async fn get_domain(
     pool: &PgPool,
+    options: &QueryOptions,
     domain_name: &DomainName,
     schema: &str,
     input: &GetDomainInput,
+    quirks: SqlQuirks,
 ) -> Result<Option<DetailedDomain>, SubgraphReadError> {
-    let only_active_clause = input
-        .only_active
-        .then(|| format!("AND {DOMAIN_NOT_EXPIRED_WHERE_CLAUSE}"))
-        .unwrap_or_default();
-    let maybe_domain = sqlx::query_as(&format!(
-        r#"
-        SELECT
-            {DETAILED_DOMAIN_DEFAULT_SELECT_CLAUSE},
-            COALESCE(
-                multi_coin_addresses.coin_to_addr,
-                '{{}}'::json
-            ) as other_addresses
-        FROM {schema}.domain
-        LEFT JOIN (
-            SELECT 
-                d.id as domain_id, json_object_agg(mac.coin_type, encode(mac.addr, 'hex')) AS coin_to_addr 
-            FROM {schema}.domain d
-            LEFT JOIN {schema}.multicoin_addr_changed mac ON d.resolver = mac.resolver
-            WHERE 
-                d.id = $1
-                AND d.{DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
-                AND mac.coin_type IS NOT NULL
-                AND mac.addr IS NOT NULL
-            GROUP BY d.id
-        ) multi_coin_addresses ON {schema}.domain.id = multi_coin_addresses.domain_id
-        WHERE 
-            id = $1 
-            AND {DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
-        {only_active_clause}
-        ;"#,
-    ))
-    .bind(&domain_name.id)
-    .fetch_optional(pool)
-    .await?;
+    get_domain_by_id(
+        pool,
+        options,
+        &domain_name.id,
+        schema,
+        input.only_active,
+        quirks,
+    )
+    .await
+}
+
+#[instrument(
+    name = "get_domain_by_id",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn get_domain_by_id(
+    pool: &PgPool,
+    options: &QueryOptions,
+    id: &str,
+    schema: &str,
+    only_active: bool,
+    quirks: SqlQuirks,
+) -> Result<Option<DetailedDomain>, SubgraphReadError> {
+    let (sql, values) = get_domain_sql(schema, id, only_active, quirks);
+    let maybe_domain = options
+        .run(pool, "get_domain", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_optional(&mut **conn)
+                .boxed()
+        })
+        .await?;
     Ok(maybe_domain)
 }
 
+/// True if current domain with `id` exists, regardless of its expiry
+#[instrument(
+    name = "domain_exists",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn domain_exists(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    id: &str,
+) -> Result<bool, SubgraphReadError> {
+    let (sql, values) = Query::select()
+        .expr(Expr::exists(
+            Query::select()
+                .expr(Expr::val(1))
+                .from(sql_gen::schema_table(schema, "domain"))
+                .and_where(Expr::col(Alias::new("id")).eq(id))
+                .with_block_range()
+                .to_owned(),
+        ))
+        .build_sqlx(PostgresQueryBuilder);
+    let exists = options
+        .run(pool, "domain_exists", schema, |conn| {
+            sqlx::query_scalar_with(&sql, values.clone())
+                .fetch_one(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(exists)
+}
+
+/// Id of current domain with given labelhash and parent from `parent_ids`
+/// (any parent if list is empty). The earliest created domain is returned if several match
+#[instrument(
+    name = "find_domain_id_by_labelhash",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn find_domain_id_by_labelhash(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    labelhash: &str,
+    parent_ids: &[String],
+) -> Result<Option<String>, SubgraphReadError> {
+    let labelhash = labelhash.trim_start_matches("0x");
+    let mut query = Query::select();
+    query
+        .column(Alias::new("id"))
+        .from(sql_gen::schema_table(schema, "domain"))
+        .and_where(
+            Expr::col(Alias::new("labelhash"))
+                .eq(Func::cust(Alias::new("decode")).args([labelhash.into(), "hex".into()])),
+        )
+        .with_block_range();
+    if !parent_ids.is_empty() {
+        query.and_where(
+            Expr::col(Alias::new("parent"))
+                .eq(PgFunc::any(Expr::val(bind_string_list(parent_ids)))),
+        );
+    }
+    let (sql, values) = query
+        .order_by(Alias::new("created_at"), Order::Asc)
+        .limit(1)
+        .build_sqlx(PostgresQueryBuilder);
+    let maybe_id = options
+        .run(pool, "find_domain_id_by_labelhash", schema, |conn| {
+            sqlx::query_scalar_with(&sql, values.clone())
+                .fetch_optional(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(maybe_id)
+}
+
+/// Resolver of the first of `ids` with resolver set, like `0x{address}-0x{node}`.
+/// Used to find wildcard resolver of the closest ancestor, so `ids` start from the closest one
+#[instrument(
+    name = "find_closest_resolver",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn find_closest_resolver(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    ids: &[String],
+) -> Result<Option<String>, SubgraphReadError> {
+    let ids = bind_string_list(ids);
+    let (sql, values) = Query::select()
+        .column(Alias::new("resolver"))
+        .from(sql_gen::schema_table(schema, "domain"))
+        .and_where(Expr::col(Alias::new("id")).eq(PgFunc::any(Expr::val(ids.clone()))))
+        .and_where(Expr::col(Alias::new("resolver")).is_not_null())
+        .with_block_range()
+        .order_by_expr(
+            Func::cust(Alias::new("array_position"))
+                .args([Expr::val(ids).into(), Expr::col(Alias::new("id")).into()])
+                .into(),
+            Order::Asc,
+        )
+        .limit(1)
+        .build_sqlx(PostgresQueryBuilder);
+    let maybe_resolver = options
+        .run(pool, "find_closest_resolver", schema, |conn| {
+            sqlx::query_scalar_with(&sql, values.clone())
+                .fetch_optional(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(maybe_resolver)
+}
+
+fn get_domain_sql(
+    schema: &str,
+    id: &str,
+    only_active: bool,
+    quirks: SqlQuirks,
+) -> (String, SqlxValues) {
+    let domain_col = |col: &str| (Alias::new(schema), Alias::new("domain"), Alias::new(col));
+    let multi_coin_addresses = Query::select()
+        .expr_as(
+            Expr::col((Alias::new("d"), Alias::new("id"))),
+            Alias::new("domain_id"),
+        )
+        .expr_as(
+            Expr::cust("json_object_agg(mac.coin_type, encode(mac.addr, 'hex'))"),
+            Alias::new("coin_to_addr"),
+        )
+        .from_as(sql_gen::schema_table(schema, "domain"), Alias::new("d"))
+        .join_as(
+            JoinType::LeftJoin,
+            sql_gen::schema_table(schema, "multicoin_addr_changed"),
+            Alias::new("mac"),
+            Expr::col((Alias::new("d"), Alias::new("resolver")))
+                .equals((Alias::new("mac"), Alias::new("resolver"))),
+        )
+        .and_where(Expr::col((Alias::new("d"), Alias::new("id"))).eq(id))
+        .and_where(sql_gen::block_range_of("d"))
+        .and_where(Expr::col((Alias::new("mac"), Alias::new("coin_type"))).is_not_null())
+        .and_where(Expr::col((Alias::new("mac"), Alias::new("addr"))).is_not_null())
+        .group_by_col((Alias::new("d"), Alias::new("id")))
+        .to_owned();
+    let content_hash = Query::select()
+        .column((Alias::new("ch"), Alias::new("hash")))
+        .from_as(
+            sql_gen::schema_table(schema, "contenthash_changed"),
+            Alias::new("ch"),
+        )
+        .and_where(
+            Expr::col((Alias::new("ch"), Alias::new("resolver"))).equals(domain_col("resolver")),
+        )
+        .order_by((Alias::new("ch"), Alias::new("block_number")), Order::Desc)
+        .limit(1)
+        .to_owned();
+
+    let mut query = sql_gen::detailed_domain_select(schema, quirks);
+    let mut q = query
+        .expr_as(
+            Expr::cust("COALESCE(multi_coin_addresses.coin_to_addr, '{}'::json)"),
+            Alias::new("other_addresses"),
+        )
+        .expr_as(sql_gen::sub_query(content_hash), Alias::new("content_hash"))
+        .expr_as(
+            Expr::cust("COALESCE(texts.key_to_value, '{}'::json)"),
+            Alias::new("text_records"),
+        )
+        .join_subquery(
+            JoinType::LeftJoin,
+            multi_coin_addresses,
+            Alias::new("multi_coin_addresses"),
+            Expr::col(domain_col("id"))
+                .equals((Alias::new("multi_coin_addresses"), Alias::new("domain_id"))),
+        )
+        .join_subquery(
+            JoinType::LeftJoin,
+            text_records_json(schema, id),
+            Alias::new("texts"),
+            Expr::col(domain_col("resolver"))
+                .equals((Alias::new("texts"), Alias::new("text_resolver"))),
+        )
+        .and_where(Expr::col(domain_col("id")).eq(id))
+        .with_block_range();
+    if only_active {
+        q = q.with_not_expired(quirks);
+    }
+    q.build_sqlx(PostgresQueryBuilder)
+}
+
+#[instrument(
+    name = "get_domain_records",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn get_domain_records(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    id: &str,
+) -> Result<Option<DomainRecordsRow>, SubgraphReadError> {
+    let (sql, values) = domain_records_sql(schema, id);
+    let maybe_records = options
+        .run(pool, "get_domain_records", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_optional(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(maybe_records)
+}
+
+/// Latest `value` of every `key` of `table` changed by resolver of domain `id`
+fn latest_of_resolver(
+    schema: &str,
+    id: &str,
+    table: &str,
+    key: &str,
+    value: &str,
+) -> SelectStatement {
+    let col = |col: &str| (Alias::new("c"), Alias::new(col));
+    let d_col = |col: &str| (Alias::new("d"), Alias::new(col));
+    Query::select()
+        .distinct_on([col(key)])
+        .columns([col("resolver"), col(key), col(value)])
+        .from_as(sql_gen::schema_table(schema, table), Alias::new("c"))
+        .join_as(
+            JoinType::InnerJoin,
+            sql_gen::schema_table(schema, "domain"),
+            Alias::new("d"),
+            Expr::col(d_col("resolver")).equals(col("resolver")),
+        )
+        .and_where(Expr::col(d_col("id")).eq(id))
+        .and_where(sql_gen::block_range_of("d"))
+        .order_by(col(key), Order::Asc)
+        .order_by(col("block_number"), Order::Desc)
+        .order_by(col("vid"), Order::Desc)
+        .to_owned()
+}
+
+/// Text records of resolver of domain `id` as json object `key_to_value`, resolver is
+/// returned as `text_resolver`, so it's not ambiguous with unqualified columns of domain.
+/// Keys with removed value are skipped
+fn text_records_json(schema: &str, id: &str) -> SelectStatement {
+    Query::select()
+        .expr_as(
+            Expr::col(Alias::new("resolver")),
+            Alias::new("text_resolver"),
+        )
+        .expr_as(
+            Expr::cust("json_object_agg(key, value)"),
+            Alias::new("key_to_value"),
+        )
+        .from_subquery(
+            latest_of_resolver(schema, id, "text_changed", "key", "value"),
+            Alias::new("t"),
+        )
+        .and_where(Expr::col(Alias::new("value")).is_not_null())
+        .group_by_col(Alias::new("resolver"))
+        .to_owned()
+}
+
+/// Latest multicoin addresses, text records and contenthash of current domain resolver.
+/// Removed addresses and text values are skipped
+fn domain_records_sql(schema: &str, id: &str) -> (String, SqlxValues) {
+    let d_col = |col: &str| (Alias::new("d"), Alias::new(col));
+    let addresses = Query::select()
+        .column(Alias::new("resolver"))
+        .expr_as(
+            Expr::cust("json_object_agg(coin_type, encode(addr, 'hex'))"),
+            Alias::new("coin_to_addr"),
+        )
+        .from_subquery(
+            latest_of_resolver(schema, id, "multicoin_addr_changed", "coin_type", "addr"),
+            Alias::new("a"),
+        )
+        .and_where(Expr::cust("octet_length(addr) > 0"))
+        .group_by_col(Alias::new("resolver"))
+        .to_owned();
+    let texts = text_records_json(schema, id);
+    let content_hash = Query::select()
+        .column((Alias::new("ch"), Alias::new("hash")))
+        .from_as(
+            sql_gen::schema_table(schema, "contenthash_changed"),
+            Alias::new("ch"),
+        )
+        .and_where(Expr::col((Alias::new("ch"), Alias::new("resolver"))).equals(d_col("resolver")))
+        .order_by((Alias::new("ch"), Alias::new("block_number")), Order::Desc)
+        .limit(1)
+        .to_owned();
+
+    Query::select()
+        .column(d_col("id"))
+        .expr_as(
+            Expr::cust("COALESCE(addresses.coin_to_addr, '{}'::json)"),
+            Alias::new("addresses"),
+        )
+        .expr_as(
+            Expr::cust("COALESCE(texts.key_to_value, '{}'::json)"),
+            Alias::new("text_records"),
+        )
+        .expr_as(sql_gen::sub_query(content_hash), Alias::new("content_hash"))
+        .from_as(sql_gen::schema_table(schema, "domain"), Alias::new("d"))
+        .join_subquery(
+            JoinType::LeftJoin,
+            addresses,
+            Alias::new("addresses"),
+            Expr::col(d_col("resolver")).equals((Alias::new("addresses"), Alias::new("resolver"))),
+        )
+        .join_subquery(
+            JoinType::LeftJoin,
+            texts,
+            Alias::new("texts"),
+            Expr::col(d_col("resolver")).equals((Alias::new("texts"), Alias::new("text_resolver"))),
+        )
+        .and_where(Expr::col(d_col("id")).eq(id))
+        .and_where(sql_gen::block_range_of("d"))
+        .build_sqlx(PostgresQueryBuilder)
+}
+
+#[instrument(
+    name = "find_resolved_address_changes",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn find_resolved_address_changes(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    id: &str,
+) -> Result<Vec<AddrChangedEvent>, SubgraphReadError> {
+    let (sql, values) = resolved_address_changes_sql(schema, id);
+    let events = options
+        .run(pool, "find_resolved_address_changes", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(events)
+}
+
+/// `addr_changed` events of all resolvers ever set for domain, in chronological order.
+/// Same event could be emitted several times in one transaction, so duplicates are grouped
+fn resolved_address_changes_sql(schema: &str, id: &str) -> (String, SqlxValues) {
+    let ac_col = |col: &str| (Alias::new("ac"), Alias::new(col));
+    Query::select()
+        .column(ac_col("addr"))
+        .column(ac_col("block_number"))
+        .column(ac_col("transaction_id"))
+        .from_as(
+            sql_gen::schema_table(schema, "addr_changed"),
+            Alias::new("ac"),
+        )
+        .join_as(
+            JoinType::InnerJoin,
+            sql_gen::schema_table(schema, "resolver"),
+            Alias::new("r"),
+            Expr::col((Alias::new("r"), Alias::new("id"))).equals(ac_col("resolver")),
+        )
+        .and_where(Expr::col((Alias::new("r"), Alias::new("domain"))).eq(id))
+        .group_by_columns([
+            ac_col("block_number"),
+            ac_col("transaction_id"),
+            ac_col("addr"),
+        ])
+        .order_by(ac_col("block_number"), Order::Asc)
+        .order_by_expr(Expr::cust("MIN(ac.vid)"), Order::Asc)
+        .build_sqlx(PostgresQueryBuilder)
+}
+
+#[instrument(
+    name = "find_domain_changes",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn find_domain_changes(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    after_block: i64,
+    to_block: i64,
+) -> Result<Vec<DomainChangeRow>, SubgraphReadError> {
+    let (sql, values) = domain_changes_sql(schema, after_block, to_block);
+    let rows = options
+        .run(pool, "find_domain_changes", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(rows)
+}
+
+/// Changes of owner, wrapped owner and resolved address of all domains
+/// in blocks `(after_block, to_block]`, in chronological order
+fn domain_changes_sql(schema: &str, after_block: i64, to_block: i64) -> (String, SqlxValues) {
+    let col = |table: &str, col: &str| (Alias::new(table), Alias::new(col));
+    let in_blocks = |table: &str| {
+        Condition::all()
+            .add(Expr::col(col(table, "block_number")).gt(after_block))
+            .add(Expr::col(col(table, "block_number")).lte(to_block))
+    };
+    let transfers = |table: &str, kind: &str| {
+        Query::select()
+            .expr_as(Expr::col(col("t", "domain")), Alias::new("domain_id"))
+            .expr_as(Expr::val(kind), Alias::new("kind"))
+            .expr_as(Expr::col(col("t", "owner")), Alias::new("value"))
+            .column(col("t", "block_number"))
+            .column(col("t", "transaction_id"))
+            .expr_as(Expr::cust("MIN(t.vid)"), Alias::new("vid"))
+            .from_as(sql_gen::schema_table(schema, table), Alias::new("t"))
+            .cond_where(in_blocks("t"))
+            .group_by_columns([
+                col("t", "domain"),
+                col("t", "owner"),
+                col("t", "block_number"),
+                col("t", "transaction_id"),
+            ])
+            .to_owned()
+    };
+    // same event could be emitted several times in one transaction
+    // and resolver could have several versions, so duplicates are grouped
+    let addr_changes = Query::select()
+        .expr_as(Expr::col(col("r", "domain")), Alias::new("domain_id"))
+        .expr_as(Expr::val("resolved_address"), Alias::new("kind"))
+        .expr_as(Expr::col(col("ac", "addr")), Alias::new("value"))
+        .column(col("ac", "block_number"))
+        .column(col("ac", "transaction_id"))
+        .expr_as(Expr::cust("MIN(ac.vid)"), Alias::new("vid"))
+        .from_as(
+            sql_gen::schema_table(schema, "addr_changed"),
+            Alias::new("ac"),
+        )
+        .join_as(
+            JoinType::InnerJoin,
+            sql_gen::schema_table(schema, "resolver"),
+            Alias::new("r"),
+            Expr::col(col("r", "id")).equals(col("ac", "resolver")),
+        )
+        .cond_where(in_blocks("ac"))
+        .and_where(Expr::col(col("r", "domain")).is_not_null())
+        .group_by_columns([
+            col("r", "domain"),
+            col("ac", "addr"),
+            col("ac", "block_number"),
+            col("ac", "transaction_id"),
+        ])
+        .to_owned();
+    let changes = transfers("transfer", "owner")
+        .union(
+            UnionType::All,
+            transfers("wrapped_transfer", "wrapped_owner"),
+        )
+        .union(UnionType::All, addr_changes)
+        .to_owned();
+
+    Query::select()
+        .columns([
+            col("c", "domain_id"),
+            col("c", "kind"),
+            col("c", "value"),
+            col("c", "block_number"),
+            col("c", "transaction_id"),
+        ])
+        .expr_as(Expr::col(col("d", "name")), Alias::new("domain_name"))
+        .from_subquery(changes, Alias::new("c"))
+        .join_as(
+            JoinType::LeftJoin,
+            sql_gen::schema_table(schema, "domain"),
+            Alias::new("d"),
+            Condition::all()
+                .add(Expr::col(col("d", "id")).equals(col("c", "domain_id")))
+                .add(sql_gen::block_range_of("d")),
+        )
+        .order_by(col("c", "block_number"), Order::Asc)
+        .order_by(col("c", "vid"), Order::Asc)
+        .build_sqlx(PostgresQueryBuilder)
+}
+
 #[instrument(
     name = "find_domains",
-    skip(pool),
+    skip(pool, options),
     err(level = "error"),
     level = "info"
 )]
+#[allow(clippy::too_many_arguments)]
 pub async fn find_domains(
     pool: &PgPool,
+    options: &QueryOptions,
     schema: &str,
     domain_names: Option<Vec<&DomainName>>,
-    only_active: bool,
+    state: DomainStateFilter,
+    quirks: SqlQuirks,
+    tld_rules: &TldRules,
     pagination: Option<&DomainPaginationInput>,
 ) -> Result<Vec<Domain>, SubgraphReadError> {
-    let mut query = sql_gen::domain_select(schema);
-    let mut q = query.with_block_range();
-    if only_active {
-        q = q.with_not_expired();
+    let (sql, values) = select_domains(
+        schema,
+        None,
+        domain_names,
+        state,
+        quirks,
+        tld_rules,
+        pagination,
+    )?
+    .build_sqlx(PostgresQueryBuilder);
+    tracing::debug!(sql = sql, "build SQL query for 'find_domains'");
+    let domains = options
+        .run(pool, "find_domains", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(domains)
+}
+
+#[instrument(
+    name = "count_domains",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn count_domains(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    domain_names: Option<Vec<&DomainName>>,
+    state: DomainStateFilter,
+    quirks: SqlQuirks,
+    tld_rules: &TldRules,
+) -> Result<i64, SubgraphReadError> {
+    let (sql, values) = select_domains(
+        schema,
+        Some("COUNT(*)"),
+        domain_names,
+        state,
+        quirks,
+        tld_rules,
+        None,
+    )?
+    .build_sqlx(PostgresQueryBuilder);
+    let count: i64 = options
+        .run(pool, "count_domains", schema, |conn| {
+            sqlx::query_scalar_with(&sql, values.clone())
+                .fetch_one(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(count)
+}
+
+/// State of index of subgraph schema, see `pg_index.indisvalid`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexState {
+    Missing,
+    /// Index is being built concurrently, possibly by another instance
+    Building,
+    /// Concurrent build of index failed or was interrupted, index is not used by planner
+    Invalid,
+    Valid,
+}
+
+pub async fn domain_name_search_index_state(
+    pool: &PgPool,
+    schema: &str,
+) -> Result<IndexState, SubgraphReadError> {
+    // index being built concurrently is not valid until build is finished
+    let maybe_state: Option<(bool, bool)> = sqlx::query_as(
+        r#"
+        SELECT
+            i.indisvalid,
+            EXISTS (
+                SELECT 1 FROM pg_stat_progress_create_index p WHERE p.index_relid = i.indexrelid
+            )
+        FROM pg_index i
+        JOIN pg_class c ON c.oid = i.indexrelid
+        JOIN pg_namespace n ON n.oid = c.relnamespace
+        WHERE n.nspname = $1 AND c.relname = 'domain_name_search'
+        "#,
+    )
+    .bind(schema)
+    .fetch_optional(pool)
+    .await?;
+    Ok(match maybe_state {
+        None => IndexState::Missing,
+        Some((true, _)) => IndexState::Valid,
+        Some((false, true)) => IndexState::Building,
+        Some((false, false)) => IndexState::Invalid,
+    })
+}
+
+/// Index for prefix search of current domains by name. `C` collation allows
+/// to use it both for `LIKE 'prefix%'` filter and for ordering of matches.
+/// Invalid index left by failed concurrent build is dropped and built again.
+/// Returns state of index before the call
+pub async fn ensure_domain_name_search_index(
+    pool: &PgPool,
+    schema: &str,
+) -> Result<IndexState, SubgraphReadError> {
+    let state = domain_name_search_index_state(pool, schema).await?;
+    if matches!(state, IndexState::Valid | IndexState::Building) {
+        return Ok(state);
+    }
+    // concurrent build and drop don't lock writes of graph-node to domain table
+    if state == IndexState::Invalid {
+        sqlx::query(&format!(
+            "DROP INDEX CONCURRENTLY IF EXISTS {schema}.domain_name_search"
+        ))
+        .execute(pool)
+        .await?;
+    }
+    sqlx::query(&format!(
+        r#"
+        CREATE INDEX CONCURRENTLY IF NOT EXISTS domain_name_search
+        ON {schema}.domain (name COLLATE "C")
+        WHERE {DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
+        "#
+    ))
+    .execute(pool)
+    .await?;
+    Ok(state)
+}
+
+#[instrument(
+    name = "quick_search_domains",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn quick_search_domains(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    exact_name: Option<&DomainName>,
+    prefix: &str,
+    limit: u32,
+    rank: QuickSearchRank,
+    quirks: SqlQuirks,
+) -> Result<Vec<Domain>, SubgraphReadError> {
+    let (sql, values) = quick_search_domains_sql(schema, exact_name, prefix, limit, rank, quirks);
+    let domains = options
+        .run(pool, "quick_search_domains", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(domains)
+}
+
+/// Active domain with exact name goes first, then active domains with name starting
+/// with `prefix` in order of `rank`
+fn quick_search_domains_sql(
+    schema: &str,
+    exact_name: Option<&DomainName>,
+    prefix: &str,
+    limit: u32,
+    rank: QuickSearchRank,
+    quirks: SqlQuirks,
+) -> (String, SqlxValues) {
+    let sort_name = || Expr::cust(r#"name COLLATE "C""#);
+    let order_by_rank = |query: &mut SelectStatement| {
+        if rank == QuickSearchRank::RegistrationDate {
+            query.order_by(Alias::new("created_at"), Order::Desc);
+        }
+    };
+    let matches = |rank: i32| {
+        sql_gen::domain_select(schema, quirks)
+            .expr_as(Expr::val(rank), Alias::new("search_rank"))
+            .expr_as(sort_name(), Alias::new("sort_name"))
+            .with_block_range()
+            .with_non_empty_label()
+            .with_not_expired(quirks)
+            .to_owned()
+    };
+    let pattern = format!(
+        "{}%",
+        prefix
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    );
+    let mut prefix_matches = matches(1)
+        .and_where(Expr::expr(sort_name()).like(pattern))
+        .and_where(Expr::cust("name NOT LIKE '%[%'"))
+        .to_owned();
+    order_by_rank(&mut prefix_matches);
+    prefix_matches
+        .order_by_expr(sort_name(), Order::Asc)
+        .limit(limit as u64);
+    let mut query = match exact_name {
+        Some(exact_name) => {
+            prefix_matches.and_where(Expr::col(Alias::new("id")).ne(&exact_name.id));
+            matches(0)
+                .and_where(Expr::col(Alias::new("id")).eq(&exact_name.id))
+                .union(UnionType::All, prefix_matches)
+                .to_owned()
+        }
+        None => prefix_matches,
     };
-    if domain_names.is_some() {
-        q = q.and_where(Expr::cust("id = ANY($1)"));
+    query
+        .clear_order_by()
+        .order_by(Alias::new("search_rank"), Order::Asc);
+    order_by_rank(&mut query);
+    query
+        .order_by(Alias::new("sort_name"), Order::Asc)
+        .limit(limit as u64)
+        .build_sqlx(PostgresQueryBuilder)
+}
+
+fn select_domains(
+    schema: &str,
+    select_clause: Option<&str>,
+    domain_names: Option<Vec<&DomainName>>,
+    state: DomainStateFilter,
+    quirks: SqlQuirks,
+    tld_rules: &TldRules,
+    pagination: Option<&DomainPaginationInput>,
+) -> Result<SelectStatement, SubgraphReadError> {
+    let mut query = if let Some(select_clause) = select_clause {
+        sql_gen::domain_select_custom(schema, select_clause)
+    } else {
+        sql_gen::domain_select(schema, quirks)
+    };
+    let mut q = query.with_block_range().with_state_filter(state, quirks);
+    if let Some(domain_names) = domain_names {
+        let ids = domain_names
+            .iter()
+            .map(|d| d.id.clone())
+            .collect::<Vec<String>>();
+        q = q.and_where(Expr::col(Alias::new("id")).eq(PgFunc::any(Expr::val(ids))));
     } else {
-        q = q.with_non_empty_label().with_resolved_names();
+        q = q.with_non_empty_label().with_resolved_names(tld_rules);
     }
 
     if let Some(pagination) = pagination {
@@ -188,111 +1036,608 @@ pub async fn find_domains(
             .context("adding pagination to query")
             .map_err(|e| SubgraphReadError::Internal(e.to_string()))?;
     }
+    Ok(q.to_owned())
+}
 
-    let sql = q.to_string(PostgresQueryBuilder);
-    let mut query = sqlx::query_as(&sql);
-    tracing::debug!(sql = sql, "build SQL query for 'find_domains'");
-    if let Some(domain_names) = domain_names {
-        query = query.bind(
-            domain_names
-                .iter()
-                .map(|d| d.id.as_str())
-                .collect::<Vec<_>>(),
-        );
-    };
-    let domains = query.fetch_all(pool).await?;
+#[instrument(
+    name = "find_subdomains",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn find_subdomains(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    parent_id: &str,
+    state: DomainStateFilter,
+    quirks: SqlQuirks,
+    pagination: &DomainPaginationInput,
+) -> Result<Vec<Domain>, SubgraphReadError> {
+    let (sql, values) =
+        select_subdomains(schema, None, parent_id, state, quirks, Some(pagination))?
+            .build_sqlx(PostgresQueryBuilder);
+    let domains = options
+        .run(pool, "find_subdomains", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
+        .await?;
     Ok(domains)
 }
 
+#[instrument(
+    name = "count_subdomains",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn count_subdomains(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    parent_id: &str,
+    state: DomainStateFilter,
+    quirks: SqlQuirks,
+) -> Result<i64, SubgraphReadError> {
+    let (sql, values) =
+        select_subdomains(schema, Some("COUNT(*)"), parent_id, state, quirks, None)?
+            .build_sqlx(PostgresQueryBuilder);
+    let count: i64 = options
+        .run(pool, "count_subdomains", schema, |conn| {
+            sqlx::query_scalar_with(&sql, values.clone())
+                .fetch_one(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(count)
+}
+
+fn select_subdomains(
+    schema: &str,
+    select_clause: Option<&str>,
+    parent_id: &str,
+    state: DomainStateFilter,
+    quirks: SqlQuirks,
+    pagination: Option<&DomainPaginationInput>,
+) -> Result<SelectStatement, SubgraphReadError> {
+    let mut query = if let Some(select_clause) = select_clause {
+        sql_gen::domain_select_custom(schema, select_clause)
+    } else {
+        sql_gen::domain_select(schema, quirks)
+    };
+    let q = query
+        .with_block_range()
+        .and_where(Expr::col(Alias::new("parent")).eq(parent_id))
+        .with_state_filter(state, quirks);
+    if let Some(pagination) = pagination {
+        pagination
+            .add_to_query(q)
+            .context("adding pagination to query")
+            .map_err(|e| SubgraphReadError::Internal(e.to_string()))?;
+    }
+    Ok(q.to_owned())
+}
+
+#[instrument(
+    name = "find_text_records",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn find_text_records(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    id: &str,
+) -> Result<Vec<TextRecord>, SubgraphReadError> {
+    let (sql, values) = text_records_sql(schema, id);
+    let records = options
+        .run(pool, "find_text_records", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(records)
+}
+
+/// Latest value of every text key of current domain resolver, sorted by key.
+/// Keys with removed value are skipped
+fn text_records_sql(schema: &str, id: &str) -> (String, SqlxValues) {
+    let tc_col = |col: &str| (Alias::new("tc"), Alias::new(col));
+    let latest_values = Query::select()
+        .distinct_on([tc_col("key")])
+        .column(tc_col("key"))
+        .column(tc_col("value"))
+        .from_as(
+            sql_gen::schema_table(schema, "text_changed"),
+            Alias::new("tc"),
+        )
+        .join_as(
+            JoinType::InnerJoin,
+            sql_gen::schema_table(schema, "domain"),
+            Alias::new("d"),
+            Expr::col((Alias::new("d"), Alias::new("resolver"))).equals(tc_col("resolver")),
+        )
+        .and_where(Expr::col((Alias::new("d"), Alias::new("id"))).eq(id))
+        .and_where(sql_gen::block_range_of("d"))
+        .order_by(tc_col("key"), Order::Asc)
+        .order_by(tc_col("block_number"), Order::Desc)
+        .order_by(tc_col("vid"), Order::Desc)
+        .to_owned();
+    Query::select()
+        .columns([Alias::new("key"), Alias::new("value")])
+        .from_subquery(latest_values, Alias::new("t"))
+        .and_where(Expr::col(Alias::new("value")).is_not_null())
+        .order_by(Alias::new("key"), Order::Asc)
+        .build_sqlx(PostgresQueryBuilder)
+}
+
 #[instrument(
     name = "find_resolved_addresses",
-    skip(pool),
+    skip(pool, options),
     err(level = "error"),
     level = "info"
 )]
+#[allow(clippy::too_many_arguments)]
 pub async fn find_resolved_addresses(
     pool: &PgPool,
+    options: &QueryOptions,
     schema: &str,
     input: &LookupAddressInput,
+    parent_id: Option<&str>,
+    address_resolve_technique: &AddressResolveTechnique,
+    reverse_registrar: &ReverseRegistrar,
+    tld_rules: &TldRules,
+    quirks: SqlQuirks,
 ) -> Result<Vec<Domain>, SubgraphReadError> {
-    let sql = gen_sql_select_domains_by_address(
+    let primary_name = primary_name_sql(
+        schema,
+        input.address,
+        address_resolve_technique,
+        reverse_registrar,
+        tld_rules,
+        quirks,
+    );
+    let (sql, values) = select_domains_by_address(
         schema,
         None,
-        input.only_active,
-        input.resolved_to,
-        input.owned_by,
+        &input.address_filter(),
+        quirks,
+        parent_id,
+        tld_rules,
         Some(&input.pagination),
-    )?;
+    )?
+    .expr_as(is_primary_expr(primary_name), Alias::new("is_primary"))
+    .build_sqlx(PostgresQueryBuilder);
 
-    let domains = sqlx::query_as(&sql)
-        .bind(hex(input.address))
-        .fetch_all(pool)
+    let domains = options
+        .run(pool, "find_resolved_addresses", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
         .await?;
     Ok(domains)
 }
 
 #[instrument(
     name = "count_domains_by_address",
-    skip(pool),
+    skip(pool, options),
     err(level = "error"),
     level = "info"
 )]
 pub async fn count_domains_by_address(
     pool: &PgPool,
+    options: &QueryOptions,
     schema: &str,
-    address: Address,
-    only_active: bool,
-    resolved_to: bool,
-    owned_by: bool,
+    filter: &AddressDomainsFilter,
+    quirks: SqlQuirks,
+    parent_id: Option<&str>,
+    tld_rules: &TldRules,
 ) -> Result<i64, SubgraphReadError> {
-    let sql = gen_sql_select_domains_by_address(
+    let (sql, values) = select_domains_by_address(
         schema,
         Some("COUNT(*)"),
-        only_active,
-        resolved_to,
-        owned_by,
+        filter,
+        quirks,
+        parent_id,
+        tld_rules,
         None,
-    )?;
+    )?
+    .build_sqlx(PostgresQueryBuilder);
+
+    let count: i64 = options
+        .run(pool, "count_domains_by_address", schema, |conn| {
+            sqlx::query_scalar_with(&sql, values.clone())
+                .fetch_one(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(count)
+}
+
+#[instrument(
+    name = "find_domains_by_multicoin_address",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn find_domains_by_multicoin_address(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    input: &LookupMulticoinAddressInput,
+    parent_id: Option<&str>,
+    tld_rules: &TldRules,
+    quirks: SqlQuirks,
+) -> Result<Vec<Domain>, SubgraphReadError> {
+    let (sql, values) = select_domains_by_multicoin_address(
+        schema,
+        None,
+        input,
+        quirks,
+        parent_id,
+        tld_rules,
+        Some(&input.pagination),
+    )?
+    .build_sqlx(PostgresQueryBuilder);
+    let domains = options
+        .run(pool, "find_domains_by_multicoin_address", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(domains)
+}
 
-    let count: i64 = sqlx::query_scalar(&sql)
-        .bind(hex(address))
-        .fetch_one(pool)
+#[instrument(
+    name = "count_domains_by_multicoin_address",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn count_domains_by_multicoin_address(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    input: &LookupMulticoinAddressInput,
+    parent_id: Option<&str>,
+    tld_rules: &TldRules,
+    quirks: SqlQuirks,
+) -> Result<i64, SubgraphReadError> {
+    let (sql, values) = select_domains_by_multicoin_address(
+        schema,
+        Some("COUNT(*)"),
+        input,
+        quirks,
+        parent_id,
+        tld_rules,
+        None,
+    )?
+    .build_sqlx(PostgresQueryBuilder);
+    let count: i64 = options
+        .run(pool, "count_domains_by_multicoin_address", schema, |conn| {
+            sqlx::query_scalar_with(&sql, values.clone())
+                .fetch_one(&mut **conn)
+                .boxed()
+        })
         .await?;
     Ok(count)
 }
 
-fn gen_sql_select_domains_by_address(
+/// Batch of domains of address ordered by `(created_at, id)`, which go after `after` key.
+/// Every batch is separate query, so connection is not held between batches
+#[instrument(
+    name = "find_domains_by_address_after",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn find_domains_by_address_after(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    input: &ExportAddressInput,
+    parent_id: Option<&str>,
+    address_resolve_technique: &AddressResolveTechnique,
+    reverse_registrar: &ReverseRegistrar,
+    tld_rules: &TldRules,
+    quirks: SqlQuirks,
+    after: Option<(&BigDecimal, &str)>,
+    batch_size: u32,
+) -> Result<Vec<Domain>, SubgraphReadError> {
+    let primary_name = primary_name_sql(
+        schema,
+        input.address,
+        address_resolve_technique,
+        reverse_registrar,
+        tld_rules,
+        quirks,
+    );
+    let mut query = select_domains_by_address(
+        schema,
+        None,
+        &input.address_filter(),
+        quirks,
+        parent_id,
+        tld_rules,
+        None,
+    )?;
+    if let Some((created_at, id)) = after {
+        query.and_where(Expr::cust_with_values(
+            "(created_at, id) > ($1::numeric, $2)",
+            [created_at.to_string(), id.to_string()],
+        ));
+    }
+    let (sql, values) = query
+        .expr_as(is_primary_expr(primary_name), Alias::new("is_primary"))
+        .order_by(Alias::new("created_at"), Order::Asc)
+        .order_by(Alias::new("id"), Order::Asc)
+        .limit(batch_size as u64)
+        .build_sqlx(PostgresQueryBuilder);
+
+    let domains = options
+        .run(pool, "find_domains_by_address_after", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(domains)
+}
+
+#[instrument(
+    name = "get_address_summary",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+#[allow(clippy::too_many_arguments)]
+pub async fn get_address_summary(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    input: &LookupAddressInput,
+    parent_id: Option<&str>,
+    address_resolve_technique: &AddressResolveTechnique,
+    reverse_registrar: &ReverseRegistrar,
+    tld_rules: &TldRules,
+    quirks: SqlQuirks,
+) -> Result<Vec<AddressSummaryRow>, SubgraphReadError> {
+    let (sql, values) = get_address_summary_sql(
+        schema,
+        input,
+        parent_id,
+        address_resolve_technique,
+        reverse_registrar,
+        tld_rules,
+        quirks,
+    )?;
+    let rows = options
+        .run(pool, "get_address_summary", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
+        .await?;
+    Ok(rows)
+}
+
+fn get_address_summary_sql(
+    schema: &str,
+    input: &LookupAddressInput,
+    parent_id: Option<&str>,
+    address_resolve_technique: &AddressResolveTechnique,
+    reverse_registrar: &ReverseRegistrar,
+    tld_rules: &TldRules,
+    quirks: SqlQuirks,
+) -> Result<(String, SqlxValues), SubgraphReadError> {
+    let filter = input.address_filter();
+    let resolved_count = select_domains_by_address(
+        schema,
+        Some("COUNT(*)"),
+        &AddressDomainsFilter {
+            resolved_to: true,
+            owned_by: false,
+            registered_by: false,
+            ..filter
+        },
+        quirks,
+        parent_id,
+        tld_rules,
+        None,
+    )?;
+    let owned_count = select_domains_by_address(
+        schema,
+        Some("COUNT(*)"),
+        &AddressDomainsFilter {
+            resolved_to: false,
+            owned_by: true,
+            registered_by: false,
+            ..filter
+        },
+        quirks,
+        parent_id,
+        tld_rules,
+        None,
+    )?;
+    let mut domains_page = select_domains_by_address(
+        schema,
+        None,
+        &filter,
+        quirks,
+        parent_id,
+        tld_rules,
+        Some(&input.pagination),
+    )?;
+    let primary_name = primary_name_sql(
+        schema,
+        filter.address,
+        address_resolve_technique,
+        reverse_registrar,
+        tld_rules,
+        quirks,
+    );
+    domains_page.expr_as(
+        is_primary_expr(primary_name.clone()),
+        Alias::new("is_primary"),
+    );
+
+    let cte = |query: SelectStatement, name: &str| {
+        CommonTableExpression::new()
+            .query(query)
+            .table_name(Alias::new(name))
+            .to_owned()
+    };
+    let select_cte = |name: &str| {
+        sql_gen::sub_query(
+            Query::select()
+                .expr(Expr::col(Asterisk))
+                .from(Alias::new(name))
+                .to_owned(),
+        )
+    };
+    let with_clause = WithClause::new()
+        .cte(cte(resolved_count, "resolved_count"))
+        .cte(cte(owned_count, "owned_count"))
+        .cte(cte(primary_name, "primary_name"))
+        .cte(cte(domains_page, "domains_page"))
+        .to_owned();
+    let sort_col = (
+        Alias::new("domains_page"),
+        Alias::new(input.pagination.sort.to_string()),
+    );
+    let query = Query::select()
+        .expr_as(select_cte("resolved_count"), Alias::new("resolved_count"))
+        .expr_as(select_cte("owned_count"), Alias::new("owned_count"))
+        .expr_as(select_cte("primary_name"), Alias::new("primary_name"))
+        .column((Alias::new("domains_page"), Asterisk))
+        .from_subquery(
+            Query::select().expr(Expr::cust("1")).to_owned(),
+            Alias::new("summary"),
+        )
+        .join(
+            JoinType::LeftJoin,
+            Alias::new("domains_page"),
+            Expr::cust("TRUE"),
+        )
+        .order_by(sort_col, input.pagination.order.to_database_field())
+        .to_owned()
+        .with(with_clause);
+    Ok(query.build_sqlx(PostgresQueryBuilder))
+}
+
+/// Verified primary name of address, at most one row with `name` column
+fn primary_name_sql(
+    schema: &str,
+    address: Address,
+    address_resolve_technique: &AddressResolveTechnique,
+    reverse_registrar: &ReverseRegistrar,
+    tld_rules: &TldRules,
+    quirks: SqlQuirks,
+) -> SelectStatement {
+    match address_resolve_technique {
+        // name from reverse record of `{addr}.{reverse_registrar.node_suffix}`
+        // counts only if this name resolves back to the address
+        AddressResolveTechnique::ReverseRegistry => {
+            let reversed = Query::select()
+                .column((Alias::new("nc"), Alias::new("name")))
+                .from_as(sql_gen::schema_table(schema, "domain"), Alias::new("rd"))
+                .join_as(
+                    JoinType::InnerJoin,
+                    sql_gen::schema_table(schema, "name_changed"),
+                    Alias::new("nc"),
+                    Expr::col((Alias::new("nc"), Alias::new("resolver")))
+                        .equals((Alias::new("rd"), Alias::new("resolver"))),
+                )
+                .and_where(
+                    Expr::col((Alias::new("rd"), Alias::new("id")))
+                        .eq(reverse_registrar.reverse_node(&address).id),
+                )
+                .and_where(sql_gen::block_range_of("rd"))
+                .order_by((Alias::new("nc"), Alias::new("block_number")), Order::Desc)
+                .limit(1)
+                .to_owned();
+            Query::select()
+                .column((Alias::new("d"), Alias::new("name")))
+                .from_as(sql_gen::schema_table(schema, "domain"), Alias::new("d"))
+                .join_subquery(
+                    JoinType::InnerJoin,
+                    reversed,
+                    Alias::new("reversed"),
+                    Expr::col((Alias::new("reversed"), Alias::new("name")))
+                        .equals((Alias::new("d"), Alias::new("name"))),
+                )
+                .and_where(
+                    Expr::col((Alias::new("d"), Alias::new("resolved_address"))).eq(hex(address)),
+                )
+                .and_where(sql_gen::block_range_of("d"))
+                .and_where(Expr::cust(domain_not_expired_where_clause(quirks)))
+                .limit(1)
+                .to_owned()
+        }
+        AddressResolveTechnique::AllDomains => sql_gen::domain_select_custom(schema, "name")
+            .and_where(Expr::col(Alias::new("resolved_address")).eq(hex(address)))
+            .with_block_range()
+            .with_non_empty_label()
+            .with_not_expired(quirks)
+            .with_resolved_names(tld_rules)
+            .order_by(Alias::new("created_at"), Order::Asc)
+            .limit(1)
+            .to_owned(),
+    }
+}
+
+/// True if name of domain is primary name selected by `primary_name` query
+fn is_primary_expr(primary_name: SelectStatement) -> SimpleExpr {
+    Expr::col(Alias::new("name")).in_subquery(primary_name)
+}
+
+fn select_domains_by_address(
     schema: &str,
     select_clause: Option<&str>,
-    only_active: bool,
-    resolved_to: bool,
-    owned_by: bool,
+    filter: &AddressDomainsFilter,
+    quirks: SqlQuirks,
+    parent_id: Option<&str>,
+    tld_rules: &TldRules,
     pagination: Option<&DomainPaginationInput>,
-) -> Result<String, SubgraphReadError> {
+) -> Result<SelectStatement, SubgraphReadError> {
     let mut query = if let Some(select_clause) = select_clause {
         sql_gen::domain_select_custom(schema, select_clause)
     } else {
-        sql_gen::domain_select(schema)
+        sql_gen::domain_select(schema, quirks)
     };
 
     let mut q = query
         .with_block_range()
         .with_non_empty_label()
-        .with_resolved_names();
-    if only_active {
-        q = q.with_not_expired();
-    };
+        .with_resolved_names(tld_rules)
+        .with_state_filter(filter.state, quirks);
 
-    // Trick: in resolved_to and owned_by are not provided, binding still exists and `cond` will be false
-    let mut main_cond = Condition::any().add(Expr::cust("$1 <> $1"));
-    if resolved_to {
-        main_cond = main_cond.add(Expr::cust("resolved_address = $1"));
+    // if none of resolved_to, owned_by and registered_by is provided, empty `any` condition is false
+    let address = hex(filter.address);
+    let mut main_cond = Condition::any();
+    if filter.resolved_to {
+        main_cond = main_cond.add(Expr::col(Alias::new("resolved_address")).eq(&address));
+    }
+    if filter.owned_by {
+        main_cond = main_cond.add(Expr::col(Alias::new("owner")).eq(&address));
+        if quirks.wrapped_owners {
+            main_cond = main_cond.add(Expr::col(Alias::new("wrapped_owner")).eq(&address));
+        }
     }
-    if owned_by {
-        main_cond = main_cond.add(Expr::cust("owner = $1"));
-        main_cond = main_cond.add(Expr::cust("wrapped_owner = $1"));
+    if filter.registered_by {
+        main_cond = main_cond.add(Expr::col(Alias::new("registrant")).eq(&address));
     }
     q = q.cond_where(main_cond);
+    if let Some(parent_id) = parent_id {
+        q = q.and_where(Expr::col(Alias::new("parent")).eq(parent_id));
+    }
 
     if let Some(pagination) = pagination {
         pagination
@@ -301,90 +1646,189 @@ fn gen_sql_select_domains_by_address(
             .map_err(|e| SubgraphReadError::Internal(e.to_string()))?;
     }
 
-    Ok(q.to_string(PostgresQueryBuilder))
+    Ok(q.to_owned())
+}
+
+/// Domains whose current resolver has `input.address` as the latest address of `input.coin_type`.
+/// Resolvers are preselected by any record with the address, so only their history is scanned
+fn select_domains_by_multicoin_address(
+    schema: &str,
+    select_clause: Option<&str>,
+    input: &LookupMulticoinAddressInput,
+    quirks: SqlQuirks,
+    parent_id: Option<&str>,
+    tld_rules: &TldRules,
+    pagination: Option<&DomainPaginationInput>,
+) -> Result<SelectStatement, SubgraphReadError> {
+    let mut query = if let Some(select_clause) = select_clause {
+        sql_gen::domain_select_custom(schema, select_clause)
+    } else {
+        sql_gen::domain_select(schema, quirks)
+    };
+    let col = |col: &str| (Alias::new("c"), Alias::new(col));
+    let coin_type_eq = || {
+        Expr::expr(Func::cast_as(
+            Expr::col(col("coin_type")),
+            Alias::new("text"),
+        ))
+        .eq(input.coin_type.as_str())
+    };
+    let addr_eq = || {
+        Expr::col(col("addr")).eq(Func::cust(Alias::new("decode"))
+            .args([hex::encode(&input.address).into(), "hex".into()]))
+    };
+    let candidate_resolvers = Query::select()
+        .column(col("resolver"))
+        .from_as(
+            sql_gen::schema_table(schema, "multicoin_addr_changed"),
+            Alias::new("c"),
+        )
+        .and_where(coin_type_eq())
+        .and_where(addr_eq())
+        .to_owned();
+    let latest_records = Query::select()
+        .distinct_on([col("resolver")])
+        .columns([col("resolver"), col("addr")])
+        .from_as(
+            sql_gen::schema_table(schema, "multicoin_addr_changed"),
+            Alias::new("c"),
+        )
+        .and_where(coin_type_eq())
+        .and_where(Expr::col(col("resolver")).in_subquery(candidate_resolvers))
+        .order_by(col("resolver"), Order::Asc)
+        .order_by(col("block_number"), Order::Desc)
+        .order_by(col("vid"), Order::Desc)
+        .to_owned();
+    let matching_resolvers = Query::select()
+        .column(col("resolver"))
+        .from_subquery(latest_records, Alias::new("c"))
+        .and_where(addr_eq())
+        .to_owned();
+
+    let mut q = query
+        .with_block_range()
+        .with_non_empty_label()
+        .with_resolved_names(tld_rules)
+        .and_where(Expr::col(Alias::new("resolver")).in_subquery(matching_resolvers));
+    q = q.with_state_filter(
+        DomainStateFilter {
+            only_active: input.only_active,
+            include_burned: input.include_burned,
+        },
+        quirks,
+    );
+    if let Some(parent_id) = parent_id {
+        q = q.and_where(Expr::col(Alias::new("parent")).eq(parent_id));
+    }
+    if let Some(pagination) = pagination {
+        pagination
+            .add_to_query(q)
+            .context("adding pagination to query")
+            .map_err(|e| SubgraphReadError::Internal(e.to_string()))?;
+    }
+    Ok(q.to_owned())
 }
 
-// TODO: rewrite to sea_query generation
 #[instrument(
     name = "batch_search_addresses",
-    skip(pool, addresses),
+    skip(pool, options, addresses),
     fields(job_size = addresses.len()),
     err(level = "error"),
     level = "info",
 )]
 pub async fn batch_search_addresses(
     pool: &PgPool,
+    options: &QueryOptions,
     schema: &str,
     addresses: &[impl AsRef<str>],
+    tld_rules: &TldRules,
+    quirks: SqlQuirks,
 ) -> Result<Vec<DomainWithAddress>, SubgraphReadError> {
-    let domains: Vec<DomainWithAddress> = sqlx::query_as(&format!(
-        r#"
-        SELECT DISTINCT ON (resolved_address) id, name AS domain_name, resolved_address
-        FROM {schema}.domain
-        WHERE
-            resolved_address = ANY($1)
-            AND name NOT LIKE '%[%'
-            AND {DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
-            AND {DOMAIN_NONEMPTY_LABEL_WHERE_CLAUSE}
-            AND {DOMAIN_NOT_EXPIRED_WHERE_CLAUSE}
-        ORDER BY resolved_address, created_at
-        "#,
-    ))
-    .bind(bind_string_list(addresses))
-    .fetch_all(pool)
-    .await?;
-
-    Ok(domains)
+    in_chunks(&options.chunks, addresses, |chunk| async move {
+        let (sql, values) =
+            sql_gen::domain_select_custom(schema, "id, name AS domain_name, resolved_address")
+                .distinct_on([Alias::new("resolved_address")])
+                .and_where(
+                    Expr::col(Alias::new("resolved_address"))
+                        .eq(PgFunc::any(Expr::val(bind_string_list(chunk)))),
+                )
+                .with_resolved_names(tld_rules)
+                .with_block_range()
+                .with_non_empty_label()
+                .with_not_expired(quirks)
+                .order_by(Alias::new("resolved_address"), Order::Asc)
+                .order_by(Alias::new("created_at"), Order::Asc)
+                .build_sqlx(PostgresQueryBuilder);
+        options
+            .run(pool, "batch_search_addresses", schema, |conn| {
+                sqlx::query_as_with(&sql, values.clone())
+                    .fetch_all(&mut **conn)
+                    .boxed()
+            })
+            .await
+    })
+    .await
 }
 
 #[instrument(
-    name = "batch_search_addr_reverse_names",
-    skip(pool, addr_reverse_hashes),
-    fields(job_size = addr_reverse_hashes.len()),
+    name = "batch_resolve_domain_names",
+    skip(pool, options, names),
+    fields(job_size = names.len()),
     err(level = "error"),
     level = "info",
 )]
-pub async fn batch_search_addr_reverse_names(
+pub async fn batch_resolve_domain_names(
     pool: &PgPool,
+    options: &QueryOptions,
     schema: &str,
-    addr_reverse_hashes: &[impl AsRef<str>],
-) -> Result<Vec<ReverseRecord>, SubgraphReadError> {
-    let domains: Vec<ReverseRecord> = sqlx::query_as(&format!(
-        r#"
-        SELECT d.id as addr_reverse_id, nc.name as reversed_name
-        FROM {schema}.domain d
-        JOIN {schema}.name_changed nc ON nc.resolver = d.resolver
-        WHERE d.id = ANY($1)
-            AND d.{DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
-        ORDER BY nc.block_number DESC;
-        "#,
-    ))
-    .bind(bind_string_list(addr_reverse_hashes))
-    .fetch_all(pool)
-    .await?;
+    names: &[DomainName],
+    quirks: SqlQuirks,
+) -> Result<Vec<DomainWithAddress>, SubgraphReadError> {
+    let ids = names.iter().map(|name| name.id.clone()).collect::<Vec<_>>();
+    let (sql, values) =
+        sql_gen::domain_select_custom(schema, "id, name AS domain_name, resolved_address")
+            .and_where(Expr::col(Alias::new("id")).eq(PgFunc::any(Expr::val(ids))))
+            .and_where(Expr::col(Alias::new("resolved_address")).is_not_null())
+            .with_block_range()
+            .with_not_expired(quirks)
+            .build_sqlx(PostgresQueryBuilder);
+    let domains: Vec<DomainWithAddress> = options
+        .run(pool, "batch_resolve_domain_names", schema, |conn| {
+            sqlx::query_as_with(&sql, values.clone())
+                .fetch_all(&mut **conn)
+                .boxed()
+        })
+        .await?;
 
     Ok(domains)
 }
 
-// TODO: rewrite to sea_query generation
 #[instrument(
     name = "update_domain_name",
-    skip(pool),
+    skip(pool, options),
     err(level = "error"),
     level = "info"
 )]
 pub async fn update_domain_name(
     pool: &PgPool,
+    options: &QueryOptions,
     schema: &str,
     name: &DomainName,
-) -> Result<PgQueryResult, sqlx::Error> {
-    let result = sqlx::query(&format!(
-        "UPDATE {schema}.domain SET name = $1, label_name = $2 WHERE id = $3;"
-    ))
-    .bind(&name.name)
-    .bind(&name.label_name)
-    .bind(&name.id)
-    .execute(pool)
-    .await?;
+) -> Result<PgQueryResult, SubgraphReadError> {
+    let (sql, values) = UpdateStatement::new()
+        .table(sql_gen::schema_table(schema, "domain"))
+        .values([
+            (Alias::new("name"), name.name.clone().into()),
+            (Alias::new("label_name"), name.label_name.clone().into()),
+        ])
+        .and_where(Expr::col(Alias::new("id")).eq(&name.id))
+        .build_sqlx(PostgresQueryBuilder);
+    let result = options
+        .run(pool, "update_domain_name", schema, |conn| {
+            sqlx::query_with(&sql, values.clone())
+                .execute(&mut **conn)
+                .boxed()
+        })
+        .await?;
     Ok(result)
 }
@@ -1,9 +1,23 @@
 mod cache_views;
+mod chunks;
 mod domain;
+mod options;
+mod quirks;
+mod retry;
+mod reverse_records;
+mod stats;
+mod timeout;
 mod transaction_history;
 
 pub use cache_views::*;
+pub use chunks::*;
 pub use domain::*;
+pub use options::*;
+pub use quirks::*;
+pub use retry::*;
+pub use reverse_records::*;
+pub use stats::*;
+pub use timeout::*;
 pub use transaction_history::*;
 
 pub fn bind_string_list(list: &[impl AsRef<str>]) -> Vec<String> {
@@ -0,0 +1,167 @@
+//! Options of sql queries. They are set on [`crate::subgraphs_reader::SubgraphReader`]
+//! and passed down to every query, so readers with different settings don't affect each other
+
+use super::{with_retry, ChunkPolicy, RetryPolicy};
+use crate::subgraphs_reader::SubgraphReadError;
+use futures::future::BoxFuture;
+use sqlx::{pool::PoolConnection, PgConnection, PgPool, Postgres, Transaction};
+use std::{
+    marker::PhantomData,
+    ops::{Deref, DerefMut},
+    time::Duration,
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueryOptions {
+    /// Retries of queries failed with transient errors
+    pub retry: RetryPolicy,
+    /// Splitting of batch lookups into concurrent queries
+    pub chunks: ChunkPolicy,
+    /// Query is cancelled by database if it runs longer, not limited if not set
+    pub statement_timeout: Option<Duration>,
+}
+
+impl QueryOptions {
+    /// Connection to run single query with these options. Statement timeout is set by
+    /// `SET LOCAL` inside of transaction, so it's reset at the end of transaction
+    /// and doesn't stay on connection returned to pool
+    pub async fn connection(&self, pool: &PgPool) -> Result<QueryConnection, sqlx::Error> {
+        match self.statement_timeout {
+            Some(timeout) => {
+                let mut tx = pool.begin().await?;
+                sqlx::query(&format!(
+                    "SET LOCAL statement_timeout = {}",
+                    timeout.as_millis()
+                ))
+                .execute(&mut *tx)
+                .await?;
+                Ok(QueryConnection::Transaction(tx))
+            }
+            None => Ok(QueryConnection::Pool(pool.acquire().await?)),
+        }
+    }
+
+    /// Runs `query` with retries of [`Self::retry`] policy. Every attempt runs on its own
+    /// [`Self::connection`], which is finished after the query succeeds
+    pub async fn run<'a, T, F>(
+        &self,
+        pool: &PgPool,
+        query_name: &str,
+        schema: &str,
+        query: F,
+    ) -> Result<T, SubgraphReadError>
+    where
+        F: for<'c> Fn(&'c mut ScopedConnection<'a>) -> BoxFuture<'c, Result<T, sqlx::Error>>,
+    {
+        with_retry(&self.retry, query_name, schema, || async {
+            let mut conn = ScopedConnection {
+                conn: self.connection(pool).await?,
+                _scope: PhantomData,
+            };
+            let result = query(&mut conn).await?;
+            conn.conn.finish().await?;
+            Ok(result)
+        })
+        .await
+    }
+}
+
+/// Connection passed to query of [`QueryOptions::run`]. Lifetime of data borrowed by the query
+/// is part of the type, so the returned future can borrow both the data and the connection
+pub struct ScopedConnection<'a> {
+    conn: QueryConnection,
+    _scope: PhantomData<&'a ()>,
+}
+
+impl Deref for ScopedConnection<'_> {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        &self.conn
+    }
+}
+
+impl DerefMut for ScopedConnection<'_> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.conn
+    }
+}
+
+/// Connection returned by [`QueryOptions::connection`], should be finished after query
+pub enum QueryConnection {
+    Pool(PoolConnection<Postgres>),
+    Transaction(Transaction<'static, Postgres>),
+}
+
+impl QueryConnection {
+    pub async fn finish(self) -> Result<(), sqlx::Error> {
+        match self {
+            Self::Pool(_) => Ok(()),
+            Self::Transaction(tx) => tx.commit().await,
+        }
+    }
+}
+
+impl Deref for QueryConnection {
+    type Target = PgConnection;
+
+    fn deref(&self) -> &Self::Target {
+        match self {
+            Self::Pool(conn) => conn,
+            Self::Transaction(tx) => tx,
+        }
+    }
+}
+
+impl DerefMut for QueryConnection {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        match self {
+            Self::Pool(conn) => conn,
+            Self::Transaction(tx) => tx,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subgraphs_reader::sql::is_timeout;
+    use pretty_assertions::assert_eq;
+    use sqlx::postgres::PgPoolOptions;
+
+    #[sqlx::test(migrations = false)]
+    async fn statement_timeout_is_local(pool: PgPool) {
+        // single connection, so every query runs on the same one
+        let pool = PgPoolOptions::new()
+            .max_connections(1)
+            .connect_with(pool.connect_options().as_ref().clone())
+            .await
+            .unwrap();
+        let options = QueryOptions {
+            statement_timeout: Some(Duration::from_millis(100)),
+            ..Default::default()
+        };
+
+        let mut conn = options.connection(&pool).await.unwrap();
+        let err = sqlx::query("SELECT pg_sleep(1)")
+            .execute(&mut *conn)
+            .await
+            .expect_err("query should time out");
+        assert!(is_timeout(&err), "{err:?}");
+        drop(conn);
+
+        let mut conn = options.connection(&pool).await.unwrap();
+        let timeout: String = sqlx::query_scalar("SHOW statement_timeout")
+            .fetch_one(&mut *conn)
+            .await
+            .unwrap();
+        conn.finish().await.unwrap();
+        assert_eq!(timeout, "100ms");
+
+        let timeout: String = sqlx::query_scalar("SHOW statement_timeout")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(timeout, "0");
+    }
+}
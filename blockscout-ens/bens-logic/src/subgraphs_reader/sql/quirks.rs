@@ -0,0 +1,21 @@
+/// Differences of subgraph of naming protocol from ENS subgraph, applied by sql queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SqlQuirks {
+    /// Domains expire at `expiry_date`. Otherwise `expiry_date` is ignored and domains are always active
+    pub expiring_domains: bool,
+    /// Domains can be wrapped, so `wrapped_owner` owns domain as well as `owner`
+    pub wrapped_owners: bool,
+    /// Days after `expiry_date` during which owner still can renew domain.
+    /// Specific to registrar, so it is taken from subgraph settings
+    pub grace_period_days: u32,
+}
+
+impl Default for SqlQuirks {
+    fn default() -> Self {
+        Self {
+            expiring_domains: true,
+            wrapped_owners: true,
+            grace_period_days: 0,
+        }
+    }
+}
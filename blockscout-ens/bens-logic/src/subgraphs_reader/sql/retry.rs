@@ -0,0 +1,148 @@
+//! Retries of sql queries failed with transient errors, like connection loss
+//! during failover of graph-node database. Policy is part of [`super::QueryOptions`]
+//! passed to every query
+
+use crate::{metrics, subgraphs_reader::SubgraphReadError};
+use std::{future::Future, time::Duration};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Total number of attempts, including the first one
+    pub max_attempts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Backoff doubles after every failed attempt, starting from `initial_backoff`
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_backoff)
+    }
+}
+
+/// Connection errors and errors of concurrent transactions, which could succeed on retry.
+/// Pool timeout is not transient: pool is already exhausted, and retrying only adds load to it.
+/// See https://www.postgresql.org/docs/current/errcodes-appendix.html
+pub fn is_transient(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Io(_) | sqlx::Error::WorkerCrashed => true,
+        sqlx::Error::Database(err) => err.code().is_some_and(|code| {
+            // connection_exception class
+            code.starts_with("08")
+                // serialization_failure, deadlock_detected
+                || code == "40001"
+                || code == "40P01"
+                // admin_shutdown, crash_shutdown, cannot_connect_now
+                || code == "57P01"
+                || code == "57P02"
+                || code == "57P03"
+        }),
+        _ => false,
+    }
+}
+
+/// Runs `query` until it succeeds, fails with non-transient error or runs out of attempts.
+/// Every attempt is observed as separate query. Statements of transaction shouldn't be retried
+/// this way, since transaction is aborted after the first error
+pub async fn with_retry<T, F, Fut>(
+    policy: &RetryPolicy,
+    query_name: &str,
+    schema: &str,
+    mut query: F,
+) -> Result<T, SubgraphReadError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, SubgraphReadError>>,
+{
+    let mut attempt = 1;
+    loop {
+        match metrics::observe_query(query_name, schema, query()).await {
+            Err(SubgraphReadError::Transient(err)) if attempt < policy.max_attempts => {
+                let backoff = policy.backoff(attempt);
+                tracing::warn!(
+                    err =? err,
+                    attempt,
+                    backoff_ms = backoff.as_millis() as u64,
+                    "transient error during '{query_name}' query, retry"
+                );
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn backoff_works() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+        };
+        let backoffs: Vec<Duration> = (1..5).map(|attempt| policy.backoff(attempt)).collect();
+        assert_eq!(
+            backoffs,
+            [100, 200, 300, 300].map(Duration::from_millis).to_vec()
+        );
+    }
+
+    #[tokio::test]
+    async fn with_retry_works() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(1),
+        };
+
+        // transient errors are retried until attempts are exhausted
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), _> = with_retry(&policy, "test_retry", "sgd_test", || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(sqlx::Error::WorkerCrashed.into())
+        })
+        .await;
+        assert!(matches!(result, Err(SubgraphReadError::Transient(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        // query succeeds after transient error
+        let attempts = AtomicU32::new(0);
+        let result = with_retry(&policy, "test_retry", "sgd_test", || async {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 => Err(sqlx::Error::WorkerCrashed.into()),
+                _ => Ok(42),
+            }
+        })
+        .await;
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+
+        // other errors, including pool timeout, are not retried
+        let attempts = AtomicU32::new(0);
+        let result: Result<(), _> = with_retry(&policy, "test_retry", "sgd_test", || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(sqlx::Error::PoolTimedOut.into())
+        })
+        .await;
+        assert!(matches!(result, Err(SubgraphReadError::DbErr(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
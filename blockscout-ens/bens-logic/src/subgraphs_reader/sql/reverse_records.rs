@@ -0,0 +1,297 @@
+//! `cached_reverse_records` table of state database keeps the latest verified reverse record
+//! of every address, so batch resolving doesn't join `name_changed` on every request.
+//! Rows of subgraph are rebuilt by background refresher in single transaction,
+//! so readers see previous rows until rebuild is committed
+
+use crate::{
+    entity::subgraph::domain::{AddrReverseDomainWithActualName, ReverseRecord},
+    metrics,
+    subgraphs_reader::{
+        sql::{
+            bind_string_list,
+            domain::sql_gen::{self, QueryBuilderExt},
+            in_chunks, QueryOptions, DOMAIN_BLOCK_RANGE_WHERE_CLAUSE,
+        },
+        ReverseRegistrar, SubgraphReadError,
+    },
+};
+use ethers::types::Address;
+use futures::{FutureExt, TryStreamExt};
+use sea_query::{Alias, Expr, JoinType, Order, PostgresQueryBuilder, Query};
+use sea_query_binder::{SqlxBinder, SqlxValues};
+use sqlx::PgPool;
+use std::str::FromStr;
+use tracing::instrument;
+
+const INSERT_BATCH_SIZE: usize = 1000;
+
+/// Latest name of every `{addr}.{node_suffix}` domain of reverse registrar together
+/// with address the name resolves to. Rows are not verified yet
+fn reverse_record_candidates_sql(
+    schema: &str,
+    reverse_registrar: &ReverseRegistrar,
+) -> (String, SqlxValues) {
+    let nc = |col: &str| (Alias::new("nc"), Alias::new(col));
+    let reversed = |col: &str| (Alias::new("addr_reversed_domain"), Alias::new(col));
+    let domain = |col: &str| (Alias::new("domain"), Alias::new(col));
+    let latest_names = Query::select()
+        .distinct_on([Alias::new("resolver")])
+        .columns([Alias::new("resolver"), Alias::new("name")])
+        .from(sql_gen::schema_table(schema, "name_changed"))
+        .with_block_range()
+        .order_by(Alias::new("resolver"), Order::Asc)
+        .order_by(Alias::new("block_number"), Order::Desc)
+        .order_by(Alias::new("vid"), Order::Desc)
+        .to_owned();
+    Query::select()
+        .expr_as(Expr::col(domain("id")), Alias::new("domain_id"))
+        .expr_as(Expr::col(reversed("id")), Alias::new("reversed_domain_id"))
+        .expr_as(
+            Expr::col(domain("resolved_address")),
+            Alias::new("resolved_address"),
+        )
+        .expr_as(Expr::col(nc("name")), Alias::new("name"))
+        .from_subquery(latest_names, Alias::new("nc"))
+        .join_as(
+            JoinType::InnerJoin,
+            sql_gen::schema_table(schema, "domain"),
+            Alias::new("addr_reversed_domain"),
+            Expr::col(nc("resolver")).equals(reversed("resolver")),
+        )
+        .join_as(
+            JoinType::InnerJoin,
+            sql_gen::schema_table(schema, "domain"),
+            Alias::new("domain"),
+            Expr::col(domain("name")).equals(nc("name")),
+        )
+        .and_where(Expr::col(reversed("parent")).eq(reverse_registrar.parent_id()))
+        .and_where(sql_gen::block_range_of("addr_reversed_domain"))
+        .and_where(sql_gen::block_range_of("domain"))
+        .and_where(Expr::col(domain("resolved_address")).is_not_null())
+        .build_sqlx(PostgresQueryBuilder)
+}
+
+/// Reverse record is verified if its name resolves back to the address of `{addr}.{node_suffix}`
+fn is_verified(
+    candidate: &AddrReverseDomainWithActualName,
+    reverse_registrar: &ReverseRegistrar,
+) -> bool {
+    Address::from_str(&candidate.resolved_address)
+        .map(|addr| reverse_registrar.reverse_node(&addr).id == candidate.reversed_domain_id)
+        .unwrap_or(false)
+}
+
+/// Rebuilds cached reverse records of subgraph read from `pool` in `state_pool`
+/// and returns number of cached records
+#[instrument(
+    name = "refresh_cached_reverse_records",
+    skip(pool, state_pool),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn refresh_cached_reverse_records(
+    pool: &PgPool,
+    state_pool: &PgPool,
+    schema: &str,
+    reverse_registrar: &ReverseRegistrar,
+) -> Result<u64, SubgraphReadError> {
+    let rebuild = async {
+        let (candidates_sql, values) = reverse_record_candidates_sql(schema, reverse_registrar);
+        let mut candidates =
+            sqlx::query_as_with::<_, AddrReverseDomainWithActualName, _>(&candidates_sql, values)
+                .fetch(pool)
+                .try_chunks(INSERT_BATCH_SIZE);
+        let mut tx = state_pool.begin().await?;
+        sqlx::query("DELETE FROM cached_reverse_records WHERE schema_name = $1")
+            .bind(schema)
+            .execute(&mut *tx)
+            .await?;
+        let mut cached = 0;
+        while let Some(chunk) = candidates.try_next().await.map_err(|e| e.1)? {
+            let (ids, (addresses, names)): (Vec<_>, (Vec<_>, Vec<_>)) = chunk
+                .into_iter()
+                .filter(|c| is_verified(c, reverse_registrar))
+                .map(|c| (c.reversed_domain_id, (c.resolved_address, c.name)))
+                .unzip();
+            let result = sqlx::query(
+                r#"
+                INSERT INTO cached_reverse_records
+                    (schema_name, addr_reverse_id, resolved_address, reversed_name)
+                SELECT $1, * FROM UNNEST($2::text[], $3::text[], $4::text[])
+                ON CONFLICT DO NOTHING
+                "#,
+            )
+            .bind(schema)
+            .bind(ids)
+            .bind(addresses)
+            .bind(names)
+            .execute(&mut *tx)
+            .await?;
+            cached += result.rows_affected();
+        }
+        tx.commit().await?;
+        Ok::<_, sqlx::Error>(cached)
+    };
+    let cached = metrics::observe_query("refresh_cached_reverse_records", schema, rebuild).await?;
+    Ok(cached)
+}
+
+/// Latest names of `{addr}.{node_suffix}` domains read from subgraph. Names are not verified
+#[instrument(
+    name = "batch_search_addr_reverse_names",
+    skip(pool, options, addr_reverse_hashes),
+    fields(job_size = addr_reverse_hashes.len()),
+    err(level = "error"),
+    level = "info",
+)]
+pub async fn batch_search_addr_reverse_names(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    addr_reverse_hashes: &[impl AsRef<str>],
+) -> Result<Vec<ReverseRecord>, SubgraphReadError> {
+    let sql = format!(
+        r#"
+        SELECT DISTINCT ON (d.id) d.id AS addr_reverse_id, nc.name AS reversed_name
+        FROM {schema}.domain d
+        JOIN {schema}.name_changed nc ON nc.resolver = d.resolver
+        WHERE d.id = ANY($1)
+            AND d.{DOMAIN_BLOCK_RANGE_WHERE_CLAUSE}
+        ORDER BY d.id, nc.block_number DESC, nc.vid DESC
+        "#
+    );
+    in_chunks(&options.chunks, addr_reverse_hashes, |chunk| {
+        let sql = &sql;
+        async move {
+            options
+                .run(pool, "batch_search_addr_reverse_names", schema, |conn| {
+                    sqlx::query_as(sql)
+                        .bind(bind_string_list(chunk))
+                        .fetch_all(&mut **conn)
+                        .boxed()
+                })
+                .await
+        }
+    })
+    .await
+}
+
+/// Verified reverse records of subgraph cached in `state_pool`
+#[instrument(
+    name = "batch_search_cached_reverse_names",
+    skip(state_pool, options, addr_reverse_hashes),
+    fields(job_size = addr_reverse_hashes.len()),
+    err(level = "error"),
+    level = "info",
+)]
+pub async fn batch_search_cached_reverse_names(
+    state_pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    addr_reverse_hashes: &[impl AsRef<str>],
+) -> Result<Vec<ReverseRecord>, SubgraphReadError> {
+    let sql = r#"
+        SELECT addr_reverse_id, reversed_name
+        FROM cached_reverse_records
+        WHERE schema_name = $1 AND addr_reverse_id = ANY($2)
+        "#;
+    in_chunks(&options.chunks, addr_reverse_hashes, |chunk| async move {
+        options
+            .run(
+                state_pool,
+                "batch_search_cached_reverse_names",
+                schema,
+                |conn| {
+                    sqlx::query_as(sql)
+                        .bind(schema)
+                        .bind(bind_string_list(chunk))
+                        .fetch_all(&mut **conn)
+                        .boxed()
+                },
+            )
+            .await
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::subgraphs_reader::domain_name::DomainName;
+    use pretty_assertions::assert_eq;
+
+    const VITALIK: &str = "0xd8da6bf26964af9d7eed9e03e53415d37aa96045";
+    const WAGMI: &str = "0x9c996076a85b46061d9a70ff81f013853a86b619";
+    // reverse record points to name which doesn't resolve to this address
+    const NOT_VERIFIED: &str = "0xeefb13c7d42efcc655e528da6d6f7bbcf9a2251d";
+
+    fn addr_reverse_hashes(addresses: &[&str]) -> Vec<String> {
+        addresses
+            .iter()
+            .map(|a| DomainName::addr_reverse(&Address::from_str(a).unwrap()).id)
+            .collect()
+    }
+
+    async fn cached_names(pool: &PgPool, addresses: &[&str]) -> Vec<String> {
+        let hashes = addr_reverse_hashes(addresses);
+        let mut names: Vec<String> =
+            batch_search_cached_reverse_names(pool, &QueryOptions::default(), "sgd1", &hashes)
+                .await
+                .expect("failed to search reverse names")
+                .into_iter()
+                .map(|r| r.reversed_name)
+                .collect();
+        names.sort();
+        names
+    }
+
+    #[sqlx::test(migrations = "tests/migrations")]
+    async fn cached_reverse_records_work(pool: PgPool) {
+        // state tables live in the same test database as subgraphs
+        crate::state::run_migrations(&pool)
+            .await
+            .expect("failed to run state migrations");
+        assert_eq!(cached_names(&pool, &[VITALIK]).await, Vec::<String>::new());
+
+        // names read from subgraph are not verified
+        let mut names: Vec<String> = batch_search_addr_reverse_names(
+            &pool,
+            &QueryOptions::default(),
+            "sgd1",
+            &addr_reverse_hashes(&[VITALIK, NOT_VERIFIED]),
+        )
+        .await
+        .expect("failed to search reverse names")
+        .into_iter()
+        .map(|r| r.reversed_name)
+        .collect();
+        names.sort();
+        assert_eq!(names.len(), 2);
+        assert!(names.contains(&"vitalik.eth".to_string()));
+
+        let cached =
+            refresh_cached_reverse_records(&pool, &pool, "sgd1", &ReverseRegistrar::default())
+                .await
+                .expect("failed to refresh reverse records");
+        assert_eq!(cached, 2);
+        assert_eq!(
+            cached_names(&pool, &[VITALIK, WAGMI, NOT_VERIFIED]).await,
+            vec!["vitalik.eth", "wa🇬🇲i.eth"]
+        );
+
+        // name no longer resolves to address, so record is dropped by next refresh
+        sqlx::query("UPDATE sgd1.domain SET resolved_address = NULL WHERE name = 'vitalik.eth'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let cached =
+            refresh_cached_reverse_records(&pool, &pool, "sgd1", &ReverseRegistrar::default())
+                .await
+                .expect("failed to refresh reverse records");
+        assert_eq!(cached, 1);
+        assert_eq!(
+            cached_names(&pool, &[VITALIK, WAGMI]).await,
+            vec!["wa🇬🇲i.eth"]
+        );
+    }
+}
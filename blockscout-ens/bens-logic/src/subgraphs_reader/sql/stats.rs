@@ -0,0 +1,94 @@
+use super::{
+    domain::sql_gen::{self, QueryBuilderExt},
+    domain_not_expired_where_clause, QueryOptions, SqlQuirks, ZERO_ADDRESS,
+};
+use crate::subgraphs_reader::{NetworkStats, ReverseRegistrar, SubgraphReadError};
+use futures::FutureExt;
+use sea_query::{Alias, Condition, Expr, PostgresQueryBuilder, Query, SelectStatement};
+use sea_query_binder::SqlxBinder;
+use sqlx::PgPool;
+use tracing::instrument;
+
+#[derive(Debug, sqlx::FromRow)]
+struct DomainCounts {
+    total_domains: i64,
+    active_domains: i64,
+    registered_last_24h: i64,
+    registered_last_7d: i64,
+}
+
+/// Registered domains are current domains with known label, except reverse records
+fn registered_domains_select(
+    schema: &str,
+    reverse_registrar: &ReverseRegistrar,
+) -> SelectStatement {
+    let parent = || Expr::col(Alias::new("parent"));
+    Query::select()
+        .from(sql_gen::schema_table(schema, "domain"))
+        .with_block_range()
+        .with_non_empty_label()
+        .cond_where(
+            Condition::any()
+                .add(parent().is_null())
+                .add(parent().ne(reverse_registrar.parent_id())),
+        )
+        .to_owned()
+}
+
+#[instrument(
+    name = "get_network_stats",
+    skip(pool, options),
+    err(level = "error"),
+    level = "info"
+)]
+pub async fn get_network_stats(
+    pool: &PgPool,
+    options: &QueryOptions,
+    schema: &str,
+    reverse_registrar: &ReverseRegistrar,
+    quirks: SqlQuirks,
+) -> Result<NetworkStats, SubgraphReadError> {
+    let not_expired = domain_not_expired_where_clause(quirks);
+    let (counts_sql, counts_values) = registered_domains_select(schema, reverse_registrar)
+        .expr_as(Expr::cust("count(*)"), Alias::new("total_domains"))
+        .expr_as(
+            Expr::cust(format!("count(*) FILTER (WHERE {not_expired})")),
+            Alias::new("active_domains"),
+        )
+        .expr_as(
+            Expr::cust(
+                "count(*) FILTER (WHERE to_timestamp(created_at) > now() - interval '24 hours')",
+            ),
+            Alias::new("registered_last_24h"),
+        )
+        .expr_as(
+            Expr::cust(
+                "count(*) FILTER (WHERE to_timestamp(created_at) > now() - interval '7 days')",
+            ),
+            Alias::new("registered_last_7d"),
+        )
+        .build_sqlx(PostgresQueryBuilder);
+    let (owners_sql, owners_values) = registered_domains_select(schema, reverse_registrar)
+        .expr(Expr::cust("count(DISTINCT coalesce(wrapped_owner, owner))"))
+        .with_not_expired(quirks)
+        .and_where(Expr::col(Alias::new("owner")).ne(ZERO_ADDRESS))
+        .build_sqlx(PostgresQueryBuilder);
+    let counts = options.run(pool, "get_network_stats_counts", schema, |conn| {
+        sqlx::query_as_with::<_, DomainCounts, _>(&counts_sql, counts_values.clone())
+            .fetch_one(&mut **conn)
+            .boxed()
+    });
+    let unique_owners = options.run(pool, "get_network_stats_owners", schema, |conn| {
+        sqlx::query_scalar_with::<_, i64, _>(&owners_sql, owners_values.clone())
+            .fetch_one(&mut **conn)
+            .boxed()
+    });
+    let (counts, unique_owners) = futures::try_join!(counts, unique_owners)?;
+    Ok(NetworkStats {
+        total_domains: counts.total_domains,
+        active_domains: counts.active_domains,
+        registered_last_24h: counts.registered_last_24h,
+        registered_last_7d: counts.registered_last_7d,
+        unique_owners,
+    })
+}
@@ -0,0 +1,31 @@
+//! Server-side `statement_timeout` of read-only queries, so slow query is cancelled
+//! by database instead of holding connection. Interactive lookups and batch endpoints
+//! have separate budgets, which are set as [`super::QueryOptions::statement_timeout`]
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatementTimeouts {
+    /// Budget of queries of single domain or address lookups
+    pub interactive: Duration,
+    /// Budget of queries of batch resolving, exports and stats
+    pub batch: Duration,
+}
+
+impl Default for StatementTimeouts {
+    fn default() -> Self {
+        Self {
+            interactive: Duration::from_secs(10),
+            batch: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Statement was cancelled because of `statement_timeout` (`query_canceled` code).
+/// See https://www.postgresql.org/docs/current/errcodes-appendix.html
+pub fn is_timeout(err: &sqlx::Error) -> bool {
+    match err {
+        sqlx::Error::Database(err) => err.code().is_some_and(|code| code == "57014"),
+        _ => false,
+    }
+}
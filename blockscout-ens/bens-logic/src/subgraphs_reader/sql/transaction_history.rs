@@ -1,7 +1,10 @@
 use crate::{
     entity::subgraph::domain_event::DomainEventTransaction,
-    subgraphs_reader::{pagination::Order, EventSort, GetDomainHistoryInput, SubgraphReadError},
+    subgraphs_reader::{
+        pagination::Order, sql::QueryOptions, EventSort, GetDomainHistoryInput, SubgraphReadError,
+    },
 };
+use futures::FutureExt;
 use lazy_static::lazy_static;
 use sqlx::postgres::PgPool;
 use tera::{Context, Tera};
@@ -9,12 +12,13 @@ use tracing::instrument;
 
 #[instrument(
     name = "find_transaction_events",
-    skip(pool),
+    skip(pool, options),
     err(level = "error"),
     level = "info"
 )]
 pub async fn find_transaction_events(
     pool: &PgPool,
+    options: &QueryOptions,
     schema: &str,
     id: &str,
     input: &GetDomainHistoryInput,
@@ -23,8 +27,11 @@ pub async fn find_transaction_events(
     let order = input.order;
     let sql = sql_events_of_domain(schema, sort, order)
         .map_err(|e| SubgraphReadError::Internal(e.to_string()))?;
-    let transactions: Vec<DomainEventTransaction> =
-        sqlx::query_as(&sql).bind(id).fetch_all(pool).await?;
+    let transactions: Vec<DomainEventTransaction> = options
+        .run(pool, "find_transaction_events", schema, |conn| {
+            sqlx::query_as(&sql).bind(id).fetch_all(&mut **conn).boxed()
+        })
+        .await?;
     Ok(transactions)
 }
 
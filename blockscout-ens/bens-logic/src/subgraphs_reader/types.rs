@@ -1,15 +1,33 @@
-use super::pagination::{DomainPaginationInput, Order};
-use crate::entity::subgraph::domain::DetailedDomain;
-use ethers::types::Address;
+use super::pagination::{DomainPaginationInput, Order, PaginatedList};
+use crate::{
+    content_hash::ContentHash,
+    entity::subgraph::{
+        domain::{DetailedDomain, Domain, TextRecord},
+        domain_event::ResolvedAddressChange,
+    },
+};
+use ethers::types::{Address, Bytes, H256};
 use sea_query::{Alias, IntoIden};
 use serde::Deserialize;
-use std::fmt::Display;
+use std::{collections::BTreeMap, fmt::Display};
 
 #[derive(Debug, Clone)]
 pub struct GetDomainInput {
     pub network_id: i64,
     pub name: String,
     pub only_active: bool,
+    /// SLIP-44 coin types of `other_addresses` to return, all addresses are returned if empty
+    pub coin_types: Vec<String>,
+    /// Read history of resolved address, which requires additional requests to Blockscout
+    pub include_resolved_address_history: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetDomainByHashInput {
+    pub network_id: i64,
+    /// Namehash of domain or labelhash of its last label, as emitted in onchain events
+    pub hash: H256,
+    pub only_active: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -25,6 +43,8 @@ pub struct LookupDomainInput {
     pub network_id: i64,
     pub name: Option<String>,
     pub only_active: bool,
+    /// Include burned domains, which are owned by zero address and don't resolve to any address
+    pub include_burned: bool,
     pub pagination: DomainPaginationInput,
 }
 
@@ -34,10 +54,159 @@ pub struct LookupAddressInput {
     pub address: Address,
     pub resolved_to: bool,
     pub owned_by: bool,
+    /// Include domains registered by the address, which is owner of registration NFT.
+    /// Differs from owner for wrapped and rented names
+    pub registered_by: bool,
+    pub only_active: bool,
+    /// Include burned domains, which are owned by zero address and don't resolve to any address
+    pub include_burned: bool,
+    /// Include only direct subdomains of this domain, like `eth` or `dao.eth`
+    pub parent_domain: Option<String>,
+    pub pagination: DomainPaginationInput,
+}
+
+/// Lookup of domains whose resolver has `address` as current address of `coin_type`
+#[derive(Debug, Clone)]
+pub struct LookupMulticoinAddressInput {
+    pub network_id: i64,
+    /// SLIP-44 coin type, like `0` for BTC
+    pub coin_type: String,
+    /// Address bytes as stored in resolver, like output script for BTC
+    pub address: Bytes,
     pub only_active: bool,
+    /// Include burned domains, which are owned by zero address and don't resolve to any address
+    pub include_burned: bool,
+    /// Include only direct subdomains of this domain, like `eth` or `dao.eth`
+    pub parent_domain: Option<String>,
     pub pagination: DomainPaginationInput,
 }
 
+/// Same filters as [`LookupAddressInput`], but without pagination
+#[derive(Debug, Clone)]
+pub struct ExportAddressInput {
+    pub network_id: i64,
+    pub address: Address,
+    pub resolved_to: bool,
+    pub owned_by: bool,
+    /// Include domains registered by the address, which is owner of registration NFT.
+    /// Differs from owner for wrapped and rented names
+    pub registered_by: bool,
+    pub only_active: bool,
+    /// Include burned domains, which are owned by zero address and don't resolve to any address
+    pub include_burned: bool,
+    /// Include only direct subdomains of this domain, like `eth` or `dao.eth`
+    pub parent_domain: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetTextRecordsInput {
+    pub network_id: i64,
+    pub name: String,
+}
+
+/// Partial name or address typed into search bar
+#[derive(Debug, Clone)]
+pub struct QuickSearchInput {
+    pub network_id: i64,
+    pub query: String,
+    pub limit: u32,
+    pub rank: QuickSearchRank,
+}
+
+/// Order of domains with name starting with query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Default)]
+pub enum QuickSearchRank {
+    /// Lexicographical order of names
+    #[default]
+    Name,
+    /// Recently registered domains first
+    RegistrationDate,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetDomainRecordsInput {
+    pub network_id: i64,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct GetSubdomainsInput {
+    pub network_id: i64,
+    pub name: String,
+    pub only_active: bool,
+    /// Include burned domains, which are owned by zero address and don't resolve to any address
+    pub include_burned: bool,
+    pub pagination: DomainPaginationInput,
+}
+
+/// State of domains included in domain lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DomainStateFilter {
+    pub only_active: bool,
+    /// Include burned domains, which are owned by zero address and don't resolve to any address
+    pub include_burned: bool,
+}
+
+/// Domains related to `address` in any of selected ways.
+/// Empty list is selected if none of relations is set
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddressDomainsFilter {
+    pub address: Address,
+    pub resolved_to: bool,
+    pub owned_by: bool,
+    /// Domains registered by the address, which is owner of registration NFT
+    pub registered_by: bool,
+    pub state: DomainStateFilter,
+}
+
+impl LookupDomainInput {
+    pub fn state_filter(&self) -> DomainStateFilter {
+        DomainStateFilter {
+            only_active: self.only_active,
+            include_burned: self.include_burned,
+        }
+    }
+}
+
+impl GetSubdomainsInput {
+    pub fn state_filter(&self) -> DomainStateFilter {
+        DomainStateFilter {
+            only_active: self.only_active,
+            include_burned: self.include_burned,
+        }
+    }
+}
+
+impl LookupAddressInput {
+    pub fn address_filter(&self) -> AddressDomainsFilter {
+        AddressDomainsFilter {
+            address: self.address,
+            resolved_to: self.resolved_to,
+            owned_by: self.owned_by,
+            registered_by: self.registered_by,
+            state: DomainStateFilter {
+                only_active: self.only_active,
+                include_burned: self.include_burned,
+            },
+        }
+    }
+}
+
+impl ExportAddressInput {
+    pub fn address_filter(&self) -> AddressDomainsFilter {
+        AddressDomainsFilter {
+            address: self.address,
+            resolved_to: self.resolved_to,
+            owned_by: self.owned_by,
+            registered_by: self.registered_by,
+            state: DomainStateFilter {
+                only_active: self.only_active,
+                include_burned: self.include_burned,
+            },
+        }
+    }
+}
+
 impl Default for DomainPaginationInput {
     fn default() -> Self {
         Self {
@@ -55,6 +224,20 @@ pub struct BatchResolveAddressNamesInput {
     pub addresses: Vec<Address>,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BatchResolveAddressNamesOutput {
+    /// Primary name of every resolved address
+    pub names: BTreeMap<String, String>,
+    /// Names of addresses from protocols with lower priority, if there are any
+    pub other_names: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct BatchResolveDomainNamesInput {
+    pub network_id: i64,
+    pub names: Vec<String>,
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Default)]
 pub enum DomainSortField {
     #[default]
@@ -70,6 +253,14 @@ impl DomainSortField {
     }
 }
 
+impl Display for DomainSortField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DomainSortField::RegistrationDate => write!(f, "created_at"),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, Deserialize, Default)]
 pub enum EventSort {
     #[default]
@@ -84,10 +275,53 @@ impl Display for EventSort {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct AddressSummary {
+    pub resolved_count: i64,
+    pub owned_count: i64,
+    pub primary_name: Option<String>,
+    pub domains: PaginatedList<Domain>,
+}
+
+/// Aggregated counters of registered domains of network, reverse records are not counted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NetworkStats {
+    pub total_domains: i64,
+    /// Domains not expired yet, including grace period
+    pub active_domains: i64,
+    pub registered_last_24h: i64,
+    pub registered_last_7d: i64,
+    /// Distinct owners of active domains, wrapped owner is used for wrapped domains
+    pub unique_owners: i64,
+}
+
 #[derive(Debug, Clone)]
 pub struct GetDomainOutput {
     pub domain: DetailedDomain,
     pub tokens: Vec<DomainToken>,
+    /// Chronological list of addresses domain was resolved to
+    pub resolved_address_history: Vec<ResolvedAddressChange>,
+}
+
+/// All records of current domain resolver
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DomainRecords {
+    pub id: String,
+    pub name: String,
+    /// Addresses sorted by coin type
+    pub addresses: Vec<CoinAddress>,
+    /// Text records sorted by key
+    pub text_records: Vec<TextRecord>,
+    pub content_hash: Option<ContentHash>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CoinAddress {
+    /// SLIP-44 coin type, like `60` for ETH
+    pub coin_type: String,
+    pub coin_name: String,
+    /// Address formatted according to encoding of coin, hex if encoding is unknown
+    pub address: String,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -95,6 +329,8 @@ pub struct DomainToken {
     pub id: String,
     pub contract: Address,
     pub _type: DomainTokenType,
+    /// Link to token metadata, built from `token_metadata_url` of subgraph settings
+    pub metadata_url: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -1,11 +1,16 @@
 use crate::subgraphs_reader::{blockscout::BlockscoutClient, NetworkInfo};
-use ethers::types::TxHash;
+use ethers::types::{Address, TxHash};
 use std::collections::HashMap;
 use wiremock::{
     matchers::{method, path},
     Mock, MockServer, ResponseTemplate,
 };
 
+/// Chain id of network of [`mocked_networks_with_blockscout`]
+pub const DEFAULT_CHAIN_ID: i64 = 1;
+/// Schema of default subgraph of mocked network
+pub const DEFAULT_SCHEMA: &str = "sgd1";
+
 lazy_static::lazy_static! {
     // executing this bash command for every transaction
     // curl https://eth.blockscout.com/api/v2/transactions/<tx_hash> | jq '. | {timestamp, "from": {"hash": .from["hash"]}, hash, method, block}'
@@ -89,5 +94,23 @@ pub async fn mocked_blockscout_client() -> BlockscoutClient {
 
 pub async fn mocked_networks_with_blockscout() -> HashMap<i64, NetworkInfo> {
     let client = mocked_blockscout_client().await;
-    HashMap::from_iter([(1, NetworkInfo::from_client(client))])
+    HashMap::from_iter([(DEFAULT_CHAIN_ID, NetworkInfo::from_client(client))])
+}
+
+pub fn addr(a: &str) -> Address {
+    let a = a.trim_start_matches("0x");
+    Address::from_slice(
+        hex::decode(a)
+            .expect("invalid hex provided in addr()")
+            .as_slice(),
+    )
+}
+
+pub fn tx_hash(h: &str) -> TxHash {
+    let h = h.trim_start_matches("0x");
+    TxHash::from_slice(
+        hex::decode(h)
+            .expect("invalid hex provided in tx_hash()")
+            .as_slice(),
+    )
 }
@@ -104,3 +104,8 @@ INSERT INTO sgd1.name_changed VALUES
 (2,'[7094554,)','7094554-0-12','0xa2c122be93b0074270ebee7f6b7292c7deb45047-0xdd1e72c9290d1b177aaf5a131e67d911753afd63cf3602b65b6b27fc7d3f262c',7094554,'\xAC29B8D9F53D4FCF1D117D6F695D253C14E9BCB4F00B8F33B35540CA485FA659','wa🇬🇲i.eth'),
 (3,'[7094594,)','7094594-0-3','0xa2c122be93b0074270ebee7f6b7292c7deb45047-0xbf832e4a9151d7135110c2b892826ff4c71a5f9d619c5b85673abb70ff19a751',7094594,'\xAC29B8D9F53D4FCF1D117D6F695D253C14E9BCB4F00B8F33B35540CA485FA659','this-is-not-test.eth')
 ;
+
+-- vitalik.eth contenthash
+INSERT INTO sgd1.contenthash_changed VALUES
+(1,'[17043847,)','17043847-0-1','0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835',17043847,'\x2E2B9ED2D2AE42EB4D63D39CE4B85B7D0E19D60F73C6B36D2E5EA2B0B9FB1F1A','\xE3010170122084B970556C2BCBD1F37D3B001C6C5566FC0EE7C7F1A76FDAEDB78AEFDCA0ED00')
+;
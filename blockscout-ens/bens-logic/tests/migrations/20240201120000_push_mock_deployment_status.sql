@@ -0,0 +1,12 @@
+INSERT INTO public.ethereum_networks (name, head_block_hash, head_block_number, net_version, genesis_block_hash, namespace)
+VALUES
+('mainnet', '0x5b9f4c9b5a2d8f3a8f0c3e9b5d7f1b3c4d6a8e0f2b4c6d8e0f1a3b5c7d9e1f2a', 18000010, '1', '0xd4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3', 'chain2'),
+('gnosis', '0x0c1a3b5d7f9e2c4a6b8d0f1e3c5a7b9d2f4e6c8a0b1d3f5e7a9c2b4d6f8e0a1c', 7000000, '10200', '0xada44fd8d2ecab8b08f256af07ad3e777f17fb434f8f8e678b312f576212ba9a', 'chain4')
+;
+
+INSERT INTO subgraphs.subgraph_deployment (id, deployment, failed, synced, latest_ethereum_block_number, entity_count, health)
+VALUES
+(1, 'Qmb_1', false, true, 18000000, 100, 'healthy'),
+(2, 'Qmb_2', false, true, 7000000, 100, 'healthy'),
+(3, 'Qmb_3', false, false, 17990000, 100, 'healthy')
+;
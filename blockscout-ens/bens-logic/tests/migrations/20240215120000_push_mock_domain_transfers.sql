@@ -0,0 +1,12 @@
+-- sashaxyz.eth ownership changes
+INSERT INTO sgd1.transfer (vid, block_range, id, domain, block_number, transaction_id, owner)
+VALUES
+(1, '[17999990,)', '17999990-10', '0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 17999990, '\x1A7E2F5B9C0D3E6F8A1B4C7D0E3F6A9B2C5D8E1F4A7B0C3D6E9F2A5B8C1D4E7F', '0x1111111111111111111111111111111111111111'),
+-- not indexed by deployment status yet
+(2, '[18000005,)', '18000005-3', '0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 18000005, '\x2B8F3A6C0D1E4F7A9B2C5D8E1F4A7B0C3D6E9F2A5B8C1D4E7F0A3B6C9D2E5F8A', '0x3333333333333333333333333333333333333333')
+;
+
+INSERT INTO sgd1.wrapped_transfer (vid, block_range, id, domain, block_number, transaction_id, owner)
+VALUES
+(1, '[17999995,)', '17999995-7', '0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 17999995, '\x3C9A4B7D1E2F5A8B0C3D6E9F2A5B8C1D4E7F0A3B6C9D2E5F8A1B4C7D0E3F6A9B', '0x2222222222222222222222222222222222222222')
+;
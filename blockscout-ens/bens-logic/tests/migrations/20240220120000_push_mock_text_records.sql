@@ -0,0 +1,10 @@
+-- sashaxyz.eth text records
+INSERT INTO sgd1.text_changed (vid, block_range, id, resolver, block_number, transaction_id, key, value)
+VALUES
+(1, '[14000000,)', '14000000-1', '0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 14000000, '\x4D0B5C8E2F3A6B9C1D4E7F0A2B5C8D1E4F7A0B3C6D9E2F5A8B1C4D7E0F3A6B9C', 'url', 'https://old.sasha.xyz'),
+(2, '[14000100,)', '14000100-2', '0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 14000100, '\x5E1C6D9F3A4B7C0D2E5F8A1B3C6D9E2F5A8B1C4D7E0F3A6B9C2D5E8F1A4B7C0D', 'com.twitter', 'sasha'),
+(3, '[14000200,)', '14000200-4', '0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 14000200, '\x6F2D7E0A4B5C8D1E3F6A9B2C4D7E0F3A6B9C2D5E8F1A4B7C0D3E6F9A2B5C8D1E', 'avatar', 'https://sasha.xyz/avatar.png'),
+(4, '[15000000,)', '15000000-5', '0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 15000000, '\x7A3E8F1B5C6D9E2F4A7B0C3D5E8F1A4B7C0D3E6F9A2B5C8D1E4F7A0B3C6D9E2F', 'url', 'https://sasha.xyz'),
+-- avatar is removed
+(5, '[15000100,)', '15000100-1', '0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 15000100, '\x8B4F9A2C6D7E0F3A5B8C1D4E6F9A2B5C8D1E4F7A0B3C6D9E2F5A8B1C4D7E0F3A', 'avatar', NULL)
+;
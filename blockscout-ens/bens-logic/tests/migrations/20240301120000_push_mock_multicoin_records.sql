@@ -0,0 +1,10 @@
+-- sashaxyz.eth multicoin addresses
+INSERT INTO sgd1.multicoin_addr_changed (vid, block_range, id, resolver, block_number, transaction_id, coin_type, addr)
+VALUES
+(101, '[14000000,)', '14000000-2-0', '0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 14000000, '\x4D0B5C8E2F3A6B9C1D4E7F0A2B5C8D1E4F7A0B3C6D9E2F5A8B1C4D7E0F3A6B9C', 60, '\x66a6f7744ce4dea450910b81a7168588f992eafb'),
+(102, '[14000100,)', '14000100-3-0', '0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 14000100, '\x5E1C6D9F3A4B7C0D2E5F8A1B3C6D9E2F5A8B1C4D7E0F3A6B9C2D5E8F1A4B7C0D', 0, '\x0014751e76e8199196d454941c45d1b3a323f1433bd6'),
+(103, '[14000200,)', '14000200-5-0', '0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 14000200, '\x6F2D7E0A4B5C8D1E3F6A9B2C4D7E0F3A6B9C2D5E8F1A4B7C0D3E6F9A2B5C8D1E', 2, '\x76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac'),
+(104, '[15000000,)', '15000000-6-0', '0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 15000000, '\x7A3E8F1B5C6D9E2F4A7B0C3D5E8F1A4B7C0D3E6F9A2B5C8D1E4F7A0B3C6D9E2F', 60, '\xd8da6bf26964af9d7eed9e03e53415d37aa96045'),
+-- LTC address is removed
+(105, '[15000100,)', '15000100-2-0', '0x4976fb03c32e5b8cfe2b6ccb31c09ba78ebaba41-0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff', 15000100, '\x8B4F9A2C6D7E0F3A5B8C1D4E6F9A2B5C8D1E4F7A0B3C6D9E2F5A8B1C4D7E0F3A', 2, '\x')
+;
@@ -22,6 +22,10 @@ fn compile(
             ".blockscout.bens.v1.GetDomainRequest.only_active",
             "#[serde(default)]"
         )
+        .field_attribute(
+            ".blockscout.bens.v1.GetDomainByHashRequest.only_active",
+            "#[serde(default)]"
+        )
         .field_attribute(
             ".blockscout.bens.v1.ListDomainEventsRequest.sort",
             "#[serde(default)]"
@@ -42,6 +46,10 @@ fn compile(
             ".blockscout.bens.v1.LookupDomainNameRequest.only_active",
             "#[serde(default)]"
         )
+        .field_attribute(
+            ".blockscout.bens.v1.LookupDomainNameRequest.include_burned",
+            "#[serde(default)]"
+        )
         .field_attribute(
             ".blockscout.bens.v1.LookupAddressRequest.sort",
             "#[serde(default)]"
@@ -54,6 +62,22 @@ fn compile(
             ".blockscout.bens.v1.LookupAddressRequest.only_active",
             "#[serde(default)]"
         )
+        .field_attribute(
+            ".blockscout.bens.v1.LookupAddressRequest.include_burned",
+            "#[serde(default)]"
+        )
+        .field_attribute(
+            ".blockscout.bens.v1.LookupAddressRequest.resolved_to",
+            "#[serde(default)]"
+        )
+        .field_attribute(
+            ".blockscout.bens.v1.LookupAddressRequest.owned_by",
+            "#[serde(default)]"
+        )
+        .field_attribute(
+            ".blockscout.bens.v1.LookupAddressRequest.registered_by",
+            "#[serde(default)]"
+        )
         ;
     config.compile_protos(protos, includes)?;
     Ok(())
@@ -0,0 +1,125 @@
+//! Admin routes with results of background consistency checks.
+//! Routes are registered only if corresponding job is enabled
+
+use actix_web::{web, HttpRequest, HttpResponse};
+use bens_logic::subgraphs_reader::SubgraphNamehashReport;
+use serde::Serialize;
+use std::sync::{Arc, RwLock};
+
+const NAMEHASH_CHECK_PATH: &str = "/api/v1/admin/namehash-check";
+const API_KEY_HEADER: &str = "x-api-key";
+
+/// Reports of the latest namehash check, shared by check job and admin route
+pub struct NamehashCheckState {
+    /// None until the first check is finished
+    reports: RwLock<Option<Vec<SubgraphNamehashReport>>>,
+    api_key: Option<String>,
+}
+
+impl NamehashCheckState {
+    pub fn new(api_key: Option<String>) -> Self {
+        Self {
+            reports: RwLock::new(None),
+            api_key,
+        }
+    }
+
+    pub fn set_reports(&self, reports: Vec<SubgraphNamehashReport>) {
+        *self
+            .reports
+            .write()
+            .expect("namehash reports lock is poisoned") = Some(reports);
+    }
+
+    /// Returns error response if request is not authorized to use admin route
+    fn authorization_error(&self, req: &HttpRequest) -> Option<HttpResponse> {
+        let Some(api_key) = &self.api_key else {
+            return Some(HttpResponse::ServiceUnavailable().json(serde_json::json!({
+                "code": tonic::Code::Unavailable as i32,
+                "message": "admin api is disabled: api key is not set",
+            })));
+        };
+        let authorized = req
+            .headers()
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|value| value == api_key);
+        (!authorized).then(|| {
+            HttpResponse::Unauthorized().json(serde_json::json!({
+                "code": tonic::Code::Unauthenticated as i32,
+                "message": "invalid api key",
+            }))
+        })
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct NamehashCheckResponse {
+    reports: Option<Vec<NamehashReport>>,
+}
+
+#[derive(Debug, Serialize)]
+struct NamehashReport {
+    network_id: i64,
+    schema_name: String,
+    checked_domains: u64,
+    mismatches_count: u64,
+    mismatches: Vec<NamehashMismatch>,
+    checked_at: String,
+}
+
+#[derive(Debug, Serialize)]
+struct NamehashMismatch {
+    id: String,
+    name: String,
+    expected_id: String,
+}
+
+impl From<SubgraphNamehashReport> for NamehashReport {
+    fn from(report: SubgraphNamehashReport) -> Self {
+        Self {
+            network_id: report.network_id,
+            schema_name: report.schema_name,
+            checked_domains: report.checked_domains,
+            mismatches_count: report.mismatches_count,
+            mismatches: report
+                .mismatches
+                .into_iter()
+                .map(|m| NamehashMismatch {
+                    id: m.id,
+                    name: m.name,
+                    expected_id: m.expected_id,
+                })
+                .collect(),
+            checked_at: report.checked_at.to_rfc3339(),
+        }
+    }
+}
+
+pub fn route_admin(
+    config: &mut web::ServiceConfig,
+    namehash_check: Option<Arc<NamehashCheckState>>,
+) {
+    if let Some(state) = namehash_check {
+        config
+            .app_data(web::Data::from(state))
+            .route(NAMEHASH_CHECK_PATH, web::get().to(namehash_check_reports));
+    }
+}
+
+async fn namehash_check_reports(
+    req: HttpRequest,
+    state: web::Data<NamehashCheckState>,
+) -> HttpResponse {
+    if let Some(response) = state.authorization_error(&req) {
+        return response;
+    }
+    let reports = state
+        .reports
+        .read()
+        .expect("namehash reports lock is poisoned")
+        .clone();
+    HttpResponse::Ok().json(NamehashCheckResponse {
+        reports: reports.map(|reports| reports.into_iter().map(Into::into).collect()),
+    })
+}
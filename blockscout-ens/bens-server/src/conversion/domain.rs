@@ -1,17 +1,32 @@
 use super::ConversionError;
 use crate::conversion::order_direction_from_inner;
 use bens_logic::{
-    entity::subgraph::domain::Domain,
+    coin_type::Coin,
+    content_hash::ContentHash,
+    entity::subgraph::{
+        domain::{Domain, DomainStatus, ExpiryStatus},
+        domain_event::ResolvedAddressChange,
+    },
+    hash_name::hex,
+    name_safety::{name_warnings, NameWarning},
     subgraphs_reader::{
-        BatchResolveAddressNamesInput, DomainPaginationInput, DomainSortField, DomainToken,
-        DomainTokenType, GetDomainInput, GetDomainOutput, LookupAddressInput, LookupDomainInput,
+        BatchResolveAddressNamesInput, BatchResolveAddressNamesOutput,
+        BatchResolveDomainNamesInput, DomainPaginationInput, DomainRecords, DomainSortField,
+        DomainToken, DomainTokenType, ExportAddressInput, GetDomainByHashInput, GetDomainInput,
+        GetDomainOutput, GetDomainRecordsInput, GetSubdomainsInput, LookupAddressInput,
+        LookupDomainInput, LookupMulticoinAddressInput, NetworkStats, QuickSearchInput,
+        QuickSearchRank,
     },
 };
 use bens_proto::blockscout::bens::v1 as proto;
-use ethers::types::Address;
+use ethers::types::{Address, H256};
 use std::str::FromStr;
 
 const DEFAULT_PAGE_SIZE: u32 = 50;
+const MAX_BATCH_RESOLVE_NAMES: usize = 500;
+const DEFAULT_QUICK_SEARCH_LIMIT: u32 = 10;
+const MAX_QUICK_SEARCH_LIMIT: u32 = 50;
+const ETH_COIN_TYPE: u64 = 60;
 
 pub fn get_domain_input_from_inner(
     inner: proto::GetDomainRequest,
@@ -21,6 +36,47 @@ pub fn get_domain_input_from_inner(
         network_id: inner.chain_id,
         name,
         only_active: inner.only_active,
+        coin_types: coin_types_from_inner(inner.coin_types)?,
+        include_resolved_address_history: inner.include_resolved_address_history,
+    })
+}
+
+fn coin_types_from_inner(coin_types: Option<String>) -> Result<Vec<String>, ConversionError> {
+    coin_types
+        .iter()
+        .flat_map(|coin_types| coin_types.split(','))
+        .map(str::trim)
+        .filter(|coin_type| !coin_type.is_empty())
+        .map(|coin_type| {
+            coin_type
+                .parse::<u64>()
+                .map(|coin_type| coin_type.to_string())
+                .map_err(|_| {
+                    ConversionError::UserRequest(format!("invalid coin type '{coin_type}'"))
+                })
+        })
+        .collect()
+}
+
+pub fn get_domain_by_hash_input_from_inner(
+    inner: proto::GetDomainByHashRequest,
+) -> Result<GetDomainByHashInput, ConversionError> {
+    let hash = H256::from_str(&inner.hash)
+        .map_err(|e| ConversionError::UserRequest(format!("invalid hash '{}': {e}", inner.hash)))?;
+    Ok(GetDomainByHashInput {
+        network_id: inner.chain_id,
+        hash,
+        only_active: inner.only_active,
+    })
+}
+
+pub fn get_domain_records_input_from_inner(
+    inner: proto::GetDomainRecordsRequest,
+) -> Result<GetDomainRecordsInput, ConversionError> {
+    let name = name_from_inner(inner.name)?;
+    Ok(GetDomainRecordsInput {
+        network_id: inner.chain_id,
+        name,
     })
 }
 
@@ -34,6 +90,7 @@ pub fn lookup_domain_name_from_inner(
         network_id: inner.chain_id,
         name,
         only_active: inner.only_active,
+        include_burned: inner.include_burned,
         pagination: DomainPaginationInput {
             sort,
             order,
@@ -43,18 +100,47 @@ pub fn lookup_domain_name_from_inner(
     })
 }
 
+pub fn quick_search_input_from_inner(
+    inner: proto::QuickSearchRequest,
+) -> Result<QuickSearchInput, ConversionError> {
+    let rank = quick_search_rank_from_inner(inner.rank.as_deref().unwrap_or_default())?;
+    Ok(QuickSearchInput {
+        network_id: inner.chain_id,
+        query: inner.q,
+        limit: inner
+            .limit
+            .unwrap_or(DEFAULT_QUICK_SEARCH_LIMIT)
+            .clamp(1, MAX_QUICK_SEARCH_LIMIT),
+        rank,
+    })
+}
+
+pub fn quick_search_rank_from_inner(inner: &str) -> Result<QuickSearchRank, ConversionError> {
+    match inner {
+        "" | "name" => Ok(QuickSearchRank::Name),
+        "registration_date" | "registrationDate" => Ok(QuickSearchRank::RegistrationDate),
+        _ => Err(ConversionError::UserRequest(format!(
+            "unknow rank '{inner}'"
+        ))),
+    }
+}
+
 pub fn lookup_address_from_inner(
     inner: proto::LookupAddressRequest,
 ) -> Result<LookupAddressInput, ConversionError> {
     let sort = domain_sort_from_inner(&inner.sort)?;
     let order = order_direction_from_inner(inner.order());
     let address = address_from_str_inner(&inner.address)?;
+    let parent_domain = inner.parent_domain.map(name_from_inner).transpose()?;
     Ok(LookupAddressInput {
         network_id: inner.chain_id,
         address,
         resolved_to: inner.resolved_to,
         owned_by: inner.owned_by,
+        registered_by: inner.registered_by,
         only_active: inner.only_active,
+        include_burned: inner.include_burned,
+        parent_domain,
         pagination: DomainPaginationInput {
             sort,
             order,
@@ -64,6 +150,106 @@ pub fn lookup_address_from_inner(
     })
 }
 
+/// Lookup of address of other coin than ETH, None if request is regular address lookup
+pub fn lookup_multicoin_address_from_inner(
+    inner: &proto::LookupAddressRequest,
+) -> Result<Option<LookupMulticoinAddressInput>, ConversionError> {
+    let coin_type = match inner.coin_type {
+        Some(coin_type) if coin_type != ETH_COIN_TYPE => coin_type.to_string(),
+        _ => return Ok(None),
+    };
+    let address = Coin::find_or_unknown(&coin_type)
+        .parse_address(&inner.address)
+        .map_err(|e| {
+            ConversionError::UserRequest(format!("invalid address '{}': {e}", inner.address))
+        })?;
+    let sort = domain_sort_from_inner(&inner.sort)?;
+    let order = order_direction_from_inner(inner.order());
+    let parent_domain = inner
+        .parent_domain
+        .clone()
+        .map(name_from_inner)
+        .transpose()?;
+    Ok(Some(LookupMulticoinAddressInput {
+        network_id: inner.chain_id,
+        coin_type,
+        address: address.into(),
+        only_active: inner.only_active,
+        include_burned: inner.include_burned,
+        parent_domain,
+        pagination: DomainPaginationInput {
+            sort,
+            order,
+            page_size: page_size_from_inner(inner.page_size),
+            page_token: inner.page_token.clone(),
+        },
+    }))
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn export_address_input_from_inner(
+    chain_id: i64,
+    address: &str,
+    resolved_to: bool,
+    owned_by: bool,
+    registered_by: bool,
+    only_active: bool,
+    include_burned: bool,
+    parent_domain: Option<String>,
+) -> Result<ExportAddressInput, ConversionError> {
+    let address = address_from_str_inner(address)?;
+    let parent_domain = parent_domain.map(name_from_inner).transpose()?;
+    Ok(ExportAddressInput {
+        network_id: chain_id,
+        address,
+        resolved_to,
+        owned_by,
+        registered_by,
+        only_active,
+        include_burned,
+        parent_domain,
+    })
+}
+
+pub fn get_subdomains_input_from_inner(
+    chain_id: i64,
+    name: String,
+    only_active: bool,
+    include_burned: bool,
+    order: proto::Order,
+    page_size: Option<u32>,
+    page_token: Option<String>,
+) -> Result<GetSubdomainsInput, ConversionError> {
+    let name = name_from_inner(name)?;
+    Ok(GetSubdomainsInput {
+        network_id: chain_id,
+        name,
+        only_active,
+        include_burned,
+        pagination: DomainPaginationInput {
+            sort: DomainSortField::RegistrationDate,
+            order: order_direction_from_inner(order),
+            page_size: page_size_from_inner(page_size),
+            page_token,
+        },
+    })
+}
+
+pub fn list_domain_subdomains_from_inner(
+    inner: proto::ListDomainSubdomainsRequest,
+) -> Result<GetSubdomainsInput, ConversionError> {
+    let order = inner.order();
+    get_subdomains_input_from_inner(
+        inner.chain_id,
+        inner.name,
+        inner.only_active,
+        inner.include_burned,
+        order,
+        inner.page_size,
+        inner.page_token,
+    )
+}
+
 pub fn domain_sort_from_inner(inner: &str) -> Result<DomainSortField, ConversionError> {
     match inner {
         "" | "registration_date" | "registrationDate" => Ok(DomainSortField::RegistrationDate),
@@ -87,11 +273,46 @@ pub fn batch_resolve_from_inner(
     })
 }
 
+pub fn batch_resolve_address_names_from_logic(
+    output: BatchResolveAddressNamesOutput,
+) -> proto::BatchResolveAddressNamesResponse {
+    proto::BatchResolveAddressNamesResponse {
+        names: output.names.into_iter().collect(),
+        other_names: output
+            .other_names
+            .into_iter()
+            .map(|(address, names)| (address, proto::OtherNames { names }))
+            .collect(),
+    }
+}
+
+pub fn batch_resolve_names_from_inner(
+    inner: proto::BatchResolveDomainNamesRequest,
+) -> Result<BatchResolveDomainNamesInput, ConversionError> {
+    if inner.names.len() > MAX_BATCH_RESOLVE_NAMES {
+        return Err(ConversionError::UserRequest(format!(
+            "too many names provided: max is {MAX_BATCH_RESOLVE_NAMES}, got {}",
+            inner.names.len()
+        )));
+    }
+    let names = inner
+        .names
+        .into_iter()
+        .map(name_from_inner)
+        .collect::<Result<_, _>>()?;
+    Ok(BatchResolveDomainNamesInput {
+        network_id: inner.chain_id,
+        names,
+    })
+}
+
 pub fn detailed_domain_from_logic(
     output: GetDomainOutput,
 ) -> Result<proto::DetailedDomain, ConversionError> {
     let domain = output.domain;
-    let owner = Some(proto::Address { hash: domain.owner });
+    // offchain domains have no onchain owner and registration
+    let is_onchain = !domain.stored_offchain;
+    let owner = is_onchain.then_some(proto::Address { hash: domain.owner });
     let resolved_address = domain
         .resolved_address
         .map(|resolved_address| proto::Address {
@@ -108,39 +329,128 @@ pub fn detailed_domain_from_logic(
         .into_iter()
         .map(domain_token_from_logic)
         .collect();
+    let content_hash = domain
+        .content_hash
+        .as_deref()
+        .and_then(ContentHash::new)
+        .map(content_hash_from_logic);
+    let name = domain.name.unwrap_or_default();
+    let name_warnings = name_warnings_from_logic(&name);
     Ok(proto::DetailedDomain {
         id: domain.id,
-        name: domain.name.unwrap_or_default(),
+        name,
         owner,
         resolved_address,
         registrant,
         wrapped_owner,
         expiry_date: domain.expiry_date.map(date_from_logic),
-        registration_date: date_from_logic(domain.registration_date),
+        registration_date: if is_onchain {
+            date_from_logic(domain.registration_date)
+        } else {
+            Default::default()
+        },
         other_addresses: domain.other_addresses.0.into_iter().collect(),
         tokens,
+        content_hash,
+        resolved_address_history: output
+            .resolved_address_history
+            .into_iter()
+            .map(resolved_address_change_from_logic)
+            .collect(),
+        unsafe_name: !name_warnings.is_empty(),
+        name_warnings,
+        expiry_status: expiry_status_from_logic(domain.expiry_status).into(),
+        namehash_mismatch: domain.namehash_mismatch,
+        offchain_provider: domain.offchain_provider,
+        text_records: domain.text_records.0,
+        stored_offchain: domain.stored_offchain,
     })
 }
 
+pub fn domain_records_from_logic(r: DomainRecords) -> proto::DomainRecords {
+    proto::DomainRecords {
+        id: r.id,
+        name: r.name,
+        addresses: r
+            .addresses
+            .into_iter()
+            .map(|a| proto::CoinAddress {
+                coin_type: a.coin_type,
+                coin_name: a.coin_name,
+                address: a.address,
+            })
+            .collect(),
+        text_records: r
+            .text_records
+            .into_iter()
+            .map(|t| proto::TextRecord {
+                key: t.key,
+                value: t.value,
+            })
+            .collect(),
+        content_hash: r.content_hash.map(content_hash_from_logic),
+    }
+}
+
+pub fn network_stats_from_logic(s: NetworkStats) -> proto::NetworkStats {
+    // counters are never negative
+    let count = |c: i64| c.max(0) as u64;
+    proto::NetworkStats {
+        total_domains: count(s.total_domains),
+        active_domains: count(s.active_domains),
+        registered_last_24h: count(s.registered_last_24h),
+        registered_last_7d: count(s.registered_last_7d),
+        unique_owners: count(s.unique_owners),
+    }
+}
+
 pub fn domain_from_logic(d: Domain) -> Result<proto::Domain, ConversionError> {
     let owner = Some(proto::Address { hash: d.owner });
     let resolved_address = d.resolved_address.map(|resolved_address| proto::Address {
         hash: resolved_address,
     });
+    let registrant = d
+        .registrant
+        .map(|registrant| proto::Address { hash: registrant });
     let wrapped_owner = d.wrapped_owner.map(|wrapped_owner| proto::Address {
         hash: wrapped_owner,
     });
+    let name = d.name.unwrap_or_default();
+    let name_warnings = name_warnings_from_logic(&name);
     Ok(proto::Domain {
         id: d.id,
-        name: d.name.unwrap_or_default(),
+        name,
         owner,
+        registrant,
         wrapped_owner,
         resolved_address,
         expiry_date: d.expiry_date.map(date_from_logic),
         registration_date: date_from_logic(d.registration_date),
+        unsafe_name: !name_warnings.is_empty(),
+        name_warnings,
+        expiry_status: expiry_status_from_logic(d.expiry_status).into(),
+        status: domain_status_from_logic(d.status).into(),
+        is_primary: d.is_primary,
+        namehash_mismatch: d.namehash_mismatch,
     })
 }
 
+fn expiry_status_from_logic(status: ExpiryStatus) -> proto::ExpiryStatus {
+    match status {
+        ExpiryStatus::Active => proto::ExpiryStatus::Active,
+        ExpiryStatus::InGracePeriod => proto::ExpiryStatus::InGracePeriod,
+        ExpiryStatus::Expired => proto::ExpiryStatus::Expired,
+    }
+}
+
+fn domain_status_from_logic(status: DomainStatus) -> proto::DomainStatus {
+    match status {
+        DomainStatus::Resolved => proto::DomainStatus::Resolved,
+        DomainStatus::Unresolved => proto::DomainStatus::Unresolved,
+        DomainStatus::Burned => proto::DomainStatus::Burned,
+    }
+}
+
 pub fn pagination_from_logic(
     page_token: Option<String>,
     page_size: u32,
@@ -174,11 +484,50 @@ fn date_from_logic(d: chrono::DateTime<chrono::Utc>) -> String {
     d.to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
 }
 
+fn content_hash_from_logic(c: ContentHash) -> proto::ContentHash {
+    proto::ContentHash {
+        raw: c.raw,
+        decoded: c.decoded,
+        protocol: c.protocol.map(|p| p.as_str().to_string()),
+        hash: c.hash,
+    }
+}
+
+fn resolved_address_change_from_logic(c: ResolvedAddressChange) -> proto::ResolvedAddressChange {
+    proto::ResolvedAddressChange {
+        address: Some(proto::Address {
+            hash: hex(c.address),
+        }),
+        block_number: c.block_number,
+        transaction_hash: hex(c.transaction_hash),
+        timestamp: c.timestamp,
+    }
+}
+
+fn name_warnings_from_logic(name: &str) -> Vec<String> {
+    name_warnings(name)
+        .into_iter()
+        .map(name_warning_from_logic)
+        .collect()
+}
+
+fn name_warning_from_logic(w: NameWarning) -> String {
+    match w {
+        NameWarning::MixedScripts => "mixed_scripts",
+        NameWarning::ConfusableCharacters => "confusable_characters",
+        NameWarning::InvisibleCharacters => "invisible_characters",
+        NameWarning::DisallowedCharacters => "disallowed_characters",
+        NameWarning::NotNormalized => "not_normalized",
+    }
+    .to_string()
+}
+
 fn domain_token_from_logic(t: DomainToken) -> proto::Token {
     proto::Token {
         id: t.id,
         contract_hash: format!("{:#x}", t.contract),
         r#type: domain_token_type_from_logic(t._type).into(),
+        metadata_url: t.metadata_url,
     }
 }
 
@@ -0,0 +1,208 @@
+//! Export of all domains of address as CSV or NDJSON. Domains are read by
+//! keyset cursor and streamed in chunks, so response is not limited by page size
+
+use crate::{
+    conversion,
+    services::domain_extractor::{map_convertion_error, map_subgraph_error},
+};
+use actix_web::{
+    http::{header, StatusCode},
+    web, HttpResponse, ResponseError,
+};
+use bens_logic::{entity::subgraph::domain::Domain, subgraphs_reader::SubgraphReader};
+use bens_proto::blockscout::bens::v1 as proto;
+use futures::{stream, StreamExt};
+use serde::Deserialize;
+use std::sync::Arc;
+
+const EXPORT_PATH: &str = "/api/v1/{chain_id}/addresses:export";
+const EXPORT_BATCH_SIZE: u32 = 1000;
+const CSV_HEADER: &str =
+    "id,name,resolved_address,owner,registrant,wrapped_owner,registration_date,expiry_date,unsafe_name\n";
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum ExportFormat {
+    #[default]
+    Csv,
+    Ndjson,
+}
+
+impl ExportFormat {
+    fn content_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv; charset=utf-8",
+            ExportFormat::Ndjson => "application/x-ndjson",
+        }
+    }
+
+    fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Ndjson => "ndjson",
+        }
+    }
+
+    fn header(&self) -> Option<&'static str> {
+        match self {
+            ExportFormat::Csv => Some(CSV_HEADER),
+            ExportFormat::Ndjson => None,
+        }
+    }
+
+    fn render(&self, domain: &proto::Domain) -> Result<String, ExportError> {
+        match self {
+            ExportFormat::Csv => {
+                let hash = |a: &Option<proto::Address>| {
+                    a.as_ref().map(|a| a.hash.clone()).unwrap_or_default()
+                };
+                let row = [
+                    domain.id.clone(),
+                    domain.name.clone(),
+                    hash(&domain.resolved_address),
+                    hash(&domain.owner),
+                    hash(&domain.registrant),
+                    hash(&domain.wrapped_owner),
+                    domain.registration_date.clone(),
+                    domain.expiry_date.clone().unwrap_or_default(),
+                    domain.unsafe_name.to_string(),
+                ];
+                Ok(row
+                    .iter()
+                    .map(|v| csv_field(v))
+                    .collect::<Vec<_>>()
+                    .join(",")
+                    + "\n")
+            }
+            ExportFormat::Ndjson => serde_json::to_string(domain)
+                .map(|line| line + "\n")
+                .map_err(|e| ExportError(tonic::Status::internal(e.to_string()))),
+        }
+    }
+}
+
+/// Field is quoted only if it contains separator, quote or line break
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    address: String,
+    #[serde(default)]
+    resolved_to: bool,
+    #[serde(default)]
+    owned_by: bool,
+    #[serde(default)]
+    registered_by: bool,
+    #[serde(default)]
+    only_active: bool,
+    #[serde(default)]
+    include_burned: bool,
+    parent_domain: Option<String>,
+    #[serde(default)]
+    format: ExportFormat,
+}
+
+/// Errors are returned in the same format as errors of other http routes
+#[derive(Debug, thiserror::Error)]
+#[error("{}", .0.message())]
+struct ExportError(tonic::Status);
+
+impl ResponseError for ExportError {
+    fn status_code(&self) -> StatusCode {
+        match self.0.code() {
+            tonic::Code::InvalidArgument => StatusCode::BAD_REQUEST,
+            tonic::Code::NotFound => StatusCode::NOT_FOUND,
+            tonic::Code::Unavailable => StatusCode::SERVICE_UNAVAILABLE,
+            tonic::Code::DeadlineExceeded => StatusCode::GATEWAY_TIMEOUT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code()).json(serde_json::json!({
+            "code": self.0.code() as i32,
+            "message": self.0.message(),
+        }))
+    }
+}
+
+pub fn route_export(config: &mut web::ServiceConfig, subgraph_reader: Arc<SubgraphReader>) {
+    config
+        .app_data(web::Data::from(subgraph_reader))
+        .route(EXPORT_PATH, web::get().to(export));
+}
+
+async fn export(
+    subgraph_reader: web::Data<SubgraphReader>,
+    chain_id: web::Path<i64>,
+    query: web::Query<ExportQuery>,
+) -> Result<HttpResponse, ExportError> {
+    let query = query.into_inner();
+    let format = query.format;
+    let input = conversion::export_address_input_from_inner(
+        chain_id.into_inner(),
+        &query.address,
+        query.resolved_to,
+        query.owned_by,
+        query.registered_by,
+        query.only_active,
+        query.include_burned,
+        query.parent_domain,
+    )
+    .map_err(|e| ExportError(map_convertion_error(e)))?;
+    let filename = format!("domains-{:#x}.{}", input.address, format.extension());
+    let cursor = subgraph_reader
+        .export_address_domains(input, EXPORT_BATCH_SIZE)
+        .await
+        .map_err(|e| ExportError(map_subgraph_error(e)))?;
+
+    let header_row = stream::iter(
+        format
+            .header()
+            .map(|h| Ok(web::Bytes::from_static(h.as_bytes()))),
+    );
+    // error in the middle of response can't change status code anymore,
+    // so stream is interrupted and client gets incomplete response
+    let rows = stream::try_unfold(cursor, move |mut cursor| async move {
+        let batch = cursor
+            .next_batch()
+            .await
+            .map_err(|e| ExportError(map_subgraph_error(e)))?;
+        if batch.is_empty() {
+            return Ok(None);
+        }
+        let chunk = render_batch(format, batch)?;
+        Ok::<_, ExportError>(Some((web::Bytes::from(chunk), cursor)))
+    });
+    let body = header_row.chain(rows.map(|chunk| {
+        chunk.map_err(|e: ExportError| {
+            tracing::error!(err =? e, "failed to export domains");
+            actix_web::Error::from(e)
+        })
+    }));
+
+    Ok(HttpResponse::Ok()
+        .content_type(format.content_type())
+        .insert_header((
+            header::CONTENT_DISPOSITION,
+            format!("attachment; filename=\"{filename}\""),
+        ))
+        .streaming(body))
+}
+
+fn render_batch(format: ExportFormat, batch: Vec<Domain>) -> Result<String, ExportError> {
+    batch
+        .into_iter()
+        .map(|domain| {
+            let domain = conversion::domain_from_logic(domain)
+                .map_err(|e| ExportError(map_convertion_error(e)))?;
+            format.render(&domain)
+        })
+        .collect()
+}
@@ -0,0 +1,63 @@
+//! GraphQL API over subgraph reader, so clients could fetch domain
+//! with exactly required fields (text records, subdomains, events) in one request
+
+mod query;
+mod types;
+
+use crate::{
+    conversion::ConversionError,
+    services::domain_extractor::{map_convertion_error, map_subgraph_error},
+};
+use actix_web::{web, HttpResponse};
+use async_graphql::{
+    http::GraphiQLSource, EmptyMutation, EmptySubscription, ErrorExtensions, Schema,
+};
+use async_graphql_actix_web::{GraphQLRequest, GraphQLResponse};
+use bens_logic::subgraphs_reader::{SubgraphReadError, SubgraphReader};
+use query::QueryRoot;
+use std::sync::Arc;
+
+const GRAPHQL_PATH: &str = "/api/v1/graphql";
+const MAX_QUERY_DEPTH: usize = 10;
+
+pub type BensSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+pub fn schema(subgraph_reader: Arc<SubgraphReader>) -> BensSchema {
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(subgraph_reader)
+        .limit_depth(MAX_QUERY_DEPTH)
+        .finish()
+}
+
+pub fn route_graphql(config: &mut web::ServiceConfig, schema: BensSchema) {
+    config.app_data(web::Data::new(schema)).service(
+        web::resource(GRAPHQL_PATH)
+            .route(web::post().to(graphql))
+            .route(web::get().to(graphiql)),
+    );
+}
+
+async fn graphql(schema: web::Data<BensSchema>, request: GraphQLRequest) -> GraphQLResponse {
+    schema.execute(request.into_inner()).await.into()
+}
+
+async fn graphiql() -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(GraphiQLSource::build().endpoint(GRAPHQL_PATH).finish())
+}
+
+/// Errors are classified the same way as in gRPC/REST api,
+/// grpc status code is put into `code` extension
+fn status_error(status: tonic::Status) -> async_graphql::Error {
+    async_graphql::Error::new(status.message())
+        .extend_with(|_, e| e.set("code", format!("{:?}", status.code())))
+}
+
+fn subgraph_error(err: SubgraphReadError) -> async_graphql::Error {
+    status_error(map_subgraph_error(err))
+}
+
+fn conversion_error(err: ConversionError) -> async_graphql::Error {
+    status_error(map_convertion_error(err))
+}
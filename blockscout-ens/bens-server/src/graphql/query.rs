@@ -0,0 +1,252 @@
+use super::{
+    conversion_error, subgraph_error,
+    types::{DetailedDomain, Domain, DomainEvent, DomainPage, TextRecord},
+};
+use crate::conversion::{self, pagination_from_logic};
+use async_graphql::{ComplexObject, Context, Enum, Object, Result};
+use bens_logic::subgraphs_reader::{GetTextRecordsInput, PaginatedResult, SubgraphReader};
+use bens_proto::blockscout::bens::v1 as proto;
+use std::sync::Arc;
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+impl From<Order> for proto::Order {
+    fn from(o: Order) -> Self {
+        match o {
+            Order::Asc => proto::Order::Asc,
+            Order::Desc => proto::Order::Desc,
+        }
+    }
+}
+
+fn order_or_default(order: Option<Order>) -> proto::Order {
+    order.map(Into::into).unwrap_or(proto::Order::Unspecified)
+}
+
+fn reader<'a>(ctx: &Context<'a>) -> Result<&'a Arc<SubgraphReader>> {
+    ctx.data::<Arc<SubgraphReader>>()
+}
+
+fn domain_page(
+    result: PaginatedResult<bens_logic::entity::subgraph::domain::Domain>,
+) -> Result<DomainPage> {
+    let items = result
+        .items
+        .into_iter()
+        .map(|d| conversion::domain_from_logic(d).map(Domain::from))
+        .collect::<Result<_, _>>()
+        .map_err(conversion_error)?;
+    Ok(DomainPage {
+        items,
+        total_records: result.total_records,
+        page_size: result.page_size,
+        next_page_params: pagination_from_logic(result.next_page_token, result.page_size)
+            .map(Into::into),
+    })
+}
+
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Domain by its name, like `vitalik.eth`
+    async fn domain(
+        &self,
+        ctx: &Context<'_>,
+        chain_id: i64,
+        name: String,
+        #[graphql(default)] only_active: bool,
+        #[graphql(default)] coin_types: Vec<u64>,
+        #[graphql(default)] include_resolved_address_history: bool,
+    ) -> Result<Option<DetailedDomain>> {
+        let input = conversion::get_domain_input_from_inner(proto::GetDomainRequest {
+            name,
+            chain_id,
+            only_active,
+            coin_types: (!coin_types.is_empty()).then(|| {
+                coin_types
+                    .iter()
+                    .map(u64::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",")
+            }),
+            include_resolved_address_history,
+        })
+        .map_err(conversion_error)?;
+        reader(ctx)?
+            .get_domain(input)
+            .await
+            .map_err(subgraph_error)?
+            .map(conversion::detailed_domain_from_logic)
+            .transpose()
+            .map_err(conversion_error)
+            .map(|d| d.map(|d| DetailedDomain::from_proto(chain_id, d)))
+    }
+
+    async fn domain_by_hash(
+        &self,
+        ctx: &Context<'_>,
+        chain_id: i64,
+        hash: String,
+        #[graphql(default)] only_active: bool,
+    ) -> Result<Option<DetailedDomain>> {
+        let input =
+            conversion::get_domain_by_hash_input_from_inner(proto::GetDomainByHashRequest {
+                hash,
+                chain_id,
+                only_active,
+            })
+            .map_err(conversion_error)?;
+        reader(ctx)?
+            .get_domain_by_hash(input)
+            .await
+            .map_err(subgraph_error)?
+            .map(conversion::detailed_domain_from_logic)
+            .transpose()
+            .map_err(conversion_error)
+            .map(|d| d.map(|d| DetailedDomain::from_proto(chain_id, d)))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn lookup_domain_name(
+        &self,
+        ctx: &Context<'_>,
+        chain_id: i64,
+        name: Option<String>,
+        #[graphql(default)] only_active: bool,
+        #[graphql(default)] include_burned: bool,
+        order: Option<Order>,
+        page_size: Option<u32>,
+        page_token: Option<String>,
+    ) -> Result<DomainPage> {
+        let input = conversion::lookup_domain_name_from_inner(proto::LookupDomainNameRequest {
+            name,
+            chain_id,
+            only_active,
+            include_burned,
+            sort: Default::default(),
+            order: order_or_default(order).into(),
+            page_size,
+            page_token,
+        })
+        .map_err(conversion_error)?;
+        let result = reader(ctx)?
+            .lookup_domain_name(input)
+            .await
+            .map_err(subgraph_error)?;
+        domain_page(result)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn lookup_address(
+        &self,
+        ctx: &Context<'_>,
+        chain_id: i64,
+        address: String,
+        #[graphql(default)] resolved_to: bool,
+        #[graphql(default)] owned_by: bool,
+        #[graphql(default)] registered_by: bool,
+        #[graphql(default)] only_active: bool,
+        #[graphql(default)] include_burned: bool,
+        parent_domain: Option<String>,
+        order: Option<Order>,
+        page_size: Option<u32>,
+        page_token: Option<String>,
+        coin_type: Option<u64>,
+    ) -> Result<DomainPage> {
+        let request = proto::LookupAddressRequest {
+            address,
+            chain_id,
+            resolved_to,
+            owned_by,
+            registered_by,
+            only_active,
+            include_burned,
+            sort: Default::default(),
+            order: order_or_default(order).into(),
+            page_size,
+            page_token,
+            parent_domain,
+            coin_type,
+        };
+        let reader = reader(ctx)?;
+        let result = match conversion::lookup_multicoin_address_from_inner(&request)
+            .map_err(conversion_error)?
+        {
+            Some(input) => reader.lookup_multicoin_address(input).await,
+            None => {
+                let input =
+                    conversion::lookup_address_from_inner(request).map_err(conversion_error)?;
+                reader.lookup_address(input).await
+            }
+        }
+        .map_err(subgraph_error)?;
+        domain_page(result)
+    }
+}
+
+/// Fields below are loaded only if requested
+#[ComplexObject]
+impl DetailedDomain {
+    /// Text records of current resolver, like `url` or `com.twitter`, sorted by key
+    async fn text_records(&self, ctx: &Context<'_>) -> Result<Vec<TextRecord>> {
+        let records = reader(ctx)?
+            .get_text_records(GetTextRecordsInput {
+                network_id: self.chain_id,
+                name: self.name.clone(),
+            })
+            .await
+            .map_err(subgraph_error)?;
+        Ok(records.into_iter().map(TextRecord::from).collect())
+    }
+
+    /// Direct subdomains, like `vitalik.eth` for `eth`
+    async fn subdomains(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default)] only_active: bool,
+        #[graphql(default)] include_burned: bool,
+        order: Option<Order>,
+        page_size: Option<u32>,
+        page_token: Option<String>,
+    ) -> Result<DomainPage> {
+        let input = conversion::get_subdomains_input_from_inner(
+            self.chain_id,
+            self.name.clone(),
+            only_active,
+            include_burned,
+            order_or_default(order),
+            page_size,
+            page_token,
+        )
+        .map_err(conversion_error)?;
+        let result = reader(ctx)?
+            .get_subdomains(input)
+            .await
+            .map_err(subgraph_error)?;
+        domain_page(result)
+    }
+
+    /// Transactions with events of domain
+    async fn events(&self, ctx: &Context<'_>, order: Option<Order>) -> Result<Vec<DomainEvent>> {
+        let input = conversion::list_domain_events_from_inner(proto::ListDomainEventsRequest {
+            name: self.name.clone(),
+            chain_id: self.chain_id,
+            sort: Default::default(),
+            order: order_or_default(order).into(),
+        })
+        .map_err(conversion_error)?;
+        reader(ctx)?
+            .get_domain_history(input)
+            .await
+            .map_err(subgraph_error)?
+            .into_iter()
+            .map(|e| conversion::event_from_logic(e).map(DomainEvent::from))
+            .collect::<Result<_, _>>()
+            .map_err(conversion_error)
+    }
+}
@@ -0,0 +1,286 @@
+use async_graphql::{Enum, SimpleObject};
+use bens_logic::entity::subgraph::domain::TextRecord as LogicTextRecord;
+use bens_proto::blockscout::bens::v1 as proto;
+
+#[derive(SimpleObject)]
+pub struct Address {
+    pub hash: String,
+}
+
+#[derive(SimpleObject)]
+#[graphql(complex)]
+pub struct DetailedDomain {
+    #[graphql(skip)]
+    pub chain_id: i64,
+    pub id: String,
+    pub name: String,
+    pub tokens: Vec<Token>,
+    pub owner: Option<Address>,
+    pub resolved_address: Option<Address>,
+    pub registrant: Option<Address>,
+    pub wrapped_owner: Option<Address>,
+    pub registration_date: String,
+    pub expiry_date: Option<String>,
+    /// Addresses of other coin types, like `60` for ETH, sorted by coin type
+    pub other_addresses: Vec<OtherAddress>,
+    pub content_hash: Option<ContentHash>,
+    pub resolved_address_history: Vec<ResolvedAddressChange>,
+    pub unsafe_name: bool,
+    pub name_warnings: Vec<String>,
+    pub expiry_status: ExpiryStatus,
+    /// Name of provider, which issued domain offchain, like `coinbase` for `*.cb.id`
+    pub offchain_provider: Option<String>,
+    /// True if domain is not stored onchain and was resolved by CCIP-read gateway
+    pub stored_offchain: bool,
+}
+
+#[derive(SimpleObject)]
+pub struct Domain {
+    pub id: String,
+    pub name: String,
+    pub owner: Option<Address>,
+    pub resolved_address: Option<Address>,
+    /// Owner of registration NFT, set only for domains of registrar
+    pub registrant: Option<Address>,
+    pub wrapped_owner: Option<Address>,
+    pub registration_date: String,
+    pub expiry_date: Option<String>,
+    pub unsafe_name: bool,
+    pub name_warnings: Vec<String>,
+    pub expiry_status: ExpiryStatus,
+    pub status: DomainStatus,
+    /// True if this domain is verified primary name of looked up address
+    pub is_primary: bool,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum ExpiryStatus {
+    Active,
+    /// Expired, but owner still can renew domain
+    InGracePeriod,
+    Expired,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum DomainStatus {
+    Resolved,
+    Unresolved,
+    /// Owned by zero address and not resolved to any address
+    Burned,
+}
+
+#[derive(SimpleObject)]
+pub struct DomainPage {
+    pub items: Vec<Domain>,
+    /// Number of domains matching the filters across all pages
+    pub total_records: i64,
+    pub page_size: u32,
+    pub next_page_params: Option<Pagination>,
+}
+
+#[derive(SimpleObject)]
+pub struct Pagination {
+    pub page_token: String,
+    pub page_size: u32,
+}
+
+#[derive(SimpleObject)]
+pub struct OtherAddress {
+    pub coin_type: String,
+    pub address: String,
+}
+
+#[derive(SimpleObject)]
+pub struct ContentHash {
+    pub raw: String,
+    pub decoded: Option<String>,
+    pub protocol: Option<String>,
+    pub hash: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct ResolvedAddressChange {
+    pub address: Option<Address>,
+    pub block_number: i64,
+    pub transaction_hash: String,
+    pub timestamp: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct Token {
+    pub id: String,
+    pub contract_hash: String,
+    #[graphql(name = "type")]
+    pub _type: TokenType,
+    pub metadata_url: Option<String>,
+}
+
+#[derive(Enum, Copy, Clone, Eq, PartialEq)]
+pub enum TokenType {
+    NativeDomainToken,
+    WrappedDomainToken,
+}
+
+#[derive(SimpleObject)]
+pub struct DomainEvent {
+    pub transaction_hash: String,
+    pub timestamp: String,
+    pub from_address: Option<Address>,
+    pub action: Option<String>,
+}
+
+#[derive(SimpleObject)]
+pub struct TextRecord {
+    pub key: String,
+    pub value: String,
+}
+
+impl From<proto::Address> for Address {
+    fn from(a: proto::Address) -> Self {
+        Self { hash: a.hash }
+    }
+}
+
+impl DetailedDomain {
+    pub fn from_proto(chain_id: i64, d: proto::DetailedDomain) -> Self {
+        let expiry_status = d.expiry_status().into();
+        let mut other_addresses: Vec<OtherAddress> = d
+            .other_addresses
+            .into_iter()
+            .map(|(coin_type, address)| OtherAddress { coin_type, address })
+            .collect();
+        other_addresses.sort_by_key(|a| (a.coin_type.parse::<u64>().ok(), a.coin_type.clone()));
+        Self {
+            chain_id,
+            id: d.id,
+            name: d.name,
+            tokens: d.tokens.into_iter().map(Token::from).collect(),
+            owner: d.owner.map(Address::from),
+            resolved_address: d.resolved_address.map(Address::from),
+            registrant: d.registrant.map(Address::from),
+            wrapped_owner: d.wrapped_owner.map(Address::from),
+            registration_date: d.registration_date,
+            expiry_date: d.expiry_date,
+            other_addresses,
+            content_hash: d.content_hash.map(ContentHash::from),
+            resolved_address_history: d
+                .resolved_address_history
+                .into_iter()
+                .map(ResolvedAddressChange::from)
+                .collect(),
+            expiry_status,
+            unsafe_name: d.unsafe_name,
+            name_warnings: d.name_warnings,
+            offchain_provider: d.offchain_provider,
+            stored_offchain: d.stored_offchain,
+        }
+    }
+}
+
+impl From<proto::Domain> for Domain {
+    fn from(d: proto::Domain) -> Self {
+        let expiry_status = d.expiry_status().into();
+        let status = d.status().into();
+        Self {
+            id: d.id,
+            name: d.name,
+            owner: d.owner.map(Address::from),
+            resolved_address: d.resolved_address.map(Address::from),
+            registrant: d.registrant.map(Address::from),
+            wrapped_owner: d.wrapped_owner.map(Address::from),
+            registration_date: d.registration_date,
+            expiry_date: d.expiry_date,
+            expiry_status,
+            status,
+            unsafe_name: d.unsafe_name,
+            name_warnings: d.name_warnings,
+            is_primary: d.is_primary,
+        }
+    }
+}
+
+impl From<proto::ExpiryStatus> for ExpiryStatus {
+    fn from(s: proto::ExpiryStatus) -> Self {
+        match s {
+            proto::ExpiryStatus::Active => ExpiryStatus::Active,
+            proto::ExpiryStatus::InGracePeriod => ExpiryStatus::InGracePeriod,
+            proto::ExpiryStatus::Expired => ExpiryStatus::Expired,
+        }
+    }
+}
+
+impl From<proto::DomainStatus> for DomainStatus {
+    fn from(s: proto::DomainStatus) -> Self {
+        match s {
+            proto::DomainStatus::Resolved => DomainStatus::Resolved,
+            proto::DomainStatus::Unresolved => DomainStatus::Unresolved,
+            proto::DomainStatus::Burned => DomainStatus::Burned,
+        }
+    }
+}
+
+impl From<proto::Pagination> for Pagination {
+    fn from(p: proto::Pagination) -> Self {
+        Self {
+            page_token: p.page_token,
+            page_size: p.page_size,
+        }
+    }
+}
+
+impl From<proto::ContentHash> for ContentHash {
+    fn from(c: proto::ContentHash) -> Self {
+        Self {
+            raw: c.raw,
+            decoded: c.decoded,
+            protocol: c.protocol,
+            hash: c.hash,
+        }
+    }
+}
+
+impl From<proto::ResolvedAddressChange> for ResolvedAddressChange {
+    fn from(c: proto::ResolvedAddressChange) -> Self {
+        Self {
+            address: c.address.map(Address::from),
+            block_number: c.block_number,
+            transaction_hash: c.transaction_hash,
+            timestamp: c.timestamp,
+        }
+    }
+}
+
+impl From<proto::Token> for Token {
+    fn from(t: proto::Token) -> Self {
+        let _type = match t.r#type() {
+            proto::TokenType::NativeDomainToken => TokenType::NativeDomainToken,
+            proto::TokenType::WrappedDomainToken => TokenType::WrappedDomainToken,
+        };
+        Self {
+            id: t.id,
+            contract_hash: t.contract_hash,
+            _type,
+            metadata_url: t.metadata_url,
+        }
+    }
+}
+
+impl From<proto::DomainEvent> for DomainEvent {
+    fn from(e: proto::DomainEvent) -> Self {
+        Self {
+            transaction_hash: e.transaction_hash,
+            timestamp: e.timestamp,
+            from_address: e.from_address.map(Address::from),
+            action: e.action,
+        }
+    }
+}
+
+impl From<LogicTextRecord> for TextRecord {
+    fn from(r: LogicTextRecord) -> Self {
+        Self {
+            key: r.key,
+            value: r.value,
+        }
+    }
+}
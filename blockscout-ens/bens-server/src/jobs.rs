@@ -1,6 +1,8 @@
+use crate::{admin::NamehashCheckState, server::network_infos, settings::SubgraphsReaderSettings};
 use anyhow::Context;
-use bens_logic::subgraphs_reader::SubgraphReader;
+use bens_logic::{notifier::Notifier, subgraphs_reader::SubgraphReader};
 use std::sync::Arc;
+use tokio::sync::Mutex;
 use tokio_cron_scheduler::Job;
 
 pub fn refresh_cache_job(
@@ -29,3 +31,128 @@ pub fn refresh_cache_job(
 
     Ok(job)
 }
+
+pub fn refresh_reverse_records_job(
+    schedule: &str,
+    subgraph_reader: Arc<SubgraphReader>,
+) -> Result<Job, anyhow::Error> {
+    let job = Job::new_async(schedule, move |_uuid, mut _l| {
+        let reader = subgraph_reader.clone();
+        Box::pin(async move {
+            let now = std::time::Instant::now();
+            reader.as_ref().refresh_reverse_records().await;
+            tracing::info!(
+                elapsed_secs = now.elapsed().as_secs_f32(),
+                "refreshed cached reverse records"
+            );
+        })
+    })
+    .context("creating refresh reverse records job")?;
+
+    Ok(job)
+}
+
+pub fn ensure_search_indexes_job(
+    schedule: &str,
+    subgraph_reader: Arc<SubgraphReader>,
+) -> Result<Job, anyhow::Error> {
+    let job = Job::new_async(schedule, move |_uuid, mut _l| {
+        let reader = subgraph_reader.clone();
+        Box::pin(async move {
+            reader.as_ref().ensure_search_indexes().await;
+        })
+    })
+    .context("creating ensure search indexes job")?;
+
+    Ok(job)
+}
+
+pub fn reload_networks_job(
+    schedule: &str,
+    settings: SubgraphsReaderSettings,
+    subgraph_reader: Arc<SubgraphReader>,
+) -> Result<Job, anyhow::Error> {
+    let current_networks = Arc::new(Mutex::new(
+        settings
+            .all_networks()
+            .context("reading initial networks")?,
+    ));
+    let job = Job::new_async(schedule, move |_uuid, mut _l| {
+        let reader = subgraph_reader.clone();
+        let settings = settings.clone();
+        let current_networks = current_networks.clone();
+        Box::pin(async move {
+            let networks = match settings.all_networks() {
+                Ok(networks) => networks,
+                Err(err) => {
+                    tracing::error!(err = ?err, "error during reading networks config");
+                    return;
+                }
+            };
+            let mut current_networks = current_networks.lock().await;
+            if *current_networks == networks {
+                return;
+            }
+            tracing::info!("networks config changed, reload subgraph reader networks");
+            match reader
+                .as_ref()
+                .reload_networks(network_infos(networks.clone()))
+                .await
+            {
+                Ok(_) => {
+                    *current_networks = networks;
+                    tracing::info!(
+                        networks =? reader.network_ids(),
+                        "reloaded networks successfully"
+                    );
+                }
+                Err(err) => {
+                    tracing::error!(err = ?err, "error during reloading networks");
+                }
+            };
+        })
+    })
+    .context("creating reload networks job")?;
+
+    Ok(job)
+}
+
+pub fn namehash_check_job(
+    schedule: &str,
+    subgraph_reader: Arc<SubgraphReader>,
+    max_reported_mismatches: usize,
+    state: Arc<NamehashCheckState>,
+) -> Result<Job, anyhow::Error> {
+    let job = Job::new_async(schedule, move |_uuid, mut _l| {
+        let reader = subgraph_reader.clone();
+        let state = state.clone();
+        Box::pin(async move {
+            let now = std::time::Instant::now();
+            let reports = reader.check_namehashes(max_reported_mismatches).await;
+            tracing::info!(
+                elapsed_secs = now.elapsed().as_secs_f32(),
+                mismatches_count = reports.iter().map(|r| r.mismatches_count).sum::<u64>(),
+                "checked namehashes of subgraphs"
+            );
+            state.set_reports(reports);
+        })
+    })
+    .context("creating namehash check job")?;
+
+    Ok(job)
+}
+
+pub fn notifier_job(schedule: &str, notifier: Arc<Notifier>) -> Result<Job, anyhow::Error> {
+    let job = Job::new_async(schedule, move |_uuid, mut _l| {
+        let notifier = notifier.clone();
+        Box::pin(async move {
+            let published = notifier.poll().await;
+            if published > 0 {
+                tracing::info!(published, "published domain changes");
+            }
+        })
+    })
+    .context("creating notifier job")?;
+
+    Ok(job)
+}
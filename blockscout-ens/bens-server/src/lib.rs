@@ -1,5 +1,9 @@
+mod admin;
 mod conversion;
+mod export;
+mod graphql;
 mod jobs;
+mod rate_limit;
 mod server;
 mod services;
 mod settings;
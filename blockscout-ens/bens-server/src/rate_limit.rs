@@ -0,0 +1,346 @@
+//! Token-bucket rate limiting of http routes. Every route pattern has its own
+//! buckets per client ip and per api key, so expensive batch routes could be
+//! limited stricter than the rest of api
+
+use crate::settings::{RateLimitSettings, TokenBucketSettings};
+use actix_web::{dev::ServiceRequest, http::StatusCode, HttpResponse, ResponseError};
+use std::{
+    collections::{HashMap, HashSet},
+    net::IpAddr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Full buckets are dropped at most once per this interval
+const EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, thiserror::Error)]
+#[error("rate limit exceeded, retry in {} seconds", .retry_after.as_secs().max(1))]
+pub struct RateLimitExceeded {
+    retry_after: Duration,
+}
+
+impl ResponseError for RateLimitExceeded {
+    fn status_code(&self) -> StatusCode {
+        StatusCode::TOO_MANY_REQUESTS
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        HttpResponse::build(self.status_code())
+            .insert_header(("retry-after", self.retry_after.as_secs().max(1).to_string()))
+            .json(serde_json::json!({
+                "code": tonic::Code::ResourceExhausted as i32,
+                "message": self.to_string(),
+            }))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Client {
+    Ip(String),
+    ApiKey(String),
+}
+
+#[derive(Debug)]
+struct Route {
+    segments: Vec<String>,
+    per_ip: Option<TokenBucketSettings>,
+    per_api_key: Option<TokenBucketSettings>,
+}
+
+impl Route {
+    fn matches(&self, path: &str) -> bool {
+        let mut path_segments = path.trim_end_matches('/').split('/');
+        self.segments.iter().all(|segment| {
+            path_segments.next().is_some_and(|path_segment| {
+                (segment.starts_with('{') && segment.ends_with('}')) || segment == path_segment
+            })
+        }) && path_segments.next().is_none()
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn refill(&mut self, limit: &TokenBucketSettings, now: Instant) {
+        let elapsed = now.duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * refill_per_second(limit)).min(capacity(limit));
+        self.updated_at = now;
+    }
+}
+
+fn capacity(limit: &TokenBucketSettings) -> f64 {
+    limit.burst.unwrap_or(limit.requests_per_minute).get() as f64
+}
+
+fn refill_per_second(limit: &TokenBucketSettings) -> f64 {
+    limit.requests_per_minute.get() as f64 / 60.0
+}
+
+#[derive(Debug)]
+struct Buckets {
+    buckets: HashMap<(usize, Client), Bucket>,
+    evicted_at: Instant,
+}
+
+#[derive(Debug)]
+pub struct RateLimiter {
+    api_key_header: String,
+    api_keys: HashSet<String>,
+    trusted_proxies: HashSet<IpAddr>,
+    routes: Vec<Route>,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    pub fn new(settings: &RateLimitSettings) -> Self {
+        let routes = settings
+            .routes
+            .iter()
+            .map(|route| Route {
+                segments: route
+                    .pattern
+                    .trim_end_matches('/')
+                    .split('/')
+                    .map(str::to_string)
+                    .collect(),
+                per_ip: route.per_ip,
+                per_api_key: route.per_api_key,
+            })
+            .collect();
+        Self {
+            api_key_header: settings.api_key_header.clone(),
+            api_keys: settings.api_keys.iter().cloned().collect(),
+            trusted_proxies: settings.trusted_proxies.iter().cloned().collect(),
+            routes,
+            buckets: Mutex::new(Buckets {
+                buckets: Default::default(),
+                evicted_at: Instant::now(),
+            }),
+        }
+    }
+
+    pub fn check_request(&self, req: &ServiceRequest) -> Result<(), RateLimitExceeded> {
+        let api_key = req
+            .headers()
+            .get(&self.api_key_header)
+            .and_then(|value| value.to_str().ok());
+        let ip = self.client_ip(req);
+        self.check(req.path(), api_key, ip.as_deref(), Instant::now())
+    }
+
+    /// Forwarded headers could be set by anyone, so they are
+    /// trusted only if request comes from configured proxy
+    fn client_ip(&self, req: &ServiceRequest) -> Option<String> {
+        let peer_ip = req.peer_addr()?.ip();
+        if self.trusted_proxies.contains(&peer_ip) {
+            req.connection_info()
+                .realip_remote_addr()
+                .map(|addr| strip_port(addr).to_string())
+        } else {
+            Some(peer_ip.to_string())
+        }
+    }
+
+    fn check(
+        &self,
+        path: &str,
+        api_key: Option<&str>,
+        ip: Option<&str>,
+        now: Instant,
+    ) -> Result<(), RateLimitExceeded> {
+        let Some((route_index, route)) = self
+            .routes
+            .iter()
+            .enumerate()
+            .find(|(_, route)| route.matches(path))
+        else {
+            return Ok(());
+        };
+        let known_api_key = api_key.filter(|key| self.api_keys.contains(*key));
+        let (client, limit) = match (known_api_key, route.per_api_key, route.per_ip) {
+            (Some(key), Some(limit), _) => (Client::ApiKey(key.to_string()), limit),
+            (_, _, Some(limit)) => match ip {
+                Some(ip) => (Client::Ip(ip.to_string()), limit),
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+
+        let mut buckets = self.buckets.lock().expect("rate limiter lock is poisoned");
+        if now.duration_since(buckets.evicted_at) >= EVICTION_INTERVAL {
+            self.drop_full_buckets(&mut buckets.buckets, now);
+            buckets.evicted_at = now;
+        }
+        let bucket = buckets
+            .buckets
+            .entry((route_index, client))
+            .or_insert_with(|| Bucket {
+                tokens: capacity(&limit),
+                updated_at: now,
+            });
+        bucket.refill(&limit, now);
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            Ok(())
+        } else {
+            let retry_after =
+                Duration::from_secs_f64((1.0 - bucket.tokens) / refill_per_second(&limit));
+            Err(RateLimitExceeded { retry_after })
+        }
+    }
+
+    fn drop_full_buckets(&self, buckets: &mut HashMap<(usize, Client), Bucket>, now: Instant) {
+        buckets.retain(|(route_index, client), bucket| {
+            let route = &self.routes[*route_index];
+            let limit = match client {
+                Client::Ip(_) => route.per_ip,
+                Client::ApiKey(_) => route.per_api_key,
+            };
+            limit.is_some_and(|limit| {
+                bucket.refill(&limit, now);
+                bucket.tokens < capacity(&limit)
+            })
+        });
+    }
+}
+
+/// `realip_remote_addr` keeps port of peer address, like `1.1.1.1:443` or `[::1]:443`
+fn strip_port(addr: &str) -> &str {
+    if let Some(rest) = addr.strip_prefix('[') {
+        return rest.split_once(']').map_or(addr, |(ip, _)| ip);
+    }
+    match addr.split_once(':') {
+        Some((ip, port)) if !port.contains(':') => ip,
+        _ => addr,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::RouteRateLimitSettings;
+    use actix_web::test::TestRequest;
+    use pretty_assertions::assert_eq;
+    use std::num::NonZeroU32;
+
+    const BATCH_ROUTE: &str = "/api/v1/{chain_id}/addresses:batch-resolve-names";
+
+    fn limiter(per_ip: TokenBucketSettings, per_api_key: TokenBucketSettings) -> RateLimiter {
+        RateLimiter::new(&RateLimitSettings {
+            api_key_header: "x-api-key".to_string(),
+            api_keys: vec!["secret".to_string()],
+            trusted_proxies: vec![],
+            routes: vec![
+                RouteRateLimitSettings {
+                    pattern: BATCH_ROUTE.to_string(),
+                    per_ip: Some(per_ip),
+                    per_api_key: Some(per_api_key),
+                },
+                // overlaps with previous route, which is matched first
+                RouteRateLimitSettings {
+                    pattern: "/api/v1/{chain_id}/{method}".to_string(),
+                    per_ip: None,
+                    per_api_key: None,
+                },
+            ],
+        })
+    }
+
+    fn limit(requests_per_minute: u32, burst: Option<u32>) -> TokenBucketSettings {
+        TokenBucketSettings {
+            requests_per_minute: NonZeroU32::new(requests_per_minute).unwrap(),
+            burst: burst.and_then(NonZeroU32::new),
+        }
+    }
+
+    #[test]
+    fn route_matching_works() {
+        let limiter = limiter(limit(60, None), limit(60, None));
+        let route = &limiter.routes[0];
+        assert!(route.matches("/api/v1/1/addresses:batch-resolve-names"));
+        assert!(route.matches("/api/v1/10200/addresses:batch-resolve-names/"));
+        assert!(!route.matches("/api/v1/1/addresses:lookup"));
+        assert!(!route.matches("/api/v1/addresses:batch-resolve-names"));
+        assert!(!route.matches("/api/v1/1/addresses:batch-resolve-names/extra"));
+        assert!(limiter.routes[1].matches("/api/v1/1/addresses:batch-resolve-names"));
+    }
+
+    #[test]
+    fn forwarded_ip_is_trusted_only_from_proxy() {
+        let mut limiter = limiter(limit(60, None), limit(60, None));
+        let request = || {
+            TestRequest::default()
+                .peer_addr("10.0.0.1:5000".parse().unwrap())
+                .insert_header(("x-forwarded-for", "1.1.1.1"))
+                .to_srv_request()
+        };
+        assert_eq!(limiter.client_ip(&request()).as_deref(), Some("10.0.0.1"));
+        limiter.trusted_proxies = HashSet::from(["10.0.0.1".parse().unwrap()]);
+        assert_eq!(limiter.client_ip(&request()).as_deref(), Some("1.1.1.1"));
+
+        assert_eq!(strip_port("1.1.1.1:443"), "1.1.1.1");
+        assert_eq!(strip_port("[::1]:443"), "::1");
+        assert_eq!(strip_port("::1"), "::1");
+    }
+
+    #[test]
+    fn full_buckets_are_evicted_periodically() {
+        let limiter = limiter(limit(60, None), limit(60, None));
+        let path = "/api/v1/1/addresses:batch-resolve-names";
+        let now = Instant::now();
+        for ip in ["1.1.1.1", "2.2.2.2"] {
+            assert!(limiter.check(path, None, Some(ip), now).is_ok());
+        }
+        let buckets_len = || limiter.buckets.lock().unwrap().buckets.len();
+        assert_eq!(buckets_len(), 2);
+        // buckets are refilled in a minute, so both of them are dropped by next eviction
+        assert!(limiter
+            .check(path, None, Some("3.3.3.3"), now + EVICTION_INTERVAL)
+            .is_ok());
+        assert_eq!(buckets_len(), 1);
+    }
+
+    #[test]
+    fn token_bucket_works() {
+        let limiter = limiter(limit(60, Some(2)), limit(600, None));
+        let path = "/api/v1/1/addresses:batch-resolve-names";
+        let now = Instant::now();
+
+        // burst is spent, then one token per second is refilled
+        assert!(limiter.check(path, None, Some("1.1.1.1"), now).is_ok());
+        assert!(limiter.check(path, None, Some("1.1.1.1"), now).is_ok());
+        let err = limiter
+            .check(path, None, Some("1.1.1.1"), now)
+            .expect_err("limit should be exceeded");
+        assert_eq!(err.retry_after, Duration::from_secs(1));
+        assert!(limiter
+            .check(path, None, Some("1.1.1.1"), now + Duration::from_secs(1))
+            .is_ok());
+
+        // other ips and other routes are not affected
+        assert!(limiter.check(path, None, Some("2.2.2.2"), now).is_ok());
+        for _ in 0..10 {
+            assert!(limiter
+                .check("/api/v1/1/addresses:lookup", None, Some("1.1.1.1"), now)
+                .is_ok());
+        }
+
+        // known api key has its own bucket, unknown key is limited by ip
+        assert!(limiter
+            .check(path, Some("unknown"), Some("1.1.1.1"), now)
+            .is_err());
+        for _ in 0..600 {
+            assert!(limiter
+                .check(path, Some("secret"), Some("1.1.1.1"), now)
+                .is_ok());
+        }
+        assert!(limiter
+            .check(path, Some("secret"), Some("1.1.1.1"), now)
+            .is_err());
+    }
+}
@@ -1,10 +1,22 @@
 use crate::{
+    admin::{self, NamehashCheckState},
+    export,
+    graphql::{self, BensSchema},
     jobs,
+    rate_limit::RateLimiter,
     services::{domain_extractor::DomainsExtractorService, health::HealthService},
-    settings::Settings,
+    settings::{NetworkSettings, PublisherSettings, Settings},
 };
+use actix_web::{dev::Service, web};
 use anyhow::Context;
-use bens_logic::subgraphs_reader::{blockscout::BlockscoutClient, NetworkInfo, SubgraphReader};
+use bens_logic::{
+    notifier::{EventPublisher, Notifier, WebhookPublisher},
+    state,
+    subgraphs_reader::{
+        blockscout::BlockscoutClient, label_decoder::LabelDecoderClient, lens::LensClient,
+        offchain::OffchainProvider, resolver_rpc::ResolverRpcClient, NetworkInfo, SubgraphReader,
+    },
+};
 use bens_proto::blockscout::bens::v1::{
     domains_extractor_actix::route_domains_extractor,
     domains_extractor_server::DomainsExtractorServer, health_actix::route_health,
@@ -12,7 +24,7 @@ use bens_proto::blockscout::bens::v1::{
 };
 use blockscout_service_launcher::{launcher, launcher::LaunchSettings};
 use sqlx::postgres::PgPoolOptions;
-use std::sync::Arc;
+use std::{collections::HashMap, sync::Arc};
 use tokio_cron_scheduler::JobScheduler;
 
 const SERVICE_NAME: &str = "bens";
@@ -21,6 +33,10 @@ const SERVICE_NAME: &str = "bens";
 struct Router {
     domains_extractor: Arc<DomainsExtractorService>,
     health: Arc<HealthService>,
+    graphql_schema: BensSchema,
+    subgraph_reader: Arc<SubgraphReader>,
+    rate_limiter: Option<Arc<RateLimiter>>,
+    namehash_check: Option<Arc<NamehashCheckState>>,
 }
 
 impl Router {
@@ -35,9 +51,22 @@ impl Router {
 
 impl launcher::HttpRouter for Router {
     fn register_routes(&self, service_config: &mut actix_web::web::ServiceConfig) {
-        service_config.configure(|config| route_health(config, self.health.clone()));
-        service_config
-            .configure(|config| route_domains_extractor(config, self.domains_extractor.clone()));
+        let rate_limiter = self.rate_limiter.clone();
+        service_config.service(
+            web::scope("")
+                .wrap_fn(move |req, srv| {
+                    let response = rate_limiter
+                        .as_ref()
+                        .map_or(Ok(()), |limiter| limiter.check_request(&req))
+                        .map(|_| srv.call(req));
+                    async move { response?.await }
+                })
+                .configure(|config| route_health(config, self.health.clone()))
+                .configure(|config| route_domains_extractor(config, self.domains_extractor.clone()))
+                .configure(|config| graphql::route_graphql(config, self.graphql_schema.clone()))
+                .configure(|config| export::route_export(config, self.subgraph_reader.clone()))
+                .configure(|config| admin::route_admin(config, self.namehash_check.clone())),
+        );
     }
 }
 
@@ -48,8 +77,6 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
         &settings.jaeger,
     )?;
 
-    let health = Arc::new(HealthService::default());
-
     let database_url = settings.database.connect.url();
     let pool = Arc::new(
         PgPoolOptions::new()
@@ -58,58 +85,146 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
             .await
             .context("database connect")?,
     );
-    let networks = settings
-        .subgraphs_reader
-        .networks
-        .into_iter()
-        .map(|(id, network)| {
-            let blockscout_client = BlockscoutClient::new(
-                network.blockscout.url,
-                network.blockscout.max_concurrent_requests,
-                network.blockscout.timeout,
-            );
-            (
-                id,
-                NetworkInfo {
-                    blockscout_client,
-                    subgraph_configs: network
-                        .subgraphs
-                        .into_iter()
-                        .map(|(name, settings)| {
-                            (
-                                name,
-                                bens_logic::subgraphs_reader::SubgraphSettings::from(settings),
-                            )
-                        })
-                        .collect(),
-                },
-            )
-        })
-        .collect();
+    let networks = network_infos(
+        settings
+            .subgraphs_reader
+            .all_networks()
+            .context("failed to read networks")?,
+    );
 
     tracing::info!("found networks from config: {networks:?}");
 
-    let subgraph_reader = Arc::new(
-        SubgraphReader::initialize(pool, networks)
-            .await
-            .context("failed to initialize subgraph-reader")?,
-    );
+    let mut subgraph_reader = SubgraphReader::initialize(pool, networks)
+        .await
+        .context("failed to initialize subgraph-reader")?
+        .with_retry_policy(settings.subgraphs_reader.query_retry.into())
+        .with_chunk_policy(settings.subgraphs_reader.batch_chunks.into());
+    if let Some(replica_database) = &settings.replica_database {
+        let replica_pool = Arc::new(
+            PgPoolOptions::new()
+                .max_connections(40)
+                .connect(&replica_database.connect.clone().url())
+                .await
+                .context("replica database connect")?,
+        );
+        subgraph_reader = subgraph_reader.with_read_replica(replica_pool);
+    }
+    if let Some(statement_timeout) = settings.subgraphs_reader.statement_timeout {
+        subgraph_reader = subgraph_reader.with_statement_timeouts(statement_timeout.into());
+    }
+    if let Some(cache) = &settings.subgraphs_reader.batch_search_cache {
+        subgraph_reader = subgraph_reader.with_batch_search_cache(cache.ttl_seconds, cache.size);
+    }
+    let state_pool = match &settings.state_database {
+        Some(state_database) => {
+            let state_pool = Arc::new(
+                PgPoolOptions::new()
+                    .max_connections(10)
+                    .connect(&state_database.connect.clone().url())
+                    .await
+                    .context("state database connect")?,
+            );
+            if state_database.run_migrations {
+                state::run_migrations(&state_pool)
+                    .await
+                    .context("state database migrations")?;
+            }
+            Some(state_pool)
+        }
+        None => None,
+    };
+    if let Some(state_pool) = &state_pool {
+        subgraph_reader = subgraph_reader.with_reverse_records_cache(state_pool.clone());
+    }
+    let subgraph_reader = Arc::new(subgraph_reader);
     let domains_extractor = Arc::new(DomainsExtractorService::new(subgraph_reader.clone()));
+    let health = Arc::new(HealthService::new(
+        subgraph_reader.clone(),
+        settings.subgraphs_reader.max_blocks_lag,
+    ));
 
+    let scheduler = JobScheduler::new().await?;
     if settings.subgraphs_reader.cache_enabled {
-        let scheduler = JobScheduler::new().await?;
         scheduler
             .add(jobs::refresh_cache_job(
                 &settings.subgraphs_reader.refresh_cache_schedule,
                 subgraph_reader.clone(),
             )?)
             .await?;
-        scheduler.start().await?;
     }
+    if state_pool.is_some() {
+        // initial refresh shouldn't delay startup, records are read from subgraph until it's done
+        let reader = subgraph_reader.clone();
+        tokio::spawn(async move { reader.refresh_reverse_records().await });
+        scheduler
+            .add(jobs::refresh_reverse_records_job(
+                &settings.subgraphs_reader.refresh_reverse_records_schedule,
+                subgraph_reader.clone(),
+            )?)
+            .await?;
+    }
+    // concurrent build of index could take a while, so it shouldn't delay startup
+    let reader = subgraph_reader.clone();
+    tokio::spawn(async move { reader.ensure_search_indexes().await });
+    scheduler
+        .add(jobs::ensure_search_indexes_job(
+            &settings.subgraphs_reader.search_indexes_schedule,
+            subgraph_reader.clone(),
+        )?)
+        .await?;
+    if let Some(networks_config) = &settings.subgraphs_reader.networks_config {
+        scheduler
+            .add(jobs::reload_networks_job(
+                &networks_config.reload_schedule,
+                settings.subgraphs_reader.clone(),
+                subgraph_reader.clone(),
+            )?)
+            .await?;
+    }
+    let namehash_check = match &settings.subgraphs_reader.namehash_check {
+        Some(check_settings) => {
+            let state = Arc::new(NamehashCheckState::new(check_settings.api_key.clone()));
+            scheduler
+                .add(jobs::namehash_check_job(
+                    &check_settings.schedule,
+                    subgraph_reader.clone(),
+                    check_settings.max_reported_mismatches,
+                    state.clone(),
+                )?)
+                .await?;
+            Some(state)
+        }
+        None => None,
+    };
+    if let Some(notifier_settings) = &settings.notifier {
+        let state_pool = state_pool
+            .clone()
+            .context("notifier requires state database to store its cursors")?;
+        let notifier = Arc::new(Notifier::new(
+            subgraph_reader.clone(),
+            event_publisher(&notifier_settings.publisher)?,
+            notifier_settings.max_blocks_per_poll,
+            state_pool,
+        ));
+        scheduler
+            .add(jobs::notifier_job(
+                &notifier_settings.poll_schedule,
+                notifier,
+            )?)
+            .await?;
+    }
+    scheduler.start().await?;
 
     let router = Router {
         domains_extractor,
         health,
+        graphql_schema: graphql::schema(subgraph_reader.clone()),
+        subgraph_reader: subgraph_reader.clone(),
+        rate_limiter: settings
+            .rate_limit
+            .as_ref()
+            .map(|rate_limit| Arc::new(RateLimiter::new(rate_limit))),
+        namehash_check,
     };
 
     let grpc_router = router.grpc_router();
@@ -123,3 +238,78 @@ pub async fn run(settings: Settings) -> Result<(), anyhow::Error> {
 
     launcher::launch(&launch_settings, http_router, grpc_router).await
 }
+
+pub fn network_infos(networks: HashMap<i64, NetworkSettings>) -> HashMap<i64, NetworkInfo> {
+    networks
+        .into_iter()
+        .map(|(id, network)| {
+            let blockscout_client = BlockscoutClient::new(
+                network.blockscout.url,
+                network.blockscout.max_concurrent_requests,
+                network.blockscout.timeout,
+            );
+            (
+                id,
+                NetworkInfo {
+                    blockscout_client,
+                    label_decoder: network.label_decoder.map(|decoder| {
+                        LabelDecoderClient::new(
+                            decoder.url,
+                            decoder.max_concurrent_requests,
+                            decoder.timeout,
+                        )
+                    }),
+                    offchain_providers: network
+                        .offchain_providers
+                        .into_iter()
+                        .map(|provider| {
+                            OffchainProvider::new(
+                                provider.name,
+                                provider.parent.to_lowercase(),
+                                provider.resolver,
+                                provider.gateway_url,
+                                provider.timeout,
+                            )
+                        })
+                        .collect(),
+                    resolver_rpc: network.resolver_rpc.map(|rpc| {
+                        let client = ResolverRpcClient::new(rpc.url, rpc.timeout);
+                        match rpc.allowed_gateway_hosts {
+                            Some(hosts) => client.with_allowed_gateway_hosts(hosts),
+                            None => client,
+                        }
+                    }),
+                    lens: network
+                        .lens
+                        .map(|lens| LensClient::new(lens.url, lens.timeout)),
+                    subgraph_configs: network
+                        .subgraphs
+                        .into_iter()
+                        .map(|(name, settings)| {
+                            (
+                                name,
+                                bens_logic::subgraphs_reader::SubgraphSettings::from(settings),
+                            )
+                        })
+                        .collect(),
+                    protocol_priority: network.protocol_priority,
+                    merged_protocols: network.merged_protocols,
+                },
+            )
+        })
+        .collect()
+}
+
+fn event_publisher(settings: &PublisherSettings) -> Result<Arc<dyn EventPublisher>, anyhow::Error> {
+    let publisher: Arc<dyn EventPublisher> = match settings {
+        PublisherSettings::Webhook { url, timeout } => {
+            Arc::new(WebhookPublisher::new(url.clone(), *timeout))
+        }
+        #[cfg(feature = "kafka")]
+        PublisherSettings::Kafka { brokers, topic } => Arc::new(
+            bens_logic::notifier::KafkaPublisher::new(brokers, topic.clone())
+                .context("failed to create kafka producer")?,
+        ),
+    };
+    Ok(publisher)
+}
@@ -1,14 +1,23 @@
-use crate::conversion::{self, batch_resolve_from_inner, pagination_from_logic, ConversionError};
+use crate::conversion::{
+    self, batch_resolve_address_names_from_logic, batch_resolve_from_inner,
+    batch_resolve_names_from_inner, pagination_from_logic, ConversionError,
+};
 use async_trait::async_trait;
 use bens_logic::{
     entity,
-    subgraphs_reader::{SubgraphReadError, SubgraphReader},
+    subgraphs_reader::{
+        AddressDomainsFilter, DomainStateFilter, SubgraphReadError, SubgraphReader,
+    },
 };
 use bens_proto::blockscout::bens::v1::{
     domains_extractor_server::DomainsExtractor, BatchResolveAddressNamesRequest,
-    BatchResolveAddressNamesResponse, DetailedDomain, Domain, DomainEvent, GetAddressRequest,
-    GetAddressResponse, GetDomainRequest, ListDomainEventsRequest, ListDomainEventsResponse,
-    LookupAddressRequest, LookupAddressResponse, LookupDomainNameRequest, LookupDomainNameResponse,
+    BatchResolveAddressNamesResponse, BatchResolveDomainNamesRequest,
+    BatchResolveDomainNamesResponse, DetailedDomain, Domain, DomainEvent, DomainRecords,
+    GetAddressRequest, GetAddressResponse, GetDomainByHashRequest, GetDomainRecordsRequest,
+    GetDomainRequest, GetNetworkStatsRequest, ListDomainEventsRequest, ListDomainEventsResponse,
+    ListDomainSubdomainsRequest, ListDomainSubdomainsResponse, LookupAddressRequest,
+    LookupAddressResponse, LookupDomainNameRequest, LookupDomainNameResponse, NetworkStats,
+    QuickSearchRequest, QuickSearchResponse,
 };
 use std::sync::Arc;
 
@@ -63,6 +72,23 @@ impl DomainsExtractor for DomainsExtractorService {
         Ok(tonic::Response::new(response))
     }
 
+    async fn get_domain_records(
+        &self,
+        request: tonic::Request<GetDomainRecordsRequest>,
+    ) -> Result<tonic::Response<DomainRecords>, tonic::Status> {
+        let request = request.into_inner();
+        let input = conversion::get_domain_records_input_from_inner(request)
+            .map_err(map_convertion_error)?;
+        let records = self
+            .subgraph_reader
+            .get_domain_records(input)
+            .await
+            .map_err(map_subgraph_error)?
+            .map(conversion::domain_records_from_logic)
+            .ok_or_else(|| tonic::Status::not_found("domain not found"))?;
+        Ok(tonic::Response::new(records))
+    }
+
     async fn lookup_domain_name(
         &self,
         request: tonic::Request<LookupDomainNameRequest>,
@@ -70,7 +96,6 @@ impl DomainsExtractor for DomainsExtractorService {
         let request = request.into_inner();
         let input =
             conversion::lookup_domain_name_from_inner(request).map_err(map_convertion_error)?;
-        let page_size = input.pagination.page_size;
         let result = self
             .subgraph_reader
             .lookup_domain_name(input)
@@ -79,27 +104,52 @@ impl DomainsExtractor for DomainsExtractorService {
         let domains = from_resolved_domains_result(result.items)?;
         let response = LookupDomainNameResponse {
             items: domains,
-            next_page_params: pagination_from_logic(result.next_page_token, page_size),
+            next_page_params: pagination_from_logic(result.next_page_token, result.page_size),
+            total_records: result.total_records as i32,
+            page_size: result.page_size,
         };
         Ok(tonic::Response::new(response))
     }
 
+    async fn quick_search(
+        &self,
+        request: tonic::Request<QuickSearchRequest>,
+    ) -> Result<tonic::Response<QuickSearchResponse>, tonic::Status> {
+        let input = conversion::quick_search_input_from_inner(request.into_inner())
+            .map_err(map_convertion_error)?;
+        let items = self
+            .subgraph_reader
+            .quick_search(input)
+            .await
+            .map_err(map_subgraph_error)?
+            .into_iter()
+            .map(conversion::domain_from_logic)
+            .collect::<Result<_, _>>()
+            .map_err(map_convertion_error)?;
+        Ok(tonic::Response::new(QuickSearchResponse { items }))
+    }
+
     async fn lookup_address(
         &self,
         request: tonic::Request<LookupAddressRequest>,
     ) -> Result<tonic::Response<LookupAddressResponse>, tonic::Status> {
         let request = request.into_inner();
-        let input = conversion::lookup_address_from_inner(request).map_err(map_convertion_error)?;
-        let page_size = input.pagination.page_size;
-        let result = self
-            .subgraph_reader
-            .lookup_address(input)
-            .await
-            .map_err(map_subgraph_error)?;
+        let multicoin_input = conversion::lookup_multicoin_address_from_inner(&request)
+            .map_err(map_convertion_error)?;
+        let result = if let Some(input) = multicoin_input {
+            self.subgraph_reader.lookup_multicoin_address(input).await
+        } else {
+            let input =
+                conversion::lookup_address_from_inner(request).map_err(map_convertion_error)?;
+            self.subgraph_reader.lookup_address(input).await
+        }
+        .map_err(map_subgraph_error)?;
         let items = from_resolved_domains_result(result.items)?;
         let response = LookupAddressResponse {
             items,
-            next_page_params: pagination_from_logic(result.next_page_token, page_size),
+            next_page_params: pagination_from_logic(result.next_page_token, result.page_size),
+            total_records: result.total_records as i32,
+            page_size: result.page_size,
         };
         Ok(tonic::Response::new(response))
     }
@@ -124,7 +174,19 @@ impl DomainsExtractor for DomainsExtractorService {
 
         let resolved_domains_count = self
             .subgraph_reader
-            .count_domains_by_address(network_id, address, true, false)
+            .count_domains_by_address(
+                network_id,
+                AddressDomainsFilter {
+                    address,
+                    resolved_to: true,
+                    owned_by: false,
+                    registered_by: false,
+                    state: DomainStateFilter {
+                        only_active: true,
+                        include_burned: true,
+                    },
+                },
+            )
             .await
             .map_err(map_subgraph_error)? as i32;
         Ok(tonic::Response::new(GetAddressResponse {
@@ -139,21 +201,101 @@ impl DomainsExtractor for DomainsExtractorService {
     ) -> Result<tonic::Response<BatchResolveAddressNamesResponse>, tonic::Status> {
         let request = request.into_inner();
         let input = batch_resolve_from_inner(request).map_err(map_convertion_error)?;
-        let names = self
+        let output = self
             .subgraph_reader
             .batch_resolve_address_names(input)
             .await
             .map_err(map_subgraph_error)?;
-        let response = BatchResolveAddressNamesResponse { names };
+        let response = batch_resolve_address_names_from_logic(output);
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn batch_resolve_domain_names(
+        &self,
+        request: tonic::Request<BatchResolveDomainNamesRequest>,
+    ) -> Result<tonic::Response<BatchResolveDomainNamesResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let input = batch_resolve_names_from_inner(request).map_err(map_convertion_error)?;
+        let addresses = self
+            .subgraph_reader
+            .batch_resolve_domain_names(input)
+            .await
+            .map_err(map_subgraph_error)?;
+        let response = BatchResolveDomainNamesResponse { addresses };
+        Ok(tonic::Response::new(response))
+    }
+
+    async fn get_network_stats(
+        &self,
+        request: tonic::Request<GetNetworkStatsRequest>,
+    ) -> Result<tonic::Response<NetworkStats>, tonic::Status> {
+        let request = request.into_inner();
+        let stats = self
+            .subgraph_reader
+            .get_network_stats(request.chain_id)
+            .await
+            .map_err(map_subgraph_error)?;
+        Ok(tonic::Response::new(conversion::network_stats_from_logic(
+            stats,
+        )))
+    }
+
+    async fn get_domain_by_hash(
+        &self,
+        request: tonic::Request<GetDomainByHashRequest>,
+    ) -> Result<tonic::Response<DetailedDomain>, tonic::Status> {
+        let request = request.into_inner();
+        let input = conversion::get_domain_by_hash_input_from_inner(request)
+            .map_err(map_convertion_error)?;
+        let domain = self
+            .subgraph_reader
+            .get_domain_by_hash(input)
+            .await
+            .map_err(map_subgraph_error)?
+            .map(conversion::detailed_domain_from_logic)
+            .transpose()
+            .map_err(map_convertion_error)?
+            .ok_or_else(|| tonic::Status::not_found("domain not found"))?;
+        Ok(tonic::Response::new(domain))
+    }
+
+    async fn list_domain_subdomains(
+        &self,
+        request: tonic::Request<ListDomainSubdomainsRequest>,
+    ) -> Result<tonic::Response<ListDomainSubdomainsResponse>, tonic::Status> {
+        let request = request.into_inner();
+        let input =
+            conversion::list_domain_subdomains_from_inner(request).map_err(map_convertion_error)?;
+        let result = self
+            .subgraph_reader
+            .get_subdomains(input)
+            .await
+            .map_err(map_subgraph_error)?;
+        let domains = from_resolved_domains_result(result.items)?;
+        let response = ListDomainSubdomainsResponse {
+            items: domains,
+            next_page_params: pagination_from_logic(result.next_page_token, result.page_size),
+            total_records: result.total_records as i32,
+            page_size: result.page_size,
+        };
         Ok(tonic::Response::new(response))
     }
 }
 
-fn map_subgraph_error(err: SubgraphReadError) -> tonic::Status {
+pub(crate) fn map_subgraph_error(err: SubgraphReadError) -> tonic::Status {
     match err {
         SubgraphReadError::NetworkNotFound(id) => {
             tonic::Status::invalid_argument(format!("network {id} not found"))
         }
+        SubgraphReadError::InvalidName(err) => tonic::Status::invalid_argument(err.to_string()),
+        SubgraphReadError::Transient(err) => {
+            tracing::warn!(err =? err, "transient db error during request handle");
+            tonic::Status::unavailable("database is temporarily unavailable, retry later")
+        }
+        SubgraphReadError::Timeout(err) => {
+            tracing::warn!(err =? err, "db query timeout during request handle");
+            tonic::Status::deadline_exceeded("database query timed out")
+        }
         _ => {
             tracing::error!(err =? err, "error during request handle");
             tonic::Status::internal("internal error")
@@ -161,7 +303,7 @@ fn map_subgraph_error(err: SubgraphReadError) -> tonic::Status {
     }
 }
 
-fn map_convertion_error(err: ConversionError) -> tonic::Status {
+pub(crate) fn map_convertion_error(err: ConversionError) -> tonic::Status {
     match err {
         ConversionError::UserRequest(_) => tonic::Status::invalid_argument(err.to_string()),
         ConversionError::LogicOutput(_) => tonic::Status::internal(err.to_string()),
@@ -1,16 +1,60 @@
+use bens_logic::subgraphs_reader::SubgraphReader;
 use bens_proto::blockscout::bens::v1::{
     health_check_response, health_server::Health, HealthCheckRequest, HealthCheckResponse,
 };
+use std::sync::Arc;
 
-#[derive(Default)]
-pub struct HealthService {}
+/// Service name for readiness check: `/health?service=readiness`.
+/// Fails with UNAVAILABLE if any subgraph of served networks is stale
+pub const READINESS_SERVICE: &str = "readiness";
+
+pub struct HealthService {
+    subgraph_reader: Arc<SubgraphReader>,
+    max_blocks_lag: i64,
+}
+
+impl HealthService {
+    pub fn new(subgraph_reader: Arc<SubgraphReader>, max_blocks_lag: i64) -> Self {
+        Self {
+            subgraph_reader,
+            max_blocks_lag,
+        }
+    }
+
+    async fn check_readiness(&self) -> Result<(), tonic::Status> {
+        let freshness = self
+            .subgraph_reader
+            .freshness(self.max_blocks_lag)
+            .await
+            .map_err(|err| {
+                tracing::error!(err = ?err, "failed to get subgraphs freshness");
+                tonic::Status::unavailable("failed to get subgraphs freshness")
+            })?;
+        let stale_networks: Vec<String> = freshness
+            .iter()
+            .filter(|network| network.is_stale)
+            .map(|network| network.network_id.to_string())
+            .collect();
+        if !stale_networks.is_empty() {
+            tracing::warn!(freshness =? freshness, "found stale networks");
+            return Err(tonic::Status::unavailable(format!(
+                "subgraphs of networks [{}] are stale",
+                stale_networks.join(", ")
+            )));
+        }
+        Ok(())
+    }
+}
 
 #[async_trait::async_trait]
 impl Health for HealthService {
     async fn check(
         &self,
-        _request: tonic::Request<HealthCheckRequest>,
+        request: tonic::Request<HealthCheckRequest>,
     ) -> Result<tonic::Response<HealthCheckResponse>, tonic::Status> {
+        if request.into_inner().service.as_deref() == Some(READINESS_SERVICE) {
+            self.check_readiness().await?;
+        }
         Ok(tonic::Response::new(HealthCheckResponse {
             status: health_check_response::ServingStatus::Serving.into(),
         }))
@@ -1,4 +1,8 @@
-use bens_logic::subgraphs_reader::AddressResolveTechnique;
+use anyhow::Context;
+use bens_logic::subgraphs_reader::{
+    AddressResolveTechnique, ChunkPolicy, ProtocolKind, RetryPolicy, ReverseRegistrar,
+    StatementTimeouts, TldRules, DEFAULT_GRACE_PERIOD_DAYS,
+};
 use blockscout_service_launcher::{
     database::{DatabaseConnectSettings, DatabaseSettings},
     launcher::{ConfigSettings, MetricsSettings, ServerSettings},
@@ -6,7 +10,7 @@ use blockscout_service_launcher::{
 };
 use ethers::types::{Address, Bytes};
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::{collections::HashMap, net::IpAddr, num::NonZeroU32, path::PathBuf, time::Duration};
 use url::Url;
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -23,6 +27,112 @@ pub struct Settings {
     #[serde(default)]
     pub subgraphs_reader: SubgraphsReaderSettings,
     pub database: DatabaseSettings,
+    /// Optional. Read replica of graph-node database, used for read-only queries
+    #[serde(default)]
+    pub replica_database: Option<ReplicaDatabaseSettings>,
+    /// Optional. Own database of bens for state derived from subgraphs,
+    /// like cached reverse records. Required by notifier
+    #[serde(default)]
+    pub state_database: Option<StateDatabaseSettings>,
+    /// Optional. Publishes changes of domain owners and resolved addresses
+    #[serde(default)]
+    pub notifier: Option<NotifierSettings>,
+    /// Optional. Token-bucket rate limiting of http routes
+    #[serde(default)]
+    pub rate_limit: Option<RateLimitSettings>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ReplicaDatabaseSettings {
+    pub connect: DatabaseConnectSettings,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct StateDatabaseSettings {
+    pub connect: DatabaseConnectSettings,
+    #[serde(default)]
+    pub run_migrations: bool,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct NotifierSettings {
+    #[serde(default = "default_notifier_poll_schedule")]
+    pub poll_schedule: String,
+    /// Max number of blocks of every network processed by one poll
+    #[serde(default = "default_notifier_max_blocks_per_poll")]
+    pub max_blocks_per_poll: i64,
+    pub publisher: PublisherSettings,
+}
+
+fn default_notifier_poll_schedule() -> String {
+    "*/10 * * * * *".to_string() // every 10 seconds
+}
+
+fn default_notifier_max_blocks_per_poll() -> i64 {
+    1000
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PublisherSettings {
+    Webhook {
+        url: Url,
+        #[serde(default = "default_webhook_timeout")]
+        timeout: u64,
+    },
+    #[cfg(feature = "kafka")]
+    Kafka { brokers: String, topic: String },
+}
+
+fn default_webhook_timeout() -> u64 {
+    30
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RateLimitSettings {
+    /// Header with api key of the client
+    #[serde(default = "default_api_key_header")]
+    pub api_key_header: String,
+    /// Only listed keys get `per_api_key` limits,
+    /// requests with unknown keys are limited by ip
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+    /// Client ip is taken from `Forwarded`/`X-Forwarded-For` headers only if
+    /// request comes from one of these proxies, otherwise peer address is used
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Limits by route pattern, first matching route is used
+    #[serde(default)]
+    pub routes: Vec<RouteRateLimitSettings>,
+}
+
+fn default_api_key_header() -> String {
+    "x-api-key".to_string()
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct RouteRateLimitSettings {
+    /// Route pattern, like `/api/v1/{chain_id}/addresses:batch-resolve-names`.
+    /// Any segment in braces matches any path segment
+    pub pattern: String,
+    #[serde(default)]
+    pub per_ip: Option<TokenBucketSettings>,
+    #[serde(default)]
+    pub per_api_key: Option<TokenBucketSettings>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct TokenBucketSettings {
+    pub requests_per_minute: NonZeroU32,
+    /// Max number of requests in a row, `requests_per_minute` by default
+    #[serde(default)]
+    pub burst: Option<NonZeroU32>,
 }
 
 impl ConfigSettings for Settings {
@@ -38,31 +148,297 @@ pub struct SubgraphsReaderSettings {
     pub refresh_cache_schedule: String,
     #[serde(default = "default_cache_enabled")]
     pub cache_enabled: bool,
+    /// Schedule of rebuilding cached reverse records of subgraphs without cache views
+    #[serde(default = "default_refresh_reverse_records_schedule")]
+    pub refresh_reverse_records_schedule: String,
+    /// Schedule of building missing or invalid search indexes of subgraphs
+    #[serde(default = "default_search_indexes_schedule")]
+    pub search_indexes_schedule: String,
+    #[serde(default)]
+    pub networks_config: Option<NetworksConfigSettings>,
+    /// Max number of blocks subgraph could lag behind chain head
+    /// before readiness check starts to fail
+    #[serde(default = "default_max_blocks_lag")]
+    pub max_blocks_lag: i64,
+    /// Optional. Short-living in-memory cache of batch address searches
+    #[serde(default)]
+    pub batch_search_cache: Option<BatchSearchCacheSettings>,
+    /// Retries of queries failed with transient database errors
+    #[serde(default)]
+    pub query_retry: QueryRetrySettings,
+    /// Splitting of large batch address lookups into concurrent queries
+    #[serde(default)]
+    pub batch_chunks: BatchChunksSettings,
+    /// Optional. Database cancels read-only queries running longer than their budget
+    #[serde(default)]
+    pub statement_timeout: Option<StatementTimeoutSettings>,
+    /// Optional. Periodic verification of namehashes of all subgraph domains
+    #[serde(default)]
+    pub namehash_check: Option<NamehashCheckSettings>,
 }
 
 fn default_cache_enabled() -> bool {
     true
 }
 
+fn default_max_blocks_lag() -> i64 {
+    100
+}
+
 fn default_refresh_cache_schedule() -> String {
     "0 0 * * * *".to_string() // every hour
 }
 
+fn default_refresh_reverse_records_schedule() -> String {
+    "0 */5 * * * *".to_string() // every 5 minutes
+}
+
+fn default_search_indexes_schedule() -> String {
+    "0 */10 * * * *".to_string() // every 10 minutes
+}
+
 impl Default for SubgraphsReaderSettings {
     fn default() -> Self {
         Self {
             networks: Default::default(),
             refresh_cache_schedule: default_refresh_cache_schedule(),
             cache_enabled: default_cache_enabled(),
+            refresh_reverse_records_schedule: default_refresh_reverse_records_schedule(),
+            search_indexes_schedule: default_search_indexes_schedule(),
+            networks_config: Default::default(),
+            max_blocks_lag: default_max_blocks_lag(),
+            batch_search_cache: Default::default(),
+            query_retry: Default::default(),
+            batch_chunks: Default::default(),
+            statement_timeout: Default::default(),
+            namehash_check: Default::default(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BatchSearchCacheSettings {
+    #[serde(default = "default_batch_search_cache_ttl_seconds")]
+    pub ttl_seconds: u64,
+    #[serde(default = "default_batch_search_cache_size")]
+    pub size: usize,
+}
+
+fn default_batch_search_cache_ttl_seconds() -> u64 {
+    30
+}
+
+fn default_batch_search_cache_size() -> usize {
+    100_000
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct QueryRetrySettings {
+    /// Total number of attempts of query, 1 disables retries
+    #[serde(default = "default_query_retry_max_attempts")]
+    pub max_attempts: u32,
+    /// Backoff before the first retry, doubled after every next attempt
+    #[serde(default = "default_query_retry_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_query_retry_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+}
+
+fn default_query_retry_max_attempts() -> u32 {
+    3
+}
+
+fn default_query_retry_initial_backoff_ms() -> u64 {
+    100
+}
+
+fn default_query_retry_max_backoff_ms() -> u64 {
+    2000
+}
+
+impl Default for QueryRetrySettings {
+    fn default() -> Self {
+        Self {
+            max_attempts: default_query_retry_max_attempts(),
+            initial_backoff_ms: default_query_retry_initial_backoff_ms(),
+            max_backoff_ms: default_query_retry_max_backoff_ms(),
         }
     }
 }
 
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct NamehashCheckSettings {
+    #[serde(default = "default_namehash_check_schedule")]
+    pub schedule: String,
+    /// Max number of mismatched domains listed in report of every subgraph
+    #[serde(default = "default_namehash_check_max_reported_mismatches")]
+    pub max_reported_mismatches: usize,
+    /// Admin endpoint requires `x-api-key` header with this key and is disabled if it's not set
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+fn default_namehash_check_schedule() -> String {
+    "0 0 3 * * *".to_string() // every day at 03:00
+}
+
+fn default_namehash_check_max_reported_mismatches() -> usize {
+    100
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct BatchChunksSettings {
+    /// Max number of addresses bound into one query
+    #[serde(default = "default_batch_chunk_size")]
+    pub chunk_size: usize,
+    #[serde(default = "default_batch_max_concurrent_chunks")]
+    pub max_concurrent_chunks: usize,
+}
+
+fn default_batch_chunk_size() -> usize {
+    1000
+}
+
+fn default_batch_max_concurrent_chunks() -> usize {
+    4
+}
+
+impl Default for BatchChunksSettings {
+    fn default() -> Self {
+        Self {
+            chunk_size: default_batch_chunk_size(),
+            max_concurrent_chunks: default_batch_max_concurrent_chunks(),
+        }
+    }
+}
+
+impl From<BatchChunksSettings> for ChunkPolicy {
+    fn from(settings: BatchChunksSettings) -> Self {
+        Self {
+            chunk_size: settings.chunk_size.max(1),
+            max_concurrent_chunks: settings.max_concurrent_chunks.max(1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct StatementTimeoutSettings {
+    /// Budget of single domain or address lookups
+    #[serde(default = "default_statement_timeout_interactive_ms")]
+    pub interactive_ms: u64,
+    /// Budget of batch resolving, exports and stats
+    #[serde(default = "default_statement_timeout_batch_ms")]
+    pub batch_ms: u64,
+}
+
+fn default_statement_timeout_interactive_ms() -> u64 {
+    10_000
+}
+
+fn default_statement_timeout_batch_ms() -> u64 {
+    60_000
+}
+
+impl From<StatementTimeoutSettings> for StatementTimeouts {
+    fn from(settings: StatementTimeoutSettings) -> Self {
+        Self {
+            interactive: Duration::from_millis(settings.interactive_ms),
+            batch: Duration::from_millis(settings.batch_ms),
+        }
+    }
+}
+
+impl From<QueryRetrySettings> for RetryPolicy {
+    fn from(settings: QueryRetrySettings) -> Self {
+        Self {
+            max_attempts: settings.max_attempts.max(1),
+            initial_backoff: Duration::from_millis(settings.initial_backoff_ms),
+            max_backoff: Duration::from_millis(settings.max_backoff_ms),
+        }
+    }
+}
+
+/// Separate file with networks, which is re-read by schedule,
+/// so networks could be added or removed without restart
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct NetworksConfigSettings {
+    pub path: PathBuf,
+    #[serde(default = "default_networks_config_reload_schedule")]
+    pub reload_schedule: String,
+}
+
+fn default_networks_config_reload_schedule() -> String {
+    "0 * * * * *".to_string() // every minute
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq, Default)]
+#[serde(deny_unknown_fields)]
+struct NetworksConfig {
+    #[serde(default)]
+    networks: HashMap<i64, NetworkSettings>,
+}
+
+impl NetworksConfigSettings {
+    pub fn load_networks(&self) -> Result<HashMap<i64, NetworkSettings>, anyhow::Error> {
+        let config: NetworksConfig = config::Config::builder()
+            .add_source(config::File::from(self.path.as_path()))
+            .build()?
+            .try_deserialize()?;
+        Ok(config.networks)
+    }
+}
+
+impl SubgraphsReaderSettings {
+    /// Networks from main config merged with networks from `networks_config` file.
+    /// Networks from file take precedence
+    pub fn all_networks(&self) -> Result<HashMap<i64, NetworkSettings>, anyhow::Error> {
+        let mut networks = self.networks.clone();
+        if let Some(networks_config) = &self.networks_config {
+            let file_networks = networks_config.load_networks().with_context(|| {
+                format!(
+                    "failed to load networks from {}",
+                    networks_config.path.display()
+                )
+            })?;
+            networks.extend(file_networks);
+        }
+        Ok(networks)
+    }
+}
+
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq, Default)]
 pub struct NetworkSettings {
     pub blockscout: BlockscoutSettings,
     #[serde(default)]
     pub subgraphs: HashMap<String, SubgraphSettings>,
+    /// Names of subgraphs whose reverse records win in address resolving, highest priority first.
+    /// Only default subgraph is used if empty
+    #[serde(default)]
+    pub protocol_priority: Vec<String>,
+    /// Names of subgraphs whose domains are merged in lists, like lookup by name or address.
+    /// Only default subgraph is used if empty
+    #[serde(default)]
+    pub merged_protocols: Vec<String>,
+    /// Service used to decode unknown labels of domains, disabled by default
+    #[serde(default)]
+    pub label_decoder: Option<LabelDecoderSettings>,
+    /// Providers of subdomains issued offchain, like `*.cb.id`
+    #[serde(default)]
+    pub offchain_providers: Vec<OffchainProviderSettings>,
+    /// Json rpc node used to resolve names missing in subgraph by wildcard resolvers
+    /// of their ancestors, following CCIP-read if needed. Disabled by default
+    #[serde(default)]
+    pub resolver_rpc: Option<ResolverRpcSettings>,
+    /// Lens API used to resolve Lens handles like `lens/stani` and handles of addresses.
+    /// Disabled by default
+    #[serde(default)]
+    pub lens: Option<LensSettings>,
 }
 
 #[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
@@ -75,12 +451,49 @@ pub struct SubgraphSettings {
     pub empty_label_hash: Option<Bytes>,
     #[serde(default)]
     pub native_token_contract: Option<Address>,
+    #[serde(default)]
+    pub wrapped_token_contract: Option<Address>,
+    /// Template of token metadata link with `{contract}` and `{id}` placeholders
+    #[serde(default)]
+    pub token_metadata_url: Option<String>,
+    /// Names with other top-level domains are rejected, empty list allows any tld
+    #[serde(default)]
+    pub allowed_tlds: Vec<String>,
+    /// Tld appended to names consisting of single label, like `vitalik`
+    #[serde(default)]
+    pub default_tld: Option<String>,
+    /// Tlds registered onchain, like `eth`. Names with other tlds, like `example.com`,
+    /// are treated as imported from DNS and parsed by DNS rules. Disabled if empty
+    #[serde(default)]
+    pub native_tlds: Vec<String>,
+    /// Days after expiry date during which owner still can renew domain
+    #[serde(default = "default_grace_period_days")]
+    pub grace_period_days: u32,
+    /// Suffix of reverse records nodes, `addr.reverse` by default.
+    /// L2 reverse registrars use `{coin_type}.reverse`, like `80002105.reverse` on Base
+    #[serde(default)]
+    pub reverse_node_suffix: Option<String>,
+    /// ENSIP-11 coin type of L2 reverse registrar, used to build
+    /// `{coin_type}.reverse` suffix if `reverse_node_suffix` is not set
+    #[serde(default)]
+    pub reverse_coin_type: Option<u64>,
+    /// Flag returned domains with namehash of name not matching id
+    #[serde(default)]
+    pub verify_namehash: bool,
+    /// Naming protocol of subgraph, `ens` or `unstoppable_domains`. Protocol defines namehash,
+    /// default TLDs and how addresses are stored
+    #[serde(default)]
+    pub protocol: ProtocolKind,
 }
 
 fn default_use_cache() -> bool {
     true
 }
 
+fn default_grace_period_days() -> u32 {
+    DEFAULT_GRACE_PERIOD_DAYS
+}
+
 impl From<SubgraphSettings> for bens_logic::subgraphs_reader::SubgraphSettings {
     fn from(value: SubgraphSettings) -> Self {
         Self {
@@ -88,6 +501,25 @@ impl From<SubgraphSettings> for bens_logic::subgraphs_reader::SubgraphSettings {
             address_resolve_technique: value.address_resolve_technique,
             empty_label_hash: value.empty_label_hash,
             native_token_contract: value.native_token_contract,
+            wrapped_token_contract: value.wrapped_token_contract,
+            token_metadata_url: value.token_metadata_url,
+            tld_rules: TldRules {
+                allowed_tlds: if value.allowed_tlds.is_empty() {
+                    value.protocol.default_tlds()
+                } else {
+                    value.allowed_tlds
+                },
+                default_tld: value.default_tld,
+                native_tlds: value.native_tlds,
+            },
+            grace_period_days: value.grace_period_days,
+            reverse_registrar: match (value.reverse_node_suffix, value.reverse_coin_type) {
+                (Some(node_suffix), _) => ReverseRegistrar { node_suffix },
+                (None, Some(coin_type)) => ReverseRegistrar::from_coin_type(coin_type),
+                (None, None) => ReverseRegistrar::default(),
+            },
+            verify_namehash: value.verify_namehash,
+            protocol: value.protocol,
         }
     }
 }
@@ -124,6 +556,53 @@ impl Default for BlockscoutSettings {
     }
 }
 
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LabelDecoderSettings {
+    /// Template of request url with `{labelhash}` (hex) and `{token_id}` (decimal labelhash) placeholders,
+    /// like `https://metadata.ens.domains/mainnet/0x57f1887a8bf19b14fc0df6fd9b2acc9af147ea85/{token_id}`
+    pub url: String,
+    #[serde(default = "default_max_concurrent_requests")]
+    pub max_concurrent_requests: usize,
+    #[serde(default = "default_blockscout_timeout")]
+    pub timeout: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ResolverRpcSettings {
+    pub url: String,
+    #[serde(default = "default_blockscout_timeout")]
+    pub timeout: u64,
+    /// Optional. If set, only gateways of offchain lookups on these hosts are requested,
+    /// otherwise any https gateway on public host is requested
+    #[serde(default)]
+    pub allowed_gateway_hosts: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct LensSettings {
+    pub url: String,
+    #[serde(default = "default_blockscout_timeout")]
+    pub timeout: u64,
+}
+
+#[derive(Debug, Clone, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct OffchainProviderSettings {
+    /// Name of provider returned with resolved domains, like `coinbase`
+    pub name: String,
+    /// Parent of offchain subdomains, like `cb.id`
+    pub parent: String,
+    /// Offchain resolver of parent, passed to gateway as `{sender}`
+    pub resolver: Address,
+    /// CCIP-read gateway url with `{sender}` and `{data}` placeholders
+    pub gateway_url: String,
+    #[serde(default = "default_blockscout_timeout")]
+    pub timeout: u64,
+}
+
 impl Settings {
     pub fn default(database_url: String) -> Self {
         Self {
@@ -137,6 +616,10 @@ impl Settings {
                 create_database: Default::default(),
                 run_migrations: Default::default(),
             },
+            replica_database: Default::default(),
+            state_database: Default::default(),
+            notifier: Default::default(),
+            rate_limit: Default::default(),
         }
     }
 }
@@ -56,11 +56,23 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
     // Sleep until server will start and calculate all values
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
+    // readiness check
+    let request: Value = send_get_request(&base, "/health?service=readiness").await;
+    assert_eq!(request, json!({"status": "SERVING"}));
+
     // get detailed domain
-    let request: Value = send_get_request(&base, "/api/v1/1/domains/vitalik.eth").await;
-    let vitalik_detailed_json = json!({
+    let request: Value = send_get_request(
+        &base,
+        "/api/v1/1/domains/vitalik.eth?include_resolved_address_history=true",
+    )
+    .await;
+    let mut vitalik_detailed_json = json!({
         "expiry_date": "2038-01-19T03:14:07.000Z",
         "id": "0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835",
+        "namehash_mismatch": false,
+        "offchain_provider": null,
+        "text_records": {},
+        "stored_offchain": false,
         "name": "vitalik.eth",
         "other_addresses": {
             "RSK": "0xf0d485009714cE586358E3761754929904D76B9D",
@@ -74,6 +86,9 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
         },
         "wrapped_owner": null,
         "registration_date": "2017-06-18T08:39:14.000Z",
+        "unsafe_name": false,
+        "name_warnings": [],
+        "expiry_status": "ACTIVE",
         "resolved_address": {
             "hash": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
         },
@@ -82,10 +97,55 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
                 "id": "79233663829379634837589865448569342784712482819484549289560981379859480642508",
                 "contract_hash": "0x57f1887a8bf19b14fc0df6fd9b2acc9af147ea85",
                 "type": "NATIVE_DOMAIN_TOKEN",
+                "metadata_url": "https://metadata.ens.domains/mainnet/0x57f1887a8bf19b14fc0df6fd9b2acc9af147ea85/79233663829379634837589865448569342784712482819484549289560981379859480642508",
             }
         ],
+        "content_hash": {
+            "raw": "0xe3010170122084b970556c2bcbd1f37d3b001c6c5566fc0ee7c7f1a76fdaedb78aefdca0ed00",
+            "decoded": "ipfs://QmXGkXcfasLtjLk9i5brs2sMYNbk34c8FGdBDCP864mrhV",
+            "protocol": "ipfs",
+            "hash": "QmXGkXcfasLtjLk9i5brs2sMYNbk34c8FGdBDCP864mrhV",
+        },
+        "resolved_address_history": [
+            {
+                "address": {
+                    "hash": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+                },
+                "block_number": "8834378",
+                "transaction_hash": "0x09922ac0caf1efcc8f68ce004f382b46732258870154d8805707a1d4b098dfd0",
+                "timestamp": "2019-10-29T13:47:34.000000Z",
+            },
+            {
+                "address": {
+                    "hash": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+                },
+                "block_number": "11862656",
+                "transaction_hash": "0x160ef4492c731ac6b59beebe1e234890cd55d4c556f8847624a0b47125fe4f84",
+                "timestamp": "2021-02-15T17:19:09.000000Z",
+            },
+        ],
     });
     assert_eq!(request, vitalik_detailed_json.clone());
+    // resolved address history is returned only on request
+    vitalik_detailed_json["resolved_address_history"] = json!([]);
+    let request: Value = send_get_request(&base, "/api/v1/1/domains/vitalik.eth").await;
+    assert_eq!(request, vitalik_detailed_json.clone());
+    // get detailed domain by namehash and by labelhash
+    for hash in [
+        "0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835",
+        "0xaf2caa1c2ca1d027f1ac823b529d0a67cd144264b2789fa2ea4d63a67c7103cc",
+    ] {
+        let request: Value =
+            send_get_request(&base, &format!("/api/v1/1/domains:by-hash/{hash}")).await;
+        assert_eq!(request, vitalik_detailed_json.clone());
+    }
+    // other addresses filtered by coin types
+    let request: Value =
+        send_get_request(&base, "/api/v1/1/domains/vitalik.eth?coin_types=137,0").await;
+    assert_eq!(
+        request["other_addresses"],
+        json!({"RSK": "0xf0d485009714cE586358E3761754929904D76B9D"})
+    );
     // get detailed domain with emojied name and with wrapped token
     let request: Value = send_get_request(&base, "/api/v1/1/domains/wa🇬🇲i.eth").await;
     assert_eq!(
@@ -93,6 +153,10 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
         json!({
             "expiry_date": "2038-01-19T03:14:07.000Z",
             "id": "0x5d438d292de31e08576d5bcd8a93aa41b401b9d9aeaba57da1a32c003e5fd5f5",
+            "namehash_mismatch": false,
+            "offchain_provider": null,
+            "text_records": {},
+            "stored_offchain": false,
             "name": "wa🇬🇲i.eth",
             "other_addresses": {},
             "owner": {
@@ -102,6 +166,9 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
                 "hash": "0x9c996076a85b46061d9a70ff81f013853a86b619",
             },
             "registration_date": "2021-11-12T11:36:46.000Z",
+            "unsafe_name": false,
+            "name_warnings": [],
+            "expiry_status": "ACTIVE",
             "resolved_address": {
                 "hash": "0x9c996076a85b46061d9a70ff81f013853a86b619",
             },
@@ -110,16 +177,20 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
                     "contract_hash": "0x57f1887a8bf19b14fc0df6fd9b2acc9af147ea85",
                     "id": "46567936673033819165815925923418529171479684343878036049875289456825310839168",
                     "type": "NATIVE_DOMAIN_TOKEN",
+                    "metadata_url": "https://metadata.ens.domains/mainnet/0x57f1887a8bf19b14fc0df6fd9b2acc9af147ea85/46567936673033819165815925923418529171479684343878036049875289456825310839168",
                 },
                 {
                     "contract_hash": "0xd4416b13d2b3a9abae7acd5d6c2bbdbe25686401",
                     "id": "42184447928009120460686389475560276149795188091233200941948299907753855407605",
                     "type": "WRAPPED_DOMAIN_TOKEN",
+                    "metadata_url": "https://metadata.ens.domains/mainnet/0xd4416b13d2b3a9abae7acd5d6c2bbdbe25686401/42184447928009120460686389475560276149795188091233200941948299907753855407605",
                 },
             ],
             "wrapped_owner": {
                 "hash": "0x9c996076a85b46061d9a70ff81f013853a86b619",
             },
+            "content_hash": null,
+            "resolved_address_history": [],
         })
     );
 
@@ -192,17 +263,53 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
     )
     .await;
 
+    // domain records
+    let records: Value = send_get_request(&base, "/api/v1/1/domains/sashaxyz.eth/records").await;
+    assert_eq!(
+        records,
+        json!({
+            "id": "0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff",
+            "name": "sashaxyz.eth",
+            "addresses": [
+                {
+                    "coin_type": "0",
+                    "coin_name": "BTC",
+                    "address": "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+                },
+                {
+                    "coin_type": "60",
+                    "coin_name": "ETH",
+                    "address": "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+                },
+            ],
+            "text_records": [
+                {"key": "com.twitter", "value": "sasha"},
+                {"key": "url", "value": "https://sasha.xyz"},
+            ],
+            "content_hash": null,
+        })
+    );
+
     // all domains lookup + check pagination
     let expected_domains = [
         json!({
             "expiry_date": "2038-01-19T03:14:07.000Z",
             "id": "0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff",
+            "namehash_mismatch": false,
             "name": "sashaxyz.eth",
             "owner": {
                 "hash": "0x66a6f7744ce4dea450910b81a7168588f992eafb",
             },
+            "registrant": {
+                "hash": "0x66a6f7744ce4dea450910b81a7168588f992eafb",
+            },
             "wrapped_owner": null,
             "registration_date": "2021-12-24T10:23:57.000Z",
+            "unsafe_name": false,
+            "name_warnings": [],
+            "expiry_status": "ACTIVE",
+            "status": "RESOLVED",
+            "is_primary": false,
             "resolved_address": {
                 "hash": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
             },
@@ -210,14 +317,23 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
         json!({
             "expiry_date": "2038-01-19T03:14:07.000Z",
             "id": "0x5d438d292de31e08576d5bcd8a93aa41b401b9d9aeaba57da1a32c003e5fd5f5",
+            "namehash_mismatch": false,
             "name": "wa🇬🇲i.eth",
             "owner": {
                 "hash": "0xd4416b13d2b3a9abae7acd5d6c2bbdbe25686401",
             },
+            "registrant": {
+                "hash": "0x9c996076a85b46061d9a70ff81f013853a86b619",
+            },
             "wrapped_owner": {
                 "hash": "0x9c996076a85b46061d9a70ff81f013853a86b619",
             },
             "registration_date": "2021-11-12T11:36:46.000Z",
+            "unsafe_name": false,
+            "name_warnings": [],
+            "expiry_status": "ACTIVE",
+            "status": "RESOLVED",
+            "is_primary": false,
             "resolved_address": {
                 "hash": "0x9c996076a85b46061d9a70ff81f013853a86b619",
             },
@@ -225,12 +341,21 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
         json!({
             "expiry_date": "2038-01-19T03:14:07.000Z",
             "id": "0xeb4f647bea6caa36333c816d7b46fdcb05f9466ecacc140ea8c66faf15b3d9f1",
+            "namehash_mismatch": false,
             "name": "test.eth",
             "owner": {
                 "hash": "0xbd6bbe64bf841b81fc5a6e2b760029e316f2783b",
             },
+            "registrant": {
+                "hash": "0xbd6bbe64bf841b81fc5a6e2b760029e316f2783b",
+            },
             "wrapped_owner": null,
             "registration_date": "2019-10-24T07:26:47.000Z",
+            "unsafe_name": false,
+            "name_warnings": [],
+            "expiry_status": "ACTIVE",
+            "status": "RESOLVED",
+            "is_primary": false,
             "resolved_address": {
                 "hash": "0xeefb13c7d42efcc655e528da6d6f7bbcf9a2251d",
             },
@@ -238,11 +363,18 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
         json!({
             "expiry_date": null,
             "id": "0x6db3aa7fbaf005b22a12dd698aa41e3456ea93d2ab312796ee29fca980c99dcd",
+            "namehash_mismatch": false,
             "name": "biglobe.eth",
             "owner": {
                 "hash": "0x916a3bc6f0306426adaaa101fe28fea7a5f69b06",
             },
+            "registrant": null,
             "registration_date": "2017-07-08T02:11:54.000Z",
+            "unsafe_name": false,
+            "name_warnings": [],
+            "expiry_status": "ACTIVE",
+            "status": "UNRESOLVED",
+            "is_primary": false,
             "resolved_address": null,
             "wrapped_owner": null,
         }),
@@ -253,7 +385,7 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
         "/api/v1/1/domains:lookup",
         HashMap::from_iter([("page_size".into(), "2".into())]),
         expected_domains[0..2].to_vec(),
-        Some((2, Some(page_token.clone()))),
+        Some((2, Some(page_token.clone()), 25)),
     )
     .await;
     expect_list_results(
@@ -264,7 +396,7 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
             ("page_token".into(), page_token.to_string()),
         ]),
         expected_domains[2..4].to_vec(),
-        Some((2, Some("1499286330".into()))),
+        Some((2, Some("1499286330".into()), 25)),
     )
     .await;
 
@@ -272,6 +404,7 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
     let expected_domains = vec![json!(
         {
             "id": "0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff",
+            "namehash_mismatch": false,
             "name": "sashaxyz.eth",
             "resolved_address": {
                 "hash": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045"
@@ -279,8 +412,16 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
             "owner": {
                 "hash": "0x66a6f7744ce4dea450910b81a7168588f992eafb"
             },
+            "registrant": {
+                "hash": "0x66a6f7744ce4dea450910b81a7168588f992eafb"
+            },
             "wrapped_owner": null,
             "registration_date": "2021-12-24T10:23:57.000Z",
+            "unsafe_name": false,
+            "name_warnings": [],
+            "expiry_status": "ACTIVE",
+            "status": "RESOLVED",
+            "is_primary": false,
             "expiry_date": "2038-01-19T03:14:07.000Z"
         }
     )];
@@ -289,14 +430,63 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
         "/api/v1/1/domains:lookup",
         HashMap::from_iter([("name".into(), "sashaxyz.eth".into())]),
         expected_domains.clone(),
-        Some((50, None)),
+        Some((50, None, 1)),
+    )
+    .await;
+    // subdomains of domain without subdomains
+    expect_list_results(
+        &base,
+        "/api/v1/1/domains/vitalik.eth/subdomains",
+        HashMap::new(),
+        vec![],
+        Some((50, None, 0)),
+    )
+    .await;
+    // lookup of BTC address
+    expect_list_results(
+        &base,
+        "/api/v1/1/addresses:lookup",
+        HashMap::from_iter([
+            (
+                "address".into(),
+                "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4".into(),
+            ),
+            ("coin_type".into(), "0".into()),
+        ]),
+        expected_domains.clone(),
+        Some((50, None, 1)),
     )
     .await;
 
+    // quick search
+    let response: Value =
+        send_get_request(&base, "/api/v1/1/domains:quick-search?q=bl&limit=2").await;
+    let names: Vec<&str> = response["items"]
+        .as_array()
+        .expect("items should be array")
+        .iter()
+        .map(|d| d["name"].as_str().expect("name should be string"))
+        .collect();
+    assert_eq!(names, vec!["blogger.eth", "blogspot.eth"]);
+
+    // network stats
+    let stats: Value = send_get_request(&base, "/api/v1/1/stats").await;
+    assert_eq!(
+        stats,
+        json!({
+            "total_domains": "25",
+            "active_domains": "24",
+            "registered_last_24h": "0",
+            "registered_last_7d": "0",
+            "unique_owners": "24",
+        })
+    );
+
     // address lookup
     let expected_addresses: Vec<Value> = vec![json!(
         {
             "id": "0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835",
+            "namehash_mismatch": false,
             "name": "vitalik.eth",
             "resolved_address": {
                 "hash": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045"
@@ -304,8 +494,16 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
             "owner": {
                 "hash": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045"
             },
+            "registrant": {
+                "hash": "0x220866b1a2219f40e72f5c628b65d54268ca3a9d"
+            },
             "wrapped_owner": null,
             "registration_date": "2017-06-18T08:39:14.000Z",
+            "unsafe_name": false,
+            "name_warnings": [],
+            "expiry_status": "ACTIVE",
+            "status": "RESOLVED",
+            "is_primary": true,
             "expiry_date": "2038-01-19T03:14:07.000Z"
         }
     )]
@@ -326,10 +524,47 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
             ("sort".into(), "registration_date".into()),
         ]),
         expected_addresses.clone(),
-        Some((50, None)),
+        Some((50, None, 2)),
+    )
+    .await;
+    // all vitalik domains are direct subdomains of `eth`
+    expect_list_results(
+        &base,
+        "/api/v1/1/addresses:lookup",
+        HashMap::from_iter([
+            (
+                "address".into(),
+                "0xd8da6bf26964af9d7eed9e03e53415d37aa96045".into(),
+            ),
+            ("resolved_to".into(), "true".into()),
+            ("owned_by".into(), "true".into()),
+            ("order".into(), "ASC".into()),
+            ("sort".into(), "registration_date".into()),
+            ("parent_domain".into(), "eth".into()),
+        ]),
+        expected_addresses.clone(),
+        Some((50, None, 2)),
     )
     .await;
 
+    // export of all address domains
+    let export_route = "/api/v1/1/addresses:export?address=0xd8da6bf26964af9d7eed9e03e53415d37aa96045&resolved_to=true&owned_by=true";
+    let csv = send_get_text_request(&base, export_route).await;
+    assert_eq!(
+        csv.lines().collect::<Vec<_>>(),
+        vec![
+            "id,name,resolved_address,owner,registrant,wrapped_owner,registration_date,expiry_date,unsafe_name",
+            "0xee6c4522aab0003e8d14cd40a6af439055fd2577951148c14b6cea9a53475835,vitalik.eth,0xd8da6bf26964af9d7eed9e03e53415d37aa96045,0xd8da6bf26964af9d7eed9e03e53415d37aa96045,0x220866b1a2219f40e72f5c628b65d54268ca3a9d,,2017-06-18T08:39:14.000Z,2038-01-19T03:14:07.000Z,false",
+            "0x68b620f61c87062cf680144f898582a631c90e39dd1badb35c241be0a7284fff,sashaxyz.eth,0xd8da6bf26964af9d7eed9e03e53415d37aa96045,0x66a6f7744ce4dea450910b81a7168588f992eafb,0x66a6f7744ce4dea450910b81a7168588f992eafb,,2021-12-24T10:23:57.000Z,2038-01-19T03:14:07.000Z,false",
+        ]
+    );
+    let ndjson = send_get_text_request(&base, &format!("{export_route}&format=ndjson")).await;
+    let exported: Vec<Value> = ndjson
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("invalid json line"))
+        .collect();
+    assert_eq!(exported, expected_addresses);
+
     // batch address resolving
     let response: Value = send_post_request(
         &base,
@@ -351,6 +586,31 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
             "names": {
                 "0x9c996076a85b46061d9a70ff81f013853a86b619": "wa🇬🇲i.eth",
                 "0xd8da6bf26964af9d7eed9e03e53415d37aa96045": "vitalik.eth",
+            },
+            "other_names": {},
+        })
+    );
+
+    // batch name resolving
+    let response: Value = send_post_request(
+        &base,
+        "/api/v1/1/domains:batch-resolve-addresses",
+        &json!({
+            "names": [
+                "vitalik.eth",
+                "sashaxyz.eth",
+                "expired.eth",
+                "this-name-does-not-exist.eth",
+            ],
+        }),
+    )
+    .await;
+    assert_eq!(
+        response,
+        json!({
+            "addresses": {
+                "sashaxyz.eth": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
+                "vitalik.eth": "0xd8da6bf26964af9d7eed9e03e53415d37aa96045",
             }
         })
     );
@@ -367,6 +627,59 @@ async fn check_basic_scenario_eth(settings: Settings, base: Url) {
             "resolved_domains_count": 2,
         })
     );
+
+    // graphql
+    let response: Value = send_post_request(
+        &base,
+        "/api/v1/graphql",
+        &json!({
+            "query": r#"{
+                domain(chainId: 1, name: "sashaxyz.eth") {
+                    name
+                    owner { hash }
+                    textRecords { key value }
+                    subdomains { items { name } totalRecords pageSize nextPageParams { pageToken } }
+                }
+            }"#,
+        }),
+    )
+    .await;
+    assert_eq!(
+        response,
+        json!({
+            "data": {
+                "domain": {
+                    "name": "sashaxyz.eth",
+                    "owner": {
+                        "hash": "0x66a6f7744ce4dea450910b81a7168588f992eafb"
+                    },
+                    "textRecords": [
+                        {"key": "com.twitter", "value": "sasha"},
+                        {"key": "url", "value": "https://sasha.xyz"},
+                    ],
+                    "subdomains": {
+                        "items": [],
+                        "totalRecords": 0,
+                        "pageSize": 50,
+                        "nextPageParams": null,
+                    },
+                }
+            }
+        })
+    );
+    let response: Value = send_post_request(
+        &base,
+        "/api/v1/graphql",
+        &json!({
+            "query": r#"{ domain(chainId: 1, name: "vita_lik.eth") { name } }"#,
+        }),
+    )
+    .await;
+    assert_eq!(response["data"], json!(null));
+    assert_eq!(
+        response["errors"][0]["extensions"]["code"],
+        json!("InvalidArgument")
+    );
 }
 
 async fn check_basic_scenario_gno(settings: Settings, base: Url) {
@@ -382,23 +695,36 @@ async fn check_basic_scenario_gno(settings: Settings, base: Url) {
     // Sleep until server will start and calculate all values
     tokio::time::sleep(std::time::Duration::from_secs(1)).await;
 
-    let request: Value =
-        send_get_request(&base, &format!("/api/v1/{network_id}/domains/levvv.gno")).await;
+    let request: Value = send_get_request(
+        &base,
+        &format!("/api/v1/{network_id}/domains/levvv.gno?include_resolved_address_history=true"),
+    )
+    .await;
     assert_eq!(
         request,
         json!({
             "expiry_date": "2025-02-26T14:58:37.000Z",
             "id": "0xa3504cdec527495c69c760c85d5be9996252f853b91fd0df04c5b6aa2deb3347",
+            "namehash_mismatch": false,
+            "offchain_provider": null,
+            "text_records": {},
+            "stored_offchain": false,
             "name": "levvv.gno",
             "other_addresses": {},
             "owner": {
                 "hash": "0xc0de20a37e2dac848f81a93bd85fe4acdde7c0de",
             },
+            "registrant": {
+                "hash": "0xc0de20a37e2dac848f81a93bd85fe4acdde7c0de",
+            },
             "wrapped_owner": null,
             "registrant":{
                 "hash": "0xc0de20a37e2dac848f81a93bd85fe4acdde7c0de",
             },
             "registration_date": "2023-11-29T09:09:25.000Z",
+            "unsafe_name": false,
+            "name_warnings": [],
+            "expiry_status": "EXPIRED",
             "resolved_address":{
                 "hash": "0xc0de20a37e2dac848f81a93bd85fe4acdde7c0de",
             },
@@ -407,8 +733,21 @@ async fn check_basic_scenario_gno(settings: Settings, base: Url) {
                     "id": "11990319655936053415661126359086567018700354293176496925267203544835860524390",
                     "contract_hash": "0xfd3d666db2557983f3f04d61f90e35cc696f6d60",
                     "type": "NATIVE_DOMAIN_TOKEN",
+                    "metadata_url": null,
                 }
-            ]
+            ],
+            "content_hash": null,
+            "resolved_address_history": [
+                {
+                    "address": {
+                        "hash": "0xc0de20a37e2dac848f81a93bd85fe4acdde7c0de",
+                    },
+                    "block_number": "7159135",
+                    "transaction_hash": "0x77de9e07a1dac58e8385e75e1a362009ec0a57baa9a5273e988fec9b56a4e429",
+                    // transaction is unknown to mocked blockscout
+                    "timestamp": null,
+                },
+            ],
         })
     );
 }
@@ -418,13 +757,15 @@ async fn expect_list_results(
     route: &str,
     query_params: HashMap<String, String>,
     expected_items: Vec<Value>,
-    maybe_expected_paginated: Option<(u32, Option<String>)>,
+    maybe_expected_paginated: Option<(u32, Option<String>, i32)>,
 ) {
     let route_with_query = build_query(route, &query_params);
     let request: Value = send_get_request(base, &route_with_query).await;
     let mut expected: HashMap<String, Value> =
         HashMap::from_iter([("items".to_owned(), json!(expected_items))]);
-    if let Some((page_size, page_token)) = maybe_expected_paginated {
+    if let Some((page_size, page_token, total_records)) = maybe_expected_paginated {
+        expected.insert("total_records".to_owned(), json!(total_records));
+        expected.insert("page_size".to_owned(), json!(page_size));
         if let Some(page_token) = page_token {
             expected.insert(
                 "next_page_params".to_owned(),
@@ -440,6 +781,18 @@ async fn expect_list_results(
     assert_eq!(request, json!(expected));
 }
 
+async fn send_get_text_request(base: &Url, route: &str) -> String {
+    let response = reqwest::get(base.join(route).unwrap())
+        .await
+        .expect("failed to send request");
+    assert!(
+        response.status().is_success(),
+        "invalid status code: {}",
+        response.status()
+    );
+    response.text().await.expect("failed to read body")
+}
+
 fn build_query(route: &str, query_params: &HashMap<String, String>) -> String {
     if !query_params.is_empty() {
         let query = query_params
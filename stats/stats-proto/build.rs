@@ -16,7 +16,19 @@ fn compile(
         .protoc_arg("--openapiv2_opt")
         .protoc_arg("grpc_api_configuration=proto/api_config_http.yaml,output_format=yaml,allow_merge=true,merge_file_name=stats")
         .bytes(["."])
-        .type_attribute(".", "#[actix_prost_macros::serde]");
+        .type_attribute(".", "#[actix_prost_macros::serde]")
+        .field_attribute(
+            ".blockscout.stats.v1.GetLineChartRequest.resolution",
+            "#[serde(default)]",
+        )
+        .field_attribute(
+            ".blockscout.stats.v1.BatchGetChartsRequest.lines",
+            "#[serde(default)]",
+        )
+        .field_attribute(
+            ".blockscout.stats.v1.BatchGetChartsRequest.counters",
+            "#[serde(default)]",
+        );
 
     config.compile_protos(protos, includes)?;
     Ok(())
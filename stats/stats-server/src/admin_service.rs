@@ -0,0 +1,212 @@
+use crate::update_service::{UpdateJob, UpdateJobStatus, UpdateService};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use stats_proto::blockscout::stats::v1 as proto;
+use std::{str::FromStr, sync::Arc};
+use tonic::{metadata::MetadataMap, Request, Response, Status};
+
+const API_KEY_NAME: &str = "x-api-key";
+
+pub struct AdminService {
+    update_service: Arc<UpdateService>,
+    api_key: Option<String>,
+}
+
+impl AdminService {
+    pub fn new(update_service: Arc<UpdateService>, api_key: Option<String>) -> Self {
+        Self {
+            update_service,
+            api_key,
+        }
+    }
+
+    /// Returns error if request is not authorized to use admin api
+    fn authorization_error(&self, metadata: &MetadataMap) -> Option<Status> {
+        let Some(expected) = &self.api_key else {
+            return Some(Status::unavailable(
+                "admin api is disabled: api key is not set",
+            ));
+        };
+        match metadata.get(API_KEY_NAME).map(|api_key| api_key.to_str()) {
+            Some(Ok(api_key)) if api_key == expected => None,
+            Some(Err(err)) => Some(Status::invalid_argument(format!(
+                "invalid api key value ({API_KEY_NAME}): {err}"
+            ))),
+            _ => Some(Status::unauthenticated("invalid api key")),
+        }
+    }
+}
+
+fn job_to_proto(job: UpdateJob) -> proto::UpdateJob {
+    let status = match job.status() {
+        UpdateJobStatus::Running => proto::UpdateJobStatus::Running,
+        UpdateJobStatus::Done => proto::UpdateJobStatus::Done,
+        UpdateJobStatus::Failed => proto::UpdateJobStatus::Failed,
+    };
+    proto::UpdateJob {
+        id: job.id.to_string(),
+        status: status.into(),
+        charts: job.charts,
+        updated_charts: job.updated_charts,
+        failed_charts: job.failed_charts,
+    }
+}
+
+#[async_trait]
+impl proto::stats_admin_service_server::StatsAdminService for AdminService {
+    async fn force_full_update(
+        &self,
+        request: Request<proto::ForceFullUpdateRequest>,
+    ) -> Result<Response<proto::UpdateJob>, Status> {
+        if let Some(err) = self.authorization_error(request.metadata()) {
+            return Err(err);
+        }
+        let charts = request.into_inner().charts;
+        let job = self
+            .update_service
+            .start_force_full_update(charts)
+            .map_err(|name| Status::not_found(format!("chart {name} not found")))?;
+        Ok(Response::new(job_to_proto(job)))
+    }
+
+    async fn repair_chart(
+        &self,
+        request: Request<proto::RepairChartRequest>,
+    ) -> Result<Response<proto::UpdateJob>, Status> {
+        if let Some(err) = self.authorization_error(request.metadata()) {
+            return Err(err);
+        }
+        let request = request.into_inner();
+        let parse_date = |date: &str, field: &str| {
+            NaiveDate::from_str(date)
+                .map_err(|_| Status::invalid_argument(format!("invalid {field} date: {date}")))
+        };
+        let from = parse_date(&request.from, "from")?;
+        let to = parse_date(&request.to, "to")?;
+        if from > to {
+            return Err(Status::invalid_argument("from must not be after to"));
+        }
+        let job = self
+            .update_service
+            .start_repair(request.name, from, to)
+            .map_err(|name| Status::not_found(format!("line chart {name} not found")))?;
+        Ok(Response::new(job_to_proto(job)))
+    }
+
+    async fn get_update_job(
+        &self,
+        request: Request<proto::GetUpdateJobRequest>,
+    ) -> Result<Response<proto::UpdateJob>, Status> {
+        if let Some(err) = self.authorization_error(request.metadata()) {
+            return Err(err);
+        }
+        let id = request.into_inner().id;
+        let job = id
+            .parse()
+            .ok()
+            .and_then(|id| self.update_service.get_update_job(id))
+            .ok_or_else(|| Status::not_found(format!("update job {id} not found")))?;
+        Ok(Response::new(job_to_proto(job)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        charts::Charts,
+        config::toml_config::{Config, LineCharts},
+    };
+    use proto::stats_admin_service_server::StatsAdminService;
+    use sea_orm::DatabaseConnection;
+    use tonic::Code;
+
+    async fn admin_service(api_key: Option<&str>) -> AdminService {
+        let db = Arc::new(DatabaseConnection::Disconnected);
+        let charts = Arc::new(
+            Charts::new(
+                Config {
+                    counters: vec![],
+                    lines: LineCharts { sections: vec![] },
+                },
+                None,
+                None,
+                false,
+            )
+            .unwrap(),
+        );
+        let update_service = Arc::new(UpdateService::new(db.clone(), db, charts, 1).await.unwrap());
+        AdminService::new(update_service, api_key.map(String::from))
+    }
+
+    fn request<T>(message: T, api_key: Option<&str>) -> Request<T> {
+        let mut request = Request::new(message);
+        if let Some(api_key) = api_key {
+            request
+                .metadata_mut()
+                .insert(API_KEY_NAME, api_key.parse().unwrap());
+        }
+        request
+    }
+
+    #[tokio::test]
+    async fn admin_api_requires_key() {
+        let service = admin_service(None).await;
+        let err = service
+            .get_update_job(request(Default::default(), Some("key")))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::Unavailable);
+
+        let service = admin_service(Some("key")).await;
+        for api_key in [None, Some("wrong")] {
+            let err = service
+                .get_update_job(request(Default::default(), api_key))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), Code::Unauthenticated);
+        }
+        let err = service
+            .get_update_job(request(Default::default(), Some("key")))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn force_update_unknown_chart_fails() {
+        let service = admin_service(Some("key")).await;
+        let message = proto::ForceFullUpdateRequest {
+            charts: vec!["unknownChart".to_string()],
+            chain_id: None,
+        };
+        let err = service
+            .force_full_update(request(message, Some("key")))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn repair_validates_request() {
+        let service = admin_service(Some("key")).await;
+        let repair = |from: &str, to: &str| proto::RepairChartRequest {
+            name: "newTxns".to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            chain_id: None,
+        };
+        for (message, code) in [
+            (repair("2023-01-01", "2023-01-07"), Code::NotFound),
+            (repair("2023-01-07", "2023-01-01"), Code::InvalidArgument),
+            (repair("yesterday", "2023-01-01"), Code::InvalidArgument),
+            (repair("2023-01-01", ""), Code::InvalidArgument),
+        ] {
+            let err = service
+                .repair_chart(request(message, Some("key")))
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), code);
+        }
+    }
+}
@@ -0,0 +1,359 @@
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sea_orm::DatabaseConnection;
+use serde::{Deserialize, Serialize};
+use stats::{ChartMetadata, UpdateError};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlertKind {
+    /// Chart failed to update `failures` times in a row
+    Failing { failures: u32, error: String },
+    /// Chart hasn't been updated successfully within the staleness window
+    Stale {
+        last_updated_at: Option<DateTime<Utc>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    pub chain_id: Option<String>,
+    pub chart: String,
+    pub kind: AlertKind,
+}
+
+impl Alert {
+    pub fn message(&self) -> String {
+        let chart = match &self.chain_id {
+            Some(chain_id) => format!("chart {} of chain {}", self.chart, chain_id),
+            None => format!("chart {}", self.chart),
+        };
+        match &self.kind {
+            AlertKind::Failing { failures, error } => {
+                format!("{chart} failed to update {failures} times in a row: {error}")
+            }
+            AlertKind::Stale {
+                last_updated_at: Some(time),
+            } => format!("{chart} wasn't updated since {}", time.to_rfc3339()),
+            AlertKind::Stale {
+                last_updated_at: None,
+            } => format!("{chart} was never updated"),
+        }
+    }
+}
+
+/// Payload of alerts sent to webhook
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertFormat {
+    /// `{"chain_id": .., "chart": .., "kind": "failing" | "stale", "message": ..}`
+    #[default]
+    Json,
+    /// `{"text": ..}`, accepted by slack incoming webhooks and compatible services
+    Slack,
+}
+
+impl AlertFormat {
+    pub fn payload(&self, alert: &Alert) -> serde_json::Value {
+        match self {
+            AlertFormat::Json => {
+                let kind = match alert.kind {
+                    AlertKind::Failing { .. } => "failing",
+                    AlertKind::Stale { .. } => "stale",
+                };
+                serde_json::json!({
+                    "chain_id": alert.chain_id,
+                    "chart": alert.chart,
+                    "kind": kind,
+                    "message": alert.message(),
+                })
+            }
+            AlertFormat::Slack => serde_json::json!({ "text": alert.message() }),
+        }
+    }
+}
+
+#[async_trait]
+pub trait AlertSink: Send + Sync {
+    async fn send(&self, alert: &Alert) -> Result<(), anyhow::Error>;
+}
+
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+    format: AlertFormat,
+}
+
+impl WebhookSink {
+    pub fn new(url: String, format: AlertFormat, timeout: Duration) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(timeout)
+                .build()
+                .expect("failed to build http client"),
+            url,
+            format,
+        }
+    }
+}
+
+#[async_trait]
+impl AlertSink for WebhookSink {
+    async fn send(&self, alert: &Alert) -> Result<(), anyhow::Error> {
+        self.client
+            .post(&self.url)
+            .json(&self.format.payload(alert))
+            .send()
+            .await?
+            .error_for_status()?;
+        Ok(())
+    }
+}
+
+/// Tracks results of chart updates of a single chain and sends alerts about failing charts.
+/// Every alert is sent once, until the chart is updated successfully again
+pub struct Alerter {
+    sink: Arc<dyn AlertSink>,
+    chain_id: Option<String>,
+    max_consecutive_failures: u32,
+    /// Fallback for charts that were never updated, so they aren't stale right on start
+    started_at: DateTime<Utc>,
+    stale: Mutex<HashSet<String>>,
+}
+
+impl Alerter {
+    pub fn new(
+        sink: Arc<dyn AlertSink>,
+        chain_id: Option<String>,
+        max_consecutive_failures: u32,
+    ) -> Self {
+        Self {
+            sink,
+            chain_id,
+            max_consecutive_failures,
+            started_at: Utc::now(),
+            stale: Default::default(),
+        }
+    }
+
+    /// Handles result of the update run, `failed_updates` is the persisted number
+    /// of runs of the chart that failed in a row. The alert is sent in background,
+    /// so slow sink doesn't delay chart updates
+    pub fn on_update(
+        self: &Arc<Self>,
+        chart: &str,
+        failed_updates: u32,
+        result: &Result<(), UpdateError>,
+    ) {
+        let error = result.as_ref().err().map(|err| err.to_string());
+        if let Some(alert) = self.check_update(chart, failed_updates, error) {
+            let this = self.clone();
+            tokio::spawn(async move { this.send(alert).await });
+        }
+    }
+
+    fn check_update(
+        &self,
+        chart: &str,
+        failed_updates: u32,
+        error: Option<String>,
+    ) -> Option<Alert> {
+        let Some(error) = error else {
+            self.stale
+                .lock()
+                .expect("stale mutex is poisoned")
+                .remove(chart);
+            return None;
+        };
+        (failed_updates == self.max_consecutive_failures).then(|| Alert {
+            chain_id: self.chain_id.clone(),
+            chart: chart.to_string(),
+            kind: AlertKind::Failing {
+                failures: failed_updates,
+                error,
+            },
+        })
+    }
+
+    fn find_stale(
+        &self,
+        charts: &[String],
+        metadata: &HashMap<String, ChartMetadata>,
+        max_staleness: Duration,
+        now: DateTime<Utc>,
+    ) -> Vec<Alert> {
+        let max_staleness = chrono::Duration::from_std(max_staleness)
+            .unwrap_or_else(|_| chrono::Duration::max_value());
+        let mut stale = self.stale.lock().expect("stale mutex is poisoned");
+        charts
+            .iter()
+            .filter_map(|chart| {
+                let last_updated_at = metadata
+                    .get(chart)
+                    .and_then(|metadata| metadata.last_updated_at);
+                let since = last_updated_at
+                    .unwrap_or(self.started_at)
+                    .max(self.started_at);
+                if now - since <= max_staleness || !stale.insert(chart.clone()) {
+                    return None;
+                }
+                Some(Alert {
+                    chain_id: self.chain_id.clone(),
+                    chart: chart.clone(),
+                    kind: AlertKind::Stale { last_updated_at },
+                })
+            })
+            .collect()
+    }
+
+    /// Checks every `interval` whether `charts` were updated successfully within `max_staleness`
+    pub async fn run_staleness_checks(
+        self: Arc<Self>,
+        db: Arc<DatabaseConnection>,
+        charts: Vec<String>,
+        max_staleness: Duration,
+        interval: Duration,
+    ) {
+        loop {
+            tokio::time::sleep(interval).await;
+            let metadata = match stats::get_charts_metadata(&db).await {
+                Ok(metadata) => metadata,
+                Err(err) => {
+                    tracing::error!(
+                        "failed to read charts metadata for staleness check: {}",
+                        err
+                    );
+                    continue;
+                }
+            };
+            for alert in self.find_stale(&charts, &metadata, max_staleness, Utc::now()) {
+                self.send(alert).await;
+            }
+        }
+    }
+
+    async fn send(&self, alert: Alert) {
+        tracing::warn!(chart = alert.chart.as_str(), "{}", alert.message());
+        if let Err(err) = self.sink.send(&alert).await {
+            tracing::error!(
+                chart = alert.chart.as_str(),
+                "failed to send alert: {}",
+                err
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    struct MockSink;
+
+    #[async_trait]
+    impl AlertSink for MockSink {
+        async fn send(&self, _alert: &Alert) -> Result<(), anyhow::Error> {
+            Ok(())
+        }
+    }
+
+    fn alerter() -> Alerter {
+        Alerter::new(Arc::new(MockSink), Some("100".into()), 3)
+    }
+
+    #[test]
+    fn alert_is_sent_once_per_failure_streak() {
+        let alerter = alerter();
+        let error = || Some("boom".to_string());
+        assert_eq!(alerter.check_update("newTxns", 1, error()), None);
+        assert_eq!(alerter.check_update("newTxns", 2, error()), None);
+        let alert = alerter.check_update("newTxns", 3, error()).unwrap();
+        assert_eq!(
+            alert.kind,
+            AlertKind::Failing {
+                failures: 3,
+                error: "boom".into()
+            }
+        );
+        assert_eq!(
+            alert.message(),
+            "chart newTxns of chain 100 failed to update 3 times in a row: boom"
+        );
+        assert_eq!(alerter.check_update("newTxns", 4, error()), None);
+
+        // success resets the streak
+        assert_eq!(alerter.check_update("newTxns", 0, None), None);
+        assert_eq!(alerter.check_update("newTxns", 1, error()), None);
+        assert_eq!(alerter.check_update("newTxns", 2, error()), None);
+        assert!(alerter.check_update("newTxns", 3, error()).is_some());
+    }
+
+    #[test]
+    fn stale_charts_are_found() {
+        let alerter = alerter();
+        let charts = vec!["newTxns".to_string(), "newBlocks".to_string()];
+        let hour = Duration::from_secs(3600);
+        let fresh = ChartMetadata {
+            last_updated_at: Some(alerter.started_at + chrono::Duration::hours(2)),
+            last_attempt_at: None,
+            last_error: None,
+            last_duration_ms: None,
+            last_rows_written: None,
+            failed_updates: 0,
+        };
+        let metadata = HashMap::from([("newTxns".to_string(), fresh)]);
+
+        let now = alerter.started_at + chrono::Duration::minutes(30);
+        assert_eq!(alerter.find_stale(&charts, &metadata, hour, now), vec![]);
+
+        // `newBlocks` was never updated since start
+        let now = alerter.started_at + chrono::Duration::minutes(90);
+        let alerts = alerter.find_stale(&charts, &metadata, hour, now);
+        assert_eq!(
+            alerts,
+            vec![Alert {
+                chain_id: Some("100".into()),
+                chart: "newBlocks".into(),
+                kind: AlertKind::Stale {
+                    last_updated_at: None
+                },
+            }]
+        );
+        // already alerted
+        assert_eq!(alerter.find_stale(&charts, &metadata, hour, now), vec![]);
+
+        let now = alerter.started_at + chrono::Duration::hours(4);
+        let alerts = alerter.find_stale(&charts, &metadata, hour, now);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0].chart, "newTxns");
+    }
+
+    #[test]
+    fn payload_formats() {
+        let alert = Alert {
+            chain_id: None,
+            chart: "newTxns".into(),
+            kind: AlertKind::Stale {
+                last_updated_at: None,
+            },
+        };
+        assert_eq!(
+            AlertFormat::Json.payload(&alert),
+            serde_json::json!({
+                "chain_id": null,
+                "chart": "newTxns",
+                "kind": "stale",
+                "message": "chart newTxns was never updated",
+            })
+        );
+        assert_eq!(
+            AlertFormat::Slack.payload(&alert),
+            serde_json::json!({ "text": "chart newTxns was never updated" })
+        );
+    }
+}
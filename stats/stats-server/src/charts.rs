@@ -1,8 +1,16 @@
 use crate::config::{
     toml_config::{Config, LineChartSection},
-    ChartSettings,
+    ChainType, ChartSettings,
+};
+use stats::{
+    cache::{Cache, SharedCache},
+    counters,
+    entity::sea_orm_active_enums::ChartType,
+    lines,
+    market::{self, MarketDataSource},
+    rollup::{self, RollupType},
+    user_ops, AggregationPolicy, Chart, CounterSnapshots, CustomSqlChart, MissingDatePolicy,
 };
-use stats::{cache::Cache, counters, entity::sea_orm_active_enums::ChartType, lines, Chart};
 use std::{
     collections::{BTreeMap, HashMap, HashSet},
     hash::Hash,
@@ -14,11 +22,22 @@ pub type ArcChart = Arc<dyn Chart + Send + Sync + 'static>;
 pub struct ChartInfo {
     pub chart: ArcChart,
     pub settings: ChartSettings,
+    /// Id of the config section the line chart is grouped in
+    pub section: Option<String>,
+}
+
+/// Moving average series derived from line chart `parent`
+pub struct MovingAverageInfo {
+    pub parent: String,
+    pub window: u32,
 }
 
 pub struct Charts {
     pub config: Config,
     pub charts_info: BTreeMap<String, ChartInfo>,
+    pub moving_averages: BTreeMap<String, MovingAverageInfo>,
+    /// Names of charts in order of update, dependencies go before dependent charts
+    pub charts_order: Vec<String>,
     pub counters_filter: HashSet<String>,
     pub lines_filter: HashSet<String>,
 }
@@ -36,13 +55,81 @@ fn new_hashset_check_duplicates<T: Hash + Eq, I: IntoIterator<Item = T>>(
     Ok(result)
 }
 
+/// Orders charts so that every chart goes after its dependencies,
+/// otherwise the order of registration is kept
+fn sorted_by_dependencies(charts: Vec<ArcChart>) -> Result<Vec<ArcChart>, anyhow::Error> {
+    let names: HashSet<_> = charts
+        .iter()
+        .map(|chart| chart.name().to_string())
+        .collect();
+    let mut done = HashSet::new();
+    let mut pending = charts;
+    let mut sorted = Vec::with_capacity(pending.len());
+    while !pending.is_empty() {
+        let ready = pending.iter().position(|chart| {
+            chart
+                .dependencies()
+                .into_iter()
+                .all(|dependency| done.contains(dependency) || !names.contains(dependency))
+        });
+        let Some(ready) = ready else {
+            let cyclic: Vec<_> = pending.iter().map(|chart| chart.name()).collect();
+            return Err(anyhow::anyhow!(
+                "found cyclic dependencies between charts: {:?}",
+                cyclic
+            ));
+        };
+        let chart = pending.remove(ready);
+        done.insert(chart.name().to_string());
+        sorted.push(chart);
+    }
+    Ok(sorted)
+}
+
+/// Overridden missing date policy has to agree with aggregation of the chart:
+/// points of cumulative charts can't be filled with zeros and points of daily
+/// amounts can't be filled with previous values
+fn check_missing_date_policy(
+    name: &str,
+    aggregation: AggregationPolicy,
+    policy: MissingDatePolicy,
+) -> Result<(), anyhow::Error> {
+    let compatible = match policy {
+        MissingDatePolicy::LeaveGap => true,
+        MissingDatePolicy::FillZero => aggregation != AggregationPolicy::Last,
+        MissingDatePolicy::FillPrevious => aggregation == AggregationPolicy::Last,
+    };
+    if !compatible {
+        return Err(anyhow::anyhow!(
+            "missing date policy {:?} of chart {} contradicts its aggregation {:?}",
+            policy,
+            name,
+            aggregation
+        ));
+    }
+    Ok(())
+}
+
 impl Charts {
-    pub fn new(config: Config) -> Result<Self, anyhow::Error> {
-        Self::validated(config)
+    /// Charts of L2 data are enabled only if the chain is a `rollup`
+    /// Market charts are available only if `market_data` source is set
+    /// Account abstraction charts are enabled only if `user_ops` are indexed
+    pub fn new(
+        config: Config,
+        rollup: Option<RollupType>,
+        market_data: Option<Arc<dyn MarketDataSource>>,
+        user_ops: bool,
+    ) -> Result<Self, anyhow::Error> {
+        Self::validated(config, rollup, market_data, user_ops)
     }
 
-    fn validated(config: Config) -> Result<Self, anyhow::Error> {
-        let config = Self::remove_disabled_charts(config);
+    fn validated(
+        config: Config,
+        rollup: Option<RollupType>,
+        market_data: Option<Arc<dyn MarketDataSource>>,
+        user_ops: bool,
+    ) -> Result<Self, anyhow::Error> {
+        let config = Self::remove_disabled_charts(config, rollup);
         let counters_filter = config.counters.iter().map(|counter| counter.id.clone());
         let counters_filter = new_hashset_check_duplicates(counters_filter)
             .map_err(|id| anyhow::anyhow!("encountered same id twice: {}", id))?;
@@ -58,7 +145,10 @@ impl Charts {
         let mut counters_unknown = counters_filter.clone();
         let mut lines_unknown = lines_filter.clone();
         let settings = Self::new_settings(&config);
-        let charts_info = Self::all_charts()
+        let sections = Self::new_sections(&config);
+        let charts =
+            Self::with_custom_charts(Self::all_charts(rollup, market_data, user_ops), &config)?;
+        let charts_info: Vec<_> = sorted_by_dependencies(charts)?
             .into_iter()
             .filter(|chart| match chart.chart_type() {
                 ChartType::Counter => counters_unknown.remove(chart.name()),
@@ -70,11 +160,34 @@ impl Charts {
                     let info = ChartInfo {
                         chart,
                         settings: settings.to_owned(),
+                        section: sections.get(&name).cloned(),
                     };
                     (name.to_string(), info)
                 })
             })
             .collect();
+        for (name, info) in &charts_info {
+            if let Some(policy) = info.settings.missing_date_policy {
+                check_missing_date_policy(name, info.chart.aggregation_policy(), policy)?;
+            }
+        }
+        let charts_order = charts_info.iter().map(|(name, _)| name.clone()).collect();
+        let moving_averages = charts_info
+            .iter()
+            .flat_map(|(name, info)| {
+                info.chart
+                    .moving_average_windows()
+                    .iter()
+                    .map(move |&window| {
+                        let info = MovingAverageInfo {
+                            parent: name.clone(),
+                            window,
+                        };
+                        (stats::moving_average_name(name, window), info)
+                    })
+            })
+            .collect();
+        let charts_info = charts_info.into_iter().collect();
 
         if !counters_unknown.is_empty() || !lines_unknown.is_empty() {
             return Err(anyhow::anyhow!(
@@ -86,16 +199,21 @@ impl Charts {
         Ok(Self {
             config,
             charts_info,
+            moving_averages,
+            charts_order,
             counters_filter,
             lines_filter,
         })
     }
 
-    fn remove_disabled_charts(config: Config) -> Config {
+    /// Charts restricted to other chain types are removed as well,
+    /// so the same config can be used for all chains
+    fn remove_disabled_charts(config: Config, rollup: Option<RollupType>) -> Config {
+        let chain_type = ChainType::from(rollup);
         let counters = config
             .counters
             .into_iter()
-            .filter(|info| info.settings.enabled)
+            .filter(|info| info.settings.is_enabled_for(chain_type))
             .collect();
         let lines = config
             .lines
@@ -107,7 +225,7 @@ impl Charts {
                 charts: sec
                     .charts
                     .into_iter()
-                    .filter(|info| info.settings.enabled)
+                    .filter(|info| info.settings.is_enabled_for(chain_type))
                     .collect(),
             })
             .filter(|sec| !sec.charts.is_empty())
@@ -131,9 +249,85 @@ impl Charts {
             .collect()
     }
 
-    fn all_charts() -> Vec<ArcChart> {
+    fn new_sections(config: &Config) -> HashMap<String, String> {
+        config
+            .lines
+            .sections
+            .iter()
+            .flat_map(|section| {
+                section
+                    .charts
+                    .iter()
+                    .map(|chart| (chart.id.clone(), section.id.clone()))
+            })
+            .collect()
+    }
+
+    /// Appends charts defined by sql queries and snapshots of counters in config
+    fn with_custom_charts(
+        mut charts: Vec<ArcChart>,
+        config: &Config,
+    ) -> Result<Vec<ArcChart>, anyhow::Error> {
+        let counters = config
+            .counters
+            .iter()
+            .map(|counter| (&counter.id, &counter.settings, ChartType::Counter));
+        let lines = config
+            .lines
+            .sections
+            .iter()
+            .flat_map(|section| section.charts.iter())
+            .map(|line| (&line.id, &line.settings, ChartType::Line));
+        for (id, settings, chart_type) in counters.chain(lines) {
+            let custom: ArcChart = match (&settings.sql, &settings.snapshots_of, &chart_type) {
+                (None, None, _) => continue,
+                (Some(sql), None, _) => Arc::new(CustomSqlChart::new(
+                    id.clone(),
+                    chart_type.clone(),
+                    sql.clone(),
+                )),
+                (None, Some(counter), ChartType::Line) => {
+                    if !config.counters.iter().any(|c| &c.id == counter) {
+                        return Err(anyhow::anyhow!(
+                            "chart {} takes snapshots of unknown counter {}",
+                            id,
+                            counter
+                        ));
+                    }
+                    Arc::new(CounterSnapshots::new(id.clone(), counter.clone()))
+                }
+                (None, Some(_), ChartType::Counter) => {
+                    return Err(anyhow::anyhow!(
+                        "counter {} can't be defined by snapshots",
+                        id
+                    ))
+                }
+                (Some(_), Some(_), _) => {
+                    return Err(anyhow::anyhow!(
+                        "chart {} can't have both sql and snapshots_of",
+                        id
+                    ))
+                }
+            };
+            if charts.iter().any(|chart| chart.name() == id) {
+                return Err(anyhow::anyhow!(
+                    "custom chart {} conflicts with built-in chart",
+                    id
+                ));
+            }
+            charts.push(custom);
+        }
+        Ok(charts)
+    }
+
+    fn all_charts(
+        rollup: Option<RollupType>,
+        market_data: Option<Arc<dyn MarketDataSource>>,
+        user_ops: bool,
+    ) -> Vec<ArcChart> {
         let accounts_cache = Cache::default();
-        let new_txns = Arc::new(lines::NewTxns::default());
+        let txns_stats_cache = SharedCache::default();
+        let new_txns = Arc::new(lines::NewTxns::new(txns_stats_cache.clone()));
         let new_native_coin_transfers = Arc::new(lines::NewNativeCoinTransfers::default());
         let native_coin_holders_growth = Arc::new(lines::NativeCoinHoldersGrowth::default());
 
@@ -144,30 +338,38 @@ impl Charts {
         let new_contracts = Arc::new(lines::NewContracts::default());
         let contracts_growth = Arc::new(lines::ContractsGrowth::new(new_contracts.clone()));
 
-        vec![
+        let mut charts: Vec<ArcChart> = vec![
             // tier 1
             Arc::new(counters::TotalAddresses::default()),
             Arc::new(lines::AverageBlockRewards::default()),
+            Arc::new(lines::BlockRewards::default()),
             Arc::new(counters::TotalTokens::default()),
+            Arc::new(lines::NewTokens::default()),
             Arc::new(lines::NativeCoinSupply::default()),
             native_coin_holders_growth.clone(),
             new_txns.clone(),
+            Arc::new(lines::NewTxnsByType::default()),
             Arc::new(lines::NewAccounts::new(accounts_cache.clone())),
             new_verified_contracts.clone(),
             new_contracts.clone(),
             new_native_coin_transfers.clone(),
+            Arc::new(lines::NativeCoinTransfersVolume::default()),
             Arc::new(lines::NewBlocks::default()),
+            Arc::new(lines::GasUsed::default()),
             Arc::new(lines::GasUsedGrowth::default()),
             Arc::new(lines::AverageBlockSize::default()),
+            Arc::new(lines::AverageBlockTime::default()),
             Arc::new(counters::TotalBlocks::default()),
-            Arc::new(lines::TxnsFee::default()),
+            Arc::new(lines::TxnsFee::new(txns_stats_cache.clone())),
             Arc::new(lines::AverageGasLimit::default()),
             Arc::new(counters::AverageBlockTime::default()),
             Arc::new(lines::ActiveAccounts::default()),
             Arc::new(lines::AverageGasPrice::default()),
-            Arc::new(lines::AverageTxnFee::default()),
+            Arc::new(lines::GasPricePercentiles::default()),
+            Arc::new(lines::AverageTxnFee::new(txns_stats_cache)),
             Arc::new(lines::TxnsSuccessRate::default()),
             Arc::new(counters::CompletedTxns::default()),
+            Arc::new(counters::TxnsFee24h::default()),
             Arc::new(lines::AccountsGrowth::new(accounts_cache.clone())),
             Arc::new(counters::TotalAccounts::new(accounts_cache)),
             // tier 2
@@ -191,6 +393,330 @@ impl Charts {
             Arc::new(counters::TotalVerifiedContracts::new(
                 verified_contracts_growth,
             )),
-        ]
+        ];
+        if let Some(rollup) = rollup {
+            charts.extend([
+                Arc::new(rollup::NewBatches::new(rollup)) as ArcChart,
+                Arc::new(rollup::NewWithdrawals::new(rollup)),
+                Arc::new(rollup::L1DataFees::new(rollup)),
+            ]);
+        }
+        if let Some(source) = market_data {
+            let market_cache = SharedCache::default();
+            charts.extend([
+                Arc::new(market::NativeCoinPrice::new(
+                    source.clone(),
+                    market_cache.clone(),
+                )) as ArcChart,
+                Arc::new(market::NativeCoinMarketCap::new(source, market_cache)),
+            ]);
+        }
+        if user_ops {
+            charts.extend([
+                Arc::new(user_ops::NewUserOps::default()) as ArcChart,
+                Arc::new(user_ops::ActiveSmartAccounts::default()),
+            ]);
+        }
+        charts
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use chrono::NaiveDate;
+    use sea_orm::DatabaseConnection;
+    use stats::{market::MarketPoint, UpdateError, UpdateParameters};
+
+    struct MockSource;
+
+    #[async_trait]
+    impl MarketDataSource for MockSource {
+        async fn daily_points(
+            &self,
+            _since: Option<NaiveDate>,
+        ) -> Result<Vec<MarketPoint>, UpdateError> {
+            Ok(vec![])
+        }
+    }
+
+    struct MockChart {
+        name: &'static str,
+        dependencies: Vec<&'static str>,
+    }
+
+    #[async_trait]
+    impl Chart for MockChart {
+        fn name(&self) -> &str {
+            self.name
+        }
+        fn chart_type(&self) -> ChartType {
+            ChartType::Line
+        }
+        fn dependencies(&self) -> Vec<&str> {
+            self.dependencies.clone()
+        }
+
+        async fn update(
+            &self,
+            _db: &DatabaseConnection,
+            _blockscout: &DatabaseConnection,
+            _params: UpdateParameters,
+        ) -> Result<(), UpdateError> {
+            Ok(())
+        }
+    }
+
+    fn mock(name: &'static str, dependencies: Vec<&'static str>) -> ArcChart {
+        Arc::new(MockChart { name, dependencies })
+    }
+
+    fn names(charts: Vec<ArcChart>) -> Vec<String> {
+        charts
+            .iter()
+            .map(|chart| chart.name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn sorted_by_dependencies_works() {
+        let charts = vec![
+            mock("total", vec!["growth"]),
+            mock("growth", vec!["new"]),
+            mock("other", vec![]),
+            mock("new", vec!["disabled"]),
+            mock("last", vec!["new"]),
+        ];
+        let sorted = sorted_by_dependencies(charts).unwrap();
+        assert_eq!(names(sorted), ["other", "new", "growth", "total", "last"]);
+
+        let all = Charts::all_charts(Some(RollupType::Optimism), Some(Arc::new(MockSource)), true);
+        let registered = names(all.clone());
+        let sorted = names(sorted_by_dependencies(all).unwrap());
+        assert_eq!(registered.len(), sorted.len());
+        for chart in
+            Charts::all_charts(Some(RollupType::Optimism), Some(Arc::new(MockSource)), true)
+        {
+            let position = sorted.iter().position(|name| name == chart.name());
+            for dependency in chart.dependencies() {
+                let dependency_position = sorted.iter().position(|name| name == dependency);
+                assert!(
+                    dependency_position < position,
+                    "{dependency} is not before {}",
+                    chart.name()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn cyclic_dependencies_fail() {
+        let charts = vec![
+            mock("other", vec![]),
+            mock("first", vec!["second"]),
+            mock("second", vec!["first"]),
+        ];
+        assert!(sorted_by_dependencies(charts).is_err());
+    }
+
+    #[test]
+    fn custom_charts_are_registered() {
+        let config: Config = toml::from_str(
+            r#"
+            [[counters]]
+            id = "totalBlocks"
+            title = "Total blocks"
+            description = "Number of blocks"
+
+            [[counters]]
+            id = "totalWithdrawals"
+            title = "Total withdrawals"
+            description = "Number of withdrawals"
+            sql = "SELECT CURRENT_DATE as date, COUNT(*)::NUMERIC as value FROM withdrawals"
+
+            [lines]
+            sections = []
+            "#,
+        )
+        .unwrap();
+        let charts = Charts::new(config.clone(), None, None, false).unwrap();
+        assert_eq!(charts.charts_order, ["totalBlocks", "totalWithdrawals"]);
+        let chart = &charts.charts_info["totalWithdrawals"].chart;
+        assert_eq!(chart.chart_type(), ChartType::Counter);
+
+        let mut config = config;
+        config.counters[0].settings.sql = Some("SELECT 1".into());
+        assert!(Charts::new(config, None, None, false).is_err());
+    }
+
+    #[test]
+    fn counter_snapshots_are_registered() {
+        let config: Config = toml::from_str(
+            r#"
+            [[counters]]
+            id = "totalBlocks"
+            title = "Total blocks"
+            description = "Number of blocks"
+
+            [[lines.sections]]
+            id = "blocks"
+            title = "Blocks"
+
+            [[lines.sections.charts]]
+            id = "totalBlocksHistory"
+            title = "Total blocks"
+            description = "Number of blocks at the end of day"
+            snapshots_of = "totalBlocks"
+            "#,
+        )
+        .unwrap();
+        let charts = Charts::new(config.clone(), None, None, false).unwrap();
+        assert_eq!(charts.charts_order, ["totalBlocks", "totalBlocksHistory"]);
+        let chart = &charts.charts_info["totalBlocksHistory"].chart;
+        assert_eq!(chart.chart_type(), ChartType::Line);
+        assert_eq!(chart.dependencies(), ["totalBlocks"]);
+
+        let mut unknown = config.clone();
+        unknown.lines.sections[0].charts[0].settings.snapshots_of = Some("totalTxns".into());
+        assert!(Charts::new(unknown, None, None, false).is_err());
+
+        let mut with_sql = config;
+        with_sql.lines.sections[0].charts[0].settings.sql = Some("SELECT 1".into());
+        assert!(Charts::new(with_sql, None, None, false).is_err());
+    }
+
+    #[test]
+    fn charts_are_restricted_to_chain_types() {
+        let config: Config = toml::from_str(
+            r#"
+            counters = []
+
+            [[lines.sections]]
+            id = "blocks"
+            title = "Blocks"
+
+            [[lines.sections.charts]]
+            id = "newBlocks"
+            title = "New blocks"
+            description = "New blocks number"
+            chain_types = ["l1"]
+
+            [[lines.sections]]
+            id = "rollup"
+            title = "Rollup"
+
+            [[lines.sections.charts]]
+            id = "newBatches"
+            title = "New batches"
+            description = "Number of batches submitted to L1"
+            chain_types = ["optimism", "arbitrum"]
+            "#,
+        )
+        .unwrap();
+        let charts = Charts::new(config.clone(), None, None, false).unwrap();
+        assert_eq!(charts.charts_order, ["newBlocks"]);
+        assert_eq!(
+            charts.charts_info["newBlocks"].section.as_deref(),
+            Some("blocks")
+        );
+        assert!(!charts.lines_filter.contains("newBatches"));
+        assert_eq!(charts.config.lines.sections.len(), 1);
+
+        let charts = Charts::new(config.clone(), Some(RollupType::Arbitrum), None, false).unwrap();
+        assert_eq!(charts.charts_order, ["newBatches"]);
+
+        // rollup charts are unknown to other chains
+        let mut unrestricted = config;
+        unrestricted.lines.sections[1].charts[0]
+            .settings
+            .chain_types
+            .clear();
+        assert!(Charts::new(unrestricted, None, None, false).is_err());
+    }
+
+    #[test]
+    fn market_charts_require_source() {
+        let config: Config = toml::from_str(
+            r#"
+            counters = []
+
+            [[lines.sections]]
+            id = "market"
+            title = "Market"
+
+            [[lines.sections.charts]]
+            id = "nativeCoinPrice"
+            title = "Price"
+            description = "Daily price of the native coin"
+            "#,
+        )
+        .unwrap();
+        assert!(Charts::new(config.clone(), None, None, false).is_err());
+        let charts = Charts::new(config, None, Some(Arc::new(MockSource)), false).unwrap();
+        assert_eq!(charts.charts_order, ["nativeCoinPrice"]);
+    }
+
+    #[test]
+    fn user_ops_charts_require_indexed_user_ops() {
+        let config: Config = toml::from_str(
+            r#"
+            counters = []
+
+            [[lines.sections]]
+            id = "account_abstraction"
+            title = "Account abstraction"
+
+            [[lines.sections.charts]]
+            id = "newUserOps"
+            title = "User operations"
+            description = "Number of user operations per day"
+            "#,
+        )
+        .unwrap();
+        assert!(Charts::new(config.clone(), None, None, false).is_err());
+        let charts = Charts::new(config, None, None, true).unwrap();
+        assert_eq!(charts.charts_order, ["newUserOps"]);
+    }
+
+    #[test]
+    fn missing_date_policy_must_agree_with_aggregation() {
+        let config: Config = toml::from_str(
+            r#"
+            counters = []
+
+            [[lines.sections]]
+            id = "blocks"
+            title = "Blocks"
+
+            [[lines.sections.charts]]
+            id = "newBlocks"
+            title = "New blocks"
+            description = "New blocks number"
+            missing_date_policy = "leave_gap"
+            "#,
+        )
+        .unwrap();
+        assert!(Charts::new(config.clone(), None, None, false).is_ok());
+
+        let mut fill_previous = config;
+        fill_previous.lines.sections[0].charts[0]
+            .settings
+            .missing_date_policy = Some(MissingDatePolicy::FillPrevious);
+        assert!(Charts::new(fill_previous, None, None, false).is_err());
+    }
+
+    #[test]
+    fn default_config_sets_units_of_wei_charts() {
+        let path = std::path::Path::new("../config/charts.json");
+        let config = crate::config::read_charts_config(path).unwrap();
+        for rollup in [None, Some(RollupType::Optimism)] {
+            let charts = Charts::new(config.clone(), rollup, None, false).unwrap();
+            for (name, info) in &charts.charts_info {
+                if info.chart.unit().is_some() {
+                    assert!(info.settings.units.is_some(), "units of {name} are not set");
+                }
+            }
+        }
     }
 }
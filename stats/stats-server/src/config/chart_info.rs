@@ -1,6 +1,7 @@
 use cron::Schedule;
 use serde::{Deserialize, Serialize};
 use serde_with::{serde_as, DisplayFromStr};
+use stats::{rollup::RollupType, MissingDatePolicy};
 
 #[serde_as]
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
@@ -11,12 +12,46 @@ pub struct ChartSettings {
     #[serde_as(as = "Option<DisplayFromStr>")]
     pub update_schedule: Option<Schedule>,
     pub units: Option<String>,
+    /// Overrides default policy of the line chart
+    pub missing_date_policy: Option<MissingDatePolicy>,
+    /// Query against blockscout database defining a custom chart,
+    /// see [`stats::CustomSqlChart`]. Must be unset for built-in charts
+    pub sql: Option<String>,
+    /// Id of the counter whose daily snapshots form this line chart,
+    /// see [`stats::CounterSnapshots`]. Must be unset for built-in charts
+    pub snapshots_of: Option<String>,
+    /// Types of chains the chart is enabled for, all chains if empty
+    pub chain_types: Vec<ChainType>,
 }
 
 fn enabled_default() -> bool {
     true
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainType {
+    L1,
+    Optimism,
+    Arbitrum,
+}
+
+impl From<Option<RollupType>> for ChainType {
+    fn from(rollup: Option<RollupType>) -> Self {
+        match rollup {
+            None => ChainType::L1,
+            Some(RollupType::Optimism) => ChainType::Optimism,
+            Some(RollupType::Arbitrum) => ChainType::Arbitrum,
+        }
+    }
+}
+
+impl ChartSettings {
+    pub fn is_enabled_for(&self, chain_type: ChainType) -> bool {
+        self.enabled && (self.chain_types.is_empty() || self.chain_types.contains(&chain_type))
+    }
+}
+
 #[derive(Default, Debug, Clone, Serialize, Deserialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct CounterInfo {
@@ -1,5 +1,5 @@
 use super::{
-    chart_info::{CounterInfo, LineChartInfo},
+    chart_info::{ChartSettings, CounterInfo, LineChartInfo},
     toml_config,
 };
 use convert_case::{Case, Casing};
@@ -58,11 +58,19 @@ impl From<(String, LineChartSection)> for toml_config::LineChartSection {
 
 impl From<(String, LineChartInfo)> for toml_config::LineChartInfo {
     fn from((id, info): (String, LineChartInfo)) -> Self {
+        // counter is referenced by its id in json config
+        let snapshots_of = info
+            .settings
+            .snapshots_of
+            .map(|counter| counter.from_case(Case::Snake).to_case(Case::Camel));
         Self {
             id: id.from_case(Case::Snake).to_case(Case::Camel),
             title: info.title,
             description: info.description,
-            settings: info.settings,
+            settings: ChartSettings {
+                snapshots_of,
+                ..info.settings
+            },
         }
     }
 }
@@ -4,5 +4,5 @@ mod read;
 pub mod json_config;
 pub mod toml_config;
 
-pub use chart_info::ChartSettings;
+pub use chart_info::{ChainType, ChartSettings};
 pub use read::read_charts_config;
@@ -27,6 +27,8 @@ impl From<LineChartInfo> for proto::LineChartInfo {
             title: value.title,
             description: value.description,
             units: value.settings.units,
+            last_updated_at: None,
+            section: None,
         }
     }
 }
@@ -47,9 +49,16 @@ impl From<Vec<LineChartSection>> for LineCharts {
 impl From<LineChartSection> for proto::LineChartSection {
     fn from(value: LineChartSection) -> Self {
         Self {
-            id: value.id,
             title: value.title,
-            charts: value.charts.into_iter().map(|x| x.into()).collect(),
+            charts: value
+                .charts
+                .into_iter()
+                .map(|x| proto::LineChartInfo {
+                    section: Some(value.id.clone()),
+                    ..x.into()
+                })
+                .collect(),
+            id: value.id,
         }
     }
 }
@@ -0,0 +1,153 @@
+use crate::{charts::Charts, multichain::Multichain};
+use actix_web::{web, HttpResponse};
+use bytes::Bytes;
+use chrono::NaiveDate;
+use futures::{stream, StreamExt};
+use sea_orm::DatabaseConnection;
+use serde::Deserialize;
+use stats::{DateValue, ReadError};
+use std::{str::FromStr, sync::Arc};
+
+/// Amount of points read from database for a single chunk of the response
+const EXPORT_CHUNK_SIZE: u64 = 1000;
+
+const CSV_HEADER: &str = "date,value\n";
+
+/// Exports stored line charts as csv, which doesn't fit into grpc api
+pub struct ExportService {
+    db: Arc<DatabaseConnection>,
+    charts: Arc<Charts>,
+}
+
+impl ExportService {
+    pub fn new(db: Arc<DatabaseConnection>, charts: Arc<Charts>) -> Self {
+        Self { db, charts }
+    }
+
+    fn is_line_chart(&self, name: &str) -> bool {
+        self.charts.lines_filter.contains(name) || self.charts.moving_averages.contains_key(name)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ExportQuery {
+    from: Option<String>,
+    to: Option<String>,
+    #[serde(rename = "chainId")]
+    chain_id: Option<String>,
+}
+
+pub fn route_export(config: &mut web::ServiceConfig, export: Arc<Multichain<ExportService>>) {
+    config
+        .app_data(web::Data::from(export))
+        .route("/api/v1/lines/{name}/csv", web::get().to(export_line_chart));
+}
+
+fn csv_rows(points: &[DateValue]) -> Bytes {
+    points
+        .iter()
+        .map(|point| format!("{},{}\n", point.date, point.value))
+        .collect::<String>()
+        .into()
+}
+
+/// Date to read the chunk after `chunk` from, `None` if `chunk` is the last one
+fn next_chunk_start(chunk: &[DateValue]) -> Option<NaiveDate> {
+    if (chunk.len() as u64) < EXPORT_CHUNK_SIZE {
+        return None;
+    }
+    chunk.last().and_then(|point| point.date.succ_opt())
+}
+
+async fn export_line_chart(
+    export: web::Data<Multichain<ExportService>>,
+    name: web::Path<String>,
+    query: web::Query<ExportQuery>,
+) -> HttpResponse {
+    let export = match export.chain(query.chain_id.as_deref()) {
+        Ok(export) => export,
+        Err(status) => return HttpResponse::NotFound().body(status.message().to_string()),
+    };
+    let name = name.into_inner();
+    if !export.is_line_chart(&name) {
+        return HttpResponse::NotFound().body(format!("chart {name} not found"));
+    }
+    let from = query
+        .from
+        .as_ref()
+        .and_then(|date| NaiveDate::from_str(date).ok());
+    let to = query
+        .to
+        .as_ref()
+        .and_then(|date| NaiveDate::from_str(date).ok());
+
+    // first chunk is read before responding, so missing chart is reported with proper status
+    let first_chunk =
+        match stats::get_chart_data_chunk(&export.db, &name, from, to, EXPORT_CHUNK_SIZE).await {
+            Ok(chunk) => chunk,
+            Err(ReadError::NotFound(err)) => {
+                return HttpResponse::NotFound().body(format!("chart {err} not found"))
+            }
+            Err(err) => {
+                tracing::error!(err = ?err, chart = name.as_str(), "failed to export chart");
+                return HttpResponse::InternalServerError().body(err.to_string());
+            }
+        };
+    let head = stream::iter([
+        Ok::<_, ReadError>(Bytes::from_static(CSV_HEADER.as_bytes())),
+        Ok(csv_rows(&first_chunk)),
+    ]);
+    let db = export.db.clone();
+    let rest = stream::try_unfold(next_chunk_start(&first_chunk), move |from| {
+        let db = db.clone();
+        let name = name.clone();
+        async move {
+            let Some(from) = from else {
+                return Ok(None);
+            };
+            let chunk = stats::get_chart_data_chunk(&db, &name, Some(from), to, EXPORT_CHUNK_SIZE)
+                .await
+                .map_err(|err| {
+                    tracing::error!(err = ?err, chart = name.as_str(), "failed to export chart");
+                    err
+                })?;
+            Ok::<_, ReadError>(Some((csv_rows(&chunk), next_chunk_start(&chunk))))
+        }
+    });
+    HttpResponse::Ok()
+        .content_type("text/csv")
+        .streaming(head.chain(rest))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn point(date: &str, value: &str) -> DateValue {
+        DateValue {
+            date: NaiveDate::from_str(date).unwrap(),
+            value: value.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn points_are_formatted_as_csv() {
+        let rows = csv_rows(&[point("2022-11-10", "100"), point("2022-11-11", "1.5")]);
+        assert_eq!(rows, Bytes::from("2022-11-10,100\n2022-11-11,1.5\n"));
+        assert_eq!(csv_rows(&[]), Bytes::new());
+    }
+
+    #[test]
+    fn chunks_continue_after_last_date() {
+        let full: Vec<_> = (0..EXPORT_CHUNK_SIZE)
+            .map(|_| point("2022-11-10", "1"))
+            .collect();
+        assert_eq!(
+            next_chunk_start(&full),
+            Some(NaiveDate::from_str("2022-11-11").unwrap())
+        );
+        assert_eq!(next_chunk_start(&full[1..]), None);
+        assert_eq!(next_chunk_start(&[]), None);
+    }
+}
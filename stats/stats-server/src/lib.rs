@@ -1,6 +1,12 @@
+mod admin_service;
+mod alerts;
 mod charts;
 mod config;
+mod export;
 mod health;
+mod market_data;
+mod multichain;
+mod read_cache;
 mod read_service;
 mod serializers;
 mod server;
@@ -0,0 +1,137 @@
+use crate::settings::MarketDataSettings;
+use async_trait::async_trait;
+use chrono::{NaiveDate, NaiveDateTime, Utc};
+use serde::Deserialize;
+use stats::{
+    market::{MarketDataSource, MarketPoint},
+    UpdateError,
+};
+use std::collections::BTreeMap;
+
+/// Response of `/coins/{id}/market_chart`, every point is `[unix time in ms, value]`
+#[derive(Debug, Deserialize)]
+struct MarketChartResponse {
+    prices: Vec<(f64, f64)>,
+    market_caps: Vec<(f64, f64)>,
+}
+
+impl MarketChartResponse {
+    /// The last value of the day is used, so the point of today is the current one
+    fn daily_points(self) -> Vec<MarketPoint> {
+        let mut points = BTreeMap::new();
+        for (timestamp, price) in self.prices {
+            if let Some(point) = point_of_day(&mut points, timestamp) {
+                point.price = Some(price);
+            }
+        }
+        for (timestamp, market_cap) in self.market_caps {
+            if let Some(point) = point_of_day(&mut points, timestamp) {
+                point.market_cap = Some(market_cap);
+            }
+        }
+        points.into_values().collect()
+    }
+}
+
+fn point_of_day(
+    points: &mut BTreeMap<NaiveDate, MarketPoint>,
+    timestamp: f64,
+) -> Option<&mut MarketPoint> {
+    let date = NaiveDateTime::from_timestamp_millis(timestamp as i64)?.date();
+    Some(points.entry(date).or_insert(MarketPoint {
+        date,
+        price: None,
+        market_cap: None,
+    }))
+}
+
+/// Market data of the native coin from CoinGecko or api compatible with it
+pub struct CoinGeckoSource {
+    client: reqwest::Client,
+    settings: MarketDataSettings,
+}
+
+impl CoinGeckoSource {
+    pub fn new(settings: MarketDataSettings) -> Self {
+        Self {
+            client: reqwest::Client::builder()
+                .timeout(settings.timeout)
+                .build()
+                .expect("failed to build http client"),
+            settings,
+        }
+    }
+
+    async fn fetch(&self, since: Option<NaiveDate>) -> Result<MarketChartResponse, reqwest::Error> {
+        let days = match since {
+            Some(since) => ((Utc::now().date_naive() - since).num_days() + 1).to_string(),
+            None => "max".to_string(),
+        };
+        let url = format!(
+            "{}/coins/{}/market_chart",
+            self.settings.url.trim_end_matches('/'),
+            self.settings.coin_id
+        );
+        let mut request = self.client.get(url).query(&[
+            ("vs_currency", self.settings.vs_currency.as_str()),
+            ("days", days.as_str()),
+            ("interval", "daily"),
+        ]);
+        if let Some(api_key) = &self.settings.api_key {
+            request = request.header(&self.settings.api_key_header, api_key);
+        }
+        request.send().await?.error_for_status()?.json().await
+    }
+}
+
+#[async_trait]
+impl MarketDataSource for CoinGeckoSource {
+    async fn daily_points(
+        &self,
+        since: Option<NaiveDate>,
+    ) -> Result<Vec<MarketPoint>, UpdateError> {
+        let response = self
+            .fetch(since)
+            .await
+            .map_err(|err| UpdateError::External(err.to_string()))?;
+        Ok(response.daily_points())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    #[test]
+    fn daily_points_are_parsed() {
+        let response: MarketChartResponse = serde_json::from_str(
+            r#"{
+                "prices": [
+                    [1672531200000, 1196.77],
+                    [1672617600000, 1214.56],
+                    [1672650000000, 1220.5]
+                ],
+                "market_caps": [
+                    [1672531200000, 144120000000.5],
+                    [1672650000000, 147000000000]
+                ],
+                "total_volumes": []
+            }"#,
+        )
+        .unwrap();
+        let point = |date: &str, price, market_cap| MarketPoint {
+            date: NaiveDate::from_str(date).unwrap(),
+            price,
+            market_cap,
+        };
+        assert_eq!(
+            response.daily_points(),
+            vec![
+                point("2023-01-01", Some(1196.77), Some(144120000000.5)),
+                point("2023-01-02", Some(1220.5), Some(147000000000.0)),
+            ]
+        );
+    }
+}
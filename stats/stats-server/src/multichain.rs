@@ -0,0 +1,132 @@
+use crate::{admin_service::AdminService, read_service::ReadService};
+use async_trait::async_trait;
+use stats_proto::blockscout::stats::v1::{
+    self as proto, stats_admin_service_server::StatsAdminService,
+    stats_service_server::StatsService,
+};
+use std::{collections::BTreeMap, sync::Arc};
+use tonic::{Request, Response, Status};
+
+/// Services of separate chains, requests are dispatched by their `chain_id`
+pub struct Multichain<S> {
+    main: Arc<S>,
+    chains: BTreeMap<String, Arc<S>>,
+}
+
+impl<S> Multichain<S> {
+    /// `chains` contain all chains addressable by id, including the main one if it has id
+    pub fn new(main: Arc<S>, chains: BTreeMap<String, Arc<S>>) -> Self {
+        Self { main, chains }
+    }
+
+    /// Service of the main chain is returned if `chain_id` is not set
+    pub fn chain(&self, chain_id: Option<&str>) -> Result<&S, Status> {
+        match chain_id {
+            None => Ok(&self.main),
+            Some(chain_id) => self
+                .chains
+                .get(chain_id)
+                .map(|service| service.as_ref())
+                .ok_or_else(|| Status::not_found(format!("chain {chain_id} not found"))),
+        }
+    }
+}
+
+#[async_trait]
+impl StatsService for Multichain<ReadService> {
+    async fn get_counters(
+        &self,
+        request: Request<proto::GetCountersRequest>,
+    ) -> Result<Response<proto::Counters>, Status> {
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        chain.get_counters(request).await
+    }
+
+    async fn get_line_charts(
+        &self,
+        request: Request<proto::GetLineChartsRequest>,
+    ) -> Result<Response<proto::LineCharts>, Status> {
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        chain.get_line_charts(request).await
+    }
+
+    async fn get_line_chart(
+        &self,
+        request: Request<proto::GetLineChartRequest>,
+    ) -> Result<Response<proto::LineChart>, Status> {
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        chain.get_line_chart(request).await
+    }
+
+    async fn batch_get_charts(
+        &self,
+        request: Request<proto::BatchGetChartsRequest>,
+    ) -> Result<Response<proto::BatchCharts>, Status> {
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        chain.batch_get_charts(request).await
+    }
+
+    async fn get_charts_status(
+        &self,
+        request: Request<proto::GetChartsStatusRequest>,
+    ) -> Result<Response<proto::ChartsStatus>, Status> {
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        chain.get_charts_status(request).await
+    }
+
+    async fn get_token_stats(
+        &self,
+        request: Request<proto::GetTokenStatsRequest>,
+    ) -> Result<Response<proto::TokenStats>, Status> {
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        chain.get_token_stats(request).await
+    }
+}
+
+#[async_trait]
+impl StatsAdminService for Multichain<AdminService> {
+    async fn force_full_update(
+        &self,
+        request: Request<proto::ForceFullUpdateRequest>,
+    ) -> Result<Response<proto::UpdateJob>, Status> {
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        chain.force_full_update(request).await
+    }
+
+    async fn get_update_job(
+        &self,
+        request: Request<proto::GetUpdateJobRequest>,
+    ) -> Result<Response<proto::UpdateJob>, Status> {
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        chain.get_update_job(request).await
+    }
+
+    async fn repair_chart(
+        &self,
+        request: Request<proto::RepairChartRequest>,
+    ) -> Result<Response<proto::UpdateJob>, Status> {
+        let chain = self.chain(request.get_ref().chain_id.as_deref())?;
+        chain.repair_chart(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::Code;
+
+    #[test]
+    fn chains_are_dispatched_by_id() {
+        let main = Arc::new("main");
+        let other = Arc::new("other");
+        let chains = BTreeMap::from([("1".to_string(), main.clone()), ("100".to_string(), other)]);
+        let multichain = Multichain::new(main, chains);
+        assert_eq!(*multichain.chain(None).unwrap(), "main");
+        assert_eq!(*multichain.chain(Some("1")).unwrap(), "main");
+        assert_eq!(*multichain.chain(Some("100")).unwrap(), "other");
+        assert_eq!(
+            multichain.chain(Some("5")).unwrap_err().code(),
+            Code::NotFound
+        );
+    }
+}
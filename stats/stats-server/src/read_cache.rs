@@ -0,0 +1,236 @@
+use chrono::NaiveDate;
+use stats::{DateValue, ResolutionKind};
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ReadCacheKey {
+    pub chart: String,
+    pub resolution: ResolutionKind,
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+#[derive(Debug)]
+struct ReadCacheEntry {
+    data: Vec<DateValue>,
+    fetched_at: Instant,
+}
+
+#[derive(Debug, Default)]
+struct ReadCacheData {
+    entries: HashMap<ReadCacheKey, ReadCacheEntry>,
+    /// Incremented on every invalidation of the chart, so data read before
+    /// the invalidation is not put into the cache after it
+    generations: HashMap<String, u64>,
+}
+
+impl ReadCacheData {
+    fn generation(&self, chart: &str) -> u64 {
+        self.generations.get(chart).copied().unwrap_or_default()
+    }
+}
+
+/// Points of line charts read from stats database.
+///
+/// Stored data changes only when the chart is updated, so entries are kept
+/// until the updater invalidates the chart or `max_age` passes
+#[derive(Debug)]
+pub struct ReadCache {
+    max_entries: usize,
+    max_age: Duration,
+    data: Mutex<ReadCacheData>,
+}
+
+impl ReadCache {
+    pub fn new(max_entries: usize, max_age: Duration) -> Self {
+        Self {
+            max_entries,
+            max_age,
+            data: Default::default(),
+        }
+    }
+
+    pub async fn get_or_fetch<E, F: Future<Output = Result<Vec<DateValue>, E>>>(
+        &self,
+        key: ReadCacheKey,
+        fetch: F,
+    ) -> Result<Vec<DateValue>, E> {
+        let generation = {
+            let data = self.data.lock().expect("read cache mutex is poisoned");
+            if let Some(entry) = data.entries.get(&key) {
+                if entry.fetched_at.elapsed() <= self.max_age {
+                    return Ok(entry.data.clone());
+                }
+            }
+            data.generation(&key.chart)
+        };
+        let fetched = fetch.await?;
+
+        let mut data = self.data.lock().expect("read cache mutex is poisoned");
+        if data.generation(&key.chart) != generation {
+            return Ok(fetched);
+        }
+        if data.entries.len() >= self.max_entries && !data.entries.contains_key(&key) {
+            let oldest = data
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.fetched_at)
+                .map(|(key, _)| key.clone());
+            match oldest {
+                Some(oldest) => {
+                    data.entries.remove(&oldest);
+                }
+                // zero capacity
+                None => return Ok(fetched),
+            }
+        }
+        data.entries.insert(
+            key,
+            ReadCacheEntry {
+                data: fetched.clone(),
+                fetched_at: Instant::now(),
+            },
+        );
+        Ok(fetched)
+    }
+
+    /// Drops all cached data of `charts`
+    pub fn invalidate<'a>(&self, charts: impl IntoIterator<Item = &'a str>) {
+        let mut data = self.data.lock().expect("read cache mutex is poisoned");
+        for chart in charts {
+            data.entries.retain(|key, _| key.chart != chart);
+            *data.generations.entry(chart.to_string()).or_default() += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::str::FromStr;
+
+    fn key(chart: &str, from: Option<&str>) -> ReadCacheKey {
+        ReadCacheKey {
+            chart: chart.into(),
+            resolution: ResolutionKind::Day,
+            from: from.map(|date| NaiveDate::from_str(date).unwrap()),
+            to: None,
+        }
+    }
+
+    fn points(value: i64) -> Result<Vec<DateValue>, ()> {
+        Ok(vec![DateValue {
+            date: NaiveDate::from_str("2023-01-01").unwrap(),
+            value: value.into(),
+        }])
+    }
+
+    fn assert_not_called() -> Result<Vec<DateValue>, ()> {
+        panic!("should use cached version");
+    }
+
+    #[tokio::test]
+    async fn cached_until_invalidated() {
+        let cache = ReadCache::new(10, Duration::from_secs(3600));
+        assert_eq!(
+            cache
+                .get_or_fetch(key("newTxns", None), async { points(1) })
+                .await,
+            points(1)
+        );
+        assert_eq!(
+            cache
+                .get_or_fetch(key("newTxns", None), async { assert_not_called() })
+                .await,
+            points(1)
+        );
+        // other range is cached separately
+        assert_eq!(
+            cache
+                .get_or_fetch(key("newTxns", Some("2023-01-01")), async { points(2) })
+                .await,
+            points(2)
+        );
+        assert_eq!(
+            cache
+                .get_or_fetch(key("newBlocks", None), async { points(3) })
+                .await,
+            points(3)
+        );
+
+        cache.invalidate(["newTxns"]);
+        assert_eq!(
+            cache
+                .get_or_fetch(key("newTxns", None), async { points(4) })
+                .await,
+            points(4)
+        );
+        assert_eq!(
+            cache
+                .get_or_fetch(key("newBlocks", None), async { assert_not_called() })
+                .await,
+            points(3)
+        );
+    }
+
+    #[tokio::test]
+    async fn data_read_before_invalidation_is_not_cached() {
+        let cache = ReadCache::new(10, Duration::from_secs(3600));
+        let fetch = async {
+            cache.invalidate(["newTxns"]);
+            points(1)
+        };
+        assert_eq!(
+            cache.get_or_fetch(key("newTxns", None), fetch).await,
+            points(1)
+        );
+        assert_eq!(
+            cache
+                .get_or_fetch(key("newTxns", None), async { points(2) })
+                .await,
+            points(2)
+        );
+    }
+
+    #[tokio::test]
+    async fn oldest_entry_is_evicted() {
+        let cache = ReadCache::new(2, Duration::from_secs(3600));
+        for (chart, value) in [("a", 1), ("b", 2), ("c", 3)] {
+            cache
+                .get_or_fetch(key(chart, None), async { points(value) })
+                .await
+                .unwrap();
+        }
+        assert_eq!(
+            cache
+                .get_or_fetch(key("c", None), async { assert_not_called() })
+                .await,
+            points(3)
+        );
+        assert_eq!(
+            cache
+                .get_or_fetch(key("a", None), async { points(4) })
+                .await,
+            points(4)
+        );
+
+        let outdated = ReadCache::new(2, Duration::ZERO);
+        outdated
+            .get_or_fetch(key("a", None), async { points(1) })
+            .await
+            .unwrap();
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(
+            outdated
+                .get_or_fetch(key("a", None), async { points(2) })
+                .await,
+            points(2)
+        );
+    }
+}
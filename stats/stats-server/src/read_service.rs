@@ -1,24 +1,43 @@
-use crate::{charts::Charts, serializers::serialize_line_points};
+use crate::{
+    charts::Charts,
+    read_cache::{ReadCache, ReadCacheKey},
+    serializers::serialize_line_points,
+};
 use async_trait::async_trait;
-use chrono::NaiveDate;
+use chrono::{Duration, NaiveDate, Utc};
 use sea_orm::{DatabaseConnection, DbErr};
-use stats::ReadError;
+use stats::{
+    token_stats::TokenStatKind, AggregationPolicy, ChartMetadata, ReadError, ResolutionKind,
+};
 use stats_proto::blockscout::stats::v1::{
-    stats_service_server::StatsService, Counter, Counters, GetCountersRequest, GetLineChartRequest,
-    GetLineChartsRequest, LineChart, LineCharts,
+    stats_service_server::StatsService, BatchCharts, BatchGetChartsRequest, ChartStatus,
+    ChartsStatus, Counter, Counters, GetChartsStatusRequest, GetCountersRequest,
+    GetLineChartRequest, GetLineChartsRequest, GetTokenStatsRequest, LineChart, LineChartInfo,
+    LineChartSeries, LineCharts, Resolution, TokenStats,
 };
-use std::{str::FromStr, sync::Arc};
+use std::{collections::HashMap, str::FromStr, sync::Arc};
 use tonic::{Request, Response, Status};
 
 #[derive(Clone)]
 pub struct ReadService {
     db: Arc<DatabaseConnection>,
     charts: Arc<Charts>,
+    cache: Option<Arc<ReadCache>>,
 }
 
 impl ReadService {
     pub async fn new(db: Arc<DatabaseConnection>, charts: Arc<Charts>) -> Result<Self, DbErr> {
-        Ok(Self { db, charts })
+        Ok(Self {
+            db,
+            charts,
+            cache: None,
+        })
+    }
+
+    /// Points of line charts are served from `cache` when possible
+    pub fn with_cache(mut self, cache: Arc<ReadCache>) -> Self {
+        self.cache = Some(cache);
+        self
     }
 }
 
@@ -32,21 +51,70 @@ fn map_read_error(err: ReadError) -> Status {
     }
 }
 
-#[async_trait]
-impl StatsService for ReadService {
-    async fn get_counters(
-        &self,
-        _request: Request<GetCountersRequest>,
-    ) -> Result<Response<Counters>, Status> {
-        let mut data = stats::get_counters(&self.db)
-            .await
-            .map_err(map_read_error)?;
+fn last_updated_at(metadata: &HashMap<String, ChartMetadata>, name: &str) -> Option<String> {
+    metadata
+        .get(name)
+        .and_then(|metadata| metadata.last_updated_at)
+        .map(|time| time.to_rfc3339())
+}
 
-        let counters = self
-            .charts
+fn chart_status(name: &str, metadata: Option<&ChartMetadata>) -> ChartStatus {
+    let to_u32 = |value: i64| u32::try_from(value).unwrap_or(u32::MAX);
+    ChartStatus {
+        id: name.to_string(),
+        last_updated_at: metadata
+            .and_then(|metadata| metadata.last_updated_at)
+            .map(|time| time.to_rfc3339()),
+        last_attempt_at: metadata
+            .and_then(|metadata| metadata.last_attempt_at)
+            .map(|time| time.to_rfc3339()),
+        last_error: metadata.and_then(|metadata| metadata.last_error.clone()),
+        last_duration_ms: metadata
+            .and_then(|metadata| metadata.last_duration_ms)
+            .map(to_u32),
+        last_rows_written: metadata
+            .and_then(|metadata| metadata.last_rows_written)
+            .map(to_u32),
+    }
+}
+
+/// Values of `series` at dates of `total`, dates missing in `series` are zero.
+/// Aligned series are merged and paged the same way as the total
+fn aligned_to(series: Vec<stats::DateValue>, total: &[stats::DateValue]) -> Vec<stats::DateValue> {
+    let values: HashMap<_, _> = series
+        .into_iter()
+        .map(|point| (point.date, point.value))
+        .collect();
+    total
+        .iter()
+        .map(|point| stats::DateValue {
+            date: point.date,
+            value: values.get(&point.date).copied().unwrap_or_default(),
+        })
+        .collect()
+}
+
+fn resolution_from_proto(resolution: Resolution) -> ResolutionKind {
+    match resolution {
+        Resolution::Day => ResolutionKind::Day,
+        Resolution::Week => ResolutionKind::Week,
+        Resolution::Month => ResolutionKind::Month,
+        Resolution::Year => ResolutionKind::Year,
+    }
+}
+
+impl ReadService {
+    fn counters(
+        &self,
+        mut data: HashMap<String, stats::DateValue>,
+        metadata: &HashMap<String, ChartMetadata>,
+        names: Option<&[String]>,
+    ) -> Vec<Counter> {
+        self.charts
             .config
             .counters
             .iter()
+            .filter(|counter| names.map_or(true, |names| names.contains(&counter.id)))
             .filter_map(|counter| {
                 self.charts
                     .charts_info
@@ -62,56 +130,376 @@ impl StatsService for ReadService {
                     };
                     Counter {
                         id: counter.id.clone(),
-                        value: point.value,
+                        value: point.value.to_string(),
                         title: counter.title.clone(),
                         description: counter.description.clone(),
                         units: info.settings.units.clone(),
+                        last_updated_at: last_updated_at(metadata, &counter.id),
                     }
                 })
             })
-            .collect();
-        let counters = Counters { counters };
-        Ok(Response::new(counters))
+            .collect()
     }
 
-    async fn get_line_chart(
+    async fn line_chart(
         &self,
-        request: Request<GetLineChartRequest>,
-    ) -> Result<Response<LineChart>, Status> {
-        let request = request.into_inner();
+        request: GetLineChartRequest,
+        metadata: &HashMap<String, ChartMetadata>,
+    ) -> Result<LineChart, Status> {
+        let moving_average = self.charts.moving_averages.get(&request.name);
+        let chart_name = moving_average.map_or(&request.name, |info| &info.parent);
         let chart_info = self
             .charts
             .charts_info
-            .get(&request.name)
+            .get(chart_name)
             .ok_or_else(|| Status::not_found(format!("chart {} not found", request.name)))?;
+        let aggregation = match moving_average {
+            Some(_) => AggregationPolicy::Average,
+            None => chart_info.chart.aggregation_policy(),
+        };
 
+        let resolution = resolution_from_proto(request.resolution());
+        if request.max_points == Some(0) {
+            return Err(Status::invalid_argument("max_points must be positive"));
+        }
+        if request.page_size == Some(0) {
+            return Err(Status::invalid_argument("page_size must be positive"));
+        }
+        if request.page_size.is_some() && request.max_points.is_some() {
+            return Err(Status::invalid_argument(
+                "max_points can't be used together with page_size",
+            ));
+        }
+        let page_start = request
+            .page_token
+            .as_deref()
+            .map(NaiveDate::from_str)
+            .transpose()
+            .map_err(|_| Status::invalid_argument("invalid page_token"))?;
         let from = request
             .from
             .and_then(|date| NaiveDate::from_str(&date).ok());
+        // page token is the date of the first point of the page
+        let from = match (from, page_start) {
+            (Some(from), Some(page_start)) => Some(from.max(page_start)),
+            (from, page_start) => page_start.or(from),
+        };
         let to = request.to.and_then(|date| NaiveDate::from_str(&date).ok());
-        let policy = Some(chart_info.chart.missing_date_policy());
-        let mut data = stats::get_chart_data(&self.db, &request.name, from, to, policy)
-            .await
-            .map_err(map_read_error)?;
+        let policy = chart_info
+            .settings
+            .missing_date_policy
+            .unwrap_or_else(|| chart_info.chart.missing_date_policy());
+        // periods of such charts are computed by blockscout, not aggregated from days
+        let stored_resolution = moving_average.is_none()
+            && resolution != ResolutionKind::Day
+            && chart_info.chart.period_sql().is_some();
+        // the page is read up to the next one only, if the chart doesn't end earlier
+        let next_page_start = match request.page_size {
+            Some(page_size) => {
+                let name = match stored_resolution {
+                    true => stats::resolution_name(chart_name, resolution),
+                    false => request.name.clone(),
+                };
+                stats::get_next_page_start(&self.db, &name, from, page_size.into(), resolution)
+                    .await
+                    .map_err(map_read_error)?
+            }
+            None => None,
+        };
+        let to = match next_page_start {
+            Some(next_page_start) => {
+                let page_end = next_page_start - Duration::days(1);
+                Some(to.map_or(page_end, |to| to.min(page_end)))
+            }
+            None => to,
+        };
+        let fetch = async {
+            if stored_resolution {
+                stats::get_chart_data(
+                    &self.db,
+                    &stats::resolution_name(chart_name, resolution),
+                    from.map(|from| resolution.period_start(from)),
+                    to,
+                    None,
+                )
+                .await
+            } else {
+                stats::get_chart_data_with_resolution(
+                    &self.db,
+                    &request.name,
+                    from,
+                    to,
+                    Some(policy),
+                    resolution,
+                    aggregation,
+                )
+                .await
+            }
+        };
+        let mut data = match &self.cache {
+            Some(cache) => {
+                let key = ReadCacheKey {
+                    chart: request.name.clone(),
+                    resolution,
+                    from,
+                    to,
+                };
+                cache.get_or_fetch(key, fetch).await
+            }
+            None => fetch.await,
+        }
+        .map_err(map_read_error)?;
 
         if chart_info.chart.drop_last_point() {
             // remove last data point, because it can be partially updated
             if let Some(last) = data.last() {
-                if last.is_partial() {
+                if resolution.period_end(last.date) >= Utc::now().date_naive() {
                     data.pop();
                 }
             }
         }
+        let keys: &[&str] = match moving_average {
+            Some(_) => &[],
+            None => chart_info.chart.series(),
+        };
+        let series = futures::future::try_join_all(keys.iter().map(|key| {
+            stats::get_series_data(&self.db, chart_name, key, from, to, resolution, aggregation)
+        }))
+        .await
+        .map_err(map_read_error)?;
+        let mut series: Vec<_> = series
+            .into_iter()
+            .map(|series| aligned_to(series, &data))
+            .collect();
+
+        if let Some(max_points) = request.max_points {
+            data = stats::downsample(data, max_points as usize, aggregation)
+                .map_err(map_read_error)?;
+            series = series
+                .into_iter()
+                .map(|series| stats::downsample(series, max_points as usize, aggregation))
+                .collect::<Result<_, _>>()
+                .map_err(map_read_error)?;
+        }
+        let next_page_token = match request.page_size {
+            Some(page_size) => {
+                // filled missing dates can make the page longer than `page_size`
+                let (page, filled_page_start) = stats::paginate(data, page_size as usize);
+                data = page;
+                series = series
+                    .into_iter()
+                    .map(|series| stats::paginate(series, page_size as usize).0)
+                    .collect();
+                filled_page_start
+                    .or(next_page_start)
+                    .map(|date| date.to_string())
+            }
+            None => None,
+        };
         let serialized_chart = serialize_line_points(data);
-        Ok(Response::new(LineChart {
+        let series = keys
+            .iter()
+            .zip(series)
+            .map(|(key, series)| LineChartSeries {
+                key: key.to_string(),
+                chart: serialize_line_points(series),
+            })
+            .collect();
+        let info = self
+            .charts
+            .config
+            .lines
+            .sections
+            .iter()
+            .flat_map(|section| section.charts.iter())
+            .find(|info| &info.id == chart_name)
+            .map(|info| {
+                let info = LineChartInfo {
+                    section: chart_info.section.clone(),
+                    ..info.clone().into()
+                };
+                match moving_average {
+                    Some(moving_average) => LineChartInfo {
+                        id: request.name.clone(),
+                        title: format!(
+                            "{} ({}-day moving average)",
+                            info.title, moving_average.window
+                        ),
+                        last_updated_at: last_updated_at(metadata, &request.name),
+                        ..info
+                    },
+                    None => LineChartInfo {
+                        last_updated_at: last_updated_at(metadata, &info.id),
+                        ..info
+                    },
+                }
+            });
+        Ok(LineChart {
             chart: serialized_chart,
-        }))
+            info,
+            resolution: request.resolution,
+            next_page_token,
+            series,
+        })
+    }
+}
+
+#[async_trait]
+impl StatsService for ReadService {
+    async fn get_counters(
+        &self,
+        _request: Request<GetCountersRequest>,
+    ) -> Result<Response<Counters>, Status> {
+        let data = stats::get_counters(&self.db)
+            .await
+            .map_err(map_read_error)?;
+        let metadata = stats::get_charts_metadata(&self.db)
+            .await
+            .map_err(map_read_error)?;
+        let counters = self.counters(data, &metadata, None);
+        Ok(Response::new(Counters { counters }))
+    }
+
+    async fn get_line_chart(
+        &self,
+        request: Request<GetLineChartRequest>,
+    ) -> Result<Response<LineChart>, Status> {
+        let metadata = stats::get_charts_metadata(&self.db)
+            .await
+            .map_err(map_read_error)?;
+        let chart = self.line_chart(request.into_inner(), &metadata).await?;
+        Ok(Response::new(chart))
+    }
+
+    async fn batch_get_charts(
+        &self,
+        request: Request<BatchGetChartsRequest>,
+    ) -> Result<Response<BatchCharts>, Status> {
+        let request = request.into_inner();
+        if let Some(name) = request.counters.iter().find(|name| {
+            !self
+                .charts
+                .config
+                .counters
+                .iter()
+                .any(|counter| &counter.id == *name)
+        }) {
+            return Err(Status::not_found(format!("counter {name} not found")));
+        }
+        let metadata = stats::get_charts_metadata(&self.db)
+            .await
+            .map_err(map_read_error)?;
+        let counters = async {
+            if request.counters.is_empty() {
+                return Ok::<_, Status>(vec![]);
+            }
+            let data = stats::get_counters(&self.db)
+                .await
+                .map_err(map_read_error)?;
+            Ok(self.counters(data, &metadata, Some(&request.counters)))
+        };
+        let lines = futures::future::try_join_all(
+            request
+                .lines
+                .into_iter()
+                .map(|line| self.line_chart(line, &metadata)),
+        );
+        let (lines, counters) = futures::try_join!(lines, counters)?;
+        Ok(Response::new(BatchCharts { lines, counters }))
+    }
+
+    async fn get_charts_status(
+        &self,
+        _request: Request<GetChartsStatusRequest>,
+    ) -> Result<Response<ChartsStatus>, Status> {
+        let metadata = stats::get_charts_metadata(&self.db)
+            .await
+            .map_err(map_read_error)?;
+        let charts = self
+            .charts
+            .charts_order
+            .iter()
+            .map(|name| chart_status(name, metadata.get(name)))
+            .collect();
+        Ok(Response::new(ChartsStatus { charts }))
     }
 
     async fn get_line_charts(
         &self,
         _request: Request<GetLineChartsRequest>,
     ) -> Result<Response<LineCharts>, Status> {
-        Ok(Response::new(self.charts.config.lines.clone().into()))
+        let metadata = stats::get_charts_metadata(&self.db)
+            .await
+            .map_err(map_read_error)?;
+        let mut lines: LineCharts = self.charts.config.lines.clone().into();
+        for info in lines
+            .sections
+            .iter_mut()
+            .flat_map(|section| section.charts.iter_mut())
+        {
+            info.last_updated_at = last_updated_at(&metadata, &info.id);
+        }
+        Ok(Response::new(lines))
+    }
+
+    async fn get_token_stats(
+        &self,
+        request: Request<GetTokenStatsRequest>,
+    ) -> Result<Response<TokenStats>, Status> {
+        let request = request.into_inner();
+        let address = hex::decode(request.address.trim_start_matches("0x"))
+            .map_err(|_| Status::invalid_argument("invalid token address"))?;
+        let from = request
+            .from
+            .and_then(|date| NaiveDate::from_str(&date).ok());
+        let to = request.to.and_then(|date| NaiveDate::from_str(&date).ok());
+        let (transfers, holders) = futures::try_join!(
+            stats::token_stats::get_token_stats(
+                &self.db,
+                &address,
+                TokenStatKind::Transfers,
+                from,
+                to
+            ),
+            stats::token_stats::get_token_stats(
+                &self.db,
+                &address,
+                TokenStatKind::Holders,
+                from,
+                to
+            ),
+        )
+        .map_err(map_read_error)?;
+        Ok(Response::new(TokenStats {
+            address: request.address,
+            transfers: serialize_line_points(transfers),
+            holders: serialize_line_points(holders),
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn points(points: &[(&str, i64)]) -> Vec<stats::DateValue> {
+        points
+            .iter()
+            .map(|(date, value)| stats::DateValue {
+                date: NaiveDate::from_str(date).unwrap(),
+                value: (*value).into(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn series_are_aligned_to_total() {
+        let total = points(&[("2023-01-01", 5), ("2023-01-02", 3), ("2023-01-03", 4)]);
+        let series = points(&[("2022-12-31", 1), ("2023-01-02", 2), ("2023-01-04", 7)]);
+        assert_eq!(
+            aligned_to(series, &total),
+            points(&[("2023-01-01", 0), ("2023-01-02", 2), ("2023-01-03", 0)])
+        );
     }
 }
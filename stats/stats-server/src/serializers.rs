@@ -5,7 +5,7 @@ pub fn serialize_line_points(data: Vec<DateValue>) -> Vec<Point> {
     data.into_iter()
         .map(|point| Point {
             date: point.date.to_string(),
-            value: point.value,
+            value: point.value.to_string(),
         })
         .collect()
 }
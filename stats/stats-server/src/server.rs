@@ -1,50 +1,109 @@
 use crate::{
-    charts::Charts, config::read_charts_config, health::HealthService, read_service::ReadService,
-    settings::Settings, update_service::UpdateService,
+    admin_service::AdminService,
+    alerts::{Alerter, WebhookSink},
+    charts::Charts,
+    config::{read_charts_config, toml_config::Config},
+    export::{route_export, ExportService},
+    health::HealthService,
+    market_data::CoinGeckoSource,
+    multichain::Multichain,
+    read_cache::ReadCache,
+    read_service::ReadService,
+    settings::{Settings, TokenStatsSettings},
+    update_service::UpdateService,
 };
 use blockscout_service_launcher::launcher::{self, LaunchSettings};
 use sea_orm::{ConnectOptions, Database};
+use stats::{market::MarketDataSource, rollup::RollupType, token_stats::TokenSelection};
 use stats_proto::blockscout::stats::v1::{
     health_actix::route_health,
     health_server::HealthServer,
+    stats_admin_service_actix::route_stats_admin_service,
+    stats_admin_service_server::StatsAdminServiceServer,
     stats_service_actix::route_stats_service,
     stats_service_server::{StatsService, StatsServiceServer},
 };
-use std::sync::Arc;
+use std::{collections::BTreeMap, sync::Arc};
 
 const SERVICE_NAME: &str = "stats";
 
 #[derive(Clone)]
 struct HttpRouter<S: StatsService> {
     stats: Arc<S>,
+    admin: Arc<Multichain<AdminService>>,
     health: Arc<HealthService>,
+    export: Arc<Multichain<ExportService>>,
 }
 
 impl<S: StatsService> launcher::HttpRouter for HttpRouter<S> {
     fn register_routes(&self, service_config: &mut actix_web::web::ServiceConfig) {
         service_config
             .configure(|config| route_health(config, self.health.clone()))
-            .configure(|config| route_stats_service(config, self.stats.clone()));
+            .configure(|config| route_stats_service(config, self.stats.clone()))
+            .configure(|config| route_stats_admin_service(config, self.admin.clone()))
+            .configure(|config| route_export(config, self.export.clone()));
     }
 }
 
 fn grpc_router<S: StatsService>(
     stats: Arc<S>,
+    admin: Arc<Multichain<AdminService>>,
     health: Arc<HealthService>,
 ) -> tonic::transport::server::Router {
     tonic::transport::Server::builder()
         .add_service(HealthServer::from_arc(health))
         .add_service(StatsServiceServer::from_arc(stats))
+        .add_service(StatsAdminServiceServer::from_arc(admin))
 }
 
-pub async fn stats(settings: Settings) -> Result<(), anyhow::Error> {
-    blockscout_service_launcher::tracing::init_logs(
-        SERVICE_NAME,
-        &settings.tracing,
-        &settings.jaeger,
-    )?;
-    let charts_config = read_charts_config(&settings.charts_config)?;
-    let mut opt = ConnectOptions::new(settings.db_url.clone());
+fn token_selection(settings: &TokenStatsSettings) -> Result<TokenSelection, anyhow::Error> {
+    if settings.tokens.is_empty() {
+        return Ok(TokenSelection::Top(settings.top_tokens));
+    }
+    let addresses = settings
+        .tokens
+        .iter()
+        .map(|address| {
+            let bytes = hex::decode(address.trim_start_matches("0x"))
+                .map_err(|err| anyhow::anyhow!("invalid token address {}: {}", address, err))?;
+            if bytes.len() != 20 {
+                anyhow::bail!("invalid token address {}: expected 20 bytes", address);
+            }
+            Ok(bytes)
+        })
+        .collect::<Result<_, _>>()?;
+    Ok(TokenSelection::Addresses(addresses))
+}
+
+/// Services of a single chain, which has its own stats and blockscout databases
+struct ChainServices {
+    read: Arc<ReadService>,
+    admin: Arc<AdminService>,
+    export: Arc<ExportService>,
+}
+
+fn multichain<S>(
+    main: &ChainServices,
+    chains: &BTreeMap<&str, &ChainServices>,
+    service: impl Fn(&ChainServices) -> &Arc<S>,
+) -> Arc<Multichain<S>> {
+    let chains = chains
+        .iter()
+        .map(|(id, chain)| (id.to_string(), service(chain).clone()))
+        .collect();
+    Arc::new(Multichain::new(service(main).clone(), chains))
+}
+
+/// Connects to databases of the chain and starts updating its charts
+async fn init_chain(
+    settings: &Settings,
+    charts_config: &Config,
+    db_url: &str,
+    blockscout_db_url: &str,
+    rollup: Option<RollupType>,
+    chain_id: Option<&str>,
+) -> Result<ChainServices, anyhow::Error> {
+    let mut opt = ConnectOptions::new(db_url.to_string());
     opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
     blockscout_service_launcher::database::initialize_postgres::<stats::migration::Migrator>(
         opt.clone(),
@@ -54,37 +113,195 @@ pub async fn stats(settings: Settings) -> Result<(), anyhow::Error> {
     .await?;
     let db = Arc::new(Database::connect(opt).await?);
 
-    let mut opt = ConnectOptions::new(settings.blockscout_db_url.clone());
+    let mut opt = ConnectOptions::new(blockscout_db_url.to_string());
     opt.sqlx_logging_level(tracing::log::LevelFilter::Debug);
     let blockscout = Arc::new(Database::connect(opt).await?);
 
-    let charts = Arc::new(Charts::new(charts_config)?);
+    let market_data = settings.market_data.enabled.then(|| {
+        Arc::new(CoinGeckoSource::new(settings.market_data.clone())) as Arc<dyn MarketDataSource>
+    });
+    let user_ops = stats::user_ops::user_ops_indexed(&blockscout).await?;
+    if user_ops {
+        tracing::info!("user operations are indexed, account abstraction charts are available");
+    }
+    let charts = Arc::new(Charts::new(
+        charts_config.clone(),
+        rollup,
+        market_data,
+        user_ops,
+    )?);
 
     // TODO: may be run this with migrations or have special config
     for chart_info in charts.charts_info.values() {
         chart_info.chart.create(&db).await?;
     }
 
-    let update_service =
-        Arc::new(UpdateService::new(db.clone(), blockscout, charts.clone()).await?);
+    let mut update_service = UpdateService::new(
+        db.clone(),
+        blockscout,
+        charts.clone(),
+        settings.concurrent_updates,
+    )
+    .await?
+    .with_recompute_window_days(settings.recompute_window_days);
+    if let Some(chain_id) = chain_id {
+        update_service = update_service.with_chain_id(chain_id.to_string());
+    }
+    if let Some(url) = &settings.alerts.webhook_url {
+        let sink = Arc::new(WebhookSink::new(
+            url.clone(),
+            settings.alerts.format,
+            settings.alerts.timeout,
+        ));
+        let alerter = Arc::new(Alerter::new(
+            sink,
+            chain_id.map(String::from),
+            settings.alerts.max_consecutive_failures,
+        ));
+        if let Some(max_staleness) = settings.alerts.max_staleness {
+            tokio::spawn(alerter.clone().run_staleness_checks(
+                db.clone(),
+                charts.charts_order.clone(),
+                max_staleness,
+                settings.alerts.staleness_check_interval,
+            ));
+        }
+        update_service = update_service.with_alerter(alerter);
+    }
+    let read_cache = settings.read_cache.enabled.then(|| {
+        Arc::new(ReadCache::new(
+            settings.read_cache.max_entries,
+            settings.read_cache.max_age,
+        ))
+    });
+    if let Some(read_cache) = &read_cache {
+        update_service = update_service.with_read_cache(read_cache.clone());
+    }
+    let update_service = Arc::new(update_service);
 
-    tokio::spawn(async move {
+    if settings.realtime_counters.enabled {
         update_service
+            .start_realtime_counters(
+                &settings.realtime_counters.counters,
+                settings.realtime_counters.poll_interval,
+            )
+            .map_err(|name| {
+                anyhow::anyhow!(
+                    "realtime counter {name} is not enabled or can't be refreshed incrementally"
+                )
+            })?;
+    }
+    if settings.market_data.enabled {
+        update_service.start_market_data_updates(settings.market_data.poll_interval);
+    }
+    if settings.token_stats.enabled {
+        let tokens = token_selection(&settings.token_stats)?;
+        let schedule = settings
+            .token_stats
+            .update_schedule
+            .clone()
+            .unwrap_or_else(|| settings.default_schedule.clone());
+        tokio::spawn(update_service.clone().run_token_stats_updater(
+            tokens,
+            schedule,
+            settings.schedule_jitter,
+            settings.force_update_on_start,
+        ));
+    }
+
+    let admin_service = Arc::new(AdminService::new(
+        update_service.clone(),
+        settings.api_key.clone(),
+    ));
+
+    let updater = update_service.clone();
+    let concurrent_start_updates = settings.concurrent_start_updates;
+    let default_schedule = settings.default_schedule.clone();
+    let schedule_jitter = settings.schedule_jitter;
+    let force_update_on_start = settings.force_update_on_start;
+    tokio::spawn(async move {
+        updater
             .force_async_update_and_run(
-                settings.concurrent_start_updates,
-                settings.default_schedule,
-                settings.force_update_on_start,
+                concurrent_start_updates,
+                default_schedule,
+                schedule_jitter,
+                force_update_on_start,
             )
             .await;
     });
 
-    let read_service = Arc::new(ReadService::new(db, charts).await?);
+    let export = Arc::new(ExportService::new(db.clone(), charts.clone()));
+    let mut read_service = ReadService::new(db, charts).await?;
+    if let Some(read_cache) = read_cache {
+        read_service = read_service.with_cache(read_cache);
+    }
+    let read_service = Arc::new(read_service);
+    Ok(ChainServices {
+        read: read_service,
+        admin: admin_service,
+        export,
+    })
+}
+
+pub async fn stats(settings: Settings) -> Result<(), anyhow::Error> {
+    blockscout_service_launcher::tracing::init_logs(
+        SERVICE_NAME,
+        &settings.tracing,
+        &settings.jaeger,
+    )?;
+    let charts_config = read_charts_config(&settings.charts_config)?;
+    if settings.concurrent_updates == 0 {
+        anyhow::bail!("concurrent_updates must be at least 1");
+    }
+    if let Some(chain_id) = &settings.chain_id {
+        if settings.chains.contains_key(chain_id) {
+            anyhow::bail!("main chain {chain_id} is configured in chains too");
+        }
+    }
+
+    let main = init_chain(
+        &settings,
+        &charts_config,
+        &settings.db_url,
+        &settings.blockscout_db_url,
+        settings.rollup,
+        settings.chain_id.as_deref(),
+    )
+    .await?;
+
+    let mut other_chains = BTreeMap::new();
+    for (chain_id, chain) in &settings.chains {
+        tracing::info!(chain_id = chain_id.as_str(), "initializing chain");
+        let services = init_chain(
+            &settings,
+            &charts_config,
+            &chain.db_url,
+            &chain.blockscout_db_url,
+            chain.rollup,
+            Some(chain_id.as_str()),
+        )
+        .await?;
+        other_chains.insert(chain_id.as_str(), services);
+    }
+    let mut chains: BTreeMap<_, _> = other_chains
+        .iter()
+        .map(|(id, chain)| (*id, chain))
+        .collect();
+    if let Some(chain_id) = &settings.chain_id {
+        chains.insert(chain_id.as_str(), &main);
+    }
+
+    let read_service = multichain(&main, &chains, |chain| &chain.read);
+    let admin_service = multichain(&main, &chains, |chain| &chain.admin);
+    let export = multichain(&main, &chains, |chain| &chain.export);
     let health = Arc::new(HealthService::default());
 
-    let grpc_router = grpc_router(read_service.clone(), health.clone());
+    let grpc_router = grpc_router(read_service.clone(), admin_service.clone(), health.clone());
     let http_router = HttpRouter {
         stats: read_service,
+        admin: admin_service,
         health: health.clone(),
+        export,
     };
 
     let launch_settings = LaunchSettings {
@@ -1,3 +1,4 @@
+use crate::alerts::AlertFormat;
 use blockscout_service_launcher::{
     launcher::{
         ConfigSettings, GrpcServerSettings, HttpServerSettings, MetricsSettings, ServerSettings,
@@ -6,8 +7,9 @@ use blockscout_service_launcher::{
 };
 use cron::Schedule;
 use serde::{Deserialize, Serialize};
-use serde_with::{serde_as, DisplayFromStr};
-use std::{net::SocketAddr, path::PathBuf, str::FromStr};
+use serde_with::{serde_as, DisplayFromStr, DurationSeconds};
+use stats::rollup::RollupType;
+use std::{collections::BTreeMap, net::SocketAddr, path::PathBuf, str::FromStr, time::Duration};
 
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -19,9 +21,31 @@ pub struct Settings {
     pub blockscout_db_url: String,
     #[serde_as(as = "DisplayFromStr")]
     pub default_schedule: Schedule,
+    /// Upper bound of random delay added to every scheduled update,
+    /// so charts with the same schedule don't hit blockscout db at once
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub schedule_jitter: Duration,
     pub force_update_on_start: Option<bool>, // None = no update
     pub concurrent_start_updates: usize,
+    /// Max number of simultaneous chart updates, both initial and scheduled
+    pub concurrent_updates: usize,
+    /// Number of last days recomputed on every update, replacing stored values
+    pub recompute_window_days: u64,
     pub charts_config: PathBuf,
+    /// Key required by admin api, admin api is disabled if not set
+    pub api_key: Option<String>,
+    pub token_stats: TokenStatsSettings,
+    pub realtime_counters: RealtimeCountersSettings,
+    pub alerts: AlertsSettings,
+    pub read_cache: ReadCacheSettings,
+    pub market_data: MarketDataSettings,
+    /// Id of the main chain, which is configured by `db_url` and `blockscout_db_url`
+    /// and serves requests without chain id
+    pub chain_id: Option<String>,
+    /// Other chains served by the service, keyed by chain id
+    pub chains: BTreeMap<String, ChainSettings>,
+    /// Rollup stack of the main chain, charts of L2 data are enabled only if set
+    pub rollup: Option<RollupType>,
 
     pub server: ServerSettings,
     pub metrics: MetricsSettings,
@@ -46,9 +70,21 @@ impl Default for Settings {
             },
             db_url: Default::default(),
             default_schedule: Schedule::from_str("0 0 1 * * * *").unwrap(),
+            schedule_jitter: Duration::from_secs(0),
             force_update_on_start: Some(false),
             concurrent_start_updates: 3,
+            concurrent_updates: 4,
+            recompute_window_days: 1,
             charts_config: PathBuf::from_str("config/charts.json").unwrap(),
+            api_key: None,
+            token_stats: Default::default(),
+            realtime_counters: Default::default(),
+            alerts: Default::default(),
+            read_cache: Default::default(),
+            market_data: Default::default(),
+            chain_id: None,
+            chains: Default::default(),
+            rollup: None,
             blockscout_db_url: Default::default(),
             create_database: Default::default(),
             run_migrations: Default::default(),
@@ -59,6 +95,153 @@ impl Default for Settings {
     }
 }
 
+/// Refresh of fast-moving counters on every new block, apart from their schedule
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct RealtimeCountersSettings {
+    pub enabled: bool,
+    pub counters: Vec<String>,
+    /// How often the last block of blockscout is checked
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub poll_interval: Duration,
+}
+
+impl Default for RealtimeCountersSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            counters: vec!["totalBlocks".to_string(), "totalTxns".to_string()],
+            poll_interval: Duration::from_secs(5),
+        }
+    }
+}
+
+/// Alerts about charts that fail to update, sent to webhook
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertsSettings {
+    /// Alerts are disabled if not set
+    pub webhook_url: Option<String>,
+    pub format: AlertFormat,
+    /// Alert is sent when the chart fails to update this many times in a row
+    pub max_consecutive_failures: u32,
+    /// Alert is sent when the chart isn't updated successfully for this long,
+    /// staleness isn't checked if not set
+    #[serde_as(as = "Option<DurationSeconds<u64>>")]
+    pub max_staleness: Option<Duration>,
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub staleness_check_interval: Duration,
+    /// Timeout of requests to the webhook
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub timeout: Duration,
+}
+
+impl Default for AlertsSettings {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            format: Default::default(),
+            max_consecutive_failures: 3,
+            max_staleness: None,
+            staleness_check_interval: Duration::from_secs(60),
+            timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// In-memory cache of line chart points served by api
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct ReadCacheSettings {
+    pub enabled: bool,
+    /// Maximal number of cached (chart, resolution, range) requests of every chain
+    pub max_entries: usize,
+    /// Cached data is dropped after this period even if the chart wasn't updated
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub max_age: Duration,
+}
+
+impl Default for ReadCacheSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_entries: 1000,
+            max_age: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+/// Source of native coin price and market cap charts, api compatible with CoinGecko
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct MarketDataSettings {
+    pub enabled: bool,
+    pub url: String,
+    pub api_key: Option<String>,
+    pub api_key_header: String,
+    pub coin_id: String,
+    pub vs_currency: String,
+    /// How often market charts are updated in addition to their schedule
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub poll_interval: Duration,
+    /// Timeout of requests to the api
+    #[serde_as(as = "DurationSeconds<u64>")]
+    pub timeout: Duration,
+}
+
+impl Default for MarketDataSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            url: "https://api.coingecko.com/api/v3".to_string(),
+            api_key: None,
+            api_key_header: "x-cg-pro-api-key".to_string(),
+            coin_id: "ethereum".to_string(),
+            vs_currency: "usd".to_string(),
+            poll_interval: Duration::from_secs(10 * 60),
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Databases of additional chain, charts of all chains are set by the same charts config
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct ChainSettings {
+    pub db_url: String,
+    pub blockscout_db_url: String,
+    pub rollup: Option<RollupType>,
+}
+
+/// Collection of daily transfers and holders of separate tokens
+#[serde_as]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default, deny_unknown_fields)]
+pub struct TokenStatsSettings {
+    pub enabled: bool,
+    /// Addresses of tracked tokens. If empty, `top_tokens` most active tokens are tracked
+    pub tokens: Vec<String>,
+    pub top_tokens: u64,
+    /// `default_schedule` is used if not set
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub update_schedule: Option<Schedule>,
+}
+
+impl Default for TokenStatsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            tokens: vec![],
+            top_tokens: 10,
+            update_schedule: None,
+        }
+    }
+}
+
 impl ConfigSettings for Settings {
     const SERVICE_NAME: &'static str = "STATS";
 }
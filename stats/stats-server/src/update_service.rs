@@ -1,23 +1,91 @@
-use crate::charts::{ArcChart, Charts};
-use chrono::Utc;
+use crate::{
+    alerts::Alerter,
+    charts::{ArcChart, Charts},
+    read_cache::ReadCache,
+};
+use chrono::{NaiveDate, Utc};
 use cron::Schedule;
+use rand::Rng;
 use sea_orm::{DatabaseConnection, DbErr};
-use std::sync::Arc;
+use stats::{
+    entity::sea_orm_active_enums::ChartType, token_stats::TokenSelection, RealtimeCursor,
+    UpdateError, UpdateParameters,
+};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::sync::Semaphore;
+
+/// Label of token stats updates in chart metrics
+const TOKEN_STATS_ID: &str = "tokenStats";
+
+/// Number of finished update jobs kept to be queried, older ones are dropped
+const MAX_FINISHED_JOBS: usize = 100;
 
 pub struct UpdateService {
     db: Arc<DatabaseConnection>,
     blockscout: Arc<DatabaseConnection>,
     charts: Arc<Charts>,
+    update_semaphore: Arc<Semaphore>,
+    jobs: Mutex<HashMap<u64, UpdateJob>>,
+    next_job_id: AtomicU64,
+    alerter: Option<Arc<Alerter>>,
+    read_cache: Option<Arc<ReadCache>>,
+    /// Id of the chain in metrics and update mutexes of charts, empty for the main chain without id
+    chain_id: String,
+    /// Number of last stored days recomputed by partial updates
+    recompute_window_days: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateJobStatus {
+    Running,
+    Done,
+    Failed,
+}
+
+/// Full recompute of several charts requested through admin api
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateJob {
+    pub id: u64,
+    pub charts: Vec<String>,
+    pub updated_charts: Vec<String>,
+    pub failed_charts: Vec<String>,
+}
+
+impl UpdateJob {
+    pub fn status(&self) -> UpdateJobStatus {
+        if self.updated_charts.len() + self.failed_charts.len() < self.charts.len() {
+            UpdateJobStatus::Running
+        } else if self.failed_charts.is_empty() {
+            UpdateJobStatus::Done
+        } else {
+            UpdateJobStatus::Failed
+        }
+    }
 }
 
-fn time_till_next_call(schedule: &Schedule) -> std::time::Duration {
-    let default = std::time::Duration::from_millis(500);
+fn time_till_next_call(schedule: &Schedule, jitter: Duration) -> Duration {
+    let default = Duration::from_millis(500);
     let now = Utc::now();
 
-    schedule
+    let till_next_call = schedule
         .upcoming(Utc)
         .next()
-        .map_or(default, |t| (t - now).to_std().unwrap_or(default))
+        .map_or(default, |t| (t - now).to_std().unwrap_or(default));
+    till_next_call + random_jitter(jitter)
+}
+
+fn random_jitter(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+    rand::thread_rng().gen_range(Duration::ZERO..=max)
 }
 
 impl UpdateService {
@@ -25,24 +93,81 @@ impl UpdateService {
         db: Arc<DatabaseConnection>,
         blockscout: Arc<DatabaseConnection>,
         charts: Arc<Charts>,
+        concurrent_updates: usize,
     ) -> Result<Self, DbErr> {
         Ok(Self {
             db,
             blockscout,
             charts,
+            update_semaphore: Arc::new(Semaphore::new(concurrent_updates)),
+            jobs: Default::default(),
+            next_job_id: AtomicU64::new(1),
+            alerter: None,
+            read_cache: None,
+            chain_id: String::new(),
+            recompute_window_days: UpdateParameters::default().recompute_window_days,
         })
     }
+
+    pub fn with_chain_id(mut self, chain_id: String) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    pub fn with_recompute_window_days(mut self, days: u64) -> Self {
+        self.recompute_window_days = days;
+        self
+    }
+
+    fn update_parameters(&self, force_full: bool) -> UpdateParameters {
+        UpdateParameters {
+            force_full,
+            recompute_window_days: self.recompute_window_days,
+        }
+    }
+
+    /// Results of all chart updates are reported to `alerter`
+    pub fn with_alerter(mut self, alerter: Arc<Alerter>) -> Self {
+        self.alerter = Some(alerter);
+        self
+    }
+
+    /// Cached data of updated charts is dropped from `read_cache`
+    pub fn with_read_cache(mut self, read_cache: Arc<ReadCache>) -> Self {
+        self.read_cache = Some(read_cache);
+        self
+    }
+
+    /// Names of charts whose stored data can be changed by update of `chart`:
+    /// the chart itself, its parents updated along with it and moving averages of them
+    fn affected_charts<'a>(&'a self, chart: &'a ArcChart) -> Vec<&'a str> {
+        let mut charts = vec![chart.name()];
+        charts.extend(chart.dependencies());
+        let moving_averages = self
+            .charts
+            .moving_averages
+            .iter()
+            .filter(|(_, info)| charts.contains(&info.parent.as_str()))
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        charts.extend(moving_averages);
+        charts
+    }
+
     pub async fn force_async_update_and_run(
         self: Arc<Self>,
         concurrent_tasks: usize,
         default_schedule: Schedule,
+        schedule_jitter: Duration,
         force_update_on_start: Option<bool>,
     ) {
-        let semaphore = Arc::new(tokio::sync::Semaphore::new(concurrent_tasks));
+        let semaphore = Arc::new(Semaphore::new(concurrent_tasks));
+        // permits are granted in order of request, so parents are updated first
         let tasks = self
             .charts
-            .charts_info
-            .values()
+            .charts_order
+            .iter()
+            .filter_map(|name| self.charts.charts_info.get(name))
             .map(|chart_info| {
                 let this = self.clone();
                 let chart = chart_info.chart.clone();
@@ -51,9 +176,9 @@ impl UpdateService {
                 async move {
                     let _permit = sema.acquire().await.expect("failed to acquire permit");
                     if let Some(force_full) = force_update_on_start {
-                        this.clone().update(chart.clone(), force_full).await
+                        let _ = this.clone().update(chart.clone(), force_full).await;
                     };
-                    this.spawn_chart_updater(chart, &default_schedule);
+                    this.spawn_chart_updater(chart, &default_schedule, schedule_jitter);
                 }
             })
             .collect::<Vec<_>>();
@@ -61,7 +186,12 @@ impl UpdateService {
         tracing::info!("initial updating is done");
     }
 
-    fn spawn_chart_updater(self: &Arc<Self>, chart: ArcChart, default_schedule: &Schedule) {
+    fn spawn_chart_updater(
+        self: &Arc<Self>,
+        chart: ArcChart,
+        default_schedule: &Schedule,
+        jitter: Duration,
+    ) {
         let chart_info = self
             .charts
             .charts_info
@@ -75,39 +205,583 @@ impl UpdateService {
             .as_ref()
             .unwrap_or(default_schedule)
             .clone();
-        tokio::spawn(async move { this.run_cron(chart, schedule).await });
+        tokio::spawn(async move { this.run_cron(chart, schedule, jitter).await });
+    }
+
+    /// Starts full recompute of `charts` in background, all charts are recomputed if empty.
+    /// Returns name of unknown chart if any
+    pub fn start_force_full_update(
+        self: &Arc<Self>,
+        charts: Vec<String>,
+    ) -> Result<UpdateJob, String> {
+        if let Some(unknown) = charts
+            .iter()
+            .find(|name| !self.charts.charts_info.contains_key(*name))
+        {
+            return Err(unknown.clone());
+        }
+        // keep update order, so parents are recomputed before dependent charts
+        let charts: Vec<_> = self
+            .charts
+            .charts_order
+            .iter()
+            .filter(|name| charts.is_empty() || charts.contains(name))
+            .cloned()
+            .collect();
+        let job = self.create_job(charts.clone());
+        tracing::info!(job_id = job.id, charts = ?job.charts, "starting forced full update");
+
+        let this = self.clone();
+        let job_id = job.id;
+        tokio::spawn(async move {
+            for name in charts {
+                let chart = this.charts.charts_info[&name].chart.clone();
+                let updated = this.clone().update(chart, true).await.is_ok();
+                this.record_job_result(job_id, name, updated);
+            }
+        });
+        Ok(job)
+    }
+
+    /// Starts recompute of values of line chart `name` dated by `[from, to]` in background.
+    /// Charts depending on it are updated afterwards. Returns `name` if the chart is unknown
+    pub fn start_repair(
+        self: &Arc<Self>,
+        name: String,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<UpdateJob, String> {
+        let Some(chart) = self
+            .charts
+            .charts_info
+            .get(&name)
+            .filter(|info| info.chart.chart_type() == ChartType::Line)
+            .map(|info| info.chart.clone())
+        else {
+            return Err(name);
+        };
+        let dependent = self.dependent_charts(&name);
+        let job = self.create_job([vec![name.clone()], dependent.clone()].concat());
+        tracing::info!(
+            job_id = job.id,
+            charts = ?job.charts,
+            from = ?from,
+            to = ?to,
+            "starting chart repair"
+        );
+
+        let this = self.clone();
+        let job_id = job.id;
+        tokio::spawn(async move {
+            let repaired = this.clone().repair(chart, from, to).await.is_ok();
+            this.record_job_result(job_id, name, repaired);
+            for name in dependent {
+                // dependent charts are not updated from data that failed to be repaired
+                let updated = repaired && {
+                    let chart = this.charts.charts_info[&name].chart.clone();
+                    this.clone().update(chart, false).await.is_ok()
+                };
+                this.record_job_result(job_id, name, updated);
+            }
+        });
+        Ok(job)
+    }
+
+    /// Names of enabled charts computed from `name` directly or through other charts, in update order
+    fn dependent_charts(&self, name: &str) -> Vec<String> {
+        let mut affected = vec![name];
+        let mut dependent = vec![];
+        for chart_name in &self.charts.charts_order {
+            let chart = &self.charts.charts_info[chart_name].chart;
+            if chart
+                .dependencies()
+                .iter()
+                .any(|parent| affected.contains(parent))
+            {
+                affected.push(chart_name.as_str());
+                dependent.push(chart_name.clone());
+            }
+        }
+        dependent
+    }
+
+    fn create_job(&self, charts: Vec<String>) -> UpdateJob {
+        let job = UpdateJob {
+            id: self.next_job_id.fetch_add(1, Ordering::Relaxed),
+            charts,
+            updated_charts: vec![],
+            failed_charts: vec![],
+        };
+        let mut jobs = self.jobs.lock().expect("jobs mutex is poisoned");
+        jobs.insert(job.id, job.clone());
+        let mut finished: Vec<_> = jobs
+            .values()
+            .filter(|job| job.status() != UpdateJobStatus::Running)
+            .map(|job| job.id)
+            .collect();
+        if finished.len() > MAX_FINISHED_JOBS {
+            finished.sort_unstable();
+            for id in &finished[..finished.len() - MAX_FINISHED_JOBS] {
+                jobs.remove(id);
+            }
+        }
+        job
+    }
+
+    fn record_job_result(&self, job_id: u64, name: String, updated: bool) {
+        let mut jobs = self.jobs.lock().expect("jobs mutex is poisoned");
+        if let Some(job) = jobs.get_mut(&job_id) {
+            if updated {
+                job.updated_charts.push(name);
+            } else {
+                job.failed_charts.push(name);
+            }
+        }
+    }
+
+    pub fn get_update_job(&self, id: u64) -> Option<UpdateJob> {
+        self.jobs
+            .lock()
+            .expect("jobs mutex is poisoned")
+            .get(&id)
+            .cloned()
+    }
+
+    /// Updates that failed with retryable error are retried with backoff. Neither
+    /// the permit nor the update mutex of the chart is held while waiting for retry
+    async fn update(self: Arc<Self>, chart: ArcChart, force_full: bool) -> Result<(), UpdateError> {
+        let this = &self;
+        let chart = &chart;
+        let result = stats::with_retries(&Default::default(), chart.name(), || async move {
+            let _permit = this
+                .update_semaphore
+                .acquire()
+                .await
+                .expect("failed to acquire permit");
+            this.update_chart(chart.clone(), force_full).await
+        })
+        .await;
+        self.save_update_run(chart, &result).await;
+        result
+    }
+
+    /// Persists the outcome of the update run and reports it to alerter
+    async fn save_update_run(&self, chart: &ArcChart, result: &Result<(), UpdateError>) {
+        let failed_updates =
+            match stats::save_update_run(&self.db, chart.name(), result.is_ok()).await {
+                Ok(failed_updates) => failed_updates,
+                Err(err) => {
+                    tracing::error!(chart = chart.name(), "failed to save update run: {}", err);
+                    return;
+                }
+            };
+        if let Some(alerter) = &self.alerter {
+            alerter.on_update(chart.name(), failed_updates, result);
+        }
+    }
+
+    async fn repair(
+        self: Arc<Self>,
+        chart: ArcChart,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<(), UpdateError> {
+        let _permit = self
+            .update_semaphore
+            .acquire()
+            .await
+            .expect("failed to acquire permit");
+        let result = stats::metrics::with_chain_id(
+            &self.chain_id,
+            chart.repair_with_mutex(&self.db, &self.blockscout, from, to),
+        )
+        .await;
+        match &result {
+            Ok(()) => {
+                tracing::info!(chart = chart.name(), "successfully repaired chart");
+                if let Some(read_cache) = &self.read_cache {
+                    read_cache.invalidate(self.affected_charts(&chart));
+                }
+            }
+            Err(err) => tracing::error!(
+                chart = chart.name(),
+                "error during repairing chart: {}",
+                err
+            ),
+        }
+        result
     }
 
-    async fn update(self: Arc<Self>, chart: ArcChart, force_full: bool) {
+    /// Updates the chart bypassing the limit of concurrent updates
+    async fn update_chart(&self, chart: ArcChart, force_full: bool) -> Result<(), UpdateError> {
         tracing::info!(chart = chart.name(), "updating chart");
+        let start = Instant::now();
         let result = {
             let _timer = stats::metrics::CHART_UPDATE_TIME
-                .with_label_values(&[chart.name()])
+                .with_label_values(&[&self.chain_id, chart.name()])
                 .start_timer();
-            chart
-                .update_with_mutex(&self.db, &self.blockscout, force_full)
-                .await
+            stats::metrics::with_chain_id(
+                &self.chain_id,
+                chart.update_with_mutex(
+                    &self.db,
+                    &self.blockscout,
+                    self.update_parameters(force_full),
+                ),
+            )
+            .await
         };
-        if let Err(err) = result {
+        if let Err(err) = &result {
             stats::metrics::UPDATE_ERRORS
-                .with_label_values(&[chart.name()])
+                .with_label_values(&[&self.chain_id, chart.name()])
                 .inc();
-            tracing::error!(chart = chart.name(), "error during updating chart: {}", err);
+            tracing::error!(
+                chart = chart.name(),
+                elapsed = ?start.elapsed(),
+                "error during updating chart: {}",
+                err
+            );
         } else {
-            tracing::info!(chart = chart.name(), "successfully updated chart");
+            stats::metrics::CHART_LAST_SUCCESS_TIME
+                .with_label_values(&[&self.chain_id, chart.name()])
+                .set(chrono::Utc::now().timestamp());
+            tracing::info!(
+                chart = chart.name(),
+                elapsed = ?start.elapsed(),
+                "successfully updated chart"
+            );
+        }
+        if let (Some(read_cache), Ok(())) = (&self.read_cache, &result) {
+            read_cache.invalidate(self.affected_charts(&chart));
         }
+        result
     }
 
-    async fn run_cron(self: Arc<Self>, chart: ArcChart, schedule: Schedule) {
+    /// Updates stats of `tokens` on start if requested and then by `schedule`
+    pub async fn run_token_stats_updater(
+        self: Arc<Self>,
+        tokens: TokenSelection,
+        schedule: Schedule,
+        jitter: Duration,
+        force_update_on_start: Option<bool>,
+    ) {
+        if let Some(force_full) = force_update_on_start {
+            let _ = self.update_token_stats(&tokens, force_full).await;
+        }
         loop {
-            let sleep_duration = time_till_next_call(&schedule);
+            let sleep_duration = time_till_next_call(&schedule, jitter);
+            tracing::info!(
+                "scheduled next run of token stats update in {:?}",
+                sleep_duration
+            );
+            tokio::time::sleep(sleep_duration).await;
+            let _ = self.update_token_stats(&tokens, false).await;
+        }
+    }
+
+    async fn update_token_stats(
+        &self,
+        tokens: &TokenSelection,
+        force_full: bool,
+    ) -> Result<(), UpdateError> {
+        tracing::info!("updating token stats");
+        let start = Instant::now();
+        let result = {
+            let _timer = stats::metrics::CHART_UPDATE_TIME
+                .with_label_values(&[&self.chain_id, TOKEN_STATS_ID])
+                .start_timer();
+            async {
+                let tokens = {
+                    let _permit = self
+                        .update_semaphore
+                        .acquire()
+                        .await
+                        .expect("failed to acquire permit");
+                    stats::with_retries(&Default::default(), TOKEN_STATS_ID, || {
+                        stats::token_stats::selected_tokens(&self.blockscout, tokens)
+                    })
+                    .await?
+                };
+                // permit is taken per token, so charts updates are not blocked
+                // by stats of all tokens at once
+                let mut rows = 0;
+                for token in &tokens {
+                    let _permit = self
+                        .update_semaphore
+                        .acquire()
+                        .await
+                        .expect("failed to acquire permit");
+                    rows += stats::with_retries(&Default::default(), TOKEN_STATS_ID, || {
+                        stats::token_stats::update_token_stats(
+                            &self.db,
+                            &self.blockscout,
+                            token,
+                            self.update_parameters(force_full),
+                        )
+                    })
+                    .await?;
+                }
+                Ok::<_, UpdateError>(rows)
+            }
+            .await
+        };
+        match &result {
+            Ok(rows) => {
+                stats::metrics::CHART_ROWS_WRITTEN
+                    .with_label_values(&[&self.chain_id, TOKEN_STATS_ID])
+                    .inc_by(*rows);
+                stats::metrics::CHART_LAST_SUCCESS_TIME
+                    .with_label_values(&[&self.chain_id, TOKEN_STATS_ID])
+                    .set(chrono::Utc::now().timestamp());
+                tracing::info!(elapsed = ?start.elapsed(), "successfully updated token stats");
+            }
+            Err(err) => {
+                stats::metrics::UPDATE_ERRORS
+                    .with_label_values(&[&self.chain_id, TOKEN_STATS_ID])
+                    .inc();
+                tracing::error!(
+                    elapsed = ?start.elapsed(),
+                    "error during updating token stats: {}",
+                    err
+                );
+            }
+        }
+        result.map(|_| ())
+    }
+
+    /// Starts refreshing `counters` on every new block in blockscout, which is checked every `poll_interval`.
+    /// Only counters with incremental query can be refreshed. Returns name of the counter
+    /// that isn't enabled or can't be refreshed, if any
+    pub fn start_realtime_counters(
+        self: &Arc<Self>,
+        counters: &[String],
+        poll_interval: Duration,
+    ) -> Result<(), String> {
+        let counters = counters
+            .iter()
+            .map(|name| {
+                self.charts
+                    .charts_info
+                    .get(name)
+                    .filter(|info| info.chart.chart_type() == ChartType::Counter)
+                    .filter(|info| info.chart.increment_sql().is_some())
+                    .map(|info| info.chart.clone())
+                    .ok_or_else(|| name.clone())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let this = self.clone();
+        tokio::spawn(async move { this.run_realtime_counters(counters, poll_interval).await });
+        Ok(())
+    }
+
+    async fn run_realtime_counters(
+        self: Arc<Self>,
+        counters: Vec<ArcChart>,
+        poll_interval: Duration,
+    ) {
+        let mut cursors: HashMap<String, RealtimeCursor> = HashMap::new();
+        loop {
+            tokio::time::sleep(poll_interval).await;
+            let max_block = match stats::get_max_block_blockscout(&self.blockscout).await {
+                Ok(Some(max_block)) => max_block,
+                Ok(None) => continue,
+                Err(err) => {
+                    tracing::warn!("failed to get last block for realtime counters: {}", err);
+                    continue;
+                }
+            };
+            for chart in &counters {
+                let cursor = cursors.get(chart.name()).copied();
+                if cursor.map(|cursor| cursor.block) == Some(max_block) {
+                    continue;
+                }
+                let _permit = self
+                    .update_semaphore
+                    .acquire()
+                    .await
+                    .expect("failed to acquire permit");
+                let result = stats::metrics::with_chain_id(
+                    &self.chain_id,
+                    stats::refresh_counter(
+                        &self.db,
+                        &self.blockscout,
+                        chart.as_ref(),
+                        cursor,
+                        max_block,
+                    ),
+                )
+                .await;
+                match result {
+                    Ok(cursor) => {
+                        cursors.insert(chart.name().to_string(), cursor);
+                    }
+                    Err(err) => {
+                        tracing::warn!(chart = chart.name(), "failed to refresh counter: {}", err);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Refreshes enabled market charts every `poll_interval`, so today's price
+    /// doesn't wait for the daily schedule
+    pub fn start_market_data_updates(self: &Arc<Self>, poll_interval: Duration) {
+        let charts: Vec<_> = stats::market::CHARTS
+            .iter()
+            .filter_map(|name| self.charts.charts_info.get(*name))
+            .map(|info| info.chart.clone())
+            .collect();
+        if charts.is_empty() {
+            return;
+        }
+        let this = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(poll_interval).await;
+                for chart in &charts {
+                    let _ = this.clone().update(chart.clone(), false).await;
+                }
+            }
+        });
+    }
+
+    async fn run_cron(self: Arc<Self>, chart: ArcChart, schedule: Schedule, jitter: Duration) {
+        loop {
+            let sleep_duration = time_till_next_call(&schedule, jitter);
             tracing::info!(
                 chart = chart.name(),
                 "scheduled next run of chart update in {:?}",
                 sleep_duration
             );
             tokio::time::sleep(sleep_duration).await;
-            self.clone().update(chart.clone(), false).await;
+            let _ = self.clone().update(chart.clone(), false).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::toml_config::Config;
+
+    #[tokio::test]
+    async fn realtime_counters_must_be_enabled() {
+        let config: Config = toml::from_str(
+            r#"
+            [[counters]]
+            id = "totalBlocks"
+            title = "Total blocks"
+            description = "Number of all blocks"
+
+            [[counters]]
+            id = "averageBlockTime"
+            title = "Average block time"
+            description = "Average time taken in seconds for a block to be included in the blockchain"
+
+            [lines]
+            sections = []
+            "#,
+        )
+        .unwrap();
+        let db = Arc::new(DatabaseConnection::Disconnected);
+        let charts = Arc::new(Charts::new(config, None, None, false).unwrap());
+        let service = Arc::new(UpdateService::new(db.clone(), db, charts, 1).await.unwrap());
+        let result =
+            service.start_realtime_counters(&["totalTxns".to_string()], Duration::from_secs(5));
+        assert_eq!(result, Err("totalTxns".to_string()));
+        // can't be refreshed by incremental query
+        let result = service
+            .start_realtime_counters(&["averageBlockTime".to_string()], Duration::from_secs(5));
+        assert_eq!(result, Err("averageBlockTime".to_string()));
+        assert_eq!(
+            service.start_realtime_counters(&[], Duration::from_secs(5)),
+            Ok(())
+        );
+    }
+
+    #[tokio::test]
+    async fn related_charts_are_found() {
+        let config: Config = toml::from_str(
+            r#"
+            counters = []
+
+            [[lines.sections]]
+            id = "transactions"
+            title = "Transactions"
+
+            [[lines.sections.charts]]
+            id = "newTxns"
+            title = "New transactions"
+            description = "Number of new transactions"
+
+            [[lines.sections.charts]]
+            id = "txnsGrowth"
+            title = "Transactions growth"
+            description = "Cumulative number of transactions"
+            "#,
+        )
+        .unwrap();
+        let charts = Arc::new(Charts::new(config, None, None, false).unwrap());
+        let db = Arc::new(DatabaseConnection::Disconnected);
+        let service = UpdateService::new(db.clone(), db, charts.clone(), 1)
+            .await
+            .unwrap();
+        let affected = |name: &str| {
+            let chart = &charts.charts_info[name].chart;
+            let mut affected = service.affected_charts(chart);
+            affected.sort();
+            affected.join(",")
+        };
+        assert_eq!(affected("newTxns"), "newTxns,newTxnsMa30,newTxnsMa7");
+        assert_eq!(
+            affected("txnsGrowth"),
+            "newTxns,newTxnsMa30,newTxnsMa7,txnsGrowth"
+        );
+        assert_eq!(service.dependent_charts("newTxns"), ["txnsGrowth"]);
+        assert!(service.dependent_charts("txnsGrowth").is_empty());
+    }
+
+    #[test]
+    fn random_jitter_is_bounded() {
+        assert_eq!(random_jitter(Duration::ZERO), Duration::ZERO);
+        let max = Duration::from_secs(10);
+        for _ in 0..100 {
+            assert!(random_jitter(max) <= max);
         }
     }
+
+    #[tokio::test]
+    async fn finished_jobs_are_evicted() {
+        let config: Config = toml::from_str(
+            r#"
+            counters = []
+
+            [lines]
+            sections = []
+            "#,
+        )
+        .unwrap();
+        let charts = Arc::new(Charts::new(config, None, None, false).unwrap());
+        let db = Arc::new(DatabaseConnection::Disconnected);
+        let service = UpdateService::new(db.clone(), db, charts, 1)
+            .await
+            .unwrap();
+
+        let running = service.create_job(vec!["newTxns".to_string()]);
+        let finished: Vec<_> = (0..MAX_FINISHED_JOBS + 10)
+            .map(|_| service.create_job(vec![]))
+            .collect();
+        assert_eq!(
+            service.get_update_job(running.id).map(|job| job.status()),
+            Some(UpdateJobStatus::Running)
+        );
+        assert_eq!(service.get_update_job(finished[9].id), None);
+        assert_eq!(
+            service.get_update_job(finished[10].id),
+            Some(finished[10].clone())
+        );
+        assert_eq!(
+            service.jobs.lock().unwrap().len(),
+            MAX_FINISHED_JOBS + 1
+        );
+    }
 }
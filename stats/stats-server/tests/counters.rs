@@ -3,7 +3,7 @@ use blockscout_service_launcher::{
     test_server::{get_test_server_settings, init_server, send_get_request},
 };
 use stats::tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
-use stats_proto::blockscout::stats::v1::Counters;
+use stats_proto::blockscout::stats::v1::{ChartsStatus, Counters};
 use stats_server::{stats, Settings};
 use std::{collections::HashSet, path::PathBuf, str::FromStr};
 
@@ -30,6 +30,7 @@ async fn test_counters_ok() {
     for counter in counters.counters.iter() {
         assert!(!counter.description.is_empty());
         assert!(!counter.title.is_empty());
+        assert!(counter.last_updated_at.is_some());
     }
     let counter_names: HashSet<_> = counters.counters.iter().map(|c| c.id.as_str()).collect();
     let expected_counter_names: HashSet<_> = [
@@ -37,6 +38,7 @@ async fn test_counters_ok() {
         "totalAddresses",
         "averageBlockTime",
         "completedTxns",
+        "txnsFee24h",
         "totalTxns",
         "totalAccounts",
         "totalTokens",
@@ -51,4 +53,18 @@ async fn test_counters_ok() {
     .collect();
 
     assert_eq!(counter_names, expected_counter_names);
+
+    let status: ChartsStatus = send_get_request(&base, "/api/v1/charts/status").await;
+    for counter in expected_counter_names {
+        let chart = status
+            .charts
+            .iter()
+            .find(|chart| chart.id == counter)
+            .unwrap_or_else(|| panic!("status of {counter} is missing"));
+        assert!(chart.last_updated_at.is_some());
+        assert!(chart.last_attempt_at.is_some());
+        assert_eq!(chart.last_error, None);
+        assert!(chart.last_duration_ms.is_some());
+        assert!(chart.last_rows_written.is_some());
+    }
 }
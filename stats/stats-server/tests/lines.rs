@@ -1,6 +1,6 @@
 use blockscout_service_launcher::{
     launcher::ConfigSettings,
-    test_server::{get_test_server_settings, init_server, send_get_request},
+    test_server::{get_test_server_settings, init_server, send_get_request, send_post_request},
 };
 use stats::tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
 use stats_server::{stats, Settings};
@@ -41,23 +41,40 @@ async fn test_lines_ok() {
         "contracts",
     ];
     assert_eq!(sections, expected_sections, "wrong sections response");
+    for section in &line_charts.sections {
+        for chart in &section.charts {
+            assert_eq!(
+                chart.section.as_ref(),
+                Some(&section.id),
+                "wrong section of chart '{}'",
+                chart.id
+            );
+        }
+    }
 
     for line_name in [
         "accountsGrowth",
         "activeAccounts",
         "averageBlockSize",
+        "averageBlockTimeHistory",
         "averageBlockRewards",
+        "blockRewards",
         "newAccounts",
         "averageGasLimit",
         "averageGasPrice",
         "averageTxnFee",
+        "gasPricePercentiles",
+        "gasUsed",
         "gasUsedGrowth",
         // "nativeCoinHoldersGrowth",
         // "nativeCoinSupply",
         // "newNativeCoinHolders",
         "newBlocks",
         "newNativeCoinTransfers",
+        "nativeCoinTransfersVolume",
+        "newTokens",
         "newTxns",
+        "newTxnsByType",
         "txnsFee",
         "txnsGrowth",
         "txnsSuccessRate",
@@ -68,9 +85,20 @@ async fn test_lines_ok() {
     ] {
         let chart: serde_json::Value =
             send_get_request(&base, &format!("/api/v1/lines/{line_name}")).await;
+        let chart = chart.as_object().expect("response has to be json object");
+        let info = chart
+            .get("info")
+            .expect("response doesn't have 'info' field");
+        assert_eq!(info["id"], line_name, "wrong info of chart '{line_name}'");
+        assert!(
+            info["section"].is_string(),
+            "chart '{line_name}' has no section"
+        );
+        assert!(
+            info["lastUpdatedAt"].is_string(),
+            "chart '{line_name}' is not marked as updated"
+        );
         let chart = chart
-            .as_object()
-            .expect("response has to be json object")
             .get("chart")
             .expect("response doesn't have 'chart' field")
             .as_array()
@@ -78,4 +106,124 @@ async fn test_lines_ok() {
 
         assert!(!chart.is_empty(), "chart '{line_name}' is empty");
     }
+
+    let chart: stats_proto::blockscout::stats::v1::LineChart =
+        send_get_request(&base, "/api/v1/lines/newTxnsByType?maxPoints=3").await;
+    let keys: Vec<&str> = chart
+        .series
+        .iter()
+        .map(|series| series.key.as_str())
+        .collect();
+    assert_eq!(
+        keys,
+        [
+            "contractCreation",
+            "tokenTransfer",
+            "contractCall",
+            "coinTransfer",
+            "legacy",
+            "eip1559"
+        ],
+        "wrong series of chart 'newTxnsByType'"
+    );
+    for series in &chart.series {
+        let dates: Vec<_> = series.chart.iter().map(|point| &point.date).collect();
+        let expected: Vec<_> = chart.chart.iter().map(|point| &point.date).collect();
+        assert_eq!(dates, expected, "series {} is not aligned", series.key);
+    }
+
+    for resolution in ["DAY", "WEEK", "MONTH", "YEAR"] {
+        let chart: serde_json::Value = send_get_request(
+            &base,
+            &format!("/api/v1/lines/newTxns?resolution={resolution}"),
+        )
+        .await;
+        let chart = chart
+            .get("chart")
+            .and_then(|chart| chart.as_array())
+            .expect("response doesn't have 'chart' array");
+        assert!(
+            !chart.is_empty(),
+            "chart 'newTxns' is empty for resolution {resolution}"
+        );
+    }
+
+    let chart: serde_json::Value =
+        send_get_request(&base, "/api/v1/lines/newTxns?from=2022-11-01&maxPoints=3").await;
+    let chart = chart
+        .get("chart")
+        .and_then(|chart| chart.as_array())
+        .expect("response doesn't have 'chart' array");
+    assert!(
+        !chart.is_empty() && chart.len() <= 3,
+        "chart 'newTxns' has wrong number of points: {}",
+        chart.len()
+    );
+
+    let full: serde_json::Value = send_get_request(&base, "/api/v1/lines/newTxns").await;
+    assert!(full["nextPageToken"].is_null());
+    let mut pages = vec![];
+    let mut page_token = None;
+    loop {
+        let path = match &page_token {
+            Some(token) => format!("/api/v1/lines/newTxns?pageSize=3&pageToken={token}"),
+            None => "/api/v1/lines/newTxns?pageSize=3".to_string(),
+        };
+        let page: serde_json::Value = send_get_request(&base, &path).await;
+        let chart = page["chart"]
+            .as_array()
+            .expect("response doesn't have 'chart' array");
+        assert!(chart.len() <= 3, "page has too many points");
+        pages.extend(chart.iter().cloned());
+        match page["nextPageToken"].as_str() {
+            Some(token) => page_token = Some(token.to_string()),
+            None => break,
+        }
+    }
+    assert_eq!(&pages, full["chart"].as_array().unwrap());
+
+    let batch: serde_json::Value = send_post_request(
+        &base,
+        "/api/v1/batch",
+        &serde_json::json!({
+            "lines": [
+                {"name": "newTxns", "resolution": "MONTH"},
+                {"name": "txnsGrowth", "maxPoints": 2},
+            ],
+            "counters": ["totalBlocks"],
+        }),
+    )
+    .await;
+    let lines = batch["lines"]
+        .as_array()
+        .expect("response doesn't have 'lines' array");
+    let ids: Vec<_> = lines
+        .iter()
+        .map(|line| line["info"]["id"].as_str())
+        .collect();
+    assert_eq!(
+        ids,
+        [Some("newTxns"), Some("txnsGrowth")],
+        "wrong batch lines"
+    );
+    assert_eq!(lines[0]["resolution"], "MONTH");
+    assert!(lines[1]["chart"].as_array().unwrap().len() <= 2);
+    let counters = batch["counters"]
+        .as_array()
+        .expect("response doesn't have 'counters' array");
+    assert_eq!(counters.len(), 1, "wrong batch counters");
+    assert_eq!(counters[0]["id"], "totalBlocks");
+
+    for line_name in ["newTxnsMa7", "newTxnsMa30"] {
+        let chart: serde_json::Value =
+            send_get_request(&base, &format!("/api/v1/lines/{line_name}")).await;
+        assert_eq!(
+            chart["info"]["id"], line_name,
+            "wrong info of chart '{line_name}'"
+        );
+        assert!(
+            chart["chart"].is_array(),
+            "response doesn't have 'chart' array"
+        );
+    }
 }
@@ -9,7 +9,7 @@ pub struct Model {
     pub id: i32,
     pub chart_id: i32,
     pub date: Date,
-    pub value: String,
+    pub value: Decimal,
     pub created_at: DateTime,
     pub min_blockscout_block: Option<i64>,
 }
@@ -12,6 +12,13 @@ pub struct Model {
     pub name: String,
     pub chart_type: ChartType,
     pub created_at: DateTime,
+    pub last_updated_at: Option<DateTimeWithTimeZone>,
+    pub last_update_attempt_at: Option<DateTimeWithTimeZone>,
+    #[sea_orm(column_type = "Text", nullable)]
+    pub last_update_error: Option<String>,
+    pub last_update_duration_ms: Option<i64>,
+    pub last_update_rows: Option<i64>,
+    pub failed_updates: i32,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
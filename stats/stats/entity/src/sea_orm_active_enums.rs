@@ -10,3 +10,12 @@ pub enum ChartType {
     #[sea_orm(string_value = "LINE")]
     Line,
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, DeriveActiveEnum)]
+#[sea_orm(rs_type = "String", db_type = "Enum", enum_name = "token_stat_kind")]
+pub enum TokenStatKind {
+    #[sea_orm(string_value = "TRANSFERS")]
+    Transfers,
+    #[sea_orm(string_value = "HOLDERS")]
+    Holders,
+}
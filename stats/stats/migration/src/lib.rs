@@ -3,6 +3,12 @@ use sea_orm_migration::sea_orm::{ConnectionTrait, Statement, TransactionTrait};
 
 mod m20220101_000001_init;
 mod m20230814_105206_drop_zero_timestamp;
+mod m20230901_120000_add_charts_last_updated_at;
+mod m20230915_120000_chart_data_value_numeric;
+mod m20230920_120000_add_charts_update_status;
+mod m20230925_120000_add_token_stats;
+mod m20231001_120000_add_charts_failed_updates;
+mod m20231005_120000_add_chart_series_data;
 
 pub struct Migrator;
 
@@ -12,6 +18,12 @@ impl MigratorTrait for Migrator {
         vec![
             Box::new(m20220101_000001_init::Migration),
             Box::new(m20230814_105206_drop_zero_timestamp::Migration),
+            Box::new(m20230901_120000_add_charts_last_updated_at::Migration),
+            Box::new(m20230915_120000_chart_data_value_numeric::Migration),
+            Box::new(m20230920_120000_add_charts_update_status::Migration),
+            Box::new(m20230925_120000_add_token_stats::Migration),
+            Box::new(m20231001_120000_add_charts_failed_updates::Migration),
+            Box::new(m20231005_120000_add_chart_series_data::Migration),
         ]
     }
 }
@@ -0,0 +1,17 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"ALTER TABLE "charts" ADD COLUMN "last_updated_at" timestamptz"#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"ALTER TABLE "charts" DROP COLUMN "last_updated_at""#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,19 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql =
+            r#"ALTER TABLE "chart_data" ALTER COLUMN "value" TYPE numeric USING "value"::numeric"#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql =
+            r#"ALTER TABLE "chart_data" ALTER COLUMN "value" TYPE varchar(64) USING "value"::text"#;
+        crate::from_sql(manager, sql).await
+    }
+}
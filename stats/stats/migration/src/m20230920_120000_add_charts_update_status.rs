@@ -0,0 +1,33 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+ALTER TABLE "charts" ADD COLUMN "last_update_attempt_at" timestamptz;
+
+ALTER TABLE "charts" ADD COLUMN "last_update_error" text;
+
+ALTER TABLE "charts" ADD COLUMN "last_update_duration_ms" bigint;
+
+ALTER TABLE "charts" ADD COLUMN "last_update_rows" bigint;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+ALTER TABLE "charts" DROP COLUMN "last_update_rows";
+
+ALTER TABLE "charts" DROP COLUMN "last_update_duration_ms";
+
+ALTER TABLE "charts" DROP COLUMN "last_update_error";
+
+ALTER TABLE "charts" DROP COLUMN "last_update_attempt_at";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+CREATE TYPE "token_stat_kind" AS ENUM (
+  'TRANSFERS',
+  'HOLDERS'
+);
+
+CREATE TABLE "token_stats" (
+  "id" INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+  "token_address" bytea NOT NULL,
+  "kind" token_stat_kind NOT NULL,
+  "date" date NOT NULL,
+  "value" numeric NOT NULL,
+  "created_at" timestamp NOT NULL DEFAULT (now())
+);
+
+CREATE UNIQUE INDEX ON "token_stats" ("token_address", "kind", "date");
+
+COMMENT ON TABLE "token_stats" IS 'Table contains daily stats of tracked tokens';
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+DROP TABLE "token_stats";
+
+DROP TYPE "token_stat_kind";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
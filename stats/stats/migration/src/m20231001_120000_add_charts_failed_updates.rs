@@ -0,0 +1,21 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+ALTER TABLE "charts" ADD COLUMN "failed_updates" integer NOT NULL DEFAULT 0;
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+ALTER TABLE "charts" DROP COLUMN "failed_updates";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
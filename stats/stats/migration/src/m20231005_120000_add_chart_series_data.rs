@@ -0,0 +1,35 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+CREATE TABLE "chart_series_data" (
+  "id" INT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+  "chart_id" int NOT NULL,
+  "series" varchar(64) NOT NULL,
+  "date" date NOT NULL,
+  "value" numeric NOT NULL,
+  "created_at" timestamp NOT NULL DEFAULT (now()),
+  "min_blockscout_block" bigint
+);
+
+CREATE UNIQUE INDEX ON "chart_series_data" ("chart_id", "series", "date");
+
+COMMENT ON TABLE "chart_series_data" IS 'Table contains data points of series of charts';
+
+ALTER TABLE "chart_series_data" ADD FOREIGN KEY ("chart_id") REFERENCES "charts" ("id");
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let sql = r#"
+DROP TABLE "chart_series_data";
+        "#;
+        crate::from_sql(manager, sql).await
+    }
+}
@@ -1,4 +1,8 @@
-use std::{future::Future, sync::Arc};
+use std::{
+    future::Future,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::sync::Mutex;
 
 #[derive(Default, Debug)]
@@ -48,6 +52,88 @@ impl<T> Clone for Cache<T> {
     }
 }
 
+/// Data is considered fresh enough to be reused during this period
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(30 * 60);
+
+#[derive(Debug)]
+struct SharedCacheEntry<K, T> {
+    key: K,
+    data: T,
+    fetched_at: Instant,
+}
+
+/// Result of blockscout query shared by several charts.
+///
+/// Like [`Cache`], data fetched by one holder is reused by others, but only
+/// if it was requested with the same `key` (e.g. date of the last stored point)
+/// and is not older than `max_age`, so charts updated in one cycle
+/// read blockscout database once
+#[derive(Debug)]
+pub struct SharedCache<K, T> {
+    data: Arc<Mutex<CacheData<SharedCacheEntry<K, T>>>>,
+    last_version: u64,
+    max_age: Duration,
+}
+
+impl<K, T> SharedCache<K, T> {
+    pub fn new(max_age: Duration) -> Self {
+        Self {
+            data: Arc::new(Mutex::new(CacheData {
+                data: None,
+                version: 0,
+            })),
+            last_version: 0,
+            max_age,
+        }
+    }
+}
+
+impl<K, T> Default for SharedCache<K, T> {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_AGE)
+    }
+}
+
+impl<K: PartialEq, T: Clone> SharedCache<K, T> {
+    pub async fn get_or_update<E, F: Future<Output = Result<T, E>>>(
+        &mut self,
+        key: K,
+        updater: F,
+    ) -> Result<T, E> {
+        let mut cache = self.data.lock().await;
+        let data = match cache.data.as_ref() {
+            Some(entry)
+                if cache.version > self.last_version
+                    && entry.key == key
+                    && entry.fetched_at.elapsed() <= self.max_age =>
+            {
+                entry.data.clone()
+            }
+            _ => {
+                let data = updater.await?;
+                cache.update(SharedCacheEntry {
+                    key,
+                    data: data.clone(),
+                    fetched_at: Instant::now(),
+                });
+                data
+            }
+        };
+        self.last_version = cache.version;
+        Ok(data)
+    }
+}
+
+impl<K, T> Clone for SharedCache<K, T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            last_version: 0,
+            max_age: self.max_age,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,4 +177,35 @@ mod tests {
         );
         assert_eq!(Ok(5), baz.get_or_update(async move { value(5) }).await);
     }
+
+    #[tokio::test]
+    async fn shared_cache_works() {
+        let mut foo: SharedCache<u32, u32> = SharedCache::default();
+        let mut bar = foo.clone();
+
+        assert_eq!(Ok(1), foo.get_or_update(1, async move { value(1) }).await);
+        assert_eq!(
+            Ok(1),
+            bar.get_or_update(1, async move { assert_not_called() })
+                .await
+        );
+        // cached data of other key is not reused
+        assert_eq!(Ok(2), bar.get_or_update(2, async move { value(2) }).await);
+        // holder doesn't reuse data fetched by itself
+        assert_eq!(Ok(3), bar.get_or_update(2, async move { value(3) }).await);
+        assert_eq!(
+            Ok(3),
+            foo.get_or_update(2, async move { assert_not_called() })
+                .await
+        );
+
+        let mut outdated: SharedCache<u32, u32> = SharedCache::new(Duration::ZERO);
+        let mut baz = outdated.clone();
+        assert_eq!(
+            Ok(4),
+            outdated.get_or_update(1, async move { value(4) }).await
+        );
+        std::thread::sleep(Duration::from_millis(1));
+        assert_eq!(Ok(5), baz.get_or_update(1, async move { value(5) }).await);
+    }
 }
@@ -1,35 +1,178 @@
-use super::mutex::get_global_update_mutex;
-use crate::ReadError;
+use super::{
+    moving_average::update_moving_averages,
+    mutex::get_global_update_mutex,
+    periods::{create_periods, repair_periods, update_periods},
+    retry::is_transient,
+    updater::ChartPartialUpdater,
+};
+use crate::{metrics, ReadError};
 use async_trait::async_trait;
+use chrono::{Datelike, Duration, NaiveDate};
 use entity::{charts, sea_orm_active_enums::ChartType};
-use sea_orm::{prelude::*, sea_query, FromQueryResult, QuerySelect, Set};
+use sea_orm::{prelude::*, sea_query, DbBackend, FromQueryResult, QuerySelect, Set, Statement};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration as StdDuration, Instant};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum UpdateError {
     #[error("blockscout database error: {0}")]
     BlockscoutDB(DbErr),
+    #[error("blockscout database is unavailable: {0}")]
+    BlockscoutUnavailable(DbErr),
     #[error("stats database error: {0}")]
     StatsDB(DbErr),
     #[error("chart {0} not found")]
     NotFound(String),
+    #[error("chart {0} can't recompute a date range")]
+    RepairUnsupported(String),
+    #[error("external source error: {0}")]
+    External(String),
     #[error("internal error: {0}")]
     Internal(String),
 }
 
+impl UpdateError {
+    /// Error of blockscout database query, retryable if it's transient
+    pub fn blockscout(err: DbErr) -> Self {
+        if is_transient(&err) {
+            UpdateError::BlockscoutUnavailable(err)
+        } else {
+            UpdateError::BlockscoutDB(err)
+        }
+    }
+
+    /// Whether the update can succeed if it is simply run again
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, UpdateError::BlockscoutUnavailable(_))
+    }
+}
+
 impl From<ReadError> for UpdateError {
     fn from(read: ReadError) -> Self {
         match read {
             ReadError::DB(db) => UpdateError::StatsDB(db),
             ReadError::NotFound(err) => UpdateError::NotFound(err),
+            ReadError::Internal(err) => UpdateError::Internal(err),
         }
     }
 }
 
+/// Parameters of a chart update, shared by the chart and the charts it depends on
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UpdateParameters {
+    /// Recompute all values instead of continuing from the stored ones
+    pub force_full: bool,
+    /// Number of last stored days recomputed by partial update,
+    /// so values of partially indexed days or reorged blocks are replaced
+    pub recompute_window_days: u64,
+}
+
+impl Default for UpdateParameters {
+    fn default() -> Self {
+        Self {
+            force_full: false,
+            recompute_window_days: 1,
+        }
+    }
+}
+
+impl UpdateParameters {
+    pub fn with_force_full(force_full: bool) -> Self {
+        Self {
+            force_full,
+            ..Default::default()
+        }
+    }
+}
+
+/// How dates without data are shown in line chart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum MissingDatePolicy {
     FillZero,
     FillPrevious,
+    LeaveGap,
+}
+
+/// How daily points of line chart are combined into one point
+/// of bigger resolution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregationPolicy {
+    Sum,
+    Average,
+    Last,
+}
+
+/// Unit of stored values of chart denominated in wei. Values are divided
+/// in the query to blockscout, so stored data and api responses are in this unit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    /// Native coin of the chain, `10^18` wei
+    NativeCoin,
+    /// `10^9` wei
+    Gwei,
+}
+
+impl Unit {
+    /// Number of wei in the unit, raw values are divided by it
+    pub const fn wei(&self) -> i64 {
+        match self {
+            Unit::NativeCoin => 1_000_000_000_000_000_000,
+            Unit::Gwei => 1_000_000_000,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResolutionKind {
+    #[default]
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl ResolutionKind {
+    /// First day of period containing `date`. Weeks start on monday
+    pub fn period_start(&self, date: NaiveDate) -> NaiveDate {
+        match self {
+            ResolutionKind::Day => date,
+            ResolutionKind::Week => {
+                date - Duration::days(date.weekday().num_days_from_monday().into())
+            }
+            ResolutionKind::Month => date.with_day(1).expect("first day of month exists"),
+            ResolutionKind::Year => date.with_ordinal(1).expect("first day of year exists"),
+        }
+    }
+
+    /// Last day of period containing `date`
+    pub fn period_end(&self, date: NaiveDate) -> NaiveDate {
+        let start = self.period_start(date);
+        let next_start = match self {
+            ResolutionKind::Day => start + Duration::days(1),
+            ResolutionKind::Week => start + Duration::days(7),
+            ResolutionKind::Month => match start.month() {
+                12 => NaiveDate::from_ymd_opt(start.year() + 1, 1, 1),
+                month => NaiveDate::from_ymd_opt(start.year(), month + 1, 1),
+            }
+            .expect("first day of month exists"),
+            ResolutionKind::Year => {
+                NaiveDate::from_ymd_opt(start.year() + 1, 1, 1).expect("first day of year exists")
+            }
+        };
+        next_start - Duration::days(1)
+    }
+
+    /// Field of postgres `DATE_TRUNC` giving the start of period, the same as [`Self::period_start`]
+    pub(crate) fn date_trunc_field(&self) -> &'static str {
+        match self {
+            ResolutionKind::Day => "day",
+            ResolutionKind::Week => "week",
+            ResolutionKind::Month => "month",
+            ResolutionKind::Year => "year",
+        }
+    }
 }
 
 #[async_trait]
@@ -39,32 +182,113 @@ pub trait Chart: Sync {
     fn missing_date_policy(&self) -> MissingDatePolicy {
         MissingDatePolicy::FillZero
     }
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        match self.missing_date_policy() {
+            MissingDatePolicy::FillZero | MissingDatePolicy::LeaveGap => AggregationPolicy::Sum,
+            MissingDatePolicy::FillPrevious => AggregationPolicy::Last,
+        }
+    }
+    /// Unit of stored values, `None` if they are not amounts of wei
+    fn unit(&self) -> Option<Unit> {
+        None
+    }
     fn relevant_or_zero(&self) -> bool {
         false
     }
     fn drop_last_point(&self) -> bool {
         self.chart_type() == ChartType::Line
     }
+    /// Names of charts whose stored data is used to compute this chart.
+    /// They have to be updated before the chart
+    fn dependencies(&self) -> Vec<&str> {
+        vec![]
+    }
+    /// Windows (in days) of moving averages derived from the chart after every update.
+    /// Each one is stored as separate line chart named by [`moving_average_name`]
+    ///
+    /// [`moving_average_name`]: super::moving_average_name
+    fn moving_average_windows(&self) -> &[u32] {
+        &[]
+    }
+    /// Keys of series of the line chart. Their points are written by the update of the chart
+    /// with [`insert_series_data`] and returned along with points of the chart
+    ///
+    /// [`insert_series_data`]: super::insert_series_data
+    fn series(&self) -> &[&str] {
+        &[]
+    }
+    /// Query of chart values in bigger resolutions, for charts whose daily points can't be
+    /// combined into them, like numbers of distinct accounts. `$1` is the `date_trunc` field
+    /// of the resolution, points are computed for dates in `[$2, $3]` (both nullable) and dated
+    /// by the first day of their period. Each resolution is stored as separate line chart
+    /// named by [`resolution_name`]
+    ///
+    /// [`resolution_name`]: super::resolution_name
+    fn period_sql(&self) -> Option<&str> {
+        None
+    }
+    /// Query adding blocks in `($1, $2]` to the counter, so it can be refreshed on new
+    /// blocks without recomputing it. Returns `date` of the last added block and the
+    /// `value` to add. Has to be cheap: it runs on every new block
+    fn increment_sql(&self) -> Option<&str> {
+        None
+    }
 
     async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
-        create_chart(db, self.name().into(), self.chart_type()).await
+        create_chart(db, self.name().into(), self.chart_type()).await?;
+        create_periods(db, self).await
     }
 
     async fn update(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError>;
 
+    /// Charts computed from blockscout by date return themselves here,
+    /// so they are repaired with [`ChartPartialUpdater::repair_with_values`]
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        None
+    }
+
+    /// Recomputes and overwrites stored values of days in `[from, to]`, other days are kept.
+    /// Only charts with [`Self::partial_updater`] support it
+    async fn repair(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<(), UpdateError> {
+        match self.partial_updater() {
+            Some(updater) => updater.repair_with_values(db, blockscout, from, to).await,
+            None => Err(UpdateError::RepairUnsupported(self.name().into())),
+        }
+    }
+
+    async fn repair_with_mutex(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<(), UpdateError> {
+        let mutex = get_global_update_mutex(&metrics::chain_id(), self.name()).await;
+        let _permit = mutex.lock().await;
+        self.repair(db, blockscout, from, to).await?;
+        repair_periods(db, blockscout, self, from, to).await?;
+        update_moving_averages(db, self).await
+    }
+
     async fn update_with_mutex(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
         let name = self.name();
-        let mutex = get_global_update_mutex(name).await;
+        let mutex = get_global_update_mutex(&metrics::chain_id(), name).await;
         let _permit = {
             match mutex.try_lock() {
                 Ok(v) => v,
@@ -77,10 +301,37 @@ pub trait Chart: Sync {
                 }
             }
         };
-        self.update(db, blockscout, force_full).await
+        let rows_written =
+            metrics::CHART_ROWS_WRITTEN.with_label_values(&[&metrics::chain_id(), name]);
+        let rows_before = rows_written.get();
+        let started = Instant::now();
+        let result = async {
+            self.update(db, blockscout, params).await?;
+            update_periods(db, blockscout, self, params).await?;
+            update_moving_averages(db, self).await
+        }
+        .await;
+        let attempt = UpdateAttempt {
+            duration: started.elapsed(),
+            rows_written: rows_written.get() - rows_before,
+            error: result.as_ref().err().map(|err| err.to_string()),
+        };
+        let saved = save_update_attempt(db, name, &attempt).await;
+        // error of the update itself is more important than failure to record it
+        result?;
+        saved.map_err(UpdateError::StatsDB)
     }
 }
 
+/// Outcome of a single chart update run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UpdateAttempt {
+    pub duration: StdDuration,
+    pub rows_written: u64,
+    /// `None` if the update succeeded
+    pub error: Option<String>,
+}
+
 #[derive(Debug, FromQueryResult)]
 struct ChartID {
     id: i32,
@@ -119,3 +370,70 @@ pub async fn create_chart(
     .await?;
     Ok(())
 }
+
+pub async fn set_last_updated_at(db: &DatabaseConnection, name: &str) -> Result<(), DbErr> {
+    charts::Entity::update_many()
+        .col_expr(charts::Column::LastUpdatedAt, Expr::cust("now()"))
+        .filter(charts::Column::Name.eq(name))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// Records the update attempt. Successful attempt also moves `last_updated_at`
+pub async fn save_update_attempt(
+    db: &DatabaseConnection,
+    name: &str,
+    attempt: &UpdateAttempt,
+) -> Result<(), DbErr> {
+    let duration_ms = i64::try_from(attempt.duration.as_millis()).unwrap_or(i64::MAX);
+    let rows = i64::try_from(attempt.rows_written).unwrap_or(i64::MAX);
+    let mut update = charts::Entity::update_many()
+        .col_expr(charts::Column::LastUpdateAttemptAt, Expr::cust("now()"))
+        .col_expr(
+            charts::Column::LastUpdateError,
+            Expr::value(attempt.error.clone()),
+        )
+        .col_expr(
+            charts::Column::LastUpdateDurationMs,
+            Expr::value(duration_ms),
+        )
+        .col_expr(charts::Column::LastUpdateRows, Expr::value(rows));
+    if attempt.error.is_none() {
+        update = update.col_expr(charts::Column::LastUpdatedAt, Expr::cust("now()"));
+    }
+    update
+        .filter(charts::Column::Name.eq(name))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+#[derive(Debug, FromQueryResult)]
+struct FailedUpdates {
+    failed_updates: i32,
+}
+
+/// Records the outcome of the update run, all retries of the run count as one.
+/// Returns the number of runs that failed in a row, `0` if this one succeeded
+pub async fn save_update_run(
+    db: &DatabaseConnection,
+    name: &str,
+    succeeded: bool,
+) -> Result<u32, DbErr> {
+    let stmnt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        UPDATE charts
+        SET failed_updates = CASE WHEN $2 THEN 0 ELSE failed_updates + 1 END
+        WHERE name = $1
+        RETURNING failed_updates
+        "#,
+        vec![name.into(), succeeded.into()],
+    );
+    let failed_updates = FailedUpdates::find_by_statement(stmnt)
+        .one(db)
+        .await?
+        .map_or(0, |row| row.failed_updates);
+    Ok(failed_updates.try_into().unwrap_or_default())
+}
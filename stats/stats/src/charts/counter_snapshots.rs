@@ -0,0 +1,146 @@
+use super::{
+    find_chart,
+    insert::{insert_data_many, DateValue},
+};
+use crate::{
+    get_chart_data, metrics, AggregationPolicy, MissingDatePolicy, UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+
+/// Line chart of daily snapshots of `counter`.
+///
+/// Every update stores the latest value of the counter as the value of the current day,
+/// so history of the counter is collected from the moment the chart is enabled
+#[derive(Debug, Clone)]
+pub struct CounterSnapshots {
+    name: String,
+    counter: String,
+}
+
+impl CounterSnapshots {
+    pub fn new(name: String, counter: String) -> Self {
+        Self { name, counter }
+    }
+}
+
+#[async_trait]
+impl crate::Chart for CounterSnapshots {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    fn missing_date_policy(&self) -> MissingDatePolicy {
+        MissingDatePolicy::FillPrevious
+    }
+
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        AggregationPolicy::Last
+    }
+
+    fn drop_last_point(&self) -> bool {
+        false
+    }
+
+    fn dependencies(&self) -> Vec<&str> {
+        vec![self.counter.as_str()]
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        _blockscout: &DatabaseConnection,
+        _params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        let chart_id = find_chart(db, &self.name)
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .ok_or_else(|| UpdateError::NotFound(self.name.clone()))?;
+        let Some(last) = get_chart_data(db, &self.counter, None, None, None)
+            .await?
+            .pop()
+        else {
+            tracing::warn!(
+                chart = self.name.as_str(),
+                counter = self.counter.as_str(),
+                "counter has no value yet, snapshot is skipped"
+            );
+            return Ok(());
+        };
+        let snapshot = DateValue {
+            date: Utc::now().date_naive(),
+            value: last.value,
+        };
+        let rows = insert_data_many(db, [snapshot.active_model(chart_id, None)])
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        metrics::CHART_ROWS_WRITTEN
+            .with_label_values(&[&metrics::chain_id(), &self.name])
+            .inc_by(rows);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        counters::TotalBlocks,
+        tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data},
+        Chart,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_counter_snapshots() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("update_counter_snapshots").await;
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+        let counter = TotalBlocks::default();
+        let chart = CounterSnapshots::new("totalBlocksHistory".into(), counter.name().into());
+        counter.create(&db).await.unwrap();
+        chart.create(&db).await.unwrap();
+
+        // counter is not computed yet
+        chart
+            .update(&db, &blockscout, UpdateParameters::default())
+            .await
+            .unwrap();
+        let data = get_chart_data(&db, chart.name(), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(data, vec![]);
+
+        counter
+            .update(&db, &blockscout, UpdateParameters::default())
+            .await
+            .unwrap();
+        chart
+            .update(&db, &blockscout, UpdateParameters::default())
+            .await
+            .unwrap();
+        // repeated update replaces snapshot of the same day
+        chart
+            .update(&db, &blockscout, UpdateParameters::default())
+            .await
+            .unwrap();
+        let data = get_chart_data(&db, chart.name(), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            data,
+            vec![DateValue {
+                date: Utc::now().date_naive(),
+                value: 13.into(),
+            }]
+        );
+    }
+}
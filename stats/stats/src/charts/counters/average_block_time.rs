@@ -3,7 +3,7 @@ use crate::{
         insert::{DateValue, DateValueDouble},
         updater::ChartFullUpdater,
     },
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -39,11 +39,10 @@ impl ChartFullUpdater for AverageBlockTime {
         ))
         .one(blockscout)
         .await
-        .map_err(UpdateError::BlockscoutDB)?
-        .map(DateValue::from)
+        .map_err(UpdateError::blockscout)?
         .ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
 
-        Ok(vec![item])
+        Ok(vec![item.try_into()?])
     }
 }
 
@@ -61,9 +60,9 @@ impl crate::Chart for AverageBlockTime {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
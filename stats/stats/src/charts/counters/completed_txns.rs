@@ -1,6 +1,6 @@
 use crate::{
     charts::{insert::DateValue, updater::ChartFullUpdater},
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -25,7 +25,7 @@ impl ChartFullUpdater for CompletedTxns {
         // also we need last date of block, that's why 3rd query is needed
         let data = DateValue::find_by_statement(Statement::from_string(
             DbBackend::Postgres,
-            r#"SELECT (all_success - all_success_dropped)::TEXT AS value, last_block_date AS date 
+            r#"SELECT (all_success - all_success_dropped)::NUMERIC AS value, last_block_date AS date 
             FROM (
                 SELECT (
                     SELECT COUNT(*) AS all_success
@@ -46,7 +46,7 @@ impl ChartFullUpdater for CompletedTxns {
         ))
         .one(blockscout)
         .await
-        .map_err(UpdateError::BlockscoutDB)?
+        .map_err(UpdateError::blockscout)?
         .ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
 
         Ok(vec![data])
@@ -67,9 +67,9 @@ impl crate::Chart for CompletedTxns {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
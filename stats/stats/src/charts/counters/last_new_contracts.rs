@@ -5,7 +5,7 @@ use crate::{
         updater::{last_point, ChartDependentUpdater},
     },
     lines::NewContracts,
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -43,6 +43,9 @@ impl crate::Chart for LastNewContracts {
     fn chart_type(&self) -> ChartType {
         ChartType::Counter
     }
+    fn dependencies(&self) -> Vec<&str> {
+        vec![self.parent.name()]
+    }
     fn relevant_or_zero(&self) -> bool {
         true
     }
@@ -56,9 +59,9 @@ impl crate::Chart for LastNewContracts {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
@@ -1,6 +1,6 @@
 use crate::{
     charts::{insert::DateValue, updater::ChartFullUpdater},
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -9,11 +9,11 @@ use sea_orm::prelude::*;
 #[derive(Debug)]
 pub struct MockCounter {
     name: String,
-    value: String,
+    value: Decimal,
 }
 
 impl MockCounter {
-    pub fn new(name: String, value: String) -> Self {
+    pub fn new(name: String, value: Decimal) -> Self {
         Self { name, value }
     }
 }
@@ -26,7 +26,7 @@ impl ChartFullUpdater for MockCounter {
     ) -> Result<Vec<DateValue>, UpdateError> {
         let item = DateValue {
             date: chrono::offset::Local::now().date_naive(),
-            value: self.value.clone(),
+            value: self.value,
         };
         Ok(vec![item])
     }
@@ -46,8 +46,8 @@ impl crate::Chart for MockCounter {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
@@ -12,6 +12,7 @@ mod total_native_coin_transfers;
 mod total_tokens;
 mod total_txns;
 mod total_verified_contracts;
+mod txns_fee_24h;
 
 pub use average_block_time::AverageBlockTime;
 pub use completed_txns::CompletedTxns;
@@ -27,3 +28,4 @@ pub use total_native_coin_transfers::TotalNativeCoinTransfers;
 pub use total_tokens::TotalTokens;
 pub use total_txns::TotalTxns;
 pub use total_verified_contracts::TotalVerifiedContracts;
+pub use txns_fee_24h::TxnsFee24h;
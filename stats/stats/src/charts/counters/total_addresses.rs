@@ -1,6 +1,6 @@
 use crate::{
     charts::{insert::DateValue, updater::ChartFullUpdater},
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -19,7 +19,7 @@ impl ChartFullUpdater for TotalAddresses {
             DbBackend::Postgres,
             r#"SELECT date, value FROM ( 
                 SELECT (
-                    SELECT COUNT(*)::TEXT as value FROM addresses
+                    SELECT COUNT(*)::NUMERIC as value FROM addresses
                 ), (
                     SELECT MAX(b.timestamp)::DATE AS date
                     FROM blocks b
@@ -30,7 +30,7 @@ impl ChartFullUpdater for TotalAddresses {
         ))
         .one(blockscout)
         .await
-        .map_err(UpdateError::BlockscoutDB)?
+        .map_err(UpdateError::blockscout)?
         .ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
 
         Ok(vec![data])
@@ -51,9 +51,9 @@ impl crate::Chart for TotalAddresses {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
@@ -1,6 +1,6 @@
 use crate::{
     charts::{insert::DateValue, updater::ChartFullUpdater},
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use blockscout_db::entity::blocks;
@@ -31,12 +31,12 @@ impl ChartFullUpdater for TotalBlocks {
             .into_model::<TotalBlocksData>()
             .one(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?
+            .map_err(UpdateError::blockscout)?
             .ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
 
         let data = DateValue {
             date: data.timestamp.date(),
-            value: data.number.to_string(),
+            value: data.number.into(),
         };
         Ok(vec![data])
     }
@@ -52,13 +52,28 @@ impl crate::Chart for TotalBlocks {
         ChartType::Counter
     }
 
+    fn increment_sql(&self) -> Option<&str> {
+        Some(
+            r#"
+            SELECT
+                MAX(timestamp)::DATE as date,
+                COUNT(*)::NUMERIC as value
+            FROM blocks
+            WHERE
+                consensus = true AND
+                number > $1 AND
+                number <= $2
+            "#,
+        )
+    }
+
     async fn update(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
@@ -88,7 +103,7 @@ mod tests {
         chart_data::Entity::insert(chart_data::ActiveModel {
             chart_id: Set(1),
             date: Set(NaiveDate::from_str("2022-11-10").unwrap()),
-            value: Set(1.to_string()),
+            value: Set(1.into()),
             ..Default::default()
         })
         .exec(&db as &DatabaseConnection)
@@ -97,9 +112,12 @@ mod tests {
 
         fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
 
-        updater.update(&db, &blockscout, true).await.unwrap();
+        updater
+            .update(&db, &blockscout, UpdateParameters::with_force_full(true))
+            .await
+            .unwrap();
         let data = get_counters(&db).await.unwrap();
-        assert_eq!("13", data[updater.name()].value);
+        assert_eq!("13", data[updater.name()].value.to_string());
     }
 
     #[tokio::test]
@@ -113,9 +131,12 @@ mod tests {
 
         fill_mock_blockscout_data(&blockscout, "2022-11-12").await;
 
-        updater.update(&db, &blockscout, true).await.unwrap();
+        updater
+            .update(&db, &blockscout, UpdateParameters::with_force_full(true))
+            .await
+            .unwrap();
         let data = get_counters(&db).await.unwrap();
-        assert_eq!("9", data[updater.name()].value);
+        assert_eq!("9", data[updater.name()].value.to_string());
     }
 
     #[tokio::test]
@@ -130,7 +151,7 @@ mod tests {
         chart_data::Entity::insert(chart_data::ActiveModel {
             chart_id: Set(1),
             date: Set(NaiveDate::from_str("2022-11-11").unwrap()),
-            value: Set(1.to_string()),
+            value: Set(1.into()),
             ..Default::default()
         })
         .exec(&db as &DatabaseConnection)
@@ -139,8 +160,11 @@ mod tests {
 
         fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
 
-        updater.update(&db, &blockscout, true).await.unwrap();
+        updater
+            .update(&db, &blockscout, UpdateParameters::with_force_full(true))
+            .await
+            .unwrap();
         let data = get_counters(&db).await.unwrap();
-        assert_eq!("13", data[updater.name()].value);
+        assert_eq!("13", data[updater.name()].value.to_string());
     }
 }
@@ -5,7 +5,7 @@ use crate::{
         updater::{last_point, ChartDependentUpdater},
     },
     lines::ContractsGrowth,
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -45,6 +45,10 @@ impl crate::Chart for TotalContracts {
         ChartType::Counter
     }
 
+    fn dependencies(&self) -> Vec<&str> {
+        vec![self.parent.name()]
+    }
+
     async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
         self.parent.create(db).await?;
         create_chart(db, self.name().into(), self.chart_type()).await
@@ -54,9 +58,9 @@ impl crate::Chart for TotalContracts {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
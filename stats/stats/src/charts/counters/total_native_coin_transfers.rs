@@ -2,10 +2,10 @@ use crate::{
     charts::{
         create_chart,
         insert::DateValue,
-        updater::{parse_and_sum, ChartDependentUpdater},
+        updater::{sum, ChartDependentUpdater},
     },
     lines::NewNativeCoinTransfers,
-    Chart, UpdateError,
+    Chart, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -30,8 +30,8 @@ impl ChartDependentUpdater<NewNativeCoinTransfers> for TotalNativeCoinTransfers
     }
 
     async fn get_values(&self, parent_data: Vec<DateValue>) -> Result<Vec<DateValue>, UpdateError> {
-        let sum = parse_and_sum::<i64>(parent_data, self.name(), self.parent.name())?;
-        Ok(sum.into_iter().collect())
+        let total = sum(parent_data, self.name(), self.parent.name());
+        Ok(total.into_iter().collect())
     }
 }
 
@@ -45,6 +45,10 @@ impl crate::Chart for TotalNativeCoinTransfers {
         ChartType::Counter
     }
 
+    fn dependencies(&self) -> Vec<&str> {
+        vec![self.parent.name()]
+    }
+
     async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
         self.parent.create(db).await?;
         create_chart(db, self.name().into(), self.chart_type()).await
@@ -54,9 +58,9 @@ impl crate::Chart for TotalNativeCoinTransfers {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
@@ -1,6 +1,6 @@
 use crate::{
     charts::{insert::DateValue, updater::ChartFullUpdater},
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -20,7 +20,7 @@ impl ChartFullUpdater for TotalTokens {
             r#"
             SELECT 
                 (
-                    SELECT count(*)::text
+                    SELECT count(*)::NUMERIC
                         FROM tokens
                 ) AS "value",
                 (
@@ -33,7 +33,7 @@ impl ChartFullUpdater for TotalTokens {
         ))
         .one(blockscout)
         .await
-        .map_err(UpdateError::BlockscoutDB)?
+        .map_err(UpdateError::blockscout)?
         .ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
 
         Ok(vec![data])
@@ -54,9 +54,9 @@ impl crate::Chart for TotalTokens {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
@@ -2,10 +2,10 @@ use crate::{
     charts::{
         create_chart,
         insert::DateValue,
-        updater::{parse_and_sum, ChartDependentUpdater},
+        updater::{sum, ChartDependentUpdater},
     },
     lines::NewTxns,
-    Chart, UpdateError,
+    Chart, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -30,8 +30,8 @@ impl ChartDependentUpdater<NewTxns> for TotalTxns {
     }
 
     async fn get_values(&self, parent_data: Vec<DateValue>) -> Result<Vec<DateValue>, UpdateError> {
-        let sum = parse_and_sum::<i64>(parent_data, self.name(), self.parent.name())?;
-        Ok(sum.into_iter().collect())
+        let total = sum(parent_data, self.name(), self.parent.name());
+        Ok(total.into_iter().collect())
     }
 }
 
@@ -45,6 +45,26 @@ impl crate::Chart for TotalTxns {
         ChartType::Counter
     }
 
+    fn increment_sql(&self) -> Option<&str> {
+        Some(
+            r#"
+            SELECT
+                MAX(b.timestamp)::DATE as date,
+                COUNT(*)::NUMERIC as value
+            FROM transactions t
+            JOIN blocks       b ON t.block_hash = b.hash
+            WHERE
+                b.consensus = true AND
+                b.number > $1 AND
+                b.number <= $2
+            "#,
+        )
+    }
+
+    fn dependencies(&self) -> Vec<&str> {
+        vec![self.parent.name()]
+    }
+
     async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
         self.parent.create(db).await?;
         create_chart(db, self.name().into(), self.chart_type()).await
@@ -54,9 +74,9 @@ impl crate::Chart for TotalTxns {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
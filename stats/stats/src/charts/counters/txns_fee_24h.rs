@@ -0,0 +1,88 @@
+use crate::{
+    charts::{
+        insert::{DateValue, DateValueDouble},
+        updater::ChartFullUpdater,
+    },
+    Unit, UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+#[derive(Default, Debug)]
+pub struct TxnsFee24h {}
+
+#[async_trait]
+impl ChartFullUpdater for TxnsFee24h {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        // last 24 hours are counted from the last block rather than current time,
+        // so the value stays relevant while blockscout is catching up
+        let data = DateValueDouble::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT 
+                COALESCE(
+                    (SUM(t.gas_used * t.gas_price) / $1)::FLOAT,
+                    0
+                ) AS value,
+                last_block.timestamp::DATE AS date
+            FROM (
+                SELECT MAX(b.timestamp) AS timestamp
+                FROM blocks b
+                WHERE b.consensus = true
+            ) last_block
+            LEFT JOIN blocks b ON
+                b.consensus = true AND
+                b.timestamp > last_block.timestamp - INTERVAL '24 hours'
+            LEFT JOIN transactions t ON t.block_hash = b.hash
+            GROUP BY last_block.timestamp
+            "#,
+            vec![Unit::NativeCoin.wei().into()],
+        ))
+        .one(blockscout)
+        .await
+        .map_err(UpdateError::blockscout)?
+        .ok_or_else(|| UpdateError::Internal("query returned nothing".into()))?;
+
+        Ok(vec![data.try_into()?])
+    }
+}
+
+#[async_trait]
+impl crate::Chart for TxnsFee24h {
+    fn name(&self) -> &str {
+        "txnsFee24h"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Counter
+    }
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::NativeCoin)
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::simple_test_counter;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_txns_fee_24h() {
+        let counter = TxnsFee24h::default();
+        simple_test_counter("update_txns_fee_24h", counter, "0.000023592592569").await;
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    charts::{
+        insert::DateValue,
+        updater::{ChartPartialUpdater, FULL_SYNC_BATCH_DAYS},
+    },
+    UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::{Duration, NaiveDate};
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement, TransactionTrait};
+
+/// Chart defined by operator's SQL query against blockscout database.
+///
+/// The query has to return `date` and `value` columns. If the query uses `$1`,
+/// it is bound to the date of the last stored point, or `NULL` when the chart
+/// is computed from scratch, so the query can filter by `date > $1 OR $1 IS NULL`.
+/// If it uses `$2` as well, it is bound to the last date to compute or `NULL`, otherwise
+/// points after that date are dropped after the query. The query is run in read only transaction
+#[derive(Debug, Clone)]
+pub struct CustomSqlChart {
+    name: String,
+    chart_type: ChartType,
+    sql: String,
+    uses_last_date: bool,
+    uses_to_date: bool,
+}
+
+impl CustomSqlChart {
+    pub fn new(name: String, chart_type: ChartType, sql: String) -> Self {
+        let uses_last_date = uses_parameter(&sql, 1);
+        let uses_to_date = uses_parameter(&sql, 2);
+        Self {
+            name,
+            chart_type,
+            sql,
+            uses_last_date,
+            uses_to_date,
+        }
+    }
+}
+
+/// Returns true if `sql` refers to `$n` parameter, e.g. for `$1` `$10` and others don't count
+fn uses_parameter(sql: &str, n: usize) -> bool {
+    let parameter = format!("${n}");
+    sql.match_indices(&parameter)
+        .any(|(i, _)| !sql[i + parameter.len()..].starts_with(|c: char| c.is_ascii_digit()))
+}
+
+#[async_trait]
+impl ChartPartialUpdater for CustomSqlChart {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        // postgres can't infer type of unused `$1`, so it is bound only if used or `$2` is
+        let values = match (self.uses_last_date, self.uses_to_date) {
+            (_, true) => vec![last_row.map(|row| row.date).into(), to.into()],
+            (true, false) => vec![last_row.map(|row| row.date).into()],
+            (false, false) => vec![],
+        };
+        let stmnt = Statement::from_sql_and_values(DbBackend::Postgres, &self.sql, values);
+        let txn = blockscout.begin().await.map_err(UpdateError::blockscout)?;
+        txn.execute(Statement::from_string(
+            DbBackend::Postgres,
+            "SET TRANSACTION READ ONLY".into(),
+        ))
+        .await
+        .map_err(UpdateError::blockscout)?;
+        let data = DateValue::find_by_statement(stmnt)
+            .all(&txn)
+            .await
+            .map_err(UpdateError::blockscout)?;
+        txn.commit().await.map_err(UpdateError::blockscout)?;
+        Ok(data
+            .into_iter()
+            .filter(|value| to.map_or(true, |to| value.date <= to))
+            .collect())
+    }
+
+    // query without `$2` would read all the data for every window
+    fn full_sync_step(&self) -> Option<Duration> {
+        match self.uses_to_date {
+            true => Some(Duration::days(FULL_SYNC_BATCH_DAYS)),
+            false => None,
+        }
+    }
+}
+
+#[async_trait]
+impl crate::Chart for CustomSqlChart {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn chart_type(&self) -> ChartType {
+        self.chart_type.clone()
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::simple_test::{simple_test_chart, simple_test_counter};
+
+    #[test]
+    fn parameters_are_detected() {
+        assert!(uses_parameter("SELECT $1::DATE as date", 1));
+        assert!(uses_parameter("WHERE ($1 IS NULL OR date > $1)", 1));
+        assert!(!uses_parameter("SELECT CURRENT_DATE as date", 1));
+        assert!(!uses_parameter("WHERE date > $10", 1));
+        assert!(uses_parameter("WHERE date > $1 AND date <= $2", 2));
+        assert!(!uses_parameter("WHERE date > $1", 2));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_custom_sql_line() {
+        let chart = CustomSqlChart::new(
+            "customNewBlocks".into(),
+            ChartType::Line,
+            r#"
+            SELECT DATE(timestamp) as date, COUNT(*)::NUMERIC as value
+            FROM blocks
+            WHERE
+                timestamp != to_timestamp(0) AND
+                ($1::DATE IS NULL OR DATE(timestamp) > $1) AND
+                consensus = true
+            GROUP BY date
+            "#
+            .into(),
+        );
+        simple_test_chart(
+            "update_custom_sql_line",
+            chart,
+            vec![
+                ("2022-11-09", "1"),
+                ("2022-11-10", "3"),
+                ("2022-11-11", "4"),
+                ("2022-11-12", "1"),
+                ("2022-12-01", "1"),
+                ("2023-01-01", "1"),
+                ("2023-02-01", "1"),
+                ("2023-03-01", "1"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_custom_sql_counter() {
+        let counter = CustomSqlChart::new(
+            "customTotalBlocks".into(),
+            ChartType::Counter,
+            r#"
+            SELECT DATE(MAX(timestamp)) as date, COUNT(*)::NUMERIC as value
+            FROM blocks
+            WHERE consensus = true
+            "#
+            .into(),
+        );
+        simple_test_counter("update_custom_sql_counter", counter, "13").await;
+    }
+}
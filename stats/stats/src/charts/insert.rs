@@ -1,8 +1,8 @@
-use std::num::ParseIntError;
-
+use crate::UpdateError;
 use chrono::{NaiveDate, Utc};
 use entity::chart_data;
 use sea_orm::{prelude::*, sea_query, ConnectionTrait, FromQueryResult, Set};
+use std::str::FromStr;
 
 #[derive(FromQueryResult, Debug, Clone)]
 pub struct DateValueInt {
@@ -14,56 +14,37 @@ impl From<DateValueInt> for DateValue {
     fn from(value: DateValueInt) -> Self {
         Self {
             date: value.date,
-            value: value.value.to_string(),
+            value: value.value.into(),
         }
     }
 }
 
-impl TryFrom<DateValue> for DateValueInt {
-    type Error = ParseIntError;
-
-    fn try_from(value: DateValue) -> Result<Self, Self::Error> {
-        Ok(Self {
-            date: value.date,
-            value: value.value.parse()?,
-        })
-    }
-}
-
 #[derive(FromQueryResult, Debug, Clone)]
 pub struct DateValueDouble {
     pub date: NaiveDate,
     pub value: f64,
 }
 
-impl From<DateValueDouble> for DateValue {
-    fn from(value: DateValueDouble) -> Self {
-        Self {
-            date: value.date,
-            value: value.value.to_string(),
-        }
-    }
-}
-
-#[derive(FromQueryResult, Debug, Clone)]
-pub struct DateValueDecimal {
-    pub date: NaiveDate,
-    pub value: Decimal,
-}
+impl TryFrom<DateValueDouble> for DateValue {
+    type Error = UpdateError;
 
-impl From<DateValueDecimal> for DateValue {
-    fn from(value: DateValueDecimal) -> Self {
-        Self {
+    fn try_from(value: DateValueDouble) -> Result<Self, Self::Error> {
+        // parsing shortest representation of float keeps values like
+        // `0.1` exact instead of storing all digits of its binary form
+        let decimal = Decimal::from_str(&value.value.to_string()).map_err(|e| {
+            UpdateError::Internal(format!("cannot convert '{}' to decimal: {e}", value.value))
+        })?;
+        Ok(Self {
             date: value.date,
-            value: value.value.to_string(),
-        }
+            value: decimal,
+        })
     }
 }
 
 #[derive(FromQueryResult, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
 pub struct DateValue {
     pub date: NaiveDate,
-    pub value: String,
+    pub value: Decimal,
 }
 
 impl DateValue {
@@ -76,7 +57,7 @@ impl DateValue {
             id: Default::default(),
             chart_id: Set(chart_id),
             date: Set(self.date),
-            value: Set(self.value.clone()),
+            value: Set(self.value),
             created_at: Default::default(),
             min_blockscout_block: Set(min_blockscout_block),
         }
@@ -85,7 +66,7 @@ impl DateValue {
     pub fn zero(date: NaiveDate) -> Self {
         Self {
             date,
-            value: "0".to_string(),
+            value: Decimal::ZERO,
         }
     }
 
@@ -104,13 +85,15 @@ impl DateValue {
     }
 }
 
-pub async fn insert_data_many<C, D>(db: &C, data: D) -> Result<(), DbErr>
+/// Returns number of written rows
+pub async fn insert_data_many<C, D>(db: &C, data: D) -> Result<u64, DbErr>
 where
     C: ConnectionTrait,
     D: IntoIterator<Item = chart_data::ActiveModel> + Send + Sync,
 {
-    let mut data = data.into_iter().peekable();
-    if data.peek().is_some() {
+    let data: Vec<_> = data.into_iter().collect();
+    let rows = data.len() as u64;
+    if !data.is_empty() {
         chart_data::Entity::insert_many(data)
             .on_conflict(
                 sea_query::OnConflict::columns([
@@ -123,5 +106,5 @@ where
             .exec(db)
             .await?;
     }
-    Ok(())
+    Ok(rows)
 }
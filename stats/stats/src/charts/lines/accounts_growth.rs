@@ -5,7 +5,7 @@ use crate::{
         insert::{DateValue, DateValueInt},
         updater::ChartFullUpdater,
     },
-    MissingDatePolicy, UpdateError,
+    MissingDatePolicy, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -70,9 +70,9 @@ impl crate::Chart for AccountsGrowth {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
@@ -1,8 +1,9 @@
 use crate::{
     charts::{insert::DateValue, updater::ChartPartialUpdater},
-    UpdateError,
+    AggregationPolicy, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -15,6 +16,7 @@ impl ChartPartialUpdater for ActiveAccounts {
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
@@ -22,38 +24,40 @@ impl ChartPartialUpdater for ActiveAccounts {
                 r#"
                 SELECT 
                     DATE(blocks.timestamp) as date, 
-                    COUNT(DISTINCT from_address_hash)::TEXT as value
+                    COUNT(DISTINCT from_address_hash)::NUMERIC as value
                 FROM transactions 
                 JOIN blocks on transactions.block_hash = blocks.hash
                 WHERE 
                     blocks.timestamp != to_timestamp(0) AND
                     date(blocks.timestamp) > $1 AND
+                    ($2::date IS NULL OR date(blocks.timestamp) <= $2) AND
                     blocks.consensus = true
                 GROUP BY date(blocks.timestamp);
                 "#,
-                vec![row.date.into()],
+                vec![row.date.into(), to.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 r#"
                 SELECT 
                     DATE(blocks.timestamp) as date, 
-                    COUNT(DISTINCT from_address_hash)::TEXT as value
+                    COUNT(DISTINCT from_address_hash)::NUMERIC as value
                 FROM transactions 
                 JOIN blocks on transactions.block_hash = blocks.hash
                 WHERE 
+                    ($1::date IS NULL OR date(blocks.timestamp) <= $1) AND
                     blocks.timestamp != to_timestamp(0) AND
                     blocks.consensus = true
                 GROUP BY date(blocks.timestamp);
                 "#,
-                vec![],
+                vec![to.into()],
             ),
         };
 
         let data = DateValue::find_by_statement(stmnt)
             .all(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
+            .map_err(UpdateError::blockscout)?;
         Ok(data)
     }
 }
@@ -66,22 +70,46 @@ impl crate::Chart for ActiveAccounts {
     fn chart_type(&self) -> ChartType {
         ChartType::Line
     }
+    // distinct accounts of days can't be summed up
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        AggregationPolicy::Average
+    }
+    fn period_sql(&self) -> Option<&str> {
+        Some(
+            r#"
+            SELECT
+                DATE_TRUNC($1, blocks.timestamp)::DATE as date,
+                COUNT(DISTINCT from_address_hash)::NUMERIC as value
+            FROM transactions
+            JOIN blocks on transactions.block_hash = blocks.hash
+            WHERE
+                blocks.timestamp != to_timestamp(0) AND
+                ($2::date IS NULL OR DATE(blocks.timestamp) >= $2) AND
+                ($3::date IS NULL OR DATE(blocks.timestamp) <= $3) AND
+                blocks.consensus = true
+            GROUP BY 1
+            "#,
+        )
+    }
 
     async fn update(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::tests::simple_test::simple_test_chart;
-
     use super::ActiveAccounts;
+    use crate::tests::simple_test::{ranged_test_chart, simple_test_chart};
 
     #[tokio::test]
     #[ignore = "needs database to run"]
@@ -103,4 +131,43 @@ mod tests {
         )
         .await;
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn ranged_update_active_accounts() {
+        let chart = ActiveAccounts::default();
+        ranged_test_chart(
+            "ranged_update_active_accounts",
+            chart,
+            vec![
+                ("2022-11-08", "0"),
+                ("2022-11-09", "1"),
+                ("2022-11-10", "3"),
+                ("2022-11-11", "4"),
+                ("2022-11-12", "1"),
+                ("2022-11-13", "0"),
+                ("2022-11-14", "0"),
+                ("2022-11-15", "0"),
+                ("2022-11-16", "0"),
+                ("2022-11-17", "0"),
+                ("2022-11-18", "0"),
+                ("2022-11-19", "0"),
+                ("2022-11-20", "0"),
+                ("2022-11-21", "0"),
+                ("2022-11-22", "0"),
+                ("2022-11-23", "0"),
+                ("2022-11-24", "0"),
+                ("2022-11-25", "0"),
+                ("2022-11-26", "0"),
+                ("2022-11-27", "0"),
+                ("2022-11-28", "0"),
+                ("2022-11-29", "0"),
+                ("2022-11-30", "0"),
+                ("2022-12-01", "1"),
+            ],
+            "2022-11-08".parse().unwrap(),
+            "2022-12-01".parse().unwrap(),
+        )
+        .await;
+    }
 }
@@ -3,23 +3,23 @@ use crate::{
         insert::{DateValue, DateValueDouble},
         updater::ChartPartialUpdater,
     },
-    UpdateError,
+    AggregationPolicy, Unit, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
 #[derive(Default, Debug)]
 pub struct AverageBlockRewards {}
 
-const ETH: i64 = 1_000_000_000_000_000_000;
-
 #[async_trait]
 impl ChartPartialUpdater for AverageBlockRewards {
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
@@ -32,11 +32,12 @@ impl ChartPartialUpdater for AverageBlockRewards {
                 JOIN blocks ON block_rewards.block_hash = blocks.hash
                 WHERE 
                     blocks.timestamp != to_timestamp(0) AND 
-                    date(blocks.timestamp) > $2 AND 
+                    date(blocks.timestamp) > $2 AND
+                    ($3::date IS NULL OR date(blocks.timestamp) <= $3) AND 
                     blocks.consensus = true
                 GROUP BY date
                 "#,
-                vec![ETH.into(), row.date.into()],
+                vec![Unit::NativeCoin.wei().into(), row.date.into(), to.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
@@ -47,19 +48,23 @@ impl ChartPartialUpdater for AverageBlockRewards {
                 FROM block_rewards
                 JOIN blocks ON block_rewards.block_hash = blocks.hash
                 WHERE 
+                    ($2::date IS NULL OR date(blocks.timestamp) <= $2) AND
                     blocks.timestamp != to_timestamp(0) AND 
                     blocks.consensus = true
                 GROUP BY date
                 "#,
-                vec![ETH.into()],
+                vec![Unit::NativeCoin.wei().into(), to.into()],
             ),
         };
 
         let data = DateValueDouble::find_by_statement(stmnt)
             .all(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
-        let data = data.into_iter().map(DateValue::from).collect();
+            .map_err(UpdateError::blockscout)?;
+        let data = data
+            .into_iter()
+            .map(DateValue::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(data)
     }
 }
@@ -73,14 +78,25 @@ impl crate::Chart for AverageBlockRewards {
     fn chart_type(&self) -> ChartType {
         ChartType::Line
     }
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::NativeCoin)
+    }
+
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        AggregationPolicy::Average
+    }
 
     async fn update(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
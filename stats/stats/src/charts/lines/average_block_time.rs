@@ -0,0 +1,164 @@
+use crate::{
+    charts::{
+        insert::{DateValue, DateValueDouble},
+        updater::ChartPartialUpdater,
+    },
+    AggregationPolicy, UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+/// Average interval in seconds between consecutive consensus blocks.
+///
+/// Interval is attributed to the day of the later block, so the first block of a day
+/// is compared with the last block of the previous one.
+/// Named differently from the counter [`crate::counters::AverageBlockTime`],
+/// since names of charts are unique
+#[derive(Default, Debug)]
+pub struct AverageBlockTime {}
+
+#[async_trait]
+impl ChartPartialUpdater for AverageBlockTime {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let stmnt = match last_row {
+            // blocks of the last stored day are needed only as predecessors of the next ones
+            Some(row) => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT
+                    DATE(t.timestamp) as date,
+                    AVG(t.diff)::FLOAT as value
+                FROM
+                (
+                    SELECT
+                        blocks.timestamp,
+                        EXTRACT(
+                            EPOCH FROM blocks.timestamp - lag(blocks.timestamp) OVER (ORDER BY blocks.number)
+                        ) as diff
+                    FROM blocks
+                    WHERE
+                        blocks.timestamp != to_timestamp(0) AND
+                        DATE(blocks.timestamp) >= $1 AND
+                        ($2::date IS NULL OR DATE(blocks.timestamp) <= $2) AND
+                        blocks.consensus = true
+                ) t
+                WHERE
+                    t.diff IS NOT NULL AND
+                    DATE(t.timestamp) > $1
+                GROUP BY date
+                "#,
+                vec![row.date.into(), to.into()],
+            ),
+            None => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT
+                    DATE(t.timestamp) as date,
+                    AVG(t.diff)::FLOAT as value
+                FROM
+                (
+                    SELECT
+                        blocks.timestamp,
+                        EXTRACT(
+                            EPOCH FROM blocks.timestamp - lag(blocks.timestamp) OVER (ORDER BY blocks.number)
+                        ) as diff
+                    FROM blocks
+                    WHERE
+                        blocks.timestamp != to_timestamp(0) AND
+                        ($1::date IS NULL OR DATE(blocks.timestamp) <= $1) AND
+                        blocks.consensus = true
+                ) t
+                WHERE t.diff IS NOT NULL
+                GROUP BY date
+                "#,
+                vec![to.into()],
+            ),
+        };
+
+        let data = DateValueDouble::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?;
+        let data = data
+            .into_iter()
+            .map(DateValue::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for AverageBlockTime {
+    fn name(&self) -> &str {
+        "averageBlockTimeHistory"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        AggregationPolicy::Average
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::AverageBlockTime;
+    use crate::tests::{
+        mock_generator::MockGenerator,
+        simple_test::{incremental_test_chart, simple_test_chart},
+    };
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_average_block_time() {
+        let chart = AverageBlockTime::default();
+        simple_test_chart(
+            "update_average_block_time_line",
+            chart,
+            vec![
+                ("2022-11-10", "28800"),
+                ("2022-11-11", "21600"),
+                ("2022-11-12", "1"),
+                ("2022-12-01", "1677600"),
+                ("2023-01-01", "2678400"),
+                ("2023-02-01", "2678400"),
+                ("2023-03-01", "2419200"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_average_block_time_incremental() {
+        let chart = AverageBlockTime::default();
+        incremental_test_chart(
+            "update_average_block_time_incremental",
+            chart,
+            MockGenerator::default(),
+        )
+        .await;
+    }
+}
@@ -1,8 +1,9 @@
 use crate::{
     charts::{insert::DateValue, updater::ChartPartialUpdater},
-    UpdateError,
+    AggregationPolicy, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -15,6 +16,7 @@ impl ChartPartialUpdater for AverageGasLimit {
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
@@ -22,36 +24,38 @@ impl ChartPartialUpdater for AverageGasLimit {
                 r#"
                 SELECT 
                     DATE(blocks.timestamp) as date,
-                    ROUND(AVG(blocks.gas_limit))::TEXT as value
+                    ROUND(AVG(blocks.gas_limit))::NUMERIC as value
                 FROM blocks
                 WHERE
                     blocks.timestamp != to_timestamp(0) AND
                     DATE(blocks.timestamp) > $1 AND
+                    ($2::date IS NULL OR DATE(blocks.timestamp) <= $2) AND
                     blocks.consensus = true
                 GROUP BY date
                 "#,
-                vec![row.date.into()],
+                vec![row.date.into(), to.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 r#"
                 SELECT 
                     DATE(blocks.timestamp) as date,
-                    ROUND(AVG(blocks.gas_limit))::TEXT as value
+                    ROUND(AVG(blocks.gas_limit))::NUMERIC as value
                 FROM blocks 
                 WHERE 
+                    ($1::date IS NULL OR DATE(blocks.timestamp) <= $1) AND
                     blocks.timestamp != to_timestamp(0) AND
                     blocks.consensus = true
                 GROUP BY date
                 "#,
-                vec![],
+                vec![to.into()],
             ),
         };
 
         let data = DateValue::find_by_statement(stmnt)
             .all(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
+            .map_err(UpdateError::blockscout)?;
         Ok(data)
     }
 }
@@ -66,13 +70,21 @@ impl crate::Chart for AverageGasLimit {
         ChartType::Line
     }
 
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        AggregationPolicy::Average
+    }
+
     async fn update(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
@@ -3,23 +3,23 @@ use crate::{
         insert::{DateValue, DateValueDouble},
         updater::ChartPartialUpdater,
     },
-    UpdateError,
+    AggregationPolicy, Unit, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
 #[derive(Default, Debug)]
 pub struct AverageGasPrice {}
 
-const GWEI: i64 = 1_000_000_000;
-
 #[async_trait]
 impl ChartPartialUpdater for AverageGasPrice {
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
@@ -41,10 +41,11 @@ impl ChartPartialUpdater for AverageGasPrice {
                     WHERE 
                         blocks.timestamp != to_timestamp(0) AND
                         date(blocks.timestamp) > $2 AND
+                        ($3::date IS NULL OR date(blocks.timestamp) <= $3) AND
                         blocks.consensus = true
                     GROUP BY date
                     "#,
-                vec![GWEI.into(), row.date.into()],
+                vec![Unit::Gwei.wei().into(), row.date.into(), to.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
@@ -63,19 +64,23 @@ impl ChartPartialUpdater for AverageGasPrice {
                     FROM transactions
                     JOIN blocks ON transactions.block_hash = blocks.hash
                     WHERE 
+                        ($2::date IS NULL OR date(blocks.timestamp) <= $2) AND
                         blocks.timestamp != to_timestamp(0) AND
                         blocks.consensus = true
                     GROUP BY date
                     "#,
-                vec![GWEI.into()],
+                vec![Unit::Gwei.wei().into(), to.into()],
             ),
         };
 
         let data = DateValueDouble::find_by_statement(stmnt)
             .all(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
-        let data = data.into_iter().map(DateValue::from).collect();
+            .map_err(UpdateError::blockscout)?;
+        let data = data
+            .into_iter()
+            .map(DateValue::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(data)
     }
 }
@@ -88,21 +93,32 @@ impl crate::Chart for AverageGasPrice {
     fn chart_type(&self) -> ChartType {
         ChartType::Line
     }
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::Gwei)
+    }
+
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        AggregationPolicy::Average
+    }
 
     async fn update(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::simple_test::simple_test_chart;
+    use crate::tests::simple_test::{ranged_test_chart, simple_test_chart};
 
     #[tokio::test]
     #[ignore = "needs database to run"]
@@ -125,4 +141,26 @@ mod tests {
         )
         .await;
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn ranged_update_average_gas_price() {
+        let chart = AverageGasPrice::default();
+
+        ranged_test_chart(
+            "ranged_update_average_gas_price",
+            chart,
+            vec![
+                ("2022-11-08", "0"),
+                ("2022-11-09", "0.4493827156"),
+                ("2022-11-10", "1.96604938075"),
+                ("2022-11-11", "3.2901234535"),
+                ("2022-11-12", "5.8419753028"),
+                ("2022-11-13", "0"),
+            ],
+            "2022-11-08".parse().unwrap(),
+            "2022-11-13".parse().unwrap(),
+        )
+        .await;
+    }
 }
@@ -0,0 +1,124 @@
+use crate::{
+    charts::{
+        insert::{DateValue, DateValueDouble},
+        updater::ChartPartialUpdater,
+    },
+    Unit, UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+#[derive(Default, Debug)]
+pub struct BlockRewards {}
+
+#[async_trait]
+impl ChartPartialUpdater for BlockRewards {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let stmnt = match last_row {
+            Some(row) => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT
+                    DATE(blocks.timestamp) as date,
+                    (SUM(block_rewards.reward) / $1)::FLOAT as value
+                FROM block_rewards
+                JOIN blocks ON block_rewards.block_hash = blocks.hash
+                WHERE 
+                    blocks.timestamp != to_timestamp(0) AND 
+                    date(blocks.timestamp) > $2 AND
+                    ($3::date IS NULL OR date(blocks.timestamp) <= $3) AND 
+                    blocks.consensus = true
+                GROUP BY date
+                "#,
+                vec![Unit::NativeCoin.wei().into(), row.date.into(), to.into()],
+            ),
+            None => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT
+                    DATE(blocks.timestamp) as date,
+                    (SUM(block_rewards.reward) / $1)::FLOAT as value
+                FROM block_rewards
+                JOIN blocks ON block_rewards.block_hash = blocks.hash
+                WHERE 
+                    ($2::date IS NULL OR date(blocks.timestamp) <= $2) AND
+                    blocks.timestamp != to_timestamp(0) AND 
+                    blocks.consensus = true
+                GROUP BY date
+                "#,
+                vec![Unit::NativeCoin.wei().into(), to.into()],
+            ),
+        };
+
+        let data = DateValueDouble::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?;
+        let data = data
+            .into_iter()
+            .map(DateValue::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for BlockRewards {
+    fn name(&self) -> &str {
+        "blockRewards"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::NativeCoin)
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlockRewards;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_block_rewards() {
+        let chart = BlockRewards::default();
+        simple_test_chart(
+            "update_block_rewards",
+            chart,
+            vec![
+                ("2022-11-09", "0"),
+                ("2022-11-10", "6"),
+                ("2022-11-11", "7"),
+                ("2022-11-12", "3"),
+                ("2022-12-01", "4"),
+                ("2023-01-01", "0"),
+                ("2023-02-01", "1"),
+                ("2023-03-01", "2"),
+            ],
+        )
+        .await;
+    }
+}
@@ -0,0 +1,208 @@
+use crate::{
+    charts::{
+        find_chart,
+        insert::{insert_data_many, DateValue, DateValueDouble},
+        insert_series_data,
+        updater::{get_last_row, get_min_block_blockscout},
+    },
+    metrics, AggregationPolicy, Unit, UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement, TransactionTrait};
+
+const PERCENTILE_SERIES: [&str; 2] = ["median", "p90"];
+
+/// Daily percentiles of transactions gas price (Gwei)
+#[derive(FromQueryResult, Debug, Clone)]
+struct GasPricePercentilesValue {
+    date: NaiveDate,
+    median: Option<f64>,
+    p90: Option<f64>,
+}
+
+impl GasPricePercentilesValue {
+    /// Percentiles in order of [`PERCENTILE_SERIES`]
+    fn percentiles(&self) -> [Option<f64>; 2] {
+        [self.median, self.p90]
+    }
+}
+
+async fn read_gas_price_percentiles(
+    blockscout: &DatabaseConnection,
+    after: Option<NaiveDate>,
+) -> Result<Vec<GasPricePercentilesValue>, UpdateError> {
+    let stmnt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        WITH gas_prices AS (
+            SELECT
+                blocks.timestamp::date as date,
+                COALESCE(
+                    transactions.gas_price,
+                    blocks.base_fee_per_gas + LEAST(
+                        transactions.max_priority_fee_per_gas,
+                        transactions.max_fee_per_gas - blocks.base_fee_per_gas
+                    )
+                )::float as gas_price
+            FROM transactions
+            JOIN blocks ON transactions.block_hash = blocks.hash
+            WHERE
+                blocks.timestamp != to_timestamp(0) AND
+                ($2::date IS NULL OR date(blocks.timestamp) > $2) AND
+                blocks.consensus = true
+        )
+        SELECT
+            date,
+            percentile_cont(0.5) WITHIN GROUP (ORDER BY gas_price) / $1 as median,
+            percentile_cont(0.9) WITHIN GROUP (ORDER BY gas_price) / $1 as p90
+        FROM gas_prices
+        GROUP BY date
+        "#,
+        vec![Unit::Gwei.wei().into(), after.into()],
+    );
+
+    GasPricePercentilesValue::find_by_statement(stmnt)
+        .all(blockscout)
+        .await
+        .map_err(UpdateError::blockscout)
+}
+
+/// Converts one of the percentiles into chart points, days without value are skipped
+fn percentile_points(
+    data: &[GasPricePercentilesValue],
+    value: impl Fn(&GasPricePercentilesValue) -> Option<f64>,
+) -> Result<Vec<DateValue>, UpdateError> {
+    data.iter()
+        .filter_map(|percentiles| {
+            value(percentiles).map(|value| DateValueDouble {
+                date: percentiles.date,
+                value,
+            })
+        })
+        .map(DateValue::try_from)
+        .collect()
+}
+
+/// Median and 90th percentile of gas price per day. The chart stores
+/// the median, both percentiles are stored as its series, see [`PERCENTILE_SERIES`]
+#[derive(Default, Debug)]
+pub struct GasPricePercentiles {}
+
+#[async_trait]
+impl crate::Chart for GasPricePercentiles {
+    fn name(&self) -> &str {
+        "gasPricePercentiles"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::Gwei)
+    }
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        AggregationPolicy::Average
+    }
+    fn series(&self) -> &[&str] {
+        &PERCENTILE_SERIES
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        let chart_id = find_chart(db, self.name())
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
+        let min_blockscout_block = get_min_block_blockscout(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?;
+        let last_row = get_last_row(
+            self,
+            chart_id,
+            min_blockscout_block,
+            db,
+            params.force_full,
+            params.recompute_window_days,
+        )
+        .await?;
+        let data = {
+            let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
+                .with_label_values(&[&metrics::chain_id(), self.name()])
+                .start_timer();
+            read_gas_price_percentiles(blockscout, last_row.map(|row| row.date)).await?
+        };
+
+        // the chart and its series are written together, so they always agree
+        let txn = db.begin().await.map_err(UpdateError::StatsDB)?;
+        let median = percentile_points(&data, |percentiles| percentiles.median)?;
+        let mut rows = insert_data_many(
+            &txn,
+            median
+                .into_iter()
+                .map(|value| value.active_model(chart_id, Some(min_blockscout_block))),
+        )
+        .await
+        .map_err(UpdateError::StatsDB)?;
+        for (i, key) in PERCENTILE_SERIES.iter().enumerate() {
+            let series = percentile_points(&data, |percentiles| percentiles.percentiles()[i])?;
+            rows += insert_series_data(&txn, chart_id, key, &series, Some(min_blockscout_block))
+                .await
+                .map_err(UpdateError::StatsDB)?;
+        }
+        txn.commit().await.map_err(UpdateError::StatsDB)?;
+        metrics::CHART_ROWS_WRITTEN
+            .with_label_values(&[&metrics::chain_id(), self.name()])
+            .inc_by(rows);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GasPricePercentiles;
+    use crate::tests::simple_test::series_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_gas_price_percentiles() {
+        let chart = GasPricePercentiles::default();
+        let median = vec![
+            ("2022-11-09", "0"),
+            ("2022-11-10", "1.6851851835"),
+            ("2022-11-11", "1.123456789"),
+            ("2022-11-12", "8.987654312"),
+            ("2022-12-01", "10.111111101"),
+            ("2023-01-01", "1.123456789"),
+            ("2023-02-01", "12.358024679"),
+            ("2023-03-01", "1.123456789"),
+        ];
+        series_test_chart(
+            "update_gas_price_percentiles",
+            chart,
+            median.clone(),
+            vec![
+                ("median", median),
+                (
+                    "p90",
+                    vec![
+                        ("2022-11-09", "1.123456789"),
+                        ("2022-11-10", "3.370370367"),
+                        ("2022-11-11", "6.740740734"),
+                        ("2022-11-12", "8.987654312"),
+                        ("2022-12-01", "10.111111101"),
+                        ("2023-01-01", "1.123456789"),
+                        ("2023-02-01", "12.358024679"),
+                        ("2023-03-01", "1.123456789"),
+                    ],
+                ),
+            ],
+        )
+        .await;
+    }
+}
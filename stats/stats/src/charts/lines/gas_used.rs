@@ -0,0 +1,133 @@
+use crate::{
+    charts::{insert::DateValue, updater::ChartPartialUpdater},
+    UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+#[derive(Default, Debug)]
+pub struct GasUsed {}
+
+#[async_trait]
+impl ChartPartialUpdater for GasUsed {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let stmnt = match last_row {
+            Some(row) => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT 
+                    DATE(blocks.timestamp) as date, 
+                    SUM(blocks.gas_used)::NUMERIC as value
+                FROM blocks
+                WHERE 
+                    blocks.timestamp != to_timestamp(0) AND
+                    DATE(blocks.timestamp) > $1 AND
+                    ($2::date IS NULL OR DATE(blocks.timestamp) <= $2) AND 
+                    blocks.consensus = true
+                GROUP BY date;
+                "#,
+                vec![row.date.into(), to.into()],
+            ),
+            None => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT 
+                    DATE(blocks.timestamp) as date, 
+                    SUM(blocks.gas_used)::NUMERIC as value
+                FROM blocks
+                WHERE 
+                    ($1::date IS NULL OR DATE(blocks.timestamp) <= $1) AND
+                    blocks.timestamp != to_timestamp(0) AND 
+                    blocks.consensus = true
+                GROUP BY date;
+                "#,
+                vec![to.into()],
+            ),
+        };
+
+        let data = DateValue::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for GasUsed {
+    fn name(&self) -> &str {
+        "gasUsed"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GasUsed;
+    use crate::tests::simple_test::{ranged_test_chart, simple_test_chart};
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_gas_used() {
+        let chart = GasUsed::default();
+        simple_test_chart(
+            "update_gas_used",
+            chart,
+            vec![
+                ("2022-11-09", "10000"),
+                ("2022-11-10", "81780"),
+                ("2022-11-11", "129860"),
+                ("2022-11-12", "29040"),
+                ("2022-12-01", "37670"),
+                ("2023-01-01", "46300"),
+                ("2023-02-01", "54930"),
+                ("2023-03-01", "13560"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn ranged_update_gas_used() {
+        let chart = GasUsed::default();
+        ranged_test_chart(
+            "ranged_update_gas_used",
+            chart,
+            vec![
+                ("2022-11-08", "0"),
+                ("2022-11-09", "10000"),
+                ("2022-11-10", "81780"),
+                ("2022-11-11", "129860"),
+                ("2022-11-12", "29040"),
+                ("2022-11-13", "0"),
+            ],
+            "2022-11-08".parse().unwrap(),
+            "2022-11-13".parse().unwrap(),
+        )
+        .await;
+    }
+}
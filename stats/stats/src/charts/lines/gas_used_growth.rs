@@ -1,11 +1,9 @@
 use crate::{
-    charts::{
-        insert::{DateValue, DateValueDecimal},
-        updater::ChartPartialUpdater,
-    },
-    MissingDatePolicy, UpdateError,
+    charts::{insert::DateValue, updater::ChartPartialUpdater},
+    MissingDatePolicy, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -18,12 +16,10 @@ impl ChartPartialUpdater for GasUsedGrowth {
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         let data = match last_row {
             Some(row) => {
-                let last_value = Decimal::from_str_exact(&row.value).map_err(|e| {
-                    UpdateError::Internal(format!("failed to parse previous value: {e}"))
-                })?;
                 let stmnt = Statement::from_sql_and_values(
                     DbBackend::Postgres,
                     r#"
@@ -33,23 +29,23 @@ impl ChartPartialUpdater for GasUsedGrowth {
                     FROM blocks
                     WHERE 
                         blocks.timestamp != to_timestamp(0) AND 
-                        DATE(blocks.timestamp) > $1 AND 
+                        DATE(blocks.timestamp) > $1 AND
+                        ($2::date IS NULL OR DATE(blocks.timestamp) <= $2) AND 
                         blocks.consensus = true
                     GROUP BY date(blocks.timestamp)
                     ORDER BY date;
                     "#,
-                    vec![row.date.into()],
+                    vec![row.date.into(), to.into()],
                 );
-                DateValueDecimal::find_by_statement(stmnt)
+                DateValue::find_by_statement(stmnt)
                     .all(blockscout)
                     .await
-                    .map_err(UpdateError::BlockscoutDB)?
+                    .map_err(UpdateError::blockscout)?
                     .into_iter()
                     .map(|mut point| {
-                        point.value += last_value;
+                        point.value += row.value;
                         point
                     })
-                    .map(|point| point.into())
                     .collect()
             }
             None => {
@@ -58,20 +54,21 @@ impl ChartPartialUpdater for GasUsedGrowth {
                     r#"
                     SELECT 
                         DATE(blocks.timestamp) as date, 
-                        (sum(sum(blocks.gas_used)) OVER (ORDER BY date(blocks.timestamp)))::TEXT AS value
+                        (sum(sum(blocks.gas_used)) OVER (ORDER BY date(blocks.timestamp)))::NUMERIC AS value
                     FROM blocks
                     WHERE 
+                        ($1::date IS NULL OR DATE(blocks.timestamp) <= $1) AND
                         blocks.timestamp != to_timestamp(0) AND 
                         blocks.consensus = true
                     GROUP BY date(blocks.timestamp)
                     ORDER BY date;
                     "#,
-                    vec![],
+                    vec![to.into()],
                 );
                 DateValue::find_by_statement(stmnt)
                     .all(blockscout)
                     .await
-                    .map_err(UpdateError::BlockscoutDB)?
+                    .map_err(UpdateError::blockscout)?
             }
         };
 
@@ -98,9 +95,13 @@ impl crate::Chart for GasUsedGrowth {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
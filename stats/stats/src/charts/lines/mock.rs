@@ -1,6 +1,6 @@
 use crate::{
     charts::{insert::DateValue, updater::ChartFullUpdater},
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use chrono::{Duration, NaiveDate};
@@ -19,7 +19,7 @@ fn generate_intervals(mut start: NaiveDate) -> Vec<NaiveDate> {
     times
 }
 
-pub fn mocked_lines<T: SampleUniform + PartialOrd + Clone + ToString>(
+pub fn mocked_lines<T: SampleUniform + PartialOrd + Clone + Into<Decimal>>(
     range: Range<T>,
 ) -> Vec<DateValue> {
     let mut rng = StdRng::seed_from_u64(222);
@@ -30,26 +30,26 @@ pub fn mocked_lines<T: SampleUniform + PartialOrd + Clone + ToString>(
             let value = rng.gen_range(range);
             DateValue {
                 date,
-                value: value.to_string(),
+                value: value.into(),
             }
         })
         .collect()
 }
 
 #[derive(Debug)]
-pub struct MockLine<T: SampleUniform + PartialOrd + Clone + ToString> {
+pub struct MockLine<T: SampleUniform + PartialOrd + Clone + Into<Decimal>> {
     name: String,
     range: Range<T>,
 }
 
-impl<T: SampleUniform + PartialOrd + Clone + ToString> MockLine<T> {
+impl<T: SampleUniform + PartialOrd + Clone + Into<Decimal>> MockLine<T> {
     pub fn new(name: String, range: Range<T>) -> Self {
         Self { name, range }
     }
 }
 
 #[async_trait]
-impl<T: SampleUniform + PartialOrd + Clone + ToString + Send + Sync + 'static> ChartFullUpdater
+impl<T: SampleUniform + PartialOrd + Clone + Into<Decimal> + Send + Sync + 'static> ChartFullUpdater
     for MockLine<T>
 {
     async fn get_values(
@@ -61,7 +61,7 @@ impl<T: SampleUniform + PartialOrd + Clone + ToString + Send + Sync + 'static> C
 }
 
 #[async_trait]
-impl<T: SampleUniform + PartialOrd + Clone + ToString + Send + Sync + 'static> crate::Chart
+impl<T: SampleUniform + PartialOrd + Clone + Into<Decimal> + Send + Sync + 'static> crate::Chart
     for MockLine<T>
 {
     fn name(&self) -> &str {
@@ -76,8 +76,8 @@ impl<T: SampleUniform + PartialOrd + Clone + ToString + Send + Sync + 'static> c
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
@@ -4,22 +4,30 @@ mod accounts_growth;
 mod active_accounts;
 mod average_block_rewards;
 mod average_block_size;
+mod average_block_time;
 mod average_gas_limit;
 mod average_gas_price;
 mod average_txn_fee;
+mod block_rewards;
 mod contracts_growth;
+mod gas_price_percentiles;
+mod gas_used;
 mod gas_used_growth;
 mod native_coin_holders_growth;
 mod native_coin_supply;
+mod native_coin_transfers_volume;
 mod new_accounts;
 mod new_blocks;
 mod new_contracts;
 mod new_native_coin_holders;
 mod new_native_coin_transfers;
+mod new_tokens;
 mod new_txns;
+mod new_txns_by_type;
 mod new_verified_contracts;
 mod txns_fee;
 mod txns_growth;
+mod txns_stats;
 mod txns_success_rate;
 mod verified_contracts_growth;
 
@@ -27,22 +35,30 @@ pub use accounts_growth::AccountsGrowth;
 pub use active_accounts::ActiveAccounts;
 pub use average_block_rewards::AverageBlockRewards;
 pub use average_block_size::AverageBlockSize;
+pub use average_block_time::AverageBlockTime;
 pub use average_gas_limit::AverageGasLimit;
 pub use average_gas_price::AverageGasPrice;
 pub use average_txn_fee::AverageTxnFee;
+pub use block_rewards::BlockRewards;
 pub use contracts_growth::ContractsGrowth;
+pub use gas_price_percentiles::GasPricePercentiles;
+pub use gas_used::GasUsed;
 pub use gas_used_growth::GasUsedGrowth;
 pub use mock::MockLine;
 pub use native_coin_holders_growth::NativeCoinHoldersGrowth;
 pub use native_coin_supply::NativeCoinSupply;
+pub use native_coin_transfers_volume::NativeCoinTransfersVolume;
 pub use new_accounts::NewAccounts;
 pub use new_blocks::NewBlocks;
 pub use new_contracts::NewContracts;
 pub use new_native_coin_holders::NewNativeCoinHolders;
 pub use new_native_coin_transfers::NewNativeCoinTransfers;
+pub use new_tokens::NewTokens;
 pub use new_txns::NewTxns;
+pub use new_txns_by_type::NewTxnsByType;
 pub use new_verified_contracts::NewVerifiedContracts;
 pub use txns_fee::TxnsFee;
 pub use txns_growth::TxnsGrowth;
+pub use txns_stats::TxnsStatsCache;
 pub use txns_success_rate::TxnsSuccessRate;
 pub use verified_contracts_growth::VerifiedContractsGrowth;
@@ -4,7 +4,7 @@ use crate::{
         insert::{insert_data_many, DateValue},
         updater::{get_last_row, get_min_block_blockscout},
     },
-    Chart, MissingDatePolicy, UpdateError,
+    metrics, Chart, MissingDatePolicy, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use blockscout_db::entity::address_coin_balances_daily;
@@ -63,7 +63,7 @@ impl crate::Chart for NativeCoinHoldersGrowth {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
         let chart_id = find_chart(db, self.name())
             .await
@@ -71,12 +71,18 @@ impl crate::Chart for NativeCoinHoldersGrowth {
             .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
         let min_blockscout_block = get_min_block_blockscout(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
-        // settings offset to zero to get actual last row,
+            .map_err(UpdateError::blockscout)?;
+        // settings window to zero to get actual last row,
         // because support table contains information of actual last day
-        let offset = Some(0);
-        let last_row =
-            get_last_row(self, chart_id, min_blockscout_block, db, force_full, offset).await?;
+        let last_row = get_last_row(
+            self,
+            chart_id,
+            min_blockscout_block,
+            db,
+            params.force_full,
+            0,
+        )
+        .await?;
         self.update_sequentially_with_support_table(
             db,
             blockscout,
@@ -103,14 +109,14 @@ impl NativeCoinHoldersGrowth {
         let all_days = match last_row {
             Some(last_row) => get_unique_ordered_days(blockscout, Some(last_row.date))
                 .await
-                .map_err(UpdateError::BlockscoutDB)?,
+                .map_err(UpdateError::blockscout)?,
             None => {
                 self.clear_support_table(db)
                     .await
-                    .map_err(UpdateError::BlockscoutDB)?;
+                    .map_err(UpdateError::blockscout)?;
                 get_unique_ordered_days(blockscout, None)
                     .await
-                    .map_err(UpdateError::BlockscoutDB)?
+                    .map_err(UpdateError::blockscout)?
             }
         };
 
@@ -133,9 +139,12 @@ impl NativeCoinHoldersGrowth {
                 .into_iter()
                 .map(|result| result.active_model(chart_id, Some(min_blockscout_block)))
                 .collect();
-            insert_data_many(&db_tx, data)
+            let rows = insert_data_many(&db_tx, data)
                 .await
                 .map_err(UpdateError::StatsDB)?;
+            metrics::CHART_ROWS_WRITTEN
+                .with_label_values(&[&metrics::chain_id(), self.name()])
+                .inc_by(rows);
             db_tx.commit().await.map_err(UpdateError::StatsDB)?;
         }
         Ok(())
@@ -183,7 +192,7 @@ impl NativeCoinHoldersGrowth {
                 .map_err(|e| UpdateError::Internal(format!("cannot count holders: {e}")))?;
             result.push(DateValue {
                 date,
-                value: new_count.to_string(),
+                value: new_count.into(),
             });
         }
         Ok(result)
@@ -3,14 +3,13 @@ use crate::{
         insert::{DateValue, DateValueDouble},
         updater::ChartPartialUpdater,
     },
-    UpdateError,
+    AggregationPolicy, Unit, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
-const ETH: i64 = 1_000_000_000_000_000_000;
-
 #[derive(Default, Debug)]
 pub struct NativeCoinSupply {}
 
@@ -20,6 +19,7 @@ impl ChartPartialUpdater for NativeCoinSupply {
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
@@ -36,12 +36,12 @@ impl ChartPartialUpdater for NativeCoinSupply {
                                 END
                             ) / $1)::float AS value
                         FROM address_coin_balances_daily
-                        WHERE day > $2 AND day != to_timestamp(0)
+                        WHERE day > $2 AND ($3::date IS NULL OR day <= $3) AND day != to_timestamp(0)
                         GROUP BY day
                     ) as intermediate
                     WHERE value is not NULL;
                 ",
-                vec![ETH.into(), row.date.into()],
+                vec![Unit::NativeCoin.wei().into(), row.date.into(), to.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
@@ -57,20 +57,23 @@ impl ChartPartialUpdater for NativeCoinSupply {
                                 END
                             ) / $1)::float AS value
                         FROM address_coin_balances_daily
-                        WHERE day != to_timestamp(0)
+                        WHERE ($2::date IS NULL OR day <= $2) AND day != to_timestamp(0)
                         GROUP BY day
                     ) as intermediate
                     WHERE value is not NULL;
                 ",
-                vec![ETH.into()],
+                vec![Unit::NativeCoin.wei().into(), to.into()],
             ),
         };
 
         let data = DateValueDouble::find_by_statement(stmnt)
             .all(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
-        let data = data.into_iter().map(DateValue::from).collect();
+            .map_err(UpdateError::blockscout)?;
+        let data = data
+            .into_iter()
+            .map(DateValue::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(data)
     }
 }
@@ -84,14 +87,25 @@ impl crate::Chart for NativeCoinSupply {
     fn chart_type(&self) -> ChartType {
         ChartType::Line
     }
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::NativeCoin)
+    }
+
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        AggregationPolicy::Last
+    }
 
     async fn update(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
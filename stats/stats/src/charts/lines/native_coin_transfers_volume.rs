@@ -0,0 +1,123 @@
+use crate::{
+    charts::{
+        insert::{DateValue, DateValueDouble},
+        updater::ChartPartialUpdater,
+    },
+    Unit, UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+#[derive(Default, Debug)]
+pub struct NativeCoinTransfersVolume {}
+
+#[async_trait]
+impl ChartPartialUpdater for NativeCoinTransfersVolume {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let stmnt = match last_row {
+            Some(row) => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT 
+                    DATE(b.timestamp) as date,
+                    (SUM(t.value) / $1)::FLOAT as value
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    DATE(b.timestamp) > $2 AND
+                    ($3::date IS NULL OR DATE(b.timestamp) <= $3) AND
+                    b.consensus = true AND
+                    t.value > 0
+                GROUP BY DATE(b.timestamp)
+                "#,
+                vec![Unit::NativeCoin.wei().into(), row.date.into(), to.into()],
+            ),
+            None => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"
+                SELECT 
+                    DATE(b.timestamp) as date,
+                    (SUM(t.value) / $1)::FLOAT as value
+                FROM transactions t
+                JOIN blocks       b ON t.block_hash = b.hash
+                WHERE
+                    ($2::date IS NULL OR DATE(b.timestamp) <= $2) AND
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    t.value > 0
+                GROUP BY DATE(b.timestamp)
+                "#,
+                vec![Unit::NativeCoin.wei().into(), to.into()],
+            ),
+        };
+
+        let data = DateValueDouble::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?
+            .into_iter()
+            .map(DateValue::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for NativeCoinTransfersVolume {
+    fn name(&self) -> &str {
+        "nativeCoinTransfersVolume"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::NativeCoin)
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NativeCoinTransfersVolume;
+    use crate::tests::simple_test::simple_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_native_coin_transfers_volume() {
+        let chart = NativeCoinTransfersVolume::default();
+        simple_test_chart(
+            "update_native_coin_transfers_volume",
+            chart,
+            vec![
+                ("2022-11-09", "0.000002"),
+                ("2022-11-10", "0.000004"),
+                ("2022-11-11", "0.000004"),
+                ("2022-11-12", "0.000002"),
+                ("2022-12-01", "0.000002"),
+                ("2023-02-01", "0.000002"),
+            ],
+        )
+        .await;
+    }
+}
@@ -2,11 +2,12 @@ use crate::{
     charts::{
         cache::Cache,
         insert::{DateValue, DateValueInt},
-        updater::ChartFullUpdater,
+        updater::ChartPartialUpdater,
     },
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::{Duration, NaiveDate};
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 use tokio::sync::Mutex;
@@ -49,26 +50,41 @@ impl NewAccounts {
         let mut data = DateValueInt::find_by_statement(stmnt)
             .all(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
+            .map_err(UpdateError::blockscout)?;
         data.sort_by_key(|v| v.date);
         Ok(data)
     }
 }
 
 #[async_trait]
-impl ChartFullUpdater for NewAccounts {
+impl ChartPartialUpdater for NewAccounts {
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
-        let mut cache = self.cache.lock().await;
-        Ok(cache
-            .get_or_update(async move { Self::read_values(blockscout).await })
-            .await?
+        // first transaction date of address can't be found from transactions of
+        // the range, so full data shared with accounts growth chart is used
+        // and only the points of the range are written
+        let data = {
+            let mut cache = self.cache.lock().await;
+            cache
+                .get_or_update(async move { Self::read_values(blockscout).await })
+                .await?
+        };
+        Ok(data
             .into_iter()
+            .filter(|value| last_row.as_ref().map_or(true, |row| value.date > row.date))
+            .filter(|value| to.map_or(true, |to| value.date <= to))
             .map(DateValue::from)
             .collect())
     }
+
+    // values are computed from the full data anyway
+    fn full_sync_step(&self) -> Option<Duration> {
+        None
+    }
 }
 
 #[async_trait]
@@ -84,16 +100,32 @@ impl crate::Chart for NewAccounts {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::tests::simple_test::simple_test_chart;
+    use crate::{
+        charts::updater::get_min_block_blockscout,
+        get_chart_data,
+        tests::{
+            init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data,
+            simple_test::simple_test_chart,
+        },
+        Chart,
+    };
+    use entity::chart_data;
+    use pretty_assertions::assert_eq;
+    use sea_orm::Set;
+    use std::str::FromStr;
 
     #[tokio::test]
     #[ignore = "needs database to run"]
@@ -111,4 +143,57 @@ mod tests {
         )
         .await;
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_accounts_recurrent() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("update_new_accounts_recurrent").await;
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+        let chart = NewAccounts::new(Cache::default());
+        chart.create(&db).await.unwrap();
+
+        let min_blockscout_block = get_min_block_blockscout(&blockscout).await.unwrap();
+        // set wrong value and check, that only points after second to last one were rewritten
+        chart_data::Entity::insert_many([
+            chart_data::ActiveModel {
+                chart_id: Set(1),
+                date: Set(NaiveDate::from_str("2022-11-09").unwrap()),
+                value: Set(1.into()),
+                min_blockscout_block: Set(Some(min_blockscout_block)),
+                ..Default::default()
+            },
+            chart_data::ActiveModel {
+                chart_id: Set(1),
+                date: Set(NaiveDate::from_str("2022-11-10").unwrap()),
+                value: Set(100.into()),
+                min_blockscout_block: Set(Some(min_blockscout_block)),
+                ..Default::default()
+            },
+        ])
+        .exec(&db as &DatabaseConnection)
+        .await
+        .unwrap();
+
+        chart
+            .update(&db, &blockscout, UpdateParameters::default())
+            .await
+            .unwrap();
+        let data = get_chart_data(&db, chart.name(), None, None, None)
+            .await
+            .unwrap();
+        let data: Vec<_> = data
+            .iter()
+            .map(|p| (p.date.to_string(), p.value.to_string()))
+            .collect();
+        // accounts first seen at `2022-11-09` are not counted again
+        let expected = vec![
+            ("2022-11-09".to_string(), "1".to_string()),
+            ("2022-11-10".to_string(), "3".to_string()),
+            ("2022-11-11".to_string(), "4".to_string()),
+            ("2023-03-01".to_string(), "1".to_string()),
+        ];
+        assert_eq!(expected, data);
+    }
 }
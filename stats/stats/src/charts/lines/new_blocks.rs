@@ -1,8 +1,9 @@
 use crate::{
     charts::{insert::DateValue, updater::ChartPartialUpdater},
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -15,38 +16,41 @@ impl ChartPartialUpdater for NewBlocks {
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 r#"
-                    SELECT date(blocks.timestamp) as date, COUNT(*)::TEXT as value
+                    SELECT date(blocks.timestamp) as date, COUNT(*)::NUMERIC as value
                         FROM public.blocks
                         WHERE 
                             blocks.timestamp != to_timestamp(0) AND
                             date(blocks.timestamp) > $1 AND
+                            ($2::date IS NULL OR date(blocks.timestamp) <= $2) AND
                             consensus = true
                         GROUP BY date;
                     "#,
-                vec![row.date.into()],
+                vec![row.date.into(), to.into()],
             ),
-            None => Statement::from_string(
+            None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 r#"
-                    SELECT date(blocks.timestamp) as date, COUNT(*)::TEXT as value
+                    SELECT date(blocks.timestamp) as date, COUNT(*)::NUMERIC as value
                         FROM public.blocks
                         WHERE 
                             blocks.timestamp != to_timestamp(0) AND 
+                            ($1::date IS NULL OR date(blocks.timestamp) <= $1) AND
                             consensus = true
                         GROUP BY date;
-                    "#
-                .into(),
+                    "#,
+                vec![to.into()],
             ),
         };
         let data = DateValue::find_by_statement(stmnt)
             .all(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
+            .map_err(UpdateError::blockscout)?;
         Ok(data)
     }
 }
@@ -65,9 +69,13 @@ impl crate::Chart for NewBlocks {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
@@ -77,7 +85,10 @@ mod tests {
     use crate::{
         charts::updater::get_min_block_blockscout,
         get_chart_data,
-        tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data},
+        tests::{
+            init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data,
+            mock_generator::MockGenerator, simple_test::incremental_test_chart,
+        },
         Chart,
     };
     use chrono::NaiveDate;
@@ -102,14 +113,14 @@ mod tests {
             chart_data::ActiveModel {
                 chart_id: Set(1),
                 date: Set(NaiveDate::from_str("2022-11-10").unwrap()),
-                value: Set(3.to_string()),
+                value: Set(3.into()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
             },
             chart_data::ActiveModel {
                 chart_id: Set(1),
                 date: Set(NaiveDate::from_str("2022-11-11").unwrap()),
-                value: Set(100.to_string()),
+                value: Set(100.into()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
             },
@@ -119,47 +130,53 @@ mod tests {
         .unwrap();
 
         // Note that update is not full, therefore there is no entry with date `2022-11-09`
-        updater.update(&db, &blockscout, false).await.unwrap();
+        updater
+            .update(&db, &blockscout, UpdateParameters::default())
+            .await
+            .unwrap();
         let data = get_chart_data(&db, updater.name(), None, None, None)
             .await
             .unwrap();
         let expected = vec![
             DateValue {
                 date: NaiveDate::from_str("2022-11-10").unwrap(),
-                value: "3".into(),
+                value: 3.into(),
             },
             DateValue {
                 date: NaiveDate::from_str("2022-11-11").unwrap(),
-                value: "4".into(),
+                value: 4.into(),
             },
             DateValue {
                 date: NaiveDate::from_str("2022-11-12").unwrap(),
-                value: "1".into(),
+                value: 1.into(),
             },
         ];
         assert_eq!(expected, data);
 
         // note that update is full, therefore there is entry with date `2022-11-09`
-        updater.update(&db, &blockscout, true).await.unwrap();
+        updater
+            .update(&db, &blockscout, UpdateParameters::with_force_full(true))
+            .await
+            .unwrap();
         let data = get_chart_data(&db, updater.name(), None, None, None)
             .await
             .unwrap();
         let expected = vec![
             DateValue {
                 date: NaiveDate::from_str("2022-11-09").unwrap(),
-                value: "1".into(),
+                value: 1.into(),
             },
             DateValue {
                 date: NaiveDate::from_str("2022-11-10").unwrap(),
-                value: "3".into(),
+                value: 3.into(),
             },
             DateValue {
                 date: NaiveDate::from_str("2022-11-11").unwrap(),
-                value: "4".into(),
+                value: 4.into(),
             },
             DateValue {
                 date: NaiveDate::from_str("2022-11-12").unwrap(),
-                value: "1".into(),
+                value: 1.into(),
             },
         ];
         assert_eq!(expected, data);
@@ -175,26 +192,29 @@ mod tests {
         let updater = NewBlocks::default();
         updater.create(&db).await.unwrap();
 
-        updater.update(&db, &blockscout, true).await.unwrap();
+        updater
+            .update(&db, &blockscout, UpdateParameters::with_force_full(true))
+            .await
+            .unwrap();
         let data = get_chart_data(&db, updater.name(), None, None, None)
             .await
             .unwrap();
         let expected = vec![
             DateValue {
                 date: NaiveDate::from_str("2022-11-09").unwrap(),
-                value: "1".into(),
+                value: 1.into(),
             },
             DateValue {
                 date: NaiveDate::from_str("2022-11-10").unwrap(),
-                value: "3".into(),
+                value: 3.into(),
             },
             DateValue {
                 date: NaiveDate::from_str("2022-11-11").unwrap(),
-                value: "4".into(),
+                value: 4.into(),
             },
             DateValue {
                 date: NaiveDate::from_str("2022-11-12").unwrap(),
-                value: "1".into(),
+                value: 1.into(),
             },
         ];
         assert_eq!(expected, data);
@@ -217,28 +237,28 @@ mod tests {
             chart_data::ActiveModel {
                 chart_id: Set(1),
                 date: Set(NaiveDate::from_str("2022-11-09").unwrap()),
-                value: Set(2.to_string()),
+                value: Set(2.into()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
             },
             chart_data::ActiveModel {
                 chart_id: Set(1),
                 date: Set(NaiveDate::from_str("2022-11-10").unwrap()),
-                value: Set(4.to_string()),
+                value: Set(4.into()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
             },
             chart_data::ActiveModel {
                 chart_id: Set(1),
                 date: Set(NaiveDate::from_str("2022-11-11").unwrap()),
-                value: Set(5.to_string()),
+                value: Set(5.into()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
             },
             chart_data::ActiveModel {
                 chart_id: Set(1),
                 date: Set(NaiveDate::from_str("2022-11-12").unwrap()),
-                value: Set(2.to_string()),
+                value: Set(2.into()),
                 min_blockscout_block: Set(Some(min_blockscout_block)),
                 ..Default::default()
             },
@@ -247,28 +267,106 @@ mod tests {
         .await
         .unwrap();
 
-        updater.update(&db, &blockscout, false).await.unwrap();
+        updater
+            .update(&db, &blockscout, UpdateParameters::default())
+            .await
+            .unwrap();
         let data = get_chart_data(&db, updater.name(), None, None, None)
             .await
             .unwrap();
         let expected = vec![
             DateValue {
                 date: NaiveDate::from_str("2022-11-09").unwrap(),
-                value: "2".into(),
+                value: 2.into(),
             },
             DateValue {
                 date: NaiveDate::from_str("2022-11-10").unwrap(),
-                value: "4".into(),
+                value: 4.into(),
             },
             DateValue {
                 date: NaiveDate::from_str("2022-11-11").unwrap(),
-                value: "5".into(),
+                value: 5.into(),
             },
             DateValue {
                 date: NaiveDate::from_str("2022-11-12").unwrap(),
-                value: "1".into(),
+                value: 1.into(),
             },
         ];
         assert_eq!(expected, data);
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn repair_new_blocks_range() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("repair_new_blocks_range").await;
+        fill_mock_blockscout_data(&blockscout, "2022-11-12").await;
+
+        let updater = NewBlocks::default();
+        updater.create(&db).await.unwrap();
+
+        let min_blockscout_block = get_min_block_blockscout(&blockscout).await.unwrap();
+        // set wrong values and check, that only values of the range were rewritten
+        let rows = [
+            ("2022-11-09", 2),
+            ("2022-11-10", 4),
+            ("2022-11-11", 5),
+            ("2022-11-12", 2),
+        ]
+        .map(|(date, value)| chart_data::ActiveModel {
+            chart_id: Set(1),
+            date: Set(NaiveDate::from_str(date).unwrap()),
+            value: Set(value.into()),
+            min_blockscout_block: Set(Some(min_blockscout_block)),
+            ..Default::default()
+        });
+        chart_data::Entity::insert_many(rows)
+            .exec(&db as &DatabaseConnection)
+            .await
+            .unwrap();
+
+        updater
+            .repair(
+                &db,
+                &blockscout,
+                NaiveDate::from_str("2022-11-10").unwrap(),
+                NaiveDate::from_str("2022-11-11").unwrap(),
+            )
+            .await
+            .unwrap();
+        let data = get_chart_data(&db, updater.name(), None, None, None)
+            .await
+            .unwrap();
+        let expected = vec![
+            DateValue {
+                date: NaiveDate::from_str("2022-11-09").unwrap(),
+                value: 2.into(),
+            },
+            DateValue {
+                date: NaiveDate::from_str("2022-11-10").unwrap(),
+                value: 3.into(),
+            },
+            DateValue {
+                date: NaiveDate::from_str("2022-11-11").unwrap(),
+                value: 4.into(),
+            },
+            DateValue {
+                date: NaiveDate::from_str("2022-11-12").unwrap(),
+                value: 2.into(),
+            },
+        ];
+        assert_eq!(expected, data);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_blocks_incremental() {
+        let chart = NewBlocks::default();
+        incremental_test_chart(
+            "update_new_blocks_incremental",
+            chart,
+            MockGenerator::default(),
+        )
+        .await;
+    }
 }
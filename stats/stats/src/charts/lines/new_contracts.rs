@@ -1,4 +1,4 @@
-use crate::{charts::updater::ChartBatchUpdater, UpdateError};
+use crate::{charts::updater::ChartBatchUpdater, UpdateError, UpdateParameters};
 use async_trait::async_trait;
 use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
@@ -12,7 +12,7 @@ impl ChartBatchUpdater for NewContracts {
     fn get_query(&self, from: NaiveDate, to: NaiveDate) -> Statement {
         Statement::from_sql_and_values(
             DbBackend::Postgres,
-            r#"SELECT day AS date, COUNT(*)::text AS value
+            r#"SELECT day AS date, COUNT(*)::NUMERIC AS value
                 FROM (
                     SELECT 
                         DISTINCT ON (txns_plus_internal_txns.hash)
@@ -64,9 +64,9 @@ impl crate::Chart for NewContracts {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
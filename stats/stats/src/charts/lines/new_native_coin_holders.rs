@@ -1,12 +1,7 @@
 use super::NativeCoinHoldersGrowth;
 use crate::{
-    charts::{
-        create_chart,
-        insert::{DateValue, DateValueInt},
-        updater::ChartDependentUpdater,
-        Chart,
-    },
-    UpdateError,
+    charts::{create_chart, insert::DateValue, updater::ChartDependentUpdater, Chart},
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -34,25 +29,16 @@ impl ChartDependentUpdater<NativeCoinHoldersGrowth> for NewNativeCoinHolders {
         mut parent_data: Vec<DateValue>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         parent_data.sort();
-        let data: Result<Vec<_>, _> = parent_data
+        let data = parent_data
             .into_iter()
-            .map(DateValueInt::try_from)
-            .scan(0, |prev, point| {
-                Some(point.map(|mut point| {
-                    let new = point.value;
-                    point.value -= *prev;
-                    *prev = new;
-                    point
-                }))
+            .scan(Decimal::ZERO, |prev, mut point| {
+                let new = point.value;
+                point.value -= *prev;
+                *prev = new;
+                Some(point)
             })
-            .map(|point| point.map(DateValue::from))
             .collect();
-        Ok(data.map_err(|e| {
-            let parent_name = self.parent.name();
-            UpdateError::Internal(format!(
-                "failed to parse values in chart '{parent_name}': {e}",
-            ))
-        })?)
+        Ok(data)
     }
 }
 
@@ -66,6 +52,10 @@ impl Chart for NewNativeCoinHolders {
         ChartType::Line
     }
 
+    fn dependencies(&self) -> Vec<&str> {
+        vec![self.parent.name()]
+    }
+
     async fn create(&self, db: &DatabaseConnection) -> Result<(), DbErr> {
         self.parent.create(db).await?;
         create_chart(db, self.name().into(), self.chart_type()).await
@@ -75,9 +65,9 @@ impl Chart for NewNativeCoinHolders {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
     }
 }
 
@@ -1,8 +1,9 @@
 use crate::{
     charts::{insert::DateValue, updater::ChartPartialUpdater},
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -15,6 +16,7 @@ impl ChartPartialUpdater for NewNativeCoinTransfers {
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
@@ -22,42 +24,44 @@ impl ChartPartialUpdater for NewNativeCoinTransfers {
                 r#"
                 SELECT 
                     DATE(b.timestamp) as date,
-                    COUNT(*)::TEXT as value
+                    COUNT(*)::NUMERIC as value
                 FROM transactions t
                 JOIN blocks       b ON t.block_hash = b.hash
                 WHERE
                     b.timestamp != to_timestamp(0) AND
                     DATE(b.timestamp) > $1 AND
+                    ($2::date IS NULL OR DATE(b.timestamp) <= $2) AND
                     b.consensus = true AND
                     LENGTH(t.input) = 0 AND
                     t.value >= 0
                 GROUP BY date
                 "#,
-                vec![row.date.into()],
+                vec![row.date.into(), to.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 r#"
                 SELECT 
                     DATE(b.timestamp) as date,
-                    COUNT(*)::TEXT as value
+                    COUNT(*)::NUMERIC as value
                 FROM transactions t
                 JOIN blocks       b ON t.block_hash = b.hash
                 WHERE
+                    ($1::date IS NULL OR DATE(b.timestamp) <= $1) AND
                     b.timestamp != to_timestamp(0) AND
                     b.consensus = true AND
                     LENGTH(t.input) = 0 AND
                     t.value >= 0
                 GROUP BY date
                 "#,
-                vec![],
+                vec![to.into()],
             ),
         };
 
         let data = DateValue::find_by_statement(stmnt)
             .all(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
+            .map_err(UpdateError::blockscout)?;
         Ok(data)
     }
 }
@@ -76,9 +80,13 @@ impl crate::Chart for NewNativeCoinTransfers {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
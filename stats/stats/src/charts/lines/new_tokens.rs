@@ -0,0 +1,117 @@
+use crate::{
+    charts::{insert::DateValue, updater::ChartPartialUpdater},
+    UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+#[derive(Default, Debug)]
+pub struct NewTokens {}
+
+#[async_trait]
+impl ChartPartialUpdater for NewTokens {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let stmnt = match last_row {
+            Some(row) => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT
+                    DATE(tokens.inserted_at) as date,
+                    COUNT(*)::NUMERIC as value
+                FROM tokens
+                WHERE DATE(tokens.inserted_at) > $1
+                    AND ($2::date IS NULL OR DATE(tokens.inserted_at) <= $2)
+                GROUP BY DATE(tokens.inserted_at)"#,
+                vec![row.date.into(), to.into()],
+            ),
+            None => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"SELECT
+                    DATE(tokens.inserted_at) as date,
+                    COUNT(*)::NUMERIC as value
+                FROM tokens
+                WHERE ($1::date IS NULL OR DATE(tokens.inserted_at) <= $1)
+                GROUP BY DATE(tokens.inserted_at)"#,
+                vec![to.into()],
+            ),
+        };
+
+        let data = DateValue::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for NewTokens {
+    fn name(&self) -> &str {
+        "newTokens"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NewTokens;
+    use crate::tests::simple_test::{ranged_test_chart, simple_test_chart};
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_tokens() {
+        let chart = NewTokens::default();
+        simple_test_chart(
+            "update_new_tokens",
+            chart,
+            vec![
+                ("2022-11-09", "1"),
+                ("2022-11-10", "2"),
+                ("2022-11-11", "1"),
+            ],
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn ranged_update_new_tokens() {
+        let chart = NewTokens::default();
+        ranged_test_chart(
+            "ranged_update_new_tokens",
+            chart,
+            vec![
+                ("2022-11-08", "0"),
+                ("2022-11-09", "1"),
+                ("2022-11-10", "2"),
+                ("2022-11-11", "1"),
+                ("2022-11-12", "0"),
+            ],
+            "2022-11-08".parse().unwrap(),
+            "2022-11-12".parse().unwrap(),
+        )
+        .await;
+    }
+}
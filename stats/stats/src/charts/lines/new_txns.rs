@@ -1,13 +1,26 @@
+use super::txns_stats::{get_txns_stats, TxnsStatsCache};
 use crate::{
     charts::{insert::DateValue, updater::ChartPartialUpdater},
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
-use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+use sea_orm::prelude::*;
+use tokio::sync::Mutex;
 
 #[derive(Default, Debug)]
-pub struct NewTxns {}
+pub struct NewTxns {
+    cache: Mutex<TxnsStatsCache>,
+}
+
+impl NewTxns {
+    pub fn new(cache: TxnsStatsCache) -> Self {
+        Self {
+            cache: Mutex::new(cache),
+        }
+    }
+}
 
 #[async_trait]
 impl ChartPartialUpdater for NewTxns {
@@ -15,45 +28,16 @@ impl ChartPartialUpdater for NewTxns {
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
-        let stmnt = match last_row {
-            Some(row) => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                SELECT 
-                    date(b.timestamp) as date, 
-                    COUNT(*)::TEXT as value
-                FROM transactions t
-                JOIN blocks       b ON t.block_hash = b.hash
-                WHERE 
-                    b.timestamp != to_timestamp(0) AND
-                    date(b.timestamp) > $1 AND 
-                    b.consensus = true
-                GROUP BY date;
-                "#,
-                vec![row.date.into()],
-            ),
-            None => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                SELECT 
-                    date(b.timestamp) as date, 
-                    COUNT(*)::TEXT as value
-                FROM transactions t
-                JOIN blocks       b ON t.block_hash = b.hash
-                WHERE
-                    b.timestamp != to_timestamp(0) AND 
-                    b.consensus = true
-                GROUP BY date;
-                "#,
-                vec![],
-            ),
-        };
-
-        let data = DateValue::find_by_statement(stmnt)
-            .all(blockscout)
-            .await
-            .map_err(UpdateError::BlockscoutDB)?;
+        let data = get_txns_stats(&self.cache, blockscout, last_row, to).await?;
+        let data = data
+            .into_iter()
+            .map(|stats| DateValue {
+                date: stats.date,
+                value: stats.count.into(),
+            })
+            .collect();
         Ok(data)
     }
 }
@@ -67,21 +51,31 @@ impl crate::Chart for NewTxns {
     fn chart_type(&self) -> ChartType {
         ChartType::Line
     }
+    fn moving_average_windows(&self) -> &[u32] {
+        &[7, 30]
+    }
 
     async fn update(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::NewTxns;
-    use crate::tests::simple_test::{ranged_test_chart, simple_test_chart};
+    use crate::tests::{
+        mock_generator::MockGenerator,
+        simple_test::{incremental_test_chart, ranged_test_chart, simple_test_chart},
+    };
 
     #[tokio::test]
     #[ignore = "needs database to run"]
@@ -142,4 +136,16 @@ mod tests {
         )
         .await;
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_txns_incremental() {
+        let chart = NewTxns::default();
+        incremental_test_chart(
+            "update_new_txns_incremental",
+            chart,
+            MockGenerator::default(),
+        )
+        .await;
+    }
 }
@@ -0,0 +1,242 @@
+use crate::{
+    charts::{
+        find_chart,
+        insert::{insert_data_many, DateValue},
+        insert_series_data,
+        updater::{get_last_row, get_min_block_blockscout},
+        INSERT_BATCH_ROWS,
+    },
+    metrics, UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement, TransactionTrait};
+
+/// Transactions are split twice. By purpose, every transaction counted by the first
+/// purpose it matches, so purposes sum up to the total. And by envelope type,
+/// transactions of other types (e.g. access lists) are in neither of them
+const TXN_SERIES: [&str; 6] = [
+    "contractCreation",
+    "tokenTransfer",
+    "contractCall",
+    "coinTransfer",
+    "legacy",
+    "eip1559",
+];
+
+#[derive(FromQueryResult, Debug)]
+struct TxnsByTypeValue {
+    date: NaiveDate,
+    contract_creation: i64,
+    token_transfer: i64,
+    contract_call: i64,
+    coin_transfer: i64,
+    legacy: i64,
+    eip1559: i64,
+}
+
+impl TxnsByTypeValue {
+    /// Counts in order of [`TXN_SERIES`]
+    fn counts(&self) -> [i64; 6] {
+        [
+            self.contract_creation,
+            self.token_transfer,
+            self.contract_call,
+            self.coin_transfer,
+            self.legacy,
+            self.eip1559,
+        ]
+    }
+
+    fn total(&self) -> i64 {
+        self.contract_creation + self.token_transfer + self.contract_call + self.coin_transfer
+    }
+}
+
+async fn read_txns_by_type(
+    blockscout: &DatabaseConnection,
+    after: Option<NaiveDate>,
+) -> Result<Vec<TxnsByTypeValue>, UpdateError> {
+    let stmnt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        WITH txns AS (
+            SELECT
+                DATE(b.timestamp) as date,
+                CASE
+                    WHEN t.to_address_hash IS NULL OR t.created_contract_address_hash IS NOT NULL
+                        THEN 'contract_creation'
+                    WHEN tt.transaction_hash IS NOT NULL THEN 'token_transfer'
+                    WHEN length(t.input) > 0 THEN 'contract_call'
+                    ELSE 'coin_transfer'
+                END as purpose,
+                t.type as type
+            FROM transactions t
+            JOIN blocks       b ON t.block_hash = b.hash
+            LEFT JOIN (
+                SELECT DISTINCT tt.transaction_hash
+                FROM token_transfers tt
+                JOIN blocks          tb ON tt.block_hash = tb.hash
+                WHERE
+                    tb.timestamp != to_timestamp(0) AND
+                    ($1::date IS NULL OR DATE(tb.timestamp) > $1) AND
+                    tb.consensus = true
+            ) tt ON tt.transaction_hash = t.hash
+            WHERE
+                b.timestamp != to_timestamp(0) AND
+                ($1::date IS NULL OR DATE(b.timestamp) > $1) AND
+                b.consensus = true
+        )
+        SELECT
+            date,
+            COUNT(*) FILTER (WHERE purpose = 'contract_creation') as contract_creation,
+            COUNT(*) FILTER (WHERE purpose = 'token_transfer') as token_transfer,
+            COUNT(*) FILTER (WHERE purpose = 'contract_call') as contract_call,
+            COUNT(*) FILTER (WHERE purpose = 'coin_transfer') as coin_transfer,
+            COUNT(*) FILTER (WHERE type IS NULL OR type = 0) as legacy,
+            COUNT(*) FILTER (WHERE type = 2) as eip1559
+        FROM txns
+        GROUP BY date
+        "#,
+        vec![after.into()],
+    );
+
+    TxnsByTypeValue::find_by_statement(stmnt)
+        .all(blockscout)
+        .await
+        .map_err(UpdateError::blockscout)
+}
+
+/// Number of transactions per day split by type of transaction.
+/// The chart stores the total, every type is stored as its series, see [`TXN_SERIES`]
+#[derive(Default, Debug)]
+pub struct NewTxnsByType {}
+
+#[async_trait]
+impl crate::Chart for NewTxnsByType {
+    fn name(&self) -> &str {
+        "newTxnsByType"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+    fn series(&self) -> &[&str] {
+        &TXN_SERIES
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        let chart_id = find_chart(db, self.name())
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
+        let min_blockscout_block = get_min_block_blockscout(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?;
+        let last_row = get_last_row(
+            self,
+            chart_id,
+            min_blockscout_block,
+            db,
+            params.force_full,
+            params.recompute_window_days,
+        )
+        .await?;
+        let data = {
+            let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
+                .with_label_values(&[&metrics::chain_id(), self.name()])
+                .start_timer();
+            read_txns_by_type(blockscout, last_row.map(|row| row.date)).await?
+        };
+
+        let point = |date, value: i64| DateValue {
+            date,
+            value: value.into(),
+        };
+        let total: Vec<_> = data
+            .iter()
+            .map(|value| {
+                point(value.date, value.total()).active_model(chart_id, Some(min_blockscout_block))
+            })
+            .collect();
+
+        // the total and its series are written together, so they always agree
+        let txn = db.begin().await.map_err(UpdateError::StatsDB)?;
+        let mut rows = 0;
+        for batch in total.chunks(INSERT_BATCH_ROWS) {
+            rows += insert_data_many(&txn, batch.to_vec())
+                .await
+                .map_err(UpdateError::StatsDB)?;
+        }
+        for (i, key) in TXN_SERIES.iter().enumerate() {
+            let series: Vec<_> = data
+                .iter()
+                .map(|value| point(value.date, value.counts()[i]))
+                .collect();
+            rows += insert_series_data(&txn, chart_id, key, &series, Some(min_blockscout_block))
+                .await
+                .map_err(UpdateError::StatsDB)?;
+        }
+        txn.commit().await.map_err(UpdateError::StatsDB)?;
+        metrics::CHART_ROWS_WRITTEN
+            .with_label_values(&[&metrics::chain_id(), self.name()])
+            .inc_by(rows);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NewTxnsByType;
+    use crate::tests::simple_test::series_test_chart;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_txns_by_type() {
+        let chart = NewTxnsByType::default();
+        let dates = [
+            "2022-11-09",
+            "2022-11-10",
+            "2022-11-11",
+            "2022-11-12",
+            "2022-12-01",
+            "2023-01-01",
+            "2023-02-01",
+            "2023-03-01",
+        ];
+        let series = |values: [&'static str; 8]| dates.into_iter().zip(values).collect();
+        series_test_chart(
+            "update_new_txns_by_type",
+            chart,
+            series(["5", "12", "14", "5", "5", "1", "4", "1"]),
+            vec![
+                (
+                    "contractCreation",
+                    series(["2", "6", "8", "2", "2", "1", "1", "1"]),
+                ),
+                (
+                    "tokenTransfer",
+                    series(["1", "2", "2", "1", "1", "0", "1", "0"]),
+                ),
+                (
+                    "contractCall",
+                    series(["0", "0", "0", "0", "0", "0", "0", "0"]),
+                ),
+                (
+                    "coinTransfer",
+                    series(["2", "4", "4", "2", "2", "0", "2", "0"]),
+                ),
+                // mock transactions have no type
+                ("legacy", series(["5", "12", "14", "5", "5", "1", "4", "1"])),
+                ("eip1559", series(["0", "0", "0", "0", "0", "0", "0", "0"])),
+            ],
+        )
+        .await;
+    }
+}
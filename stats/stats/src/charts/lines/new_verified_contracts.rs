@@ -1,8 +1,9 @@
 use crate::{
     charts::{insert::DateValue, updater::ChartPartialUpdater},
-    UpdateError,
+    UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
@@ -15,33 +16,36 @@ impl ChartPartialUpdater for NewVerifiedContracts {
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 r#"SELECT
                     DATE(smart_contracts.inserted_at) as date,
-                    COUNT(*)::TEXT as value
+                    COUNT(*)::NUMERIC as value
                 FROM smart_contracts
                 WHERE DATE(smart_contracts.inserted_at) > $1
+                    AND ($2::date IS NULL OR DATE(smart_contracts.inserted_at) <= $2)
                 GROUP BY DATE(smart_contracts.inserted_at)"#,
-                vec![row.date.into()],
+                vec![row.date.into(), to.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 r#"SELECT
                     DATE(smart_contracts.inserted_at) as date,
-                    COUNT(*)::TEXT as value
+                    COUNT(*)::NUMERIC as value
                 FROM smart_contracts
+                WHERE ($1::date IS NULL OR DATE(smart_contracts.inserted_at) <= $1)
                 GROUP BY DATE(smart_contracts.inserted_at)"#,
-                vec![],
+                vec![to.into()],
             ),
         };
 
         let data = DateValue::find_by_statement(stmnt)
             .all(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
+            .map_err(UpdateError::blockscout)?;
         Ok(data)
     }
 }
@@ -60,16 +64,20 @@ impl crate::Chart for NewVerifiedContracts {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::NewVerifiedContracts;
-    use crate::tests::simple_test::simple_test_chart;
+    use crate::tests::simple_test::{ranged_test_chart, simple_test_chart};
 
     #[tokio::test]
     #[ignore = "needs database to run"]
@@ -86,4 +94,24 @@ mod tests {
         )
         .await;
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn ranged_update_new_verified_contracts() {
+        let chart = NewVerifiedContracts::default();
+        ranged_test_chart(
+            "ranged_update_new_verified_contracts",
+            chart,
+            vec![
+                ("2022-11-13", "0"),
+                ("2022-11-14", "1"),
+                ("2022-11-15", "1"),
+                ("2022-11-16", "1"),
+                ("2022-11-17", "0"),
+            ],
+            "2022-11-13".parse().unwrap(),
+            "2022-11-17".parse().unwrap(),
+        )
+        .await;
+    }
 }
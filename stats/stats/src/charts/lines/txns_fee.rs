@@ -1,18 +1,26 @@
+use super::txns_stats::{float_points, get_txns_stats, TxnsStatsCache};
 use crate::{
-    charts::{
-        insert::{DateValue, DateValueDouble},
-        updater::ChartPartialUpdater,
-    },
-    UpdateError,
+    charts::{insert::DateValue, updater::ChartPartialUpdater},
+    Unit, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
-use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+use sea_orm::prelude::*;
+use tokio::sync::Mutex;
 
 #[derive(Default, Debug)]
-pub struct TxnsFee {}
+pub struct TxnsFee {
+    cache: Mutex<TxnsStatsCache>,
+}
 
-const ETHER: i64 = i64::pow(10, 18);
+impl TxnsFee {
+    pub fn new(cache: TxnsStatsCache) -> Self {
+        Self {
+            cache: Mutex::new(cache),
+        }
+    }
+}
 
 #[async_trait]
 impl ChartPartialUpdater for TxnsFee {
@@ -20,49 +28,10 @@ impl ChartPartialUpdater for TxnsFee {
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
-        let stmnt = match last_row {
-            Some(row) => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                SELECT 
-                    DATE(b.timestamp) as date, 
-                    (SUM(t.gas_used * t.gas_price) / $1)::FLOAT as value
-                FROM transactions t
-                JOIN blocks       b ON t.block_hash = b.hash
-                WHERE
-                    b.timestamp != to_timestamp(0) AND
-                    DATE(b.timestamp) > $2 AND
-                    b.consensus = true
-                GROUP BY DATE(b.timestamp)
-                "#,
-                vec![ETHER.into(), row.date.into()],
-            ),
-            None => Statement::from_sql_and_values(
-                DbBackend::Postgres,
-                r#"
-                SELECT 
-                    DATE(b.timestamp) as date, 
-                    (SUM(t.gas_used * t.gas_price) / $1)::FLOAT as value
-                FROM transactions t
-                JOIN blocks       b ON t.block_hash = b.hash
-                WHERE
-                    b.timestamp != to_timestamp(0) AND 
-                    b.consensus = true
-                GROUP BY DATE(b.timestamp)
-                "#,
-                vec![ETHER.into()],
-            ),
-        };
-
-        let data = DateValueDouble::find_by_statement(stmnt)
-            .all(blockscout)
-            .await
-            .map_err(UpdateError::BlockscoutDB)?
-            .into_iter()
-            .map(DateValue::from)
-            .collect::<Vec<_>>();
-        Ok(data)
+        let data = get_txns_stats(&self.cache, blockscout, last_row, to).await?;
+        float_points(data, |stats| stats.fee_sum)
     }
 }
 
@@ -75,14 +44,21 @@ impl crate::Chart for TxnsFee {
     fn chart_type(&self) -> ChartType {
         ChartType::Line
     }
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::NativeCoin)
+    }
 
     async fn update(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
@@ -4,9 +4,9 @@ use crate::{
         chart::Chart,
         create_chart,
         insert::DateValue,
-        updater::{parse_and_growth, ChartDependentUpdater},
+        updater::{growth, ChartDependentUpdater},
     },
-    MissingDatePolicy, UpdateError,
+    MissingDatePolicy, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use entity::sea_orm_active_enums::ChartType;
@@ -31,7 +31,7 @@ impl ChartDependentUpdater<NewTxns> for TxnsGrowth {
     }
 
     async fn get_values(&self, parent_data: Vec<DateValue>) -> Result<Vec<DateValue>, UpdateError> {
-        parse_and_growth::<i64>(parent_data, self.parent.name())
+        Ok(growth(parent_data))
     }
 }
 
@@ -43,6 +43,9 @@ impl crate::Chart for TxnsGrowth {
     fn chart_type(&self) -> ChartType {
         ChartType::Line
     }
+    fn dependencies(&self) -> Vec<&str> {
+        vec![self.parent.name()]
+    }
     fn missing_date_policy(&self) -> MissingDatePolicy {
         MissingDatePolicy::FillPrevious
     }
@@ -59,9 +62,9 @@ impl crate::Chart for TxnsGrowth {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await?;
+        self.update_with_values(db, blockscout, params).await?;
         Ok(())
     }
 }
@@ -0,0 +1,104 @@
+use crate::{
+    charts::{
+        cache::SharedCache,
+        insert::{DateValue, DateValueDouble},
+    },
+    Unit, UpdateError,
+};
+use chrono::NaiveDate;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+/// Daily aggregates of `transactions JOIN blocks` used by several charts
+#[derive(FromQueryResult, Debug, Clone)]
+pub struct TxnsStatsValue {
+    pub date: NaiveDate,
+    pub count: i64,
+    pub fee_sum: Option<f64>,
+    pub fee_average: Option<f64>,
+}
+
+/// Stats of days after the first key date up to the second one, unbounded if they are `None`
+pub type TxnsStatsCache = SharedCache<(Option<NaiveDate>, Option<NaiveDate>), Vec<TxnsStatsValue>>;
+
+pub async fn read_txns_stats(
+    blockscout: &DatabaseConnection,
+    after: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<TxnsStatsValue>, UpdateError> {
+    let stmnt = match after {
+        Some(date) => Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                DATE(b.timestamp) as date,
+                COUNT(*) as count,
+                (SUM(t.gas_used * t.gas_price) / $1)::FLOAT as fee_sum,
+                (AVG(t.gas_used * t.gas_price) / $1)::FLOAT as fee_average
+            FROM transactions t
+            JOIN blocks       b ON t.block_hash = b.hash
+            WHERE
+                b.timestamp != to_timestamp(0) AND
+                DATE(b.timestamp) > $2 AND
+                ($3::date IS NULL OR DATE(b.timestamp) <= $3) AND
+                b.consensus = true
+            GROUP BY DATE(b.timestamp)
+            "#,
+            vec![Unit::NativeCoin.wei().into(), date.into(), to.into()],
+        ),
+        None => Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                DATE(b.timestamp) as date,
+                COUNT(*) as count,
+                (SUM(t.gas_used * t.gas_price) / $1)::FLOAT as fee_sum,
+                (AVG(t.gas_used * t.gas_price) / $1)::FLOAT as fee_average
+            FROM transactions t
+            JOIN blocks       b ON t.block_hash = b.hash
+            WHERE
+                b.timestamp != to_timestamp(0) AND
+                ($2::date IS NULL OR DATE(b.timestamp) <= $2) AND
+                b.consensus = true
+            GROUP BY DATE(b.timestamp)
+            "#,
+            vec![Unit::NativeCoin.wei().into(), to.into()],
+        ),
+    };
+
+    TxnsStatsValue::find_by_statement(stmnt)
+        .all(blockscout)
+        .await
+        .map_err(UpdateError::blockscout)
+}
+
+/// Reads stats of days after `last_row` up to `to` through the cache shared between charts
+pub async fn get_txns_stats(
+    cache: &tokio::sync::Mutex<TxnsStatsCache>,
+    blockscout: &DatabaseConnection,
+    last_row: Option<DateValue>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<TxnsStatsValue>, UpdateError> {
+    let after = last_row.map(|row| row.date);
+    let mut cache = cache.lock().await;
+    cache
+        .get_or_update((after, to), async move {
+            read_txns_stats(blockscout, after, to).await
+        })
+        .await
+}
+
+/// Converts one of float stats into chart points, days without value are skipped
+pub fn float_points(
+    data: Vec<TxnsStatsValue>,
+    value: impl Fn(&TxnsStatsValue) -> Option<f64>,
+) -> Result<Vec<DateValue>, UpdateError> {
+    data.into_iter()
+        .filter_map(|stats| {
+            value(&stats).map(|value| DateValueDouble {
+                date: stats.date,
+                value,
+            })
+        })
+        .map(DateValue::try_from)
+        .collect()
+}
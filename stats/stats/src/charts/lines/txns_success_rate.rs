@@ -3,12 +3,15 @@ use crate::{
         insert::{DateValue, DateValueDouble},
         updater::ChartPartialUpdater,
     },
-    UpdateError,
+    AggregationPolicy, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
+use chrono::NaiveDate;
 use entity::sea_orm_active_enums::ChartType;
 use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
 
+/// Ratio of transactions succeeded according to their receipt status
+/// among all mined transactions of the day
 #[derive(Default, Debug)]
 pub struct TxnsSuccessRate {}
 
@@ -18,6 +21,7 @@ impl ChartPartialUpdater for TxnsSuccessRate {
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError> {
         let stmnt = match last_row {
             Some(row) => Statement::from_sql_and_values(
@@ -25,7 +29,7 @@ impl ChartPartialUpdater for TxnsSuccessRate {
                 r#"
                 SELECT 
                     DATE(b.timestamp) as date, 
-                    COUNT(CASE WHEN t.error IS NULL THEN 1 END)::FLOAT
+                    COUNT(CASE WHEN t.status = 1 THEN 1 END)::FLOAT
                         / COUNT(*)::FLOAT as value
                 FROM transactions t
                 JOIN blocks       b ON t.block_hash = b.hash
@@ -33,39 +37,43 @@ impl ChartPartialUpdater for TxnsSuccessRate {
                     b.timestamp != to_timestamp(0) AND
                     b.consensus = true AND
                     t.block_hash IS NOT NULL AND 
+                    t.status IS NOT NULL AND
                     (t.error IS NULL OR t.error::text != 'dropped/replaced') AND
-                    DATE(b.timestamp) > $1
+                    DATE(b.timestamp) > $1 AND
+                    ($2::date IS NULL OR DATE(b.timestamp) <= $2)
                 GROUP BY DATE(b.timestamp)
                 "#,
-                vec![row.date.into()],
+                vec![row.date.into(), to.into()],
             ),
             None => Statement::from_sql_and_values(
                 DbBackend::Postgres,
                 r#"
                 SELECT 
                     DATE(b.timestamp) as date, 
-                    COUNT(CASE WHEN t.error IS NULL THEN 1 END)::FLOAT
+                    COUNT(CASE WHEN t.status = 1 THEN 1 END)::FLOAT
                         / COUNT(*)::FLOAT as value
                 FROM transactions t
                 JOIN blocks       b ON t.block_hash = b.hash
                 WHERE 
+                    ($1::date IS NULL OR DATE(b.timestamp) <= $1) AND
                     b.timestamp != to_timestamp(0) AND
                     b.consensus = true AND
                     t.block_hash IS NOT NULL AND
+                    t.status IS NOT NULL AND
                     (t.error IS NULL OR t.error::text != 'dropped/replaced')
                 GROUP BY DATE(b.timestamp)
                 "#,
-                vec![],
+                vec![to.into()],
             ),
         };
 
         let data = DateValueDouble::find_by_statement(stmnt)
             .all(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?
+            .map_err(UpdateError::blockscout)?
             .into_iter()
-            .map(DateValue::from)
-            .collect::<Vec<_>>();
+            .map(DateValue::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
         Ok(data)
     }
 }
@@ -80,38 +88,93 @@ impl crate::Chart for TxnsSuccessRate {
         ChartType::Line
     }
 
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        AggregationPolicy::Average
+    }
+
     async fn update(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
-        self.update_with_values(db, blockscout, force_full).await
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::TxnsSuccessRate;
-    use crate::tests::simple_test::simple_test_chart;
+    use super::*;
+    use crate::{
+        get_chart_data,
+        tests::{
+            init_db::init_db_all,
+            mock_blockscout::{fill_mock_blockscout_data, mock_failed_transaction},
+        },
+        Chart,
+    };
+    use blockscout_db::entity::{blocks, transactions};
+    use pretty_assertions::assert_eq;
+    use sea_orm::{IntoActiveModel, Set};
 
     #[tokio::test]
     #[ignore = "needs database to run"]
     async fn update_txns_success_rate() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("update_txns_success_rate").await;
         let chart = TxnsSuccessRate::default();
-        simple_test_chart(
-            "update_txns_success_rate",
-            chart,
-            vec![
-                ("2022-11-09", "1"),
-                ("2022-11-10", "1"),
-                ("2022-11-11", "1"),
-                ("2022-11-12", "1"),
-                ("2022-12-01", "1"),
-                ("2023-01-01", "1"),
-                ("2023-02-01", "1"),
-            ],
-        )
-        .await;
+        chart.create(&db).await.unwrap();
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+        // the only transaction of 2023-01-01 is accompanied by a reverted one
+        let block = blocks::Entity::find()
+            .filter(blocks::Column::Number.eq(10))
+            .one(&blockscout)
+            .await
+            .unwrap()
+            .unwrap()
+            .into_active_model();
+        let mut reverted =
+            mock_failed_transaction(vec![123, 23], Some(&block), Some("Reverted".into()));
+        reverted.index = Set(Some(100));
+        transactions::Entity::insert(reverted)
+            .exec(&blockscout)
+            .await
+            .unwrap();
+
+        let expected = vec![
+            ("2022-11-09", "1"),
+            ("2022-11-10", "1"),
+            ("2022-11-11", "1"),
+            ("2022-11-12", "1"),
+            ("2022-12-01", "1"),
+            ("2023-01-01", "0.5"),
+            ("2023-02-01", "1"),
+        ];
+        for force_full in [true, false] {
+            chart
+                .update(
+                    &db,
+                    &blockscout,
+                    UpdateParameters::with_force_full(force_full),
+                )
+                .await
+                .unwrap();
+            let data: Vec<_> = get_chart_data(&db, chart.name(), None, None, None)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|p| (p.date.to_string(), p.value.to_string()))
+                .collect();
+            let data: Vec<_> = data
+                .iter()
+                .map(|(date, value)| (date.as_str(), value.as_str()))
+                .collect();
+            assert_eq!(data, expected);
+        }
     }
 }
@@ -0,0 +1,124 @@
+//! Charts of native coin market data, which is not stored in blockscout
+//! and is pulled from external source instead
+
+mod native_coin_market_cap;
+mod native_coin_price;
+
+pub use native_coin_market_cap::NativeCoinMarketCap;
+pub use native_coin_price::NativeCoinPrice;
+
+use super::{
+    cache::SharedCache,
+    find_chart,
+    insert::{insert_data_many, DateValue, DateValueDouble},
+    updater::get_row_before,
+};
+use crate::{metrics, UpdateError, UpdateParameters};
+use async_trait::async_trait;
+use chrono::{NaiveDate, Utc};
+use sea_orm::DatabaseConnection;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/// Market data of the native coin at the end of `date`
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarketPoint {
+    pub date: NaiveDate,
+    pub price: Option<f64>,
+    pub market_cap: Option<f64>,
+}
+
+#[async_trait]
+pub trait MarketDataSource: Send + Sync {
+    /// Daily points starting from `since`, whole available history if it's `None`
+    async fn daily_points(&self, since: Option<NaiveDate>)
+        -> Result<Vec<MarketPoint>, UpdateError>;
+}
+
+/// Points fetched by one market chart are reused by others in the same update cycle
+pub type MarketDataCache = SharedCache<Option<NaiveDate>, Vec<MarketPoint>>;
+
+/// Names of all market charts
+pub const CHARTS: [&str; 2] = ["nativeCoinPrice", "nativeCoinMarketCap"];
+
+/// Stores `value` of points fetched from `source`. Stored points before today
+/// are kept by partial update, only history since the last of them is fetched
+async fn update_market_chart(
+    db: &DatabaseConnection,
+    name: &str,
+    source: &Arc<dyn MarketDataSource>,
+    cache: &Mutex<MarketDataCache>,
+    params: UpdateParameters,
+    value: impl Fn(&MarketPoint) -> Option<f64>,
+) -> Result<(), UpdateError> {
+    let chart_id = find_chart(db, name)
+        .await
+        .map_err(UpdateError::StatsDB)?
+        .ok_or_else(|| UpdateError::NotFound(name.into()))?;
+    let since = if params.force_full {
+        None
+    } else {
+        get_row_before(db, chart_id, Utc::now().date_naive())
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .map(|row| row.date)
+    };
+    let points = {
+        let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
+            .with_label_values(&[&metrics::chain_id(), name])
+            .start_timer();
+        let mut cache = cache.lock().await;
+        cache
+            .get_or_update(since, source.daily_points(since))
+            .await?
+    };
+    let values = points
+        .iter()
+        .filter_map(|point| {
+            value(point).map(|value| DateValueDouble {
+                date: point.date,
+                value,
+            })
+        })
+        .map(|point| DateValue::try_from(point).map(|point| point.active_model(chart_id, None)))
+        .collect::<Result<Vec<_>, _>>()?;
+    let rows = insert_data_many(db, values)
+        .await
+        .map_err(UpdateError::StatsDB)?;
+    metrics::CHART_ROWS_WRITTEN
+        .with_label_values(&[&metrics::chain_id(), name])
+        .inc_by(rows);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    /// Source with points of the first days of 2023
+    pub struct MockSource;
+
+    #[async_trait]
+    impl MarketDataSource for MockSource {
+        async fn daily_points(
+            &self,
+            since: Option<NaiveDate>,
+        ) -> Result<Vec<MarketPoint>, UpdateError> {
+            let points = [
+                ("2023-01-01", Some(1200.5), Some(145_000_000_000.0)),
+                ("2023-01-02", Some(1215.25), None),
+                ("2023-01-03", None, Some(148_000_000_000.0)),
+            ];
+            Ok(points
+                .into_iter()
+                .map(|(date, price, market_cap)| MarketPoint {
+                    date: NaiveDate::from_str(date).unwrap(),
+                    price,
+                    market_cap,
+                })
+                .filter(|point| since.map_or(true, |since| point.date >= since))
+                .collect())
+        }
+    }
+}
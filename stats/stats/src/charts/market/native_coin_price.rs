@@ -0,0 +1,90 @@
+use super::{update_market_chart, MarketDataCache, MarketDataSource};
+use crate::{AggregationPolicy, MissingDatePolicy, UpdateError, UpdateParameters};
+use async_trait::async_trait;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+pub struct NativeCoinPrice {
+    source: Arc<dyn MarketDataSource>,
+    cache: Mutex<MarketDataCache>,
+}
+
+impl NativeCoinPrice {
+    pub fn new(source: Arc<dyn MarketDataSource>, cache: MarketDataCache) -> Self {
+        Self {
+            source,
+            cache: Mutex::new(cache),
+        }
+    }
+}
+
+#[async_trait]
+impl crate::Chart for NativeCoinPrice {
+    fn name(&self) -> &str {
+        "nativeCoinPrice"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    fn missing_date_policy(&self) -> MissingDatePolicy {
+        MissingDatePolicy::FillPrevious
+    }
+
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        AggregationPolicy::Average
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        _blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        update_market_chart(
+            db,
+            self.name(),
+            &self.source,
+            &self.cache,
+            params,
+            |point| point.price,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        charts::market::tests::MockSource, get_chart_data, tests::init_db::init_db, Chart,
+    };
+    use pretty_assertions::assert_eq;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_native_coin_price() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let db = init_db("update_native_coin_price").await;
+        let chart = NativeCoinPrice::new(Arc::new(MockSource), Default::default());
+        chart.create(&db).await.unwrap();
+
+        chart
+            .update(&db, &db, UpdateParameters::default())
+            .await
+            .unwrap();
+        let data: Vec<_> = get_chart_data(&db, chart.name(), None, None, None)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|point| (point.date.to_string(), point.value.to_string()))
+            .collect();
+        // day without price is not stored
+        let expected = [("2023-01-01", "1200.5"), ("2023-01-02", "1215.25")]
+            .map(|(date, value)| (date.to_string(), value.to_string()));
+        assert_eq!(data, expected);
+    }
+}
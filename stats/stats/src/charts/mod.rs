@@ -1,9 +1,30 @@
 pub mod cache;
 mod chart;
+mod counter_snapshots;
 pub mod counters;
+mod custom_sql;
 pub mod insert;
 pub mod lines;
+pub mod market;
+mod moving_average;
 mod mutex;
+mod periods;
+mod realtime;
+mod retry;
+pub mod rollup;
+mod series;
 pub mod updater;
+pub mod user_ops;
 
-pub use chart::{create_chart, find_chart, Chart, MissingDatePolicy, UpdateError};
+pub use chart::{
+    create_chart, find_chart, save_update_attempt, save_update_run, set_last_updated_at,
+    AggregationPolicy, Chart, MissingDatePolicy, ResolutionKind, Unit, UpdateAttempt, UpdateError,
+    UpdateParameters,
+};
+pub use counter_snapshots::CounterSnapshots;
+pub use custom_sql::CustomSqlChart;
+pub use moving_average::moving_average_name;
+pub use periods::resolution_name;
+pub use realtime::{refresh_counter, RealtimeCursor};
+pub use retry::{with_retries, RetryPolicy};
+pub use series::{insert_series_data, INSERT_BATCH_ROWS};
@@ -0,0 +1,135 @@
+use super::{
+    chart::{create_chart, set_last_updated_at},
+    find_chart,
+    insert::{insert_data_many, DateValue},
+};
+use crate::{get_chart_data, metrics, Chart, UpdateError};
+use chrono::Duration;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::prelude::*;
+
+/// Name of the series with moving average of chart `name` over `window` days
+pub fn moving_average_name(name: &str, window: u32) -> String {
+    format!("{name}Ma{window}")
+}
+
+/// Every point is an average of points dated by the last `window` days.
+/// Series starts from the first date with full window of history
+pub fn moving_average(data: &[DateValue], window: u32) -> Vec<DateValue> {
+    let Some(first) = data.first().filter(|_| window > 0) else {
+        return vec![];
+    };
+    let start = first.date + Duration::days(i64::from(window) - 1);
+    let mut result = Vec::new();
+    let mut window_start = 0;
+    let mut sum = Decimal::ZERO;
+    for (i, point) in data.iter().enumerate() {
+        sum += point.value;
+        while data[window_start].date + Duration::days(window.into()) <= point.date {
+            sum -= data[window_start].value;
+            window_start += 1;
+        }
+        if point.date >= start {
+            let count = Decimal::from(i - window_start + 1);
+            result.push(DateValue {
+                date: point.date,
+                value: (sum / count).normalize(),
+            });
+        }
+    }
+    result
+}
+
+/// Recomputes moving averages declared by `chart` from its stored points
+pub async fn update_moving_averages<C>(
+    db: &DatabaseConnection,
+    chart: &C,
+) -> Result<(), UpdateError>
+where
+    C: Chart + ?Sized,
+{
+    let windows = chart.moving_average_windows();
+    if windows.is_empty() {
+        return Ok(());
+    }
+    let data = get_chart_data(
+        db,
+        chart.name(),
+        None,
+        None,
+        Some(chart.missing_date_policy()),
+    )
+    .await?;
+    for &window in windows {
+        let name = moving_average_name(chart.name(), window);
+        create_chart(db, name.clone(), ChartType::Line)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        let chart_id = find_chart(db, &name)
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .ok_or_else(|| UpdateError::NotFound(name.clone()))?;
+        let values = moving_average(&data, window)
+            .into_iter()
+            .map(|point| point.active_model(chart_id, None));
+        let rows = insert_data_many(db, values)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        metrics::CHART_ROWS_WRITTEN
+            .with_label_values(&[&metrics::chain_id(), &name])
+            .inc_by(rows);
+        set_last_updated_at(db, &name)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn v(date: &str, value: &str) -> DateValue {
+        DateValue {
+            date: date.parse().unwrap(),
+            value: value.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn moving_average_works() {
+        let data = vec![
+            v("2022-11-09", "5"),
+            v("2022-11-10", "12"),
+            v("2022-11-11", "1"),
+            v("2022-11-12", "2"),
+            v("2022-11-14", "9"),
+        ];
+        for (window, expected) in [
+            (1, data.clone()),
+            (
+                2,
+                vec![
+                    v("2022-11-10", "8.5"),
+                    v("2022-11-11", "6.5"),
+                    v("2022-11-12", "1.5"),
+                    v("2022-11-14", "9"),
+                ],
+            ),
+            (
+                3,
+                vec![
+                    v("2022-11-11", "6"),
+                    v("2022-11-12", "5"),
+                    v("2022-11-14", "5.5"),
+                ],
+            ),
+            (7, vec![]),
+        ] {
+            assert_eq!(expected, moving_average(&data, window), "window {window}");
+        }
+        assert_eq!(moving_average(&data, 0), vec![]);
+        assert_eq!(moving_average(&[], 7), vec![]);
+    }
+}
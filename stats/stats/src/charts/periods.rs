@@ -0,0 +1,191 @@
+use super::{
+    chart::create_chart,
+    find_chart,
+    insert::{insert_data_many, DateValue},
+};
+use crate::{metrics, Chart, ResolutionKind, UpdateError, UpdateParameters};
+use chrono::NaiveDate;
+use entity::{chart_data, sea_orm_active_enums::ChartType};
+use sea_orm::{prelude::*, DbBackend, QueryOrder, Statement};
+
+/// Resolutions stored separately for charts with [`Chart::period_sql`]
+pub const STORED_RESOLUTIONS: [ResolutionKind; 3] = [
+    ResolutionKind::Week,
+    ResolutionKind::Month,
+    ResolutionKind::Year,
+];
+
+/// Name of the line chart with points of chart `name` in `resolution`, see [`Chart::period_sql`]
+pub fn resolution_name(name: &str, resolution: ResolutionKind) -> String {
+    match resolution {
+        ResolutionKind::Day => name.to_string(),
+        ResolutionKind::Week => format!("{name}Weekly"),
+        ResolutionKind::Month => format!("{name}Monthly"),
+        ResolutionKind::Year => format!("{name}Yearly"),
+    }
+}
+
+pub async fn create_periods<C>(db: &DatabaseConnection, chart: &C) -> Result<(), DbErr>
+where
+    C: Chart + ?Sized,
+{
+    if chart.period_sql().is_none() {
+        return Ok(());
+    }
+    for resolution in STORED_RESOLUTIONS {
+        create_chart(
+            db,
+            resolution_name(chart.name(), resolution),
+            ChartType::Line,
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+/// Recomputes stored resolutions of `chart` from the last stored period,
+/// which can be partial. All periods are recomputed if `params.force_full` is set
+pub async fn update_periods<C>(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    chart: &C,
+    params: UpdateParameters,
+) -> Result<(), UpdateError>
+where
+    C: Chart + ?Sized,
+{
+    if chart.period_sql().is_none() {
+        return Ok(());
+    }
+    for resolution in STORED_RESOLUTIONS {
+        let chart_id = period_chart_id(db, chart, resolution).await?;
+        let from = if params.force_full {
+            None
+        } else {
+            chart_data::Entity::find()
+                .filter(chart_data::Column::ChartId.eq(chart_id))
+                .order_by_desc(chart_data::Column::Date)
+                .one(db)
+                .await
+                .map_err(UpdateError::StatsDB)?
+                .map(|row| row.date)
+        };
+        update_period(db, blockscout, chart, resolution, chart_id, from, None).await?;
+    }
+    Ok(())
+}
+
+/// Recomputes stored periods of `chart` overlapping `[from, to]`
+pub async fn repair_periods<C>(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    chart: &C,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Result<(), UpdateError>
+where
+    C: Chart + ?Sized,
+{
+    if chart.period_sql().is_none() {
+        return Ok(());
+    }
+    for resolution in STORED_RESOLUTIONS {
+        let chart_id = period_chart_id(db, chart, resolution).await?;
+        update_period(
+            db,
+            blockscout,
+            chart,
+            resolution,
+            chart_id,
+            Some(resolution.period_start(from)),
+            Some(resolution.period_end(to)),
+        )
+        .await?;
+    }
+    Ok(())
+}
+
+async fn period_chart_id<C>(
+    db: &DatabaseConnection,
+    chart: &C,
+    resolution: ResolutionKind,
+) -> Result<i32, UpdateError>
+where
+    C: Chart + ?Sized,
+{
+    let name = resolution_name(chart.name(), resolution);
+    find_chart(db, &name)
+        .await
+        .map_err(UpdateError::StatsDB)?
+        .ok_or(UpdateError::NotFound(name))
+}
+
+async fn update_period<C>(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    chart: &C,
+    resolution: ResolutionKind,
+    chart_id: i32,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<(), UpdateError>
+where
+    C: Chart + ?Sized,
+{
+    let Some(sql) = chart.period_sql() else {
+        return Ok(());
+    };
+    let name = resolution_name(chart.name(), resolution);
+    let values = {
+        let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
+            .with_label_values(&[&metrics::chain_id(), &name])
+            .start_timer();
+        DateValue::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            vec![resolution.date_trunc_field().into(), from.into(), to.into()],
+        ))
+        .all(blockscout)
+        .await
+        .map_err(UpdateError::blockscout)?
+    };
+    let rows = insert_data_many(
+        db,
+        values
+            .into_iter()
+            .map(|value| value.active_model(chart_id, None)),
+    )
+    .await
+    .map_err(UpdateError::StatsDB)?;
+    metrics::CHART_ROWS_WRITTEN
+        .with_label_values(&[&metrics::chain_id(), &name])
+        .inc_by(rows);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolution_names_are_distinct() {
+        assert_eq!(
+            resolution_name("activeAccounts", ResolutionKind::Day),
+            "activeAccounts"
+        );
+        let mut names: Vec<_> = STORED_RESOLUTIONS
+            .iter()
+            .map(|resolution| resolution_name("activeAccounts", *resolution))
+            .collect();
+        names.sort();
+        names.dedup();
+        assert_eq!(
+            names,
+            [
+                "activeAccountsMonthly",
+                "activeAccountsWeekly",
+                "activeAccountsYearly"
+            ]
+        );
+    }
+}
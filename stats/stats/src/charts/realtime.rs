@@ -0,0 +1,118 @@
+use super::{
+    find_chart,
+    insert::{insert_data_many, DateValue},
+    mutex::get_global_update_mutex,
+};
+use crate::{metrics, Chart, UpdateError};
+use chrono::NaiveDate;
+use entity::{chart_data, charts};
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, QueryOrder, QuerySelect, Statement};
+
+/// Position up to which realtime refreshes are added to the stored counter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RealtimeCursor {
+    /// Last block counted in the stored value
+    pub block: i64,
+    /// `last_updated_at` of the chart when the cursor was set. Scheduled update
+    /// overwrites the value, so the cursor is valid only while it doesn't change
+    pub updated_at: Option<DateTimeWithTimeZone>,
+}
+
+#[derive(FromQueryResult)]
+struct Increment {
+    date: Option<NaiveDate>,
+    value: Decimal,
+}
+
+#[derive(FromQueryResult)]
+struct LastUpdatedAt {
+    last_updated_at: Option<DateTimeWithTimeZone>,
+}
+
+/// Adds blocks in `(cursor.block, to_block]` to the stored value of counter
+/// with [`Chart::increment_sql`] and returns the cursor for the next refresh.
+///
+/// Without valid cursor nothing is added, the cursor is just set to `to_block`.
+/// Blocks indexed between the last scheduled update and `to_block` are counted
+/// by the next scheduled update
+pub async fn refresh_counter(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    chart: &dyn Chart,
+    cursor: Option<RealtimeCursor>,
+    to_block: i64,
+) -> Result<RealtimeCursor, UpdateError> {
+    let name = chart.name();
+    let sql = chart.increment_sql().ok_or_else(|| {
+        UpdateError::Internal(format!("counter {name} can't be refreshed incrementally"))
+    })?;
+    let mutex = get_global_update_mutex(&metrics::chain_id(), name).await;
+    let _permit = mutex.lock().await;
+
+    let updated_at = charts::Entity::find()
+        .select_only()
+        .column(charts::Column::LastUpdatedAt)
+        .filter(charts::Column::Name.eq(name))
+        .into_model::<LastUpdatedAt>()
+        .one(db)
+        .await
+        .map_err(UpdateError::StatsDB)?
+        .ok_or_else(|| UpdateError::NotFound(name.into()))?
+        .last_updated_at;
+    let next = RealtimeCursor {
+        block: to_block,
+        updated_at,
+    };
+    let from_block = match cursor {
+        Some(cursor) if cursor.updated_at == updated_at && updated_at.is_some() => cursor.block,
+        _ => return Ok(next),
+    };
+    if from_block >= to_block {
+        return Ok(RealtimeCursor {
+            block: from_block,
+            updated_at,
+        });
+    }
+
+    let increment = Increment::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        sql,
+        vec![from_block.into(), to_block.into()],
+    ))
+    .one(blockscout)
+    .await
+    .map_err(UpdateError::blockscout)?;
+    let (date, increment) = match increment {
+        Some(Increment {
+            date: Some(date),
+            value,
+        }) => (date, value),
+        _ => return Ok(next),
+    };
+
+    let chart_id = find_chart(db, name)
+        .await
+        .map_err(UpdateError::StatsDB)?
+        .ok_or_else(|| UpdateError::NotFound(name.into()))?;
+    let last = chart_data::Entity::find()
+        .filter(chart_data::Column::ChartId.eq(chart_id))
+        .order_by_desc(chart_data::Column::Id)
+        .one(db)
+        .await
+        .map_err(UpdateError::StatsDB)?;
+    let value = match last {
+        Some(last) => DateValue {
+            date: last.date.max(date),
+            value: last.value + increment,
+        },
+        // nothing to add to until the scheduled update computes the counter
+        None => return Ok(next),
+    };
+    let rows = insert_data_many(db, [value.active_model(chart_id, Some(to_block))])
+        .await
+        .map_err(UpdateError::StatsDB)?;
+    metrics::CHART_ROWS_WRITTEN
+        .with_label_values(&[&metrics::chain_id(), name])
+        .inc_by(rows);
+    Ok(next)
+}
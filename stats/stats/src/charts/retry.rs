@@ -0,0 +1,169 @@
+use super::UpdateError;
+use sea_orm::{sqlx, DbErr, RuntimeErr};
+use std::{future::Future, time::Duration};
+
+/// SQLSTATE classes and codes of postgres errors that go away by themselves:
+/// connection exceptions, serialization failures, deadlocks,
+/// too many connections and server shutdown
+const TRANSIENT_SQLSTATES: [&str; 6] = ["08", "40001", "40P01", "53300", "57P01", "57P03"];
+
+/// Whether blockscout database query can succeed if it is simply repeated
+pub fn is_transient(err: &DbErr) -> bool {
+    match err {
+        DbErr::Conn(_) => true,
+        DbErr::Exec(RuntimeErr::SqlxError(err)) | DbErr::Query(RuntimeErr::SqlxError(err)) => {
+            match err {
+                // pool timeouts are not retried, that would only make pool exhaustion worse
+                sqlx::Error::Io(_) | sqlx::Error::Tls(_) | sqlx::Error::WorkerCrashed => true,
+                sqlx::Error::Database(err) => err.code().map_or(false, |code| {
+                    TRANSIENT_SQLSTATES
+                        .iter()
+                        .any(|transient| code.starts_with(transient))
+                }),
+                _ => false,
+            }
+        }
+        _ => false,
+    }
+}
+
+/// How many times and how often retryable update errors are retried within one update run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    pub max_retries: usize,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    fn backoff(&self, retry: usize) -> Duration {
+        let factor = 2u32.saturating_pow(retry as u32);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// Runs `action` until it succeeds, fails with non-retryable error
+/// or `policy.max_retries` retries are exhausted
+pub async fn with_retries<T, F, Fut>(
+    policy: &RetryPolicy,
+    name: &str,
+    mut action: F,
+) -> Result<T, UpdateError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, UpdateError>>,
+{
+    let mut retry = 0;
+    loop {
+        match action().await {
+            Err(err) if err.is_retryable() && retry < policy.max_retries => {
+                let backoff = policy.backoff(retry);
+                tracing::warn!(
+                    chart = name,
+                    retry = retry + 1,
+                    "retryable error during update, retrying in {:?}: {}",
+                    backoff,
+                    err
+                );
+                tokio::time::sleep(backoff).await;
+                retry += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn no_backoff(max_retries: usize) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    fn unavailable() -> UpdateError {
+        UpdateError::blockscout(DbErr::Conn(RuntimeErr::Internal(
+            "connection refused".into(),
+        )))
+    }
+
+    #[test]
+    fn errors_are_classified() {
+        assert!(unavailable().is_retryable());
+        assert!(matches!(
+            unavailable(),
+            UpdateError::BlockscoutUnavailable(_)
+        ));
+
+        let fatal =
+            UpdateError::blockscout(DbErr::Query(RuntimeErr::Internal("syntax error".into())));
+        assert!(!fatal.is_retryable());
+        assert!(matches!(fatal, UpdateError::BlockscoutDB(_)));
+        assert!(!UpdateError::Internal("error".into()).is_retryable());
+    }
+
+    #[test]
+    fn backoff_grows_until_max() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+        };
+        let backoffs: Vec<_> = (0..5)
+            .map(|retry| policy.backoff(retry).as_secs())
+            .collect();
+        assert_eq!(backoffs, vec![1, 2, 4, 5, 5]);
+    }
+
+    #[tokio::test]
+    async fn retryable_errors_are_retried() {
+        let attempts = AtomicUsize::new(0);
+        let result = with_retries(&no_backoff(3), "test", || async {
+            match attempts.fetch_add(1, Ordering::SeqCst) {
+                0 | 1 => Err(unavailable()),
+                _ => Ok(()),
+            }
+        })
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+
+        let attempts = AtomicUsize::new(0);
+        let result: Result<(), _> = with_retries(&no_backoff(2), "test", || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(unavailable())
+        })
+        .await;
+        assert!(result.unwrap_err().is_retryable());
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn fatal_errors_are_not_retried() {
+        let attempts = AtomicUsize::new(0);
+        let result: Result<(), _> = with_retries(&no_backoff(3), "test", || async {
+            attempts.fetch_add(1, Ordering::SeqCst);
+            Err(UpdateError::Internal("error".into()))
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,121 @@
+use super::RollupType;
+use crate::{
+    charts::{
+        insert::{DateValue, DateValueDouble},
+        updater::ChartPartialUpdater,
+    },
+    Unit, UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+/// Total fees paid per day for publishing transaction data to L1, in native coin
+#[derive(Debug)]
+pub struct L1DataFees {
+    rollup: RollupType,
+}
+
+impl L1DataFees {
+    pub fn new(rollup: RollupType) -> Self {
+        Self { rollup }
+    }
+}
+
+#[async_trait]
+impl ChartPartialUpdater for L1DataFees {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let fee = match self.rollup {
+            RollupType::Optimism => "t.l1_fee",
+            RollupType::Arbitrum => "t.gas_used_for_l1 * t.gas_price",
+        };
+        let sql = format!(
+            r#"
+            SELECT
+                DATE(b.timestamp) as date,
+                (SUM({fee}) / $2)::FLOAT as value
+            FROM transactions t
+            JOIN blocks       b ON t.block_hash = b.hash
+            WHERE
+                b.timestamp != to_timestamp(0) AND
+                b.consensus = true AND
+                {fee} IS NOT NULL AND
+                ($1::date IS NULL OR DATE(b.timestamp) > $1) AND
+                ($3::date IS NULL OR DATE(b.timestamp) <= $3)
+            GROUP BY date
+            "#
+        );
+        let stmnt = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            &sql,
+            vec![
+                last_row.map(|row| row.date).into(),
+                Unit::NativeCoin.wei().into(),
+                to.into(),
+            ],
+        );
+
+        let data = DateValueDouble::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?
+            .into_iter()
+            .map(DateValue::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for L1DataFees {
+    fn name(&self) -> &str {
+        "l1DataFees"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+    fn unit(&self) -> Option<Unit> {
+        Some(Unit::NativeCoin)
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        mock_blockscout::fill_mock_optimism_data, simple_test::simple_test_chart_with_fixture,
+    };
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_l1_data_fees() {
+        let chart = L1DataFees::new(RollupType::Optimism);
+        simple_test_chart_with_fixture(
+            "update_l1_data_fees",
+            chart,
+            vec![("2022-11-10", "0.002"), ("2022-11-11", "0.001")],
+            |blockscout| Box::pin(fill_mock_optimism_data(blockscout)),
+        )
+        .await;
+    }
+}
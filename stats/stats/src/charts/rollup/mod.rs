@@ -0,0 +1,19 @@
+//! Charts of L2 data, which blockscout indexes only for rollups
+
+mod l1_data_fees;
+mod new_batches;
+mod new_withdrawals;
+
+pub use l1_data_fees::L1DataFees;
+pub use new_batches::NewBatches;
+pub use new_withdrawals::NewWithdrawals;
+
+use serde::{Deserialize, Serialize};
+
+/// Rollup stack of the chain, defines tables the charts are computed from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RollupType {
+    Optimism,
+    Arbitrum,
+}
@@ -0,0 +1,121 @@
+use super::RollupType;
+use crate::{
+    charts::{
+        insert::{DateValue, DateValueInt},
+        updater::ChartPartialUpdater,
+    },
+    UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+/// Number of batches of L2 blocks submitted to L1 per day
+#[derive(Debug)]
+pub struct NewBatches {
+    rollup: RollupType,
+}
+
+impl NewBatches {
+    pub fn new(rollup: RollupType) -> Self {
+        Self { rollup }
+    }
+}
+
+#[async_trait]
+impl ChartPartialUpdater for NewBatches {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let sql = match self.rollup {
+            RollupType::Optimism => {
+                r#"
+                SELECT
+                    DATE(fs.l1_timestamp) as date,
+                    COUNT(*)::BIGINT as value
+                FROM op_frame_sequences fs
+                WHERE
+                    ($1::date IS NULL OR DATE(fs.l1_timestamp) > $1) AND
+                    ($2::date IS NULL OR DATE(fs.l1_timestamp) <= $2)
+                GROUP BY date
+                "#
+            }
+            RollupType::Arbitrum => {
+                r#"
+                SELECT
+                    DATE(l1.timestamp) as date,
+                    COUNT(*)::BIGINT as value
+                FROM arbitrum_l1_batches b
+                JOIN arbitrum_lifecycle_l1_transactions l1 ON b.commitment_id = l1.id
+                WHERE
+                    ($1::date IS NULL OR DATE(l1.timestamp) > $1) AND
+                    ($2::date IS NULL OR DATE(l1.timestamp) <= $2)
+                GROUP BY date
+                "#
+            }
+        };
+        let stmnt = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            vec![last_row.map(|row| row.date).into(), to.into()],
+        );
+
+        let data = DateValueInt::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?
+            .into_iter()
+            .map(DateValue::from)
+            .collect();
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for NewBatches {
+    fn name(&self) -> &str {
+        "newBatches"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        mock_blockscout::fill_mock_optimism_data, simple_test::simple_test_chart_with_fixture,
+    };
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_batches() {
+        let chart = NewBatches::new(RollupType::Optimism);
+        simple_test_chart_with_fixture(
+            "update_new_batches",
+            chart,
+            vec![("2022-11-10", "2"), ("2022-11-11", "1")],
+            |blockscout| Box::pin(fill_mock_optimism_data(blockscout)),
+        )
+        .await;
+    }
+}
@@ -0,0 +1,125 @@
+use super::RollupType;
+use crate::{
+    charts::{
+        insert::{DateValue, DateValueInt},
+        updater::ChartPartialUpdater,
+    },
+    UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+/// Number of withdrawals from L2 to L1 initiated per day
+#[derive(Debug)]
+pub struct NewWithdrawals {
+    rollup: RollupType,
+}
+
+impl NewWithdrawals {
+    pub fn new(rollup: RollupType) -> Self {
+        Self { rollup }
+    }
+}
+
+#[async_trait]
+impl ChartPartialUpdater for NewWithdrawals {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let sql = match self.rollup {
+            RollupType::Optimism => {
+                r#"
+                SELECT
+                    DATE(b.timestamp) as date,
+                    COUNT(*)::BIGINT as value
+                FROM op_withdrawals w
+                JOIN blocks b ON w.l2_block_number = b.number
+                WHERE
+                    b.timestamp != to_timestamp(0) AND
+                    b.consensus = true AND
+                    ($1::date IS NULL OR DATE(b.timestamp) > $1) AND
+                    ($2::date IS NULL OR DATE(b.timestamp) <= $2)
+                GROUP BY date
+                "#
+            }
+            RollupType::Arbitrum => {
+                r#"
+                SELECT
+                    DATE(m.origination_timestamp) as date,
+                    COUNT(*)::BIGINT as value
+                FROM arbitrum_crosslevel_messages m
+                WHERE
+                    m.direction = 'from_l2' AND
+                    m.origination_timestamp IS NOT NULL AND
+                    ($1::date IS NULL OR DATE(m.origination_timestamp) > $1) AND
+                    ($2::date IS NULL OR DATE(m.origination_timestamp) <= $2)
+                GROUP BY date
+                "#
+            }
+        };
+        let stmnt = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            sql,
+            vec![last_row.map(|row| row.date).into(), to.into()],
+        );
+
+        let data = DateValueInt::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?
+            .into_iter()
+            .map(DateValue::from)
+            .collect();
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for NewWithdrawals {
+    fn name(&self) -> &str {
+        "newWithdrawals"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::{
+        mock_blockscout::fill_mock_optimism_data, simple_test::simple_test_chart_with_fixture,
+    };
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_withdrawals() {
+        let chart = NewWithdrawals::new(RollupType::Optimism);
+        simple_test_chart_with_fixture(
+            "update_new_withdrawals",
+            chart,
+            vec![("2022-11-10", "2"), ("2022-11-11", "1")],
+            |blockscout| Box::pin(fill_mock_optimism_data(blockscout)),
+        )
+        .await;
+    }
+}
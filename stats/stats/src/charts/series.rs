@@ -0,0 +1,48 @@
+use super::insert::DateValue;
+use entity::chart_series_data;
+use sea_orm::{prelude::*, sea_query, ConnectionTrait, Set};
+
+/// Max number of points written by one insert statement
+pub const INSERT_BATCH_ROWS: usize = 1000;
+
+/// Writes points of series `key` of the chart, see [`Chart::series`].
+/// Returns number of written rows
+///
+/// [`Chart::series`]: super::Chart::series
+pub async fn insert_series_data<C>(
+    db: &C,
+    chart_id: i32,
+    key: &str,
+    values: &[DateValue],
+    min_blockscout_block: Option<i64>,
+) -> Result<u64, DbErr>
+where
+    C: ConnectionTrait,
+{
+    let mut rows = 0;
+    for batch in values.chunks(INSERT_BATCH_ROWS) {
+        let data = batch.iter().map(|value| chart_series_data::ActiveModel {
+            id: Default::default(),
+            chart_id: Set(chart_id),
+            series: Set(key.to_string()),
+            date: Set(value.date),
+            value: Set(value.value),
+            created_at: Default::default(),
+            min_blockscout_block: Set(min_blockscout_block),
+        });
+        chart_series_data::Entity::insert_many(data)
+            .on_conflict(
+                sea_query::OnConflict::columns([
+                    chart_series_data::Column::ChartId,
+                    chart_series_data::Column::Series,
+                    chart_series_data::Column::Date,
+                ])
+                .update_column(chart_series_data::Column::Value)
+                .to_owned(),
+            )
+            .exec(db)
+            .await?;
+        rows += batch.len() as u64;
+    }
+    Ok(rows)
+}
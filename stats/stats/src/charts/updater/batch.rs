@@ -1,7 +1,7 @@
 use super::{get_last_row, get_min_block_blockscout, get_min_date_blockscout};
 use crate::{
     charts::{find_chart, insert::insert_data_many},
-    metrics, Chart, DateValue, UpdateError,
+    metrics, Chart, DateValue, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use chrono::{Duration, NaiveDate, Utc};
@@ -19,7 +19,7 @@ pub trait ChartBatchUpdater: Chart {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
         let chart_id = find_chart(db, self.name())
             .await
@@ -27,16 +27,23 @@ pub trait ChartBatchUpdater: Chart {
             .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
         let min_blockscout_block = get_min_block_blockscout(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
-        // set offset to 1 because actual last row can be partially calculated
-        let offset = Some(1);
-        let last_row =
-            get_last_row(self, chart_id, min_blockscout_block, db, force_full, offset).await?;
+            .map_err(UpdateError::blockscout)?;
+        // last days are recomputed, because they can be partially calculated
+        // or contain blocks dropped by reorg
+        let last_row = get_last_row(
+            self,
+            chart_id,
+            min_blockscout_block,
+            db,
+            params.force_full,
+            params.recompute_window_days,
+        )
+        .await?;
 
         let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
-            .with_label_values(&[self.name()])
+            .with_label_values(&[&metrics::chain_id(), self.name()])
             .start_timer();
-        tracing::info!(last_row =? last_row, "start batch update");
+        tracing::info!(chart = self.name(), last_row =? last_row, "start batch update");
         self.batch_update(db, blockscout, last_row, chart_id, min_blockscout_block)
             .await
     }
@@ -49,16 +56,13 @@ pub trait ChartBatchUpdater: Chart {
         chart_id: i32,
         min_blockscout_block: i64,
     ) -> Result<(), UpdateError> {
-        let txn = blockscout
-            .begin()
-            .await
-            .map_err(UpdateError::BlockscoutDB)?;
+        let txn = blockscout.begin().await.map_err(UpdateError::blockscout)?;
         let first_date = match last_row {
             Some(last_row) => last_row.date,
             None => get_min_date_blockscout(&txn)
                 .await
                 .map(|time| time.date())
-                .map_err(UpdateError::BlockscoutDB)?,
+                .map_err(UpdateError::blockscout)?,
         };
         let last_date = Utc::now().date_naive();
 
@@ -66,21 +70,38 @@ pub trait ChartBatchUpdater: Chart {
         let n = steps.len();
 
         for (i, (from, to)) in steps.into_iter().enumerate() {
-            tracing::info!(from =? from, to =? to , "run {}/{} step of batch update", i + 1, n);
+            tracing::info!(
+                chart = self.name(),
+                from =? from,
+                to =? to,
+                "run {}/{} step of batch update",
+                i + 1,
+                n
+            );
             let query = self.get_query(from, to);
             let now = Instant::now();
             let values = DateValue::find_by_statement(query)
                 .all(&txn)
                 .await
-                .map_err(UpdateError::BlockscoutDB)?
+                .map_err(UpdateError::blockscout)?
                 .into_iter()
                 .map(|value| value.active_model(chart_id, Some(min_blockscout_block)));
             let elapsed = now.elapsed();
             let found = values.len();
-            tracing::info!(found =? found, elapsed =? elapsed, "{}/{} step of batch done", i + 1, n);
-            insert_data_many(db, values)
+            tracing::info!(
+                chart = self.name(),
+                found =? found,
+                elapsed =? elapsed,
+                "{}/{} step of batch done",
+                i + 1,
+                n
+            );
+            let rows = insert_data_many(db, values)
                 .await
                 .map_err(UpdateError::StatsDB)?;
+            metrics::CHART_ROWS_WRITTEN
+                .with_label_values(&[&metrics::chain_id(), self.name()])
+                .inc_by(rows);
         }
         Ok(())
     }
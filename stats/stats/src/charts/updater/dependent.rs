@@ -4,11 +4,11 @@ use crate::{
         find_chart,
         insert::{insert_data_many, DateValue},
     },
-    get_chart_data, Chart, UpdateError,
+    get_chart_data, metrics, Chart, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use sea_orm::prelude::*;
-use std::{fmt::Display, iter::Sum, ops::AddAssign, str::FromStr, sync::Arc};
+use std::sync::Arc;
 
 #[async_trait]
 pub trait ChartDependentUpdater<P>: Chart
@@ -23,7 +23,7 @@ where
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<Vec<DateValue>, UpdateError> {
         let parent = self.parent();
         tracing::info!(
@@ -31,7 +31,7 @@ where
             parent_chart_name = parent.name(),
             "updating parent"
         );
-        parent.update_with_mutex(db, blockscout, force_full).await?;
+        parent.update_with_mutex(db, blockscout, params).await?;
         let data = get_chart_data(db, parent.name(), None, None, None).await?;
         Ok(data)
     }
@@ -40,7 +40,7 @@ where
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
     ) -> Result<(), UpdateError> {
         let chart_id = find_chart(db, self.name())
             .await
@@ -48,49 +48,33 @@ where
             .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
         let min_blockscout_block = get_min_block_blockscout(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
-        let parent_data = self.get_parent_data(db, blockscout, force_full).await?;
+            .map_err(UpdateError::blockscout)?;
+        let parent_data = self.get_parent_data(db, blockscout, params).await?;
         let values = self
             .get_values(parent_data)
             .await?
             .into_iter()
             .map(|v| v.active_model(chart_id, Some(min_blockscout_block)));
-        insert_data_many(db, values)
+        let rows = insert_data_many(db, values)
             .await
-            .map_err(UpdateError::StatsDB)
+            .map_err(UpdateError::StatsDB)?;
+        metrics::CHART_ROWS_WRITTEN
+            .with_label_values(&[&metrics::chain_id(), self.name()])
+            .inc_by(rows);
+        Ok(())
     }
 }
 
-pub fn parse_and_growth<T>(
-    mut data: Vec<DateValue>,
-    parent_name: &str,
-) -> Result<Vec<DateValue>, UpdateError>
-where
-    T: AddAssign + FromStr + Default + Display,
-    T::Err: Display,
-{
-    let mut prev_sum = T::default();
+pub fn growth(mut data: Vec<DateValue>) -> Vec<DateValue> {
+    let mut prev_sum = Decimal::ZERO;
     for item in data.iter_mut() {
-        let value = item.value.parse::<T>().map_err(|e| {
-            UpdateError::Internal(format!(
-                "failed to parse values in chart '{parent_name}': {e}",
-            ))
-        })?;
-        prev_sum += value;
-        item.value = prev_sum.to_string();
+        prev_sum += item.value;
+        item.value = prev_sum;
     }
-    Ok(data)
+    data
 }
 
-pub fn parse_and_sum<T>(
-    data: Vec<DateValue>,
-    chart_name: &str,
-    parent_name: &str,
-) -> Result<Option<DateValue>, UpdateError>
-where
-    T: Sum + FromStr + Default + Display,
-    T::Err: Display,
-{
+pub fn sum(data: Vec<DateValue>, chart_name: &str, parent_name: &str) -> Option<DateValue> {
     let max_date = match data.iter().max() {
         Some(max_date) => max_date.date,
         None => {
@@ -99,25 +83,14 @@ where
                 parent_chart_name = parent_name,
                 "parent doesn't have any data after update"
             );
-            return Ok(None);
+            return None;
         }
     };
-    let total: T = data
-        .into_iter()
-        .map(|p| p.value.parse::<T>())
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| {
-            UpdateError::Internal(format!(
-                "failed to parse values in chart '{parent_name}': {e}",
-            ))
-        })?
-        .into_iter()
-        .sum();
-    let point = DateValue {
+    let total = data.into_iter().map(|p| p.value).sum();
+    Some(DateValue {
         date: max_date,
-        value: total.to_string(),
-    };
-    Ok(Some(point))
+        value: total,
+    })
 }
 
 pub fn last_point(data: Vec<DateValue>) -> Option<DateValue> {
@@ -3,7 +3,7 @@ use crate::{
         find_chart,
         insert::{insert_data_many, DateValue},
     },
-    metrics, Chart, UpdateError,
+    metrics, Chart, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
 use sea_orm::prelude::*;
@@ -19,7 +19,7 @@ pub trait ChartFullUpdater: Chart {
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        _force_full: bool,
+        _params: UpdateParameters,
     ) -> Result<(), UpdateError> {
         let chart_id = find_chart(db, self.name())
             .await
@@ -27,16 +27,19 @@ pub trait ChartFullUpdater: Chart {
             .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
         let values = {
             let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
-                .with_label_values(&[self.name()])
+                .with_label_values(&[&metrics::chain_id(), self.name()])
                 .start_timer();
             self.get_values(blockscout)
                 .await?
                 .into_iter()
                 .map(|value| value.active_model(chart_id, None))
         };
-        insert_data_many(db, values)
+        let rows = insert_data_many(db, values)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
+            .map_err(UpdateError::StatsDB)?;
+        metrics::CHART_ROWS_WRITTEN
+            .with_label_values(&[&metrics::chain_id(), self.name()])
+            .inc_by(rows);
         Ok(())
     }
 }
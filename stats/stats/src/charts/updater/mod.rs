@@ -1,5 +1,5 @@
 use blockscout_db::entity::blocks;
-use chrono::{NaiveDate, NaiveDateTime};
+use chrono::{Duration, NaiveDate, NaiveDateTime, Utc};
 use entity::chart_data;
 use sea_orm::{prelude::*, sea_query, ConnectionTrait, FromQueryResult, QueryOrder, QuerySelect};
 mod batch;
@@ -7,12 +7,93 @@ mod dependent;
 mod full;
 mod partial;
 
+use batch::generate_date_ranges;
 pub use batch::ChartBatchUpdater;
-pub use dependent::{last_point, parse_and_growth, parse_and_sum, ChartDependentUpdater};
+pub use dependent::{growth, last_point, sum, ChartDependentUpdater};
 pub use full::ChartFullUpdater;
 pub use partial::ChartPartialUpdater;
 
-use crate::{Chart, DateValue, UpdateError};
+use crate::{charts::insert::insert_data_many, metrics, Chart, DateValue, UpdateError};
+
+/// Number of days read from blockscout and written by one step of full update
+pub const FULL_SYNC_BATCH_DAYS: i64 = 365;
+
+/// Computes values of `chart` after `last_row` by date windows of `step`, oldest first,
+/// the same way [`ChartBatchUpdater`] does, and writes values of every window on its own.
+/// Every window continues from the last written point or the end of the previous window,
+/// so if the update fails midway, the next one continues after the last written window
+/// instead of starting over. Full update starts from the first block if `last_row` is `None`.
+/// All values are read at once if `step` is `None`
+pub async fn insert_full_sync<C>(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    chart: &C,
+    chart_id: i32,
+    min_blockscout_block: i64,
+    mut last_row: Option<DateValue>,
+    step: Option<Duration>,
+) -> Result<u64, UpdateError>
+where
+    C: ChartPartialUpdater + ?Sized,
+{
+    // last window is unbounded, so points dated after today are not lost
+    let mut bounds: Vec<_> = match step {
+        Some(step) => {
+            let first_date = match &last_row {
+                Some(row) => row.date + Duration::days(1),
+                None => get_min_date_blockscout(blockscout)
+                    .await
+                    .map_err(UpdateError::blockscout)?
+                    .date(),
+            };
+            generate_date_ranges(first_date, Utc::now().date_naive(), step)
+                .into_iter()
+                .map(|(_, next)| Some(next - Duration::days(1)))
+                .collect()
+        }
+        None => vec![],
+    };
+    bounds.pop();
+    bounds.push(None);
+
+    let windows = bounds.len();
+    let mut rows = 0;
+    for (i, to) in bounds.into_iter().enumerate() {
+        let values = {
+            let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
+                .with_label_values(&[&metrics::chain_id(), chart.name()])
+                .start_timer();
+            chart.get_values(blockscout, last_row.clone(), to).await?
+        };
+        last_row = match (values.iter().max_by_key(|value| value.date), to) {
+            (Some(last), _) => Some(last.clone()),
+            // nothing happened during the window, so the next one starts after it
+            // and cumulative charts keep their value
+            (None, Some(to)) => Some(DateValue {
+                date: to,
+                value: last_row.map(|row| row.value).unwrap_or_default(),
+            }),
+            (None, None) => last_row,
+        };
+        rows += insert_data_many(
+            db,
+            values
+                .iter()
+                .map(|value| value.active_model(chart_id, Some(min_blockscout_block))),
+        )
+        .await
+        .map_err(UpdateError::StatsDB)?;
+        tracing::info!(
+            chart = chart.name(),
+            to = ?to,
+            found = values.len(),
+            "written {}/{} window of update",
+            i + 1,
+            windows
+        );
+    }
+    Ok(rows)
+}
 
 #[derive(FromQueryResult)]
 struct MinBlock {
@@ -36,6 +117,28 @@ pub async fn get_min_block_blockscout(blockscout: &DatabaseConnection) -> Result
         .ok_or_else(|| DbErr::RecordNotFound("no blocks found in blockscout database".into()))
 }
 
+#[derive(FromQueryResult)]
+struct MaxBlock {
+    max_block: Option<i64>,
+}
+
+/// Number of the last consensus block, cheap enough to be polled often
+pub async fn get_max_block_blockscout(
+    blockscout: &DatabaseConnection,
+) -> Result<Option<i64>, DbErr> {
+    let max_block = blocks::Entity::find()
+        .select_only()
+        .column_as(
+            sea_query::Expr::col(blocks::Column::Number).max(),
+            "max_block",
+        )
+        .filter(blocks::Column::Consensus.eq(true))
+        .into_model::<MaxBlock>()
+        .one(blockscout)
+        .await?;
+    Ok(max_block.and_then(|r| r.max_block))
+}
+
 #[derive(FromQueryResult)]
 struct MinDate {
     timestamp: NaiveDateTime,
@@ -66,7 +169,7 @@ where
 #[derive(Debug, FromQueryResult)]
 struct SyncInfo {
     pub date: NaiveDate,
-    pub value: String,
+    pub value: Decimal,
     pub min_blockscout_block: Option<i64>,
 }
 
@@ -76,12 +179,11 @@ pub async fn get_last_row<C>(
     min_blockscout_block: i64,
     db: &DatabaseConnection,
     force_full: bool,
-    offset: Option<u64>,
+    window_days: u64,
 ) -> Result<Option<DateValue>, UpdateError>
 where
     C: Chart + ?Sized,
 {
-    let offset = offset.unwrap_or(0);
     let last_row = if force_full {
         tracing::info!(
             min_blockscout_block = min_blockscout_block,
@@ -95,8 +197,14 @@ where
             .column(chart_data::Column::Value)
             .column(chart_data::Column::MinBlockscoutBlock)
             .filter(chart_data::Column::ChartId.eq(chart_id))
+            .filter(sea_query::Expr::cust_with_values(
+                "date <= (SELECT MAX(date) FROM chart_data WHERE chart_id = $1) - $2::integer",
+                [
+                    sea_query::Value::from(chart_id),
+                    sea_query::Value::from(window_days as i32),
+                ],
+            ))
             .order_by_desc(chart_data::Column::Date)
-            .offset(offset)
             .into_model()
             .one(db)
             .await
@@ -148,3 +256,167 @@ where
 
     Ok(last_row)
 }
+
+/// Last stored point dated before `date`
+pub async fn get_row_before(
+    db: &DatabaseConnection,
+    chart_id: i32,
+    date: NaiveDate,
+) -> Result<Option<DateValue>, DbErr> {
+    let row = chart_data::Entity::find()
+        .filter(chart_data::Column::ChartId.eq(chart_id))
+        .filter(chart_data::Column::Date.lt(date))
+        .order_by_desc(chart_data::Column::Date)
+        .one(db)
+        .await?;
+    Ok(row.map(|row| DateValue {
+        date: row.date,
+        value: row.value,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        lines::NewBlocks,
+        tests::{
+            init_db::{init_db, init_db_all},
+            mock_blockscout::fill_mock_blockscout_data,
+        },
+    };
+    use pretty_assertions::assert_eq;
+    use sea_orm::Set;
+    use std::str::FromStr;
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn get_last_row_respects_window() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let db = init_db("get_last_row_respects_window").await;
+        let chart = NewBlocks::default();
+        chart.create(&db).await.unwrap();
+        let chart_id = 1;
+        let min_blockscout_block = 10;
+
+        let last_row = get_last_row(&chart, chart_id, min_blockscout_block, &db, false, 1)
+            .await
+            .unwrap();
+        assert_eq!(last_row, None);
+
+        let rows = ["2022-11-10", "2022-11-11", "2022-11-12", "2022-11-14"].map(|date| {
+            chart_data::ActiveModel {
+                chart_id: Set(chart_id),
+                date: Set(NaiveDate::from_str(date).unwrap()),
+                value: Set(date[8..].parse().unwrap()),
+                min_blockscout_block: Set(Some(min_blockscout_block)),
+                ..Default::default()
+            }
+        });
+        chart_data::Entity::insert_many(rows)
+            .exec(&db as &DatabaseConnection)
+            .await
+            .unwrap();
+
+        for (window, expected) in [
+            (0, Some("2022-11-14")),
+            (1, Some("2022-11-12")),
+            (2, Some("2022-11-12")),
+            (3, Some("2022-11-11")),
+            (4, Some("2022-11-10")),
+            (5, None),
+        ] {
+            let last_row = get_last_row(&chart, chart_id, min_blockscout_block, &db, false, window)
+                .await
+                .unwrap()
+                .map(|row| row.date);
+            let expected = expected.map(|date| NaiveDate::from_str(date).unwrap());
+            assert_eq!(last_row, expected, "window {window}");
+        }
+
+        let last_row = get_last_row(&chart, chart_id, min_blockscout_block, &db, true, 0)
+            .await
+            .unwrap();
+        assert_eq!(last_row, None);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn insert_full_sync_writes_all_windows() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("insert_full_sync_writes_all_windows").await;
+        let chart = NewBlocks::default();
+        chart.create(&db).await.unwrap();
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+        let expected = chart.get_values(&blockscout, None, None).await.unwrap();
+        let rows = insert_full_sync(
+            &db,
+            &blockscout,
+            &chart,
+            1,
+            10,
+            None,
+            Some(Duration::days(30)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(rows, expected.len() as u64);
+
+        let mut expected = expected;
+        expected.sort();
+        let data = crate::get_chart_data(&db, chart.name(), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(data, expected);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn insert_full_sync_continues_from_last_row() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("insert_full_sync_continues_from_last_row").await;
+        let chart = NewBlocks::default();
+        chart.create(&db).await.unwrap();
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+        let mut expected = chart.get_values(&blockscout, None, None).await.unwrap();
+        expected.sort();
+        let expected = expected.split_off(2);
+        let last_row = DateValue {
+            date: expected[0].date - Duration::days(1),
+            value: Decimal::ZERO,
+        };
+        let rows = insert_full_sync(
+            &db,
+            &blockscout,
+            &chart,
+            1,
+            10,
+            Some(last_row),
+            Some(Duration::days(7)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(rows, expected.len() as u64);
+
+        let data = crate::get_chart_data(&db, chart.name(), None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(data, expected);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn get_max_block_ignores_non_consensus() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (_db, blockscout) = init_db_all("get_max_block_ignores_non_consensus").await;
+        assert_eq!(get_max_block_blockscout(&blockscout).await.unwrap(), None);
+
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+        assert_eq!(
+            get_max_block_blockscout(&blockscout).await.unwrap(),
+            Some(12)
+        );
+    }
+}
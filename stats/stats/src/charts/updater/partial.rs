@@ -1,27 +1,84 @@
-use super::{get_last_row, get_min_block_blockscout};
+use super::{
+    get_last_row, get_min_block_blockscout, get_row_before, insert_full_sync, FULL_SYNC_BATCH_DAYS,
+};
 use crate::{
     charts::{
         find_chart,
         insert::{insert_data_many, DateValue},
     },
-    metrics, Chart, UpdateError,
+    metrics, Chart, UpdateError, UpdateParameters,
 };
 use async_trait::async_trait;
-use sea_orm::prelude::*;
+use chrono::{Duration, NaiveDate};
+use entity::chart_data;
+use sea_orm::{prelude::*, TransactionTrait};
 
 #[async_trait]
 pub trait ChartPartialUpdater: Chart {
+    /// Values of days after `last_row` up to `to` inclusive, unbounded if they are `None`.
+    /// Cumulative charts continue from the value of `last_row`
     async fn get_values(
         &self,
         blockscout: &DatabaseConnection,
         last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
     ) -> Result<Vec<DateValue>, UpdateError>;
 
+    /// Length of date windows values of update are read by, see [`insert_full_sync`].
+    /// `None` reads all values at once
+    fn full_sync_step(&self) -> Option<Duration> {
+        Some(Duration::days(FULL_SYNC_BATCH_DAYS))
+    }
+
     async fn update_with_values(
         &self,
         db: &DatabaseConnection,
         blockscout: &DatabaseConnection,
-        force_full: bool,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        let chart_id = find_chart(db, self.name())
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
+        let min_blockscout_block = get_min_block_blockscout(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?;
+        // last days are recomputed, because they can be partially calculated
+        // or contain blocks dropped by reorg
+        let last_row = get_last_row(
+            self,
+            chart_id,
+            min_blockscout_block,
+            db,
+            params.force_full,
+            params.recompute_window_days,
+        )
+        .await?;
+        let rows = insert_full_sync(
+            db,
+            blockscout,
+            self,
+            chart_id,
+            min_blockscout_block,
+            last_row,
+            self.full_sync_step(),
+        )
+        .await?;
+        metrics::CHART_ROWS_WRITTEN
+            .with_label_values(&[&metrics::chain_id(), self.name()])
+            .inc_by(rows);
+        Ok(())
+    }
+
+    /// Values of `[from, to]` are computed as if the chart was partially updated
+    /// from the last point stored before `from` up to `to`, so cumulative charts continue from it.
+    /// Stored points of the range are replaced in one transaction
+    async fn repair_with_values(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        from: NaiveDate,
+        to: NaiveDate,
     ) -> Result<(), UpdateError> {
         let chart_id = find_chart(db, self.name())
             .await
@@ -29,23 +86,43 @@ pub trait ChartPartialUpdater: Chart {
             .ok_or_else(|| UpdateError::NotFound(self.name().into()))?;
         let min_blockscout_block = get_min_block_blockscout(blockscout)
             .await
-            .map_err(UpdateError::BlockscoutDB)?;
-        // set offset to 1 because actual last row can be partially calculated
-        let offset = Some(1);
-        let last_row =
-            get_last_row(self, chart_id, min_blockscout_block, db, force_full, offset).await?;
-        let values = {
+            .map_err(UpdateError::blockscout)?;
+        let last_row = get_row_before(db, chart_id, from)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        tracing::info!(
+            chart = self.name(),
+            from = ?from,
+            to = ?to,
+            last_row = ?last_row,
+            "repairing chart"
+        );
+        let values: Vec<_> = {
             let _timer = metrics::CHART_FETCH_NEW_DATA_TIME
-                .with_label_values(&[self.name()])
+                .with_label_values(&[&metrics::chain_id(), self.name()])
                 .start_timer();
-            self.get_values(blockscout, last_row)
+            // days between the last stored point and `from` are not stored, so they are skipped
+            self.get_values(blockscout, last_row, Some(to))
                 .await?
                 .into_iter()
+                .filter(|value| from <= value.date)
                 .map(|value| value.active_model(chart_id, Some(min_blockscout_block)))
+                .collect()
         };
-        insert_data_many(db, values)
+        let txn = db.begin().await.map_err(UpdateError::StatsDB)?;
+        chart_data::Entity::delete_many()
+            .filter(chart_data::Column::ChartId.eq(chart_id))
+            .filter(chart_data::Column::Date.between(from, to))
+            .exec(&txn)
+            .await
+            .map_err(UpdateError::StatsDB)?;
+        let rows = insert_data_many(&txn, values)
             .await
             .map_err(UpdateError::StatsDB)?;
+        txn.commit().await.map_err(UpdateError::StatsDB)?;
+        metrics::CHART_ROWS_WRITTEN
+            .with_label_values(&[&metrics::chain_id(), self.name()])
+            .inc_by(rows);
         Ok(())
     }
 }
@@ -0,0 +1,124 @@
+use crate::{
+    charts::{
+        insert::{DateValue, DateValueInt},
+        updater::ChartPartialUpdater,
+    },
+    AggregationPolicy, UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+/// Number of smart accounts that sent at least one user operation per day
+#[derive(Default, Debug)]
+pub struct ActiveSmartAccounts {}
+
+#[async_trait]
+impl ChartPartialUpdater for ActiveSmartAccounts {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let stmnt = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                DATE(b.timestamp) as date,
+                COUNT(DISTINCT uo.sender)::BIGINT as value
+            FROM user_operations uo
+            JOIN blocks b ON uo.block_hash = b.hash
+            WHERE
+                b.timestamp != to_timestamp(0) AND
+                b.consensus = true AND
+                ($1::date IS NULL OR DATE(b.timestamp) > $1) AND
+                ($2::date IS NULL OR DATE(b.timestamp) <= $2)
+            GROUP BY date
+            "#,
+            vec![last_row.map(|row| row.date).into(), to.into()],
+        );
+
+        let data = DateValueInt::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?
+            .into_iter()
+            .map(DateValue::from)
+            .collect();
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for ActiveSmartAccounts {
+    fn name(&self) -> &str {
+        "activeSmartAccounts"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    // distinct accounts of days can't be summed up
+    fn aggregation_policy(&self) -> AggregationPolicy {
+        AggregationPolicy::Average
+    }
+
+    fn period_sql(&self) -> Option<&str> {
+        Some(
+            r#"
+            SELECT
+                DATE_TRUNC($1, b.timestamp)::DATE as date,
+                COUNT(DISTINCT uo.sender)::NUMERIC as value
+            FROM user_operations uo
+            JOIN blocks b ON uo.block_hash = b.hash
+            WHERE
+                b.timestamp != to_timestamp(0) AND
+                b.consensus = true AND
+                ($2::date IS NULL OR DATE(b.timestamp) >= $2) AND
+                ($3::date IS NULL OR DATE(b.timestamp) <= $3)
+            GROUP BY 1
+            "#,
+        )
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ActiveSmartAccounts;
+    use crate::tests::{
+        mock_blockscout::fill_mock_user_ops_data, simple_test::simple_test_chart_with_fixture,
+    };
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_active_smart_accounts() {
+        let chart = ActiveSmartAccounts::default();
+        simple_test_chart_with_fixture(
+            "update_active_smart_accounts",
+            chart,
+            vec![
+                ("2022-11-10", "2"),
+                ("2022-11-11", "1"),
+                ("2022-12-01", "1"),
+            ],
+            |blockscout| Box::pin(fill_mock_user_ops_data(blockscout)),
+        )
+        .await;
+    }
+}
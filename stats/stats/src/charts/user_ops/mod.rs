@@ -0,0 +1,26 @@
+//! Charts of ERC-4337 account abstraction, computed from tables of user operations
+//! indexer, which exist in blockscout database only if the indexer is deployed
+
+mod active_smart_accounts;
+mod new_user_ops;
+
+pub use active_smart_accounts::ActiveSmartAccounts;
+pub use new_user_ops::NewUserOps;
+
+use sea_orm::{DatabaseConnection, DbBackend, DbErr, FromQueryResult, Statement};
+
+#[derive(FromQueryResult)]
+struct UserOpsIndexed {
+    indexed: bool,
+}
+
+/// Whether user operations are indexed into `blockscout` database
+pub async fn user_ops_indexed(blockscout: &DatabaseConnection) -> Result<bool, DbErr> {
+    let row = UserOpsIndexed::find_by_statement(Statement::from_string(
+        DbBackend::Postgres,
+        "SELECT to_regclass('user_operations') IS NOT NULL as indexed".into(),
+    ))
+    .one(blockscout)
+    .await?;
+    Ok(row.map_or(false, |row| row.indexed))
+}
@@ -0,0 +1,101 @@
+use crate::{
+    charts::{
+        insert::{DateValue, DateValueInt},
+        updater::ChartPartialUpdater,
+    },
+    UpdateError, UpdateParameters,
+};
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::ChartType;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+/// Number of user operations included into blocks per day
+#[derive(Default, Debug)]
+pub struct NewUserOps {}
+
+#[async_trait]
+impl ChartPartialUpdater for NewUserOps {
+    async fn get_values(
+        &self,
+        blockscout: &DatabaseConnection,
+        last_row: Option<DateValue>,
+        to: Option<NaiveDate>,
+    ) -> Result<Vec<DateValue>, UpdateError> {
+        let stmnt = Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"
+            SELECT
+                DATE(b.timestamp) as date,
+                COUNT(*)::BIGINT as value
+            FROM user_operations uo
+            JOIN blocks b ON uo.block_hash = b.hash
+            WHERE
+                b.timestamp != to_timestamp(0) AND
+                b.consensus = true AND
+                ($1::date IS NULL OR DATE(b.timestamp) > $1) AND
+                ($2::date IS NULL OR DATE(b.timestamp) <= $2)
+            GROUP BY date
+            "#,
+            vec![last_row.map(|row| row.date).into(), to.into()],
+        );
+
+        let data = DateValueInt::find_by_statement(stmnt)
+            .all(blockscout)
+            .await
+            .map_err(UpdateError::blockscout)?
+            .into_iter()
+            .map(DateValue::from)
+            .collect();
+        Ok(data)
+    }
+}
+
+#[async_trait]
+impl crate::Chart for NewUserOps {
+    fn name(&self) -> &str {
+        "newUserOps"
+    }
+
+    fn chart_type(&self) -> ChartType {
+        ChartType::Line
+    }
+
+    async fn update(
+        &self,
+        db: &DatabaseConnection,
+        blockscout: &DatabaseConnection,
+        params: UpdateParameters,
+    ) -> Result<(), UpdateError> {
+        self.update_with_values(db, blockscout, params).await
+    }
+
+    fn partial_updater(&self) -> Option<&dyn ChartPartialUpdater> {
+        Some(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NewUserOps;
+    use crate::tests::{
+        mock_blockscout::fill_mock_user_ops_data, simple_test::simple_test_chart_with_fixture,
+    };
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_new_user_ops() {
+        let chart = NewUserOps::default();
+        simple_test_chart_with_fixture(
+            "update_new_user_ops",
+            chart,
+            vec![
+                ("2022-11-10", "3"),
+                ("2022-11-11", "2"),
+                ("2022-12-01", "1"),
+            ],
+            |blockscout| Box::pin(fill_mock_user_ops_data(blockscout)),
+        )
+        .await;
+    }
+}
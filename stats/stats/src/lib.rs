@@ -1,15 +1,25 @@
 mod charts;
 mod missing_date;
 mod read;
+mod resolution;
 
 pub mod metrics;
 #[cfg(feature = "test-utils")]
 pub mod tests;
+pub mod token_stats;
 
 pub use entity;
 pub use migration;
 
 pub use charts::{
-    cache, counters, insert::DateValue, lines, Chart, MissingDatePolicy, UpdateError,
+    cache, counters, insert::DateValue, insert_series_data, lines, market, moving_average_name,
+    refresh_counter, resolution_name, rollup, save_update_attempt, save_update_run,
+    set_last_updated_at, updater::get_max_block_blockscout, user_ops, with_retries,
+    AggregationPolicy, Chart, CounterSnapshots, CustomSqlChart, MissingDatePolicy, RealtimeCursor,
+    ResolutionKind, RetryPolicy, Unit, UpdateAttempt, UpdateError, UpdateParameters,
 };
-pub use read::{get_chart_data, get_counters, ReadError};
+pub use read::{
+    get_chart_data, get_chart_data_chunk, get_chart_data_with_resolution, get_charts_metadata,
+    get_counters, get_next_page_start, get_series_data, paginate, ChartMetadata, ReadError,
+};
+pub use resolution::downsample;
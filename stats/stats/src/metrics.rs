@@ -1,25 +1,56 @@
 use lazy_static::lazy_static;
-use prometheus::{register_histogram_vec, register_int_counter_vec, HistogramVec, IntCounterVec};
+use prometheus::{
+    register_histogram_vec, register_int_counter_vec, register_int_gauge_vec, HistogramVec,
+    IntCounterVec, IntGaugeVec,
+};
+use std::future::Future;
+
+tokio::task_local! {
+    static CHAIN_ID: String;
+}
+
+/// Runs `update` with metrics and update mutexes of charts keyed by `chain_id`,
+/// so the same charts of different chains are neither merged nor block each other
+pub async fn with_chain_id<F: Future>(chain_id: &str, update: F) -> F::Output {
+    CHAIN_ID.scope(chain_id.to_string(), update).await
+}
+
+/// Id of the chain set by [`with_chain_id`], empty outside of it
+pub fn chain_id() -> String {
+    CHAIN_ID.try_with(Clone::clone).unwrap_or_default()
+}
 
 lazy_static! {
     pub static ref UPDATE_ERRORS: IntCounterVec = register_int_counter_vec!(
         "stats_update_errors_total",
         "total update errors",
-        &["chart_id"],
+        &["chain_id", "chart_id"],
     )
     .unwrap();
     pub static ref CHART_UPDATE_TIME: HistogramVec = register_histogram_vec!(
         "stats_chart_update_time_seconds",
         "single chart update time",
-        &["chart_id"],
+        &["chain_id", "chart_id"],
         vec![1.0, 2.0, 4.0, 8.0, 16.0, 30.0, 60.0, 120.0, 240.0, 480.0, 960.0, 1920.0, 3840.0],
     )
     .unwrap();
     pub static ref CHART_FETCH_NEW_DATA_TIME: HistogramVec = register_histogram_vec!(
         "stats_fetch_new_data_time_seconds",
         "single chart time for fetching data from blockscout",
-        &["chart_id"],
+        &["chain_id", "chart_id"],
         vec![1.0, 2.0, 4.0, 8.0, 16.0, 30.0, 60.0, 120.0, 240.0, 480.0, 960.0, 1920.0, 3840.0],
     )
     .unwrap();
+    pub static ref CHART_ROWS_WRITTEN: IntCounterVec = register_int_counter_vec!(
+        "stats_chart_rows_written_total",
+        "total rows of chart data written during updates",
+        &["chain_id", "chart_id"],
+    )
+    .unwrap();
+    pub static ref CHART_LAST_SUCCESS_TIME: IntGaugeVec = register_int_gauge_vec!(
+        "stats_chart_last_success_timestamp_seconds",
+        "unix timestamp of the last successful chart update",
+        &["chain_id", "chart_id"],
+    )
+    .unwrap();
 }
@@ -60,6 +60,7 @@ pub fn fill_missing_points(
     match policy {
         MissingDatePolicy::FillZero => fill_zeros(data, from, to),
         MissingDatePolicy::FillPrevious => fill_previous(data, from, to),
+        MissingDatePolicy::LeaveGap => data,
     }
 }
 
@@ -102,7 +103,7 @@ fn fill_previous(data: Vec<DateValue>, from: NaiveDate, to: NaiveDate) -> Vec<Da
                 .last()
                 .map(|value| DateValue {
                     date: current_date,
-                    value: value.value.clone(),
+                    value: value.value,
                 })
                 .unwrap_or_else(|| DateValue::zero(current_date)),
         };
@@ -146,7 +147,7 @@ mod tests {
     fn v(date: &str, value: &str) -> DateValue {
         DateValue {
             date: d(date),
-            value: value.to_string(),
+            value: value.parse().unwrap(),
         }
     }
 
@@ -256,4 +257,25 @@ mod tests {
             assert_eq!(expected, actual);
         }
     }
+
+    #[test]
+    fn leave_gap_works() {
+        for (data, from, to) in [
+            (vec![], None, None),
+            (vec![], Some(d("2100-01-01")), Some(d("2100-01-01"))),
+            (
+                vec![
+                    v("2022-08-20", "20"),
+                    v("2022-08-22", "22"),
+                    v("2022-08-23", "23"),
+                    v("2022-08-25", "25"),
+                ],
+                Some(d("2022-08-18")),
+                Some(d("2022-08-27")),
+            ),
+        ] {
+            let actual = fill_missing_points(data.clone(), MissingDatePolicy::LeaveGap, from, to);
+            assert_eq!(data, actual);
+        }
+    }
 }
@@ -1,9 +1,12 @@
-use crate::{charts::insert::DateValue, missing_date::get_and_fill_chart, MissingDatePolicy};
-use chrono::NaiveDate;
-use entity::{chart_data, charts};
+use crate::{
+    charts::insert::DateValue, missing_date::get_and_fill_chart, resolution::aggregate_points,
+    AggregationPolicy, MissingDatePolicy, ResolutionKind,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use entity::{chart_data, chart_series_data, charts};
 use sea_orm::{
-    ColumnTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait, FromQueryResult, QueryFilter,
-    QueryOrder, QuerySelect, Statement,
+    prelude::Decimal, ColumnTrait, DatabaseConnection, DbBackend, DbErr, EntityTrait,
+    FromQueryResult, QueryFilter, QueryOrder, QuerySelect, Statement,
 };
 use std::collections::HashMap;
 use thiserror::Error;
@@ -14,13 +17,15 @@ pub enum ReadError {
     DB(#[from] DbErr),
     #[error("chart {0} not found")]
     NotFound(String),
+    #[error("internal error: {0}")]
+    Internal(String),
 }
 
 #[derive(Debug, FromQueryResult)]
 struct CounterData {
     name: String,
     date: NaiveDate,
-    value: String,
+    value: Decimal,
 }
 
 pub async fn get_counters(
@@ -57,6 +62,44 @@ pub async fn get_counters(
     Ok(counters)
 }
 
+/// Bookkeeping information about chart
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChartMetadata {
+    /// Time of the last successful update, `None` if chart was never updated
+    pub last_updated_at: Option<DateTime<Utc>>,
+    /// Time of the last update attempt, either successful or not
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    /// Error of the last update attempt, `None` if it succeeded
+    pub last_error: Option<String>,
+    pub last_duration_ms: Option<i64>,
+    pub last_rows_written: Option<i64>,
+    /// Number of the last update runs that failed in a row
+    pub failed_updates: u32,
+}
+
+pub async fn get_charts_metadata(
+    db: &DatabaseConnection,
+) -> Result<HashMap<String, ChartMetadata>, ReadError> {
+    let charts = charts::Entity::find().all(db).await?;
+    let metadata = charts
+        .into_iter()
+        .map(|chart| {
+            let metadata = ChartMetadata {
+                last_updated_at: chart.last_updated_at.map(|time| time.with_timezone(&Utc)),
+                last_attempt_at: chart
+                    .last_update_attempt_at
+                    .map(|time| time.with_timezone(&Utc)),
+                last_error: chart.last_update_error,
+                last_duration_ms: chart.last_update_duration_ms,
+                last_rows_written: chart.last_update_rows,
+                failed_updates: chart.failed_updates.try_into().unwrap_or_default(),
+            };
+            (chart.name, metadata)
+        })
+        .collect();
+    Ok(metadata)
+}
+
 pub async fn get_chart_data(
     db: &DatabaseConnection,
     name: &str,
@@ -73,16 +116,157 @@ pub async fn get_chart_data(
 
     let data = match policy {
         Some(policy) => get_and_fill_chart(db, chart.id, from, to, policy).await?,
-        None => get_chart(db, chart.id, from, to).await?,
+        None => get_chart(db, chart.id, from, to, None).await?,
     };
     Ok(data)
 }
 
+/// Returns first `limit` stored points of the chart from `from` up to `to`,
+/// so long charts can be read chunk by chunk without loading them fully.
+/// Missing dates are not filled
+pub async fn get_chart_data_chunk(
+    db: &DatabaseConnection,
+    name: &str,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    limit: u64,
+) -> Result<Vec<DateValue>, ReadError> {
+    let chart = charts::Entity::find()
+        .column(charts::Column::Id)
+        .filter(charts::Column::Name.eq(name))
+        .one(db)
+        .await?
+        .ok_or_else(|| ReadError::NotFound(name.into()))?;
+    let data = get_chart(db, chart.id, from, to, Some(limit)).await?;
+    Ok(data)
+}
+
+/// Returns chart data aggregated to `resolution`.
+/// Points are dated by the first day of their period, so `from` is extended
+/// to the start of its period, while the last period contains data up to `to` only.
+/// Periods without any data are missing for `AggregationPolicy::Average`
+pub async fn get_chart_data_with_resolution(
+    db: &DatabaseConnection,
+    name: &str,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    policy: Option<MissingDatePolicy>,
+    resolution: ResolutionKind,
+    aggregation: AggregationPolicy,
+) -> Result<Vec<DateValue>, ReadError> {
+    if resolution == ResolutionKind::Day {
+        return get_chart_data(db, name, from, to, policy).await;
+    }
+    let from = from.map(|from| resolution.period_start(from));
+    // average of filled zeros is not an average of actual values
+    let policy = match aggregation {
+        AggregationPolicy::Average => None,
+        AggregationPolicy::Sum | AggregationPolicy::Last => policy,
+    };
+    let data = get_chart_data(db, name, from, to, policy).await?;
+    aggregate_points(data, resolution, aggregation)
+}
+
+/// Returns points of series `key` of the chart aggregated to `resolution`,
+/// see [`Chart::series`]. Dates without points are missing
+///
+/// [`Chart::series`]: crate::Chart::series
+pub async fn get_series_data(
+    db: &DatabaseConnection,
+    name: &str,
+    key: &str,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    resolution: ResolutionKind,
+    aggregation: AggregationPolicy,
+) -> Result<Vec<DateValue>, ReadError> {
+    let chart = charts::Entity::find()
+        .column(charts::Column::Id)
+        .filter(charts::Column::Name.eq(name))
+        .one(db)
+        .await?
+        .ok_or_else(|| ReadError::NotFound(name.into()))?;
+    let mut data_request = chart_series_data::Entity::find()
+        .select_only()
+        .column(chart_series_data::Column::Date)
+        .column(chart_series_data::Column::Value)
+        .filter(chart_series_data::Column::ChartId.eq(chart.id))
+        .filter(chart_series_data::Column::Series.eq(key))
+        .order_by_asc(chart_series_data::Column::Date);
+    if let Some(from) = from {
+        let from = resolution.period_start(from);
+        data_request = data_request.filter(chart_series_data::Column::Date.gte(from));
+    }
+    if let Some(to) = to {
+        data_request = data_request.filter(chart_series_data::Column::Date.lte(to));
+    }
+    let data = data_request.into_model().all(db).await?;
+    aggregate_points(data, resolution, aggregation)
+}
+
+#[derive(FromQueryResult)]
+struct PeriodStart {
+    date: NaiveDate,
+}
+
+/// Returns start of the period the next page begins with, if a page of `page_size` periods
+/// in `resolution` from `from` doesn't contain all stored points of the chart.
+/// Only `page_size + 1` dates are read, so the page can be fetched up to the day before
+/// the returned date instead of reading the rest of the chart
+pub async fn get_next_page_start(
+    db: &DatabaseConnection,
+    name: &str,
+    from: Option<NaiveDate>,
+    page_size: u64,
+    resolution: ResolutionKind,
+) -> Result<Option<NaiveDate>, ReadError> {
+    let chart = charts::Entity::find()
+        .column(charts::Column::Id)
+        .filter(charts::Column::Name.eq(name))
+        .one(db)
+        .await?
+        .ok_or_else(|| ReadError::NotFound(name.into()))?;
+    let from = from.map(|from| resolution.period_start(from));
+    let periods = PeriodStart::find_by_statement(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        SELECT DISTINCT DATE_TRUNC($1, date)::DATE as date
+        FROM chart_data
+        WHERE chart_id = $2 AND ($3::DATE IS NULL OR date >= $3)
+        ORDER BY date
+        LIMIT $4
+        "#,
+        vec![
+            resolution.date_trunc_field().into(),
+            chart.id.into(),
+            from.into(),
+            (page_size as i64 + 1).into(),
+        ],
+    ))
+    .all(db)
+    .await?;
+    Ok(periods
+        .get(page_size as usize)
+        .map(|next_page_start| next_page_start.date))
+}
+
+/// Splits `data` into the first `page_size` points and date of the point the next page starts from,
+/// which is `None` if there are no points left
+pub fn paginate(mut data: Vec<DateValue>, page_size: usize) -> (Vec<DateValue>, Option<NaiveDate>) {
+    if data.len() <= page_size {
+        return (data, None);
+    }
+    let next_page_start = data[page_size].date;
+    data.truncate(page_size);
+    (data, Some(next_page_start))
+}
+
 async fn get_chart(
     db: &DatabaseConnection,
     chart_id: i32,
     from: Option<NaiveDate>,
     to: Option<NaiveDate>,
+    limit: Option<u64>,
 ) -> Result<Vec<DateValue>, DbErr> {
     let data_request = chart_data::Entity::find()
         .column(chart_data::Column::Date)
@@ -100,13 +284,23 @@ async fn get_chart(
     } else {
         data_request
     };
+    let data_request = if let Some(limit) = limit {
+        data_request.limit(limit)
+    } else {
+        data_request
+    };
     data_request.into_model().all(db).await
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{counters::TotalBlocks, tests::init_db::init_db, Chart};
+    use crate::{
+        charts::{save_update_attempt, save_update_run, set_last_updated_at, UpdateAttempt},
+        counters::TotalBlocks,
+        tests::init_db::init_db,
+        Chart,
+    };
     use entity::{chart_data, charts, sea_orm_active_enums::ChartType};
     use pretty_assertions::assert_eq;
     use sea_orm::{EntityTrait, Set};
@@ -116,7 +310,7 @@ mod tests {
         chart_data::ActiveModel {
             chart_id: Set(chart_id),
             date: Set(NaiveDate::from_str(date).unwrap()),
-            value: Set(value.to_string()),
+            value: Set(value.into()),
             ..Default::default()
         }
     }
@@ -150,13 +344,50 @@ mod tests {
         .unwrap();
     }
 
+    async fn insert_sparse_mock_data(db: &DatabaseConnection) {
+        charts::Entity::insert(charts::ActiveModel {
+            name: Set("sparseChart".into()),
+            chart_type: Set(ChartType::Line),
+            ..Default::default()
+        })
+        .exec(db)
+        .await
+        .unwrap();
+        chart_data::Entity::insert_many([
+            mock_chart_data(3, "2022-11-10", 10),
+            mock_chart_data(3, "2022-11-13", 13),
+        ])
+        .exec(db)
+        .await
+        .unwrap();
+    }
+
     fn value(date: &str, value: &str) -> DateValue {
         DateValue {
             date: NaiveDate::from_str(date).unwrap(),
-            value: value.to_string(),
+            value: value.parse().unwrap(),
         }
     }
 
+    #[test]
+    fn paginate_works() {
+        let data = vec![
+            value("2022-11-10", "1"),
+            value("2022-11-11", "2"),
+            value("2022-11-12", "3"),
+        ];
+        assert_eq!(
+            paginate(data.clone(), 2),
+            (
+                vec![value("2022-11-10", "1"), value("2022-11-11", "2")],
+                Some(NaiveDate::from_str("2022-11-12").unwrap())
+            )
+        );
+        assert_eq!(paginate(data.clone(), 3), (data.clone(), None));
+        assert_eq!(paginate(data.clone(), 10), (data, None));
+        assert_eq!(paginate(vec![], 10), (vec![], None));
+    }
+
     #[tokio::test]
     #[ignore = "needs database to run"]
     async fn get_counters_mock() {
@@ -185,18 +416,239 @@ mod tests {
             vec![
                 DateValue {
                     date: NaiveDate::from_str("2022-11-10").unwrap(),
-                    value: "100".into(),
+                    value: 100.into(),
                 },
                 DateValue {
                     date: NaiveDate::from_str("2022-11-11").unwrap(),
-                    value: "150".into(),
+                    value: 150.into(),
                 },
                 DateValue {
                     date: NaiveDate::from_str("2022-11-12").unwrap(),
-                    value: "200".into(),
+                    value: 200.into(),
                 },
             ],
             chart
         );
     }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn get_chart_with_resolution_mock() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let db = init_db("get_chart_with_resolution_mock").await;
+        insert_mock_data(&db).await;
+        for (resolution, aggregation, from, expected) in [
+            (
+                ResolutionKind::Day,
+                AggregationPolicy::Sum,
+                "2022-11-11",
+                vec![value("2022-11-11", "150"), value("2022-11-12", "200")],
+            ),
+            (
+                ResolutionKind::Week,
+                AggregationPolicy::Sum,
+                "2022-11-11",
+                vec![value("2022-11-07", "450")],
+            ),
+            (
+                ResolutionKind::Month,
+                AggregationPolicy::Average,
+                "2022-11-01",
+                vec![value("2022-11-01", "150")],
+            ),
+            (
+                ResolutionKind::Year,
+                AggregationPolicy::Last,
+                "2022-11-01",
+                vec![value("2022-01-01", "200")],
+            ),
+        ] {
+            let chart = get_chart_data_with_resolution(
+                &db,
+                "newBlocksPerDay",
+                Some(NaiveDate::from_str(from).unwrap()),
+                Some(NaiveDate::from_str("2022-11-12").unwrap()),
+                Some(MissingDatePolicy::FillZero),
+                resolution,
+                aggregation,
+            )
+            .await
+            .unwrap();
+            assert_eq!(expected, chart, "{resolution:?} {aggregation:?}");
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn get_chart_sparse_mock() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let db = init_db("get_chart_sparse_mock").await;
+        insert_mock_data(&db).await;
+        insert_sparse_mock_data(&db).await;
+
+        for (policy, expected) in [
+            (
+                MissingDatePolicy::FillZero,
+                vec![
+                    value("2022-11-10", "10"),
+                    value("2022-11-11", "0"),
+                    value("2022-11-12", "0"),
+                    value("2022-11-13", "13"),
+                    value("2022-11-14", "0"),
+                ],
+            ),
+            (
+                MissingDatePolicy::FillPrevious,
+                vec![
+                    value("2022-11-10", "10"),
+                    value("2022-11-11", "10"),
+                    value("2022-11-12", "10"),
+                    value("2022-11-13", "13"),
+                    value("2022-11-14", "13"),
+                ],
+            ),
+            (
+                MissingDatePolicy::LeaveGap,
+                vec![value("2022-11-10", "10"), value("2022-11-13", "13")],
+            ),
+        ] {
+            let chart = get_chart_data(
+                &db,
+                "sparseChart",
+                Some(NaiveDate::from_str("2022-11-10").unwrap()),
+                Some(NaiveDate::from_str("2022-11-14").unwrap()),
+                Some(policy),
+            )
+            .await
+            .unwrap();
+            assert_eq!(expected, chart, "{policy:?}");
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn get_chart_data_chunk_mock() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let db = init_db("get_chart_data_chunk_mock").await;
+        insert_mock_data(&db).await;
+        let chunk = get_chart_data_chunk(&db, "newBlocksPerDay", None, None, 2)
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![value("2022-11-10", "100"), value("2022-11-11", "150")],
+            chunk
+        );
+        let chunk = get_chart_data_chunk(
+            &db,
+            "newBlocksPerDay",
+            Some(NaiveDate::from_str("2022-11-12").unwrap()),
+            None,
+            2,
+        )
+        .await
+        .unwrap();
+        assert_eq!(vec![value("2022-11-12", "200")], chunk);
+        let chunk = get_chart_data_chunk(
+            &db,
+            "newBlocksPerDay",
+            Some(NaiveDate::from_str("2022-11-11").unwrap()),
+            Some(NaiveDate::from_str("2022-11-11").unwrap()),
+            2,
+        )
+        .await
+        .unwrap();
+        assert_eq!(vec![value("2022-11-11", "150")], chunk);
+        assert!(matches!(
+            get_chart_data_chunk(&db, "unknownChart", None, None, 2).await,
+            Err(ReadError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn get_next_page_start_mock() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let db = init_db("get_next_page_start_mock").await;
+        insert_mock_data(&db).await;
+        let next = get_next_page_start(&db, "newBlocksPerDay", None, 2, ResolutionKind::Day)
+            .await
+            .unwrap();
+        assert_eq!(next, Some(NaiveDate::from_str("2022-11-12").unwrap()));
+        let next = get_next_page_start(
+            &db,
+            "newBlocksPerDay",
+            Some(NaiveDate::from_str("2022-11-11").unwrap()),
+            2,
+            ResolutionKind::Day,
+        )
+        .await
+        .unwrap();
+        assert_eq!(next, None);
+        let next = get_next_page_start(&db, "newBlocksPerDay", None, 1, ResolutionKind::Week)
+            .await
+            .unwrap();
+        assert_eq!(next, None);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn get_charts_metadata_mock() {
+        let _ = tracing_subscriber::fmt::try_init();
+
+        let db = init_db("get_charts_metadata_mock").await;
+        insert_mock_data(&db).await;
+        let metadata = get_charts_metadata(&db).await.unwrap();
+        assert_eq!(metadata.len(), 2);
+        assert!(metadata.values().all(|m| m.last_updated_at.is_none()));
+
+        let before = Utc::now();
+        set_last_updated_at(&db, "newBlocksPerDay").await.unwrap();
+        let metadata = get_charts_metadata(&db).await.unwrap();
+        let last_updated_at = metadata["newBlocksPerDay"]
+            .last_updated_at
+            .expect("chart has to be marked as updated");
+        assert!(last_updated_at >= before - chrono::Duration::seconds(1));
+        assert_eq!(metadata["totalBlocks"].last_updated_at, None);
+
+        let attempt = UpdateAttempt {
+            duration: std::time::Duration::from_millis(1500),
+            rows_written: 0,
+            error: Some("blockscout database error: timeout".into()),
+        };
+        save_update_attempt(&db, "totalBlocks", &attempt)
+            .await
+            .unwrap();
+        let metadata = get_charts_metadata(&db).await.unwrap();
+        let total_blocks = &metadata["totalBlocks"];
+        assert_eq!(total_blocks.last_updated_at, None);
+        assert!(total_blocks.last_attempt_at.is_some());
+        assert_eq!(total_blocks.last_error, attempt.error);
+        assert_eq!(total_blocks.last_duration_ms, Some(1500));
+        assert_eq!(total_blocks.last_rows_written, Some(0));
+
+        let attempt = UpdateAttempt {
+            duration: std::time::Duration::from_millis(20),
+            rows_written: 1,
+            error: None,
+        };
+        save_update_attempt(&db, "totalBlocks", &attempt)
+            .await
+            .unwrap();
+        let metadata = get_charts_metadata(&db).await.unwrap();
+        let total_blocks = &metadata["totalBlocks"];
+        assert!(total_blocks.last_updated_at.is_some());
+        assert_eq!(total_blocks.last_error, None);
+        assert_eq!(total_blocks.last_rows_written, Some(1));
+
+        assert_eq!(save_update_run(&db, "totalBlocks", false).await.unwrap(), 1);
+        assert_eq!(save_update_run(&db, "totalBlocks", false).await.unwrap(), 2);
+        let metadata = get_charts_metadata(&db).await.unwrap();
+        assert_eq!(metadata["totalBlocks"].failed_updates, 2);
+        assert_eq!(metadata["newBlocksPerDay"].failed_updates, 0);
+        assert_eq!(save_update_run(&db, "totalBlocks", true).await.unwrap(), 0);
+    }
 }
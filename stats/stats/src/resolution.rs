@@ -0,0 +1,225 @@
+use crate::{AggregationPolicy, DateValue, ReadError, ResolutionKind};
+use chrono::NaiveDate;
+use sea_orm::prelude::Decimal;
+
+/// Combines sorted daily points into points of `resolution`.
+/// Every resulting point is dated by the first day of its period
+pub fn aggregate_points(
+    data: Vec<DateValue>,
+    resolution: ResolutionKind,
+    policy: AggregationPolicy,
+) -> Result<Vec<DateValue>, ReadError> {
+    if resolution == ResolutionKind::Day {
+        return Ok(data);
+    }
+    let mut periods: Vec<(NaiveDate, Vec<DateValue>)> = Vec::new();
+    for point in data {
+        let start = resolution.period_start(point.date);
+        match periods.last_mut() {
+            Some((date, points)) if *date == start => points.push(point),
+            _ => periods.push((start, vec![point])),
+        }
+    }
+    periods
+        .into_iter()
+        .map(|(date, points)| {
+            let value = aggregate_values(&points, policy)?;
+            Ok(DateValue { date, value })
+        })
+        .collect()
+}
+
+/// Merges consecutive points into buckets of equal size, so that there are
+/// at most `max_points` points. Every bucket is dated by its first point
+pub fn downsample(
+    data: Vec<DateValue>,
+    max_points: usize,
+    policy: AggregationPolicy,
+) -> Result<Vec<DateValue>, ReadError> {
+    if max_points == 0 || data.len() <= max_points {
+        return Ok(data);
+    }
+    let bucket_size = data.len().div_ceil(max_points);
+    data.chunks(bucket_size)
+        .map(|bucket| {
+            let value = aggregate_values(bucket, policy)?;
+            Ok(DateValue {
+                date: bucket[0].date,
+                value,
+            })
+        })
+        .collect()
+}
+
+fn aggregate_values(points: &[DateValue], policy: AggregationPolicy) -> Result<Decimal, ReadError> {
+    let value = match policy {
+        AggregationPolicy::Last => points.last().map(|point| point.value).unwrap_or_default(),
+        AggregationPolicy::Sum => sum_values(points)?.normalize(),
+        AggregationPolicy::Average => {
+            let sum = sum_values(points)?;
+            let count = Decimal::from(points.len().max(1));
+            (sum / count).normalize()
+        }
+    };
+    Ok(value)
+}
+
+fn sum_values(points: &[DateValue]) -> Result<Decimal, ReadError> {
+    points.iter().try_fold(Decimal::ZERO, |sum, point| {
+        sum.checked_add(point.value)
+            .ok_or_else(|| ReadError::Internal("overflow during aggregation".to_string()))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+
+    fn d(date: &str) -> NaiveDate {
+        date.parse().unwrap()
+    }
+    fn v(date: &str, value: &str) -> DateValue {
+        DateValue {
+            date: d(date),
+            value: value.parse().unwrap(),
+        }
+    }
+
+    #[test]
+    fn periods_work() {
+        for (resolution, date, start, end) in [
+            (
+                ResolutionKind::Day,
+                "2023-03-15",
+                "2023-03-15",
+                "2023-03-15",
+            ),
+            (
+                ResolutionKind::Week,
+                "2023-03-15",
+                "2023-03-13",
+                "2023-03-19",
+            ),
+            (
+                ResolutionKind::Week,
+                "2023-03-13",
+                "2023-03-13",
+                "2023-03-19",
+            ),
+            (
+                ResolutionKind::Week,
+                "2023-01-01",
+                "2022-12-26",
+                "2023-01-01",
+            ),
+            (
+                ResolutionKind::Month,
+                "2023-02-15",
+                "2023-02-01",
+                "2023-02-28",
+            ),
+            (
+                ResolutionKind::Month,
+                "2024-02-29",
+                "2024-02-01",
+                "2024-02-29",
+            ),
+            (
+                ResolutionKind::Month,
+                "2022-12-31",
+                "2022-12-01",
+                "2022-12-31",
+            ),
+            (
+                ResolutionKind::Year,
+                "2022-11-10",
+                "2022-01-01",
+                "2022-12-31",
+            ),
+        ] {
+            assert_eq!(resolution.period_start(d(date)), d(start), "{resolution:?}");
+            assert_eq!(resolution.period_end(d(date)), d(end), "{resolution:?}");
+        }
+    }
+
+    #[test]
+    fn aggregate_points_works() {
+        let data = vec![
+            v("2022-11-09", "5"),
+            v("2022-11-10", "12"),
+            v("2022-11-14", "3"),
+            v("2022-12-01", "0.5"),
+            v("2023-01-01", "1"),
+        ];
+        for (resolution, policy, expected) in [
+            (ResolutionKind::Day, AggregationPolicy::Sum, data.clone()),
+            (
+                ResolutionKind::Week,
+                AggregationPolicy::Sum,
+                vec![
+                    v("2022-11-07", "17"),
+                    v("2022-11-14", "3"),
+                    v("2022-11-28", "0.5"),
+                    v("2022-12-26", "1"),
+                ],
+            ),
+            (
+                ResolutionKind::Month,
+                AggregationPolicy::Average,
+                vec![
+                    v("2022-11-01", "6.6666666666666666666666666667"),
+                    v("2022-12-01", "0.5"),
+                    v("2023-01-01", "1"),
+                ],
+            ),
+            (
+                ResolutionKind::Year,
+                AggregationPolicy::Last,
+                vec![v("2022-01-01", "0.5"), v("2023-01-01", "1")],
+            ),
+            (
+                ResolutionKind::Year,
+                AggregationPolicy::Sum,
+                vec![v("2022-01-01", "20.5"), v("2023-01-01", "1")],
+            ),
+        ] {
+            let actual = aggregate_points(data.clone(), resolution, policy).unwrap();
+            assert_eq!(expected, actual, "{resolution:?} {policy:?}");
+        }
+    }
+
+    #[test]
+    fn downsample_works() {
+        let data = vec![
+            v("2022-11-09", "5"),
+            v("2022-11-10", "12"),
+            v("2022-11-12", "3"),
+            v("2022-11-13", "0.5"),
+            v("2022-11-14", "1"),
+        ];
+        for (max_points, policy, expected) in [
+            (0, AggregationPolicy::Sum, data.clone()),
+            (5, AggregationPolicy::Sum, data.clone()),
+            (10, AggregationPolicy::Sum, data.clone()),
+            (
+                4,
+                AggregationPolicy::Sum,
+                vec![
+                    v("2022-11-09", "17"),
+                    v("2022-11-12", "3.5"),
+                    v("2022-11-14", "1"),
+                ],
+            ),
+            (
+                2,
+                AggregationPolicy::Last,
+                vec![v("2022-11-09", "3"), v("2022-11-13", "1")],
+            ),
+            (1, AggregationPolicy::Average, vec![v("2022-11-09", "4.3")]),
+        ] {
+            let actual = downsample(data.clone(), max_points, policy).unwrap();
+            assert_eq!(expected, actual, "{max_points} {policy:?}");
+        }
+    }
+}
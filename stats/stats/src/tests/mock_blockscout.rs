@@ -1,9 +1,12 @@
 use blockscout_db::entity::{
-    address_coin_balances_daily, addresses, block_rewards, blocks, internal_transactions,
-    smart_contracts, tokens, transactions,
+    address_coin_balances_daily, address_token_balances, addresses, block_rewards, blocks,
+    internal_transactions, smart_contracts, token_transfers, tokens, transactions,
 };
 use chrono::{NaiveDate, NaiveDateTime};
-use sea_orm::{prelude::Decimal, ActiveValue::NotSet, DatabaseConnection, EntityTrait, Set};
+use sea_orm::{
+    prelude::Decimal, ActiveValue::NotSet, ConnectionTrait, DatabaseConnection, DbBackend,
+    EntityTrait, Set, Statement,
+};
 use std::str::FromStr;
 
 pub async fn fill_mock_blockscout_data(blockscout: &DatabaseConnection, max_date: &str) {
@@ -76,10 +79,19 @@ pub async fn fill_mock_blockscout_data(blockscout: &DatabaseConnection, max_date
         .await
         .unwrap();
 
+    let token_inserted_at = vec![
+        "2022-11-09T12:00:00",
+        "2022-11-10T08:00:00",
+        "2022-11-10T23:59:59",
+        "2022-11-11T00:00:00",
+    ]
+    .into_iter()
+    .map(|val| NaiveDateTime::from_str(val).unwrap());
     let tokens = accounts
         .iter()
         .take(4)
-        .map(|addr| mock_token(addr.hash.as_ref().clone()));
+        .zip(token_inserted_at)
+        .map(|(addr, inserted_at)| mock_token(addr.hash.as_ref().clone(), inserted_at));
     tokens::Entity::insert_many(tokens)
         .exec(blockscout)
         .await
@@ -271,6 +283,106 @@ pub async fn fill_mock_blockscout_data(blockscout: &DatabaseConnection, max_date
         .exec(blockscout)
         .await
         .unwrap();
+
+    // every contract call transfers the first token
+    let token = accounts[0].hash.as_ref().clone();
+    let blocks_with_txns: Vec<_> = blocks[0..blocks.len() - 1]
+        .iter()
+        .filter(|b| b.number.as_ref() % 3 != 1)
+        .collect();
+    let transfers = blocks_with_txns
+        .iter()
+        .map(|b| mock_token_transfer(b, &token, &accounts));
+    token_transfers::Entity::insert_many(transfers)
+        .exec(blockscout)
+        .await
+        .unwrap();
+    let token_balances = blocks_with_txns
+        .iter()
+        .flat_map(|b| mock_token_balances(b, &token, &accounts));
+    address_token_balances::Entity::insert_many(token_balances)
+        .exec(blockscout)
+        .await
+        .unwrap();
+}
+
+/// Adds tables indexed by blockscout for optimism rollups, which are missing in blockscout
+/// migrations, and fills them on top of [`fill_mock_blockscout_data`]
+pub async fn fill_mock_optimism_data(blockscout: &DatabaseConnection) {
+    let statements = [
+        r#"
+        CREATE TABLE op_frame_sequences (
+            id bigint PRIMARY KEY,
+            l1_transaction_hashes bytea[] NOT NULL DEFAULT '{}',
+            l1_timestamp timestamp NOT NULL
+        )
+        "#,
+        r#"
+        INSERT INTO op_frame_sequences (id, l1_timestamp) VALUES
+            (1, '2022-11-10T01:00:00'),
+            (2, '2022-11-10T13:00:00'),
+            (3, '2022-11-11T01:00:00')
+        "#,
+        r#"
+        CREATE TABLE op_withdrawals (
+            msg_nonce numeric PRIMARY KEY,
+            hash bytea NOT NULL,
+            l2_transaction_hash bytea NOT NULL,
+            l2_block_number bigint NOT NULL
+        )
+        "#,
+        // withdrawals from blocks of 2022-11-10 and 2022-11-11
+        r#"
+        INSERT INTO op_withdrawals (msg_nonce, hash, l2_transaction_hash, l2_block_number) VALUES
+            (1, '\x01', '\x01', 2),
+            (2, '\x02', '\x02', 3),
+            (3, '\x03', '\x03', 5)
+        "#,
+        "ALTER TABLE transactions ADD COLUMN l1_fee numeric",
+        // 0.001 of native coin for the first transaction of the blocks
+        "UPDATE transactions SET l1_fee = 1000000000000000 WHERE index = 0 AND block_number IN (2, 3, 5)",
+    ];
+    for sql in statements {
+        blockscout
+            .execute(Statement::from_string(DbBackend::Postgres, sql.into()))
+            .await
+            .unwrap();
+    }
+}
+
+/// Adds table of user operations indexer, which is missing in blockscout migrations,
+/// and fills it on top of [`fill_mock_blockscout_data`]
+pub async fn fill_mock_user_ops_data(blockscout: &DatabaseConnection) {
+    let statements = [
+        r#"
+        CREATE TABLE user_operations (
+            hash bytea PRIMARY KEY,
+            sender bytea NOT NULL,
+            block_number integer NOT NULL,
+            block_hash bytea NOT NULL
+        )
+        "#,
+        // operations of 3 accounts in blocks of 2022-11-10, 2022-11-11 and 2022-12-01
+        r#"
+        INSERT INTO user_operations (hash, sender, block_number, block_hash)
+        SELECT ops.hash, ops.sender, b.number, b.hash
+        FROM (VALUES
+            ('\x01'::bytea, '\x0a'::bytea, 1),
+            ('\x02'::bytea, '\x0a'::bytea, 2),
+            ('\x03'::bytea, '\x0b'::bytea, 3),
+            ('\x04'::bytea, '\x0b'::bytea, 5),
+            ('\x05'::bytea, '\x0b'::bytea, 6),
+            ('\x06'::bytea, '\x0c'::bytea, 9)
+        ) AS ops(hash, sender, number)
+        JOIN blocks b ON b.number = ops.number
+        "#,
+    ];
+    for sql in statements {
+        blockscout
+            .execute(Statement::from_string(DbBackend::Postgres, sql.into()))
+            .await
+            .unwrap();
+    }
 }
 
 fn mock_block(index: i64, ts: &str, consensus: bool) -> blocks::ActiveModel {
@@ -375,7 +487,67 @@ fn mock_transaction(
     }
 }
 
-fn mock_failed_transaction(
+/// Token transfer made by the contract call transaction of the block
+fn mock_token_transfer(
+    block: &blocks::ActiveModel,
+    token: &[u8],
+    address_list: &[addresses::ActiveModel],
+) -> token_transfers::ActiveModel {
+    let block_number = block.number.as_ref().to_owned() as i32;
+    let (from, to) = transfer_participants(block_number as usize, address_list);
+    token_transfers::ActiveModel {
+        transaction_hash: Set(vec![0, 0, 0, 0, block_number as u8, 2]),
+        log_index: Set(0),
+        from_address_hash: Set(from),
+        to_address_hash: Set(to),
+        amount: Set(Some(Decimal::new(100, 0))),
+        token_id: Set(None),
+        token_contract_address_hash: Set(token.to_vec()),
+        inserted_at: Set(Default::default()),
+        updated_at: Set(Default::default()),
+        block_number: Set(Some(block_number)),
+        block_hash: Set(block.hash.as_ref().to_vec()),
+        amounts: Set(None),
+        token_ids: Set(None),
+    }
+}
+
+fn mock_token_balances(
+    block: &blocks::ActiveModel,
+    token: &[u8],
+    address_list: &[addresses::ActiveModel],
+) -> [address_token_balances::ActiveModel; 2] {
+    let block_number = block.number.as_ref().to_owned();
+    let (from, to) = transfer_participants(block_number as usize, address_list);
+    [(from, 0), (to, 100)].map(|(address, value)| address_token_balances::ActiveModel {
+        address_hash: Set(address),
+        block_number: Set(block_number),
+        token_contract_address_hash: Set(token.to_vec()),
+        value: Set(Some(Decimal::new(value, 0))),
+        value_fetched_at: Set(None),
+        inserted_at: Set(Default::default()),
+        updated_at: Set(Default::default()),
+        token_id: Set(None),
+        token_type: Set(Some("ERC-20".into())),
+        ..Default::default()
+    })
+}
+
+/// Sender and receiver of transactions in the block, same as in `mock_transaction`
+fn transfer_participants(
+    block_number: usize,
+    address_list: &[addresses::ActiveModel],
+) -> (Vec<u8>, Vec<u8>) {
+    let from = address_list[block_number % address_list.len()]
+        .hash
+        .as_ref();
+    let to = address_list[(block_number + 1) % address_list.len()]
+        .hash
+        .as_ref();
+    (from.to_vec(), to.to_vec())
+}
+
+pub fn mock_failed_transaction(
     hash: Vec<u8>,
     block: Option<&blocks::ActiveModel>,
     error: Option<String>,
@@ -419,11 +591,11 @@ fn mock_address_coin_balance_daily(
     }
 }
 
-fn mock_token(hash: Vec<u8>) -> tokens::ActiveModel {
+fn mock_token(hash: Vec<u8>, inserted_at: NaiveDateTime) -> tokens::ActiveModel {
     tokens::ActiveModel {
         r#type: Set(Default::default()),
         contract_address_hash: Set(hash),
-        inserted_at: Set(Default::default()),
+        inserted_at: Set(inserted_at),
         updated_at: Set(Default::default()),
         ..Default::default()
     }
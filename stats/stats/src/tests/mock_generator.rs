@@ -0,0 +1,291 @@
+use blockscout_db::entity::{addresses, blocks, token_transfers, tokens, transactions};
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use sea_orm::{prelude::Decimal, DatabaseConnection, EntityTrait, Set};
+
+/// Deterministic blockscout data of a chain living through the range of days.
+///
+/// Unlike [`super::mock_blockscout::fill_mock_blockscout_data`], values are not hand-picked,
+/// so it's meant for tests comparing results of different update paths of a chart
+/// rather than for tests with exact expected values
+#[derive(Debug, Clone)]
+pub struct MockGenerator {
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub blocks_per_day: u32,
+    pub txns_per_block: u32,
+    /// At least one account is generated, it's the miner of all blocks
+    pub accounts: u32,
+    /// Every third transaction is a contract call transferring one of the tokens
+    pub tokens: u32,
+}
+
+impl Default for MockGenerator {
+    fn default() -> Self {
+        Self {
+            from: NaiveDate::from_ymd_opt(2022, 11, 1).unwrap(),
+            to: NaiveDate::from_ymd_opt(2022, 11, 30).unwrap(),
+            blocks_per_day: 4,
+            txns_per_block: 3,
+            accounts: 10,
+            tokens: 2,
+        }
+    }
+}
+
+impl MockGenerator {
+    /// Fills accounts, tokens and all days of the range
+    pub async fn fill(&self, blockscout: &DatabaseConnection) {
+        self.fill_accounts(blockscout).await;
+        self.fill_days(blockscout, self.from, self.to).await;
+    }
+
+    pub async fn fill_accounts(&self, blockscout: &DatabaseConnection) {
+        let addresses = self
+            .accounts()
+            .into_iter()
+            .chain(self.tokens())
+            .map(|hash| addresses::ActiveModel {
+                hash: Set(hash),
+                inserted_at: Set(Default::default()),
+                updated_at: Set(Default::default()),
+                ..Default::default()
+            });
+        addresses::Entity::insert_many(addresses)
+            .exec(blockscout)
+            .await
+            .unwrap();
+        if self.tokens == 0 {
+            return;
+        }
+        let tokens = self.tokens().into_iter().map(|hash| tokens::ActiveModel {
+            r#type: Set(Default::default()),
+            contract_address_hash: Set(hash),
+            inserted_at: Set(self.from.and_hms_opt(0, 0, 0).unwrap()),
+            updated_at: Set(Default::default()),
+            ..Default::default()
+        });
+        tokens::Entity::insert_many(tokens)
+            .exec(blockscout)
+            .await
+            .unwrap();
+    }
+
+    /// Fills blocks with transactions of days from `from` to `to` inclusive,
+    /// so data can be added day by day between updates
+    pub async fn fill_days(&self, blockscout: &DatabaseConnection, from: NaiveDate, to: NaiveDate) {
+        for day in from.iter_days().take_while(|day| *day <= to) {
+            let blocks = self.blocks(day);
+            let txns: Vec<_> = blocks
+                .iter()
+                .flat_map(|block| self.transactions(block))
+                .collect();
+            let transfers: Vec<_> = blocks
+                .iter()
+                .flat_map(|block| self.token_transfers(block))
+                .collect();
+            blocks::Entity::insert_many(blocks)
+                .exec(blockscout)
+                .await
+                .unwrap();
+            if !txns.is_empty() {
+                transactions::Entity::insert_many(txns)
+                    .exec(blockscout)
+                    .await
+                    .unwrap();
+            }
+            if !transfers.is_empty() {
+                token_transfers::Entity::insert_many(transfers)
+                    .exec(blockscout)
+                    .await
+                    .unwrap();
+            }
+        }
+    }
+
+    pub fn accounts(&self) -> Vec<Vec<u8>> {
+        (0..self.accounts.max(1)).map(|i| address(1, i)).collect()
+    }
+
+    pub fn tokens(&self) -> Vec<Vec<u8>> {
+        (0..self.tokens).map(|i| address(2, i)).collect()
+    }
+
+    /// Blocks of the day, evenly spread over it
+    pub fn blocks(&self, day: NaiveDate) -> Vec<blocks::ActiveModel> {
+        let day_index = (day - self.from).num_days();
+        assert!(
+            day_index >= 0 && day <= self.to,
+            "day {day} is out of range"
+        );
+        let interval = 86_400 / i64::from(self.blocks_per_day.max(1));
+        (0..i64::from(self.blocks_per_day))
+            .map(|i| {
+                let number = day_index * i64::from(self.blocks_per_day) + i;
+                let timestamp = day.and_hms_opt(0, 0, 0).unwrap() + Duration::seconds(i * interval);
+                mock_block(number, timestamp)
+            })
+            .collect()
+    }
+
+    pub fn transactions(&self, block: &blocks::ActiveModel) -> Vec<transactions::ActiveModel> {
+        let number = *block.number.as_ref();
+        (0..self.txns_per_block as i32)
+            .map(|index| {
+                let seed = number as u64 + index as u64;
+                let from = self.account(seed);
+                let to = if self.is_token_transfer(index) {
+                    self.token(seed)
+                } else {
+                    self.account(seed + 1)
+                };
+                let gas_price = 1_000_000_000 + (seed * 7 % 10) as i64 * 100_000_000;
+                transactions::ActiveModel {
+                    block_number: Set(Some(number as i32)),
+                    block_hash: Set(Some(block.hash.as_ref().to_vec())),
+                    hash: Set(txn_hash(number, index)),
+                    gas_price: Set(Decimal::new(gas_price, 0)),
+                    gas: Set(Decimal::new(21_000, 0)),
+                    input: Set(Default::default()),
+                    nonce: Set(Default::default()),
+                    r: Set(Default::default()),
+                    s: Set(Default::default()),
+                    v: Set(Default::default()),
+                    value: Set(Decimal::new(1_000_000_000_000, 0)),
+                    inserted_at: Set(Default::default()),
+                    updated_at: Set(Default::default()),
+                    from_address_hash: Set(from),
+                    to_address_hash: Set(Some(to)),
+                    cumulative_gas_used: Set(Some(Default::default())),
+                    gas_used: Set(Some(Decimal::new(21_000, 0))),
+                    index: Set(Some(index)),
+                    status: Set(Some(1)),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    pub fn token_transfers(
+        &self,
+        block: &blocks::ActiveModel,
+    ) -> Vec<token_transfers::ActiveModel> {
+        let number = *block.number.as_ref();
+        (0..self.txns_per_block as i32)
+            .filter(|index| self.is_token_transfer(*index))
+            .map(|index| {
+                let seed = number as u64 + index as u64;
+                token_transfers::ActiveModel {
+                    transaction_hash: Set(txn_hash(number, index)),
+                    log_index: Set(0),
+                    from_address_hash: Set(self.account(seed)),
+                    to_address_hash: Set(self.account(seed + 1)),
+                    amount: Set(Some(Decimal::new(100, 0))),
+                    token_id: Set(None),
+                    token_contract_address_hash: Set(self.token(seed)),
+                    status: Set(Some(0)),
+                    ..Default::default()
+                }
+            })
+            .collect()
+    }
+
+    fn is_token_transfer(&self, index: i32) -> bool {
+        self.tokens > 0 && index % 3 == 2
+    }
+
+    fn account(&self, seed: u64) -> Vec<u8> {
+        address(1, (seed % u64::from(self.accounts.max(1))) as u32)
+    }
+
+    fn token(&self, seed: u64) -> Vec<u8> {
+        address(2, (seed % u64::from(self.tokens.max(1))) as u32)
+    }
+}
+
+/// Addresses of different `kind`s don't intersect
+fn address(kind: u8, index: u32) -> Vec<u8> {
+    let mut hash = vec![kind];
+    hash.extend(index.to_be_bytes());
+    hash.resize(20, 0);
+    hash
+}
+
+fn txn_hash(block_number: i64, index: i32) -> Vec<u8> {
+    let mut hash = block_number.to_be_bytes().to_vec();
+    hash.extend(index.to_be_bytes());
+    hash.resize(32, 0);
+    hash
+}
+
+fn mock_block(number: i64, timestamp: NaiveDateTime) -> blocks::ActiveModel {
+    let size = 1000 + (number as i32 * 15485863).rem_euclid(5000);
+    blocks::ActiveModel {
+        number: Set(number),
+        hash: Set(number.to_be_bytes().to_vec()),
+        timestamp: Set(timestamp),
+        consensus: Set(true),
+        gas_limit: Set(Decimal::new(30_000_000, 0)),
+        gas_used: Set(Decimal::from(size * 10)),
+        miner_hash: Set(address(1, 0)),
+        nonce: Set(Default::default()),
+        parent_hash: Set((number - 1).to_be_bytes().to_vec()),
+        inserted_at: Set(Default::default()),
+        updated_at: Set(Default::default()),
+        size: Set(Some(size)),
+        ..Default::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pretty_assertions::assert_eq;
+    use std::collections::HashSet;
+
+    #[test]
+    fn generated_data_is_consistent() {
+        let generator = MockGenerator::default();
+        let days: Vec<_> = generator
+            .from
+            .iter_days()
+            .take_while(|day| *day <= generator.to)
+            .collect();
+        let blocks: Vec<_> = days.iter().flat_map(|day| generator.blocks(*day)).collect();
+        assert_eq!(blocks.len(), 30 * 4);
+        for (i, block) in blocks.iter().enumerate() {
+            assert_eq!(*block.number.as_ref(), i as i64);
+            let date = block.timestamp.as_ref().date();
+            assert_eq!(date, days[i / 4]);
+        }
+
+        let txns: Vec<_> = blocks
+            .iter()
+            .flat_map(|block| generator.transactions(block))
+            .collect();
+        assert_eq!(txns.len(), 30 * 4 * 3);
+        let hashes: HashSet<_> = txns.iter().map(|txn| txn.hash.as_ref().clone()).collect();
+        assert_eq!(
+            hashes.len(),
+            txns.len(),
+            "transaction hashes must be unique"
+        );
+
+        let transfers: Vec<_> = blocks
+            .iter()
+            .flat_map(|block| generator.token_transfers(block))
+            .collect();
+        assert_eq!(transfers.len(), 30 * 4);
+        for transfer in transfers {
+            assert!(hashes.contains(transfer.transaction_hash.as_ref()));
+            assert!(generator
+                .tokens()
+                .contains(transfer.token_contract_address_hash.as_ref()));
+        }
+
+        // same generator gives same data
+        assert_eq!(
+            generator.blocks(days[3]),
+            MockGenerator::default().blocks(days[3])
+        );
+    }
+}
@@ -1,3 +1,4 @@
 pub mod init_db;
 pub mod mock_blockscout;
+pub mod mock_generator;
 pub mod simple_test;
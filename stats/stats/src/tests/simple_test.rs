@@ -1,22 +1,137 @@
-use super::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data};
-use crate::{get_chart_data, get_counters, Chart, MissingDatePolicy};
+use super::{
+    init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data, mock_generator::MockGenerator,
+};
+use crate::{
+    get_chart_data, get_counters, get_series_data, AggregationPolicy, Chart, MissingDatePolicy,
+    ResolutionKind, UpdateParameters,
+};
 use chrono::NaiveDate;
 use sea_orm::DatabaseConnection;
-use std::assert_eq;
+use std::{assert_eq, future::Future, pin::Pin};
+
+/// Fills blockscout database with data needed by the chart besides the common mock data
+pub type MockFixture =
+    for<'a> fn(&'a DatabaseConnection) -> Pin<Box<dyn Future<Output = ()> + Send + 'a>>;
 
 pub async fn simple_test_chart(test_name: &str, chart: impl Chart, expected: Vec<(&str, &str)>) {
+    simple_test_chart_with_fixture(test_name, chart, expected, |_| Box::pin(async {})).await
+}
+
+/// Same as [`simple_test_chart`], but `fixture` fills blockscout database after the common mock data,
+/// e.g. with [`fill_mock_optimism_data`] or [`fill_mock_user_ops_data`]
+///
+/// [`fill_mock_optimism_data`]: super::mock_blockscout::fill_mock_optimism_data
+/// [`fill_mock_user_ops_data`]: super::mock_blockscout::fill_mock_user_ops_data
+pub async fn simple_test_chart_with_fixture(
+    test_name: &str,
+    chart: impl Chart,
+    expected: Vec<(&str, &str)>,
+    fixture: MockFixture,
+) {
     let _ = tracing_subscriber::fmt::try_init();
     let (db, blockscout) = init_db_all(test_name).await;
     chart.create(&db).await.unwrap();
     fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+    fixture(&blockscout).await;
 
-    chart.update(&db, &blockscout, true).await.unwrap();
+    chart
+        .update(&db, &blockscout, UpdateParameters::with_force_full(true))
+        .await
+        .unwrap();
     get_chart_and_assert_eq(&db, &chart, &expected, None, None, None).await;
 
-    chart.update(&db, &blockscout, false).await.unwrap();
+    chart
+        .update(&db, &blockscout, UpdateParameters::default())
+        .await
+        .unwrap();
     get_chart_and_assert_eq(&db, &chart, &expected, None, None, None).await;
 }
 
+/// Same as [`simple_test_chart`], but every series of the chart is checked as well
+pub async fn series_test_chart(
+    test_name: &str,
+    chart: impl Chart,
+    expected: Vec<(&str, &str)>,
+    expected_series: Vec<(&str, Vec<(&str, &str)>)>,
+) {
+    let _ = tracing_subscriber::fmt::try_init();
+    let (db, blockscout) = init_db_all(test_name).await;
+    chart.create(&db).await.unwrap();
+    fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+    for force_full in [true, false] {
+        chart
+            .update(
+                &db,
+                &blockscout,
+                UpdateParameters::with_force_full(force_full),
+            )
+            .await
+            .unwrap();
+        get_chart_and_assert_eq(&db, &chart, &expected, None, None, None).await;
+        for (key, expected) in &expected_series {
+            let data = get_series_data(
+                &db,
+                chart.name(),
+                key,
+                None,
+                None,
+                ResolutionKind::Day,
+                AggregationPolicy::Sum,
+            )
+            .await
+            .unwrap();
+            let data: Vec<_> = data
+                .into_iter()
+                .map(|p| (p.date.to_string(), p.value.to_string()))
+                .collect();
+            let data: Vec<(&str, &str)> = data
+                .iter()
+                .map(|(date, value)| (date.as_str(), value.as_str()))
+                .collect();
+            assert_eq!(expected, &data, "wrong series {key}");
+        }
+    }
+}
+
+/// Checks that chart updated incrementally, while generated data arrives day by day,
+/// is the same as the chart fully recomputed from all the data
+pub async fn incremental_test_chart(test_name: &str, chart: impl Chart, generator: MockGenerator) {
+    let _ = tracing_subscriber::fmt::try_init();
+    let (db, blockscout) = init_db_all(test_name).await;
+    chart.create(&db).await.unwrap();
+    generator.fill_accounts(&blockscout).await;
+
+    let days = generator.to - generator.from;
+    let half = generator.from + days / 2;
+    generator.fill_days(&blockscout, generator.from, half).await;
+    chart
+        .update(&db, &blockscout, UpdateParameters::default())
+        .await
+        .unwrap();
+    let rest = half.succ_opt().unwrap().iter_days();
+    for day in rest.take_while(|day| *day <= generator.to) {
+        generator.fill_days(&blockscout, day, day).await;
+        chart
+            .update(&db, &blockscout, UpdateParameters::default())
+            .await
+            .unwrap();
+    }
+    let incremental = get_chart_data(&db, chart.name(), None, None, None)
+        .await
+        .unwrap();
+
+    chart
+        .update(&db, &blockscout, UpdateParameters::with_force_full(true))
+        .await
+        .unwrap();
+    let full = get_chart_data(&db, chart.name(), None, None, None)
+        .await
+        .unwrap();
+    assert!(!full.is_empty(), "chart is empty");
+    assert_eq!(incremental, full);
+}
+
 pub async fn ranged_test_chart(
     test_name: &str,
     chart: impl Chart,
@@ -30,10 +145,16 @@ pub async fn ranged_test_chart(
     fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
     let policy = chart.missing_date_policy();
 
-    chart.update(&db, &blockscout, true).await.unwrap();
+    chart
+        .update(&db, &blockscout, UpdateParameters::with_force_full(true))
+        .await
+        .unwrap();
     get_chart_and_assert_eq(&db, &chart, &expected, Some(from), Some(to), Some(policy)).await;
 
-    chart.update(&db, &blockscout, false).await.unwrap();
+    chart
+        .update(&db, &blockscout, UpdateParameters::default())
+        .await
+        .unwrap();
     get_chart_and_assert_eq(&db, &chart, &expected, Some(from), Some(to), Some(policy)).await;
 }
 
@@ -50,7 +171,7 @@ async fn get_chart_and_assert_eq(
         .unwrap();
     let data: Vec<_> = data
         .into_iter()
-        .map(|p| (p.date.to_string(), p.value))
+        .map(|p| (p.date.to_string(), p.value.to_string()))
         .collect();
     let data: Vec<(&str, &str)> = data
         .iter()
@@ -65,16 +186,22 @@ pub async fn simple_test_counter(test_name: &str, counter: impl Chart, expected:
     counter.create(&db).await.unwrap();
     fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
 
-    counter.update(&db, &blockscout, true).await.unwrap();
+    counter
+        .update(&db, &blockscout, UpdateParameters::with_force_full(true))
+        .await
+        .unwrap();
     get_counter_and_assert_eq(&db, &counter, expected).await;
 
-    counter.update(&db, &blockscout, false).await.unwrap();
+    counter
+        .update(&db, &blockscout, UpdateParameters::default())
+        .await
+        .unwrap();
     get_counter_and_assert_eq(&db, &counter, expected).await;
 }
 
 async fn get_counter_and_assert_eq(db: &DatabaseConnection, counter: &impl Chart, expected: &str) {
     let data = get_counters(db).await.unwrap();
     let data = &data[counter.name()];
-    let value = &data.value;
+    let value = data.value.to_string();
     assert_eq!(expected, value);
 }
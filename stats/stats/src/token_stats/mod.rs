@@ -0,0 +1,9 @@
+//! Daily stats of separate tokens, stored apart from charts
+//! in `token_stats` table keyed by token address and stat kind
+
+mod read;
+mod update;
+
+pub use entity::sea_orm_active_enums::TokenStatKind;
+pub use read::get_token_stats;
+pub use update::{selected_tokens, update_token_stats, TokenSelection};
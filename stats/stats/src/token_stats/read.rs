@@ -0,0 +1,71 @@
+use crate::{missing_date::fill_missing_points, DateValue, MissingDatePolicy, ReadError};
+use chrono::NaiveDate;
+use entity::sea_orm_active_enums::TokenStatKind;
+use sea_orm::{prelude::*, DbBackend, FromQueryResult, Statement};
+
+fn missing_date_policy(kind: TokenStatKind) -> MissingDatePolicy {
+    match kind {
+        TokenStatKind::Transfers => MissingDatePolicy::FillZero,
+        TokenStatKind::Holders => MissingDatePolicy::FillPrevious,
+    }
+}
+
+/// Daily stats of the token, days without changes are filled.
+/// Returns `NotFound` if stats of the token are not collected
+pub async fn get_token_stats(
+    db: &DatabaseConnection,
+    token: &[u8],
+    kind: TokenStatKind,
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+) -> Result<Vec<DateValue>, ReadError> {
+    // the last point before `from` is taken to fill the beginning of the range
+    let stmnt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        SELECT date, value
+        FROM token_stats
+        WHERE
+            token_address = $1 AND
+            kind = $2::token_stat_kind AND
+            ($3::date IS NULL OR date >= (
+                SELECT COALESCE(MAX(date), '1900-01-01'::date)
+                FROM token_stats
+                WHERE token_address = $1 AND kind = $2::token_stat_kind AND date <= $3
+            )) AND
+            ($4::date IS NULL OR date <= $4)
+        ORDER BY date
+        "#,
+        vec![
+            token.to_vec().into(),
+            kind.to_value().into(),
+            from.into(),
+            to.into(),
+        ],
+    );
+    let data = DateValue::find_by_statement(stmnt).all(db).await?;
+    if data.is_empty() && !is_tracked(db, token).await? {
+        let address: String = token.iter().map(|byte| format!("{byte:02x}")).collect();
+        return Err(ReadError::NotFound(format!("token 0x{address} stats")));
+    }
+    let data = fill_missing_points(data, missing_date_policy(kind), from, to)
+        .into_iter()
+        .filter(|point| from.map_or(true, |from| point.date >= from))
+        .collect();
+    Ok(data)
+}
+
+#[derive(FromQueryResult)]
+struct Tracked {
+    tracked: bool,
+}
+
+async fn is_tracked(db: &DatabaseConnection, token: &[u8]) -> Result<bool, DbErr> {
+    let stmnt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        "SELECT EXISTS(SELECT 1 FROM token_stats WHERE token_address = $1) as tracked",
+        vec![token.to_vec().into()],
+    );
+    let tracked = Tracked::find_by_statement(stmnt).one(db).await?;
+    Ok(tracked.map_or(false, |tracked| tracked.tracked))
+}
@@ -0,0 +1,421 @@
+use crate::{DateValue, UpdateError, UpdateParameters};
+use chrono::{Duration, NaiveDate};
+use entity::{sea_orm_active_enums::TokenStatKind, token_stats};
+use sea_orm::{
+    prelude::*, sea_query, DbBackend, FromQueryResult, QueryOrder, QuerySelect, Set, Statement,
+};
+
+/// Tokens whose stats are collected
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TokenSelection {
+    /// Fixed list of token contract addresses
+    Addresses(Vec<Vec<u8>>),
+    /// Tokens with the most transfers during the last 30 days
+    Top(u64),
+}
+
+#[derive(FromQueryResult)]
+struct TokenAddress {
+    token: Vec<u8>,
+}
+
+async fn top_tokens(blockscout: &DatabaseConnection, limit: u64) -> Result<Vec<Vec<u8>>, DbErr> {
+    let stmnt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        SELECT tt.token_contract_address_hash as token
+        FROM token_transfers tt
+        JOIN blocks b ON tt.block_hash = b.hash
+        WHERE
+            b.timestamp > NOW() - INTERVAL '30 days' AND
+            b.consensus = true
+        GROUP BY tt.token_contract_address_hash
+        ORDER BY COUNT(*) DESC
+        LIMIT $1
+        "#,
+        vec![(limit as i64).into()],
+    );
+    let tokens = TokenAddress::find_by_statement(stmnt)
+        .all(blockscout)
+        .await?
+        .into_iter()
+        .map(|token| token.token)
+        .collect();
+    Ok(tokens)
+}
+
+async fn daily_transfers(
+    blockscout: &DatabaseConnection,
+    token: &[u8],
+    after: Option<NaiveDate>,
+) -> Result<Vec<DateValue>, DbErr> {
+    let stmnt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        SELECT DATE(b.timestamp) as date, COUNT(*)::NUMERIC as value
+        FROM token_transfers tt
+        JOIN blocks b ON tt.block_hash = b.hash
+        WHERE
+            tt.token_contract_address_hash = $1 AND
+            b.timestamp != to_timestamp(0) AND
+            ($2::date IS NULL OR DATE(b.timestamp) > $2) AND
+            b.consensus = true
+        GROUP BY date
+        "#,
+        vec![token.to_vec().into(), after.into()],
+    );
+    DateValue::find_by_statement(stmnt).all(blockscout).await
+}
+
+/// Number of addresses with positive balance at the end of every day
+/// when balances of the token changed. Only balances changed after `after` are read,
+/// changes are added to `holders`, the number of holders at `after`
+async fn daily_holders(
+    blockscout: &DatabaseConnection,
+    token: &[u8],
+    after: Option<NaiveDate>,
+    holders: Decimal,
+) -> Result<Vec<DateValue>, DbErr> {
+    // change of holders at the first day after `after` depends on the previous
+    // balances of addresses, so the last balance before it is read as well
+    let stmnt = Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"
+        WITH daily_balances AS (
+            SELECT DISTINCT ON (atb.address_hash, DATE(b.timestamp))
+                atb.address_hash,
+                DATE(b.timestamp) as date,
+                COALESCE(atb.value, 0) as value
+            FROM address_token_balances atb
+            JOIN blocks b ON atb.block_number = b.number
+            WHERE
+                atb.token_contract_address_hash = $1 AND
+                b.timestamp != to_timestamp(0) AND
+                ($2::date IS NULL OR DATE(b.timestamp) > $2) AND
+                b.consensus = true
+            ORDER BY atb.address_hash, DATE(b.timestamp), atb.block_number DESC
+        ),
+        previous_balances AS (
+            SELECT DISTINCT ON (atb.address_hash)
+                atb.address_hash,
+                $2::date as date,
+                COALESCE(atb.value, 0) as value
+            FROM address_token_balances atb
+            JOIN blocks b ON atb.block_number = b.number
+            WHERE
+                $2::date IS NOT NULL AND
+                atb.token_contract_address_hash = $1 AND
+                atb.address_hash IN (SELECT address_hash FROM daily_balances) AND
+                b.timestamp != to_timestamp(0) AND
+                DATE(b.timestamp) <= $2 AND
+                b.consensus = true
+            ORDER BY atb.address_hash, atb.block_number DESC
+        ),
+        changes AS (
+            SELECT date, delta
+            FROM (
+                SELECT
+                    date,
+                    (value > 0)::int - (COALESCE(
+                        LAG(value) OVER (PARTITION BY address_hash ORDER BY date), 0
+                    ) > 0)::int as delta
+                FROM (
+                    SELECT * FROM previous_balances
+                    UNION ALL
+                    SELECT * FROM daily_balances
+                ) balances
+            ) deltas
+            WHERE $2::date IS NULL OR date > $2
+        ),
+        holders AS (
+            SELECT date, $3 + SUM(SUM(delta)) OVER (ORDER BY date) as value
+            FROM changes
+            GROUP BY date
+        )
+        SELECT date, value::NUMERIC as value
+        FROM holders
+        "#,
+        vec![token.to_vec().into(), after.into(), holders.into()],
+    );
+    DateValue::find_by_statement(stmnt).all(blockscout).await
+}
+
+#[derive(FromQueryResult)]
+struct LastDate {
+    date: NaiveDate,
+}
+
+async fn last_date(
+    db: &DatabaseConnection,
+    token: &[u8],
+    kind: TokenStatKind,
+) -> Result<Option<NaiveDate>, DbErr> {
+    let last = token_stats::Entity::find()
+        .select_only()
+        .column(token_stats::Column::Date)
+        .filter(token_stats::Column::TokenAddress.eq(token.to_vec()))
+        .filter(token_stats::Column::Kind.eq(kind))
+        .order_by_desc(token_stats::Column::Date)
+        .into_model::<LastDate>()
+        .one(db)
+        .await?;
+    Ok(last.map(|last| last.date))
+}
+
+#[derive(FromQueryResult)]
+struct StoredValue {
+    value: Decimal,
+}
+
+/// Stored value of the last day before or on `date`
+async fn value_at(
+    db: &DatabaseConnection,
+    token: &[u8],
+    kind: TokenStatKind,
+    date: NaiveDate,
+) -> Result<Option<Decimal>, DbErr> {
+    let value = token_stats::Entity::find()
+        .select_only()
+        .column(token_stats::Column::Value)
+        .filter(token_stats::Column::TokenAddress.eq(token.to_vec()))
+        .filter(token_stats::Column::Kind.eq(kind))
+        .filter(token_stats::Column::Date.lte(date))
+        .order_by_desc(token_stats::Column::Date)
+        .into_model::<StoredValue>()
+        .one(db)
+        .await?;
+    Ok(value.map(|value| value.value))
+}
+
+/// Returns number of written rows
+async fn insert_token_stats(
+    db: &DatabaseConnection,
+    token: &[u8],
+    kind: TokenStatKind,
+    data: Vec<DateValue>,
+) -> Result<u64, DbErr> {
+    let rows = data.len() as u64;
+    if data.is_empty() {
+        return Ok(rows);
+    }
+    let data = data.into_iter().map(|point| token_stats::ActiveModel {
+        token_address: Set(token.to_vec()),
+        kind: Set(kind),
+        date: Set(point.date),
+        value: Set(point.value),
+        ..Default::default()
+    });
+    token_stats::Entity::insert_many(data)
+        .on_conflict(
+            sea_query::OnConflict::columns([
+                token_stats::Column::TokenAddress,
+                token_stats::Column::Kind,
+                token_stats::Column::Date,
+            ])
+            .update_column(token_stats::Column::Value)
+            .to_owned(),
+        )
+        .exec(db)
+        .await?;
+    Ok(rows)
+}
+
+async fn update_token_stat(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    token: &[u8],
+    kind: TokenStatKind,
+    params: UpdateParameters,
+) -> Result<u64, UpdateError> {
+    // last days are recomputed, because they can be partially calculated
+    // or contain blocks dropped by reorg
+    let after = match params.force_full {
+        true => None,
+        false => last_date(db, token, kind)
+            .await
+            .map_err(UpdateError::StatsDB)?
+            .map(|date| date - Duration::days(params.recompute_window_days as i64)),
+    };
+    let data = match kind {
+        TokenStatKind::Transfers => daily_transfers(blockscout, token, after).await,
+        TokenStatKind::Holders => {
+            let holders = match after {
+                Some(after) => value_at(db, token, kind, after)
+                    .await
+                    .map_err(UpdateError::StatsDB)?
+                    .unwrap_or_default(),
+                None => Decimal::ZERO,
+            };
+            daily_holders(blockscout, token, after, holders).await
+        }
+    }
+    .map_err(UpdateError::blockscout)?;
+    insert_token_stats(db, token, kind, data)
+        .await
+        .map_err(UpdateError::StatsDB)
+}
+
+/// Addresses of tokens whose stats are collected
+pub async fn selected_tokens(
+    blockscout: &DatabaseConnection,
+    selection: &TokenSelection,
+) -> Result<Vec<Vec<u8>>, UpdateError> {
+    match selection {
+        TokenSelection::Addresses(addresses) => Ok(addresses.clone()),
+        TokenSelection::Top(limit) => top_tokens(blockscout, *limit)
+            .await
+            .map_err(UpdateError::blockscout),
+    }
+}
+
+/// Updates transfers and holders stats of the token.
+/// Returns number of written rows
+pub async fn update_token_stats(
+    db: &DatabaseConnection,
+    blockscout: &DatabaseConnection,
+    token: &[u8],
+    params: UpdateParameters,
+) -> Result<u64, UpdateError> {
+    let mut rows = 0;
+    for kind in [TokenStatKind::Transfers, TokenStatKind::Holders] {
+        rows += update_token_stat(db, blockscout, token, kind, params).await?;
+    }
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        tests::{init_db::init_db_all, mock_blockscout::fill_mock_blockscout_data},
+        token_stats::get_token_stats,
+        ReadError,
+    };
+    use pretty_assertions::assert_eq;
+
+    async fn get_values(
+        db: &DatabaseConnection,
+        token: &[u8],
+        kind: TokenStatKind,
+        from: &str,
+        to: &str,
+    ) -> Vec<(String, String)> {
+        get_token_stats(db, token, kind, from.parse().ok(), to.parse().ok())
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|point| (point.date.to_string(), point.value.to_string()))
+            .collect()
+    }
+
+    fn expected(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(date, value)| (date.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs database to run"]
+    async fn update_token_stats_works() {
+        let _ = tracing_subscriber::fmt::try_init();
+        let (db, blockscout) = init_db_all("update_token_stats_works").await;
+        fill_mock_blockscout_data(&blockscout, "2023-03-01").await;
+
+        let mut token = 1_i64.to_le_bytes().to_vec();
+        token.extend(std::iter::repeat(0).take(32 - token.len()));
+        let selection = TokenSelection::Addresses(vec![token.clone()]);
+        assert_eq!(
+            selected_tokens(&blockscout, &selection).await.unwrap(),
+            vec![token.clone()]
+        );
+
+        for force_full in [true, false] {
+            update_token_stats(
+                &db,
+                &blockscout,
+                &token,
+                UpdateParameters::with_force_full(force_full),
+            )
+            .await
+            .unwrap();
+
+            let transfers = get_values(
+                &db,
+                &token,
+                TokenStatKind::Transfers,
+                "2022-11-08",
+                "2022-11-13",
+            )
+            .await;
+            assert_eq!(
+                transfers,
+                expected(&[
+                    ("2022-11-08", "0"),
+                    ("2022-11-09", "1"),
+                    ("2022-11-10", "2"),
+                    ("2022-11-11", "2"),
+                    ("2022-11-12", "1"),
+                    ("2022-11-13", "0"),
+                ])
+            );
+            let holders = get_values(
+                &db,
+                &token,
+                TokenStatKind::Holders,
+                "2022-11-08",
+                "2022-11-13",
+            )
+            .await;
+            assert_eq!(
+                holders,
+                expected(&[
+                    ("2022-11-08", "0"),
+                    ("2022-11-09", "1"),
+                    ("2022-11-10", "2"),
+                    ("2022-11-11", "3"),
+                    ("2022-11-12", "3"),
+                    ("2022-11-13", "3"),
+                ])
+            );
+
+            let transfers = get_values(
+                &db,
+                &token,
+                TokenStatKind::Transfers,
+                "2023-01-30",
+                "2023-02-02",
+            )
+            .await;
+            assert_eq!(
+                transfers,
+                expected(&[
+                    ("2023-01-30", "0"),
+                    ("2023-01-31", "0"),
+                    ("2023-02-01", "1"),
+                    ("2023-02-02", "0"),
+                ])
+            );
+            let holders = get_values(
+                &db,
+                &token,
+                TokenStatKind::Holders,
+                "2023-01-30",
+                "2023-02-02",
+            )
+            .await;
+            assert_eq!(
+                holders,
+                expected(&[
+                    ("2023-01-30", "3"),
+                    ("2023-01-31", "3"),
+                    ("2023-02-01", "3"),
+                    ("2023-02-02", "3"),
+                ])
+            );
+        }
+
+        let unknown = get_token_stats(&db, &[1, 2, 3], TokenStatKind::Transfers, None, None).await;
+        assert!(matches!(unknown, Err(ReadError::NotFound(_))));
+    }
+}